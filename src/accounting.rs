@@ -0,0 +1,252 @@
+// src/accounting.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE};
+use crate::platform::SysLock;
+use crate::platform;
+use crate::sahne_syscalls::SyscallError;
+use core::cell::UnsafeCell;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+
+// --- 1. Sayaçlar ve Sınırlar ---
+
+/// Kimliği alınamayan görevlerin (ör. çekirdek bağlamı) hesaplandığı görev kimliği.
+pub const UNKNOWN_TASK: u64 = u64::MAX;
+
+/// Bir görevin biriken I/O sayaçları.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TaskIo {
+    /// Dosya sistemi API'sinden okunan bayt.
+    pub logical_read_bytes: u64,
+    /// Dosya sistemi API'siyle yazılan bayt.
+    pub logical_write_bytes: u64,
+    /// Dosya sistemi okuma/yazma çağrısı sayısı.
+    pub logical_ops: u64,
+    /// Aygıttan okunan bayt (`AccountingDevice` üzerinden).
+    pub device_read_bytes: u64,
+    /// Aygıta yazılan bayt (`AccountingDevice` üzerinden).
+    pub device_write_bytes: u64,
+    /// Aygıt istek sayısı.
+    pub device_ops: u64,
+}
+
+/// Bir görevin dosya sistemi I/O'suna uygulanan sınır (0 = sınır yok). Süreler sistem
+/// saati biriminde ölçülür; sınırlar bir birimlik pencere başına uygulanır.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TaskLimit {
+    /// Pencere başına en fazla okunan + yazılan bayt.
+    pub bytes_per_tick: u64,
+    /// Pencere başına en fazla okuma/yazma çağrısı.
+    pub ops_per_tick: u64,
+}
+
+/// Dosya sistemi işleminin yönü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoKind {
+    Read,
+    Write,
+}
+
+/// Tek bir görevin sayaçları, sınırı ve geçerli pencerenin kullanımı.
+#[derive(Debug, Default)]
+struct TaskState {
+    io: TaskIo,
+    limit: Option<TaskLimit>,
+    window_start: u64,
+    window_bytes: u64,
+    window_ops: u64,
+}
+
+
+// --- 2. Görev Başına Hesap Tablosu ---
+
+/// Sahne64 görev kimliği başına I/O sayaçlarını tutan ve isteğe bağlı olarak bant
+/// genişliği/IOPS sınırı uygulayan tablo. Hem dosya sistemi (`SadakFs::with_io_accounting`)
+/// hem aygıt katmanı (`AccountingDevice`) aynı tabloyu paylaşabilir.
+///
+/// Sınırlar yalnızca dosya sistemi çağrılarında, dosya sistemi kilidi alınmadan önce
+/// uygulanır: sınırı aşan görev bir sonraki pencereye kadar işlemciyi bırakarak bekler,
+/// diğer görevleri bekletmez. Aygıt katmanında yalnızca sayım yapılır; geri yazma ve
+/// commit I/O'su o sırada çalışan göreve yazılır.
+pub struct IoAccounting {
+    lock: SysLock,
+    tasks: UnsafeCell<BTreeMap<u64, TaskState>>,
+}
+
+// Tabloya her erişim `lock` altında yapıldığı için paylaşım güvenlidir.
+unsafe impl Sync for IoAccounting {}
+unsafe impl Send for IoAccounting {}
+
+impl IoAccounting {
+    /// Boş bir hesap tablosu oluşturur.
+    pub fn new() -> Result<Self, SyscallError> {
+        Ok(IoAccounting {
+            lock: SysLock::new()?,
+            tasks: UnsafeCell::new(BTreeMap::new()),
+        })
+    }
+
+    /// Görevin sınırını ayarlar (`None` = sınırsız).
+    pub fn set_limit(&self, task: u64, limit: Option<TaskLimit>) {
+        self.with_task(task, |state| state.limit = limit);
+    }
+
+    /// Görevin biriken sayaçları.
+    pub fn counters(&self, task: u64) -> TaskIo {
+        self.lock.acquire();
+        let io = unsafe { &*self.tasks.get() }.get(&task).map(|state| state.io).unwrap_or_default();
+        self.lock.release();
+        io
+    }
+
+    /// Tüm görevlerin sayaçları, görev kimliği sırasıyla.
+    pub fn all_counters(&self) -> Vec<(u64, TaskIo)> {
+        self.lock.acquire();
+        let all = unsafe { &*self.tasks.get() }.iter().map(|(&task, state)| (task, state.io)).collect();
+        self.lock.release();
+        all
+    }
+
+    /// Görevin kaydını (sayaçlar ve sınır) siler; ör. görev sonlandığında.
+    pub fn forget(&self, task: u64) {
+        self.lock.acquire();
+        unsafe { &mut *self.tasks.get() }.remove(&task);
+        self.lock.release();
+    }
+
+    /// Çağıran görevin bir dosya sistemi işlemini hesaba yazar. Görevin sınırı varsa ve
+    /// geçerli pencerede aşılmışsa, pencere dolana kadar bekler.
+    pub fn charge_logical(&self, kind: IoKind, bytes: u64) {
+        let task = current_task();
+        loop {
+            // Zaman alınamazsa pencere ilerletilemez; görevi kilitlememek için sınır uygulanmaz
+            let now = system_time();
+            let admitted = self.with_task(task, |state| {
+                if let Some(now) = now.filter(|&now| now != state.window_start) {
+                    state.window_start = now;
+                    state.window_bytes = 0;
+                    state.window_ops = 0;
+                }
+
+                // Pencerenin ilk işlemi, sınırdan büyük olsa bile geçer (aksi halde hiç ilerleyemez)
+                if let Some(limit) = state.limit.filter(|_| now.is_some()) {
+                    let fresh_window = state.window_ops == 0;
+                    let over_bytes = limit.bytes_per_tick != 0 && state.window_bytes + bytes > limit.bytes_per_tick;
+                    let over_ops = limit.ops_per_tick != 0 && state.window_ops + 1 > limit.ops_per_tick;
+                    if !fresh_window && (over_bytes || over_ops) {
+                        return false;
+                    }
+                }
+
+                state.window_bytes += bytes;
+                state.window_ops += 1;
+                state.io.logical_ops += 1;
+                match kind {
+                    IoKind::Read => state.io.logical_read_bytes += bytes,
+                    IoKind::Write => state.io.logical_write_bytes += bytes,
+                }
+                true
+            });
+
+            if admitted {
+                return;
+            }
+            platform::yield_now();
+        }
+    }
+
+    /// Çağıran görevin bir aygıt isteğini hesaba yazar (sınır uygulanmaz).
+    pub fn charge_device(&self, kind: IoKind, bytes: u64) {
+        self.with_task(current_task(), |state| {
+            state.io.device_ops += 1;
+            match kind {
+                IoKind::Read => state.io.device_read_bytes += bytes,
+                IoKind::Write => state.io.device_write_bytes += bytes,
+            }
+        });
+    }
+
+    /// Görevin kaydı üzerinde (yoksa oluşturarak) kilit altında işlem yapar.
+    fn with_task<R>(&self, task: u64, f: impl FnOnce(&mut TaskState) -> R) -> R {
+        self.lock.acquire();
+        let result = f(unsafe { &mut *self.tasks.get() }.entry(task).or_default());
+        self.lock.release();
+        result
+    }
+}
+
+
+// --- 3. Aygıt Katmanı ---
+
+/// Her blok isteğini, isteği yapan görevin hesabına yazan `BlockDevice` sarmalayıcısı.
+pub struct AccountingDevice<D: BlockDevice> {
+    inner: D,
+    accounting: Arc<IoAccounting>,
+}
+
+impl<D: BlockDevice> AccountingDevice<D> {
+    /// `inner` aygıtını, istekleri `accounting` tablosuna yazacak şekilde sarar.
+    pub fn new(inner: D, accounting: Arc<IoAccounting>) -> Self {
+        AccountingDevice { inner, accounting }
+    }
+
+    /// Sarılan aygıtı geri verir.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for AccountingDevice<D> {
+    type Error = D::Error;
+
+    fn read_block(&self, id: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.accounting.charge_device(IoKind::Read, BLOCK_SIZE as u64);
+        self.inner.read_block(id, buffer)
+    }
+
+    fn write_block(&self, id: BlockId, data: &[u8]) -> Result<(), Self::Error> {
+        self.accounting.charge_device(IoKind::Write, BLOCK_SIZE as u64);
+        self.inner.write_block(id, data)
+    }
+
+    fn total_blocks(&self) -> BlockId {
+        self.inner.total_blocks()
+    }
+
+    fn read_blocks(&self, start: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.accounting.charge_device(IoKind::Read, buffer.len() as u64);
+        self.inner.read_blocks(start, buffer)
+    }
+
+    fn write_batch(&self, blocks: &[(BlockId, &[u8])]) -> Result<(), Self::Error> {
+        self.accounting.charge_device(IoKind::Write, (blocks.len() * BLOCK_SIZE) as u64);
+        self.inner.write_batch(blocks)
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+
+    fn copies(&self) -> usize {
+        self.inner.copies()
+    }
+
+    fn read_copy(&self, id: BlockId, copy: usize, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.accounting.charge_device(IoKind::Read, BLOCK_SIZE as u64);
+        self.inner.read_copy(id, copy, buffer)
+    }
+}
+
+/// Çağıran görevin kimliği; alınamazsa `UNKNOWN_TASK`.
+fn current_task() -> u64 {
+    platform::task_id().unwrap_or(UNKNOWN_TASK)
+}
+
+/// Platformun sistem zamanı; alınamazsa `None`.
+fn system_time() -> Option<u64> {
+    platform::system_time().ok()
+}
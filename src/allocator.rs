@@ -3,10 +3,18 @@
 #![allow(dead_code, unused_variables)]
 
 use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE};
-use crate::cache::{BlockCache, SysLock};
+use crate::cache::{allocate_buffer, release_buffer, BlockCache, CacheBlock};
+use crate::platform::SysLock;
+use crate::checksum::{ChecksumAlgorithm, Checksummer};
+use crate::quota::QuotaTable;
 use crate::sahne_syscalls::SyscallError;
 use core::fmt::Debug;
-use alloc::sync::Arc;
+use core::cell::UnsafeCell;
+use core::ops::Range;
+use core::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::{Arc, Weak};
 use alloc::vec::Vec;
 
 
@@ -16,11 +24,39 @@ use alloc::vec::Vec;
 // BLOCK_SIZE (4096 bayt) * 8 bit/bayt = 32768 bloğu temsil edebilir.
 const BLOCKS_PER_BITMAP_BLOCK: BlockId = (BLOCK_SIZE * 8) as BlockId;
 
+// Boş blok sayısı henüz bilinmeyen grup (bitmap bloğu okununca hesaplanır).
+const FREE_COUNT_UNKNOWN: u32 = u32::MAX;
+
+// Alan haritası kontrol noktası bloklarının sihirli sayısı ("SMP2").
+const SPACE_MAP_MAGIC: u32 = 0x534D5032;
+
+// Grup başına yalnızca boş blok sayısı taşıyan önceki biçimin sihirli sayısı ("SMAP").
+// Bu biçim yüklenmez; sayaçlar bitmap'ten yeniden hesaplanır ve ilk commit'te yeni
+// biçimde yazılır.
+const SPACE_MAP_MAGIC_V1: u32 = 0x534D4150;
+
+// Kontrol noktası bloğunun başlığı: magic (4) + checksum (4) + first_group (4)
+// + group_count (4) + next (8) + cursor (8) + flags (4) + ayrılmış (4)
+const SPACE_MAP_HEADER_SIZE: usize = 40;
+
+// Grup girdisi: boş blok sayısı (4) + grubun bitmap bloğunun checksum'u (4)
+const SPACE_MAP_ENTRY_SIZE: usize = 8;
+
+// Bir kontrol noktası bloğunun taşıyabileceği grup girdisi sayısı.
+const SPACE_MAP_GROUPS_PER_BLOCK: usize = (BLOCK_SIZE - SPACE_MAP_HEADER_SIZE) / SPACE_MAP_ENTRY_SIZE;
+
+// Kontrol noktasındaki bitmap checksum'ları diskteki bitmap'le aynı commit'te yazıldı ve
+// doğrulanabilir. Tahsis haritası günlüksüz yazıldıysa (küçük birimler) yarıda kalan bir
+// commit haritayı kontrol noktasından farklı bırakabileceğinden bu bayrak konmaz.
+const SPACE_MAP_FLAG_BITMAP_SUMS: u32 = 1 << 0;
+
 // Tahsis Hatası Türü
 #[derive(Debug)]
 pub enum AllocatorError<D: BlockDevice> {
     DeviceError(D::Error),
     OutOfSpace,
+    /// Tahsis, bir kullanıcı veya alt birim kotasını aşacaktı (EDQUOT).
+    QuotaExceeded,
     Syscall(SyscallError),
 }
 
@@ -37,63 +73,557 @@ impl<D: BlockDevice> From<SyscallError> for AllocatorError<D> {
 }
 
 
+// --- 1.5. Yerleşim İpuçları (Placement Hints) ---
+
+/// Dosya verisinin ne sıklıkla erişileceğine dair ipucu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Temperature {
+    #[default]
+    Default,
+    /// Sık erişilen veri: hızlı katmana (SSD) yerleştirilir.
+    Hot,
+    /// Nadiren erişilen veri: yavaş katmana (HDD) yerleştirilir ve oraya taşınır.
+    Cold,
+}
+
+/// Dosya verisinin nasıl erişileceğine dair ipucu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccessPattern {
+    #[default]
+    Default,
+    /// Baştan sona okunur: parçalanmamış, tam uzunlukta extent'ler tercih edilir.
+    Sequential,
+    /// Rastgele erişilir: ilk bulunan boş alan yeterlidir.
+    Random,
+}
+
+/// Dosya başına yerleşim ipucu. Inode'da tek bayt olarak saklanır.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PlacementHint {
+    pub temperature: Temperature,
+    pub access: AccessPattern,
+}
+
+impl PlacementHint {
+    /// Disk formatı: bit 0-1 sıcaklık, bit 2-3 erişim deseni.
+    pub fn to_raw(&self) -> u8 {
+        let temperature = match self.temperature {
+            Temperature::Default => 0,
+            Temperature::Hot => 1,
+            Temperature::Cold => 2,
+        };
+        let access = match self.access {
+            AccessPattern::Default => 0,
+            AccessPattern::Sequential => 1,
+            AccessPattern::Random => 2,
+        };
+        temperature | (access << 2)
+    }
+
+    /// Disk formatından okur; tanınmayan değerler `Default` sayılır.
+    pub fn from_raw(raw: u8) -> Self {
+        PlacementHint {
+            temperature: match raw & 0b11 {
+                1 => Temperature::Hot,
+                2 => Temperature::Cold,
+                _ => Temperature::Default,
+            },
+            access: match (raw >> 2) & 0b11 {
+                1 => AccessPattern::Sequential,
+                2 => AccessPattern::Random,
+                _ => AccessPattern::Default,
+            },
+        }
+    }
+}
+
+
+// --- 1.6. Snapshot Sabitlemesi ---
+
+/// Bir snapshot'ın tahsis haritası kopyası ve snapshot'tan sonra yerinde yazılan blokların
+/// snapshot anındaki halleri (bkz. `Allocator::pin`).
+///
+/// Pin yaşadığı sürece haritasında dolu görünen bloklar canlı dosya sistemi bıraksa da
+/// serbest kalmaz (bkz. `Allocator::end_transaction`); böylece snapshot'ın gördüğü bloklar
+/// yeniden kullanılmaz. Yerinde yazılan bloklar (inode'lar, nocow veri) ise üzerlerine
+/// yazılmadan önce `preserve` ile kopyalanır. Pin'i tutan son `Arc` düşünce tuttuğu bloklar
+/// bir sonraki commit'te serbest bırakılır.
+pub struct AllocationPin {
+    /// Kopyalanan tahsis haritasının diskteki ilk bloğu.
+    bitmap_start: BlockId,
+    /// Pin anındaki toplam blok sayısı.
+    total_blocks: BlockId,
+    /// Tahsis haritası bloklarının ham kopyaları (ardışık).
+    bitmap: Vec<u8>,
+    lock: SysLock,
+    /// Yerinde yazılmadan önce korunan bloklar; kayıtlar pin yaşadıkça değişmez.
+    preserved: UnsafeCell<BTreeMap<BlockId, Box<[u8; BLOCK_SIZE]>>>,
+}
+
+// `preserved`'a her erişim `lock` altında yapıldığı için paylaşım güvenlidir.
+unsafe impl Sync for AllocationPin {}
+unsafe impl Send for AllocationPin {}
+
+impl AllocationPin {
+    /// Kopyalanan tahsis haritasının diskteki ilk bloğu.
+    pub fn bitmap_start(&self) -> BlockId {
+        self.bitmap_start
+    }
+
+    /// Pin anındaki toplam blok sayısı.
+    pub fn total_blocks(&self) -> BlockId {
+        self.total_blocks
+    }
+
+    /// Tahsis haritası kopyası (`BLOCK_SIZE`'ın katı uzunlukta).
+    pub fn bitmap(&self) -> &[u8] {
+        &self.bitmap
+    }
+
+    /// Blok pin anındaki tahsis haritasında dolu mu?
+    pub fn is_allocated(&self, id: BlockId) -> bool {
+        if id >= self.total_blocks {
+            return false;
+        }
+        // Bitmap blokları ardışık olduğundan harita tek bir bit dizisi gibi okunabilir.
+        match self.bitmap.get((id / 8) as usize) {
+            Some(byte) => byte & (1 << (id % 8)) != 0,
+            None => false,
+        }
+    }
+
+    /// Blok yerinde yazılmadan önce kopyalanmalı mı? Pin anında dolu, henüz korunmamış ve
+    /// zaten kopyası tutulan tahsis haritasının dışında olmalı.
+    pub fn needs_copy(&self, id: BlockId) -> bool {
+        let bitmap_blocks = (self.bitmap.len() / BLOCK_SIZE) as BlockId;
+        if !self.is_allocated(id) || (id >= self.bitmap_start && id < self.bitmap_start + bitmap_blocks) {
+            return false;
+        }
+        !self.is_preserved(id)
+    }
+
+    /// Bloğun pin anındaki hali korundu mu?
+    pub fn is_preserved(&self, id: BlockId) -> bool {
+        self.lock.acquire();
+        let preserved = unsafe { &*self.preserved.get() }.contains_key(&id);
+        self.lock.release();
+        preserved
+    }
+
+    /// Bloğun pin anındaki halini (`data`, bir blok boyutunda) saklar; gerekmiyorsa
+    /// (bkz. `needs_copy`) hiçbir şey yapmaz.
+    pub fn preserve(&self, id: BlockId, data: &[u8]) -> Result<(), SyscallError> {
+        if data.len() != BLOCK_SIZE {
+            return Err(SyscallError::EINVAL);
+        }
+        if !self.needs_copy(id) {
+            return Ok(());
+        }
+        let mut copy = allocate_buffer()?;
+        copy.copy_from_slice(data);
+
+        self.lock.acquire();
+        let preserved = unsafe { &mut *self.preserved.get() };
+        let duplicate = preserved.contains_key(&id);
+        if !duplicate {
+            preserved.insert(id, copy);
+        }
+        self.lock.release();
+        Ok(())
+    }
+
+    /// Blok korunduysa pin anındaki halini `out`'a kopyalar.
+    ///
+    /// # Döndürür
+    /// Blok korunmuşsa `true`.
+    pub fn read_preserved(&self, id: BlockId, out: &mut [u8]) -> bool {
+        self.lock.acquire();
+        let found = match unsafe { &*self.preserved.get() }.get(&id) {
+            Some(image) if out.len() == BLOCK_SIZE => {
+                out.copy_from_slice(image.as_ref());
+                true
+            }
+            _ => false,
+        };
+        self.lock.release();
+        found
+    }
+
+    /// Korunan blok sayısı (snapshot'ın bellekte tuttuğu ek blok kopyaları).
+    pub fn preserved_count(&self) -> usize {
+        self.lock.acquire();
+        let count = unsafe { &*self.preserved.get() }.len();
+        self.lock.release();
+        count
+    }
+}
+
+impl Drop for AllocationPin {
+    /// Korunan kopyaların arabelleklerini havuza geri verir.
+    fn drop(&mut self) {
+        core::mem::take(self.preserved.get_mut()).into_values().for_each(release_buffer);
+    }
+}
+
+
 // --- 2. Tahsis Yöneticisi Yapısı ---
 
+/// Geri alınabilir bir işlemin tahsis yöneticisinin bellekteki durumunda yaptığı
+/// değişiklikler (bkz. `Allocator::begin_undo`). Tahsis haritası blokları önbellekte
+/// geri alınır.
+struct AllocatorUndo {
+    /// `fresh` kümesine eklenen (`true`) ve çıkarılan (`false`) bloklar, sırasıyla.
+    fresh_changes: Vec<(BlockId, bool)>,
+    /// İşlem başında `pending_free`'deki blok sayısı.
+    pending_free_len: usize,
+    cursor: BlockId,
+}
+
+/// Çözülmüş alan haritası kontrol noktası (bkz. `Allocator::save_space_map`).
+struct SpaceMapImage {
+    counts: Vec<u32>,
+    sums: Vec<Option<u32>>,
+    blocks: Vec<BlockId>,
+    cursor: BlockId,
+}
+
+/// Kontrol noktası zincirini okumanın sonucu.
+enum SpaceMapRead {
+    Loaded(SpaceMapImage),
+    /// Önceki biçimde (`SPACE_MAP_MAGIC_V1`) yazılmış zincir.
+    Legacy,
+    /// Zincirin ilk geçersiz bloğu.
+    Corrupt(BlockId),
+}
+
 /// Disk üzerindeki blokların tahsis durumunu yönetir.
 /// SADAK'ın boş blok bulmasını sağlar.
 pub struct Allocator<D: BlockDevice> {
     /// Tüm I/O'yu yöneten ve blokları bellekte tutan önbellek.
     cache: Arc<BlockCache<D>>,
-    /// Disk üzerindeki toplam blok sayısı.
-    total_blocks: BlockId,
+    /// Birimin toplam blok sayısı (`grow` ile büyüyebilir).
+    total_blocks: AtomicU64,
     /// Boş blokları yönetmek için kullanılan kilit.
     lock: SysLock,
     
     // Tahsis haritasının (bitmap'in) diskteki başlangıç BlockId'si
     // Bu, dosya sisteminin Superblock'unda tutulur.
-    bitmap_start_id: BlockId, 
+    bitmap_start_id: AtomicU64, 
     /// Tahsis haritasının kaç blok kapladığı.
-    bitmap_block_count: BlockId,
+    bitmap_block_count: AtomicU64,
+    /// Son commit'ten bu yana tahsis edilen bloklar. Bunlara diskteki hiçbir tutarlı
+    /// durum işaret etmediği için CoW yapılmadan yerinde değiştirilebilirler.
+    fresh: UnsafeCell<BTreeSet<BlockId>>,
+    /// Commit tamamlanınca serbest bırakılacak, son commit'e ait eski bloklar.
+    pending_free: UnsafeCell<Vec<BlockId>>,
+    /// Hızlı katmanın (SSD) bittiği blok: `[0, fast_tier_end)` hızlı, geri kalanı yavaş
+    /// katmandır (HDD). 0 ise aygıt tek katmanlıdır.
+    fast_tier_end: AtomicU64,
+    /// Grup (bitmap bloğu) başına boş blok sayısı; `FREE_COUNT_UNKNOWN` ise grubun
+    /// bitmap bloğu henüz okunmadı. Dolu gruplar tarama sırasında hiç okunmaz.
+    group_free: UnsafeCell<Vec<u32>>,
+    /// Grup başına bitmap bloğunun diskteki (son commit'teki) içeriğinin checksum'u;
+    /// `None` ise kontrol noktası yazılırken bloktan hesaplanır.
+    group_sum: UnsafeCell<Vec<Option<u32>>>,
+    /// `allocate_block` taramasının başlayacağı grup (son tahsisin yapıldığı grup).
+    cursor: UnsafeCell<BlockId>,
+    /// Son commit'te yazılan alan haritası kontrol noktasının blokları.
+    space_map_blocks: UnsafeCell<Vec<BlockId>>,
+    /// Kullanıcı ve alt birim kotaları; sahibi bildirilen tahsislerde uygulanır.
+    quota: QuotaTable,
+    /// Tahsislerin yapılabileceği üst sınır (`set_allocation_limit`; küçültme sırasında
+    /// birimin sonundaki alana yeni blok tahsis edilmez). `BlockId::MAX` = sınır yok.
+    allocation_limit: AtomicU64,
+    /// Dosya verisine verilmeyen, metadata için ayrılmış blok sayısı (bkz. `allocate_data_extent`).
+    reserved_blocks: AtomicU64,
+    /// Alan haritası kontrol noktasının checksum algoritması (`ChecksumAlgorithm` kodu).
+    space_map_checksum: AtomicU8,
+    /// Açık geri alma kaydı (bkz. `begin_undo`).
+    undo: UnsafeCell<Option<AllocatorUndo>>,
+    /// Snapshot sabitlemeleri; düşmüş olanlar `end_transaction`'da ayıklanır.
+    pins: UnsafeCell<Vec<Weak<AllocationPin>>>,
+    /// Canlı dosya sisteminin bıraktığı ama bir pin'in hâlâ gördüğü için serbest
+    /// bırakılmayan bloklar.
+    held: UnsafeCell<BTreeSet<BlockId>>,
 }
 
 impl<D: BlockDevice> Allocator<D> {
     
-    /// Yeni bir Tahsis Yöneticisi örneği oluşturur.
-    pub fn new(cache: Arc<BlockCache<D>>, bitmap_start_id: BlockId) -> Result<Self, AllocatorError<D>> {
-        let device = cache.device.clone();
-        let total_blocks = device.total_blocks();
-        
+    /// `total_blocks` bloklu birim için yeni bir Tahsis Yöneticisi örneği oluşturur.
+    /// Aygıt birimden büyük olabilir (bkz. `grow`); fazlası tahsis edilmez.
+    pub fn new(cache: Arc<BlockCache<D>>, bitmap_start_id: BlockId, total_blocks: BlockId) -> Result<Self, AllocatorError<D>> {
+
         // Tahsis haritasının ihtiyaç duyduğu blok sayısını hesapla:
         // Toplam blok / (Blok başına bit)
         let bitmap_block_count = (total_blocks + BLOCKS_PER_BITMAP_BLOCK - 1) / BLOCKS_PER_BITMAP_BLOCK;
 
         Ok(Allocator {
             cache,
-            total_blocks,
+            total_blocks: AtomicU64::new(total_blocks),
             lock: SysLock::new().map_err(AllocatorError::Syscall)?,
-            bitmap_start_id,
-            bitmap_block_count,
+            bitmap_start_id: AtomicU64::new(bitmap_start_id),
+            bitmap_block_count: AtomicU64::new(bitmap_block_count),
+            fresh: UnsafeCell::new(BTreeSet::new()),
+            pending_free: UnsafeCell::new(Vec::new()),
+            fast_tier_end: AtomicU64::new(0),
+            group_free: UnsafeCell::new(alloc::vec![FREE_COUNT_UNKNOWN; bitmap_block_count as usize]),
+            group_sum: UnsafeCell::new(alloc::vec![None; bitmap_block_count as usize]),
+            cursor: UnsafeCell::new(0),
+            space_map_blocks: UnsafeCell::new(Vec::new()),
+            quota: QuotaTable::new().map_err(AllocatorError::Syscall)?,
+            allocation_limit: AtomicU64::new(BlockId::MAX),
+            reserved_blocks: AtomicU64::new(0),
+            space_map_checksum: AtomicU8::new(ChecksumAlgorithm::Crc32c as u8),
+            undo: UnsafeCell::new(None),
+            pins: UnsafeCell::new(Vec::new()),
+            held: UnsafeCell::new(BTreeSet::new()),
         })
     }
 
+    /// Tahsis haritasını biçimlendirir (yalnızca `format` sırasında çağrılır).
+    ///
+    /// Tüm bitmap blokları sıfırlanır; Superblock (Blok 0) ile bitmap'in kendi
+    /// blokları ve aygıt sınırının ötesine düşen bitler dolu olarak işaretlenir.
+    pub fn format_bitmap(&self) -> Result<(), AllocatorError<D>> {
+        let reserved_end = self.bitmap_start() + self.bitmap_block_count();
+        let mapped_end = self.bitmap_block_count() * BLOCKS_PER_BITMAP_BLOCK;
+
+        for i in 0..self.bitmap_block_count() {
+            let bitmap_arc = self.cache.get_block(self.bitmap_start() + i)?;
+            let bitmap_block = unsafe { &mut *bitmap_arc.get() };
+            bitmap_block.data.fill(0);
+            bitmap_block.is_dirty = true;
+        }
+
+        // Blok 0 ile bitmap arasındaki bloklar da (varsa) ayrılmış sayılır.
+        for id in (0..reserved_end).chain(self.total_blocks()..mapped_end) {
+            let (bitmap_block_id, byte_index, bit_index) = self.locate_bit(id);
+            let bitmap_arc = self.cache.get_block(bitmap_block_id)?;
+            let bitmap_block = unsafe { &mut *bitmap_arc.get() };
+            bitmap_block.data[byte_index] |= 1 << bit_index;
+        }
+
+        Ok(())
+    }
+
+    /// Birimi `new_total` bloğa büyütür; yeni alan hemen tahsis edilebilir olur.
+    ///
+    /// Harita yeni boyuta sığıyorsa yalnızca aygıt sınırının ötesi diye dolu işaretli
+    /// bitler temizlenir. Sığmıyorsa harita, yeni alanın başına büyütülmüş bir kopya
+    /// olarak taşınır: eski harita son commit'in tutarlı durumuna ait olduğundan
+    /// bloklarına dokunulmaz ve commit'ten sonra serbest kalır. Çağıran ardından
+    /// Superblock'a yeni boyutu ve `bitmap_start`'ı yazıp commit etmelidir.
+    ///
+    /// # Döndürür
+    /// Harita taşındıysa `true`.
+    pub fn grow(&self, new_total: BlockId) -> Result<bool, AllocatorError<D>> {
+        self.lock.acquire();
+        let result = self.grow_locked(new_total);
+        self.lock.release();
+        result
+    }
+
+    /// `grow` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn grow_locked(&self, new_total: BlockId) -> Result<bool, AllocatorError<D>> {
+        let old_total = self.total_blocks();
+        let old_start = self.bitmap_start();
+        let old_count = self.bitmap_block_count();
+        if new_total <= old_total {
+            return Err(AllocatorError::Syscall(SyscallError::EINVAL));
+        }
+        let new_count = (new_total + BLOCKS_PER_BITMAP_BLOCK - 1) / BLOCKS_PER_BITMAP_BLOCK;
+        let relocate = new_count != old_count;
+
+        // 1. Gerekirse haritayı yeni alanın başına kopyala; yeni gruplar boş başlar
+        if relocate {
+            let new_start = old_total;
+            if new_start + new_count > new_total {
+                return Err(AllocatorError::OutOfSpace);
+            }
+            for i in 0..new_count {
+                let target_arc = self.cache.get_block(new_start + i)?;
+                let target = unsafe { &mut *target_arc.get() };
+                if i < old_count {
+                    let source_arc = self.cache.get_block(old_start + i)?;
+                    target.data.copy_from_slice(unsafe { &*source_arc.get() }.data.as_ref());
+                } else {
+                    target.data.fill(0);
+                }
+                target.is_dirty = true;
+            }
+            self.bitmap_start_id.store(new_start, Ordering::Release);
+            self.bitmap_block_count.store(new_count, Ordering::Release);
+        }
+
+        // 2. Eski sınırın ötesindeki bitleri temizle; yeni haritanın bloklarını ve yeni
+        // sınırın ötesini dolu işaretle
+        let new_start = self.bitmap_start();
+        let mapped_end = new_count * BLOCKS_PER_BITMAP_BLOCK;
+        let updates = (old_total..new_total.min(old_count * BLOCKS_PER_BITMAP_BLOCK))
+            .map(|id| (id, false))
+            .chain((new_start..new_start + new_count).filter(|_| relocate).map(|id| (id, true)))
+            .chain((new_total..mapped_end).map(|id| (id, true)));
+        for (id, used) in updates {
+            let (bitmap_block_id, byte_index, bit_index) = self.locate_bit(id);
+            let bitmap_arc = self.cache.get_block(bitmap_block_id)?;
+            let bitmap_block = unsafe { &mut *bitmap_arc.get() };
+            if used {
+                bitmap_block.data[byte_index] |= 1 << bit_index;
+            } else {
+                bitmap_block.data[byte_index] &= !(1 << bit_index);
+            }
+            bitmap_block.is_dirty = true;
+        }
+
+        // 3. Değişen grupların sayaçları yeniden sayılır; eski harita commit'ten sonra bırakılır
+        let group_free = unsafe { &mut *self.group_free.get() };
+        group_free.resize(new_count as usize, FREE_COUNT_UNKNOWN);
+        for count in &mut group_free[(old_total / BLOCKS_PER_BITMAP_BLOCK) as usize..] {
+            *count = FREE_COUNT_UNKNOWN;
+        }
+        // Harita taşındıysa tüm bloklar, taşınmadıysa yalnızca değişen gruplar yeniden özetlenir
+        let group_sum = unsafe { &mut *self.group_sum.get() };
+        group_sum.resize(new_count as usize, None);
+        let first_changed = if relocate { 0 } else { (old_total / BLOCKS_PER_BITMAP_BLOCK) as usize };
+        group_sum[first_changed..].fill(None);
+        if relocate {
+            let pending_free = unsafe { &mut *self.pending_free.get() };
+            pending_free.extend(old_start..old_start + old_count);
+        }
+        self.total_blocks.store(new_total, Ordering::Release);
+        Ok(relocate)
+    }
+
+    /// Birimi `new_total` bloğa küçültür; `[new_total, total_blocks)` aralığı artık
+    /// haritada yer almaz.
+    ///
+    /// Çağıran, aralıkta haritanın kendi blokları dışında dolu blok kalmadığını doğrulamış
+    /// olmalıdır (bkz. `set_allocation_limit`) ve serbest bırakılmayı bekleyen blok
+    /// olmamalıdır (commit'ten hemen sonra çağrılmalı). Haritanın küçülen hali yeni
+    /// sınırın altına sığmıyorsa boş alanda ardışık bir kopyaya taşınır. Çağıran ardından
+    /// Superblock'a yeni boyutu ve `bitmap_start`'ı yazıp commit etmelidir.
+    ///
+    /// # Döndürür
+    /// Harita taşındıysa `true`.
+    pub fn shrink(&self, new_total: BlockId) -> Result<bool, AllocatorError<D>> {
+        self.lock.acquire();
+        let result = self.shrink_locked(new_total);
+        self.lock.release();
+        result
+    }
+
+    /// `shrink` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn shrink_locked(&self, new_total: BlockId) -> Result<bool, AllocatorError<D>> {
+        let old_total = self.total_blocks();
+        let old_start = self.bitmap_start();
+        let old_count = self.bitmap_block_count();
+        if new_total == 0 || new_total >= old_total {
+            return Err(AllocatorError::Syscall(SyscallError::EINVAL));
+        }
+        if !unsafe { &*self.pending_free.get() }.is_empty() {
+            return Err(AllocatorError::Syscall(SyscallError::EAGAIN));
+        }
+        let new_count = (new_total + BLOCKS_PER_BITMAP_BLOCK - 1) / BLOCKS_PER_BITMAP_BLOCK;
+        let relocate = old_start + new_count > new_total;
+
+        // 1. Haritanın kalan kısmı yeni sınırı aşıyorsa sınırın altındaki boş alana kopyala
+        if relocate {
+            let (new_start, _) = self
+                .find_free_run_locked(0..new_total, new_count, new_count)?
+                .ok_or(AllocatorError::OutOfSpace)?;
+            // Yeni blokların bitleri kopyadan önce eski haritada işaretlenir
+            self.mark_run_locked(new_start, new_count)?;
+            for i in 0..new_count {
+                let source_arc = self.cache.get_block(old_start + i)?;
+                let target_arc = self.cache.get_block(new_start + i)?;
+                let target = unsafe { &mut *target_arc.get() };
+                target.data.copy_from_slice(unsafe { &*source_arc.get() }.data.as_ref());
+                target.is_dirty = true;
+            }
+            self.bitmap_start_id.store(new_start, Ordering::Release);
+        }
+        self.bitmap_block_count.store(new_count, Ordering::Release);
+
+        // 2. Yeni sınırın ötesi, son grupta dolu işaretlenir
+        for id in new_total..new_count * BLOCKS_PER_BITMAP_BLOCK {
+            let (bitmap_block_id, byte_index, bit_index) = self.locate_bit(id);
+            let bitmap_arc = self.cache.get_block(bitmap_block_id)?;
+            let bitmap_block = unsafe { &mut *bitmap_arc.get() };
+            bitmap_block.data[byte_index] |= 1 << bit_index;
+            bitmap_block.is_dirty = true;
+        }
+
+        // 3. Sayaçları kırp; haritadan çıkan ve sınırın altında kalan eski harita blokları
+        // commit'ten sonra bırakılır
+        let group_free = unsafe { &mut *self.group_free.get() };
+        group_free.truncate(new_count as usize);
+        group_free[new_count as usize - 1] = FREE_COUNT_UNKNOWN;
+        let group_sum = unsafe { &mut *self.group_sum.get() };
+        group_sum.truncate(new_count as usize);
+        group_sum[new_count as usize - 1] = None;
+        let cursor = unsafe { &mut *self.cursor.get() };
+        if *cursor >= new_count {
+            *cursor = 0;
+        }
+        let kept = if relocate { 0..0 } else { old_start..old_start + new_count };
+        let pending_free = unsafe { &mut *self.pending_free.get() };
+        pending_free.extend((old_start..old_start + old_count).filter(|id| *id < new_total && !kept.contains(id)));
+        self.total_blocks.store(new_total, Ordering::Release);
+        Ok(relocate)
+    }
+
+    /// Yeni tahsisleri `[0, limit)` aralığıyla sınırlar; küçültmeden önce birimin sonundaki
+    /// canlı bloklar taşınırken yeni blokların oraya düşmemesi için. `BlockId::MAX` sınırı
+    /// kaldırır.
+    pub fn set_allocation_limit(&self, limit: BlockId) {
+        self.allocation_limit.store(limit, Ordering::Release);
+    }
+
+    /// Tahsis edilebilecek son bloktan bir sonraki blok.
+    fn allocatable_end(&self) -> BlockId {
+        self.total_blocks().min(self.allocation_limit.load(Ordering::Acquire))
+    }
+
     /// Yeni, boş bir disk bloğu tahsis eder (CoW için kritik).
     ///
     /// # Döndürür
     /// Tahsis edilen bloğun ID'si.
     pub fn allocate_block(&self) -> Result<BlockId, AllocatorError<D>> {
+        // Sahibi bildirilmiş tahsisler önce kotadan düşülür
+        if self.quota.reserve(1) == 0 {
+            return Err(AllocatorError::QuotaExceeded);
+        }
+
         self.lock.acquire(); // Eş zamanlı tahsisleri engellemek için kilidi al.
 
-        // Basitleştirilmiş Arama Mantığı (Gerçek FS'de bu karmaşıktır)
-        // 1. Bitmap bloklarını tek tek tara.
-        for i in 0..self.bitmap_block_count {
-            let bitmap_block_id = self.bitmap_start_id + i;
+        // 1. Bitmap bloklarını son tahsisin yapıldığı gruptan başlayarak tara; boş
+        // bloğu olmadığı bilinen grupların bitmap blokları okunmaz.
+        let cursor = unsafe { *self.cursor.get() };
+        let end = self.allocatable_end();
+        for step in 0..self.bitmap_block_count() {
+            let i = (cursor + step) % self.bitmap_block_count();
+            if i * BLOCKS_PER_BITMAP_BLOCK >= end {
+                continue;
+            }
+            match self.group_free_locked(i) {
+                Ok(0) => continue,
+                Ok(_) => {}
+                Err(e) => {
+                    self.lock.release();
+                    return Err(e);
+                }
+            }
+            let bitmap_block_id = self.bitmap_start() + i;
             
             // Önbellekten bitmap bloğunu oku (Cache/I/O)
             let bitmap_arc = self.cache.get_block(bitmap_block_id)?;
             let bitmap_block = unsafe { &mut *bitmap_arc.get() };
 
             // Bitmap içinde ilk boş biti (bloğu) ara.
-            if let Some((byte_index, bit_index)) = self.find_free_bit(&mut bitmap_block.data.as_mut()) {
+            let found = self
+                .find_free_bit(&mut bitmap_block.data.as_mut())
+                .filter(|&(byte_index, bit_index)| i * BLOCKS_PER_BITMAP_BLOCK + byte_index as BlockId * 8 + (bit_index as BlockId) < end);
+            if let Some((byte_index, bit_index)) = found {
                 
                 // 2. Bloğu tahsis et (Bit'i 1 olarak işaretle)
                 let byte_mut = &mut bitmap_block.data.as_mut()[byte_index];
@@ -108,16 +638,685 @@ impl<D: BlockDevice> Allocator<D> {
                                              (bit_index as BlockId);
                 
                 // Tahsis edilmiş blok ID'si
+                unsafe { &mut *self.fresh.get() }.insert(block_offset_in_bitmap);
+                self.note_fresh_locked(block_offset_in_bitmap, true);
+                self.adjust_group_locked(block_offset_in_bitmap, -1);
+                unsafe { *self.cursor.get() = i };
                 self.lock.release(); // Kilidi bırak.
                 return Ok(block_offset_in_bitmap);
             }
         }
 
         self.lock.release(); // Kilidi bırak.
+        self.quota.unreserve(1);
         Err(AllocatorError::OutOfSpace) // Boş blok bulunamadı
     }
-    
+
+    /// En fazla `max_blocks` uzunluğunda ardışık boş blok aralığı (extent) tahsis eder.
+    /// Dönen uzunluk istenenden kısa olabilir.
+    ///
+    /// # Döndürür
+    /// (ilk blok ID'si, blok sayısı)
+    pub fn allocate_extent(&self, max_blocks: u64) -> Result<(BlockId, u64), AllocatorError<D>> {
+        self.allocate_extent_hinted(max_blocks, PlacementHint::default())
+    }
+
+    /// Yerleşim ipucuna göre extent tahsis eder.
+    ///
+    /// Sıcak veri önce hızlı katmanda, soğuk veri önce yavaş katmanda aranır; tercih
+    /// edilen katman doluysa diğerine düşülür. Ardışık erişimli dosyalarda önce tam
+    /// uzunlukta bir boşluk aranır, bulunamazsa ilk boş aralık kullanılır.
+    pub fn allocate_extent_hinted(&self, max_blocks: u64, hint: PlacementHint) -> Result<(BlockId, u64), AllocatorError<D>> {
+        // Kota kalan miktarı aşan extent'i kısaltır
+        let max_blocks = self.quota.reserve(max_blocks.max(1));
+        if max_blocks == 0 {
+            return Err(AllocatorError::QuotaExceeded);
+        }
+        let end = self.allocatable_end();
+        let fast_end = self.fast_tier_end().min(end);
+        let (primary, secondary) = match hint.temperature {
+            Temperature::Hot => (0..fast_end, fast_end..end),
+            Temperature::Cold => (fast_end..end, 0..fast_end),
+            Temperature::Default => (0..end, 0..0),
+        };
+        let min_len = match hint.access {
+            AccessPattern::Sequential => max_blocks,
+            _ => 1,
+        };
+
+        self.lock.acquire();
+        let mut result = Err(AllocatorError::OutOfSpace);
+        'search: for region in [primary, secondary] {
+            for need in [min_len, 1] {
+                match self.find_free_run_locked(region.clone(), need, max_blocks) {
+                    Ok(Some((start, len))) => {
+                        result = self.mark_run_locked(start, len).map(|_| (start, len));
+                        break 'search;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        result = Err(e);
+                        break 'search;
+                    }
+                }
+            }
+        }
+        self.lock.release();
+
+        let used = result.as_ref().map_or(0, |&(_, len)| len);
+        self.quota.unreserve(max_blocks - used);
+        result
+    }
+
+    /// Dosya verisi için extent tahsis eder. Boş blok sayısı metadata için ayrılan
+    /// miktara (`set_reserved_blocks`) inmişse `OutOfSpace` döner; böylece veriyle dolan
+    /// birimde silme ve CoW güncellemeleri için metadata bloğu kalır.
+    pub fn allocate_data_extent(&self, max_blocks: u64, hint: PlacementHint) -> Result<(BlockId, u64), AllocatorError<D>> {
+        let reserved = self.reserved_blocks.load(Ordering::Acquire);
+        if reserved != 0 {
+            let available = self.free_blocks()?.saturating_sub(reserved);
+            if available == 0 {
+                return Err(AllocatorError::OutOfSpace);
+            }
+            return self.allocate_extent_hinted(max_blocks.min(available), hint);
+        }
+        self.allocate_extent_hinted(max_blocks, hint)
+    }
+
+    /// Metadata için ayrılan, dosya verisine verilmeyen blok sayısını ayarlar (0 = yok).
+    pub fn set_reserved_blocks(&self, blocks: u64) {
+        self.reserved_blocks.store(blocks, Ordering::Release);
+    }
+
+    /// Alan haritası kontrol noktasının checksum algoritmasını ayarlar (varsayılan CRC32C);
+    /// `load_space_map`'ten önce çağrılmalıdır.
+    pub fn set_space_map_checksum(&self, algorithm: ChecksumAlgorithm) {
+        self.space_map_checksum.store(algorithm as u8, Ordering::Release);
+    }
+
+    /// Alan haritası kontrol noktasının checksum algoritması.
+    fn space_map_checksum(&self) -> ChecksumAlgorithm {
+        ChecksumAlgorithm::from_raw(self.space_map_checksum.load(Ordering::Acquire)).unwrap_or(ChecksumAlgorithm::Crc32c)
+    }
+
+    /// Metadata için ayrılan blok sayısı.
+    pub fn reserved_blocks(&self) -> u64 {
+        self.reserved_blocks.load(Ordering::Acquire)
+    }
+
+    /// Kullanıcı ve alt birim kota tablosu.
+    pub fn quota(&self) -> &QuotaTable {
+        &self.quota
+    }
+
+    /// Hızlı katmanın sınırını ayarlar (`[0, end)` hızlı katman; 0 = katman yok).
+    pub fn set_fast_tier_end(&self, end: BlockId) {
+        self.fast_tier_end.store(end, Ordering::SeqCst);
+    }
+
+    /// Hızlı katmanın bittiği blok (0 = katman yok).
+    pub fn fast_tier_end(&self) -> BlockId {
+        self.fast_tier_end.load(Ordering::SeqCst)
+    }
+
+    /// `region` içinde en az `min_len` uzunluğunda boş bir aralık arar; aralık en fazla
+    /// `max_len` bloğa kadar büyütülür (çağıran `lock`'u tutmalıdır).
+    fn find_free_run_locked(&self, region: Range<BlockId>, min_len: u64, max_len: u64) -> Result<Option<(BlockId, u64)>, AllocatorError<D>> {
+        let mut bitmap = None;
+        let mut id = region.start;
+
+        while id < region.end {
+            // Boş bloğu olmadığı bilinen grubun tamamını atla
+            let group = id / BLOCKS_PER_BITMAP_BLOCK;
+            if unsafe { &*self.group_free.get() }[group as usize] == 0 {
+                id = (group + 1) * BLOCKS_PER_BITMAP_BLOCK;
+                continue;
+            }
+            if self.test_bit_locked(id, &mut bitmap)? {
+                id += 1;
+                continue;
+            }
+
+            let start = id;
+            let mut len = 1;
+            while len < max_len && start + len < region.end && !self.test_bit_locked(start + len, &mut bitmap)? {
+                len += 1;
+            }
+
+            if len >= min_len {
+                return Ok(Some((start, len)));
+            }
+            id = start + len;
+        }
+
+        Ok(None)
+    }
+
+    /// Bir bloğun tahsis bitini okur. `bitmap`, ardışık çağrılarda aynı bitmap bloğunu
+    /// tekrar önbellekten istememek için son kullanılan bloğu tutar.
+    fn test_bit_locked(
+        &self,
+        id: BlockId,
+        bitmap: &mut Option<(BlockId, Arc<UnsafeCell<CacheBlock>>)>,
+    ) -> Result<bool, AllocatorError<D>> {
+        let (bitmap_block_id, byte_index, bit_index) = self.locate_bit(id);
+        let bitmap_arc = match bitmap {
+            Some((cached, arc)) if *cached == bitmap_block_id => arc.clone(),
+            _ => {
+                let arc = self.cache.get_block(bitmap_block_id)?;
+                *bitmap = Some((bitmap_block_id, arc.clone()));
+                arc
+            }
+        };
+        let bitmap_block = unsafe { &*bitmap_arc.get() };
+        Ok(bitmap_block.data[byte_index] & (1 << bit_index) != 0)
+    }
+
+    /// `[start, start + len)` aralığını dolu olarak işaretler (çağıran `lock`'u tutmalıdır).
+    fn mark_run_locked(&self, start: BlockId, len: u64) -> Result<(), AllocatorError<D>> {
+        let fresh = unsafe { &mut *self.fresh.get() };
+        for id in start..start + len {
+            let (bitmap_block_id, byte_index, bit_index) = self.locate_bit(id);
+            let bitmap_arc = self.cache.get_block(bitmap_block_id)?;
+            let bitmap_block = unsafe { &mut *bitmap_arc.get() };
+            bitmap_block.data[byte_index] |= 1 << bit_index;
+            bitmap_block.is_dirty = true;
+            fresh.insert(id);
+            self.note_fresh_locked(id, true);
+            self.adjust_group_locked(id, -1);
+        }
+        Ok(())
+    }
+
+    /// Bloğun grubunun boş sayacını `delta` kadar değiştirir; sayaç bilinmiyorsa
+    /// dokunulmaz (çağıran `lock`'u tutmalıdır).
+    fn adjust_group_locked(&self, id: BlockId, delta: i32) {
+        let group_free = unsafe { &mut *self.group_free.get() };
+        let count = &mut group_free[(id / BLOCKS_PER_BITMAP_BLOCK) as usize];
+        if *count != FREE_COUNT_UNKNOWN {
+            *count = count.saturating_add_signed(delta);
+        }
+    }
+
+    /// Grubun boş blok sayısı; bilinmiyorsa bitmap bloğu okunup sayılır
+    /// (çağıran `lock`'u tutmalıdır).
+    fn group_free_locked(&self, group: BlockId) -> Result<u32, AllocatorError<D>> {
+        let known = unsafe { &*self.group_free.get() }[group as usize];
+        if known != FREE_COUNT_UNKNOWN {
+            return Ok(known);
+        }
+
+        // Aygıt sınırının ötesindeki bitler biçimlendirmede dolu işaretlendiğinden sayılmaz.
+        let bitmap_arc = self.cache.get_block(self.bitmap_start() + group)?;
+        let bitmap_block = unsafe { &*bitmap_arc.get() };
+        let free = bitmap_block.data.iter().map(|byte| byte.count_zeros()).sum();
+        let group_free = unsafe { &mut *self.group_free.get() };
+        group_free[group as usize] = free;
+        Ok(free)
+    }
+
+
+    // --- Alan Haritası Kontrol Noktası ---
+
+    /// Grup sayaçlarını ve tarama imlecini yeni tahsis edilen bloklara yazar (commit
+    /// sırasında, kirli bloklar yazılmadan önce çağrılır). Önceki kontrol noktasının
+    /// blokları bırakılır.
+    ///
+    /// Bilinmeyen sayaçlar bilinmeyen olarak yazılır; böylece büyük bir aygıtta tüm
+    /// bitmap'i okumaya gerek kalmaz. Sayacı bilinen her grubun bitmap bloğunun checksum'u
+    /// da yazılır; montaj denetimi bunlarla haritayı doğrular (bkz. `find_corrupt_space_map`).
+    /// `journaled` değilse tahsis haritası Superblock'tan önce yerine yazılacağından
+    /// checksum'lar doğrulanabilir işaretlenmez.
+    ///
+    /// # Döndürür
+    /// Zincirin ilk bloğu (Superblock'ta saklanır).
+    pub fn save_space_map(&self, journaled: bool) -> Result<BlockId, AllocatorError<D>> {
+        // 1. Önceki zinciri bırak; bloklar bu commit diske yazılınca serbest kalır
+        let old_blocks = core::mem::take(unsafe { &mut *self.space_map_blocks.get() });
+        for id in old_blocks {
+            self.release_block(id)?;
+        }
+
+        // 2. Yeni zincir için bloklar tahsis et (sayaçlar tahsislerden sonra okunmalı)
+        let group_count = self.bitmap_block_count() as usize;
+        let chain_len = group_count.div_ceil(SPACE_MAP_GROUPS_PER_BLOCK).max(1);
+        let mut blocks = Vec::with_capacity(chain_len);
+        for _ in 0..chain_len {
+            blocks.push(self.allocate_block()?);
+        }
+
+        // 3. Sayaçları zincire yaz
+        self.lock.acquire();
+        let result = self.write_space_map_locked(&blocks, journaled);
+        self.lock.release();
+        result?;
+
+        let root = blocks[0];
+        unsafe { *self.space_map_blocks.get() = blocks };
+        Ok(root)
+    }
+
+    /// `save_space_map` yazma gövdesi (çağıran `lock`'u tutmalıdır).
+    fn write_space_map_locked(&self, blocks: &[BlockId], journaled: bool) -> Result<(), AllocatorError<D>> {
+        let group_free = unsafe { &*self.group_free.get() };
+        let cursor = unsafe { *self.cursor.get() };
+        let flags = if journaled { SPACE_MAP_FLAG_BITMAP_SUMS } else { 0 };
+
+        // Sayacı bilinen grupların bitmap checksum'ları: bu commit'te yazılacak (kirli)
+        // bloklar ve özeti henüz bilinmeyenler içeriklerinden yeniden hesaplanır
+        let group_sum = unsafe { &mut *self.group_sum.get() };
+        let mut sums = Vec::with_capacity(group_free.len());
+        for (group, &count) in group_free.iter().enumerate() {
+            let id = self.bitmap_start() + group as BlockId;
+            let sum = match group_sum[group] {
+                _ if count == FREE_COUNT_UNKNOWN => 0,
+                Some(sum) if !self.cache.has_dirty_in_range(id, 1) => sum,
+                _ => {
+                    let bitmap_arc = self.cache.get_block(id)?;
+                    let sum = self.space_map_checksum().checksum(unsafe { &*bitmap_arc.get() }.data.as_ref());
+                    group_sum[group] = Some(sum);
+                    sum
+                }
+            };
+            sums.push(sum);
+        }
+
+        for (index, &id) in blocks.iter().enumerate() {
+            let first_group = index * SPACE_MAP_GROUPS_PER_BLOCK;
+            let range = first_group.min(group_free.len())..(first_group + SPACE_MAP_GROUPS_PER_BLOCK).min(group_free.len());
+            let counts = &group_free[range.clone()];
+            let next = blocks.get(index + 1).copied().unwrap_or(0);
+
+            let block_arc = self.cache.get_block(id)?;
+            let block = unsafe { &mut *block_arc.get() };
+            let data = block.data.as_mut();
+            data.fill(0);
+            data[0..4].copy_from_slice(&SPACE_MAP_MAGIC.to_le_bytes());
+            data[8..12].copy_from_slice(&(first_group as u32).to_le_bytes());
+            data[12..16].copy_from_slice(&(counts.len() as u32).to_le_bytes());
+            data[16..24].copy_from_slice(&next.to_le_bytes());
+            data[24..32].copy_from_slice(&cursor.to_le_bytes());
+            data[32..36].copy_from_slice(&flags.to_le_bytes());
+            for (i, (count, sum)) in counts.iter().zip(&sums[range]).enumerate() {
+                let at = SPACE_MAP_HEADER_SIZE + i * SPACE_MAP_ENTRY_SIZE;
+                data[at..at + 4].copy_from_slice(&count.to_le_bytes());
+                data[at + 4..at + 8].copy_from_slice(&sum.to_le_bytes());
+            }
+            let crc = self.space_map_checksum().checksum_excluding(data, 4, 4);
+            data[4..8].copy_from_slice(&crc.to_le_bytes());
+            block.is_dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Montaj sırasında, Superblock'un işaret ettiği kontrol noktasını yükler. Zincir
+    /// bozuksa veya bu aygıta uymuyorsa yok sayılır ve sayaçlar tahsis sırasında
+    /// bitmap'ten hesaplanır.
+    ///
+    /// # Döndürür
+    /// Kontrol noktası yüklendiyse `true`.
+    pub fn load_space_map(&self, root: BlockId) -> Result<bool, AllocatorError<D>> {
+        let SpaceMapRead::Loaded(image) = self.read_space_map(root)? else {
+            return Ok(false);
+        };
+
+        self.lock.acquire();
+        unsafe {
+            *self.group_free.get() = image.counts;
+            *self.group_sum.get() = image.sums;
+            *self.cursor.get() = image.cursor.min(self.bitmap_block_count().saturating_sub(1));
+            *self.space_map_blocks.get() = image.blocks;
+        }
+        self.lock.release();
+        Ok(true)
+    }
+
+    /// Montaj denetimi: `root` kontrol noktası zincirini ve zincirin checksum'unu taşıdığı
+    /// bitmap bloklarını doğrular.
+    ///
+    /// # Döndürür
+    /// Checksum'u, başlığı veya zinciri bozuk ilk kontrol noktası bloğu ya da içeriği
+    /// kayıtlı checksum'la uyuşmayan ilk bitmap bloğu. Önceki biçimdeki zincir bitmap
+    /// checksum'u taşımadığından yalnızca yüklenmez, bozuk sayılmaz.
+    pub fn find_corrupt_space_map(&self, root: BlockId) -> Result<Option<BlockId>, AllocatorError<D>> {
+        let image = match self.read_space_map(root)? {
+            SpaceMapRead::Loaded(image) => image,
+            SpaceMapRead::Legacy => return Ok(None),
+            SpaceMapRead::Corrupt(id) => return Ok(Some(id)),
+        };
+        for (group, sum) in image.sums.iter().enumerate() {
+            let Some(sum) = *sum else { continue };
+            let id = self.bitmap_start() + group as BlockId;
+            let bitmap_arc = self.cache.get_block(id)?;
+            if self.space_map_checksum().checksum(unsafe { &*bitmap_arc.get() }.data.as_ref()) != sum {
+                return Ok(Some(id));
+            }
+        }
+        Ok(None)
+    }
+
+    /// `root` kontrol noktası zincirini okur ve çözer.
+    fn read_space_map(&self, root: BlockId) -> Result<SpaceMapRead, AllocatorError<D>> {
+        let group_count = self.bitmap_block_count() as usize;
+        let max_chain_len = group_count.div_ceil(SPACE_MAP_GROUPS_PER_BLOCK).max(1);
+        let mut image = SpaceMapImage {
+            counts: Vec::with_capacity(group_count),
+            sums: Vec::with_capacity(group_count),
+            blocks: Vec::new(),
+            cursor: 0,
+        };
+        let mut id = root;
+
+        while id != 0 {
+            if id >= self.total_blocks() || image.blocks.len() >= max_chain_len {
+                return Ok(SpaceMapRead::Corrupt(id));
+            }
+            let block_arc = self.cache.get_block(id)?;
+            let data = unsafe { &*block_arc.get() }.data.as_ref();
+
+            let u32_at = |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+            let mut u64_bytes = [0u8; 8];
+            if u32_at(0) == SPACE_MAP_MAGIC_V1 && image.blocks.is_empty() {
+                return Ok(SpaceMapRead::Legacy);
+            }
+            let stored = u32_at(4);
+            let first_group = u32_at(8) as usize;
+            let len = u32_at(12) as usize;
+            if u32_at(0) != SPACE_MAP_MAGIC
+                || stored != self.space_map_checksum().checksum_excluding(data, 4, 4)
+                || first_group != image.counts.len()
+                || len > SPACE_MAP_GROUPS_PER_BLOCK
+            {
+                return Ok(SpaceMapRead::Corrupt(id));
+            }
+
+            let sums_valid = u32_at(32) & SPACE_MAP_FLAG_BITMAP_SUMS != 0;
+            for i in 0..len {
+                let at = SPACE_MAP_HEADER_SIZE + i * SPACE_MAP_ENTRY_SIZE;
+                let count = u32_at(at);
+                image.counts.push(count);
+                image.sums.push((sums_valid && count != FREE_COUNT_UNKNOWN).then(|| u32_at(at + 4)));
+            }
+            u64_bytes.copy_from_slice(&data[24..32]);
+            image.cursor = u64::from_le_bytes(u64_bytes);
+            image.blocks.push(id);
+            u64_bytes.copy_from_slice(&data[16..24]);
+            id = u64::from_le_bytes(u64_bytes);
+        }
+
+        if image.counts.len() != group_count || image.blocks.is_empty() {
+            return Ok(SpaceMapRead::Corrupt(root));
+        }
+        Ok(SpaceMapRead::Loaded(image))
+    }
+
+    /// Artık kullanılmayan bir bloğu bırakır.
+    ///
+    /// Blok son commit'ten sonra tahsis edildiyse hemen serbest bırakılır. Aksi halde
+    /// son tutarlı durum hâlâ ona işaret ettiği için serbest bırakma `end_transaction`'a
+    /// (yeni Superblock diske yazılana kadar) ertelenir; orada canlı bir pin'in gördüğü
+    /// bloklar pin düşene kadar tutulur. Taze bloklar hiçbir pin'de dolu olamaz: pin'lerin
+    /// gördüğü bloklar serbest kalmadığından yeniden tahsis de edilmez.
+    pub fn release_block(&self, id: BlockId) -> Result<(), AllocatorError<D>> {
+        self.lock.acquire();
+        let was_fresh = unsafe { &mut *self.fresh.get() }.remove(&id);
+        if was_fresh {
+            self.note_fresh_locked(id, false);
+        } else {
+            unsafe { &mut *self.pending_free.get() }.push(id);
+        }
+        self.lock.release();
+
+        if was_fresh {
+            self.free_block(id)?;
+        }
+        Ok(())
+    }
+
+    /// Blok son commit'ten sonra mı tahsis edildi? (CoW gerekmeden değiştirilebilir mi?)
+    pub fn is_fresh(&self, id: BlockId) -> bool {
+        self.lock.acquire();
+        let fresh = unsafe { &*self.fresh.get() }.contains(&id);
+        self.lock.release();
+        fresh
+    }
+
+    /// Son commit'ten sonra tahsis edilen blokların listesi (sıralı).
+    pub fn fresh_blocks(&self) -> Vec<BlockId> {
+        self.lock.acquire();
+        let fresh = unsafe { &*self.fresh.get() }.iter().copied().collect();
+        self.lock.release();
+        fresh
+    }
+
+    /// Son commit'te yazılan alan haritası kontrol noktasının blokları.
+    pub fn space_map_blocks(&self) -> Vec<BlockId> {
+        self.lock.acquire();
+        let blocks = unsafe { &*self.space_map_blocks.get() }.clone();
+        self.lock.release();
+        blocks
+    }
+
+    /// Commit tamamlandıktan sonra çağrılır: ertelenen bloklar serbest bırakılır ve
+    /// bu işlemde tahsis edilen bloklar artık kalıcı durumun parçası sayılır.
+    ///
+    /// Canlı bir pin'in (snapshot'ın) haritasında dolu görünen bloklar serbest bırakılmaz,
+    /// tutulur; pin'i düşmüş tutulan bloklar bu turda serbest kalır. Tutulan bloklar diskte
+    /// dolu göründüğünden, snapshot'lar bellekte tutulduğu için bir çökme onları sızıntı
+    /// olarak bırakır (`fsck` `Leaked` olarak onarır).
+    pub fn end_transaction(&self) -> Result<(), AllocatorError<D>> {
+        self.lock.acquire();
+        let pending = core::mem::take(unsafe { &mut *self.pending_free.get() });
+        unsafe { &mut *self.fresh.get() }.clear();
+        let pins = self.live_pins_locked();
+        let held = unsafe { &mut *self.held.get() };
+        let mut release = Vec::new();
+        held.retain(|&id| {
+            let pinned = pins.iter().any(|pin| pin.is_allocated(id));
+            if !pinned {
+                release.push(id);
+            }
+            pinned
+        });
+        for id in pending {
+            if pins.iter().any(|pin| pin.is_allocated(id)) {
+                held.insert(id);
+            } else {
+                release.push(id);
+            }
+        }
+        self.lock.release();
+
+        for id in release {
+            self.free_block(id)?;
+        }
+        Ok(())
+    }
+
+    /// O anki tahsis haritası kopyasını (`bitmap`, `bitmap_start`'tan başlayan bloklar)
+    /// bir pin olarak kaydeder; pin yaşadığı sürece haritada dolu görünen bloklar serbest
+    /// bırakılmaz (bkz. `AllocationPin`). Çağıran haritayı son commit'ten hemen sonra,
+    /// başka tahsis ve bırakma yapılmadan kopyalamalıdır.
+    pub fn pin(&self, bitmap_start: BlockId, total_blocks: BlockId, bitmap: Vec<u8>) -> Result<Arc<AllocationPin>, AllocatorError<D>> {
+        if bitmap.len() % BLOCK_SIZE != 0 {
+            return Err(AllocatorError::Syscall(SyscallError::EINVAL));
+        }
+        let pin = Arc::new(AllocationPin {
+            bitmap_start,
+            total_blocks,
+            bitmap,
+            lock: SysLock::new().map_err(AllocatorError::Syscall)?,
+            preserved: UnsafeCell::new(BTreeMap::new()),
+        });
+
+        self.lock.acquire();
+        unsafe { &mut *self.pins.get() }.push(Arc::downgrade(&pin));
+        self.lock.release();
+        Ok(pin)
+    }
+
+    /// Canlı pin'ler (yerinde yazılacak blokları korumak için).
+    pub fn live_pins(&self) -> Vec<Arc<AllocationPin>> {
+        self.lock.acquire();
+        let pins = self.live_pins_locked();
+        self.lock.release();
+        pins
+    }
+
+    /// Canlı pin'ler; düşmüş olanlar listeden çıkarılır (çağıran `lock`'u tutmalıdır).
+    fn live_pins_locked(&self) -> Vec<Arc<AllocationPin>> {
+        let pins = unsafe { &mut *self.pins.get() };
+        pins.retain(|pin| pin.strong_count() != 0);
+        pins.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    /// Blok, canlı dosya sistemi bıraktığı halde bir pin için mi tutuluyor?
+    pub fn is_held(&self, id: BlockId) -> bool {
+        self.lock.acquire();
+        let held = unsafe { &*self.held.get() }.contains(&id);
+        self.lock.release();
+        held
+    }
+
+    /// Pin'ler için tutulan blok sayısı.
+    pub fn held_count(&self) -> u64 {
+        self.lock.acquire();
+        let count = unsafe { &*self.held.get() }.len() as u64;
+        self.lock.release();
+        count
+    }
+
+    /// Son commit'ten beri yapılan tahsis ve bırakmaları unutur (geri alınan transaction).
+    ///
+    /// Çağıran, tahsis haritasının kirli önbellek kopyalarını ayrıca atmalıdır; grup boş
+    /// sayıları bilinmez işaretlenir ve haritadan yeniden okunur. Kotada ayrılan bloklar
+    /// da bırakılır.
+    pub fn abort_transaction(&self) {
+        self.lock.acquire();
+        unsafe { &mut *self.pending_free.get() }.clear();
+        unsafe { &mut *self.fresh.get() }.clear();
+        unsafe { &mut *self.group_free.get() }.fill(FREE_COUNT_UNKNOWN);
+        unsafe { &mut *self.group_sum.get() }.fill(None);
+        self.lock.release();
+        self.quota.end_charge();
+    }
+
+    /// Tek bir işlemin tahsis ve bırakmalarını kaydetmeye başlar; `rollback_undo` bunları
+    /// geri alır. Tahsis haritasının kirli blokları önbelleğin kaydıyla (bkz.
+    /// `BlockCache::begin_undo`) ayrıca geri alınmalıdır.
+    pub fn begin_undo(&self) {
+        self.lock.acquire();
+        unsafe {
+            *self.undo.get() = Some(AllocatorUndo {
+                fresh_changes: Vec::new(),
+                pending_free_len: (*self.pending_free.get()).len(),
+                cursor: *self.cursor.get(),
+            });
+        }
+        self.lock.release();
+    }
+
+    /// Kaydı kapatır; işlemin tahsis ve bırakmaları kalır.
+    pub fn end_undo(&self) {
+        self.lock.acquire();
+        unsafe { *self.undo.get() = None };
+        self.lock.release();
+    }
+
+    /// Kayıt boyunca yapılan tahsis ve bırakmaları ters sırayla geri alır ve kaydı kapatır.
+    /// Grup boş sayıları bilinmez işaretlenir ve (geri alınmış) haritadan yeniden okunur.
+    pub fn rollback_undo(&self) {
+        self.lock.acquire();
+        if let Some(undo) = unsafe { &mut *self.undo.get() }.take() {
+            let fresh = unsafe { &mut *self.fresh.get() };
+            for &(id, added) in undo.fresh_changes.iter().rev() {
+                if added {
+                    fresh.remove(&id);
+                } else {
+                    fresh.insert(id);
+                }
+            }
+            unsafe { &mut *self.pending_free.get() }.truncate(undo.pending_free_len);
+            unsafe { *self.cursor.get() = undo.cursor };
+            unsafe { &mut *self.group_free.get() }.fill(FREE_COUNT_UNKNOWN);
+            unsafe { &mut *self.group_sum.get() }.fill(None);
+        }
+        self.lock.release();
+    }
+
+    /// Geri alma kaydı açıksa `fresh` kümesindeki değişikliği kaydeder (çağıran `lock`'u
+    /// tutmalıdır).
+    fn note_fresh_locked(&self, id: BlockId, added: bool) {
+        if let Some(undo) = unsafe { &mut *self.undo.get() } {
+            undo.fresh_changes.push((id, added));
+        }
+    }
+
+    /// Tahsis haritasının kapladığı blok sayısını döndürür.
+    pub fn bitmap_block_count(&self) -> BlockId {
+        self.bitmap_block_count.load(Ordering::Acquire)
+    }
+
+    /// Tahsis haritasının ilk bloğu (`grow` haritayı taşıyabilir).
+    pub fn bitmap_start(&self) -> BlockId {
+        self.bitmap_start_id.load(Ordering::Acquire)
+    }
+
+    /// Disk üzerindeki toplam blok sayısı.
+    pub fn total_blocks(&self) -> BlockId {
+        self.total_blocks.load(Ordering::Acquire)
+    }
+
+    /// Tahsis haritasına göre boş blok sayısı. Sayacı bilinmeyen grupların bitmap blokları
+    /// okunup sayılır ve sayaçlar sonraki çağrılar için saklanır. Serbest bırakılması
+    /// commit'e ertelenen bloklar dolu sayılır (bkz. `pending_free_count`).
+    pub fn free_blocks(&self) -> Result<u64, AllocatorError<D>> {
+        self.lock.acquire();
+        let mut free = 0u64;
+        let mut result = Ok(());
+        for group in 0..self.bitmap_block_count() {
+            match self.group_free_locked(group) {
+                Ok(count) => free += count as u64,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        self.lock.release();
+        result.map(|_| free)
+    }
+
+    /// Commit tamamlanınca serbest bırakılacak blok sayısı.
+    pub fn pending_free_count(&self) -> u64 {
+        self.lock.acquire();
+        let count = unsafe { &*self.pending_free.get() }.len() as u64;
+        self.lock.release();
+        count
+    }
+
+    /// Verilen bloğun tahsis haritasında dolu (1) olarak işaretli olup olmadığını döndürür.
+    pub fn is_allocated(&self, id: BlockId) -> Result<bool, AllocatorError<D>> {
+        if id >= self.total_blocks() {
+            return Ok(false);
+        }
+
+        let (bitmap_block_id, byte_index, bit_index) = self.locate_bit(id);
+        let bitmap_arc = self.cache.get_block(bitmap_block_id)?;
+        let bitmap_block = unsafe { &*bitmap_arc.get() };
+
+        Ok(bitmap_block.data[byte_index] & (1 << bit_index) != 0)
+    }
+
     // --- Yardımcı Fonksiyonlar ---
+
+    /// Bir bloğun tahsis haritasındaki yerini bulur: (bitmap bloğu, bayt indeksi, bit indeksi).
+    fn locate_bit(&self, id: BlockId) -> (BlockId, usize, u8) {
+        let bitmap_block_id = self.bitmap_start() + id / BLOCKS_PER_BITMAP_BLOCK;
+        let bit_in_block = id % BLOCKS_PER_BITMAP_BLOCK;
+
+        (bitmap_block_id, (bit_in_block / 8) as usize, (bit_in_block % 8) as u8)
+    }
     
     /// Verilen bitmap diliminde ilk boş (0) biti bulur.
     fn find_free_bit(&self, bitmap: &mut [u8]) -> Option<(usize, u8)> {
@@ -137,11 +1336,58 @@ impl<D: BlockDevice> Allocator<D> {
     }
 
     /// Tahsis edilmiş bir bloğu serbest bırakır (Bit'i 0 olarak işaretler).
+    /// Son tutarlı durumun hâlâ kullandığı bloklar için `release_block` tercih edilmelidir.
     pub fn free_block(&self, id: BlockId) -> Result<(), AllocatorError<D>> {
-        // Bu fonksiyon, id'yi kullanarak ilgili bitmap bloğunu bulur,
-        // önbelleğe alır, biti 0 yapar ve bloğu kirli olarak işaretler.
-        // Implementasyon için allocate_block'un tersi mantık gereklidir.
-        // ...
+        if id >= self.total_blocks() {
+            return Err(AllocatorError::Syscall(SyscallError::EINVAL));
+        }
+
+        self.lock.acquire();
+
+        // allocate_block'un tersi: ilgili bitmap bloğunu bul, biti 0 yap, kirli işaretle.
+        let (bitmap_block_id, byte_index, bit_index) = self.locate_bit(id);
+        let bitmap_arc = match self.cache.get_block(bitmap_block_id) {
+            Ok(arc) => arc,
+            Err(e) => {
+                self.lock.release();
+                return Err(AllocatorError::DeviceError(e));
+            }
+        };
+        let bitmap_block = unsafe { &mut *bitmap_arc.get() };
+        if bitmap_block.data[byte_index] & (1 << bit_index) != 0 {
+            self.adjust_group_locked(id, 1);
+        }
+        bitmap_block.data[byte_index] &= !(1 << bit_index);
+        bitmap_block.is_dirty = true;
+
+        self.lock.release();
+        Ok(())
+    }
+
+    /// Kalıcı durumun zaten başvurduğu ama haritada boş görünen bir bloğu dolu işaretler
+    /// (tutarlılık onarımı). Blok taze sayılmaz; CoW ile korunmaya devam eder.
+    pub fn mark_allocated(&self, id: BlockId) -> Result<(), AllocatorError<D>> {
+        if id >= self.total_blocks() {
+            return Err(AllocatorError::Syscall(SyscallError::EINVAL));
+        }
+
+        self.lock.acquire();
+        let (bitmap_block_id, byte_index, bit_index) = self.locate_bit(id);
+        let bitmap_arc = match self.cache.get_block(bitmap_block_id) {
+            Ok(arc) => arc,
+            Err(e) => {
+                self.lock.release();
+                return Err(AllocatorError::DeviceError(e));
+            }
+        };
+        let bitmap_block = unsafe { &mut *bitmap_arc.get() };
+        if bitmap_block.data[byte_index] & (1 << bit_index) == 0 {
+            self.adjust_group_locked(id, -1);
+        }
+        bitmap_block.data[byte_index] |= 1 << bit_index;
+        bitmap_block.is_dirty = true;
+
+        self.lock.release();
         Ok(())
     }
 }
@@ -0,0 +1,63 @@
+// src/api.rs
+
+// Kararlı, sürümlenmiş genel API cephesi. Dış tüketiciler (ana makine araçları, fuzz
+// testleri, başka çekirdekler, Sahne64 sürücüleri) modüllere doğrudan değil bu cepheye
+// bağlanmalıdır; modüller arasındaki taşımalar bu yolları değiştirmez.
+//
+// `core`: sistem çağrısı bağımlılığı olmayan motor (dosya sistemi, B-Ağacı, tahsis,
+// checksum, önbellek politikası) ve taşınabilir aygıtlar. Yalnızca `platform` ilkellerine
+// dayanır; `host` özelliğiyle her yerde derlenir.
+// `kernel`: Sahne64'e özgü arka uç (kaynak, virtio, NVMe aygıtları ve aygıt keşfi).
+
+#![allow(dead_code, unused_variables)]
+
+
+// --- 1. Sürüm ---
+
+/// Cephenin ana sürümü; yollar kaldırıldığında ya da imzalar uyumsuz değiştiğinde artar.
+pub const API_VERSION_MAJOR: u16 = 1;
+
+/// Cephenin ara sürümü; yalnızca ekleme yapıldığında artar.
+pub const API_VERSION_MINOR: u16 = 1;
+
+/// Tüketicinin derlendiği `major.minor` sürümünün bu cepheyle uyumlu olup olmadığı:
+/// ana sürüm aynı ve tüketicinin beklediği ara sürüm bu cepheninkinden büyük olmamalıdır.
+pub const fn is_compatible(major: u16, minor: u16) -> bool {
+    major == API_VERSION_MAJOR && minor <= API_VERSION_MINOR
+}
+
+
+// --- 2. Motor (sadak-core) ---
+
+pub mod core {
+    pub use crate::block_device::{BlockDevice, BlockDeviceError, BlockId, BufferRequirements, RamDevice, BLOCK_SIZE};
+    pub use crate::checksum::{ChecksumAlgorithm, ChecksumPolicy};
+    pub use crate::clock::{Clock, FakeClock, KernelClock, MonotonicClock};
+    pub use crate::crypt::{CryptDevice, CryptError, FileKey, CRYPT_KEY_SIZE};
+    pub use crate::dir::{Casefold, DirEntry};
+    pub use crate::fs::{
+        Access, Credentials, DataMode, DirCursor, DirIterator, DiskUsage, EarlyFs, ErrorsPolicy, ExportHandle, FeatureSet, FileHandle,
+        FormatOptions, FsStat, Inode, MountOptions, OpenFlags, OpenMode, SadakFs, SadakFsError, ScrubReport,
+        SeekFrom, SpaceUsage, Superblock, Transaction,
+    };
+    pub use crate::kv::{KvStore, KvTransaction};
+    pub use crate::multipath::{MultipathDevice, MultipathError, PathPolicy};
+    pub use crate::platform::SysLock;
+    pub use crate::sahne_syscalls::SyscallError;
+    #[cfg(feature = "raid")]
+    pub use crate::raid::{Raid1Device, RaidError};
+    #[cfg(feature = "snapshot")]
+    pub use crate::snapshot::{RetentionPolicy, Snapshot, SnapshotDevice, SnapshotError};
+}
+
+
+// --- 3. Sahne64 Arka Ucu (sadak-kernel) ---
+
+#[cfg(not(feature = "host"))]
+pub mod kernel {
+    pub use crate::block_device::Sahne64Device;
+    pub use crate::discovery::{discover, find_by_label, find_by_uuid, DiscoveredVolume};
+    pub use crate::hotplug::{DeviceEvent, HotplugListener};
+    pub use crate::nvme::NvmeDevice;
+    pub use crate::virtio::VirtioBlkDevice;
+}
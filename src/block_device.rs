@@ -3,10 +3,18 @@
 // sahne_syscalls modülünü dahil et (Bir önceki adımda oluşturuldu)
 use crate::sahne_syscalls::{
     self, ResourceHandle, SyscallError,
-    SYSCALL_RESOURCE_READ, SYSCALL_RESOURCE_WRITE,
+    SYSCALL_RESOURCE_READ, SYSCALL_RESOURCE_WRITE, SYSCALL_RESOURCE_RELEASE,
     raw_syscall
 };
 use core::fmt::Debug;
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+use alloc::vec;
+use alloc::vec::Vec;
+
+// Her BlockDevice uygulamasının çalıştırabileceği uygunluk (conformance) test paketi.
+#[cfg(feature = "host")]
+pub mod conformance;
 
 // --- 1. Sabit Tanımlamaları ---
 // Sektör/Blok boyutu (genellikle 4096 bayt). 
@@ -23,6 +31,32 @@ impl BlockDeviceError for SyscallError {}
 
 
 // --- 3. Blok Aygıt Trait'i ---
+
+/// Aygıtın kopyasız (doğrudan bellek erişimiyle) aktarım yapabilmesi için arabelleklerden
+/// beklediği özellikler (bkz. `BlockDevice::buffer_requirements`). Önbellek blok
+/// arabelleklerini buna göre tahsis eder; gereksinimi karşılamayan arabellekler aygıtın
+/// olağan yoluyla, kendi ara belleğinden (bounce buffer) kopyalanarak aktarılır.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferRequirements {
+    /// Arabellek başlangıcının hizalanması gereken bayt sayısı (2'nin kuvveti, en fazla `BLOCK_SIZE`).
+    pub alignment: usize,
+    /// Arabellek fiziksel olarak ardışık olmalı ve fiziksel adresi bilinmeli mi (DMA)?
+    pub dma: bool,
+}
+
+impl BufferRequirements {
+    /// Özel gereksinim yok; aygıt her arabelleği olağan yoluyla aktarır.
+    pub const NONE: BufferRequirements = BufferRequirements { alignment: 1, dma: false };
+    /// Sayfa (blok) hizalı, fiziksel olarak ardışık DMA belleği; virtio ve NVMe bunu ister.
+    pub const DMA_PAGE: BufferRequirements = BufferRequirements { alignment: BLOCK_SIZE, dma: true };
+
+    /// `address`'teki, fiziksel adresi `phys` olan (biliniyorsa) arabellek bu gereksinimleri
+    /// karşılıyor mu?
+    pub fn is_met(&self, address: usize, phys: Option<u64>) -> bool {
+        address % self.alignment == 0 && (!self.dma || phys.is_some())
+    }
+}
+
 /// SADAK dosya sisteminin temel disk I/O işlemlerini soyutlayan trait.
 /// Bu trait, her türlü fiziksel sürücü (HDD, SSD, SD kart) için uygulanmalıdır.
 pub trait BlockDevice {
@@ -46,12 +80,80 @@ pub trait BlockDevice {
     /// Aygıtın toplam blok sayısını döndürür.
     fn total_blocks(&self) -> BlockId;
 
+    /// (Opsiyonel) `start` bloğundan başlayan ardışık blokları tek istekte okur.
+    ///
+    /// `buffer` uzunluğu `BLOCK_SIZE`'ın katı olmalıdır. Varsayılan uygulama blok blok
+    /// okur; tek seferde büyük I/O yapabilen aygıtlar bunu geçersiz kılmalıdır.
+    fn read_blocks(&self, start: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        for (i, chunk) in buffer.chunks_mut(BLOCK_SIZE).enumerate() {
+            self.read_block(start + i as BlockId, chunk)?;
+        }
+        Ok(())
+    }
+
+    /// (Opsiyonel) Birbirinden bağımsız blok yazmalarını tek seferde verir.
+    ///
+    /// Sıra ve kısmi başarı garanti edilmez: hata dönerse yazmaların bir kısmı diske
+    /// ulaşmış olabilir. Varsayılan uygulama blok blok yazar; birden çok kuyruğu olan
+    /// aygıtlar (NVMe) istekleri paralel yürütmek için bunu geçersiz kılmalıdır.
+    fn write_batch(&self, blocks: &[(BlockId, &[u8])]) -> Result<(), Self::Error> {
+        for &(id, data) in blocks {
+            self.write_block(id, data)?;
+        }
+        Ok(())
+    }
+
     /// (Opsiyonel) Verilerin kalıcı olarak diske yazılmasını zorlar (fsync).
     fn flush(&self) -> Result<(), Self::Error> {
         Ok(()) // Varsayılan olarak hiçbir şey yapmaz
     }
+
+    /// (Opsiyonel) Her bloğun tutulduğu bağımsız kopya sayısı (ör. yansıtmalı dizide üye
+    /// sayısı). Varsayılan 1'dir.
+    fn copies(&self) -> usize {
+        1
+    }
+
+    /// (Opsiyonel) Bloğun `copy` numaralı kopyasını okur. Okuma onarımı, checksum'u
+    /// tutmayan bir bloğun sağlam kopyasını aramak için kullanır. Varsayılan uygulama
+    /// tek kopyayı `read_block` ile okur.
+    fn read_copy(&self, id: BlockId, copy: usize, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_block(id, buffer)
+    }
+
+    /// (Opsiyonel) Kopyasız aktarım için arabellek gereksinimleri. Varsayılan `NONE`;
+    /// aşağıdaki `_dma` yöntemlerini geçersiz kılan aygıtlar gerçek gereksinimlerini bildirmelidir.
+    fn buffer_requirements(&self) -> BufferRequirements {
+        BufferRequirements::NONE
+    }
+
+    /// (Opsiyonel) Bloğu fiziksel adresi `phys` olan `buffer`'a ara bellek kopyası olmadan
+    /// okur. Varsayılan uygulama `read_block`'a düşer.
+    ///
+    /// # Safety
+    /// `phys`, `buffer`'ın fiziksel adresi olmalı; `buffer` fiziksel olarak ardışık olmalı
+    /// ve `buffer_requirements`'ı karşılamalıdır. Aksi halde aygıt rastgele belleğe yazar.
+    unsafe fn read_block_dma(&self, id: BlockId, buffer: &mut [u8], phys: u64) -> Result<(), Self::Error> {
+        self.read_block(id, buffer)
+    }
+
+    /// (Opsiyonel) `write_batch`'in kopyasız hali: `phys[i]`, `blocks[i]` verisinin fiziksel
+    /// adresidir; `None` olan bloklar olağan yoldan (ara bellekle) yazılır. Varsayılan
+    /// uygulama `write_batch`'e düşer.
+    ///
+    /// # Safety
+    /// `Some` olan her adres, `read_block_dma`'daki koşullarla ilgili verinin fiziksel adresi olmalıdır.
+    unsafe fn write_batch_dma(&self, blocks: &[(BlockId, &[u8])], phys: &[Option<u64>]) -> Result<(), Self::Error> {
+        self.write_batch(blocks)
+    }
 }
 
+// TODO: Şifreleme katmanı (`crypt::CryptDevice`) çevrimiçi birim anahtarı değişimini
+// desteklemeli: bloklar arka planda eski anahtardan yeni anahtara yeniden
+// şifrelenir ve ilerleme kalıcı bir imleçte (anahtar başlığında) tutulur; okumalar blok
+// imlecin gerisindeyse yeni, ilerisindeyse eski anahtarı kullanır. Şu an yalnızca
+// parola (anahtar yuvası) değiştirilebilir.
+
 
 // --- 4. Sahne64 Tabanlı Blok Aygıt Uygulaması ---
 
@@ -80,6 +182,12 @@ impl Sahne64Device {
     }
 }
 
+impl Drop for Sahne64Device {
+    fn drop(&mut self) {
+        unsafe { raw_syscall(SYSCALL_RESOURCE_RELEASE, self.handle, 0, 0, 0, 0, 0) };
+    }
+}
+
 impl BlockDevice for Sahne64Device {
     type Error = SyscallError;
 
@@ -170,4 +278,118 @@ impl BlockDevice for Sahne64Device {
     fn total_blocks(&self) -> BlockId {
         self.capacity_blocks
     }
-}
\ No newline at end of file
+
+    fn read_blocks(&self, start: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if buffer.is_empty() || buffer.len() % BLOCK_SIZE != 0 {
+            return Err(SyscallError::EINVAL);
+        }
+
+        // 1. Tek bir konumlanma ile ilk bloğa git
+        let offset = start * BLOCK_SIZE as u64;
+        let seek_result = unsafe {
+            raw_syscall(
+                sahne_syscalls::SYSCALL_RESOURCE_SEEK,
+                self.handle,
+                offset,
+                0, 0, 0, 0,
+            )
+        };
+        if seek_result < 0 {
+            return Err(SyscallError::from_raw(seek_result));
+        }
+
+        // 2. Tüm aralığı tek bir okuma çağrısıyla al
+        let read_len = unsafe {
+            raw_syscall(
+                SYSCALL_RESOURCE_READ,
+                self.handle,
+                buffer.as_mut_ptr() as u64,
+                buffer.len() as u64,
+                0, 0, 0,
+            )
+        };
+
+        if read_len as usize != buffer.len() {
+            if read_len < 0 {
+                return Err(SyscallError::from_raw(read_len));
+            } else {
+                return Err(SyscallError::EIO); // Aralığın tamamı okunamadı
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// --- 5. Bellek Tabanlı Blok Aygıt (RAM Disk) ---
+
+/// Tüm blokları bellekte tutan basit bir BlockDevice uygulaması.
+/// Geçici birimler, testler ve uygunluk (conformance) paketinin referans aygıtı olarak kullanılır.
+pub struct RamDevice {
+    /// Aygıtın ham içeriği (capacity_blocks * BLOCK_SIZE bayt).
+    storage: UnsafeCell<Vec<u8>>,
+    /// Depolamaya eş zamanlı erişimi koruyan basit döndürme kilidi (spin lock).
+    busy: AtomicBool,
+    /// Aygıtın toplam kapasitesi (blok cinsinden).
+    capacity_blocks: BlockId,
+}
+
+// Depolamaya her erişim `busy` kilidi altında yapıldığı için paylaşım güvenlidir.
+unsafe impl Sync for RamDevice {}
+
+impl Debug for RamDevice {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RamDevice").field("capacity_blocks", &self.capacity_blocks).finish()
+    }
+}
+
+impl RamDevice {
+    /// Verilen kapasitede, sıfırlanmış yeni bir RAM diski oluşturur.
+    pub fn new(capacity: BlockId) -> Self {
+        RamDevice {
+            storage: UnsafeCell::new(vec![0u8; capacity as usize * BLOCK_SIZE]),
+            busy: AtomicBool::new(false),
+            capacity_blocks: capacity,
+        }
+    }
+
+    /// Kilidi alarak depolamanın ilgili bloğu üzerinde işlem yapar.
+    fn with_block<R>(&self, id: BlockId, f: impl FnOnce(&mut [u8]) -> R) -> R {
+        while self.busy.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            core::hint::spin_loop();
+        }
+
+        let storage = unsafe { &mut *self.storage.get() };
+        let start = id as usize * BLOCK_SIZE;
+        let result = f(&mut storage[start..start + BLOCK_SIZE]);
+
+        self.busy.store(false, Ordering::Release);
+        result
+    }
+}
+
+impl BlockDevice for RamDevice {
+    type Error = SyscallError;
+
+    fn read_block(&self, id: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if buffer.len() != BLOCK_SIZE || id >= self.capacity_blocks {
+            return Err(SyscallError::EINVAL);
+        }
+
+        self.with_block(id, |block| buffer.copy_from_slice(block));
+        Ok(())
+    }
+
+    fn write_block(&self, id: BlockId, data: &[u8]) -> Result<(), Self::Error> {
+        if data.len() != BLOCK_SIZE || id >= self.capacity_blocks {
+            return Err(SyscallError::EINVAL);
+        }
+
+        self.with_block(id, |block| block.copy_from_slice(data));
+        Ok(())
+    }
+
+    fn total_blocks(&self) -> BlockId {
+        self.capacity_blocks
+    }
+}
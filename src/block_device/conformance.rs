@@ -0,0 +1,274 @@
+// src/block_device/conformance.rs
+
+#![allow(dead_code, unused_variables)]
+
+// Eş zamanlılık testleri için iş parçacıkları gerekir; bu modül yalnızca
+// host özelliğiyle derlendiği için std burada kullanılabilir.
+extern crate std;
+
+use super::{BlockDevice, BlockId, BLOCK_SIZE};
+use core::sync::atomic::{AtomicBool, Ordering};
+use alloc::vec;
+use alloc::vec::Vec;
+use std::thread;
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Eş zamanlılık testinde çalıştırılacak iş parçacığı sayısı.
+const CONCURRENCY_THREADS: u64 = 4;
+
+// Her iş parçacığının yazacağı blok sayısı.
+const BLOCKS_PER_THREAD: u64 = 8;
+
+/// Bir BlockDevice uygulamasının ihlal ettiği sözleşme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConformanceFailure {
+    /// Aygıt sıfır blok bildiriyor.
+    ZeroSize,
+    /// `BLOCK_SIZE` dışındaki bir arabellek hata verilmeden kabul edildi.
+    AcceptedMisalignedBuffer,
+    /// Aygıt sınırı dışındaki bir blok hata verilmeden kabul edildi.
+    AcceptedOutOfRange(BlockId),
+    /// Geçerli bir blok üzerinde I/O başarısız oldu.
+    IoFailed(BlockId),
+    /// Okunan veri, yazılan veriyle eşleşmiyor.
+    DataMismatch(BlockId),
+    /// `flush` çağrısı başarısız oldu.
+    FlushFailed,
+    /// Alttaki aygıtların tamamı hata verirken işlem başarılı döndü.
+    ErrorSwallowed(BlockId),
+}
+
+
+// --- 2. Hata Enjeksiyonu İçin Yardımcı Aygıt ---
+
+/// Başka bir aygıtı sarmalayan ve istendiğinde tüm I/O'yu başarısız kılan aygıt.
+/// Katmanlı aygıtların (RAID, vb.) hata yayılımını test etmek için üye olarak kullanılır.
+#[derive(Debug)]
+pub struct FaultyDevice<D: BlockDevice> {
+    inner: D,
+    failing: AtomicBool,
+}
+
+impl<D: BlockDevice> FaultyDevice<D> {
+    /// Sağlıklı durumda başlayan yeni bir hata enjeksiyon aygıtı oluşturur.
+    pub fn new(inner: D) -> Self {
+        FaultyDevice { inner, failing: AtomicBool::new(false) }
+    }
+
+    /// Hata enjeksiyonunu açar veya kapatır.
+    pub fn set_failing(&self, failing: bool) {
+        self.failing.store(failing, Ordering::SeqCst);
+    }
+
+    /// Şu anda hata enjekte ediliyor mu?
+    fn is_failing(&self) -> bool {
+        self.failing.load(Ordering::SeqCst)
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for FaultyDevice<D>
+where
+    D::Error: From<crate::sahne_syscalls::SyscallError>,
+{
+    type Error = D::Error;
+
+    fn read_block(&self, id: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if self.is_failing() {
+            return Err(D::Error::from(crate::sahne_syscalls::SyscallError::EIO));
+        }
+        self.inner.read_block(id, buffer)
+    }
+
+    fn write_block(&self, id: BlockId, data: &[u8]) -> Result<(), Self::Error> {
+        if self.is_failing() {
+            return Err(D::Error::from(crate::sahne_syscalls::SyscallError::EIO));
+        }
+        self.inner.write_block(id, data)
+    }
+
+    fn total_blocks(&self) -> BlockId {
+        self.inner.total_blocks()
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        if self.is_failing() {
+            return Err(D::Error::from(crate::sahne_syscalls::SyscallError::EIO));
+        }
+        self.inner.flush()
+    }
+}
+
+
+// --- 3. Sözleşme Kontrolleri ---
+// DİKKAT: Kontroller yıkıcıdır; test edilen aygıtın blokları üzerine yazılır.
+
+/// Test bloklarına yazılacak, bloğa ve tohuma (seed) özgü desen.
+fn pattern(id: BlockId, seed: u8) -> [u8; BLOCK_SIZE] {
+    let mut data = [0u8; BLOCK_SIZE];
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte = (id as u8) ^ seed ^ (i as u8).rotate_left(3);
+    }
+    data
+}
+
+/// Bir bloğa desen yazar ve geri okuyarak doğrular.
+fn write_and_verify<D: BlockDevice>(device: &D, id: BlockId, seed: u8) -> Result<(), ConformanceFailure> {
+    let expected = pattern(id, seed);
+    device.write_block(id, &expected).map_err(|_| ConformanceFailure::IoFailed(id))?;
+
+    let mut buffer = vec![0u8; BLOCK_SIZE];
+    device.read_block(id, &mut buffer).map_err(|_| ConformanceFailure::IoFailed(id))?;
+
+    if buffer[..] != expected[..] {
+        return Err(ConformanceFailure::DataMismatch(id));
+    }
+    Ok(())
+}
+
+/// Aygıt sıfırdan büyük bir boyut bildirmelidir.
+pub fn check_size<D: BlockDevice>(device: &D) -> Result<(), ConformanceFailure> {
+    if device.total_blocks() == 0 {
+        return Err(ConformanceFailure::ZeroSize);
+    }
+    Ok(())
+}
+
+/// `BLOCK_SIZE` dışındaki arabellekler hem okumada hem yazmada reddedilmelidir.
+pub fn check_alignment<D: BlockDevice>(device: &D) -> Result<(), ConformanceFailure> {
+    let mut short = vec![0u8; BLOCK_SIZE - 1];
+    let mut long = vec![0u8; BLOCK_SIZE + 1];
+
+    if device.read_block(0, &mut short).is_ok()
+        || device.read_block(0, &mut long).is_ok()
+        || device.write_block(0, &short).is_ok()
+        || device.write_block(0, &long).is_ok()
+    {
+        return Err(ConformanceFailure::AcceptedMisalignedBuffer);
+    }
+    Ok(())
+}
+
+/// İlk, ortadaki ve son blok yazılıp aynen geri okunabilmelidir;
+/// aygıt sınırının dışındaki bloklar ise reddedilmelidir.
+pub fn check_roundtrip_and_bounds<D: BlockDevice>(device: &D) -> Result<(), ConformanceFailure> {
+    let total = device.total_blocks();
+    for id in [0, total / 2, total - 1] {
+        write_and_verify(device, id, 0xA5)?;
+    }
+
+    let mut buffer = vec![0u8; BLOCK_SIZE];
+    if device.read_block(total, &mut buffer).is_ok() {
+        return Err(ConformanceFailure::AcceptedOutOfRange(total));
+    }
+    if device.write_block(total, &buffer).is_ok() {
+        return Err(ConformanceFailure::AcceptedOutOfRange(total));
+    }
+    Ok(())
+}
+
+/// `flush` başarılı olmalı ve önceden yazılan veriyi korumalıdır.
+pub fn check_flush<D: BlockDevice>(device: &D) -> Result<(), ConformanceFailure> {
+    let id = device.total_blocks() - 1;
+    let expected = pattern(id, 0x3C);
+    device.write_block(id, &expected).map_err(|_| ConformanceFailure::IoFailed(id))?;
+    device.flush().map_err(|_| ConformanceFailure::FlushFailed)?;
+
+    let mut buffer = vec![0u8; BLOCK_SIZE];
+    device.read_block(id, &mut buffer).map_err(|_| ConformanceFailure::IoFailed(id))?;
+    if buffer[..] != expected[..] {
+        return Err(ConformanceFailure::DataMismatch(id));
+    }
+    Ok(())
+}
+
+/// Alttaki aygıtların tamamı hata verirken işlemler başarılı görünmemelidir.
+///
+/// # Parametreler
+/// * `set_faults`: Test edilen aygıtın altındaki tüm `FaultyDevice` üyelerini açıp kapatır.
+pub fn check_error_propagation<D: BlockDevice>(
+    device: &D,
+    set_faults: &dyn Fn(bool),
+) -> Result<(), ConformanceFailure> {
+    let id = 0;
+    let mut buffer = vec![0u8; BLOCK_SIZE];
+
+    set_faults(true);
+    let read_ok = device.read_block(id, &mut buffer).is_ok();
+    let write_ok = device.write_block(id, &buffer).is_ok();
+    let flush_ok = device.flush().is_ok();
+    set_faults(false);
+
+    if read_ok || write_ok || flush_ok {
+        return Err(ConformanceFailure::ErrorSwallowed(id));
+    }
+    Ok(())
+}
+
+/// Farklı iş parçacıklarından ayrık bloklara eş zamanlı yazılan veriler karışmamalıdır.
+pub fn check_concurrency<D: BlockDevice + Sync>(device: &D) -> Result<(), ConformanceFailure> {
+    let needed = CONCURRENCY_THREADS * BLOCKS_PER_THREAD;
+    if device.total_blocks() < needed {
+        // Küçük aygıtlarda eş zamanlılık kontrolü atlanır.
+        return Ok(());
+    }
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..CONCURRENCY_THREADS)
+            .map(|t| {
+                scope.spawn(move || -> Result<(), ConformanceFailure> {
+                    for i in 0..BLOCKS_PER_THREAD {
+                        let id = i * CONCURRENCY_THREADS + t;
+                        write_and_verify(device, id, t as u8)?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .try_for_each(|w| w.join().unwrap_or(Err(ConformanceFailure::IoFailed(0))))
+    })
+}
+
+
+// --- 4. Toplu Çalıştırıcı ---
+
+/// Hata enjeksiyonu gerektirmeyen tüm sözleşme kontrollerini sırayla çalıştırır.
+pub fn run_all<D: BlockDevice + Sync>(device: &D) -> Result<(), ConformanceFailure> {
+    check_size(device)?;
+    check_alignment(device)?;
+    check_roundtrip_and_bounds(device)?;
+    check_flush(device)?;
+    check_concurrency(device)
+}
+
+#[cfg(all(test, feature = "host"))]
+mod tests {
+    use super::*;
+    use crate::block_device::RamDevice;
+    use crate::raid::Raid1Device;
+    use alloc::sync::Arc;
+
+    #[test]
+    fn ram_device_conforms() {
+        let device = RamDevice::new(64);
+        assert_eq!(run_all(&device), Ok(()));
+        let faulty = FaultyDevice::new(RamDevice::new(64));
+        assert_eq!(check_error_propagation(&faulty, &|failing| faulty.set_failing(failing)), Ok(()));
+    }
+
+    #[test]
+    fn raid1_over_faulty_members_conforms() {
+        let members: Vec<Arc<FaultyDevice<RamDevice>>> =
+            (0..2).map(|_| Arc::new(FaultyDevice::new(RamDevice::new(64)))).collect();
+        let raid = Raid1Device::new(members.clone()).expect("RAID-1");
+        assert_eq!(run_all(&raid), Ok(()));
+
+        // Tüm üyeler arızalıyken dizi hatayı yutmamalı
+        let set_faults = |failing: bool| members.iter().for_each(|member| member.set_failing(failing));
+        assert_eq!(check_error_propagation(&raid, &set_faults), Ok(()));
+    }
+}
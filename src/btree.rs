@@ -2,13 +2,15 @@
 
 #![allow(dead_code, unused_variables)]
 
-use crate::block_device::BlockId;
-use crate::cache::{CacheBlock, SysLock, BlockCache};
-use crate::checksum;
+use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE};
+use crate::cache::{try_zeroed, CacheBlock, FairLock, BlockCache, WRITE_STAMP_HEAD};
+use crate::allocator::{Allocator, AllocatorError};
+use crate::checksum::{ChecksumAlgorithm, Checksummer};
 use crate::sahne_syscalls::SyscallError;
 use core::mem;
-use core::cell::UnsafeCell;
+use core::cell::{Cell, UnsafeCell};
 use alloc::sync::Arc;
+use alloc::vec;
 use alloc::vec::Vec;
 
 
@@ -18,8 +20,101 @@ use alloc::vec::Vec;
 // BLOCK_SIZE'a göre ayarlanmalıdır, şimdilik sabit bir sayı kullanalım.
 pub const BTREE_NODE_ORDER: usize = 32;
 
-// Düğümün disk üzerindeki boyutu (byte)
-const BTREE_NODE_SIZE: usize = BLOCK_SIZE; 
+// Düğüm bloğunun disk üzerindeki boyutu (byte); varsayılan düğüm boyutudur.
+pub const BTREE_NODE_SIZE: usize = BLOCK_SIZE;
+
+// En büyük düğüm boyutu (byte). Daha büyük düğümler ardışık bloklara yayılır (bkz.
+// `BTree::with_node_size`); ağaç alçalır, hızlı aygıtlarda metadata I/O sayısı azalır.
+pub const BTREE_NODE_SIZE_MAX: usize = 64 * 1024;
+
+// Bir düğümün en fazla blok sayısı.
+const BTREE_NODE_BLOCKS_MAX: u64 = (BTREE_NODE_SIZE_MAX / BLOCK_SIZE) as u64;
+
+// Düğüm tipleri (BTreeNodeHeader::node_type)
+pub const NODE_TYPE_LEAF: u8 = 1;
+pub const NODE_TYPE_INTERNAL: u8 = 2;
+// Çok bloklu düğümün ilkinden sonraki blokları; öğeleri baş bloğun devamıdır.
+pub const NODE_TYPE_CONTINUATION: u8 = 3;
+
+// Anahtarın disk üzerindeki boyutu: object_id (8) + item_type (1) + offset (8)
+const BTREE_KEY_SIZE: usize = 17;
+
+// Bir öğenin (slot) disk üzerindeki sabit boyutu.
+// Veri alanı BTREE_NODE_ORDER adet eşit slota bölünür (4064 / 32 = 127 bayt).
+const BTREE_ITEM_SIZE: usize = (BTREE_NODE_SIZE - mem::size_of::<BTreeNodeHeader>()) / BTREE_NODE_ORDER;
+
+// Bir öğenin taşıyabileceği en büyük değer boyutu (anahtar ve 2 baytlık uzunluk alanı hariç).
+pub const BTREE_VALUE_MAX: usize = BTREE_ITEM_SIZE - BTREE_KEY_SIZE - 2;
+
+// Öğe türleri (BTreeKey::item_type). Değerler disk formatının parçasıdır, değiştirilmemelidir.
+// Dosya veri ağacında dosya bloklarını fiziksel bloklara eşleyen kayıt (bkz. `extent`).
+pub const ITEM_TYPE_EXTENT: u8 = 1;
+// Metadata ağacında bir dizinin isim -> inode girdisi (bkz. `dir`).
+pub const ITEM_TYPE_DIR_ENTRY: u8 = 2;
+// Metadata ağacında adlandırılmış bir anahtar alanının kaydı (bkz. `kv`).
+pub const ITEM_TYPE_KV_KEYSPACE: u8 = 3;
+// Metadata ağacında bir anahtar alanının tek bir anahtar-değer öğesi (bkz. `kv`).
+pub const ITEM_TYPE_KV_ITEM: u8 = 4;
+// Metadata ağacında bir inode'un genişletilmiş özniteliği (bkz. `xattr`).
+pub const ITEM_TYPE_XATTR: u8 = 5;
+// Metadata ağacında bir snapshot döneminde değişen blok aralığı (bkz. `changes`).
+pub const ITEM_TYPE_CHANGED_BLOCKS: u8 = 6;
+// Birden fazla dosyanın paylaştığı veri bloğunun ek başvuru sayısı (bkz. `extent::SharedBlock`)
+pub const ITEM_TYPE_SHARED_BLOCK: u8 = 7;
+// Alt birim (subvolume) tablosu kaydı (bkz. `subvolume::Subvolume`)
+pub const ITEM_TYPE_SUBVOLUME: u8 = 8;
+// Kullanıcı veya alt birim kotası kaydı (bkz. `quota::QuotaId`)
+pub const ITEM_TYPE_QUOTA: u8 = 9;
+// Dosya veri ağacında son bloğun paylaşılan kuyruk bloğundaki parçası (bkz. `tail`)
+pub const ITEM_TYPE_TAIL_FRAGMENT: u8 = 10;
+// Metadata ağacında bir kuyruk bloğunun dilim doluluk kaydı (bkz. `tail::TailBlock`)
+pub const ITEM_TYPE_TAIL_BLOCK: u8 = 11;
+// Metadata ağacında inode numarası -> inode bloğu kaydı (bkz. `inode_table`)
+pub const ITEM_TYPE_INODE_MAP: u8 = 12;
+// Metadata ağacında açıkken son bağlantısı kaldırılmış inode kaydı (bkz. `orphan`)
+pub const ITEM_TYPE_ORPHAN: u8 = 13;
+// Metadata ağacında bir alt birimin öznitelik bayrakları (bkz. `subvolume::SUBVOLUME_ATTR_NOCOW`)
+pub const ITEM_TYPE_SUBVOLUME_ATTRS: u8 = 14;
+// Metadata ağacında ad özetli düzene göçü süren bir dizinin kalan doğrusal aralığı (bkz. `dir::DirMigration`)
+pub const ITEM_TYPE_DIR_MIGRATION: u8 = 15;
+
+
+// --- 1.5. Anahtar Yapısı ---
+
+/// B-Ağacındaki her öğeyi sıralayan bileşik anahtar.
+/// Sıralama sırasıyla `object_id`, `item_type` ve `offset` alanlarına göredir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BTreeKey {
+    /// Öğenin ait olduğu nesne (ör. inode).
+    pub object_id: u64,
+    /// Öğenin türü (dizin girdisi, extent, vb.).
+    pub item_type: u8,
+    /// Tür içindeki konum (ör. dosya içi blok indeksi).
+    pub offset: u64,
+}
+
+impl BTreeKey {
+    /// Anahtarı disk formatına (little-endian) yazar.
+    fn encode(&self, out: &mut [u8]) {
+        out[0..8].copy_from_slice(&self.object_id.to_le_bytes());
+        out[8] = self.item_type;
+        out[9..17].copy_from_slice(&self.offset.to_le_bytes());
+    }
+
+    /// Anahtarı disk formatından okur.
+    fn decode(raw: &[u8]) -> Self {
+        let mut object_id = [0u8; 8];
+        let mut offset = [0u8; 8];
+        object_id.copy_from_slice(&raw[0..8]);
+        offset.copy_from_slice(&raw[9..17]);
+
+        BTreeKey {
+            object_id: u64::from_le_bytes(object_id),
+            item_type: raw[8],
+            offset: u64::from_le_bytes(offset),
+        }
+    }
+}
 
 
 // --- 2. Düğüm Başlığı Yapısı (Metadata) ---
@@ -38,12 +133,21 @@ pub struct BTreeNodeHeader {
     pub block_id: BlockId, 
     /// Metadata bütünlüğü için CRC32C kontrol toplamı
     pub checksum: u32, 
+    /// Baş blokta düğümün blok sayısı (0 = tek blok), devam bloklarında bloğun düğüm
+    /// içindeki sırası
+    pub span: u8,
     /// Doldurma baytları (padding)
-    padding: [u8; 8], 
+    padding: [u8; 3], 
+    /// Yazma damgalı birimde bloğun son 8 baytıyla aynı sıra numarası, yoksa 0 (bkz.
+    /// `BlockCache::enable_write_stamps`)
+    pub write_stamp: u64,
     // Toplam 8 + 4 + 2 + 1 + 1 = 16 byte
 }
 
 
+// Baştaki yazma damgası önbelleğin denetlediği yerde olmalı.
+const _: () = assert!(mem::offset_of!(BTreeNodeHeader, write_stamp) == WRITE_STAMP_HEAD);
+
 // --- 3. Düğüm Yapısı (Payload) ---
 
 /// B-Ağacı düğümünün disk üzerindeki temsili. 
@@ -57,6 +161,118 @@ pub struct BTreeNode {
     pub data_area: [u8; BTREE_NODE_SIZE - mem::size_of::<BTreeNodeHeader>()],
 }
 
+impl BTreeNode {
+    /// Ham blok verisini düğüm olarak yorumlar.
+    pub fn from_block(block: &CacheBlock) -> &BTreeNode {
+        unsafe { &*(block.data.as_ptr() as *const BTreeNode) }
+    }
+
+    /// Düğüm yaprak düğüm mü?
+    pub fn is_leaf(&self) -> bool {
+        self.header.node_type == NODE_TYPE_LEAF
+    }
+
+    /// Kullanımdaki öğe sayısı.
+    pub fn len(&self) -> usize {
+        self.header.num_entries as usize
+    }
+
+    /// `index` numaralı slotun ham baytları.
+    fn slot(&self, index: usize) -> &[u8] {
+        let start = index * BTREE_ITEM_SIZE;
+        &self.data_area[start..start + BTREE_ITEM_SIZE]
+    }
+
+    /// `index` numaralı öğenin anahtarı.
+    pub fn key(&self, index: usize) -> BTreeKey {
+        BTreeKey::decode(self.slot(index))
+    }
+
+    /// `index` numaralı öğenin değeri (yaprak düğümlerde).
+    pub fn value(&self, index: usize) -> &[u8] {
+        let slot = self.slot(index);
+        let len = u16::from_le_bytes([slot[BTREE_KEY_SIZE], slot[BTREE_KEY_SIZE + 1]]) as usize;
+        let start = BTREE_KEY_SIZE + 2;
+        &slot[start..start + len.min(BTREE_VALUE_MAX)]
+    }
+
+    /// `index` numaralı öğenin işaret ettiği çocuk düğüm (iç düğümlerde).
+    pub fn child_id(&self, index: usize) -> BlockId {
+        let mut raw = [0u8; 8];
+        raw.copy_from_slice(&self.value(index)[..8]);
+        BlockId::from_le_bytes(raw)
+    }
+
+    /// Tüm çocuk düğüm ID'lerinin kopyası (iç düğümlerde).
+    /// Özyinelemeli dolaşmalar bu kopyayla üst düğüm bloğunu erken bırakabilir;
+    /// böylece aynı anda yalnızca tek bir düğüm arabelleği sabitlenmiş olur.
+    pub fn child_ids(&self) -> Vec<BlockId> {
+        (0..self.len()).map(|i| self.child_id(i)).collect()
+    }
+}
+
+/// Bir veya birden çok bloğa yayılan düğümün okuma görünümü (bkz. `BTree::load_node`).
+/// Her blok önbellekte ayrı bir girdidir ve kendi başlığını taşır; `index` numaralı öğe
+/// `index / slots` numaralı bloktadır. Öğe sayısı baş bloğun başlığındadır.
+struct NodeView {
+    blocks: Vec<Arc<UnsafeCell<CacheBlock>>>,
+    /// Blok başına öğe yuvası sayısı (bkz. `BTree::slots`).
+    slots: usize,
+}
+
+impl NodeView {
+    /// Düğümün `index` numaralı bloğu.
+    fn block(&self, index: usize) -> &BTreeNode {
+        BTreeNode::from_block(unsafe { &*self.blocks[index].get() })
+    }
+
+    /// Düğüm yaprak düğüm mü?
+    fn is_leaf(&self) -> bool {
+        self.block(0).is_leaf()
+    }
+
+    /// Kullanımdaki öğe sayısı.
+    fn len(&self) -> usize {
+        self.block(0).len()
+    }
+
+    /// Düğümün seviyesi (yapraklar 0).
+    fn level(&self) -> u8 {
+        self.block(0).header.level
+    }
+
+    /// `index` numaralı öğenin anahtarı.
+    fn key(&self, index: usize) -> BTreeKey {
+        self.block(index / self.slots).key(index % self.slots)
+    }
+
+    /// `index` numaralı öğenin değeri (yaprak düğümlerde).
+    fn value(&self, index: usize) -> &[u8] {
+        self.block(index / self.slots).value(index % self.slots)
+    }
+
+    /// Tüm çocuk düğüm ID'lerinin kopyası (iç düğümlerde).
+    fn child_ids(&self) -> Vec<BlockId> {
+        (0..self.len()).map(|i| self.block(i / self.slots).child_id(i % self.slots)).collect()
+    }
+}
+
+/// Düğüm boyutu (byte) geçerliyse düğümün blok sayısını döndürür: `BTREE_NODE_SIZE` ile
+/// `BTREE_NODE_SIZE_MAX` arasında ikinin kuvveti olmalıdır.
+pub fn node_blocks_for(size: usize) -> Option<u64> {
+    if size.is_power_of_two() && (BTREE_NODE_SIZE..=BTREE_NODE_SIZE_MAX).contains(&size) {
+        Some((size / BLOCK_SIZE) as u64)
+    } else {
+        None
+    }
+}
+
+/// Bir düğüm bloğunun `algorithm` ile checksum'unu, başlıktaki checksum alanını sıfır
+/// sayarak hesaplar.
+pub fn node_checksum(algorithm: ChecksumAlgorithm, data: &[u8]) -> u32 {
+    algorithm.checksum_excluding(data, mem::offset_of!(BTreeNodeHeader, checksum), mem::size_of::<u32>())
+}
+
 
 // --- 4. B-Ağacı Yönetim Yapısı (CoW İçin) ---
 
@@ -65,9 +281,16 @@ pub struct BTree<D: BlockDevice> {
     /// Tüm I/O'yu yöneten ve blokları bellekte tutan önbellek.
     cache: Arc<BlockCache<D>>,
     /// Ağacın kök düğümünün diskteki ID'si. (Bu, CoW işleminde sıkça değişir)
-    root_id: BlockId,
+    root_id: Cell<BlockId>,
+    /// Düğüm checksum'larının algoritması (bkz. `with_checksum`).
+    checksum: ChecksumAlgorithm,
+    /// Bir düğümün kapladığı ardışık blok sayısı (bkz. `with_node_size`).
+    node_blocks: u64,
+    /// Bir düğüm bloğundaki öğe yuvası sayısı. Yazma damgalı birimde son yuva bloğun son
+    /// 8 baytını kapladığından kullanılmaz (bkz. `BlockCache::enable_write_stamps`).
+    slots: usize,
     // Düğüm işlemlerini eş zamanlı yapmak için kilit
-    lock: SysLock, 
+    lock: FairLock, 
 }
 
 impl<D: BlockDevice> BTree<D> {
@@ -76,26 +299,57 @@ impl<D: BlockDevice> BTree<D> {
 
     /// B-Ağacını diskten yükler veya yeni bir ağaç oluşturur.
     pub fn new(cache: Arc<BlockCache<D>>, root_id: BlockId) -> Result<Self, D::Error> {
+        let slots = if cache.write_stamps() { BTREE_NODE_ORDER - 1 } else { BTREE_NODE_ORDER };
         Ok(BTree {
             cache,
-            root_id,
-            lock: SysLock::new().map_err(|e| D::Error::from(e))?, // Hata dönüşümünü kullan
+            root_id: Cell::new(root_id),
+            checksum: ChecksumAlgorithm::Crc32c,
+            node_blocks: 1,
+            slots,
+            lock: FairLock::new(),
         })
     }
 
+    /// Düğümleri `size` baytlık (birden çok ardışık bloğa yayılan) ağaç. Boyut
+    /// `node_blocks_for` ile doğrulanmış olmalıdır; ağacın boyutu Superblock'tan gelir.
+    ///
+    /// Düğümün her bloğu kendi başlığını ve checksum'unu taşır; bloklar birlikte tahsis
+    /// edilir ve CoW ile hep birlikte yeni bir yere yazılır. Eski düğüm commit
+    /// tamamlanana kadar yerinde kaldığından çok bloklu yazma da atomiktir.
+    pub fn with_node_size(mut self, size: usize) -> Self {
+        self.node_blocks = node_blocks_for(size).unwrap_or(1);
+        self
+    }
+
+    /// Düğüm boyutu (byte).
+    pub fn node_size(&self) -> usize {
+        self.node_blocks as usize * BLOCK_SIZE
+    }
+
+    /// Bir düğüme sığan en fazla öğe sayısı.
+    fn node_order(&self) -> usize {
+        self.slots * self.node_blocks as usize
+    }
+
+    /// Düğüm checksum'larını `algorithm` ile yazan ve doğrulayan ağaç (varsayılan CRC32C).
+    /// Ağacın algoritması Superblock'taki checksum politikasından gelir.
+    pub fn with_checksum(mut self, algorithm: ChecksumAlgorithm) -> Self {
+        self.checksum = algorithm;
+        self
+    }
+
     /// Bir düğüm bloğunun metadata Checksum'unu doğrular.
     ///
     /// # Parametreler
     /// * `node_block`: Önbellekten alınmış ham düğüm bloğu.
     pub fn verify_checksum(&self, node_block: &CacheBlock) -> bool {
         // Düğüm başlığını ve içeriğini almak için ham veriyi kullan.
-        // Güvenli olmayan (unsafe) blokları okuma işlemi.
-        let node_ptr = node_block.data.as_ptr() as *const BTreeNode;
+        let node = BTreeNode::from_block(node_block);
         
-        // Ham baytlar üzerinde Checksum hesaplamak için `data` dilimini kullan.
-        let calculated_crc = checksum::checksum_data(node_block.data.as_ref());
+        // Checksum alanı, hesaplamaya sıfır olarak katılır.
+        let calculated_crc = node_checksum(self.checksum, node_block.data.as_ref());
         
-        let stored_crc = unsafe { (*node_ptr).header.checksum };
+        let stored_crc = node.header.checksum;
 
         // CRC'nin eşleşip eşleşmediğini kontrol et.
         calculated_crc == stored_crc
@@ -104,22 +358,644 @@ impl<D: BlockDevice> BTree<D> {
     // --- Basit Düğüm Okuma İşlemi ---
 
     /// Bir B-Ağacı düğümünü diskten okur, önbelleğe alır ve Checksum'u doğrular.
+    ///
+    /// Checksum tutmazsa ve aygıt bloğun birden çok kopyasını tutuyorsa (ör. RAID-1) diğer
+    /// kopyalar denenir; sağlam kopya bulunursa düğüm onunla onarılır (bkz. `heal_from_copies`).
+    /// Yazma damgalı birimde damgaları uyuşmayan (yırtık yazılmış) düğüm `EIO` döner.
     pub fn get_node(&self, id: BlockId) -> Result<Arc<UnsafeCell<CacheBlock>>, D::Error> {
-        let block_arc = self.cache.get_block(id)?;
+        let block_arc = self.cache.get_stamped_block(id)?;
         
         // Checksum doğrulaması
-        let block_ref = unsafe { &*block_arc.get() };
+        let block_mut = unsafe { &mut *block_arc.get() };
 
-        if !self.verify_checksum(block_ref) {
-            // Checksum hatası durumunda kritik hata döndür.
+        if !self.verify_checksum(block_mut) && !self.heal_from_copies(id, block_mut)? {
+            // Sağlam kopya da yoksa kritik hata döndür.
             return Err(D::Error::from(SyscallError::EIO));
         }
 
         Ok(block_arc)
     }
 
-    // TODO: CoW için ana işlevler: 
-    // - `copy_on_write_node(id)`: Bloğu kopyalar ve yeni ID atar.
-    // - `insert_entry(...)`: Ağaca yeni giriş ekler.
-    // - `split_node(...)`: Düğümü ikiye böler.
+    /// Checksum'u tutmayan düğümün aygıttaki diğer kopyalarını sırayla okur. Sağlam bir kopya
+    /// bulunursa önbellekteki blok onunla değiştirilir ve blok aygıta yeniden yazılır; yazma
+    /// tüm kopyalara gittiğinden bozuk üye de düzelir. Kirli (henüz yazılmamış) bloklara
+    /// dokunulmaz.
+    ///
+    /// # Döndürür
+    /// Düğüm onarıldıysa `true`.
+    fn heal_from_copies(&self, id: BlockId, block: &mut CacheBlock) -> Result<bool, D::Error> {
+        let device = &self.cache.device;
+        if device.copies() < 2 || block.is_dirty {
+            return Ok(false);
+        }
+
+        let checksum_at = mem::offset_of!(BTreeNodeHeader, checksum);
+        let mut buffer = try_zeroed(BLOCK_SIZE)?;
+        for copy in 0..device.copies() {
+            if device.read_copy(id, copy, &mut buffer).is_err() {
+                continue;
+            }
+            let stored = u32::from_ne_bytes([buffer[checksum_at], buffer[checksum_at + 1], buffer[checksum_at + 2], buffer[checksum_at + 3]]);
+            if node_checksum(self.checksum, &buffer) != stored {
+                continue;
+            }
+
+            block.data.copy_from_slice(&buffer);
+            device.write_block(id, &buffer)?;
+            device.flush()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// `id`'deki düğümün tüm bloklarını okur ve doğrular (bkz. `get_node`). Baş bloğun
+    /// bildirdiği blok sayısı ağacınkiyle uyuşmalı, devam blokları bu düğüme ait olmalı
+    /// ve öğe sayısı düğüme sığmalıdır; aksi halde `EIO`.
+    fn load_node(&self, id: BlockId) -> Result<NodeView, D::Error> {
+        let head = self.get_node(id)?;
+        let (span, len) = {
+            let node = BTreeNode::from_block(unsafe { &*head.get() });
+            (node.header.span.max(1) as u64, node.len())
+        };
+        if span != self.node_blocks || len > self.node_order() {
+            return Err(D::Error::from(SyscallError::EIO));
+        }
+
+        let mut blocks = Vec::with_capacity(span as usize);
+        blocks.push(head);
+        for k in 1..span {
+            let block_arc = self.get_node(id + k)?;
+            let header = &BTreeNode::from_block(unsafe { &*block_arc.get() }).header;
+            if header.node_type != NODE_TYPE_CONTINUATION || header.span as u64 != k {
+                return Err(D::Error::from(SyscallError::EIO));
+            }
+            blocks.push(block_arc);
+        }
+        Ok(NodeView { blocks, slots: self.slots })
+    }
+
+    /// Yeni bir düğüm için `node_size` baytlık ardışık alan tahsis eder. Çok bloklu
+    /// düğümde yeterince uzun ardışık boş alan yoksa `OutOfSpace` döner.
+    pub fn allocate_node(&self, allocator: &Allocator<D>) -> Result<BlockId, AllocatorError<D>> {
+        if self.node_blocks == 1 {
+            return allocator.allocate_block();
+        }
+        let (start, len) = allocator.allocate_extent(self.node_blocks)?;
+        if len < self.node_blocks {
+            for id in start..start + len {
+                allocator.release_block(id)?;
+            }
+            return Err(AllocatorError::OutOfSpace);
+        }
+        Ok(start)
+    }
+
+    /// Düğümün tüm bloklarını bırakır (commit tamamlanınca serbest kalırlar).
+    fn release_node(&self, allocator: &Allocator<D>, id: BlockId) -> Result<(), AllocatorError<D>> {
+        for block in id..id + self.node_blocks {
+            allocator.release_block(block)?;
+        }
+        Ok(())
+    }
+
+    /// Ağacın kök düğümünün diskteki ID'si.
+    pub fn root_id(&self) -> BlockId {
+        self.root_id.get()
+    }
+
+    /// Ağacın seviye sayısı (kök düğümün `level`'ı + 1); tek yapraklı ağaçta 1. Kök
+    /// bölündükçe ağaç kendiliğinden büyür, tek çocuklu iç kök kaldırıldıkça alçalır.
+    pub fn height(&self) -> Result<u8, D::Error> {
+        let block_arc = self.get_node(self.root_id())?;
+        let level = BTreeNode::from_block(unsafe { &*block_arc.get() }).header.level;
+        Ok(level.saturating_add(1))
+    }
+
+    /// Ağacı başka bir köke (ör. geri alınan transaction'da son commit'teki köke) çevirir.
+    pub fn set_root(&self, root_id: BlockId) {
+        self.root_id.set(root_id);
+    }
+
+    /// Kök bloğunu boş bir yaprak düğüm olarak başlatır (format sırasında kullanılır).
+    pub fn init_empty_root(&self) -> Result<(), D::Error> {
+        self.write_node(self.root_id(), NODE_TYPE_LEAF, 0, &[])
+    }
+
+    // --- Okuma İşlemleri ---
+
+    /// Ağaçtaki tüm öğeleri anahtar sırasıyla ziyaret eder.
+    ///
+    /// # Parametreler
+    /// * `visit`: Her yaprak öğesi için (anahtar, değer) ile çağrılır.
+    pub fn walk<F: FnMut(&BTreeKey, &[u8])>(&self, visit: &mut F) -> Result<(), D::Error> {
+        self.walk_subtree(self.root_id(), visit)
+    }
+
+    /// `walk` için özyinelemeli yardımcı.
+    fn walk_subtree<F: FnMut(&BTreeKey, &[u8])>(&self, id: BlockId, visit: &mut F) -> Result<(), D::Error> {
+        let children = {
+            let node = self.load_node(id)?;
+
+            if node.is_leaf() {
+                for i in 0..node.len() {
+                    visit(&node.key(i), node.value(i));
+                }
+                return Ok(());
+            }
+            node.child_ids()
+        };
+
+        // Üst düğüm bırakıldıktan sonra çocuklara inilir (tek arabellek).
+        for child in children {
+            self.walk_subtree(child, visit)?;
+        }
+
+        Ok(())
+    }
+
+    /// `start` anahtarına eşit veya ondan büyük öğeleri anahtar sırasıyla ziyaret eder.
+    ///
+    /// # Parametreler
+    /// * `visit`: Her yaprak öğesi için çağrılır; `false` dönerse dolaşma durur.
+    pub fn walk_from<F: FnMut(&BTreeKey, &[u8]) -> bool>(&self, start: &BTreeKey, visit: &mut F) -> Result<(), D::Error> {
+        self.walk_subtree_from(self.root_id(), Some(start), visit)?;
+        Ok(())
+    }
+
+    /// `walk_from` için özyinelemeli yardımcı. Dolaşma durdurulduysa `false` döner.
+    fn walk_subtree_from<F: FnMut(&BTreeKey, &[u8]) -> bool>(
+        &self,
+        id: BlockId,
+        start: Option<&BTreeKey>,
+        visit: &mut F,
+    ) -> Result<bool, D::Error> {
+        let (children, first) = {
+            let node = self.load_node(id)?;
+
+            if node.is_leaf() {
+                for i in 0..node.len() {
+                    let key = node.key(i);
+                    if start.map_or(false, |s| key < *s) {
+                        continue;
+                    }
+                    if !visit(&key, node.value(i)) {
+                        return Ok(false);
+                    }
+                }
+                return Ok(true);
+            }
+
+            // `start`'ı içerebilecek ilk çocuk: anahtarı `start`'tan büyük olmayan son çocuk.
+            let first = match start {
+                Some(s) => (1..node.len()).take_while(|&i| node.key(i) <= *s).last().unwrap_or(0),
+                None => 0,
+            };
+            (node.child_ids(), first)
+        };
+
+        for (i, child) in children.into_iter().enumerate().skip(first) {
+            // İlk çocuktan sonrakilerin tüm anahtarları `start`'tan büyüktür.
+            let bound = if i == first { start } else { None };
+            if !self.walk_subtree_from(child, bound, visit)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// `key`'e eşit veya ondan küçük en büyük anahtarlı öğeyi bulur.
+    ///
+    /// # Döndürür
+    /// Bulunan öğenin anahtarı ve değerinin kopyası; böyle bir öğe yoksa `None`.
+    pub fn lookup_floor(&self, key: &BTreeKey) -> Result<Option<(BTreeKey, Vec<u8>)>, D::Error> {
+        self.floor_in_subtree(self.root_id(), key)
+    }
+
+    /// `lookup_floor` için özyinelemeli yardımcı.
+    fn floor_in_subtree(&self, id: BlockId, key: &BTreeKey) -> Result<Option<(BTreeKey, Vec<u8>)>, D::Error> {
+        let (children, position) = {
+            let node = self.load_node(id)?;
+            let position = (0..node.len()).take_while(|&i| node.key(i) <= *key).last();
+
+            if node.is_leaf() {
+                return Ok(position.map(|i| (node.key(i), node.value(i).to_vec())));
+            }
+            match position {
+                Some(position) => (node.child_ids(), position),
+                None => return Ok(None),
+            }
+        };
+
+        // Silmelerden sonra boş kalmış bir çocuk varsa bir öncekine bakılır.
+        for child in children[..=position].iter().rev() {
+            if let Some(found) = self.floor_in_subtree(*child, key)? {
+                return Ok(Some(found));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Anahtarı tam olarak eşleşen öğenin değerini döndürür.
+    pub fn lookup(&self, key: &BTreeKey) -> Result<Option<Vec<u8>>, D::Error> {
+        Ok(match self.lookup_floor(key)? {
+            Some((found, value)) if found == *key => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Ağacın tüm düğümlerini seviye seviye önbelleğe önceden okur.
+    ///
+    /// Her seviyedeki çocuk düğümler tek bir `prefetch` çağrısıyla istenir; böylece
+    /// ardışık okumalarda blok eşleme (ör. extent yaprakları) ek metadata I/O'su
+    /// beklemeden çözülür.
+    ///
+    /// # Döndürür
+    /// Diskten okunan düğüm sayısı.
+    pub fn prefetch(&self) -> Result<usize, D::Error> {
+        let mut loaded = self.cache.prefetch(&self.node_blocks_of(&[self.root_id()]))?;
+        let mut level = vec![self.root_id()];
+
+        while !level.is_empty() {
+            let mut next_level = Vec::new();
+            for id in level {
+                let node = self.load_node(id)?;
+                if !node.is_leaf() {
+                    next_level.extend(node.child_ids());
+                }
+            }
+
+            loaded += self.cache.prefetch(&self.node_blocks_of(&next_level))?;
+            level = next_level;
+        }
+
+        Ok(loaded)
+    }
+
+    /// Düğümlerin (baş blok ID'leri) kapladığı tüm bloklar.
+    fn node_blocks_of(&self, nodes: &[BlockId]) -> Vec<BlockId> {
+        nodes.iter().flat_map(|&id| id..id + self.node_blocks).collect()
+    }
+
+    /// Ağacın tüm düğümlerinin blok ID'lerini (kök dahil, seviye sırasıyla; çok bloklu
+    /// düğümlerin bütün blokları) döndürür. Ağacın sahibi silinirken düğüm bloklarını
+    /// bırakmak için kullanılır.
+    pub fn node_ids(&self) -> Result<Vec<BlockId>, D::Error> {
+        let mut nodes = vec![self.root_id()];
+        let mut next = 0;
+
+        while next < nodes.len() {
+            let node = self.load_node(nodes[next])?;
+            if !node.is_leaf() {
+                nodes.extend(node.child_ids());
+            }
+            next += 1;
+        }
+
+        Ok(self.node_blocks_of(&nodes))
+    }
+
+    // --- Doğrulama İşlemleri ---
+
+    /// Kökten başlayarak en fazla `levels` seviye derinliğe kadar düğüm checksum'larını doğrular.
+    ///
+    /// # Döndürür
+    /// Bozuk bulunan ilk düğümün ID'si; tüm düğümler sağlamsa `None`.
+    pub fn find_corrupt_node(&self, levels: u8) -> Result<Option<BlockId>, D::Error> {
+        self.find_corrupt_in_subtree(self.root_id(), levels)
+    }
+
+    /// `find_corrupt_node` için özyinelemeli yardımcı.
+    fn find_corrupt_in_subtree(&self, id: BlockId, levels: u8) -> Result<Option<BlockId>, D::Error> {
+        if levels == 0 {
+            return Ok(None);
+        }
+
+        let children = {
+            for block in id..id + self.node_blocks {
+                let block_arc = self.cache.get_block(block)?;
+                if !self.verify_checksum(unsafe { &*block_arc.get() }) {
+                    return Ok(Some(block));
+                }
+            }
+
+            let node = match self.load_node(id) {
+                Ok(node) => node,
+                // Blokların checksum'u tutuyor ama düğüm olarak birbirine uymuyor
+                Err(_) => return Ok(Some(id)),
+            };
+            if node.is_leaf() {
+                return Ok(None);
+            }
+            node.child_ids()
+        };
+
+        for child in children {
+            if let Some(bad) = self.find_corrupt_in_subtree(child, levels - 1)? {
+                return Ok(Some(bad));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // --- Yazma İşlemleri (CoW) ---
+    // Son commit'e ait düğümler yerinde değiştirilmez: önce `copy_on_write_node` ile yeni
+    // bir bloğa kopyalanır, eski blok commit tamamlanınca serbest bırakılır. Kök değişirse
+    // yeni kök `root_id()` ile alınıp sahibine (Superblock veya Inode) yazılmalıdır.
+
+    /// Ağaca bir öğe ekler; anahtar zaten varsa değerini değiştirir.
+    pub fn insert_entry(&self, allocator: &Allocator<D>, key: &BTreeKey, value: &[u8]) -> Result<(), AllocatorError<D>> {
+        if value.len() > BTREE_VALUE_MAX {
+            return Err(AllocatorError::Syscall(SyscallError::EINVAL));
+        }
+
+        let root_id = self.copy_on_write_node(allocator, self.root_id())?;
+        self.root_id.set(root_id);
+
+        if let Some((split_key, right_id)) = self.insert_into(allocator, root_id, key, value)? {
+            // Kök bölündü: ağaç bir seviye büyür.
+            let (first_key, level) = {
+                let node = self.load_node(root_id).map_err(AllocatorError::DeviceError)?;
+                (node.key(0), node.level())
+            };
+
+            let new_root = self.allocate_node(allocator)?;
+            let items = [
+                (first_key, root_id.to_le_bytes().to_vec()),
+                (split_key, right_id.to_le_bytes().to_vec()),
+            ];
+            self.write_node(new_root, NODE_TYPE_INTERNAL, level + 1, &items)
+                .map_err(AllocatorError::DeviceError)?;
+            self.root_id.set(new_root);
+        }
+
+        Ok(())
+    }
+
+    /// Anahtarı eşleşen öğeyi ağaçtan çıkarır.
+    ///
+    /// Düğümler yeniden dengelenmez; yalnızca tamamen boşalan düğümler üst düğümden
+    /// çıkarılıp serbest bırakılır. Tek çocuğu kalan iç kök kaldırılır ve ağaç alçalır.
+    ///
+    /// # Döndürür
+    /// Çıkarılan öğenin değeri; anahtar yoksa `None`.
+    pub fn remove_entry(&self, allocator: &Allocator<D>, key: &BTreeKey) -> Result<Option<Vec<u8>>, AllocatorError<D>> {
+        if self.lookup(key).map_err(AllocatorError::DeviceError)?.is_none() {
+            return Ok(None);
+        }
+
+        let root_id = self.copy_on_write_node(allocator, self.root_id())?;
+        self.root_id.set(root_id);
+
+        let removed = self.remove_from(allocator, root_id, key)?;
+
+        // Tüm çocukları boşalan iç kök, boş bir yaprağa dönüşür.
+        let (is_leaf, len) = {
+            let node = self.load_node(root_id).map_err(AllocatorError::DeviceError)?;
+            (node.is_leaf(), node.len())
+        };
+        if !is_leaf && len == 0 {
+            self.write_node(root_id, NODE_TYPE_LEAF, 0, &[]).map_err(AllocatorError::DeviceError)?;
+        }
+
+        // Tek çocuklu iç kökler kaldırılır: çocuk yeni kök olur, ağaç bir seviye alçalır.
+        let mut root_id = root_id;
+        loop {
+            let only_child = {
+                let node = self.load_node(root_id).map_err(AllocatorError::DeviceError)?;
+                if node.is_leaf() || node.len() != 1 { None } else { Some(node.child_ids()[0]) }
+            };
+            let Some(child) = only_child else {
+                break;
+            };
+            self.release_node(allocator, root_id)?;
+            root_id = child;
+        }
+        self.root_id.set(root_id);
+
+        Ok(removed)
+    }
+
+    /// Bir düğümü değiştirilebilir hale getirir: son commit'ten sonra tahsis edilmişse
+    /// aynı ID'yi, değilse içeriğin kopyalandığı yeni düğümün ID'sini döndürür. Çok
+    /// bloklu düğümün bütün blokları birlikte kopyalanır.
+    pub fn copy_on_write_node(&self, allocator: &Allocator<D>, id: BlockId) -> Result<BlockId, AllocatorError<D>> {
+        if allocator.is_fresh(id) {
+            return Ok(id);
+        }
+
+        let old = self.load_node(id).map_err(AllocatorError::DeviceError)?;
+        let new_id = self.allocate_node(allocator)?;
+        for (k, old_arc) in old.blocks.iter().enumerate() {
+            let block_id = new_id + k as u64;
+            let new_arc = self.cache.get_block(block_id).map_err(AllocatorError::DeviceError)?;
+
+            let new_block = unsafe { &mut *new_arc.get() };
+            new_block.data.copy_from_slice(unsafe { &(*old_arc.get()).data[..] });
+            let node = unsafe { &mut *(new_block.data.as_mut_ptr() as *mut BTreeNode) };
+            node.header.block_id = block_id;
+            self.cache.stamp(new_block.data.as_mut());
+            node.header.checksum = node_checksum(self.checksum, new_block.data.as_ref());
+            new_block.is_dirty = true;
+        }
+
+        self.release_node(allocator, id)?;
+        Ok(new_id)
+    }
+
+    /// `limit` ve ötesindeki blokta duran tüm düğümleri yeni tahsis edilen bloklara taşır
+    /// (küçültme için). Taşınan düğümün üst düğümleri de CoW ile yeniden yazılır; kök
+    /// değişirse yeni kök `root_id()` ile alınıp sahibine yazılmalıdır. Tahsisler
+    /// `Allocator::set_allocation_limit` ile `limit`'in altına sınırlanmış olmalıdır.
+    pub fn relocate(&self, allocator: &Allocator<D>, limit: BlockId) -> Result<(), AllocatorError<D>> {
+        let root_id = self.relocate_subtree(allocator, self.root_id(), limit)?;
+        self.root_id.set(root_id);
+        Ok(())
+    }
+
+    /// `relocate` için özyinelemeli yardımcı.
+    ///
+    /// # Döndürür
+    /// Düğümün (taşındıysa veya çocukları değiştiyse yeni) ID'si.
+    fn relocate_subtree(&self, allocator: &Allocator<D>, id: BlockId, limit: BlockId) -> Result<BlockId, AllocatorError<D>> {
+        let (node_type, level, mut items) = self.read_items(id).map_err(AllocatorError::DeviceError)?;
+
+        let mut changed = false;
+        if node_type == NODE_TYPE_INTERNAL {
+            for item in items.iter_mut() {
+                let child = Self::decode_child(&item.1);
+                let new_child = self.relocate_subtree(allocator, child, limit)?;
+                if new_child != child {
+                    item.1 = new_child.to_le_bytes().to_vec();
+                    changed = true;
+                }
+            }
+        }
+        let inside = id + self.node_blocks <= limit;
+        if inside && !changed {
+            return Ok(id);
+        }
+
+        let new_id = if inside && allocator.is_fresh(id) {
+            id
+        } else {
+            let new_id = self.allocate_node(allocator)?;
+            self.release_node(allocator, id)?;
+            new_id
+        };
+        self.write_node(new_id, node_type, level, &items).map_err(AllocatorError::DeviceError)?;
+        Ok(new_id)
+    }
+
+    /// `insert_entry` için özyinelemeli yardımcı. `id` değiştirilebilir (CoW yapılmış) olmalıdır.
+    ///
+    /// # Döndürür
+    /// Düğüm bölündüyse yeni sağ düğümün ilk anahtarı ve ID'si.
+    fn insert_into(
+        &self,
+        allocator: &Allocator<D>,
+        id: BlockId,
+        key: &BTreeKey,
+        value: &[u8],
+    ) -> Result<Option<(BTreeKey, BlockId)>, AllocatorError<D>> {
+        let (node_type, level, mut items) = self.read_items(id).map_err(AllocatorError::DeviceError)?;
+
+        if node_type == NODE_TYPE_LEAF {
+            match items.binary_search_by(|(k, _)| k.cmp(key)) {
+                Ok(i) => items[i].1 = value.to_vec(),
+                Err(i) => items.insert(i, (*key, value.to_vec())),
+            }
+        } else {
+            // Anahtarı içermesi gereken çocuk; ilk anahtar her zaman alt ağacın en küçüğüdür.
+            let position = items.iter().rposition(|(k, _)| k <= key).unwrap_or(0);
+            if *key < items[position].0 {
+                items[position].0 = *key;
+            }
+
+            let child = Self::decode_child(&items[position].1);
+            let new_child = self.copy_on_write_node(allocator, child)?;
+            items[position].1 = new_child.to_le_bytes().to_vec();
+
+            if let Some((split_key, right_id)) = self.insert_into(allocator, new_child, key, value)? {
+                items.insert(position + 1, (split_key, right_id.to_le_bytes().to_vec()));
+            }
+        }
+
+        self.store_items(allocator, id, node_type, level, items)
+    }
+
+    /// `remove_entry` için özyinelemeli yardımcı. `id` değiştirilebilir (CoW yapılmış) olmalıdır.
+    fn remove_from(&self, allocator: &Allocator<D>, id: BlockId, key: &BTreeKey) -> Result<Option<Vec<u8>>, AllocatorError<D>> {
+        let (node_type, level, mut items) = self.read_items(id).map_err(AllocatorError::DeviceError)?;
+
+        let removed = if node_type == NODE_TYPE_LEAF {
+            match items.binary_search_by(|(k, _)| k.cmp(key)) {
+                Ok(i) => Some(items.remove(i).1),
+                Err(_) => None,
+            }
+        } else {
+            let Some(position) = items.iter().rposition(|(k, _)| k <= key) else {
+                return Ok(None);
+            };
+
+            let child = Self::decode_child(&items[position].1);
+            let new_child = self.copy_on_write_node(allocator, child)?;
+            let removed = self.remove_from(allocator, new_child, key)?;
+
+            let child_empty = self.load_node(new_child).map_err(AllocatorError::DeviceError)?.len() == 0;
+            if child_empty {
+                items.remove(position);
+                self.release_node(allocator, new_child)?;
+            } else {
+                items[position].1 = new_child.to_le_bytes().to_vec();
+            }
+            removed
+        };
+
+        self.write_node(id, node_type, level, &items).map_err(AllocatorError::DeviceError)?;
+        Ok(removed)
+    }
+
+    /// Öğeleri düğüme yazar; düğüme sığmıyorsa ikiye böler.
+    ///
+    /// # Döndürür
+    /// Bölünme olduysa yeni sağ düğümün ilk anahtarı ve ID'si.
+    fn store_items(
+        &self,
+        allocator: &Allocator<D>,
+        id: BlockId,
+        node_type: u8,
+        level: u8,
+        mut items: Vec<(BTreeKey, Vec<u8>)>,
+    ) -> Result<Option<(BTreeKey, BlockId)>, AllocatorError<D>> {
+        if items.len() <= self.node_order() {
+            self.write_node(id, node_type, level, &items).map_err(AllocatorError::DeviceError)?;
+            return Ok(None);
+        }
+
+        let right_items = items.split_off(items.len() / 2);
+        let right_id = self.allocate_node(allocator)?;
+        self.write_node(id, node_type, level, &items).map_err(AllocatorError::DeviceError)?;
+        self.write_node(right_id, node_type, level, &right_items).map_err(AllocatorError::DeviceError)?;
+
+        Ok(Some((right_items[0].0, right_id)))
+    }
+
+    /// Bir düğümün tüm öğelerini (anahtar, değer) kopyaları olarak okur.
+    fn read_items(&self, id: BlockId) -> Result<(u8, u8, Vec<(BTreeKey, Vec<u8>)>), D::Error> {
+        let node = self.load_node(id)?;
+        let items = (0..node.len()).map(|i| (node.key(i), node.value(i).to_vec())).collect();
+        Ok((node.block(0).header.node_type, node.level(), items))
+    }
+
+    /// Bir düğümü verilen öğelerle baştan yazar, bloklarının checksum'larını hesaplar ve
+    /// kirli işaretler. Öğeler sırayla blok başına `slots` tane dağıtılır.
+    fn write_node(&self, id: BlockId, node_type: u8, level: u8, items: &[(BTreeKey, Vec<u8>)]) -> Result<(), D::Error> {
+        if items.len() > self.node_order() {
+            return Err(D::Error::from(SyscallError::EINVAL));
+        }
+
+        for k in 0..self.node_blocks {
+            let block_id = id + k;
+            let block_arc = self.cache.get_block(block_id)?;
+            let block_mut = unsafe { &mut *block_arc.get() };
+            let first = (k as usize * self.slots).min(items.len());
+            let chunk = &items[first..(first + self.slots).min(items.len())];
+
+            block_mut.data.fill(0);
+            let node = unsafe { &mut *(block_mut.data.as_mut_ptr() as *mut BTreeNode) };
+            node.header.level = level;
+            node.header.block_id = block_id;
+            if k == 0 {
+                node.header.node_type = node_type;
+                node.header.num_entries = items.len() as u16;
+                node.header.span = if self.node_blocks > 1 { self.node_blocks as u8 } else { 0 };
+            } else {
+                node.header.node_type = NODE_TYPE_CONTINUATION;
+                node.header.num_entries = chunk.len() as u16;
+                node.header.span = k as u8;
+            }
+
+            for (i, (key, value)) in chunk.iter().enumerate() {
+                let start = i * BTREE_ITEM_SIZE;
+                let slot = &mut node.data_area[start..start + BTREE_ITEM_SIZE];
+                key.encode(&mut slot[..BTREE_KEY_SIZE]);
+                slot[BTREE_KEY_SIZE..BTREE_KEY_SIZE + 2].copy_from_slice(&(value.len() as u16).to_le_bytes());
+                slot[BTREE_KEY_SIZE + 2..BTREE_KEY_SIZE + 2 + value.len()].copy_from_slice(value);
+            }
+
+            self.cache.stamp(block_mut.data.as_mut());
+            node.header.checksum = node_checksum(self.checksum, block_mut.data.as_ref());
+            block_mut.is_dirty = true;
+        }
+        Ok(())
+    }
+
+    /// İç düğüm değerinden çocuk blok ID'sini çözer.
+    fn decode_child(value: &[u8]) -> BlockId {
+        let mut raw = [0u8; 8];
+        raw.copy_from_slice(&value[..8]);
+        BlockId::from_le_bytes(raw)
+    }
 }
\ No newline at end of file
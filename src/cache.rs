@@ -3,153 +3,922 @@
 #![allow(dead_code, unused_variables)]
 
 use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::sync::Arc;
+use alloc::vec::Vec;
 use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicU64, Ordering};
 
-use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE};
-use crate::sahne_syscalls::{
-    self, ResourceHandle, SyscallError,
-    SYSCALL_LOCK_CREATE, SYSCALL_LOCK_ACQUIRE, SYSCALL_LOCK_RELEASE,
-    SYSCALL_MEMORY_ALLOCATE, SYSCALL_MEMORY_RELEASE,
-    raw_syscall
-};
+use crate::block_device::{BlockDevice, BlockId, BufferRequirements, BLOCK_SIZE};
+use crate::checksum::checksum_excluding;
+use crate::platform::{self, SysLock};
+use crate::seal::SealVerifier;
+use crate::sahne_syscalls::SyscallError;
 
 
-// --- 1. Bellek ve Kilit Sarmalayıcıları (Sahne64'e Özel) ---
+// --- 1. Bellek ve Kilit Sarmalayıcıları ---
 
-// Sahne64'ün kilit handle'ı için bir tür alias'ı
-pub type LockHandle = u64;
+// Sıra bekleyen bir görevin, işlemciyi bırakmadan önce kaç kez döneceği.
+const FAIR_LOCK_SPIN_LIMIT: u32 = 64;
 
-/// Sahne64 çekirdeğinin kilitlerini sarmalayan temel bir yapı.
-/// Bu, önbelleğe eş zamanlı erişimi yönetmek için kullanılacaktır.
-pub struct SysLock {
-    handle: LockHandle,
+/// Görevlere kilidi istedikleri sırayla (FIFO) veren bilet kilidi (ticket lock).
+///
+/// `SysLock`'ta sıra çekirdeğe bağlıdır ve yoğun yazıcılar okuyucuları aç bırakabilir.
+/// Burada her görev bir bilet alır ve yalnızca sırası geldiğinde kilide girer; bekleyen
+/// görevler kısa bir dönüşten sonra `platform::yield_now` ile işlemciyi bırakır.
+/// Arayüzü `SysLock` ile aynıdır.
+pub struct FairLock {
+    /// Sıradaki verilecek bilet.
+    next_ticket: AtomicU64,
+    /// Şu anda kilide girmesine izin verilen bilet.
+    now_serving: AtomicU64,
 }
 
-impl SysLock {
-    /// Yeni bir çekirdek kilidi oluşturur.
-    pub fn new() -> Result<Self, SyscallError> {
-        let result = unsafe { raw_syscall(SYSCALL_LOCK_CREATE, 0, 0, 0, 0, 0, 0) };
-        if result < 0 {
-            Err(SyscallError::from_raw(result))
-        } else {
-            Ok(SysLock { handle: result as LockHandle })
+impl FairLock {
+    /// Yeni, serbest bir bilet kilidi oluşturur (çekirdek kaynağı gerektirmez).
+    pub const fn new() -> Self {
+        FairLock {
+            next_ticket: AtomicU64::new(0),
+            now_serving: AtomicU64::new(0),
         }
     }
 
-    /// Kilidi alır (Bloklayabilir).
+    /// Bilet alır ve sırası gelene kadar bekler.
     pub fn acquire(&self) {
-        unsafe { raw_syscall(SYSCALL_LOCK_ACQUIRE, self.handle, 0, 0, 0, 0, 0) };
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+
+        let mut spins = 0;
+        while self.now_serving.load(Ordering::Acquire) != ticket {
+            if spins < FAIR_LOCK_SPIN_LIMIT {
+                spins += 1;
+                core::hint::spin_loop();
+            } else {
+                // Sıfır süreli uyku: işlemciyi diğer görevlere bırak.
+                platform::yield_now();
+            }
+        }
     }
 
-    /// Kilidi serbest bırakır.
+    /// Kilidi sıradaki bilete devreder.
     pub fn release(&self) {
-        unsafe { raw_syscall(SYSCALL_LOCK_RELEASE, self.handle, 0, 0, 0, 0, 0) };
+        self.now_serving.fetch_add(1, Ordering::Release);
     }
 }
 
-// NOT: `Drop` trait'i, kilit handle'ını serbest bırakmak için uygulanmalıdır.
-
 
 // --- 2. Önbellek Yapıları ---
 
+// Önbellekte aynı anda tutulabilecek blok sayısı. low_memory profilinde önbellek
+// bu boyutta, oluşturulurken önceden tahsis edilir ve asla büyümez.
+#[cfg(feature = "low_memory")]
+pub const CACHE_CAPACITY_BLOCKS: usize = 32;
+#[cfg(not(feature = "low_memory"))]
+pub const CACHE_CAPACITY_BLOCKS: usize = 4096;
+
+// `set_capacity` ile verilebilecek en küçük kapasite; bir B-Ağacı yolunun ve CoW
+// kopyalarının aynı anda önbellekte tutulabilmesi için gerekir.
+pub const CACHE_MIN_BLOCKS: usize = 16;
+
+// Önden okuma (readahead/prefetch) yapılabilir mi? Küçük hedeflerde bellek için kapalıdır.
+pub const READAHEAD_ENABLED: bool = !cfg!(feature = "low_memory");
+
+// Yazma damgasının bloğun başındaki yeri (bkz. `BlockCache::enable_write_stamps`); ilk
+// 512 baytlık kesimde, B-Ağacı düğüm başlığının içindedir. İkinci damga son 8 bayttır.
+pub const WRITE_STAMP_HEAD: usize = 24;
+
+// Yazma damgasının boyutu (byte).
+pub const WRITE_STAMP_SIZE: usize = 8;
+
+/// Bloğun baştaki ve sondaki yazma damgaları aynı mı? Farklıysa bloğun 512 baytlık
+/// kesimleri iki ayrı yazmadan gelmiştir (yarıda kalan, yırtık yazma).
+pub fn write_stamps_match(data: &[u8]) -> bool {
+    data[WRITE_STAMP_HEAD..WRITE_STAMP_HEAD + WRITE_STAMP_SIZE] == data[BLOCK_SIZE - WRITE_STAMP_SIZE..BLOCK_SIZE]
+}
+
+/// Platformun bellek tahsisiyle sıfırlanmış bir blok arabelleği tahsis eder.
+pub(crate) fn allocate_buffer() -> Result<Box<[u8; BLOCK_SIZE]>, SyscallError> {
+    let memory = platform::memory_allocate(BLOCK_SIZE)?;
+
+    // Tahsis edilen ham bellek alanını Box<[u8; BLOCK_SIZE]> 'a dönüştür (ownership'i Rust'a ver)
+    Ok(unsafe { Box::from_raw(memory as *mut [u8; BLOCK_SIZE]) })
+}
+
+/// `len` baytlık sıfırlanmış bir çalışma arabelleği; bellek yetmezse `vec!` gibi
+/// durdurmak yerine `ENOMEM` döner. G/Ç yollarındaki geçici arabellekler için kullanılır.
+pub(crate) fn try_zeroed(len: usize) -> Result<Vec<u8>, SyscallError> {
+    let mut buffer = Vec::new();
+    buffer.try_reserve_exact(len).map_err(|_| SyscallError::ENOMEM)?;
+    buffer.resize(len, 0);
+    Ok(buffer)
+}
+
+/// `allocate_buffer` ile alınmış bir arabelleği platforma geri verir.
+pub(crate) fn release_buffer(buffer: Box<[u8; BLOCK_SIZE]>) {
+    let ptr = Box::into_raw(buffer);
+    unsafe { platform::memory_release(ptr as *mut u8, BLOCK_SIZE) };
+}
+
+/// Önbellek bloğunun arabelleği: `allocate_buffer` belleği ya da, aygıt kopyasız aktarım
+/// istiyorsa (bkz. `BufferRequirements::dma`), fiziksel adresi bilinen sayfa hizalı DMA
+/// belleği. Düşürüldüğünde bellek, tahsis edildiği çağrının karşılığıyla platforma geri verilir.
+pub struct BlockBuffer {
+    ptr: NonNull<[u8; BLOCK_SIZE]>,
+    /// DMA belleğiyse fiziksel adresi.
+    phys: Option<u64>,
+}
+
+// Arabellek tek sahiplidir (`Box` gibi); eş zamanlı erişimi önbelleğin kilitleri düzenler.
+unsafe impl Send for BlockBuffer {}
+unsafe impl Sync for BlockBuffer {}
+
+impl BlockBuffer {
+    /// Aygıtın gereksinimlerine göre arabellek tahsis eder: DMA isteniyorsa önce DMA
+    /// belleği denenir, tükenmişse olağan belleğe düşülür (aygıt ara belleğiyle aktarır).
+    pub(crate) fn allocate(requirements: BufferRequirements) -> Result<Self, SyscallError> {
+        if requirements.dma {
+            if let Ok(buffer) = Self::allocate_dma() {
+                return Ok(buffer);
+            }
+        }
+        Ok(BlockBuffer::from(allocate_buffer()?))
+    }
+
+    /// Fiziksel olarak ardışık, sayfa hizalı, sıfırlanmış bir DMA arabelleği tahsis eder.
+    fn allocate_dma() -> Result<Self, SyscallError> {
+        let (memory, phys) = platform::dma_allocate(BLOCK_SIZE)?;
+        let ptr = NonNull::new(memory as *mut [u8; BLOCK_SIZE]).ok_or(SyscallError::ENOMEM)?;
+        Ok(BlockBuffer { ptr, phys: Some(phys) })
+    }
+
+    /// Arabellek `requirements`'ı karşılıyorsa kopyasız aktarımda aygıta verilecek fiziksel adresi.
+    pub(crate) fn dma_address(&self, requirements: BufferRequirements) -> Option<u64> {
+        match self.phys {
+            Some(phys) if requirements.dma && requirements.is_met(self.ptr.as_ptr() as usize, self.phys) => Some(phys),
+            _ => None,
+        }
+    }
+}
+
+impl From<Box<[u8; BLOCK_SIZE]>> for BlockBuffer {
+    /// `allocate_buffer` ile alınmış bir arabelleği sarar.
+    fn from(buffer: Box<[u8; BLOCK_SIZE]>) -> Self {
+        // SAFETY: `Box::into_raw` hiçbir zaman boş işaretçi döndürmez
+        BlockBuffer { ptr: unsafe { NonNull::new_unchecked(Box::into_raw(buffer)) }, phys: None }
+    }
+}
+
+impl Deref for BlockBuffer {
+    type Target = [u8; BLOCK_SIZE];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl DerefMut for BlockBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl AsRef<[u8; BLOCK_SIZE]> for BlockBuffer {
+    fn as_ref(&self) -> &[u8; BLOCK_SIZE] {
+        self
+    }
+}
+
+impl AsMut<[u8; BLOCK_SIZE]> for BlockBuffer {
+    fn as_mut(&mut self) -> &mut [u8; BLOCK_SIZE] {
+        self
+    }
+}
+
+impl Drop for BlockBuffer {
+    fn drop(&mut self) {
+        let memory = self.ptr.as_ptr() as *mut u8;
+        match self.phys {
+            Some(_) => unsafe { platform::dma_release(memory, BLOCK_SIZE) },
+            None => unsafe { platform::memory_release(memory, BLOCK_SIZE) },
+        }
+    }
+}
+
 /// Diskten okunan/diske yazılacak tek bir bloğu temsil eder.
 /// `Arc` ve `UnsafeCell`, CoW için gereken Paylaşımlı Mutluluk (Shared Mutability) sağlar.
 pub struct CacheBlock {
     /// Bloğun ham bayt verisi (BLOCK_SIZE boyutunda).
-    data: Box<[u8; BLOCK_SIZE]>, 
+    pub(crate) data: BlockBuffer, 
     /// Diskteki mantıksal blok numarası (eğer tahsis edilmişse).
-    block_id: BlockId,
+    pub(crate) block_id: BlockId,
     /// Blok değiştirildi mi? (Diske yazılması gerekiyor mu?)
-    is_dirty: bool,
+    pub(crate) is_dirty: bool,
+    /// Bloğa en son erişildiği önbellek "saati" (LRU tahliyesi için).
+    pub(crate) last_used: u64,
 }
 
 impl CacheBlock {
     /// Yeni, boş (sıfırlanmış) bir önbellek bloğu oluşturur.
     /// Sahne64'ün bellek tahsis çağrısını kullanır.
     pub fn new_empty(id: BlockId) -> Result<Arc<UnsafeCell<Self>>, SyscallError> {
-        // Blok için dinamik olarak bellek tahsis et (Sahne64 çağrısı)
-        let total_size = BLOCK_SIZE;
-        let mem_ptr = unsafe {
-            raw_syscall(SYSCALL_MEMORY_ALLOCATE, total_size as u64, 0, 0, 0, 0, 0)
-        };
-
-        if mem_ptr == 0 {
-            return Err(SyscallError::ENOMEM);
-        }
-
-        // Tahsis edilen ham bellek alanını Box<[u8; BLOCK_SIZE]> 'a dönüştür.
-        let data_box = unsafe {
-             // Tahsis edilen alanı *mut [u8; BLOCK_SIZE] olarak varsay
-            let slice_ptr: *mut [u8; BLOCK_SIZE] = mem_ptr as *mut [u8; BLOCK_SIZE];
-            
-            // Veriyi sıfırla (güvenlik için)
-            core::ptr::write_bytes(slice_ptr as *mut u8, 0, BLOCK_SIZE);
-
-            // Box'a dönüştür (ownership'i Rust'a ver)
-            Box::from_raw(slice_ptr)
-        };
-
+        Ok(Self::with_buffer(id, BlockBuffer::from(allocate_buffer()?)))
+    }
 
-        Ok(Arc::new(UnsafeCell::new(CacheBlock {
-            data: data_box,
+    /// Önceden tahsis edilmiş (ör. havuzdan alınmış) bir arabellekle önbellek bloğu oluşturur.
+    pub fn with_buffer(id: BlockId, data: BlockBuffer) -> Arc<UnsafeCell<Self>> {
+        Arc::new(UnsafeCell::new(CacheBlock {
+            data,
             block_id: id,
             is_dirty: true, // Yeni blok tahsis edildiği için kirli sayılır
-        })))
+            last_used: 0,
+        }))
     }
-    
-    // NOT: `Drop` trait'i, `Box<...>` serbest bırakıldığında `SYSCALL_MEMORY_RELEASE` 
-    // çağırmak üzere dikkatlice uygulanmalıdır.
 }
 
 
+/// Geri alınabilir bir işlemin başından beri erişilen blokların önceki halleri
+/// (bkz. `BlockCache::begin_undo`).
+struct UndoLog {
+    /// İlk erişimde kirli olan blokların o anki kopyası; temiz olan veya önbellekte
+    /// bulunmayan bloklar için `None` (diskteki halleri zaten öncekidir).
+    before: BTreeMap<BlockId, Option<Box<[u8; BLOCK_SIZE]>>>,
+    /// Kayıt eksiksiz mi? Araya giren geri yazma veya bellek yetersizliği kaydı bozar.
+    complete: bool,
+}
+
 /// SADAK'ın blok I/O'sunu yöneten ana önbellek yapısı.
 /// Bu, CoW için kritik olan "blokları diskte değil, bellekte tutma" görevini üstlenir.
 pub struct BlockCache<D: BlockDevice> {
-    device: Arc<D>,
-    // TODO: Önbellek haritası (örneğin, BlockId -> Arc<UnsafeCell<CacheBlock>>) 
-    // Lock ile korunan bir HashTable veya BTreeMap olmalıdır.
-    // cache_map: Locked<HashMap<BlockId, Arc<UnsafeCell<CacheBlock>>>>,
-    lock: SysLock,
+    pub(crate) device: Arc<D>,
+    /// Önbellek haritası (BlockId -> blok). Yalnızca `lock` alınmışken erişilir.
+    cache_map: UnsafeCell<BTreeMap<BlockId, Arc<UnsafeCell<CacheBlock>>>>,
+    /// Tahliye edilen bloklardan geri kazanılan (veya önceden tahsis edilen) boş arabellekler.
+    buffer_pool: UnsafeCell<Vec<BlockBuffer>>,
+    /// Aygıtın kopyasız aktarım gereksinimleri; blok arabellekleri buna göre tahsis edilir.
+    requirements: BufferRequirements,
+    /// Her erişimde artan önbellek saati (LRU için).
+    access_tick: UnsafeCell<u64>,
+    /// Bu ID ve sonrasındaki bloklar aygıttan okunmaz (bkz. `set_block_limit`).
+    block_limit: UnsafeCell<BlockId>,
+    /// Mühürlü birimde aygıttan okunan her bloğu doğrulayan Merkle ağacı (bkz. `set_seal`).
+    seal: UnsafeCell<Option<SealVerifier>>,
+    /// Aynı anda tutulabilecek blok sayısı (bkz. `set_capacity`).
+    capacity: UnsafeCell<usize>,
+    /// Tahliye edilmeyen bloklar (bkz. `pin`).
+    pinned: UnsafeCell<BTreeSet<BlockId>>,
+    /// Açık geri alma kaydı (bkz. `begin_undo`).
+    undo: UnsafeCell<Option<UndoLog>>,
+    /// Son basılan yazma damgası; `None` ise damga basılmaz ve doğrulanmaz (bkz.
+    /// `enable_write_stamps`).
+    write_stamp: UnsafeCell<Option<u64>>,
+    lock: FairLock,
 }
 
 impl<D: BlockDevice> BlockCache<D> {
     pub fn new(device: Arc<D>) -> Result<Self, SyscallError> {
+        // low_memory profilinde tüm önbellek belleği baştan tahsis edilir;
+        // çalışma sırasında yeni tahsis yapılmaz.
+        let requirements = device.buffer_requirements();
+        let mut buffer_pool = Vec::new();
+        if cfg!(feature = "low_memory") {
+            buffer_pool.reserve_exact(CACHE_CAPACITY_BLOCKS);
+            for _ in 0..CACHE_CAPACITY_BLOCKS {
+                buffer_pool.push(BlockBuffer::allocate(requirements)?);
+            }
+        }
+
         Ok(BlockCache {
             device,
-            lock: SysLock::new()?, // Önbellek erişimi için kilidi oluştur
+            cache_map: UnsafeCell::new(BTreeMap::new()),
+            buffer_pool: UnsafeCell::new(buffer_pool),
+            requirements,
+            access_tick: UnsafeCell::new(0),
+            block_limit: UnsafeCell::new(BlockId::MAX),
+            seal: UnsafeCell::new(None),
+            capacity: UnsafeCell::new(CACHE_CAPACITY_BLOCKS),
+            pinned: UnsafeCell::new(BTreeSet::new()),
+            undo: UnsafeCell::new(None),
+            write_stamp: UnsafeCell::new(None),
+            lock: FairLock::new(), // Önbellek erişimi için FIFO kilidi oluştur
         })
     }
     
+    /// Temiz ve başka yerde tutulmayan önbellek bloklarını ve havuzdaki boş arabellekleri
+    /// Sahne64'e geri verir; geri verilen arabellek sayısını döndürür. Kirli bloklar ve
+    /// hâlâ kullanılan bloklar korunur.
+    pub fn release_memory(&self) -> usize {
+        self.lock.acquire();
+        let cache_map = unsafe { &mut *self.cache_map.get() };
+        let pool = unsafe { &mut *self.buffer_pool.get() };
+        let pinned = unsafe { &*self.pinned.get() };
+
+        let idle: Vec<BlockId> = cache_map
+            .iter()
+            .filter(|(id, b)| Arc::strong_count(b) == 1 && unsafe { !(*b.get()).is_dirty } && !pinned.contains(id))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in idle {
+            if let Some(block_arc) = cache_map.remove(&id) {
+                if let Ok(cell) = Arc::try_unwrap(block_arc) {
+                    pool.push(cell.into_inner().data);
+                }
+            }
+        }
+
+        // Arabellekler düşürülürken Sahne64'e geri verilir
+        let released = pool.len();
+        pool.clear();
+        self.lock.release();
+        released
+    }
+
+    /// `limit` ve sonrasındaki blokların aygıttan okunmasını engeller; bu bloklar için
+    /// `get_block` `EINVAL` döndürür. Aygıt superblock'un beklediğinden küçük olduğunda
+    /// (kurtarma montajı) aralık dışı bloklara hiç dokunulmaması için kullanılır.
+    pub fn set_block_limit(&self, limit: BlockId) {
+        self.lock.acquire();
+        unsafe { *self.block_limit.get() = limit };
+        self.lock.release();
+    }
+
+    /// Önbelleğin kapasitesini değiştirir (en az `CACHE_MIN_BLOCKS`). Küçültmede fazlalık
+    /// temiz bloklar hemen tahliye edilir; kirli bloklar sonraki commit'e kadar kalır.
+    /// low_memory profilinde arabellek havuzu yeni kapasiteye göre tahsis edilir veya bırakılır.
+    pub fn set_capacity(&self, capacity: usize) -> Result<(), SyscallError> {
+        let capacity = capacity.max(CACHE_MIN_BLOCKS);
+        self.lock.acquire();
+        let cache_map = unsafe { &*self.cache_map.get() };
+        while cache_map.len() > capacity && self.evict_one_locked() {}
+
+        let pool = unsafe { &mut *self.buffer_pool.get() };
+        let spare = capacity.saturating_sub(cache_map.len());
+        pool.truncate(spare);
+        let mut result = Ok(());
+        if cfg!(feature = "low_memory") {
+            while pool.len() < spare {
+                match BlockBuffer::allocate(self.requirements) {
+                    Ok(buffer) => pool.push(buffer),
+                    Err(e) => {
+                        result = Err(e);
+                        break;
+                    }
+                }
+            }
+        }
+        if result.is_ok() {
+            unsafe { *self.capacity.get() = capacity };
+        }
+        self.lock.release();
+        result
+    }
+
+    /// Önbelleğin kapasitesi (blok).
+    pub fn capacity(&self) -> usize {
+        self.lock.acquire();
+        let capacity = unsafe { *self.capacity.get() };
+        self.lock.release();
+        capacity
+    }
+
+    /// Bundan sonra aygıttan okunan her bloğun `verifier` ile doğrulanmasını sağlar;
+    /// mühürle uyuşmayan bloklar için `get_block` `EIO` döndürür. Önbellekte zaten
+    /// bulunan bloklar (ör. Superblock) yeniden doğrulanmaz.
+    pub fn set_seal(&self, verifier: SealVerifier) {
+        self.lock.acquire();
+        unsafe { *self.seal.get() = Some(verifier) };
+        self.lock.release();
+    }
+
+    /// Metadata düğümlerine yazma damgası basılmasını ve `get_stamped_block` ile okunurken
+    /// doğrulanmasını açar (`FEATURE_INCOMPAT_WRITE_STAMPS`). Aynı sıra numarası bloğun
+    /// `WRITE_STAMP_HEAD` konumuna ve son 8 baytına yazılır; atomik yazma birimi 512 bayt
+    /// olan aygıtta yarıda kalan 4 KiB yazma iki farklı damga bırakır. Sıra `seed`'in
+    /// ardından sayar; çağıran montajlar arasında artan bir değer vermelidir.
+    pub fn enable_write_stamps(&self, seed: u64) {
+        self.lock.acquire();
+        unsafe { *self.write_stamp.get() = Some(seed) };
+        self.lock.release();
+    }
+
+    /// Yazma damgaları açık mı?
+    pub fn write_stamps(&self) -> bool {
+        self.lock.acquire();
+        let enabled = unsafe { (*self.write_stamp.get()).is_some() };
+        self.lock.release();
+        enabled
+    }
+
+    /// Bloğa bir sonraki yazma damgasını basar; damgalar kapalıysa bloğa dokunmaz.
+    /// Çağıran bloğun checksum'unu damgadan sonra hesaplamalıdır.
+    pub fn stamp(&self, data: &mut [u8]) {
+        self.lock.acquire();
+        if let Some(seq) = unsafe { &mut *self.write_stamp.get() } {
+            *seq += 1;
+            let raw = seq.to_le_bytes();
+            data[WRITE_STAMP_HEAD..WRITE_STAMP_HEAD + WRITE_STAMP_SIZE].copy_from_slice(&raw);
+            data[BLOCK_SIZE - WRITE_STAMP_SIZE..BLOCK_SIZE].copy_from_slice(&raw);
+        }
+        self.lock.release();
+    }
+
+    /// `ids` bloklarını önbelleğe yükler ve tahliyeden korur; önceki sabitlenmiş küme
+    /// bırakılır (boş liste sabitlemeyi kaldırır). Sabitlenen bloklar kapasiteye sayılır;
+    /// kapasiteyi doldurursa önbellek kapasitesinin ötesine büyür.
+    pub fn pin(&self, ids: &[BlockId]) -> Result<(), D::Error> {
+        self.lock.acquire();
+        unsafe { *self.pinned.get() = ids.iter().copied().collect() };
+        let mut result = Ok(());
+        for &id in ids {
+            if let Err(e) = self.get_block_locked(id, false) {
+                result = Err(e);
+                break;
+            }
+        }
+        self.lock.release();
+        result
+    }
+
+    /// Sabitlenmiş blok sayısı.
+    pub fn pinned_count(&self) -> usize {
+        self.lock.acquire();
+        let count = unsafe { (*self.pinned.get()).len() };
+        self.lock.release();
+        count
+    }
+
     /// Belirli bir blok numarasını önbellekten alır veya diskten okur.
     pub fn get_block(&self, id: BlockId) -> Result<Arc<UnsafeCell<CacheBlock>>, D::Error> {
-        // Basitleştirilmiş implementasyon: Her zaman diskten oku.
-        // Gerçek implementasyonda önce 'lock' alınır ve 'cache_map' kontrol edilir.
-        
-        // 1. Bellek Tahsis Et (SYSCALL_MEMORY_ALLOCATE kullanılarak)
-        let block_arc = match CacheBlock::new_empty(id) {
-            Ok(b) => b,
-            Err(e) => {
-                // Sahne64 sistem çağrısı hatasını yay
-                // D::Error'a dönüşüm için `from` kullanıyoruz, D'nin bu trait'i uygulaması gerekir.
-                return Err(D::Error::from(e)); 
+        self.lock.acquire();
+        let result = self.get_block_locked(id, false);
+        self.lock.release();
+        result
+    }
+
+    /// `get_block` gibi, ama yazma damgaları açıksa (bkz. `enable_write_stamps`) bloğun iki
+    /// damgası uyuşmadığında `EIO` döndürür. Önden okunmuş bloklar da ilk istekte denetlenir.
+    pub fn get_stamped_block(&self, id: BlockId) -> Result<Arc<UnsafeCell<CacheBlock>>, D::Error> {
+        self.lock.acquire();
+        let result = self.get_block_locked(id, true);
+        self.lock.release();
+        result
+    }
+
+    /// `get_block`'un kilit alınmış halde çalışan gövdesi; `stamped` ise yazma damgaları
+    /// doğrulanır (bkz. `get_stamped_block`).
+    fn get_block_locked(&self, id: BlockId, stamped: bool) -> Result<Arc<UnsafeCell<CacheBlock>>, D::Error> {
+        let cache_map = unsafe { &mut *self.cache_map.get() };
+        let tick = self.next_tick_locked();
+        let check_stamps = stamped && unsafe { (*self.write_stamp.get()).is_some() };
+
+        // 0. Blok zaten önbellekteyse (kirli olabilir) aynı kopyayı döndür.
+        if let Some(block_arc) = cache_map.get(&id) {
+            if check_stamps && !write_stamps_match(unsafe { (*block_arc.get()).data.as_ref() }) {
+                return Err(D::Error::from(SyscallError::EIO));
             }
+            unsafe { (*block_arc.get()).last_used = tick };
+            self.record_undo_locked(id, Some(unsafe { &*block_arc.get() }));
+            return Ok(block_arc.clone());
+        }
+
+        // Aygıtın sonunun ötesi okunmaz (kurtarma montajı)
+        if id >= unsafe { *self.block_limit.get() } {
+            return Err(D::Error::from(SyscallError::EINVAL));
+        }
+
+        // Önbellek doluysa önce kullanılmayan temiz bir bloğu tahliye et.
+        if cache_map.len() >= unsafe { *self.capacity.get() } {
+            self.evict_one_locked();
+        }
+        
+        // 1. Bellek Tahsis Et (önce havuzdan, yoksa platformun bellek tahsisiyle)
+        let pool = unsafe { &mut *self.buffer_pool.get() };
+        let mut data = match pool.pop() {
+            Some(buffer) => buffer,
+            // Sabit boyutlu önbellek büyüyemez; çağıran önce commit etmelidir.
+            None if cfg!(feature = "low_memory") => return Err(D::Error::from(SyscallError::ENOMEM)),
+            None => match BlockBuffer::allocate(self.requirements) {
+                Ok(b) => b,
+                Err(e) => {
+                    // Sahne64 sistem çağrısı hatasını yay
+                    // D::Error'a dönüşüm için `from` kullanıyoruz, D'nin bu trait'i uygulaması gerekir.
+                    return Err(D::Error::from(e)); 
+                }
+            },
         };
         
         // 2. Diske I/O Yap (BlockDevice kullanılarak)
-        // Güvenli olmayan (unsafe) alana erişim. CoW ve kilitleme mekaniği budur.
-        let block_mut = unsafe { &mut *block_arc.get() };
-        
         // Cihazdan veriyi okur ve bloğun ham verisine yazar.
         // SYSCALL_RESOURCE_READ/SEEK, BlockDevice içinde sarmalandı.
-        self.device.read_block(id, block_mut.data.as_mut())?;
+        // DMA belleğindeki arabelleğe aygıt doğrudan okur; değilse kendi ara belleğinden kopyalar
+        let read = match data.dma_address(self.requirements) {
+            // SAFETY: adres, arabelleğin kendi DMA tahsisinden gelir
+            Some(phys) => unsafe { self.device.read_block_dma(id, data.as_mut(), phys) },
+            None => self.device.read_block(id, data.as_mut()),
+        };
+        if let Err(e) = read {
+            pool.push(data);
+            return Err(e);
+        }
+
+        // Mühürlü birimde blok, kök özete karşı doğrulanmadan önbelleğe alınmaz
+        if let Some(seal) = unsafe { &*self.seal.get() } {
+            match seal.verify(self.device.as_ref(), id, data.as_ref()) {
+                Ok(true) => {}
+                Ok(false) => {
+                    pool.push(data);
+                    return Err(D::Error::from(SyscallError::EIO));
+                }
+                Err(e) => {
+                    pool.push(data);
+                    return Err(e);
+                }
+            }
+        }
+        
+        // Damgaları uyuşmayan (yırtık yazılmış) düğüm önbelleğe alınmaz
+        if check_stamps && !write_stamps_match(data.as_ref()) {
+            pool.push(data);
+            return Err(D::Error::from(SyscallError::EIO));
+        }
+
+        let block_arc = CacheBlock::with_buffer(id, data);
+        // Güvenli olmayan (unsafe) alana erişim. CoW ve kilitleme mekaniği budur.
+        let block_mut = unsafe { &mut *block_arc.get() };
         
         // Okunan blok temizdir (kirli: false)
         block_mut.is_dirty = false;
+        block_mut.last_used = tick;
+
+        self.record_undo_locked(id, None);
+        cache_map.insert(id, block_arc.clone());
         
         Ok(block_arc)
     }
-    
-    // TODO: `release_block` (kirli ise diske yazar) ve `new_allocated_block` (yeni blok tahsis eder) 
-    // fonksiyonları bu yapıya eklenecektir.
+
+    /// Önbellek saatini ilerletir (çağıran `lock`'u tutmalıdır).
+    fn next_tick_locked(&self) -> u64 {
+        let tick = unsafe { &mut *self.access_tick.get() };
+        *tick += 1;
+        *tick
+    }
+
+    /// En uzun süredir kullanılmayan, temiz ve başka kimsenin tutmadığı bloğu
+    /// önbellekten çıkarır; arabelleğini havuza geri koyar (çağıran `lock`'u tutmalıdır).
+    ///
+    /// # Döndürür
+    /// Bir blok tahliye edildiyse `true`.
+    fn evict_one_locked(&self) -> bool {
+        let cache_map = unsafe { &mut *self.cache_map.get() };
+        let pinned = unsafe { &*self.pinned.get() };
+
+        let victim = cache_map
+            .iter()
+            .filter(|(id, b)| Arc::strong_count(b) == 1 && unsafe { !(*b.get()).is_dirty } && !pinned.contains(id))
+            .min_by_key(|(_, b)| unsafe { (*b.get()).last_used })
+            .map(|(id, _)| *id);
+
+        let Some(id) = victim else {
+            return false;
+        };
+
+        if let Some(block_arc) = cache_map.remove(&id) {
+            if let Ok(cell) = Arc::try_unwrap(block_arc) {
+                unsafe { &mut *self.buffer_pool.get() }.push(cell.into_inner().data);
+            }
+        }
+        true
+    }
+
+    /// Verilen blokları, henüz önbellekte değillerse tek geçişte önceden okur.
+    ///
+    /// Önden okuma spekülatif olduğu için önbellek doluysa çalışma kümesini
+    /// tahliye etmek yerine durur. `READAHEAD_ENABLED` kapalıysa hiçbir şey yapmaz.
+    ///
+    /// # Döndürür
+    /// Diskten okunarak önbelleğe eklenen blok sayısı.
+    pub fn prefetch(&self, ids: &[BlockId]) -> Result<usize, D::Error> {
+        if !READAHEAD_ENABLED {
+            return Ok(0);
+        }
+
+        self.lock.acquire();
+        let cache_map = unsafe { &*self.cache_map.get() };
+
+        let mut loaded = 0;
+        for &id in ids {
+            if cache_map.contains_key(&id) {
+                continue;
+            }
+            if cache_map.len() >= unsafe { *self.capacity.get() } {
+                break;
+            }
+
+            if let Err(e) = self.get_block_locked(id, false) {
+                self.lock.release();
+                return Err(e);
+            }
+            loaded += 1;
+        }
+
+        self.lock.release();
+        Ok(loaded)
+    }
+
+    /// Önbellekteki kirli (diske yazılmayı bekleyen) blok sayısını döndürür.
+    pub fn dirty_count(&self) -> usize {
+        self.lock.acquire();
+        let cache_map = unsafe { &*self.cache_map.get() };
+        let count = cache_map
+            .values()
+            .filter(|b| unsafe { (*b.get()).is_dirty })
+            .count();
+        self.lock.release();
+        count
+    }
+
+    /// Önbellekteki kirli blokların ID'leri, artan sırayla.
+    pub fn dirty_blocks(&self) -> Vec<BlockId> {
+        self.lock.acquire();
+        let cache_map = unsafe { &*self.cache_map.get() };
+        let ids = cache_map
+            .iter()
+            .filter(|(_, b)| unsafe { (*b.get()).is_dirty })
+            .map(|(id, _)| *id)
+            .collect();
+        self.lock.release();
+        ids
+    }
+
+    /// `[start, start + count)` aralığındaki bloklardan herhangi biri önbellekte kirli mi?
+    /// Önbelleği atlayan doğrudan okumalar, diskteki eski veriyi okumamak için bunu sorar.
+    pub fn has_dirty_in_range(&self, start: BlockId, count: u64) -> bool {
+        self.lock.acquire();
+        let cache_map = unsafe { &*self.cache_map.get() };
+        let dirty = cache_map
+            .range(start..start + count)
+            .any(|(_, b)| unsafe { (*b.get()).is_dirty });
+        self.lock.release();
+        dirty
+    }
+
+    /// `[start, start + count)` aralığındaki blokların önbellek kopyalarını atar.
+    /// Önbelleği atlayarak doğrudan aygıta yazılan bloklarda eski kopyaları geçersiz kılar.
+    pub fn discard_range(&self, start: BlockId, count: u64) {
+        self.lock.acquire();
+        let cache_map = unsafe { &mut *self.cache_map.get() };
+        let pool = unsafe { &mut *self.buffer_pool.get() };
+
+        let ids: Vec<BlockId> = cache_map.range(start..start + count).map(|(id, _)| *id).collect();
+        for id in ids {
+            if let Some(block_arc) = cache_map.remove(&id) {
+                if let Ok(cell) = Arc::try_unwrap(block_arc) {
+                    pool.push(cell.into_inner().data);
+                }
+            }
+        }
+        self.lock.release();
+    }
+
+    /// Kirli blokların önbellek kopyalarını yazmadan atar; bloklar bir sonraki erişimde
+    /// diskteki (son commit'teki) halleriyle okunur. Geri alınan transaction'da kullanılır.
+    ///
+    /// # Döndürür
+    /// Atılan blok sayısı.
+    pub fn discard_dirty(&self) -> usize {
+        self.lock.acquire();
+        let cache_map = unsafe { &mut *self.cache_map.get() };
+        let pool = unsafe { &mut *self.buffer_pool.get() };
+
+        let ids: Vec<BlockId> = cache_map
+            .iter()
+            .filter(|(_, b)| unsafe { (*b.get()).is_dirty })
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &ids {
+            if let Some(block_arc) = cache_map.remove(id) {
+                if let Ok(cell) = Arc::try_unwrap(block_arc) {
+                    pool.push(cell.into_inner().data);
+                }
+            }
+        }
+        self.lock.release();
+        ids.len()
+    }
+
+    /// Geri alma kaydını başlatır: bundan sonra erişilen her bloğun ilk erişimdeki hali
+    /// kaydedilir ve önbellek `rollback_undo` ile o hale döndürülebilir. Temiz blokların
+    /// kopyası tutulmaz; geri almada atılıp diskten yeniden okunurlar.
+    ///
+    /// # Döndürür
+    /// Kayıt başlatıldıysa `true`; zaten açık bir kayıt varsa `false` (iç içe işlem).
+    pub fn begin_undo(&self) -> bool {
+        self.lock.acquire();
+        let undo = unsafe { &mut *self.undo.get() };
+        let started = undo.is_none();
+        if started {
+            *undo = Some(UndoLog { before: BTreeMap::new(), complete: true });
+        }
+        self.lock.release();
+        started
+    }
+
+    /// Geri alma kaydını kapatır; kayıt boyunca yapılan değişiklikler kalır.
+    pub fn end_undo(&self) {
+        self.lock.acquire();
+        let undo = unsafe { &mut *self.undo.get() }.take();
+        self.lock.release();
+        if let Some(undo) = undo {
+            undo.before.into_values().flatten().for_each(release_buffer);
+        }
+    }
+
+    /// Kayıttaki blokları kayıt başındaki hallerine döndürür ve kaydı kapatır.
+    ///
+    /// # Döndürür
+    /// Geri alındıysa `true`; kayıt eksikse (araya geri yazma girdiyse veya kopya için
+    /// bellek ayrılamadıysa) hiçbir şey yapılmaz ve `false` döner.
+    pub fn rollback_undo(&self) -> bool {
+        self.lock.acquire();
+        let cache_map = unsafe { &mut *self.cache_map.get() };
+        let pool = unsafe { &mut *self.buffer_pool.get() };
+        let undo = match unsafe { &mut *self.undo.get() }.take() {
+            Some(undo) if undo.complete => undo,
+            Some(undo) => {
+                self.lock.release();
+                undo.before.into_values().flatten().for_each(release_buffer);
+                return false;
+            }
+            None => {
+                self.lock.release();
+                return false;
+            }
+        };
+
+        for (id, image) in undo.before {
+            match image {
+                // Önceden kirli blok: kopyası geri konur (bu arada atıldıysa yeniden eklenir)
+                Some(image) => match cache_map.get(&id) {
+                    Some(block_arc) => {
+                        let block = unsafe { &mut *block_arc.get() };
+                        block.data.copy_from_slice(image.as_ref());
+                        block.is_dirty = true;
+                        release_buffer(image);
+                    }
+                    None => {
+                        cache_map.insert(id, CacheBlock::with_buffer(id, BlockBuffer::from(image)));
+                    }
+                },
+                // Önceden temiz blok: değiştiyse atılır, diskteki haliyle yeniden okunur
+                None => {
+                    if cache_map.get(&id).is_some_and(|block_arc| unsafe { (*block_arc.get()).is_dirty }) {
+                        if let Some(block_arc) = cache_map.remove(&id) {
+                            if let Ok(cell) = Arc::try_unwrap(block_arc) {
+                                pool.push(cell.into_inner().data);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        self.lock.release();
+        true
+    }
+
+    /// Geri alma kaydı açıksa bloğun bu işlemdeki ilk erişimdeki halini kaydeder
+    /// (çağıran `lock`'u tutmalıdır). `cached` önbellekteki kopyadır; diskten yeni
+    /// okunan bloklar için `None`.
+    fn record_undo_locked(&self, id: BlockId, cached: Option<&CacheBlock>) {
+        let Some(undo) = (unsafe { &mut *self.undo.get() }) else {
+            return;
+        };
+        if undo.before.contains_key(&id) {
+            return;
+        }
+        let image = match cached {
+            Some(block) if block.is_dirty => match allocate_buffer() {
+                Ok(mut buffer) => {
+                    buffer.copy_from_slice(block.data.as_ref());
+                    Some(buffer)
+                }
+                Err(_) => {
+                    undo.complete = false;
+                    return;
+                }
+            },
+            _ => None,
+        };
+        undo.before.insert(id, image);
+    }
+
+    /// Kirli blokları artan BlockId sırasıyla diske yazar ve temiz olarak işaretler.
+    ///
+    /// # Parametreler
+    /// * `exclude`: Bu turda yazılmayacak bloklar (ör. commit'in en sonunda yazılacak Superblock).
+    ///
+    /// # Döndürür
+    /// Diske yazılan blok sayısı.
+    pub fn write_back_dirty(&self, exclude: &[BlockId]) -> Result<usize, D::Error> {
+        self.lock.acquire();
+        let cache_map = unsafe { &*self.cache_map.get() };
+
+        // Kirli bloklar tek toplu istekte verilir; çok kuyruklu aygıtlar bunları paralel
+        // yürütür. Hata durumunda hiçbiri temiz işaretlenmez ve sonraki turda yeniden yazılır.
+        let dirty: Vec<(BlockId, &[u8])> = cache_map
+            .iter()
+            .filter(|(id, block_arc)| unsafe { (*block_arc.get()).is_dirty } && !exclude.contains(id))
+            .map(|(id, block_arc)| (*id, unsafe { &*block_arc.get() }.data.as_ref() as &[u8]))
+            .collect();
+        let written = if self.requirements.dma {
+            let phys: Vec<Option<u64>> = dirty
+                .iter()
+                .map(|(id, _)| unsafe { &*cache_map[id].get() }.data.dma_address(self.requirements))
+                .collect();
+            // SAFETY: adresler, blokların kendi DMA tahsislerinden gelir
+            unsafe { self.device.write_batch_dma(&dirty, &phys) }
+        } else {
+            self.device.write_batch(&dirty)
+        };
+        if let Err(e) = written {
+            self.lock.release();
+            return Err(e);
+        }
+
+        for &(id, _) in &dirty {
+            let block_mut = unsafe { &mut *cache_map[&id].get() };
+            block_mut.is_dirty = false;
+        }
+        // Diske giden değişiklikler geri alınamaz
+        if let Some(undo) = unsafe { &mut *self.undo.get() } {
+            undo.complete &= dirty.is_empty();
+        }
+
+        self.lock.release();
+        Ok(dirty.len())
+    }
+
+    /// Önbellekteki blokların ID'leri, en son kullanılandan başlayarak en fazla `max` tane.
+    pub fn hot_blocks(&self, max: usize) -> Vec<BlockId> {
+        self.lock.acquire();
+        let cache_map = unsafe { &*self.cache_map.get() };
+        let mut blocks: Vec<(u64, BlockId)> = cache_map
+            .iter()
+            .map(|(id, block_arc)| (unsafe { (*block_arc.get()).last_used }, *id))
+            .collect();
+        self.lock.release();
+
+        blocks.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+        blocks.truncate(max);
+        blocks.into_iter().map(|(_, id)| id).collect()
+    }
+}
+
+
+// --- 3. Sıcak Blok Listesi (Warm Cache) ---
+// Temiz ayırmada en son kullanılan blokların ID'leri tek bir bloğa yazılır ve sonraki
+// montajda önceden okunur; yavaş ortamda açılışın çalışma kümesi soğuk başlamaz.
+
+// Sıcak blok listesi bloğunun sihirli sayısı ("WARM").
+const WARM_SET_MAGIC: u32 = 0x5741524D;
+
+// Blok başlığı: magic (4) + checksum (4) + count (4) + reserved (4)
+const WARM_SET_HEADER_SIZE: usize = 16;
+
+// Bir listeye yazılabilecek en fazla blok ID'si.
+pub const WARM_SET_MAX_BLOCKS: usize = (BLOCK_SIZE - WARM_SET_HEADER_SIZE) / 8;
+
+/// Blok ID'lerini sıcak blok listesi formatında `out`'a yazar (fazlası kesilir).
+pub fn encode_warm_set(ids: &[BlockId], out: &mut [u8]) {
+    let ids = &ids[..ids.len().min(WARM_SET_MAX_BLOCKS)];
+    out[..BLOCK_SIZE].fill(0);
+    out[0..4].copy_from_slice(&WARM_SET_MAGIC.to_le_bytes());
+    out[8..12].copy_from_slice(&(ids.len() as u32).to_le_bytes());
+    for (i, id) in ids.iter().enumerate() {
+        let at = WARM_SET_HEADER_SIZE + i * 8;
+        out[at..at + 8].copy_from_slice(&id.to_le_bytes());
+    }
+    let crc = checksum_excluding(&out[..BLOCK_SIZE], 4, 4);
+    out[4..8].copy_from_slice(&crc.to_le_bytes());
+}
+
+/// Sıcak blok listesi bloğunu çözer; blok bozuksa veya liste değilse `None`.
+pub fn decode_warm_set(data: &[u8]) -> Option<Vec<BlockId>> {
+    if data.len() < BLOCK_SIZE {
+        return None;
+    }
+    let u32_at = |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+    let count = u32_at(8) as usize;
+    if u32_at(0) != WARM_SET_MAGIC || u32_at(4) != checksum_excluding(&data[..BLOCK_SIZE], 4, 4) || count > WARM_SET_MAX_BLOCKS {
+        return None;
+    }
+
+    let ids = (0..count)
+        .map(|i| {
+            let at = WARM_SET_HEADER_SIZE + i * 8;
+            let mut raw = [0u8; 8];
+            raw.copy_from_slice(&data[at..at + 8]);
+            BlockId::from_le_bytes(raw)
+        })
+        .collect();
+    Some(ids)
 }
\ No newline at end of file
@@ -0,0 +1,102 @@
+// src/changes.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::BlockId;
+use crate::btree::{BTreeKey, ITEM_TYPE_CHANGED_BLOCKS};
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ---
+
+// Değişen blok kayıtlarının tutulduğu nesne kimliği. Inode blok ID'leri aygıt boyutundan
+// küçük olduğundan hiçbir inode'un öğeleriyle çakışmaz.
+const CHANGE_LOG_OBJECT: u64 = u64::MAX;
+
+// Anahtarın `offset` alanında blok ID'sine ayrılan bit sayısı; üstteki bitler dönemdir.
+const CHANGE_BLOCK_BITS: u32 = 40;
+
+// Değişiklik takibinin desteklediği en büyük aygıt (blok sayısı; 4 KiB bloklarla 4 PiB).
+pub const CHANGE_TRACKING_MAX_BLOCKS: u64 = 1 << CHANGE_BLOCK_BITS;
+
+// Değişiklik takibinin desteklediği en büyük dönem numarası.
+pub const CHANGE_EPOCH_MAX: u64 = (1 << (64 - CHANGE_BLOCK_BITS)) - 1;
+
+
+// --- 2. Değişen Blok Kayıtları ---
+
+/// Bir snapshot döneminde (iki snapshot arası) içeriği değişen ardışık blok aralığı.
+///
+/// Kayıtlar metadata ağacında `(CHANGE_LOG_OBJECT, ITEM_TYPE_CHANGED_BLOCKS,
+/// dönem << 40 | ilk blok)` anahtarıyla tutulur ve değer aralığın uzunluğudur (LE).
+/// Böylece "N döneminden sonra değişenler" tek bir sıralı taramayla bulunur ve eski
+/// dönemler baştan silinerek budanabilir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangedRun {
+    pub epoch: u64,
+    pub start: BlockId,
+    pub length: u64,
+}
+
+impl ChangedRun {
+    /// `epoch` döneminde `start` bloğundan başlayan kaydın anahtarı.
+    pub fn key(epoch: u64, start: BlockId) -> BTreeKey {
+        BTreeKey {
+            object_id: CHANGE_LOG_OBJECT,
+            item_type: ITEM_TYPE_CHANGED_BLOCKS,
+            offset: (epoch << CHANGE_BLOCK_BITS) | start,
+        }
+    }
+
+    /// Anahtar bir değişen blok kaydına mı ait?
+    pub fn is_change_key(key: &BTreeKey) -> bool {
+        key.object_id == CHANGE_LOG_OBJECT && key.item_type == ITEM_TYPE_CHANGED_BLOCKS
+    }
+
+    /// B-Ağacı değer formatı: aralık uzunluğu (LE).
+    pub fn encode(&self) -> [u8; 8] {
+        self.length.to_le_bytes()
+    }
+
+    /// Anahtar ve değerden kaydı çözer; kayıt değilse veya bozuksa `None`.
+    pub fn from_item(key: &BTreeKey, value: &[u8]) -> Option<Self> {
+        if !Self::is_change_key(key) || value.len() < 8 {
+            return None;
+        }
+        let mut length = [0u8; 8];
+        length.copy_from_slice(&value[..8]);
+        Some(ChangedRun {
+            epoch: key.offset >> CHANGE_BLOCK_BITS,
+            start: key.offset & (CHANGE_TRACKING_MAX_BLOCKS - 1),
+            length: u64::from_le_bytes(length),
+        })
+    }
+}
+
+/// Sıralı blok kümesini ardışık aralıklara (ilk blok, uzunluk) böler.
+pub fn to_runs(blocks: &BTreeSet<BlockId>) -> Vec<(BlockId, u64)> {
+    let mut runs: Vec<(BlockId, u64)> = Vec::new();
+    for &id in blocks {
+        match runs.last_mut() {
+            Some((start, len)) if *start + *len == id => *len += 1,
+            _ => runs.push((id, 1)),
+        }
+    }
+    runs
+}
+
+/// Aralıkları sıralar ve çakışan ya da bitişik olanları birleştirir.
+pub fn merge_runs(mut runs: Vec<(BlockId, u64)>) -> Vec<(BlockId, u64)> {
+    runs.sort_unstable();
+    let mut merged: Vec<(BlockId, u64)> = Vec::with_capacity(runs.len());
+    for (start, len) in runs {
+        match merged.last_mut() {
+            Some((last_start, last_len)) if start <= *last_start + *last_len => {
+                *last_len = (*last_len).max(start + len - *last_start);
+            }
+            _ => merged.push((start, len)),
+        }
+    }
+    merged
+}
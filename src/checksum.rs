@@ -2,7 +2,9 @@
 
 #![allow(dead_code)] // Şimdilik sadece fonksiyonları tanımlıyoruz
 
+use crate::seal::sha256;
 use core::u32;
+use alloc::vec::Vec;
 
 // --- 1. CRC32C Sabitleri ---
 // CRC32C (Castagnoli) polinomu: x^32 + x^28 + x^27 + x^26 + x^25 + x^23 + x^22 + x^20 + x^19 + x^18 + x^14 + x^13 + x^11 + x^10 + x^9 + x^8 + x^6 + x^0
@@ -72,4 +74,226 @@ pub fn calculate_crc32c(data: &[u8], initial_crc: u32) -> u32 {
 /// Verilen bayt dizisinin varsayılan başlangıç değeri (0xFFFFFFFF) ile CRC32C'sini hesaplar.
 pub fn checksum_data(data: &[u8]) -> u32 {
     calculate_crc32c(data, CRC32C_INITIAL)
-}
\ No newline at end of file
+}
+
+/// Yapının içinde saklanan checksum alanını sıfır kabul ederek CRC32C hesaplar.
+/// Böylece checksum, kendi değerini içermeden yazılıp doğrulanabilir.
+///
+/// # Parametreler
+/// * `data`: Checksum alanını da içeren ham blok verisi.
+/// * `field_offset`: Checksum alanının blok içindeki konumu (bayt).
+/// * `field_len`: Checksum alanının uzunluğu (bayt).
+pub fn checksum_excluding(data: &[u8], field_offset: usize, field_len: usize) -> u32 {
+    let zeros = [0u8; 8];
+    let field_end = field_offset + field_len;
+
+    let crc = calculate_crc32c(&data[..field_offset], CRC32C_INITIAL);
+    let crc = calculate_crc32c(&zeros[..field_len], crc);
+    calculate_crc32c(&data[field_end..], crc)
+}
+
+
+// --- 5. xxHash64 ---
+
+const XXH_PRIME64_1: u64 = 0x9E3779B185EBCA87;
+const XXH_PRIME64_2: u64 = 0xC2B2AE3D27D4EB4F;
+const XXH_PRIME64_3: u64 = 0x165667B19E3779F9;
+const XXH_PRIME64_4: u64 = 0x85EBCA77C2B2AE63;
+const XXH_PRIME64_5: u64 = 0x27D4EB2F165667C5;
+
+/// Verinin `seed` tohumlu xxHash64 özetini hesaplar. CRC32C'den hızlıdır ve büyük
+/// bloklarda (dosya verisi ağaçları) tercih edilir.
+pub fn xxhash64(data: &[u8], seed: u64) -> u64 {
+    let u64_at = |at: usize| {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&data[at..at + 8]);
+        u64::from_le_bytes(bytes)
+    };
+    let round = |acc: u64, input: u64| {
+        acc.wrapping_add(input.wrapping_mul(XXH_PRIME64_2))
+            .rotate_left(31)
+            .wrapping_mul(XXH_PRIME64_1)
+    };
+    let merge = |acc: u64, value: u64| (acc ^ round(0, value)).wrapping_mul(XXH_PRIME64_1).wrapping_add(XXH_PRIME64_4);
+
+    let mut at = 0;
+    let mut hash = if data.len() >= 32 {
+        let mut v = [
+            seed.wrapping_add(XXH_PRIME64_1).wrapping_add(XXH_PRIME64_2),
+            seed.wrapping_add(XXH_PRIME64_2),
+            seed,
+            seed.wrapping_sub(XXH_PRIME64_1),
+        ];
+        while at + 32 <= data.len() {
+            for (lane, acc) in v.iter_mut().enumerate() {
+                *acc = round(*acc, u64_at(at + lane * 8));
+            }
+            at += 32;
+        }
+        let mut hash = v[0]
+            .rotate_left(1)
+            .wrapping_add(v[1].rotate_left(7))
+            .wrapping_add(v[2].rotate_left(12))
+            .wrapping_add(v[3].rotate_left(18));
+        for acc in v {
+            hash = merge(hash, acc);
+        }
+        hash
+    } else {
+        seed.wrapping_add(XXH_PRIME64_5)
+    };
+    hash = hash.wrapping_add(data.len() as u64);
+
+    // Kalan baytlar: 8, 4 ve 1 baytlık parçalar
+    while at + 8 <= data.len() {
+        hash ^= round(0, u64_at(at));
+        hash = hash.rotate_left(27).wrapping_mul(XXH_PRIME64_1).wrapping_add(XXH_PRIME64_4);
+        at += 8;
+    }
+    if at + 4 <= data.len() {
+        let word = u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]) as u64;
+        hash ^= word.wrapping_mul(XXH_PRIME64_1);
+        hash = hash.rotate_left(23).wrapping_mul(XXH_PRIME64_2).wrapping_add(XXH_PRIME64_3);
+        at += 4;
+    }
+    for &byte in &data[at..] {
+        hash ^= (byte as u64).wrapping_mul(XXH_PRIME64_5);
+        hash = hash.rotate_left(11).wrapping_mul(XXH_PRIME64_1);
+    }
+
+    // Son karıştırma (avalanche)
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(XXH_PRIME64_2);
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(XXH_PRIME64_3);
+    hash ^ (hash >> 32)
+}
+
+
+// --- 6. Algoritma Seçimi ---
+
+/// Diskteki yapıların checksum alanlarında kullanılabilecek algoritmalar. Alanlar 32 bit
+/// olduğundan 64 ve 256 bitlik özetler kısaltılarak saklanır: xxHash64'ün iki yarısı
+/// XOR'lanır, SHA-256'nın ilk 4 baytı alınır.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ChecksumAlgorithm {
+    /// CRC32C (Castagnoli); varsayılan ve Superblock'ta her zaman kullanılan algoritma.
+    Crc32c = 0,
+    /// xxHash64; büyük bloklarda hızlı.
+    XxHash64 = 1,
+    /// SHA-256; kasıtlı değişikliklere karşı daha dayanıklı (ama yavaş).
+    Sha256 = 2,
+}
+
+impl ChecksumAlgorithm {
+    /// Diskteki kodu çözer; bu sürümün tanımadığı algoritma `None`.
+    pub fn from_raw(raw: u8) -> Option<Self> {
+        match raw {
+            0 => Some(ChecksumAlgorithm::Crc32c),
+            1 => Some(ChecksumAlgorithm::XxHash64),
+            2 => Some(ChecksumAlgorithm::Sha256),
+            _ => None,
+        }
+    }
+}
+
+/// Bir blok içeriğinin 32 bitlik checksum'unu hesaplayan algoritma arayüzü. Okuma ve yazma
+/// yolları, nesne türüne göre seçilen algoritmaya bu arayüz üzerinden başvurur.
+pub trait Checksummer {
+    /// `data`'nın checksum'u.
+    fn checksum(&self, data: &[u8]) -> u32;
+
+    /// `data` içinde saklanan checksum alanını sıfır kabul ederek checksum hesaplar
+    /// (bkz. `checksum_excluding`). Varsayılan uygulama veriyi kopyalayıp alanı sıfırlar.
+    fn checksum_excluding(&self, data: &[u8], field_offset: usize, field_len: usize) -> u32 {
+        let mut copy = Vec::from(data);
+        copy[field_offset..field_offset + field_len].fill(0);
+        self.checksum(&copy)
+    }
+}
+
+impl Checksummer for ChecksumAlgorithm {
+    fn checksum(&self, data: &[u8]) -> u32 {
+        match self {
+            ChecksumAlgorithm::Crc32c => checksum_data(data),
+            ChecksumAlgorithm::XxHash64 => {
+                let hash = xxhash64(data, 0);
+                (hash ^ (hash >> 32)) as u32
+            }
+            ChecksumAlgorithm::Sha256 => {
+                let digest = sha256(data);
+                u32::from_le_bytes([digest[0], digest[1], digest[2], digest[3]])
+            }
+        }
+    }
+
+    fn checksum_excluding(&self, data: &[u8], field_offset: usize, field_len: usize) -> u32 {
+        match self {
+            // CRC32C kopyalamadan, parçalar halinde hesaplanabilir
+            ChecksumAlgorithm::Crc32c => checksum_excluding(data, field_offset, field_len),
+            _ => {
+                let mut copy = Vec::from(data);
+                copy[field_offset..field_offset + field_len].fill(0);
+                self.checksum(&copy)
+            }
+        }
+    }
+}
+
+// Checksum politikasının Superblock'taki boyutu (nesne türü başına bir bayt).
+pub const CHECKSUM_POLICY_SIZE: usize = 4;
+
+/// Nesne türü başına checksum algoritması. Superblock'ta saklanır ve biçimlendirmede
+/// seçilir (bkz. `FormatOptions::checksums`); Superblock'un kendisi her zaman CRC32C'dir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChecksumPolicy {
+    /// Metadata ağacı düğümleri (dizinler, xattr'ler, alt birimler, kotalar).
+    pub metadata_tree: ChecksumAlgorithm,
+    /// Dosya veri ağaçlarının düğümleri.
+    pub data_tree: ChecksumAlgorithm,
+    /// Inode blokları.
+    pub inode: ChecksumAlgorithm,
+    /// Alan haritası kontrol noktası (grup başına boş blok sayıları).
+    pub space_map: ChecksumAlgorithm,
+}
+
+impl Default for ChecksumPolicy {
+    /// Her nesne türünde CRC32C (önceki sürümlerin biçimi).
+    fn default() -> Self {
+        ChecksumPolicy {
+            metadata_tree: ChecksumAlgorithm::Crc32c,
+            data_tree: ChecksumAlgorithm::Crc32c,
+            inode: ChecksumAlgorithm::Crc32c,
+            space_map: ChecksumAlgorithm::Crc32c,
+        }
+    }
+}
+
+impl ChecksumPolicy {
+    /// Önerilen karışım: alan haritasında CRC32C, veri ağaçlarında xxHash64, güvenlik
+    /// açısından kritik metadata'da (metadata ağacı ve inode'lar) SHA-256.
+    pub fn recommended() -> Self {
+        ChecksumPolicy {
+            metadata_tree: ChecksumAlgorithm::Sha256,
+            data_tree: ChecksumAlgorithm::XxHash64,
+            inode: ChecksumAlgorithm::Sha256,
+            space_map: ChecksumAlgorithm::Crc32c,
+        }
+    }
+
+    /// Superblock'taki disk biçimi.
+    pub fn to_raw(&self) -> [u8; CHECKSUM_POLICY_SIZE] {
+        [self.metadata_tree as u8, self.data_tree as u8, self.inode as u8, self.space_map as u8]
+    }
+
+    /// Disk biçimini çözer; tanınmayan bir algoritma varsa `None`.
+    pub fn from_raw(raw: &[u8; CHECKSUM_POLICY_SIZE]) -> Option<Self> {
+        Some(ChecksumPolicy {
+            metadata_tree: ChecksumAlgorithm::from_raw(raw[0])?,
+            data_tree: ChecksumAlgorithm::from_raw(raw[1])?,
+            inode: ChecksumAlgorithm::from_raw(raw[2])?,
+            space_map: ChecksumAlgorithm::from_raw(raw[3])?,
+        })
+    }
+}
@@ -0,0 +1,86 @@
+// src/clock.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::platform;
+use crate::sahne_syscalls::SyscallError;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+
+// --- 1. Zaman Kaynağı Trait'i ---
+
+/// Dosya sisteminin zaman damgaları (inode zamanları, commit zamanı, periyodik denetim
+/// ve scrub zamanlaması) için kullandığı zaman kaynağı.
+///
+/// Değerler yalnızca karşılaştırılır ve farkları alınır; süre sabitleri (ör.
+/// `Superblock::check_interval`) saniye birimli bir saat varsayar.
+pub trait Clock: Send + Sync {
+    /// Geçerli zaman.
+    fn now(&self) -> Result<u64, SyscallError>;
+}
+
+
+// --- 2. Zaman Kaynakları ---
+
+/// Platformun sistem saati (Sahne64'te SYSCALL_GET_SYSTEM_TIME); varsayılan kaynak.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KernelClock;
+
+impl Clock for KernelClock {
+    fn now(&self) -> Result<u64, SyscallError> {
+        platform::system_time()
+    }
+}
+
+/// Her okumada bir artan sayaç. Gerçek zaman saati (RTC) olmayan hedeflerde zaman
+/// damgaları deterministik ve kesin artan olur; değerler takvim zamanı değildir.
+///
+/// Yeniden başlatmada zamanın geri gitmemesi için sayaç birimin son commit zamanından
+/// sonra başlatılmalıdır (bkz. `EarlyFs::last_commit_time`).
+#[derive(Debug, Default)]
+pub struct MonotonicClock {
+    next: AtomicU64,
+}
+
+impl MonotonicClock {
+    /// İlk okumada `start` döndüren bir sayaç oluşturur.
+    pub fn new(start: u64) -> Self {
+        MonotonicClock { next: AtomicU64::new(start) }
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> Result<u64, SyscallError> {
+        Ok(self.next.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Zamanı çağıranın belirlediği saat; testlerde zaman damgalarını ve periyodik denetim
+/// gibi süre dolumlarını denetlemek için.
+#[derive(Debug, Default)]
+pub struct FakeClock {
+    now: AtomicU64,
+}
+
+impl FakeClock {
+    /// `now` zamanını gösteren bir saat oluşturur.
+    pub fn new(now: u64) -> Self {
+        FakeClock { now: AtomicU64::new(now) }
+    }
+
+    /// Saati `now` zamanına ayarlar.
+    pub fn set(&self, now: u64) {
+        self.now.store(now, Ordering::Relaxed);
+    }
+
+    /// Saati `delta` kadar ileri alır.
+    pub fn advance(&self, delta: u64) {
+        self.now.fetch_add(delta, Ordering::Relaxed);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Result<u64, SyscallError> {
+        Ok(self.now.load(Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,271 @@
+// src/compress.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::BLOCK_SIZE;
+use crate::zstd::{self, ZSTD_LEVEL_DEFAULT, ZSTD_LEVEL_MAX, ZSTD_LEVEL_MIN};
+
+
+// --- 1. Sabitler ---
+
+/// Birlikte sıkıştırılan en fazla ardışık dosya bloğu (sıkıştırma kümesi). Kümedeki bir
+/// bloğu okumak için kümenin tamamı çözülür; LZ4 konumları 16 bitle sınırlı olduğundan
+/// küme 64 KiB'ı aşmamalıdır.
+pub const COMPRESS_CLUSTER_BLOCKS: usize = 16;
+
+/// Bir sıkıştırma kümesinin çözülmüş boyutu (bayt).
+pub const COMPRESS_CLUSTER_BYTES: usize = COMPRESS_CLUSTER_BLOCKS * BLOCK_SIZE;
+
+const _: () = assert!(COMPRESS_CLUSTER_BYTES <= 1 << 16);
+
+/// Sıkıştırma kararı için incelenen, extent verisinin başındaki örnek uzunluğu (bayt).
+pub const COMPRESS_SAMPLE_BYTES: usize = 4096;
+
+// Bu kadar kısa örnekler sıkıştırılabilir sayılır; bayt dağılımı anlamlı değildir.
+const COMPRESS_SAMPLE_MIN: usize = 64;
+
+// Rényi (2. derece) entropi eşiği: bayt çakışma olasılığı 1/181'den (≈ 2^-7.5) küçükse
+// örnek bayt başına ~7,5 bitten fazla bilgi taşır ve sıkıştırılamaz sayılır.
+const INCOMPRESSIBLE_COLLISION_DIVISOR: u64 = 181;
+
+// LZ4 blok biçimi: en kısa eşleşme, son dizideki zorunlu değişmez bayt sayısı ve son
+// eşleşmenin girdinin sonuna en fazla yaklaşabileceği uzaklık.
+const LZ4_MIN_MATCH: usize = 4;
+const LZ4_LAST_LITERALS: usize = 5;
+const LZ4_MF_LIMIT: usize = 12;
+
+// Eşleşme aramasındaki karma tablosunun boyutu (2^12 girdi).
+const LZ4_HASH_BITS: u32 = 12;
+
+
+// --- 2. Sıkıştırılabilirlik Tahmini ---
+
+/// Extent verisinin başından alınan örneğe bakarak sıkıştırmanın denenip denenmeyeceğine
+/// karar verir. Zaten sıkıştırılmış veya şifrelenmiş veri (medya dosyaları, arşivler)
+/// düzgün bayt dağılımı gösterir; bunlar için sıkıştırıcı hiç çalıştırılmaz.
+///
+/// Entropi, bayt histogramının kareleri toplamıyla (çakışma olasılığı) tahmin edilir;
+/// logaritma ve kayan nokta gerekmez. Yalnızca `COMPRESS_SAMPLE_BYTES` kadarı incelenir.
+pub fn should_compress(data: &[u8]) -> bool {
+    let sample = &data[..data.len().min(COMPRESS_SAMPLE_BYTES)];
+    if sample.len() < COMPRESS_SAMPLE_MIN {
+        return true;
+    }
+
+    let mut histogram = [0u32; 256];
+    for &byte in sample {
+        histogram[byte as usize] += 1;
+    }
+    let collisions: u64 = histogram.iter().map(|&count| count as u64 * count as u64).sum();
+    let n = sample.len() as u64;
+
+    // Σ(c²) / n² ≥ 1 / bölen ise dağılım yeterince eğri; sıkıştırma denenir
+    collisions * INCOMPRESSIBLE_COLLISION_DIVISOR >= n * n
+}
+
+
+// --- 3. LZ4 Blok Biçimi ---
+
+/// `input`'u LZ4 blok biçiminde `output`'a sıkıştırır (çerçeve başlığı olmadan).
+///
+/// Açgözlü tek geçişli eşleştirici kullanılır; `input` en fazla `COMPRESS_CLUSTER_BYTES`
+/// olmalıdır.
+///
+/// # Döndürür
+/// Sıkıştırılmış uzunluk; sonuç `output`'a sığmazsa `None` (veri olduğu gibi saklanmalı).
+pub fn lz4_compress(input: &[u8], output: &mut [u8]) -> Option<usize> {
+    if input.len() > COMPRESS_CLUSTER_BYTES {
+        return None;
+    }
+    let mut table = [0u16; 1 << LZ4_HASH_BITS];
+    let mut out = 0;
+    let mut anchor = 0;
+    let mut ip = 0;
+
+    if input.len() > LZ4_MF_LIMIT {
+        let match_start_limit = input.len() - LZ4_MF_LIMIT;
+        let match_end_limit = input.len() - LZ4_LAST_LITERALS;
+        while ip < match_start_limit {
+            let sequence = read_u32(input, ip);
+            let slot = lz4_hash(sequence);
+            let candidate = table[slot] as usize;
+            table[slot] = ip as u16;
+
+            if candidate >= ip || read_u32(input, candidate) != sequence {
+                ip += 1;
+                continue;
+            }
+            let mut len = LZ4_MIN_MATCH;
+            while ip + len < match_end_limit && input[candidate + len] == input[ip + len] {
+                len += 1;
+            }
+            out = lz4_emit(output, out, &input[anchor..ip], Some(((ip - candidate) as u16, len)))?;
+            ip += len;
+            anchor = ip;
+        }
+    }
+
+    lz4_emit(output, out, &input[anchor..], None)
+}
+
+/// LZ4 blok biçimindeki `input`'u `output`'a çözer.
+///
+/// # Döndürür
+/// Çözülen uzunluk; girdi bozuksa veya `output`'a sığmıyorsa `None`.
+pub fn lz4_decompress(input: &[u8], output: &mut [u8]) -> Option<usize> {
+    let mut ip = 0;
+    let mut op = 0;
+    loop {
+        let token = *input.get(ip)?;
+        ip += 1;
+
+        // Değişmezler
+        let literals = lz4_read_length(input, &mut ip, (token >> 4) as usize)?;
+        let source = input.get(ip..ip.checked_add(literals)?)?;
+        output.get_mut(op..op + literals)?.copy_from_slice(source);
+        ip += literals;
+        op += literals;
+        if ip == input.len() {
+            return Some(op); // Son dizi yalnızca değişmezlerden oluşur
+        }
+
+        // Eşleşme: çözülmüş veride geriye dönük kopya (kaynakla hedef çakışabilir)
+        let offset = u16::from_le_bytes([*input.get(ip)?, *input.get(ip + 1)?]) as usize;
+        ip += 2;
+        let len = lz4_read_length(input, &mut ip, (token & 0x0F) as usize)? + LZ4_MIN_MATCH;
+        if offset == 0 || offset > op || op + len > output.len() {
+            return None;
+        }
+        for i in op..op + len {
+            output[i] = output[i - offset];
+        }
+        op += len;
+    }
+}
+
+/// Bir diziyi (değişmezler ve varsa eşleşme) `output`'un `out` konumuna yazar.
+fn lz4_emit(output: &mut [u8], mut out: usize, literals: &[u8], matched: Option<(u16, usize)>) -> Option<usize> {
+    let match_nibble = matched.map_or(0, |(_, len)| (len - LZ4_MIN_MATCH).min(15));
+    let token = ((literals.len().min(15) as u8) << 4) | match_nibble as u8;
+    *output.get_mut(out)? = token;
+    out += 1;
+
+    out = lz4_write_length(output, out, literals.len())?;
+    output.get_mut(out..out + literals.len())?.copy_from_slice(literals);
+    out += literals.len();
+
+    if let Some((offset, len)) = matched {
+        output.get_mut(out..out + 2)?.copy_from_slice(&offset.to_le_bytes());
+        out = lz4_write_length(output, out + 2, len - LZ4_MIN_MATCH)?;
+    }
+    Some(out)
+}
+
+/// Jetondaki 4 bitlik alana sığmayan uzunluğun (15 ve üstü) devam baytlarını yazar.
+fn lz4_write_length(output: &mut [u8], mut out: usize, len: usize) -> Option<usize> {
+    if len < 15 {
+        return Some(out);
+    }
+    let mut rest = len - 15;
+    while rest >= 255 {
+        *output.get_mut(out)? = 255;
+        out += 1;
+        rest -= 255;
+    }
+    *output.get_mut(out)? = rest as u8;
+    Some(out + 1)
+}
+
+/// Jetondaki 4 bitlik uzunluğu, 15 ise devam baytlarıyla tamamlar.
+fn lz4_read_length(input: &[u8], ip: &mut usize, nibble: usize) -> Option<usize> {
+    let mut len = nibble;
+    if nibble == 15 {
+        loop {
+            let byte = *input.get(*ip)?;
+            *ip += 1;
+            len = len.checked_add(byte as usize)?;
+            if byte != 255 {
+                break;
+            }
+        }
+    }
+    Some(len)
+}
+
+
+// --- 4. Algoritma Seçimi ---
+
+/// Geri yazılan verinin sıkıştırma algoritması; dosya başına (`Inode::compression`) veya
+/// alt birim özniteliği olarak seçilir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Hızlı LZ4 blok biçimi (varsayılan).
+    #[default]
+    Lz4,
+    /// Verilen düzeyde zstd: daha fazla CPU karşılığında daha yüksek oran; arşiv birimleri
+    /// için uygundur.
+    Zstd(u8),
+}
+
+impl Compression {
+    /// Disk formatı: 0 LZ4, `ZSTD_LEVEL_MIN..=ZSTD_LEVEL_MAX` zstd düzeyi.
+    pub fn to_raw(&self) -> u8 {
+        match self {
+            Compression::Lz4 => 0,
+            Compression::Zstd(level) => (*level).clamp(ZSTD_LEVEL_MIN, ZSTD_LEVEL_MAX),
+        }
+    }
+
+    /// Disk formatından okur; aralık dışı düzeyler en yüksek düzeye indirilir.
+    pub fn from_raw(raw: u8) -> Self {
+        match raw {
+            0 => Compression::Lz4,
+            level => Compression::Zstd(level.min(ZSTD_LEVEL_MAX)),
+        }
+    }
+
+    /// Özellik değerini çözer: `lz4`, `zstd` (varsayılan düzey) veya `zstd:<düzey>`.
+    /// Tanınmayan değerlerde veya aralık dışı düzeylerde `None`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.split_once(':') {
+            None if value == "lz4" => Some(Compression::Lz4),
+            None if value == "zstd" => Some(Compression::Zstd(ZSTD_LEVEL_DEFAULT)),
+            Some(("zstd", level)) => {
+                let level: u8 = level.parse().ok()?;
+                (ZSTD_LEVEL_MIN..=ZSTD_LEVEL_MAX).contains(&level).then_some(Compression::Zstd(level))
+            }
+            _ => None,
+        }
+    }
+
+    /// `input`'u bu algoritmayla `output`'a sıkıştırır.
+    ///
+    /// # Döndürür
+    /// Sıkıştırılmış uzunluk; sonuç `output`'a sığmazsa `None`.
+    pub fn compress(&self, input: &[u8], output: &mut [u8]) -> Option<usize> {
+        match self {
+            Compression::Lz4 => lz4_compress(input, output),
+            Compression::Zstd(level) => zstd::zstd_compress(input, output, *level),
+        }
+    }
+
+    /// Bu algoritmayla sıkıştırılmış `input`'u `output`'a çözer.
+    ///
+    /// # Döndürür
+    /// Çözülen uzunluk; girdi bozuksa `None`.
+    pub fn decompress(&self, input: &[u8], output: &mut [u8]) -> Option<usize> {
+        match self {
+            Compression::Lz4 => lz4_decompress(input, output),
+            Compression::Zstd(_) => zstd::zstd_decompress(input, output),
+        }
+    }
+}
+
+
+fn read_u32(data: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+}
+
+fn lz4_hash(sequence: u32) -> usize {
+    (sequence.wrapping_mul(2_654_435_761) >> (32 - LZ4_HASH_BITS)) as usize
+}
@@ -0,0 +1,659 @@
+// src/crypt.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE, BlockDeviceError};
+use crate::sahne_syscalls::SyscallError;
+use crate::seal::sha256;
+use core::fmt::Debug;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+
+// --- 1. Şifreleme Hata Türü ---
+
+/// Şifreli aygıt işlemleri sırasında ortaya çıkabilecek hatalar.
+#[derive(Debug)]
+pub enum CryptError<D: BlockDevice> {
+    /// Alttaki aygıtta I/O hatası oluştu.
+    IoError(D::Error),
+    /// Blok 0'da şifreleme başlığı yok (aygıt şifreli biçimlendirilmemiş).
+    NotEncrypted,
+    /// Başlık bu sürümün tanımadığı bir sürüm, şifre veya anahtar türetme yöntemi bildiriyor.
+    UnsupportedHeader,
+    /// Parola anahtar yuvasını açmıyor.
+    WrongPassphrase,
+    /// Aygıt başlık ve en az bir veri bloğu için çok küçük.
+    DeviceTooSmall,
+    /// Dahili sistem çağrısı hatası.
+    Syscall(SyscallError),
+}
+
+impl<D: BlockDevice + Debug> BlockDeviceError for CryptError<D> {}
+
+impl<D: BlockDevice> From<SyscallError> for CryptError<D> {
+    fn from(e: SyscallError) -> Self {
+        CryptError::Syscall(e)
+    }
+}
+
+
+// --- 2. AES-256 Blok Şifresi ---
+
+const AES_BLOCK: usize = 16;
+const AES_ROUNDS: usize = 14;
+
+/// GF(2^8) üzerinde `x` ile çarpım (AES indirgeme polinomu x^8 + x^4 + x^3 + x + 1).
+const fn xtime(a: u8) -> u8 {
+    (a << 1) ^ (((a >> 7) & 1) * 0x1B)
+}
+
+const fn gmul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+/// S-kutusunu derleme zamanında üretir: 3 ve tersi ile GF(2^8)'in tüm elemanlarını gezip
+/// her elemanın tersine afin dönüşümü uygular.
+const fn build_sbox() -> ([u8; 256], [u8; 256]) {
+    let mut sbox = [0u8; 256];
+    let mut inverse = [0u8; 256];
+    let mut p: u8 = 1;
+    let mut q: u8 = 1;
+    loop {
+        // p'yi 3 ile çarp
+        p = p ^ (p << 1) ^ (((p >> 7) & 1) * 0x1B);
+        // q'yu 3'e böl (0xF6 ile çarp)
+        q ^= q << 1;
+        q ^= q << 2;
+        q ^= q << 4;
+        if q & 0x80 != 0 {
+            q ^= 0x09;
+        }
+        let x = q ^ q.rotate_left(1) ^ q.rotate_left(2) ^ q.rotate_left(3) ^ q.rotate_left(4) ^ 0x63;
+        sbox[p as usize] = x;
+        inverse[x as usize] = p;
+        if p == 1 {
+            break;
+        }
+    }
+    sbox[0] = 0x63;
+    inverse[0x63] = 0;
+    (sbox, inverse)
+}
+
+const SBOXES: ([u8; 256], [u8; 256]) = build_sbox();
+const SBOX: [u8; 256] = SBOXES.0;
+const INV_SBOX: [u8; 256] = SBOXES.1;
+
+/// Genişletilmiş anahtarıyla birlikte AES-256.
+///
+/// Tablo tabanlı bayt uygulamasıdır; S-kutusu erişimleri veri bağımlı olduğundan aynı
+/// çekirdekteki güvenilmeyen kodun önbellek zamanlamasını gözleyebildiği ortamlar için
+/// uygun değildir.
+struct Aes256 {
+    round_keys: [[u8; AES_BLOCK]; AES_ROUNDS + 1],
+}
+
+impl Aes256 {
+    fn new(key: &[u8; 32]) -> Self {
+        let mut words = [[0u8; 4]; 4 * (AES_ROUNDS + 1)];
+        for (i, word) in words.iter_mut().take(8).enumerate() {
+            word.copy_from_slice(&key[i * 4..i * 4 + 4]);
+        }
+        let mut rcon = 1u8;
+        for i in 8..words.len() {
+            let mut temp = words[i - 1];
+            if i % 8 == 0 {
+                temp = [SBOX[temp[1] as usize] ^ rcon, SBOX[temp[2] as usize], SBOX[temp[3] as usize], SBOX[temp[0] as usize]];
+                rcon = xtime(rcon);
+            } else if i % 8 == 4 {
+                temp = temp.map(|b| SBOX[b as usize]);
+            }
+            for j in 0..4 {
+                words[i][j] = words[i - 8][j] ^ temp[j];
+            }
+        }
+
+        let mut round_keys = [[0u8; AES_BLOCK]; AES_ROUNDS + 1];
+        for (round, round_key) in round_keys.iter_mut().enumerate() {
+            for column in 0..4 {
+                round_key[column * 4..column * 4 + 4].copy_from_slice(&words[round * 4 + column]);
+            }
+        }
+        wipe(words.as_flattened_mut());
+        Aes256 { round_keys }
+    }
+
+    fn encrypt(&self, block: &mut [u8; AES_BLOCK]) {
+        xor_into(block, &self.round_keys[0]);
+        for round in 1..=AES_ROUNDS {
+            for b in block.iter_mut() {
+                *b = SBOX[*b as usize];
+            }
+            shift_rows(block);
+            if round != AES_ROUNDS {
+                mix_columns(block);
+            }
+            xor_into(block, &self.round_keys[round]);
+        }
+    }
+
+    fn decrypt(&self, block: &mut [u8; AES_BLOCK]) {
+        xor_into(block, &self.round_keys[AES_ROUNDS]);
+        for round in (0..AES_ROUNDS).rev() {
+            inv_shift_rows(block);
+            for b in block.iter_mut() {
+                *b = INV_SBOX[*b as usize];
+            }
+            xor_into(block, &self.round_keys[round]);
+            if round != 0 {
+                inv_mix_columns(block);
+            }
+        }
+    }
+}
+
+impl Drop for Aes256 {
+    fn drop(&mut self) {
+        wipe(self.round_keys.as_flattened_mut());
+    }
+}
+
+// Durum sütun düzenindedir: `state[column * 4 + row]`.
+fn shift_rows(state: &mut [u8; AES_BLOCK]) {
+    let old = *state;
+    for column in 0..4 {
+        for row in 1..4 {
+            state[column * 4 + row] = old[((column + row) % 4) * 4 + row];
+        }
+    }
+}
+
+fn inv_shift_rows(state: &mut [u8; AES_BLOCK]) {
+    let old = *state;
+    for column in 0..4 {
+        for row in 1..4 {
+            state[column * 4 + row] = old[((column + 4 - row) % 4) * 4 + row];
+        }
+    }
+}
+
+fn mix_columns(state: &mut [u8; AES_BLOCK]) {
+    for column in state.chunks_exact_mut(4) {
+        let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+        let all = a0 ^ a1 ^ a2 ^ a3;
+        column[0] = a0 ^ all ^ xtime(a0 ^ a1);
+        column[1] = a1 ^ all ^ xtime(a1 ^ a2);
+        column[2] = a2 ^ all ^ xtime(a2 ^ a3);
+        column[3] = a3 ^ all ^ xtime(a3 ^ a0);
+    }
+}
+
+fn inv_mix_columns(state: &mut [u8; AES_BLOCK]) {
+    for column in state.chunks_exact_mut(4) {
+        let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+        column[0] = gmul(a0, 14) ^ gmul(a1, 11) ^ gmul(a2, 13) ^ gmul(a3, 9);
+        column[1] = gmul(a0, 9) ^ gmul(a1, 14) ^ gmul(a2, 11) ^ gmul(a3, 13);
+        column[2] = gmul(a0, 13) ^ gmul(a1, 9) ^ gmul(a2, 14) ^ gmul(a3, 11);
+        column[3] = gmul(a0, 11) ^ gmul(a1, 13) ^ gmul(a2, 9) ^ gmul(a3, 14);
+    }
+}
+
+fn xor_into(target: &mut [u8], source: &[u8]) {
+    for (t, s) in target.iter_mut().zip(source) {
+        *t ^= *s;
+    }
+}
+
+/// Anahtar malzemesini siler. Uçucu yazma, derleyicinin "kullanılmayan" son yazmayı
+/// elemesini önler.
+fn wipe(bytes: &mut [u8]) {
+    for b in bytes.iter_mut() {
+        // SAFETY: `b` geçerli, hizalı ve yazılabilir bir bayta işaret eder.
+        unsafe { core::ptr::write_volatile(b, 0) };
+    }
+}
+
+
+// --- 3. XTS Kipi ---
+
+/// XTS anahtarının uzunluğu: veri anahtarı (K1) ve ayar (tweak) anahtarı (K2), 32'şer bayt.
+pub const CRYPT_KEY_SIZE: usize = 64;
+
+/// AES-256-XTS (IEEE P1619). Her blok ayrı bir veri birimidir; 128 bitlik ayar değeri
+/// birimin numarasıdır (küçük uçlu). 4096 bayt AES bloğunun katı olduğundan şifre metni
+/// çalma gerekmez.
+struct XtsCipher {
+    data: Aes256,
+    tweak: Aes256,
+}
+
+impl XtsCipher {
+    fn new(key: &[u8; CRYPT_KEY_SIZE]) -> Self {
+        let mut halves = [[0u8; 32]; 2];
+        for (half, chunk) in halves.iter_mut().zip(key.as_chunks::<32>().0) {
+            *half = *chunk;
+        }
+        let [k1, k2] = &halves;
+        let cipher = XtsCipher { data: Aes256::new(k1), tweak: Aes256::new(k2) };
+        wipe(halves.as_flattened_mut());
+        cipher
+    }
+
+    fn encrypt(&self, unit: u128, buffer: &mut [u8]) {
+        self.process(unit, buffer, |aes, block| aes.encrypt(block));
+    }
+
+    fn decrypt(&self, unit: u128, buffer: &mut [u8]) {
+        self.process(unit, buffer, |aes, block| aes.decrypt(block));
+    }
+
+    fn process(&self, unit: u128, buffer: &mut [u8], cipher: impl Fn(&Aes256, &mut [u8; AES_BLOCK])) {
+        let mut tweak = unit.to_le_bytes();
+        self.tweak.encrypt(&mut tweak);
+
+        for block in buffer.as_chunks_mut::<AES_BLOCK>().0 {
+            xor_into(block, &tweak);
+            cipher(&self.data, block);
+            xor_into(block, &tweak);
+
+            // Ayarı GF(2^128)'de α ile çarp (küçük uçlu bit sırası)
+            let carry = tweak[AES_BLOCK - 1] >> 7;
+            for i in (1..AES_BLOCK).rev() {
+                tweak[i] = (tweak[i] << 1) | (tweak[i - 1] >> 7);
+            }
+            tweak[0] = (tweak[0] << 1) ^ (carry * 0x87);
+        }
+    }
+}
+
+
+// --- 4. Anahtar Türetme (PBKDF2-HMAC-SHA256) ---
+
+const SHA256_BLOCK: usize = 64;
+const SHA256_SIZE: usize = 32;
+
+/// Dolgusu önceden hazırlanmış HMAC-SHA256 anahtarı. PBKDF2 aynı anahtarla on binlerce
+/// kez çağırdığından iç ve dış dolgu bir kez hesaplanır.
+struct HmacKey {
+    inner: [u8; SHA256_BLOCK],
+    outer: [u8; SHA256_BLOCK],
+}
+
+impl HmacKey {
+    fn new(key: &[u8]) -> Self {
+        let mut block = [0u8; SHA256_BLOCK];
+        if key.len() > SHA256_BLOCK {
+            block[..SHA256_SIZE].copy_from_slice(&sha256(key));
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let hmac = HmacKey { inner: block.map(|b| b ^ 0x36), outer: block.map(|b| b ^ 0x5C) };
+        wipe(&mut block);
+        hmac
+    }
+
+    /// En fazla bir SHA-256 bloğu uzunluğundaki mesajın MAC'i (yığında, ayırma yapmadan).
+    fn mac(&self, message: &[u8]) -> [u8; SHA256_SIZE] {
+        let mut buffer = [0u8; SHA256_BLOCK * 2];
+        buffer[..SHA256_BLOCK].copy_from_slice(&self.inner);
+        buffer[SHA256_BLOCK..SHA256_BLOCK + message.len()].copy_from_slice(message);
+        let inner = sha256(&buffer[..SHA256_BLOCK + message.len()]);
+
+        buffer[..SHA256_BLOCK].copy_from_slice(&self.outer);
+        buffer[SHA256_BLOCK..SHA256_BLOCK + SHA256_SIZE].copy_from_slice(&inner);
+        let mac = sha256(&buffer[..SHA256_BLOCK + SHA256_SIZE]);
+        wipe(&mut buffer);
+        mac
+    }
+}
+
+impl Drop for HmacKey {
+    fn drop(&mut self) {
+        wipe(&mut self.inner);
+        wipe(&mut self.outer);
+    }
+}
+
+/// RFC 8018 PBKDF2, PRF olarak HMAC-SHA256 ile. `salt` en fazla 59 bayt olabilir
+/// (ilk tur mesajı tek SHA-256 bloğuna sığmalı).
+fn pbkdf2_sha256(passphrase: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+    let prf = HmacKey::new(passphrase);
+    let mut first = [0u8; SHA256_BLOCK];
+    first[..salt.len()].copy_from_slice(salt);
+
+    for (index, chunk) in output.chunks_mut(SHA256_SIZE).enumerate() {
+        first[salt.len()..salt.len() + 4].copy_from_slice(&(index as u32 + 1).to_be_bytes());
+        let mut u = prf.mac(&first[..salt.len() + 4]);
+        let mut t = u;
+        for _ in 1..iterations {
+            u = prf.mac(&u);
+            xor_into(&mut t, &u);
+        }
+        chunk.copy_from_slice(&t[..chunk.len()]);
+        wipe(&mut u);
+        wipe(&mut t);
+    }
+}
+
+
+// --- 5. Şifreleme Başlığı ve Anahtar Yuvası ---
+
+/// Blok 0'daki şifreleme başlığının sihirli değeri.
+pub const CRYPT_MAGIC: [u8; 8] = *b"SADAKCRY";
+/// Başlık biçiminin sürümü.
+pub const CRYPT_VERSION: u32 = 1;
+/// Şifre kimliği: AES-256-XTS.
+pub const CRYPT_CIPHER_AES256_XTS: u32 = 1;
+/// Anahtar türetme kimliği: PBKDF2-HMAC-SHA256.
+pub const CRYPT_KDF_PBKDF2_SHA256: u32 = 1;
+/// Yeni biçimlendirilen birimlerde PBKDF2 tur sayısı. Başlıkta saklandığından ileride
+/// artırılması eski birimleri bozmaz.
+pub const CRYPT_KDF_ITERATIONS: u32 = 100_000;
+/// Anahtar türetme tuzunun uzunluğu.
+pub const CRYPT_SALT_SIZE: usize = 32;
+/// `format`'ın beklediği rastgele bayt sayısı: birim anahtarı ve tuz.
+pub const CRYPT_RANDOM_SIZE: usize = CRYPT_KEY_SIZE + CRYPT_SALT_SIZE;
+
+// Başlık alanlarının blok 0 içindeki konumları
+const HDR_MAGIC: usize = 0;
+const HDR_VERSION: usize = 8;
+const HDR_CIPHER: usize = 12;
+const HDR_KDF: usize = 16;
+const HDR_ITERATIONS: usize = 20;
+const HDR_SALT: usize = 24;
+const HDR_KEY_SLOT: usize = HDR_SALT + CRYPT_SALT_SIZE;
+const HDR_KEY_DIGEST: usize = HDR_KEY_SLOT + CRYPT_KEY_SIZE;
+const HDR_END: usize = HDR_KEY_DIGEST + SHA256_SIZE;
+
+// Anahtar yuvası veri bloklarıyla karışmaması için ayrı bir ayar değeriyle sarılır
+const KEY_SLOT_UNIT: u128 = u64::MAX as u128;
+
+// Bloğun dışında kalan alan 0 okunur; 0 başlıktaki hiçbir alan için geçerli değildir.
+fn read_u32(block: &[u8], offset: usize) -> u32 {
+    block.get(offset..).and_then(<[u8]>::first_chunk).map_or(0, |bytes| u32::from_le_bytes(*bytes))
+}
+
+/// Birim anahtarının özeti; yuvadan çözülen anahtarın (yani parolanın) doğruluğunu
+/// anahtarı açığa çıkarmadan denetler.
+fn key_digest(volume_key: &[u8; CRYPT_KEY_SIZE], salt: &[u8]) -> [u8; SHA256_SIZE] {
+    let mut message = [0u8; CRYPT_MAGIC.len() + CRYPT_SALT_SIZE];
+    message[..CRYPT_MAGIC.len()].copy_from_slice(&CRYPT_MAGIC);
+    message[CRYPT_MAGIC.len()..].copy_from_slice(salt);
+    HmacKey::new(volume_key).mac(&message)
+}
+
+/// Paroladan ve tuzdan anahtar yuvasını saran anahtarı (KEK) türetir.
+fn slot_cipher(passphrase: &[u8], salt: &[u8], iterations: u32) -> XtsCipher {
+    let mut kek = [0u8; CRYPT_KEY_SIZE];
+    pbkdf2_sha256(passphrase, salt, iterations, &mut kek);
+    let cipher = XtsCipher::new(&kek);
+    wipe(&mut kek);
+    cipher
+}
+
+/// Sabit zamanlı karşılaştırma; özet denetimi parola denemesi sırasında bilgi sızdırmaz.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+
+// --- 6. Şifreli Aygıt Yapısı ---
+
+/// Alttaki aygıtın tüm bloklarını AES-256-XTS ile şifreleyen BlockDevice uygulaması.
+///
+/// Aygıtın blok 0'ı şifreleme başlığına ayrılır: şifre ve anahtar türetme parametreleri,
+/// paroladan türetilen anahtarla sarılmış birim anahtarı (anahtar yuvası) ve parolayı
+/// doğrulayan özet burada tutulur. Dosya sistemi kalan blokları 0'dan başlayarak görür;
+/// mantıksal `n` numaralı blok aygıtın `n + 1` numaralı bloğunda, ayar değeri `n` ile
+/// şifreli durur. Parola değişimi yalnızca anahtar yuvasını yeniden sarar; veri
+/// blokları birim anahtarıyla şifreli kalır.
+///
+/// XTS bütünlük sağlamaz: şifreli blokta yapılan değişiklik okunurken rastgele veriye
+/// dönüşür ve ancak metadata checksum'larıyla fark edilir.
+pub struct CryptDevice<D: BlockDevice> {
+    /// Şifreli blokların yazıldığı aygıt.
+    inner: D,
+    /// Birim anahtarıyla kurulmuş veri şifresi.
+    cipher: XtsCipher,
+    /// Dosya sistemine sunulan blok sayısı (başlık hariç).
+    total_blocks: BlockId,
+}
+
+impl<D: BlockDevice> CryptDevice<D> {
+    /// Aygıtı şifreli olarak biçimlendirir: `random`'ın ilk 64 baytı birim anahtarı, kalan
+    /// 32 baytı tuz olur; anahtar yuvası `passphrase` ile sarılıp başlık blok 0'a yazılır.
+    ///
+    /// `random` donanım rastgele sayı üretecinden veya eşdeğer bir kaynaktan gelmelidir;
+    /// Sahne64'te henüz rastgelelik sistem çağrısı olmadığından çağırana bırakılır.
+    /// Aygıttaki mevcut veri okunamaz hale gelir; biçimlendirmeden sonra üzerine yeni
+    /// bir SADAK birimi oluşturulmalıdır.
+    pub fn format(inner: D, passphrase: &[u8], random: &[u8; CRYPT_RANDOM_SIZE]) -> Result<Self, CryptError<D>> {
+        if inner.total_blocks() < 2 {
+            return Err(CryptError::DeviceTooSmall);
+        }
+        let Some((volume_key, salt)) = random.split_first_chunk::<CRYPT_KEY_SIZE>() else {
+            return Err(CryptError::Syscall(SyscallError::EINVAL));
+        };
+        let mut volume_key = *volume_key;
+
+        let mut header = vec![0u8; BLOCK_SIZE];
+        header[HDR_MAGIC..HDR_MAGIC + 8].copy_from_slice(&CRYPT_MAGIC);
+        header[HDR_VERSION..HDR_VERSION + 4].copy_from_slice(&CRYPT_VERSION.to_le_bytes());
+        header[HDR_CIPHER..HDR_CIPHER + 4].copy_from_slice(&CRYPT_CIPHER_AES256_XTS.to_le_bytes());
+        header[HDR_KDF..HDR_KDF + 4].copy_from_slice(&CRYPT_KDF_PBKDF2_SHA256.to_le_bytes());
+        header[HDR_ITERATIONS..HDR_ITERATIONS + 4].copy_from_slice(&CRYPT_KDF_ITERATIONS.to_le_bytes());
+        Self::seal_key_slot(&mut header, &volume_key, passphrase, salt);
+
+        inner.write_block(0, &header).map_err(CryptError::IoError)?;
+        inner.flush().map_err(CryptError::IoError)?;
+
+        let device = Self::with_key(inner, &volume_key);
+        wipe(&mut volume_key);
+        Ok(device)
+    }
+
+    /// Şifreli aygıtı açar: başlığı okur, anahtar yuvasını `passphrase` ile çözer ve
+    /// birim anahtarını özetle doğrular.
+    pub fn open(inner: D, passphrase: &[u8]) -> Result<Self, CryptError<D>> {
+        if inner.total_blocks() < 2 {
+            return Err(CryptError::DeviceTooSmall);
+        }
+        let mut header = vec![0u8; BLOCK_SIZE];
+        inner.read_block(0, &mut header).map_err(CryptError::IoError)?;
+        let mut volume_key = Self::unlock(&header, passphrase)?;
+
+        let device = Self::with_key(inner, &volume_key);
+        wipe(&mut volume_key);
+        Ok(device)
+    }
+
+    /// Parolayı değiştirir: anahtar yuvası eski parolayla çözülür, yeni parola ve yeni tuzla
+    /// yeniden sarılıp başlık yerinde güncellenir. Birim anahtarı ve veri blokları değişmez.
+    /// `salt` da `format`'taki gibi rastgele olmalıdır.
+    pub fn change_passphrase(&self, old: &[u8], new: &[u8], salt: &[u8; CRYPT_SALT_SIZE]) -> Result<(), CryptError<D>> {
+        let mut header = vec![0u8; BLOCK_SIZE];
+        self.inner.read_block(0, &mut header).map_err(CryptError::IoError)?;
+        let mut volume_key = Self::unlock(&header, old)?;
+
+        header[HDR_ITERATIONS..HDR_ITERATIONS + 4].copy_from_slice(&CRYPT_KDF_ITERATIONS.to_le_bytes());
+        Self::seal_key_slot(&mut header, &volume_key, new, salt);
+        wipe(&mut volume_key);
+
+        self.inner.write_block(0, &header).map_err(CryptError::IoError)?;
+        self.inner.flush().map_err(CryptError::IoError)
+    }
+
+    /// Başlığı doğrular ve anahtar yuvasından birim anahtarını çözer.
+    fn unlock(header: &[u8], passphrase: &[u8]) -> Result<[u8; CRYPT_KEY_SIZE], CryptError<D>> {
+        if header[HDR_MAGIC..HDR_MAGIC + 8] != CRYPT_MAGIC {
+            return Err(CryptError::NotEncrypted);
+        }
+        let iterations = read_u32(header, HDR_ITERATIONS);
+        if read_u32(header, HDR_VERSION) != CRYPT_VERSION
+            || read_u32(header, HDR_CIPHER) != CRYPT_CIPHER_AES256_XTS
+            || read_u32(header, HDR_KDF) != CRYPT_KDF_PBKDF2_SHA256
+            || iterations == 0
+        {
+            return Err(CryptError::UnsupportedHeader);
+        }
+
+        let salt = &header[HDR_SALT..HDR_KEY_SLOT];
+        let mut volume_key: [u8; CRYPT_KEY_SIZE] =
+            *header.get(HDR_KEY_SLOT..).and_then(<[u8]>::first_chunk).ok_or(CryptError::UnsupportedHeader)?;
+        slot_cipher(passphrase, salt, iterations).decrypt(KEY_SLOT_UNIT, &mut volume_key);
+        if !constant_time_eq(&key_digest(&volume_key, salt), &header[HDR_KEY_DIGEST..HDR_END]) {
+            wipe(&mut volume_key);
+            return Err(CryptError::WrongPassphrase);
+        }
+        Ok(volume_key)
+    }
+
+    /// Tuzu, `passphrase` ile sarılmış birim anahtarını ve özetini başlığa yazar. Tur sayısı
+    /// başlıktan okunur.
+    fn seal_key_slot(header: &mut [u8], volume_key: &[u8; CRYPT_KEY_SIZE], passphrase: &[u8], salt: &[u8]) {
+        let iterations = read_u32(header, HDR_ITERATIONS);
+        header[HDR_SALT..HDR_KEY_SLOT].copy_from_slice(salt);
+        header[HDR_KEY_SLOT..HDR_KEY_DIGEST].copy_from_slice(volume_key);
+        slot_cipher(passphrase, salt, iterations).encrypt(KEY_SLOT_UNIT, &mut header[HDR_KEY_SLOT..HDR_KEY_DIGEST]);
+        header[HDR_KEY_DIGEST..HDR_END].copy_from_slice(&key_digest(volume_key, salt));
+    }
+
+    fn with_key(inner: D, volume_key: &[u8; CRYPT_KEY_SIZE]) -> Self {
+        let total_blocks = inner.total_blocks() - 1;
+        CryptDevice { inner, cipher: XtsCipher::new(volume_key), total_blocks }
+    }
+
+    /// Şifreli aygıtı bırakıp alttaki aygıtı döndürür; anahtar bellekten silinir.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+
+// --- 7. BlockDevice Trait'inin Uygulanması ---
+
+impl<D: BlockDevice + Debug + Sync + Send + 'static> BlockDevice for CryptDevice<D> {
+    type Error = CryptError<D>;
+
+    fn read_block(&self, id: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.inner.read_block(id + 1, buffer).map_err(CryptError::IoError)?;
+        self.cipher.decrypt(id as u128, buffer);
+        Ok(())
+    }
+
+    fn write_block(&self, id: BlockId, data: &[u8]) -> Result<(), Self::Error> {
+        let mut encrypted = data.to_vec();
+        self.cipher.encrypt(id as u128, &mut encrypted);
+        self.inner.write_block(id + 1, &encrypted).map_err(CryptError::IoError)
+    }
+
+    fn total_blocks(&self) -> BlockId {
+        self.total_blocks
+    }
+
+    fn read_blocks(&self, start: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.inner.read_blocks(start + 1, buffer).map_err(CryptError::IoError)?;
+        for (offset, block) in buffer.chunks_mut(BLOCK_SIZE).enumerate() {
+            self.cipher.decrypt((start + offset as BlockId) as u128, block);
+        }
+        Ok(())
+    }
+
+    fn write_batch(&self, blocks: &[(BlockId, &[u8])]) -> Result<(), Self::Error> {
+        let encrypted: Vec<Vec<u8>> = blocks
+            .iter()
+            .map(|&(id, data)| {
+                let mut block = data.to_vec();
+                self.cipher.encrypt(id as u128, &mut block);
+                block
+            })
+            .collect();
+        let batch: Vec<(BlockId, &[u8])> =
+            blocks.iter().zip(&encrypted).map(|(&(id, _), block)| (id + 1, block.as_slice())).collect();
+        self.inner.write_batch(&batch).map_err(CryptError::IoError)
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.inner.flush().map_err(CryptError::IoError)
+    }
+
+    fn copies(&self) -> usize {
+        self.inner.copies()
+    }
+
+    fn read_copy(&self, id: BlockId, copy: usize, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.inner.read_copy(id + 1, copy, buffer).map_err(CryptError::IoError)?;
+        self.cipher.decrypt(id as u128, buffer);
+        Ok(())
+    }
+}
+
+
+// --- 8. Dosya Başına Şifreleme Anahtarları ---
+
+// Anahtar kimliğinin türetildiği HMAC mesajı
+const FILE_KEY_ID_LABEL: &[u8] = b"SADAK file key id";
+
+/// Dosya başına şifrelemede kullanılan AES-256-XTS anahtarı (bkz. `SadakFs::add_file_key`).
+///
+/// Anahtarın kimliği anahtarın kendisinden türetilir: inode'da yalnızca kimlik saklanır,
+/// aynı anahtar her montajda aynı kimliği verir ve kimlik anahtar hakkında bilgi vermez.
+pub struct FileKey {
+    /// Anahtarın kimliği (hiçbir zaman 0 değildir; 0 şifresiz dosya demektir).
+    id: u64,
+    cipher: XtsCipher,
+}
+
+impl FileKey {
+    /// 64 baytlık anahtardan (iki AES-256 anahtarı) dosya anahtarı oluşturur.
+    pub fn new(key: &[u8; CRYPT_KEY_SIZE]) -> Self {
+        let digest = HmacKey::new(key).mac(FILE_KEY_ID_LABEL);
+        let id = digest.first_chunk().map_or(1, |bytes| u64::from_le_bytes(*bytes)).max(1);
+        FileKey { id, cipher: XtsCipher::new(key) }
+    }
+
+    /// Anahtarın kimliği.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+/// Tek bir dosyanın veri şifresi: anahtar ve dosyanın birim içinde tekil sayısı (nonce).
+///
+/// Ayar değeri fiziksel blok değil (nonce, dosya bloğu) çiftidir; böylece taşınan,
+/// birleştirilen veya bir klonla paylaşılan bloklar (klon nonce'u da devralır) yeniden
+/// şifrelenmeden okunabilir ve iki dosyanın aynı konumdaki aynı verisi farklı şifrelenir.
+pub(crate) struct FileCipher {
+    key: Arc<FileKey>,
+    nonce: u64,
+}
+
+impl FileCipher {
+    pub(crate) fn new(key: Arc<FileKey>, nonce: u64) -> Self {
+        FileCipher { key, nonce }
+    }
+
+    fn unit(&self, file_block: u64) -> u128 {
+        ((self.nonce as u128) << 64) | file_block as u128
+    }
+
+    /// Dosyanın `file_block` numaralı bloğunu yerinde şifreler.
+    pub(crate) fn encrypt(&self, file_block: u64, block: &mut [u8]) {
+        self.key.cipher.encrypt(self.unit(file_block), block);
+    }
+
+    /// Dosyanın `file_block` numaralı bloğunu yerinde çözer.
+    pub(crate) fn decrypt(&self, file_block: u64, block: &mut [u8]) {
+        self.key.cipher.decrypt(self.unit(file_block), block);
+    }
+}
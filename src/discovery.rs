@@ -0,0 +1,86 @@
+// src/discovery.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockDevice, Sahne64Device, BLOCK_SIZE};
+use crate::cache::try_zeroed;
+use crate::fs::{Superblock, SUPERBLOCK_B_BLOCK, VOLUME_UUID_SIZE};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+
+// --- 1. Bulunan Birimler ---
+
+/// Aygıt taraması sonucunda tanınan bir SADAK birimi.
+///
+/// RAID-1 üyeleri aynı superblock'u (dolayısıyla aynı UUID'yi) taşıdığından, aynı
+/// birime ait tüm aygıtlar tek bir kayıtta `members` altında toplanır.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredVolume {
+    /// Birimin kimliği.
+    pub uuid: [u8; VOLUME_UUID_SIZE],
+    /// Birimin etiketi (boş olabilir).
+    pub label: String,
+    /// Superblock'taki toplam blok sayısı.
+    pub total_blocks: u64,
+    /// Birimin bulunduğu Sahne64 kaynak yolları, tarama sırasıyla.
+    pub members: Vec<String>,
+}
+
+
+// --- 2. Tarama ---
+
+/// Verilen Sahne64 kaynak yollarını yoklar ve üzerlerinde SADAK superblock'u bulunan
+/// birimleri UUID'ye göre gruplayarak döndürür.
+///
+/// Açılamayan, okunamayan veya geçerli superblock taşımayan yollar sessizce atlanır;
+/// önyükleme kodu olası tüm aygıtları verip içinden birimi seçebilir.
+pub fn discover(paths: &[&str]) -> Vec<DiscoveredVolume> {
+    let mut volumes: Vec<DiscoveredVolume> = Vec::new();
+
+    for &path in paths {
+        let superblock = match probe(path) {
+            Some(superblock) => superblock,
+            None => continue,
+        };
+
+        match volumes.iter_mut().find(|volume| volume.uuid == superblock.uuid) {
+            Some(volume) => volume.members.push(String::from(path)),
+            None => volumes.push(DiscoveredVolume {
+                uuid: superblock.uuid,
+                label: String::from(superblock.label()),
+                total_blocks: superblock.total_blocks,
+                members: vec![String::from(path)],
+            }),
+        }
+    }
+
+    volumes
+}
+
+/// Etiketi `label` olan ilk birimi bulur.
+pub fn find_by_label(paths: &[&str], label: &str) -> Option<DiscoveredVolume> {
+    discover(paths).into_iter().find(|volume| volume.label == label)
+}
+
+/// Kimliği `uuid` olan birimi bulur.
+pub fn find_by_uuid(paths: &[&str], uuid: &[u8; VOLUME_UUID_SIZE]) -> Option<DiscoveredVolume> {
+    discover(paths).into_iter().find(|volume| &volume.uuid == uuid)
+}
+
+/// Kaynağı edinip Superblock yuvalarını okur; geçerli bir superblock varsa döndürür.
+/// Kaynak, fonksiyondan çıkarken (aygıt düşürülünce) serbest bırakılır.
+fn probe(path: &str) -> Option<Superblock> {
+    // Kapasite henüz bilinmiyor; yalnızca Superblock yuvaları okunacak.
+    let device = Sahne64Device::new(path, 0).ok()?;
+    let mut slot_a = try_zeroed(BLOCK_SIZE).ok()?;
+    let mut slot_b = try_zeroed(BLOCK_SIZE).ok()?;
+    if device.read_block(0, &mut slot_a).is_err() {
+        slot_a.fill(0);
+    }
+    if device.read_block(SUPERBLOCK_B_BLOCK, &mut slot_b).is_err() {
+        slot_b.fill(0);
+    }
+    Superblock::from_slots(&slot_a, &slot_b).map(|(superblock, _)| superblock)
+}
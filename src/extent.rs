@@ -0,0 +1,357 @@
+// src/extent.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE};
+use crate::btree::{BTreeKey, ITEM_TYPE_EXTENT, ITEM_TYPE_SHARED_BLOCK};
+use crate::cache::BlockCache;
+use crate::compress::Compression;
+use crate::page_cache::PageCache;
+use crate::zstd::ZSTD_LEVEL_DEFAULT;
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Extent kaydının disk üzerindeki boyutu: physical_start (8) + length (4) + flags (4)
+// + compressed_len (4) + skip_blocks (4). Sıkıştırmadan önce yazılan kayıtlar ilk üç
+// alandan (16 bayt) oluşur.
+pub const EXTENT_RECORD_SIZE: usize = 24;
+const EXTENT_RECORD_MIN_SIZE: usize = 16;
+
+// Veri ağaçları dosyaya özel olduğundan tüm extent anahtarları bu nesne kimliğini kullanır.
+pub const DATA_TREE_OBJECT_ID: u64 = 0;
+
+/// Extent bayrağı: örnek verisi sıkıştırılamaz bulundu (bkz. `compress::should_compress`);
+/// sıkıştırma yeniden denenmez ve veri olduğu gibi saklanır.
+pub const EXTENT_FLAG_INCOMPRESSIBLE: u32 = 1 << 0;
+
+/// Extent bayrağı: veri sıkıştırılmış bir küme olarak saklanıyor (varsayılan LZ4, bkz.
+/// `compress::lz4_compress`); dosya blokları tek tek bir disk bloğuna eşlenmez.
+pub const EXTENT_FLAG_COMPRESSED: u32 = 1 << 1;
+
+/// Extent bayrağı: sıkıştırılmış küme LZ4 yerine bir zstd çerçevesidir (bkz.
+/// `zstd::zstd_compress`); yalnızca `EXTENT_FLAG_COMPRESSED` ile birlikte anlamlıdır.
+pub const EXTENT_FLAG_ZSTD: u32 = 1 << 2;
+
+// Paylaşılan blok kayıtlarının metadata ağacındaki nesne kimliği (değişen blok kayıtlarının
+// `u64::MAX` kimliğinin hemen altı; inode ID'leriyle çakışmaz).
+const SHARED_BLOCK_OBJECT: u64 = u64::MAX - 1;
+
+
+// --- 2. Extent Kaydı ---
+
+/// Bir dosyanın ardışık mantıksal bloklarını ardışık fiziksel bloklara eşleyen kayıt.
+///
+/// Veri ağacında `(DATA_TREE_OBJECT_ID, ITEM_TYPE_EXTENT, ilk dosya bloğu)` anahtarıyla
+/// tutulur. Hiçbir extent'in kapsamadığı dosya blokları delik (hole) sayılır ve sıfır okunur.
+///
+/// Sıkıştırılmış bir extent'te `physical_start`'tan başlayan `stored_blocks()` blok, bir
+/// kümenin sıkıştırılmış halini tutar; extent, çözülmüş kümenin `skip_blocks`'tan başlayan
+/// `length` bloğunu gösterir. Küme bölünemediğinden bölünen extent'in parçaları aynı
+/// blokları paylaşır (bkz. `SharedBlock`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extent {
+    /// Extent'in başladığı dosya içi blok indeksi (anahtarın `offset` alanı).
+    pub file_block: u64,
+    /// İlk bloğun diskteki ID'si.
+    pub physical_start: BlockId,
+    /// Extent'in kapsadığı blok sayısı.
+    pub length: u32,
+    /// Extent bayrakları (`EXTENT_FLAG_*`).
+    pub flags: u32,
+    /// Sıkıştırılmış verinin bayt cinsinden uzunluğu (sıkıştırılmamışsa 0).
+    pub compressed_len: u32,
+    /// Çözülmüş kümede extent'in başladığı blok (sıkıştırılmamışsa 0).
+    pub skip_blocks: u32,
+}
+
+impl Extent {
+    /// `file_block` ile başlayan extent'in veri ağacındaki anahtarı.
+    pub fn key(file_block: u64) -> BTreeKey {
+        BTreeKey {
+            object_id: DATA_TREE_OBJECT_ID,
+            item_type: ITEM_TYPE_EXTENT,
+            offset: file_block,
+        }
+    }
+
+    /// Bir veri ağacı öğesini extent olarak çözer; öğe extent değilse `None`.
+    pub fn from_item(key: &BTreeKey, value: &[u8]) -> Option<Self> {
+        if key.object_id != DATA_TREE_OBJECT_ID
+            || key.item_type != ITEM_TYPE_EXTENT
+            || value.len() < EXTENT_RECORD_MIN_SIZE
+        {
+            return None;
+        }
+
+        let mut physical_start = [0u8; 8];
+        let mut length = [0u8; 4];
+        let mut flags = [0u8; 4];
+        physical_start.copy_from_slice(&value[0..8]);
+        length.copy_from_slice(&value[8..12]);
+        flags.copy_from_slice(&value[12..16]);
+        let (compressed_len, skip_blocks) = if value.len() >= EXTENT_RECORD_SIZE {
+            (
+                u32::from_le_bytes([value[16], value[17], value[18], value[19]]),
+                u32::from_le_bytes([value[20], value[21], value[22], value[23]]),
+            )
+        } else {
+            (0, 0)
+        };
+
+        Some(Extent {
+            file_block: key.offset,
+            physical_start: BlockId::from_le_bytes(physical_start),
+            length: u32::from_le_bytes(length),
+            flags: u32::from_le_bytes(flags),
+            compressed_len,
+            skip_blocks,
+        })
+    }
+
+    /// Kaydın değer kısmını disk formatına (little-endian) yazar.
+    pub fn encode(&self) -> [u8; EXTENT_RECORD_SIZE] {
+        let mut out = [0u8; EXTENT_RECORD_SIZE];
+        out[0..8].copy_from_slice(&self.physical_start.to_le_bytes());
+        out[8..12].copy_from_slice(&self.length.to_le_bytes());
+        out[12..16].copy_from_slice(&self.flags.to_le_bytes());
+        out[16..20].copy_from_slice(&self.compressed_len.to_le_bytes());
+        out[20..24].copy_from_slice(&self.skip_blocks.to_le_bytes());
+        out
+    }
+
+    /// Extent'in verisi sıkıştırılamaz olarak işaretli mi?
+    pub fn is_incompressible(&self) -> bool {
+        self.flags & EXTENT_FLAG_INCOMPRESSIBLE != 0
+    }
+
+    /// Extent'in kapsadığı son dosya bloğundan bir sonraki blok indeksi.
+    pub fn end(&self) -> u64 {
+        self.file_block + self.length as u64
+    }
+
+    /// Extent'in verisi sıkıştırılmış mı?
+    pub fn is_compressed(&self) -> bool {
+        self.flags & EXTENT_FLAG_COMPRESSED != 0
+    }
+
+    /// Sıkıştırılmış verinin çözücüsü (düzey çözmeyi etkilemez).
+    pub fn compression(&self) -> Compression {
+        if self.flags & EXTENT_FLAG_ZSTD != 0 { Compression::Zstd(ZSTD_LEVEL_DEFAULT) } else { Compression::Lz4 }
+    }
+
+    /// Extent'in diskte kapladığı blok sayısı.
+    pub fn stored_blocks(&self) -> u64 {
+        if self.is_compressed() {
+            (self.compressed_len as u64 + BLOCK_SIZE as u64 - 1) / BLOCK_SIZE as u64
+        } else {
+            self.length as u64
+        }
+    }
+
+    /// Extent'in diskte kapladığı bloklar.
+    pub fn physical_blocks(&self) -> core::ops::Range<BlockId> {
+        self.physical_start..self.physical_start + self.stored_blocks()
+    }
+
+    /// Extent içindeki bir dosya bloğunun diskteki ID'si (yalnızca sıkıştırılmamış extent'ler).
+    pub fn physical_for(&self, file_block: u64) -> BlockId {
+        self.physical_start + (file_block - self.file_block)
+    }
+}
+
+
+// --- 2.1. Paylaşılan Bloklar (Reflink) ---
+
+/// Klonlanan dosyalar arasında paylaşılan bir veri bloğunun başvuru kaydı.
+///
+/// Metadata ağacında `(SHARED_BLOCK_OBJECT, ITEM_TYPE_SHARED_BLOCK, fiziksel blok)`
+/// anahtarıyla tutulur; değer, bloğa ilk sahibinin dışında başvuran dosya sayısıdır (LE).
+/// Kaydı olmayan bloğun tek sahibi vardır. Paylaşılan blok yerinde değiştirilmez ve
+/// sahiplerinden biri onu bıraktığında yalnızca sayaç düşer; sayaç sıfıra inince kayıt
+/// silinir ve blok yeniden tek sahipli olur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SharedBlock {
+    pub physical: BlockId,
+    /// İlk sahip dışındaki başvuru sayısı (kayıt varken en az 1).
+    pub extra_refs: u64,
+}
+
+impl SharedBlock {
+    /// `physical` bloğunun kaydının anahtarı.
+    pub fn key(physical: BlockId) -> BTreeKey {
+        BTreeKey {
+            object_id: SHARED_BLOCK_OBJECT,
+            item_type: ITEM_TYPE_SHARED_BLOCK,
+            offset: physical,
+        }
+    }
+
+    /// Kaydın değer kısmı.
+    pub fn encode(&self) -> [u8; 8] {
+        self.extra_refs.to_le_bytes()
+    }
+
+    /// Anahtar ve değerden kaydı çözer; kayıt değilse veya bozuksa `None`.
+    pub fn from_item(key: &BTreeKey, value: &[u8]) -> Option<Self> {
+        if key.object_id != SHARED_BLOCK_OBJECT || key.item_type != ITEM_TYPE_SHARED_BLOCK || value.len() < 8 {
+            return None;
+        }
+        let mut extra_refs = [0u8; 8];
+        extra_refs.copy_from_slice(&value[..8]);
+        Some(SharedBlock { physical: key.offset, extra_refs: u64::from_le_bytes(extra_refs) })
+    }
+}
+
+
+// --- 3. Akış Okuyucu (Streaming Reader) ---
+
+/// Extent'leri sırayla alıp çağıranın arabelleğine büyük parçalar halinde okuyan ve
+/// her dolu parçayı bir alıcıya (sink) aktaran yardımcı.
+///
+/// Veri, önbelleğe alınmadan doğrudan `read_blocks` ile okunur; yalnızca önbellekte
+/// kirli kopyası olan aralıklar güncel veriyi görmek için önbellek üzerinden okunur.
+/// Henüz geri yazılmamış sayfalar, aktarılmadan önce okunan verinin üzerine kopyalanır.
+pub(crate) struct ExtentStream<'a, D: BlockDevice, F: FnMut(&[u8]) -> bool> {
+    cache: &'a BlockCache<D>,
+    /// Dosyanın geri yazılmayı bekleyen sayfaları.
+    pages: &'a PageCache,
+    /// Okunan dosyanın inode blok ID'si (sayfa anahtarı için).
+    inode_id: BlockId,
+    /// Çağıranın ara belleği (uzunluğu `BLOCK_SIZE`'ın katı).
+    buffer: &'a mut [u8],
+    /// Doldurulan her parçayla çağrılır; `false` dönerse akış durur.
+    sink: &'a mut F,
+    /// Sıradaki okunacak dosya bloğu.
+    cursor: u64,
+    /// Dosyanın son bloğundan bir sonraki blok indeksi.
+    end_block: u64,
+    /// Son parçayı kırpmak için dosyanın bayt cinsinden boyutu.
+    file_size: u64,
+    /// Alıcıya aktarılan toplam bayt sayısı.
+    delivered: u64,
+    /// Alıcı akışı durdurdu mu?
+    stopped: bool,
+}
+
+impl<'a, D: BlockDevice, F: FnMut(&[u8]) -> bool> ExtentStream<'a, D, F> {
+    /// `start_block` dosya bloğundan başlayan yeni bir akış oluşturur.
+    pub fn new(
+        cache: &'a BlockCache<D>,
+        pages: &'a PageCache,
+        inode_id: BlockId,
+        file_size: u64,
+        start_block: u64,
+        buffer: &'a mut [u8],
+        sink: &'a mut F,
+    ) -> Self {
+        ExtentStream {
+            cache,
+            pages,
+            inode_id,
+            buffer,
+            sink,
+            cursor: start_block,
+            end_block: (file_size + BLOCK_SIZE as u64 - 1) / BLOCK_SIZE as u64,
+            file_size,
+            delivered: 0,
+            stopped: false,
+        }
+    }
+
+    /// Dosyanın sonuna ulaşıldı veya alıcı akışı durdurdu mu?
+    pub fn is_done(&self) -> bool {
+        self.stopped || self.cursor >= self.end_block
+    }
+
+    /// Alıcıya aktarılan toplam bayt sayısı.
+    pub fn delivered(&self) -> u64 {
+        self.delivered
+    }
+
+    /// İmleçten `file_block`'a kadar (dosya sonuyla sınırlı) olan deliği sıfırlarla doldurur.
+    pub fn zeros_until(&mut self, file_block: u64) {
+        let until = file_block.min(self.end_block);
+        while !self.is_done() && self.cursor < until {
+            let blocks = (until - self.cursor).min(self.chunk_blocks());
+            self.buffer[..blocks as usize * BLOCK_SIZE].fill(0);
+            self.emit(blocks);
+        }
+    }
+
+    /// Bir extent'i okur; extent ile imleç arasındaki delik önce sıfırlarla doldurulur.
+    /// Extent'in imleçten önceki kısmı atlanır.
+    pub fn read_extent(&mut self, extent: &Extent) -> Result<(), D::Error> {
+        self.zeros_until(extent.file_block);
+
+        let until = extent.end().min(self.end_block);
+        while !self.is_done() && self.cursor < until {
+            let blocks = (until - self.cursor).min(self.chunk_blocks());
+            let physical = extent.physical_for(self.cursor);
+            let dest = &mut self.buffer[..blocks as usize * BLOCK_SIZE];
+
+            if self.cache.has_dirty_in_range(physical, blocks) {
+                // Diskteki kopya eski; blokları önbellekten tek tek al.
+                for (i, chunk) in dest.chunks_mut(BLOCK_SIZE).enumerate() {
+                    let block_arc = self.cache.get_block(physical + i as BlockId)?;
+                    chunk.copy_from_slice(unsafe { &(*block_arc.get()).data[..] });
+                }
+            } else {
+                self.cache.device.read_blocks(physical, dest)?;
+            }
+
+            self.emit(blocks);
+        }
+
+        Ok(())
+    }
+
+    /// Sıkıştırılmış bir extent'in çözülmüş verisini (`data`, extent'in `length` bloğu)
+    /// aktarır; extent ile imleç arasındaki delik önce sıfırlarla doldurulur.
+    pub fn read_decoded(&mut self, extent: &Extent, data: &[u8]) {
+        self.zeros_until(extent.file_block);
+
+        let until = extent.end().min(self.end_block);
+        while !self.is_done() && self.cursor < until {
+            let blocks = (until - self.cursor).min(self.chunk_blocks());
+            let from = (self.cursor - extent.file_block) as usize * BLOCK_SIZE;
+            let len = blocks as usize * BLOCK_SIZE;
+            self.buffer[..len].copy_from_slice(&data[from..from + len]);
+            self.emit(blocks);
+        }
+    }
+
+    /// Tek bir dosya bloğunu `data` içeriğiyle (kalanı sıfır) aktarır; imleçle blok
+    /// arasındaki delik önce sıfırlarla doldurulur. Kuyruk parçaları (bkz. `tail`) için.
+    pub fn read_tail(&mut self, file_block: u64, data: &[u8]) {
+        self.zeros_until(file_block);
+        if self.is_done() || self.cursor != file_block {
+            return;
+        }
+
+        let dest = &mut self.buffer[..BLOCK_SIZE];
+        dest.fill(0);
+        dest[..data.len()].copy_from_slice(data);
+        self.emit(1);
+    }
+
+    // --- Yardımcı Fonksiyonlar ---
+
+    /// Ara belleğe sığan blok sayısı.
+    fn chunk_blocks(&self) -> u64 {
+        (self.buffer.len() / BLOCK_SIZE) as u64
+    }
+
+    /// Arabelleğin ilk `blocks` bloğunu (dosya sonunda kırparak) alıcıya aktarır.
+    fn emit(&mut self, blocks: u64) {
+        let position = self.cursor * BLOCK_SIZE as u64;
+        let len = (blocks * BLOCK_SIZE as u64).min(self.file_size - position) as usize;
+        self.pages.overlay(self.inode_id, self.cursor, &mut self.buffer[..blocks as usize * BLOCK_SIZE]);
+
+        if !(self.sink)(&self.buffer[..len]) {
+            self.stopped = true;
+        }
+        self.cursor += blocks;
+        self.delivered += len as u64;
+    }
+}
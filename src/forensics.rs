@@ -0,0 +1,207 @@
+// src/forensics.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockId, BLOCK_SIZE};
+use crate::checksum::checksum_excluding;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Adli kayıt bloğunun sihirli sayısı ("FRNS").
+const FORENSIC_LOG_MAGIC: u32 = 0x46524E53;
+
+// Blok başlığı: magic (4) + checksum (4) + count (4) + reserved (4) + detected (8) + repaired (8)
+const FORENSIC_LOG_HEADER_SIZE: usize = 32;
+
+// Kaydın disk üzerindeki boyutu: timestamp (8) + block (8) + expected (4) + found (4)
+// + device (2) + source (1) + flags (1) + ayrılmış (4)
+const CORRUPTION_RECORD_SIZE: usize = 32;
+
+// Kayıt, bozukluğun sağlam bir kopyadan onarıldığını gösterir.
+const RECORD_FLAG_REPAIRED: u8 = 1 << 0;
+
+// Günlükte tutulan en fazla kayıt; dolunca en eski kayıt düşer.
+pub const FORENSIC_LOG_CAPACITY: usize = (BLOCK_SIZE - FORENSIC_LOG_HEADER_SIZE) / CORRUPTION_RECORD_SIZE;
+
+/// Bozuk bloğun ait olduğu yapı (checksum algoritması seçilen nesne türleri).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorruptionSource {
+    /// Metadata B-Ağacı düğümü.
+    MetadataTree = 1,
+    /// Bir dosyanın veri ağacı düğümü.
+    DataTree = 2,
+    /// Inode bloğu.
+    Inode = 3,
+    /// Tahsis haritası veya alan haritası bloğu.
+    SpaceMap = 4,
+}
+
+impl CorruptionSource {
+    fn from_raw(raw: u8) -> Option<Self> {
+        match raw {
+            1 => Some(CorruptionSource::MetadataTree),
+            2 => Some(CorruptionSource::DataTree),
+            3 => Some(CorruptionSource::Inode),
+            4 => Some(CorruptionSource::SpaceMap),
+            _ => None,
+        }
+    }
+}
+
+
+// --- 2. Bozukluk Kaydı ---
+
+/// Tespit edilen tek bir checksum bozukluğu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CorruptionRecord {
+    /// Tespit zamanı (Sahne64 sistem zamanı).
+    pub timestamp: u64,
+    /// Bozuk blok.
+    pub block: BlockId,
+    pub source: CorruptionSource,
+    /// Bozuk kopyanın bulunduğu aygıt (yansıtmalı dizide üye sırası; tek aygıtta 0).
+    pub device: u16,
+    /// Blokta saklı checksum.
+    pub expected: u32,
+    /// Blok içeriğinden hesaplanan checksum.
+    pub found: u32,
+    /// Blok sağlam bir kopyadan yeniden yazıldı mı?
+    pub repaired: bool,
+}
+
+impl CorruptionRecord {
+    fn encode(&self, out: &mut [u8]) {
+        out[0..8].copy_from_slice(&self.timestamp.to_le_bytes());
+        out[8..16].copy_from_slice(&self.block.to_le_bytes());
+        out[16..20].copy_from_slice(&self.expected.to_le_bytes());
+        out[20..24].copy_from_slice(&self.found.to_le_bytes());
+        out[24..26].copy_from_slice(&self.device.to_le_bytes());
+        out[26] = self.source as u8;
+        out[27] = if self.repaired { RECORD_FLAG_REPAIRED } else { 0 };
+    }
+
+    fn decode(raw: &[u8]) -> Option<Self> {
+        let u64_at = |at: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&raw[at..at + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        let u32_at = |at: usize| u32::from_le_bytes([raw[at], raw[at + 1], raw[at + 2], raw[at + 3]]);
+
+        Some(CorruptionRecord {
+            timestamp: u64_at(0),
+            block: u64_at(8),
+            expected: u32_at(16),
+            found: u32_at(20),
+            device: u16::from_le_bytes([raw[24], raw[25]]),
+            source: CorruptionSource::from_raw(raw[26])?,
+            repaired: raw[27] & RECORD_FLAG_REPAIRED != 0,
+        })
+    }
+}
+
+/// Montajlar boyunca biriken okuma onarımı sayaçları.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReadRepairStats {
+    /// Tespit edilen bozuk kopya sayısı.
+    pub detected: u64,
+    /// Sağlam bir kopyadan onarılan bozuk kopya sayısı.
+    pub repaired: u64,
+}
+
+
+// --- 3. Adli Kayıt Günlüğü ---
+
+/// Tespit edilen bozuklukların sınırlı, kalıcı günlüğü.
+///
+/// Son `FORENSIC_LOG_CAPACITY` kayıt tek bir blokta tutulur; sayaçlar ise hiç düşmez.
+/// Blok Superblock'taki `forensic_log_block` ile gösterilir ve değiştiğinde sıcak blok
+/// listesi gibi her commit'te yeni bir bloğa yazılır.
+#[derive(Debug, Default)]
+pub struct ForensicLog {
+    records: VecDeque<CorruptionRecord>,
+    stats: ReadRepairStats,
+    /// Son kalıcı yazmadan beri değişti mi?
+    dirty: bool,
+}
+
+impl ForensicLog {
+    /// Bir kaydı ekler; günlük doluysa en eski kaydı düşürür.
+    pub fn push(&mut self, record: CorruptionRecord) {
+        if self.records.len() == FORENSIC_LOG_CAPACITY {
+            self.records.pop_front();
+        }
+        self.stats.detected += 1;
+        if record.repaired {
+            self.stats.repaired += 1;
+        }
+        self.records.push_back(record);
+        self.dirty = true;
+    }
+
+    /// Kayıtlar, en eskiden en yeniye.
+    pub fn records(&self) -> Vec<CorruptionRecord> {
+        self.records.iter().copied().collect()
+    }
+
+    pub fn stats(&self) -> ReadRepairStats {
+        self.stats
+    }
+
+    /// Günlüğü bir sonraki commit'te yeniden yazılacak olarak işaretler.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Son kalıcı yazmadan beri değiştiyse `true` döner ve işareti temizler.
+    pub fn take_dirty(&mut self) -> bool {
+        core::mem::replace(&mut self.dirty, false)
+    }
+
+    /// Günlüğü blok formatında `out`'a yazar.
+    pub fn encode(&self, out: &mut [u8]) {
+        out[..BLOCK_SIZE].fill(0);
+        out[0..4].copy_from_slice(&FORENSIC_LOG_MAGIC.to_le_bytes());
+        out[8..12].copy_from_slice(&(self.records.len() as u32).to_le_bytes());
+        out[16..24].copy_from_slice(&self.stats.detected.to_le_bytes());
+        out[24..32].copy_from_slice(&self.stats.repaired.to_le_bytes());
+        for (i, record) in self.records.iter().enumerate() {
+            let at = FORENSIC_LOG_HEADER_SIZE + i * CORRUPTION_RECORD_SIZE;
+            record.encode(&mut out[at..at + CORRUPTION_RECORD_SIZE]);
+        }
+        let crc = checksum_excluding(&out[..BLOCK_SIZE], 4, 4);
+        out[4..8].copy_from_slice(&crc.to_le_bytes());
+    }
+
+    /// Günlük bloğunu çözer; blok bozuksa veya günlük değilse `None`.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < BLOCK_SIZE {
+            return None;
+        }
+        let u32_at = |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+        let u64_at = |at: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data[at..at + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        let count = u32_at(8) as usize;
+        if u32_at(0) != FORENSIC_LOG_MAGIC || u32_at(4) != checksum_excluding(&data[..BLOCK_SIZE], 4, 4) || count > FORENSIC_LOG_CAPACITY {
+            return None;
+        }
+
+        let records = (0..count)
+            .map(|i| {
+                let at = FORENSIC_LOG_HEADER_SIZE + i * CORRUPTION_RECORD_SIZE;
+                CorruptionRecord::decode(&data[at..at + CORRUPTION_RECORD_SIZE])
+            })
+            .collect::<Option<VecDeque<_>>>()?;
+        Some(ForensicLog {
+            records,
+            stats: ReadRepairStats { detected: u64_at(16), repaired: u64_at(24) },
+            dirty: false,
+        })
+    }
+}
@@ -3,15 +3,48 @@
 #![allow(dead_code, unused_variables)]
 
 use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE};
-use crate::cache::{BlockCache, SysLock};
-use crate::allocator::{Allocator, AllocatorError};
-use crate::btree::BTree;
-use crate::checksum;
-use crate::sahne_syscalls::{self, SyscallError}; // sahne_syscalls'ı ekledik
+use crate::cache::{self as block_cache, allocate_buffer, try_zeroed, BlockCache, CACHE_CAPACITY_BLOCKS, READAHEAD_ENABLED, WARM_SET_MAX_BLOCKS};
+use crate::seal::{self, SealGeometry, SealVerifier, SEAL_HASHES_PER_BLOCK, SEAL_HASH_SIZE};
+use crate::allocator::{Allocator, AllocatorError, PlacementHint, Temperature};
+use crate::btree::{node_blocks_for, node_checksum, BTree, BTreeKey, BTreeNodeHeader, BTREE_NODE_SIZE, ITEM_TYPE_DIR_ENTRY, ITEM_TYPE_DIR_MIGRATION, ITEM_TYPE_EXTENT, ITEM_TYPE_XATTR};
+use crate::extent::{Extent, ExtentStream, SharedBlock, EXTENT_FLAG_COMPRESSED, EXTENT_FLAG_INCOMPRESSIBLE, EXTENT_FLAG_ZSTD};
+use crate::compress::{self, Compression, COMPRESS_CLUSTER_BLOCKS, COMPRESS_CLUSTER_BYTES};
+use crate::crypt::{FileCipher, FileKey, CRYPT_KEY_SIZE};
+use crate::page_cache::PageCache;
+use crate::tail::{TailBlock, TailFragment, TAIL_MAX};
+use crate::dir::{Casefold, DirEntry, DirMigration, DIR_HASH_COLLISION_MAX, DIR_HASH_THRESHOLD, DIR_MIGRATE_BATCH};
+use crate::xattr::{XattrEntry, XattrValue, XATTR_VALUE_MAX};
+use crate::path::{self, Component};
+use crate::skeleton::{SkeletonEntry, SkeletonKind};
+use crate::import::{self, ImportError, ImportKind, ImportProgress, ImportSource};
+use crate::replication::{self, ReceiveSource, StreamHeader, STREAM_HEADER_SIZE, STREAM_RUN_HEADER_SIZE, STREAM_RUN_MAX_BLOCKS};
+use crate::subvolume::{Subvolume, MAIN_SUBVOLUME_ID, SUBVOLUME_ATTR_COMPRESS, SUBVOLUME_ATTR_COMPRESSION_SHIFT, SUBVOLUME_ATTR_NOCOW};
+use crate::inode_table::{InodeLocation, ROOT_INO};
+use crate::orphan::Orphan;
+use crate::fsck::{self, BlockReferences, FsckProblem, FsckReport};
+use crate::journal::{JournalHeader, JOURNAL_BLOCKS, JOURNAL_CAPACITY};
+use crate::forensics::{CorruptionRecord, CorruptionSource, ForensicLog, ReadRepairStats};
+use crate::quota::{QuotaId, QuotaUsage};
+use crate::checksum::{self, ChecksumAlgorithm, ChecksumPolicy, Checksummer, CHECKSUM_POLICY_SIZE};
+use crate::accounting::{IoAccounting, IoKind};
+use crate::clock::{Clock, KernelClock};
+#[cfg(feature = "snapshot")]
+use crate::changes::{self, ChangedRun, CHANGE_EPOCH_MAX, CHANGE_TRACKING_MAX_BLOCKS};
+use crate::platform::{self, SysLock};
+use crate::sahne_syscalls::SyscallError;
+#[cfg(feature = "snapshot")]
+use crate::snapshot::{RetentionPolicy, Snapshot, SnapshotDevice, SnapshotError};
+#[cfg(feature = "snapshot")]
+use crate::replication::SendSink;
 
 use core::mem;
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use alloc::sync::Arc;
 use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
 use core::fmt::Debug;
 
 // --- 1. Sabitler ve Türler ---
@@ -22,6 +55,118 @@ const SADAK_MAGIC: u64 = 0x5ADAKF5;
 // SADAK versiyonu
 const SADAK_VERSION: u16 = 1;
 
+// Bir işlemin ara commit yapılmadan önce biriktirebileceği varsayılan kirli blok sınırı.
+const DEFAULT_DIRTY_BLOCK_BUDGET: usize = 256;
+
+// Hızlı montaj denetiminde doğrulanacak ağaç seviyesi sayısı (kök + çocukları).
+const MOUNT_AUDIT_TREE_LEVELS: u8 = 2;
+
+// Birim kimliğinin (UUID) bayt cinsinden boyutu.
+pub const VOLUME_UUID_SIZE: usize = 16;
+
+// Birim etiketinin bayt cinsinden en büyük uzunluğu.
+pub const VOLUME_LABEL_MAX: usize = 32;
+
+// Birim özellik bayrakları (bkz. `FeatureSet`). Tanınmayan `compat` bayrakları yok sayılır,
+// tanınmayan `ro_compat` bayrakları birimi salt okunur monte ettirir, tanınmayan
+// `incompat` bayrakları montajı engeller.
+/// Yerinde yazılan metadata blokları niyet günlüğünden geçirilir (bkz. `journal`); bunu
+/// bilmeyen bir sürüm günlüğü yok sayar ve yalnızca çökme korumasını kaybeder.
+pub const FEATURE_COMPAT_JOURNAL: u32 = 1 << 0;
+/// Superblock commit'lerde Blok 0 ile `SUPERBLOCK_B_BLOCK` arasında dönüşümlü yazılır;
+/// bunu bilmeyen bir sürüm yalnızca Blok 0'ı okur ve ona yazar.
+pub const FEATURE_COMPAT_SUPERBLOCK_SLOTS: u32 = 1 << 1;
+
+/// Superblock'un ikinci (B) yuvası (bkz. `FormatOptions::superblock_slots`).
+pub const SUPERBLOCK_B_BLOCK: BlockId = 1;
+/// Dosyaların son blokları paylaşılan kuyruk bloklarında olabilir (bkz. `SadakFs::pack_tail`);
+/// bunu bilmeyen bir sürüm bu dosyaları okuyamaz.
+pub const FEATURE_INCOMPAT_TAIL_PACKING: u32 = 1 << 0;
+/// Bazı nesne türlerinde CRC32C dışında checksum algoritması kullanılıyor (bkz. `ChecksumPolicy`).
+pub const FEATURE_INCOMPAT_CHECKSUM_POLICY: u32 = 1 << 1;
+/// Bazı dosyaların verisi sıkıştırılmış extent'lerde (bkz. `SadakFs::set_compression`);
+/// bunu bilmeyen bir sürüm bu dosyaları okuyamaz.
+pub const FEATURE_INCOMPAT_COMPRESSION: u32 = 1 << 2;
+/// Sıkıştırılmış extent'lerin bazıları zstd çerçevesi (bkz. `Compression::Zstd`); yalnızca
+/// LZ4 bilen bir sürüm bu dosyaları okuyamaz.
+pub const FEATURE_INCOMPAT_ZSTD: u32 = 1 << 3;
+/// Bazı dosyaların verisi dosya anahtarlarıyla şifreli (bkz. `SadakFs::set_encryption`);
+/// bunu bilmeyen bir sürüm bu dosyaları şifreli metin olarak okur ve yazarken bozar.
+pub const FEATURE_INCOMPAT_ENCRYPTION: u32 = 1 << 4;
+/// Niyet günlüğü birden çok kesimden oluşuyor (bkz. `Superblock::journal_segments`); bunu
+/// bilmeyen bir sürüm yalnızca ilk kesimi yeniden uygular ve yarım commit'i tamamlayamaz.
+pub const FEATURE_INCOMPAT_JOURNAL_SEGMENTS: u32 = 1 << 5;
+/// Metadata ağacının düğümleri birden çok bloğa yayılıyor (bkz.
+/// `FormatOptions::metadata_node_size`); bunu bilmeyen bir sürüm ağacı okuyamaz.
+pub const FEATURE_INCOMPAT_LARGE_NODES: u32 = 1 << 6;
+/// Dizin girdileri inode'ları kalıcı inode numarasıyla gösteriyor ve dizinin numarasıyla
+/// anahtarlanıyor (bkz. `dir::DirEntry`); bunu bilmeyen bir sürüm numaraları blok ID'si
+/// sanar.
+pub const FEATURE_INCOMPAT_DIR_INO: u32 = 1 << 7;
+/// Bazı dizinler ad özetli düzene parça parça taşınıyor (bkz. `INODE_FLAG_DIR_MIGRATING`);
+/// bunu bilmeyen bir sürüm henüz taşınmamış girdileri bulamaz.
+pub const FEATURE_INCOMPAT_DIR_MIGRATE: u32 = 1 << 8;
+/// B-Ağacı düğüm bloklarının başında ve son 8 baytında aynı yazma damgası var; okumada
+/// uyuşmayan damgalar yırtık yazmayı gösterir (bkz. `BlockCache::enable_write_stamps`).
+/// Bloğun son öğe yuvası damgaya bırakıldığından bunu bilmeyen bir sürüm düğümleri yanlış
+/// okur. Atomik yazma birimi 512 bayt olan aygıtlar için biçimlendirmede seçilir.
+pub const FEATURE_INCOMPAT_WRITE_STAMPS: u32 = 1 << 9;
+/// Alanın bir kısmı metadata için ayrılmış (`Superblock::reserved_blocks`); bunu bilmeyen
+/// bir sürüm birimi okuyabilir ama yazarken ayrılan alanı veriyle doldurabilir.
+pub const FEATURE_RO_COMPAT_RESERVED_BLOCKS: u32 = 1 << 0;
+/// Inode'lar kalıcı numaralarla inode tablosunda kayıtlı (bkz. `SadakFs::get_inode`); bunu
+/// bilmeyen bir sürüm birimi okuyabilir ama yazarken tabloyu güncel tutmaz.
+pub const FEATURE_RO_COMPAT_INODE_TABLE: u32 = 1 << 1;
+/// Bazı dizinler ad özetli (bkz. `INODE_FLAG_DIR_HASHED`); bunu bilmeyen bir sürüm bu
+/// dizinleri okuyabilir ama yazarken girdileri özetlerinin dışına ekler.
+pub const FEATURE_RO_COMPAT_DIR_HASH: u32 = 1 << 2;
+/// Bazı dizinlerde adlar büyük/küçük harf ayrımsız aranır (bkz. `SadakFs::set_casefold`);
+/// bunu bilmeyen bir sürüm bu dizinleri okuyabilir ama yazarken yalnızca harf büyüklüğü
+/// farklı ikinci bir ad ekleyebilir.
+pub const FEATURE_RO_COMPAT_CASEFOLD: u32 = 1 << 3;
+
+// Bu sürümün tanıdığı özellikler.
+const FEATURES_SUPPORTED: FeatureSet = FeatureSet {
+    compat: FEATURE_COMPAT_JOURNAL | FEATURE_COMPAT_SUPERBLOCK_SLOTS,
+    ro_compat: FEATURE_RO_COMPAT_RESERVED_BLOCKS | FEATURE_RO_COMPAT_INODE_TABLE | FEATURE_RO_COMPAT_DIR_HASH | FEATURE_RO_COMPAT_CASEFOLD,
+    incompat: FEATURE_INCOMPAT_TAIL_PACKING | FEATURE_INCOMPAT_CHECKSUM_POLICY | FEATURE_INCOMPAT_COMPRESSION | FEATURE_INCOMPAT_ZSTD | FEATURE_INCOMPAT_ENCRYPTION
+        | FEATURE_INCOMPAT_JOURNAL_SEGMENTS | FEATURE_INCOMPAT_LARGE_NODES | FEATURE_INCOMPAT_DIR_INO | FEATURE_INCOMPAT_DIR_MIGRATE
+        | FEATURE_INCOMPAT_WRITE_STAMPS,
+};
+
+// Biçimlendirmede tahsis haritasından sonra kalması gereken en az blok sayısı
+// (metadata kökü, kök dizin, alan haritası ve ilk CoW kopyaları için).
+const FORMAT_MIN_FREE_BLOCKS: u64 = 16;
+
+// Superblock'taki birim durumu: monte edilmiş (veya düzgün ayrılmadan kapanmış) ve
+// `unmount` ile temiz ayrılmış.
+const VOLUME_STATE_ACTIVE: u32 = 0;
+const VOLUME_STATE_CLEAN: u32 = 1;
+
+// Yeni biçimlendirilen birimlerde, tam denetim önerilmeden önceki en fazla montaj sayısı.
+const DEFAULT_MAX_MOUNT_COUNT: u32 = 30;
+
+// Yeni biçimlendirilen birimlerde iki tam denetim arasındaki en uzun süre
+// (sistem saati biriminde; saniye varsayılarak 180 gün).
+const DEFAULT_CHECK_INTERVAL: u64 = 180 * 24 * 60 * 60;
+
+// İpucu verilmemiş bir dosyanın soğuk sayılması için son değişiklikten beri geçmesi
+// gereken süre (sistem saati biriminde; saniye varsayılarak 30 gün).
+const COLD_DATA_AGE: u64 = 30 * 24 * 60 * 60;
+
+// Toplu dosya oluşturmada tek kilit/işlem içinde oluşturulan en fazla dosya; aradaki
+// bütçe denetimi büyük arşivlerde önbelleğin kirli bloklarla dolmasını önler.
+const CREATE_BATCH_MAX: usize = 256;
+
+// İçe aktarmada kaynaktan tek seferde okunup yazılan dosya verisi (blok).
+const IMPORT_CHUNK_BLOCKS: usize = 64;
+
+// Bir scrub adımında taranan en büyük bölge (blok); hız sınırı yoksa her adım bu kadar ilerler.
+const SCRUB_MAX_STEP_BLOCKS: u64 = 1024;
+
+// Scrub ilerlemesinin Superblock'a kaydedilmesi (commit) için gereken ilerleme (blok).
+const SCRUB_CHECKPOINT_BLOCKS: u64 = 8 * 1024;
+
 // Ana Dosya Sistemi Hata Türü
 #[derive(Debug)]
 pub enum SadakFsError<D: BlockDevice> {
@@ -29,6 +174,60 @@ pub enum SadakFsError<D: BlockDevice> {
     Allocator(AllocatorError<D>),
     ChecksumError,
     InvalidSuperblock,
+    /// Montaj denetimi sırasında checksum'u tutmayan metadata bloğu bulundu.
+    MetadataCorrupted(BlockId),
+    /// Dışa aktarım tanıtıcısının işaret ettiği inode silinmiş veya yeniden kullanılmış (ESTALE).
+    StaleHandle,
+    /// Dizinde bu isimde bir girdi yok (ENOENT).
+    NotFound,
+    /// Dizinde bu isimde bir girdi zaten var (EEXIST).
+    AlreadyExists,
+    /// Dizin beklenen yerde başka türde bir inode verildi (ENOTDIR).
+    NotADirectory,
+    /// İsim boş, çok uzun veya geçersiz karakter içeriyor.
+    InvalidName,
+    /// Dosya beklenen bir işlemde dizin verildi (EISDIR).
+    IsADirectory,
+    /// Silinmek istenen dizin boş değil (ENOTEMPTY).
+    DirectoryNotEmpty,
+    /// Değer tek bir metadata öğesine sığmıyor.
+    ValueTooLarge,
+    /// Çağıranın inode üzerinde istenen izni yok (EACCES).
+    PermissionDenied,
+    /// Aygıt, superblock'un beklediğinden az blok bildiriyor (ör. yanlış kopyalanmış imaj).
+    /// Yalnızca `EarlyFs::mount_salvage` ile salt okunur monte edilebilir.
+    DeviceTooSmall { expected: u64, actual: u64 },
+    /// Birim, bu sürümün tanımadığı özellikler kullanıyor (bkz. `FeatureSet`): `incompat`
+    /// bitleriyle monte edilemez, `ro_compat` bitleriyle yazılabilir yapılamaz.
+    UnsupportedFeatures(u32),
+    /// Kurtarma montajında istenen blok aygıtın sonunun ötesinde kaldı.
+    BlockOutOfRange(BlockId),
+    /// Birim mühürlü (bkz. `SadakFs::seal`); yalnızca `EarlyFs` ile salt okunur monte
+    /// edilebilir (EROFS).
+    Sealed,
+    /// Dosya yalnızca eklenebilir bir günlük; mevcut içeriği değiştirilemez (EPERM).
+    AppendOnly,
+    /// Birim salt okunur (bkz. `SadakFs::mount_readonly`, `SadakFs::set_readonly`); yazma reddedildi (EROFS).
+    ReadOnly,
+    /// Küçültmede, kesilecek alanda taşınamayan canlı bir blok kaldı (bkz. `SadakFs::shrink`).
+    ShrinkBlocked(BlockId),
+    /// Açık bir transaction varken yapılamayan işlem (bkz. `SadakFs::begin_transaction`) (EBUSY).
+    TransactionActive,
+    /// Artımlı çoğaltma akışının temel snapshot'ı hedef aygıttaki halle uyuşmuyor (bkz.
+    /// `SadakFs::receive`).
+    StreamBaseMismatch,
+    /// Sıkıştırılmış bir extent'in verisi (ilk bloğu) çözülemedi.
+    DataCorrupted(BlockId),
+    /// Birimi daha yeni bir çit dönemiyle başka bir düğüm devraldı; bu düğümün yazmaları
+    /// reddedilir ve birim salt okunur yapılır (bkz. `SadakFs::bump_fence_epoch`).
+    Fenced,
+    /// Dosya, anahtarlıkta olmayan bir anahtarla şifreli (bkz. `SadakFs::add_file_key`);
+    /// içeriği okunamaz ve yazılamaz (ENOKEY).
+    KeyUnavailable,
+    /// Başarısız bir işlemin yarım kalan değişiklikleri geri alınamadı (işlem sırasında
+    /// geri yazma yapıldı veya kopya için bellek ayrılamadı); birim salt okunur yapıldı
+    /// (bkz. `SadakFs::atomic_locked`) (EIO).
+    RollbackFailed,
     Syscall(SyscallError),
     // Diğer hatalar...
 }
@@ -53,13 +252,320 @@ impl<D: BlockDevice> From<SyscallError> for SadakFsError<D> {
 }
 
 
+/// Periyodik denetim süresi dolduğunda montajın ne yapacağı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckPolicy {
+    /// Sayaçlar güncellenir ama denetim önerilmez.
+    Ignore,
+    /// Montaj normal yapılır; `check_recommended` denetimin gerekçesini döndürür.
+    Recommend,
+    /// Montaj, tam metadata doğrulamasıyla (`MountVerify::Deep`) yapılır.
+    Force,
+}
+
+/// Periyodik tam denetimin neden gerektiği.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckReason {
+    /// Son denetimden bu yana `max_mount_count` montaja ulaşıldı.
+    MountCount,
+    /// Son denetimden bu yana `check_interval` süresi geçti.
+    Interval,
+}
+
+/// `open` davranışını belirleyen bayraklar (birleştirmek için `|` kullanılır).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenFlags(u32);
+
+impl OpenFlags {
+    /// Okuma için aç.
+    pub const READ: OpenFlags = OpenFlags(1 << 0);
+    /// Yazma için aç (dizinlerde `IsADirectory` döner).
+    pub const WRITE: OpenFlags = OpenFlags(1 << 1);
+    /// Son bileşen yoksa boş bir dosya olarak oluştur.
+    pub const CREATE: OpenFlags = OpenFlags(1 << 2);
+    /// `CREATE` ile birlikte: son bileşen zaten varsa `AlreadyExists` döndür.
+    pub const EXCLUSIVE: OpenFlags = OpenFlags(1 << 3);
+    /// Son bileşen bir dizin olmalı.
+    pub const DIRECTORY: OpenFlags = OpenFlags(1 << 4);
+
+    /// `other`'daki tüm bayraklar açık mı?
+    pub const fn contains(&self, other: OpenFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for OpenFlags {
+    type Output = OpenFlags;
+
+    fn bitor(self, other: OpenFlags) -> OpenFlags {
+        OpenFlags(self.0 | other.0)
+    }
+}
+
+
+/// Montaj sırasında kritik metadata'nın ne kadar derin doğrulanacağı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MountVerify {
+    /// Superblock, tahsis haritası blokları, alan haritası kontrol noktası ve ağaç
+    /// köklerinin ilk seviyeleri doğrulanır; derin doğrulama arka plandaki scrub işlemine bırakılır.
+    Fast,
+    /// Hızlı denetime ek olarak tüm metadata ağacı montaj sırasında doğrulanır.
+    Deep,
+}
+
+/// Commit sırasında bir G/Ç veya metadata hatası oluştuğunda ne yapılacağı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorsPolicy {
+    /// Hata çağırana döner; birim yazılabilir kalır ve sonraki commit yeniden denenir.
+    Continue,
+    /// Hata çağırana döner ve birim salt okunur yapılır; son tutarlı commit korunur.
+    RemountReadOnly,
+    /// Sistem durdurulur (panic); hatalı bir birimle devam etmek yerine yeniden başlatılır.
+    /// `no_panic` özelliğiyle derlenmişse `RemountReadOnly` gibi davranır.
+    Panic,
+}
+
+/// Dosya verisinin çökmelere karşı nasıl yazılacağı (ext4'teki `data=` seçeneği).
+///
+/// CoW ile yazılan veri her iki kipte de yeni bloklara, Superblock'tan önce yazılır;
+/// yarım kalan bir yazma hiçbir commit'e ait olmadığından görünmez. Kipler yalnızca
+/// yerinde güncellenen veride (nocow alt birimleri, bkz. `SadakFs::set_subvolume_nocow`)
+/// ayrışır.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DataMode {
+    /// Yerinde güncellenen veri doğrudan yerine yazılır; bir çökmede blok yarım kalabilir.
+    #[default]
+    Ordered,
+    /// Yerinde güncellenen veri önce niyet günlüğüne yazılır, commit'ten sonra yerine
+    /// aktarılır (`data=journal`). Günlükte yer yoksa blok CoW ile yazılır; commit'ler
+    /// günlüğe sığacak kadar sık yapılır. Yarım yazmalara karşı bağışıktır, verimi düşürür.
+    Journal,
+}
+
+/// Montaj ayarları (bkz. `SadakFs::mount`). Varsayılanlar önceki sıfır ayarlı montajla
+/// aynıdır: yazılabilir, hızlı denetim, periyodik denetim önerilir, varsayılan önbellek
+/// boyutu, yalnızca kirli blok bütçesiyle commit, hatada devam ve sıralı veri yazımı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MountOptions {
+    /// Salt okunur montaj (bkz. `SadakFs::mount_readonly`).
+    pub read_only: bool,
+    /// Montajdaki metadata denetiminin derinliği.
+    pub verify: MountVerify,
+    /// Periyodik denetimin süresi dolduysa uygulanacak politika.
+    pub check_policy: CheckPolicy,
+    /// Blok önbelleğinin kapasitesi (blok; en az `CACHE_MIN_BLOCKS`).
+    pub cache_blocks: usize,
+    /// Son commit'ten bu kadar süre (sistem saati birimi) geçtiyse, kirli blok bütçesi
+    /// aşılmasa da sıradaki işlem sonunda commit edilir (0 = kapalı).
+    pub commit_interval: u64,
+    /// Commit hatasında uygulanacak politika.
+    pub errors: ErrorsPolicy,
+    /// Dosya verisinin yazım kipi.
+    pub data: DataMode,
+    /// Paylaşılan depolama: her commit'ten önce diskteki çit dönemi okunur; başka bir düğüm
+    /// dönemi ilerlettiyse yazma `Fenced` ile reddedilir (bkz. `SadakFs::bump_fence_epoch`).
+    pub fencing: bool,
+    /// Metadata ağacının tamamı montajda önbelleğe yüklenir ve tahliye edilmez; aramalar
+    /// diske inmez. Değişiklikler yine CoW ile commit edilir. Küçük birimler içindir: ağaç
+    /// önbellek kapasitesinin yarısını aşarsa sabitleme bırakılır (bkz. `SadakFs::metadata_pinned`).
+    pub pin_metadata: bool,
+}
+
+impl Default for MountOptions {
+    fn default() -> Self {
+        MountOptions {
+            read_only: false,
+            verify: MountVerify::Fast,
+            check_policy: CheckPolicy::Recommend,
+            cache_blocks: CACHE_CAPACITY_BLOCKS,
+            commit_interval: 0,
+            errors: ErrorsPolicy::Continue,
+            data: DataMode::Ordered,
+            fencing: false,
+            pin_metadata: false,
+        }
+    }
+}
+
+/// Birimin disk biçimi özellikleri; ext4 tarzı üç bit haritası. Yeni bir biçim değişikliği,
+/// eski sürümlerin ne yapması gerektiğine göre üç kümeden birine eklenir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FeatureSet {
+    /// Bilmeyen sürümün yok sayabileceği özellikler.
+    pub compat: u32,
+    /// Bilmeyen sürümün yalnızca salt okunur monte edebileceği özellikler (`FEATURE_RO_COMPAT_*`).
+    pub ro_compat: u32,
+    /// Bilmeyen sürümün hiç monte edemeyeceği özellikler (`FEATURE_INCOMPAT_*`).
+    pub incompat: u32,
+}
+
+impl FeatureSet {
+    /// Bu sürümün tanımadığı özellikler.
+    pub fn unknown(&self) -> FeatureSet {
+        FeatureSet {
+            compat: self.compat & !FEATURES_SUPPORTED.compat,
+            ro_compat: self.ro_compat & !FEATURES_SUPPORTED.ro_compat,
+            incompat: self.incompat & !FEATURES_SUPPORTED.incompat,
+        }
+    }
+
+    /// Hiç özellik yok mu?
+    pub fn is_empty(&self) -> bool {
+        self.compat == 0 && self.ro_compat == 0 && self.incompat == 0
+    }
+}
+
+/// Biçimlendirme ayarları (bkz. `SadakFs::format`). Varsayılanlar önceki sabit
+/// biçimlendirmeyle aynıdır; yalnızca kuyruk paketleme özelliği açık gelir.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Blok boyutu (bayt). Bu sürüm yalnızca derleme zamanındaki `BLOCK_SIZE`'ı destekler.
+    pub block_size: usize,
+    /// Dosya verisine verilmeyip metadata için ayrılan alanın yüzdesi (0-50).
+    pub reserved_percent: u8,
+    /// Tahsis haritasının ilk bloğu (en az 1). Blok 0 ile harita arasındaki bloklar
+    /// ayrılmış kalır (ör. önyükleyici alanı); `superblock_slots` açıksa Blok 1 Superblock'un
+    /// B yuvasıdır ve ayrılmış alan Blok 2'den başlar.
+    pub bitmap_start: BlockId,
+    /// Superblock iki yuvaya (Blok 0 ve `SUPERBLOCK_B_BLOCK`) dönüşümlü yazılır; yarıda
+    /// kalan bir Superblock yazması bir önceki commit'i bozmaz. `bitmap_start` 1 ise
+    /// Blok 1 haritaya ait olduğundan kapalı kalır.
+    pub superblock_slots: bool,
+    /// Birim etiketi (en fazla `VOLUME_LABEL_MAX` bayt, NUL içeremez).
+    pub label: String,
+    /// İsteğe bağlı başlangıç özellikleri. Ayarların gerektirdiği özellikler (ayrılan alan,
+    /// checksum politikası) biçimlendirmede kendiliğinden eklenir.
+    pub features: FeatureSet,
+    /// Nesne türü başına checksum algoritmaları (Superblock her zaman CRC32C'dir).
+    pub checksums: ChecksumPolicy,
+    /// Metadata ağacının düğüm boyutu (bayt): `BTREE_NODE_SIZE` ile `BTREE_NODE_SIZE_MAX`
+    /// arasında ikinin kuvveti. Büyük düğümler ağacı alçaltır ve hızlı aygıtlarda metadata
+    /// I/O sayısını azaltır; ardışık boş alan gerektirir.
+    pub metadata_node_size: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            block_size: BLOCK_SIZE,
+            reserved_percent: 0,
+            bitmap_start: 2,
+            superblock_slots: true,
+            label: String::new(),
+            features: FeatureSet { incompat: FEATURE_INCOMPAT_TAIL_PACKING, ..FeatureSet::default() },
+            checksums: ChecksumPolicy::default(),
+            metadata_node_size: BTREE_NODE_SIZE,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Varsayılan ayarlar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size;
+        self
+    }
+
+    pub fn reserved_percent(mut self, percent: u8) -> Self {
+        self.reserved_percent = percent;
+        self
+    }
+
+    pub fn bitmap_start(mut self, start: BlockId) -> Self {
+        self.bitmap_start = start;
+        self
+    }
+
+    pub fn superblock_slots(mut self, enabled: bool) -> Self {
+        self.superblock_slots = enabled;
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = String::from(label);
+        self
+    }
+
+    pub fn features(mut self, features: FeatureSet) -> Self {
+        self.features = features;
+        self
+    }
+
+    pub fn checksums(mut self, checksums: ChecksumPolicy) -> Self {
+        self.checksums = checksums;
+        self
+    }
+
+    pub fn metadata_node_size(mut self, size: usize) -> Self {
+        self.metadata_node_size = size;
+        self
+    }
+}
+
+impl MountOptions {
+    /// Varsayılan ayarlar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn verify(mut self, verify: MountVerify) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    pub fn check_policy(mut self, policy: CheckPolicy) -> Self {
+        self.check_policy = policy;
+        self
+    }
+
+    pub fn cache_blocks(mut self, blocks: usize) -> Self {
+        self.cache_blocks = blocks;
+        self
+    }
+
+    pub fn commit_interval(mut self, interval: u64) -> Self {
+        self.commit_interval = interval;
+        self
+    }
+
+    pub fn errors(mut self, policy: ErrorsPolicy) -> Self {
+        self.errors = policy;
+        self
+    }
+
+    pub fn data(mut self, mode: DataMode) -> Self {
+        self.data = mode;
+        self
+    }
+
+    pub fn fencing(mut self, enabled: bool) -> Self {
+        self.fencing = enabled;
+        self
+    }
+
+    pub fn pin_metadata(mut self, enabled: bool) -> Self {
+        self.pin_metadata = enabled;
+        self
+    }
+}
+
+
 // --- 2. Superblock Yapısı ---
 
 /// Dosya sisteminin diskteki ilk bloğunda (BlockId 0) yer alan ana metadata.
+/// Alanlar, repr(C) hizalama boşluğu oluşmayacak şekilde büyükten küçüğe sıralanmıştır.
 #[repr(C)]
 pub struct Superblock {
     pub magic: u64, // Sihirli sayı: SADAK_MAGIC
-    pub version: u16,
     pub total_blocks: BlockId,
     /// Metadata B-Ağacının kök bloğunun ID'si (Dizinler, Dosyalar)
     pub metadata_root_id: BlockId, 
@@ -67,30 +573,649 @@ pub struct Superblock {
     pub bitmap_start_id: BlockId, 
     /// Son commit zamanı (SYSCALL_GET_SYSTEM_TIME ile alınır)
     pub timestamp: u64,
+    /// Sıradaki oluşturulacak inode'a verilecek nesil (generation) numarası
+    pub next_generation: u64,
+    /// Sıradaki oluşturulacak inode'a verilecek inode numarası (bkz. `inode_table`)
+    pub next_ino: u64,
+    /// Aygıtın başındaki hızlı katmanın (SSD) blok sayısı; 0 ise katmanlama yok
+    pub fast_tier_blocks: u64,
+    /// Kök dizinin inode blok ID'si
+    pub root_dir_inode: BlockId,
+    /// Son tam metadata denetiminin zamanı
+    pub last_check_time: u64,
+    /// İki tam denetim arasındaki en uzun süre (0 = süre sınırı yok)
+    pub check_interval: u64,
+    /// Alan haritası kontrol noktasının (grup başına boş blok sayıları) ilk bloğu (0 = yok)
+    pub space_map_root: BlockId,
+    /// Geçerli değişiklik dönemi; her snapshot'ta bir artar (bkz. `changes`)
+    pub change_epoch: u64,
+    /// Süren scrub geçişinde doğrulanacak sıradaki blok (0 = yeni geçiş başlayacak)
+    pub scrub_cursor: BlockId,
+    /// Son tamamlanan scrub geçişinin zamanı (0 = hiç tamamlanmadı)
+    pub last_scrub_time: u64,
+    /// Mühürlü birimde Merkle özet ağacının ilk bloğu (0 = mühürsüz; bkz. `seal`)
+    pub seal_tree_start: BlockId,
+    /// Son temiz ayırmada yazılan sıcak blok listesi (0 = yok; bkz. `save_warm_set`)
+    pub warm_set_block: BlockId,
+    /// Dosya verisine verilmeyen, metadata için ayrılmış blok sayısı
+    pub reserved_blocks: u64,
+    /// Tespit edilen bozuklukların adli kayıt günlüğü bloğu (0 = yok; bkz. `forensics`)
+    pub forensic_log_block: BlockId,
+    /// Metadata niyet günlüğü bölgesinin ilk bloğu (0 = yok; bkz. `journal`)
+    pub journal_start: BlockId,
+    /// Son commit'in sırası; günlük başlığı bununla eşleşirse montajda yeniden uygulanır
+    pub journal_seq: u64,
+    /// Paylaşılan depolamada yazma hakkının çit dönemi; küme yöneticisi devirde artırır
+    /// (bkz. `SadakFs::bump_fence_epoch`)
+    pub fence_epoch: u64,
     /// Superblock'un Checksum'u
     pub checksum: u32,
+    /// Son tam denetimden bu yana yapılan montaj sayısı
+    pub mount_count: u32,
+    /// Tam denetim önerilmeden önceki en fazla montaj sayısı (0 = sınır yok)
+    pub max_mount_count: u32,
+    /// Birim durumu: `VOLUME_STATE_CLEAN` ise son montaj `unmount` ile kapatıldı
+    pub state: u32,
+    /// Bilmeyen sürümün yok sayabileceği özellikler (bkz. `FeatureSet`)
+    pub feature_compat: u32,
+    /// Bilmeyen sürümün yalnızca salt okunur monte edebileceği özellikler
+    pub feature_ro_compat: u32,
+    /// Bilmeyen sürümün monte edemeyeceği özellikler
+    pub feature_incompat: u32,
+    pub version: u16,
+    /// Birimi aygıt yolundan bağımsız tanımlayan kimlik (format sırasında üretilir)
+    pub uuid: [u8; VOLUME_UUID_SIZE],
+    /// İnsan tarafından okunabilir birim etiketi (UTF-8, sonu NUL ile doldurulur)
+    pub label: [u8; VOLUME_LABEL_MAX],
+    /// Mühürlü birimde özet ağacının kök özeti (SHA-256)
+    pub seal_root: [u8; SEAL_HASH_SIZE],
+    /// Nesne türü başına checksum algoritmaları (`ChecksumPolicy::to_raw`; sıfırlar = CRC32C)
+    pub checksum_algorithms: [u8; CHECKSUM_POLICY_SIZE],
+    /// Niyet günlüğü bölgesindeki `JOURNAL_BLOCKS` bloklu kesim sayısı (0 = tek kesim)
+    pub journal_segments: u16,
+    /// Metadata ağacının düğüm boyutu (bayt; 0 = `BTREE_NODE_SIZE`)
+    pub metadata_node_size: u32,
     
     // Superblock'u 4096 bayta tamamlamak için doldurma (padding)
-    padding: [u8; BLOCK_SIZE - (mem::size_of::<u64>() * 3 + mem::size_of::<u16>() + mem::size_of::<u32>() * 2 + mem::size_of::<u64>() * 2 + mem::size_of::<u8>() * 0)], 
+    padding: [u8; SUPERBLOCK_PADDING], 
+}
+
+// Superblock'u bir bloğa tamamlayan doldurma; yeni alan eklendiğinde boyutu buraya da eklenmelidir.
+const SUPERBLOCK_PADDING: usize = BLOCK_SIZE - (mem::size_of::<u64>() * 22 + mem::size_of::<u32>() * 8 + mem::size_of::<u16>() * 2 + VOLUME_UUID_SIZE + VOLUME_LABEL_MAX + SEAL_HASH_SIZE + CHECKSUM_POLICY_SIZE);
+
+// Superblock tam olarak bir blok kaplamalıdır (derleme zamanı kontrolü).
+const _: () = assert!(mem::size_of::<Superblock>() == BLOCK_SIZE);
+
+impl Superblock {
+    /// Blok 0'ın ham verisini Superblock olarak çözer; sihirli sayı veya checksum
+    /// tutmuyorsa (aygıtta SADAK birimi yoksa) `None`.
+    pub fn from_block(data: &[u8]) -> Option<Self> {
+        if data.len() < BLOCK_SIZE {
+            return None;
+        }
+        let superblock = unsafe { (data.as_ptr() as *const Superblock).read_unaligned() };
+
+        if superblock.magic != SADAK_MAGIC || superblock_checksum(&data[..BLOCK_SIZE]) != superblock.checksum {
+            return None;
+        }
+        Some(superblock)
+    }
+
+    /// Superblock yuvalarının (Blok 0 ve `SUPERBLOCK_B_BLOCK`) ham verisinden geçerli olan
+    /// en yenisini seçer. B yuvası yalnızca `FEATURE_COMPAT_SUPERBLOCK_SLOTS` taşıyorsa
+    /// ve (A da geçerliyse) aynı birime aitse dikkate alınır.
+    ///
+    /// # Döndürür
+    /// Superblock ve okunduğu yuva; ikisi de geçersizse `None`.
+    pub fn from_slots(a: &[u8], b: &[u8]) -> Option<(Self, BlockId)> {
+        let a = Self::from_block(a);
+        let b = Self::from_block(b).filter(|sb| sb.feature_compat & FEATURE_COMPAT_SUPERBLOCK_SLOTS != 0);
+        match (a, b) {
+            (Some(a), Some(b)) if b.uuid == a.uuid && b.journal_seq > a.journal_seq => Some((b, SUPERBLOCK_B_BLOCK)),
+            (Some(a), _) => Some((a, 0)),
+            (None, Some(b)) => Some((b, SUPERBLOCK_B_BLOCK)),
+            (None, None) => None,
+        }
+    }
+
+    /// Birimin disk biçimi özellikleri.
+    pub fn features(&self) -> FeatureSet {
+        FeatureSet {
+            compat: self.feature_compat,
+            ro_compat: self.feature_ro_compat,
+            incompat: self.feature_incompat,
+        }
+    }
+
+    /// Nesne türü başına checksum algoritmaları; tanınmayan kod CRC32C sayılır (montaj
+    /// böyle bir Superblock'u zaten reddeder).
+    pub fn checksum_policy(&self) -> ChecksumPolicy {
+        ChecksumPolicy::from_raw(&self.checksum_algorithms).unwrap_or_default()
+    }
+
+    /// Niyet günlüğü bölgesinin blok sayısı (tüm kesimler).
+    pub fn journal_blocks(&self) -> u64 {
+        JOURNAL_BLOCKS * self.journal_segments.max(1) as u64
+    }
+
+    /// Tek commit'te günlüğe alınabilecek en fazla yerinde yazılan blok.
+    pub fn journal_capacity(&self) -> usize {
+        JOURNAL_CAPACITY * self.journal_segments.max(1) as usize
+    }
+
+    /// Birimin etiketi (sondaki NUL baytlar hariç); geçerli UTF-8 değilse boş.
+    pub fn label(&self) -> &str {
+        let len = self.label.iter().position(|&b| b == 0).unwrap_or(VOLUME_LABEL_MAX);
+        core::str::from_utf8(&self.label[..len]).unwrap_or("")
+    }
+}
+
+/// Format sırasında birim kimliği üretir. Sahne64'te rastgele sayı kaynağı olmadığından
+/// kimlik; zaman, aygıt boyutu ve görev kimliğinden karıştırılarak (splitmix64) elde edilir
+/// ve RFC 4122 sürüm 4 biçiminde işaretlenir.
+fn generate_uuid(seed: u64) -> [u8; VOLUME_UUID_SIZE] {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    };
+
+    let mut uuid = [0u8; VOLUME_UUID_SIZE];
+    uuid[0..8].copy_from_slice(&next().to_le_bytes());
+    uuid[8..16].copy_from_slice(&next().to_le_bytes());
+    uuid[6] = (uuid[6] & 0x0F) | 0x40; // Sürüm 4
+    uuid[8] = (uuid[8] & 0x3F) | 0x80; // RFC 4122 varyantı
+    uuid
+}
+
+/// Superblock bloğunun checksum'unu, `checksum` alanını sıfır sayarak hesaplar.
+fn superblock_checksum(data: &[u8]) -> u32 {
+    checksum::checksum_excluding(data, mem::offset_of!(Superblock, checksum), mem::size_of::<u32>())
+}
+
+/// Superblock'taki sayaçlara göre periyodik tam denetimin gelip gelmediğini döndürür.
+fn check_due(superblock: &Superblock, now: u64) -> Option<CheckReason> {
+    if superblock.max_mount_count != 0 && superblock.mount_count >= superblock.max_mount_count {
+        return Some(CheckReason::MountCount);
+    }
+    if superblock.check_interval != 0 && now.saturating_sub(superblock.last_check_time) >= superblock.check_interval {
+        return Some(CheckReason::Interval);
+    }
+    None
 }
 
 
 // --- 2.5. Inode Yapısı (Dosya/Dizin Metadata'sı) ---
 
+// Inode türleri (Inode::file_type)
+pub const FILE_TYPE_REGULAR: u8 = 1;
+pub const FILE_TYPE_DIRECTORY: u8 = 2;
+/// Yalnızca kayıt eklenebilen denetim günlüğü (bkz. `SadakFs::append_log`).
+pub const FILE_TYPE_LOG: u8 = 3;
+/// Sembolik bağlantı; hedef yol dosya verisi olarak tutulur (bkz. `SadakFs::read_link`).
+pub const FILE_TYPE_SYMLINK: u8 = 4;
+
+// Sembolik bağlantı hedefinin bayt cinsinden en büyük uzunluğu.
+pub const SYMLINK_TARGET_MAX: usize = BLOCK_SIZE;
+
+// Inode bayrakları (Inode::flags)
+/// Geri yazılan veri sıkıştırılır (bkz. `SadakFs::set_compression`).
+pub const INODE_FLAG_COMPRESS: u8 = 1 << 0;
+/// Dizinin girdileri ad özetiyle anahtarlanmış (bkz. `dir::DirEntry`).
+pub const INODE_FLAG_DIR_HASHED: u8 = 1 << 1;
+/// Dizinde adlar ASCII büyük/küçük harf ayrımsız aranır (bkz. `dir::Casefold`).
+pub const INODE_FLAG_CASEFOLD: u8 = 1 << 2;
+/// `INODE_FLAG_CASEFOLD` ile birlikte: katlama ASCII yerine Unicode basit harf katlamasıyla.
+pub const INODE_FLAG_CASEFOLD_UNICODE: u8 = 1 << 3;
+/// `INODE_FLAG_DIR_HASHED` ile birlikte: eski doğrusal girdilerin bir kısmı henüz ad özetli
+/// düzene taşınmadı (bkz. `dir::DirMigration`).
+pub const INODE_FLAG_DIR_MIGRATING: u8 = 1 << 4;
+
 /// Diskteki bir dosyayı veya dizini temsil eden metadata yapısı.
+/// Dizinlerde `file_size` girdi sayısını tutar ve `data_tree_root` 0'dır; girdiler
+/// metadata ağacında saklanır (bkz. `dir::DirEntry`).
+/// Superblock gibi, alanlar hizalama boşluğu oluşmayacak şekilde büyükten küçüğe sıralanmıştır.
 #[repr(C)]
 pub struct Inode {
     pub file_size: u64, // Dosyanın bayt cinsinden boyutu
     pub block_count: u64, // Dosyanın kullandığı blok sayısı
     pub creation_time: u64,
     pub modification_time: u64,
-    pub file_type: u8, // 1=Dosya, 2=Dizin
     // Dosya veri bloklarına işaret eden doğrudan işaretçiler (CoW B-Ağacı kökleri)
     pub data_tree_root: BlockId, 
+    /// Inode'un bulunduğu bloğun ID'si (Self-referans; sayfa önbelleği anahtarı)
+    pub inode_id: BlockId,
+    /// Inode yaşadığı sürece değişmeyen inode numarası (bkz. `SadakFs::get_inode`); inode
+    /// tablosundan önce oluşturulmuş inode'larda 0'dır
+    pub ino: u64,
+    /// Oluşturulurken verilen nesil numarası; aynı blok yeni bir inode için
+    /// yeniden kullanıldığında eski tanıtıcıları ayırt etmeye yarar.
+    pub generation: u64,
+    /// Dizinlerde üst dizinin inode'u (`..` çözümlemesi için; kökte kendisi), dosyalarda 0
+    pub parent_dir: BlockId,
+    /// Inode'un ait olduğu alt birim (`Subvolume::id`; 0 = ana ad alanı); kotalar için
+    pub subvolume_id: u64,
+    /// Inode'un metadata'sı veya verisi her değiştiğinde artan sayaç (bkz. `SadakFs::change_seq`)
+    pub change_seq: u64,
+    /// Dosya verisini şifreleyen anahtarın kimliği (`FileKey::id`); 0 = şifresiz
+    pub key_id: u64,
+    /// Şifreli dosyanın ayar (tweak) değerlerini diğer dosyalarınkinden ayıran, birim içinde
+    /// tekil sayı; klonlar kaynağınkini devralır
+    pub crypt_nonce: u64,
     pub link_count: u32,
     pub checksum: u32,
+    /// Sahibin kullanıcı kimliği
+    pub uid: u32,
+    /// Sahibin grup kimliği
+    pub gid: u32,
+    /// POSIX izin bitleri (`MODE_MASK`); 0, izinlerden önce oluşturulmuş bir inode'dur
+    pub mode: u16,
+    pub file_type: u8, // 1=Dosya, 2=Dizin
+    /// Yerleşim ipucu (`PlacementHint::to_raw`): sıcak/soğuk, ardışık/rastgele
+    pub placement_hint: u8,
+    /// Dosya bayrakları (`INODE_FLAG_*`)
+    pub flags: u8,
+    /// `INODE_FLAG_COMPRESS` açıkken kullanılan algoritma (`Compression::to_raw`; 0 LZ4)
+    pub compression: u8,
+    /// Veri ağacının seviye sayısı (`BTree::height`); ağaç büyüdükçe ve alçaldıkça veri
+    /// ağacıyla birlikte güncellenir. 0: veri ağacı yok ya da yükseklik kaydından önce yazılmış inode
+    pub data_tree_height: u8,
     // Doldurma
-    padding: [u8; 128], // Toplam 256 bayt varsayalım
+    padding: [u8; INODE_PADDING], 
+}
+
+// Inode'u 256 bayta tamamlayan doldurma; yeni alan eklendiğinde boyutu buraya da eklenmelidir.
+const INODE_PADDING: usize = 256 - (mem::size_of::<u64>() * 13 + mem::size_of::<u32>() * 4 + mem::size_of::<u16>() + mem::size_of::<u8>() * 5);
+
+const _: () = assert!(mem::size_of::<Inode>() == 256);
+
+/// Inode bloğunun `algorithm` ile checksum'unu, `checksum` alanını sıfır sayarak hesaplar.
+fn inode_checksum(algorithm: ChecksumAlgorithm, data: &[u8]) -> u32 {
+    algorithm.checksum_excluding(data, mem::offset_of!(Inode, checksum), mem::size_of::<u32>())
+}
+
+/// Inode bloğundaki bir u64 alanını (yerel bayt sırasıyla) okur.
+fn inode_field_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_ne_bytes(bytes)
+}
+
+// İzin bitlerinin geçerli kısmı (setuid, setgid, sticky ve rwx üçlüleri).
+pub const MODE_MASK: u16 = 0o7777;
+
+// Yeni dosya ve dizinlerin ve izinlerden önce oluşturulmuş inode'ların varsayılan izinleri.
+pub const DEFAULT_FILE_MODE: u16 = 0o644;
+pub const DEFAULT_DIR_MODE: u16 = 0o755;
+pub const DEFAULT_SYMLINK_MODE: u16 = 0o777;
+
+/// İzin denetiminde istenen erişim türleri (birleştirmek için `|` kullanılır).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Access(u16);
+
+impl Access {
+    /// Dosyayı okuma veya dizini listeleme.
+    pub const READ: Access = Access(0o4);
+    /// Dosyaya yazma veya dizinde girdi oluşturma/silme.
+    pub const WRITE: Access = Access(0o2);
+    /// Dosyayı çalıştırma veya dizinde arama (yol çözümleme).
+    pub const EXECUTE: Access = Access(0o1);
+}
+
+impl core::ops::BitOr for Access {
+    type Output = Access;
+
+    fn bitor(self, other: Access) -> Access {
+        Access(self.0 | other.0)
+    }
+}
+
+/// İzin denetiminde kullanılan çağıran kimliği.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Credentials {
+    pub uid: u32,
+    pub gid: u32,
+}
+
+impl Credentials {
+    /// Tüm izin denetimlerini geçen yönetici kimliği.
+    pub const ROOT: Credentials = Credentials { uid: 0, gid: 0 };
+
+    /// Çağıran Sahne64 görevinin kimliği. Sahne64'te henüz kullanıcı/grup kavramı
+    /// olmadığından görev kimliği hem uid hem gid olarak kullanılır.
+    pub fn current() -> Result<Self, SyscallError> {
+        let id = platform::task_id()? as u32;
+        Ok(Credentials { uid: id, gid: id })
+    }
+}
+
+impl Inode {
+    /// Denetimde kullanılan izin bitleri; izinlerden önce oluşturulmuş inode'lar
+    /// (`mode` 0) türlerinin varsayılan izinleriyle değerlendirilir.
+    pub fn effective_mode(&self) -> u16 {
+        match self.mode {
+            0 if self.file_type == FILE_TYPE_DIRECTORY => DEFAULT_DIR_MODE,
+            0 => DEFAULT_FILE_MODE,
+            mode => mode & MODE_MASK,
+        }
+    }
+
+    /// `cred` kimliğinin bu inode üzerinde `access` erişimine izni var mı?
+    ///
+    /// Yönetici (uid 0) her zaman geçer. Diğerleri için POSIX kuralı uygulanır: sahibe
+    /// sahip üçlüsü, gruba grup üçlüsü, geri kalanlara diğerleri üçlüsü bakılır.
+    pub fn permits(&self, cred: &Credentials, access: Access) -> bool {
+        if cred.uid == Credentials::ROOT.uid {
+            return true;
+        }
+
+        let mode = self.effective_mode();
+        let bits = if cred.uid == self.uid {
+            mode >> 6
+        } else if cred.gid == self.gid {
+            mode >> 3
+        } else {
+            mode
+        };
+        bits & access.0 == access.0
+    }
+}
+
+
+// --- 2.6. Dışa Aktarım Tanıtıcısı (9P/NFS) ---
+
+// Dışa aktarım tanıtıcısının serileştirilmiş boyutu: inode_id (8) + generation (8)
+pub const EXPORT_HANDLE_SIZE: usize = 16;
+
+/// Ağ istemcilerine verilen, sunucu yeniden başlasa da geçerli kalan opak dosya tanıtıcısı.
+/// Nesil numarası sayesinde, silinip yeniden kullanılan bir inode'a işaret eden eski
+/// tanıtıcılar yeni dosyaya çözülmek yerine `StaleHandle` hatası verir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportHandle {
+    inode_id: BlockId,
+    generation: u64,
+}
+
+impl ExportHandle {
+    /// Tanıtıcıyı istemciye gönderilecek opak baytlara çevirir.
+    pub fn to_bytes(&self) -> [u8; EXPORT_HANDLE_SIZE] {
+        let mut out = [0u8; EXPORT_HANDLE_SIZE];
+        out[0..8].copy_from_slice(&self.inode_id.to_le_bytes());
+        out[8..16].copy_from_slice(&self.generation.to_le_bytes());
+        out
+    }
+
+    /// İstemciden gelen opak baytları çözer; boyut uyuşmazsa `None`.
+    pub fn from_bytes(raw: &[u8]) -> Option<Self> {
+        if raw.len() != EXPORT_HANDLE_SIZE {
+            return None;
+        }
+
+        let mut inode_id = [0u8; 8];
+        let mut generation = [0u8; 8];
+        inode_id.copy_from_slice(&raw[0..8]);
+        generation.copy_from_slice(&raw[8..16]);
+
+        Some(ExportHandle {
+            inode_id: BlockId::from_le_bytes(inode_id),
+            generation: u64::from_le_bytes(generation),
+        })
+    }
+}
+
+
+// --- 2.7. Yazma Yükü Ölçümü ---
+
+/// CoW yolunun bir ölçüm aralığında yaptığı mantıksal ve fiziksel yazmalar.
+/// Commit aralığı ve düğüm boyutu ayarlarının etkisini ölçmek için kullanılır.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WriteStats {
+    /// Uygulamaların yazdığı dosya verisi (bayt).
+    pub logical_bytes: u64,
+    /// Sayfa önbelleğinden extent'lere doğrudan yazılan veri blokları.
+    pub data_blocks: u64,
+    /// Önbellekten geri yazılan bloklar: ağaç düğümü kopyaları, inode'lar, tahsis
+    /// haritası ve `write_at` ile yazılan veri blokları.
+    pub cached_blocks: u64,
+    /// Superblock yazmaları.
+    pub superblock_writes: u64,
+    /// Niyet günlüğüne yazılan bloklar (başlıklar ve blok görüntüleri).
+    pub journal_blocks: u64,
+    /// Tamamlanan commit sayısı.
+    pub commits: u64,
+}
+
+impl WriteStats {
+    /// Aygıta yazılan toplam bayt.
+    pub fn physical_bytes(&self) -> u64 {
+        (self.data_blocks + self.cached_blocks + self.superblock_writes + self.journal_blocks) * BLOCK_SIZE as u64
+    }
+
+    /// Yazma çoğaltması (fiziksel / mantıksal) binde bir cinsinden; aralıkta dosya
+    /// verisi yazılmadıysa `None`.
+    pub fn amplification_permille(&self) -> Option<u64> {
+        if self.logical_bytes == 0 {
+            return None;
+        }
+        Some(self.physical_bytes().saturating_mul(1000) / self.logical_bytes)
+    }
+}
+
+
+// --- 2.8. Alan Hesabı (Snapshot Paylaşımı) ---
+
+/// Bir dosyanın veya snapshot'ın kapladığı alan (blok cinsinden).
+#[cfg(feature = "snapshot")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SpaceUsage {
+    /// Nesnenin başvurduğu toplam blok sayısı.
+    pub referenced: u64,
+    /// Yalnızca bu nesnenin başvurduğu (paylaşılmayan) blok sayısı.
+    pub exclusive: u64,
+}
+
+/// Snapshot'larla paylaşılan blokları hesaba katan birim doluluk raporu (`df`).
+#[cfg(feature = "snapshot")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DiskUsage {
+    /// Birimdeki toplam blok sayısı.
+    pub total_blocks: u64,
+    /// Canlı dosya sisteminin kullandığı blok sayısı.
+    pub live_blocks: u64,
+    /// Yalnızca snapshot'ların tuttuğu (canlı durumda serbest görünen) blok sayısı.
+    pub snapshot_blocks: u64,
+    /// Gerçekten boş olan blok sayısı.
+    pub free_blocks: u64,
+}
+
+
+// --- 2.9. Scrub Zamanlaması ---
+
+/// Arka plan scrub'ının hız ayarları; çalışma sırasında `set_scrub_rate` ile değiştirilebilir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScrubRate {
+    /// Sistem saati birimi başına en fazla taranan bayt (0 = sınır yok). Saniye birimli
+    /// bir saatte MB/s sınırı `mb * 1024 * 1024` olarak verilir.
+    pub bytes_per_tick: u64,
+    /// `true` ise bir önceki adımdan beri dosya okuma/yazması yapıldıysa adım ertelenir.
+    pub idle_only: bool,
+}
+
+/// Tek bir `scrub_step` çağrısının sonucu.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ScrubReport {
+    /// Aygıttan okunarak doğrulanan blok sayısı.
+    pub verified_blocks: u64,
+    /// Okunamayan (ortam hatası veren) bloklar.
+    pub unreadable: Vec<BlockId>,
+    /// Checksum'u tutmayan ilk metadata düğümü (yalnızca geçişin başında denetlenir).
+    pub corrupt_metadata: Option<BlockId>,
+    /// Onarılamayan bozuk inode'lar ve veri ağacı düğümleri (yalnızca geçişin başında
+    /// denetlenir).
+    pub corrupt_file_metadata: Vec<BlockId>,
+    /// Bu adımda sağlam bir kopyadan onarılan bozuk bloklar.
+    pub repaired: Vec<BlockId>,
+    /// Adımdan sonra sıradaki doğrulanacak blok (0 = geçiş tamamlandı).
+    pub cursor: BlockId,
+    /// Bu adımda bir geçiş tamamlandı mı?
+    pub pass_complete: bool,
+    /// Adım hız sınırı veya meşgul aygıt nedeniyle hiç iş yapmadan döndü mü?
+    pub deferred: bool,
+}
+
+/// Scrub hız sınırının bellekteki durumu (kalıcı değildir).
+#[derive(Debug, Default)]
+struct ScrubState {
+    rate: ScrubRate,
+    /// Son adımın zamanı.
+    last_step: u64,
+    /// Hız sınırından biriken tarama hakkı (bayt).
+    credit: u64,
+    /// Son adımda görülen ön plan işlem sayacı.
+    seen_ops: u64,
+    /// Superblock'a en son kaydedilen scrub konumu.
+    checkpointed: BlockId,
+}
+
+impl ScrubState {
+    /// Geçen süreye göre biriken hakkı hesaplar ve bu adımda taranabilecek blok
+    /// sayısını düşer.
+    fn take_budget(&mut self, now: u64) -> u64 {
+        let max_credit = SCRUB_MAX_STEP_BLOCKS * BLOCK_SIZE as u64;
+        if self.rate.bytes_per_tick == 0 {
+            self.last_step = now;
+            return SCRUB_MAX_STEP_BLOCKS;
+        }
+
+        let elapsed = now.saturating_sub(self.last_step);
+        self.last_step = now;
+        self.credit = self.credit.saturating_add(elapsed.saturating_mul(self.rate.bytes_per_tick)).min(max_credit);
+        let blocks = self.credit / BLOCK_SIZE as u64;
+        self.credit -= blocks * BLOCK_SIZE as u64;
+        blocks
+    }
+}
+
+
+// --- 2.10. Kapasite Raporu (statfs) ---
+
+/// Birimin kapasite ve doluluk özeti (`statfs`); bkz. `SadakFs::stat_fs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FsStat {
+    /// Blok boyutu (bayt).
+    pub block_size: u64,
+    /// Birimdeki toplam blok sayısı.
+    pub total_blocks: u64,
+    /// Tahsis haritasına göre boş blok sayısı.
+    pub free_blocks: u64,
+    /// Bir sonraki commit'te serbest kalacak eski CoW blokları (`free_blocks`'a dahil değil).
+    pub reclaimable_blocks: u64,
+    /// Metadata'nın kullandığı bloklar: Superblock, tahsis haritası, alan haritası kontrol
+    /// noktası ve metadata ağacının düğümleri. Inode blokları ve dosyaların veri ağaçları
+    /// dahil değildir.
+    pub metadata_blocks: u64,
+    /// Kullanımdaki inode sayısı (alt birim kota sayaçlarının toplamı).
+    pub inodes: u64,
+    /// Daha oluşturulabilecek en fazla inode sayısı; her inode bir blok kapladığından
+    /// boş blok sayısıdır.
+    pub free_inodes: u64,
+}
+
+
+// --- 2.11. Denetim Günlüğü Kayıtları ---
+
+// Günlük kaydı başlığının sihirli sayısı ("SLOG")
+const LOG_RECORD_MAGIC: u32 = 0x534C_4F47;
+
+// Kayıt başlığı: magic (4) + uzunluk (4) + zaman damgası (8) + checksum (4) + ayrılmış (4)
+pub const LOG_RECORD_HEADER_SIZE: usize = 24;
+
+// Tek bir günlük kaydının en büyük veri boyutu.
+pub const LOG_RECORD_MAX: usize = 64 * 1024;
+
+/// Bir denetim günlüğünden okunan kayıt; bkz. `SadakFs::read_log`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    /// Kaydın günlükteki konumu.
+    pub offset: u64,
+    /// Kaydın eklendiği zaman.
+    pub timestamp: u64,
+    /// Kaydın verisi.
+    pub data: Vec<u8>,
+    /// Sonraki kaydın konumu.
+    pub next: u64,
+}
+
+/// Kaydı başlığıyla birlikte diskteki biçimine çevirir. Checksum başlık (checksum alanı
+/// sıfırken) ve veri üzerinden hesaplanır.
+fn encode_log_record(timestamp: u64, data: &[u8]) -> Vec<u8> {
+    let mut record = Vec::with_capacity(LOG_RECORD_HEADER_SIZE + data.len());
+    record.extend_from_slice(&LOG_RECORD_MAGIC.to_le_bytes());
+    record.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    record.extend_from_slice(&timestamp.to_le_bytes());
+    record.extend_from_slice(&[0u8; 8]);
+    record.extend_from_slice(data);
+    let checksum = checksum::checksum_data(&record);
+    record[16..20].copy_from_slice(&checksum.to_le_bytes());
+    record
+}
+
+
+// --- 2.12. Dizin İmleci ---
+
+// Dizin imlecinin serileştirilmiş boyutu: sonraki sıra numarası (8) + durum (8)
+pub const DIR_CURSOR_SIZE: usize = 16;
+
+// İmleç durumu bitleri
+const DIR_CURSOR_HASHED: u64 = 1 << 0; // Sıra numarası ad özetli düzene ait
+const DIR_CURSOR_END: u64 = 1 << 1;    // Dizinin sonuna ulaşıldı
+
+/// Bir dizin okumasının kaldığı yer (bkz. `SadakFs::read_dir_from`). İmleç girdinin kendisini
+/// değil, metadata ağacındaki sıra numarasını tutar; araya giren eklemeler ve silmeler
+/// okunmuş girdileri yinelemez, okunmamış ve silinmemiş girdileri atlatmaz. Araya eklenen
+/// girdiler görülebilir veya görülmeyebilir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirCursor {
+    next: u64,
+    state: u64,
+}
+
+impl DirCursor {
+    /// Dizinin başı.
+    pub const START: DirCursor = DirCursor { next: 0, state: 0 };
+
+    /// Dizinin sonuna ulaşıldı mı?
+    pub fn is_end(&self) -> bool {
+        self.state & DIR_CURSOR_END != 0
+    }
+
+    /// İmleci çağırana verilecek opak baytlara çevirir.
+    pub fn to_bytes(&self) -> [u8; DIR_CURSOR_SIZE] {
+        let mut out = [0u8; DIR_CURSOR_SIZE];
+        out[0..8].copy_from_slice(&self.next.to_le_bytes());
+        out[8..16].copy_from_slice(&self.state.to_le_bytes());
+        out
+    }
+
+    /// Çağırandan gelen opak baytları çözer; boyut uyuşmazsa `None`.
+    pub fn from_bytes(raw: &[u8]) -> Option<Self> {
+        if raw.len() != DIR_CURSOR_SIZE {
+            return None;
+        }
+
+        let mut next = [0u8; 8];
+        let mut state = [0u8; 8];
+        next.copy_from_slice(&raw[0..8]);
+        state.copy_from_slice(&raw[8..16]);
+
+        Some(DirCursor {
+            next: u64::from_le_bytes(next),
+            state: u64::from_le_bytes(state),
+        })
+    }
 }
 
 
@@ -106,8 +1231,61 @@ pub struct SadakFs<D: BlockDevice> {
     metadata_tree: BTree<D>,
     /// Dosya sistemi yapısını eş zamanlı koruyan kilit.
     lock: SysLock,
-    /// Dosya sistemi yapısının en son hali
-    superblock: Superblock,
+    /// Henüz disk bloğu tahsis edilmemiş dosya verisi yazmaları (gecikmeli tahsis).
+    pages: PageCache,
+    /// Dosya sistemi yapısının en son hali (yalnızca `lock` alınmışken değiştirilir)
+    superblock: UnsafeCell<Superblock>,
+    /// Ara commit tetiklenmeden önce izin verilen kirli blok sayısı.
+    dirty_block_budget: usize,
+    /// Montajda ertelenen derin metadata doğrulaması henüz yapılmadı mı? (yalnızca `lock` alınmışken)
+    deep_verify_pending: UnsafeCell<bool>,
+    /// Montaj sırasında süresi dolduğu görülen ama yapılmayan periyodik denetim.
+    check_recommended: Option<CheckReason>,
+    /// Son `take_write_stats` çağrısından beri biriken yazma sayaçları (yalnızca `lock` alınmışken).
+    write_stats: UnsafeCell<WriteStats>,
+    /// Görev başına I/O hesabı ve sınırları (verilmişse).
+    accounting: Option<Arc<IoAccounting>>,
+    /// Geçerli dönemde yerinde yazılan ve henüz değişen blok kayıtlarına eklenmemiş
+    /// bloklar (yalnızca `lock` alınmışken). Yeni tahsis edilen bloklar commit sırasında
+    /// tahsis yöneticisinden alınır.
+    changed_in_place: UnsafeCell<BTreeSet<BlockId>>,
+    /// Scrub hız ayarları ve sınır durumu (yalnızca `lock` alınmışken).
+    scrub: UnsafeCell<ScrubState>,
+    /// Dosya okuma/yazma çağrısı sayacı; scrub'ın boşta kalma denetimi için.
+    foreground_ops: AtomicU64,
+    /// Önceki montaj `unmount` ile temiz kapatılmış mıydı?
+    previous_unmount_clean: bool,
+    /// Zaman damgalarının kaynağı.
+    clock: Arc<dyn Clock>,
+    /// Salt okunur mu? Öyleyse tüm yazma yolları `ReadOnly` döner (bkz. `set_readonly`).
+    read_only: AtomicBool,
+    /// Yeni kuyruk parçalarının eklendiği son kuyruk bloğu (0 = yok); bkz. `pack_tail`.
+    open_tail_block: AtomicU64,
+    /// Zamana bağlı commit aralığı (0 = kapalı); bkz. `MountOptions::commit_interval`.
+    commit_interval: u64,
+    /// Commit hatasında uygulanacak politika.
+    errors: ErrorsPolicy,
+    /// Dosya verisinin yazım kipi.
+    data_mode: DataMode,
+    /// Commit öncesi diskteki çit dönemi denetlenir mi? (bkz. `MountOptions::fencing`)
+    fencing: bool,
+    /// Metadata ağacı önbellekte sabitli mi? (bkz. `MountOptions::pin_metadata`)
+    pin_metadata: AtomicBool,
+    /// Tespit edilen bozuklukların günlüğü (yalnızca `forensics_lock` alınmışken).
+    forensics: UnsafeCell<ForensicLog>,
+    /// Okuma yolunda bozuk bulunup bir sonraki scrub adımında onarılacak bloklar
+    /// (yalnızca `forensics_lock` alınmışken).
+    pending_repair: UnsafeCell<BTreeMap<BlockId, CorruptionSource>>,
+    /// Okuma yolları `lock`'u tutmadığından adli kayıtlar ayrı bir kilitle korunur.
+    forensics_lock: SysLock,
+    /// Açık tutulan inode'lar ve tutucu sayıları (yalnızca `lock` alınmışken; bkz. `hold_inode`).
+    open_inodes: UnsafeCell<BTreeMap<BlockId, u32>>,
+    /// Açık bir transaction var mı? Varken commit'ler ertelenir (bkz. `begin_transaction`).
+    transaction_active: AtomicBool,
+    /// Yüklü dosya anahtarları, kimliklerine göre (yalnızca `keys_lock` alınmışken; bkz. `add_file_key`).
+    file_keys: UnsafeCell<BTreeMap<u64, Arc<FileKey>>>,
+    /// Okuma yolları `lock`'u tutmadığından anahtarlık ayrı bir kilitle korunur.
+    keys_lock: SysLock,
 }
 
 impl<D: BlockDevice> SadakFs<D>
@@ -116,41 +1294,194 @@ where
 {
     // --- Başlatma ve Montaj İşlemleri ---
 
-    /// Mevcut bir diskten SADAK dosya sistemini yükler (Montaj).
-    pub fn mount(device: D) -> Result<Self, SadakFsError<D>> {
-        let cache = Arc::new(BlockCache::new(Arc::new(device))?);
-        
-        // 1. Superblock'u oku (Her zaman BlockId 0'da)
-        let sb_block = cache.get_block(0)?;
-        let sb_ref = unsafe { &mut *sb_block.get() };
-        
-        // Ham veriyi Superblock yapısına dönüştür (unsafe, tip dönüşümü)
-        let sb_ptr = sb_ref.data.as_ptr() as *const Superblock;
-        let superblock = unsafe { sb_ptr.read() };
-        
-        // 2. Superblock Checksum Doğrulaması
-        let calculated_crc = checksum::checksum_data(sb_ref.data.as_ref());
-
-        if superblock.magic != SADAK_MAGIC || calculated_crc != superblock.checksum {
-            // Checksum veya Magic Number uyuşmazlığı, veri bozulması.
-            return Err(SadakFsError::InvalidSuperblock);
+    /// Mevcut bir diskten SADAK dosya sistemini `options` ayarlarıyla yükler (Montaj).
+    /// Varsayılan ayarlarla kritik metadata hızlı denetimden geçirilir, derin doğrulama
+    /// ertelenir. Birim tanınmayan `incompat` özellikleri kullanıyorsa `UnsupportedFeatures`
+    /// döner; tanınmayan `ro_compat` özellikleri varsa salt okunur monte edilir.
+    pub fn mount(device: D, options: MountOptions) -> Result<Self, SadakFsError<D>> {
+        let early = EarlyFs::mount(device)?;
+        if options.read_only {
+            check_device_size(&early.cache, &early.superblock)?;
+            return Self::promote(early, &options);
         }
-        
-        // 3. Alt Sistemleri Başlat
-        let allocator = Allocator::new(cache.clone(), superblock.bitmap_start_id)?;
-        let metadata_tree = BTree::new(cache.clone(), superblock.metadata_root_id)?;
-        
-        Ok(SadakFs {
-            cache,
-            allocator,
-            metadata_tree,
+        early.promote_with_options(&options)
+    }
+
+    /// Dosya sistemini, verilen derinlikte metadata denetimi yaparak monte eder.
+    /// Denetim başarısız olursa montaj sağlıklı sayılmaz ve hata döner.
+    /// Periyodik denetimin süresi dolduysa yalnızca önerilir (`CheckPolicy::Recommend`).
+    pub fn mount_verified(device: D, verify: MountVerify) -> Result<Self, SadakFsError<D>> {
+        Self::mount(device, MountOptions::new().verify(verify))
+    }
+
+    /// Dosya sistemini monte eder ve montaj sayacını artırır.
+    ///
+    /// Son tam denetimden bu yana `max_mount_count` montaj yapıldıysa veya
+    /// `check_interval` süresi geçtiyse `policy` uygulanır: `Force` ile montaj tam
+    /// metadata doğrulamasına yükseltilir. Başarılı her tam doğrulama sayaçları sıfırlar.
+    pub fn mount_with_policy(device: D, verify: MountVerify, policy: CheckPolicy) -> Result<Self, SadakFsError<D>> {
+        Self::mount(device, MountOptions::new().verify(verify).check_policy(policy))
+    }
+
+    /// Dosya sistemini salt okunur monte eder: tüm yazma yolları `ReadOnly` döner, hiçbir
+    /// blok kirli işaretlenmez ve diske hiçbir şey yazılmaz (montaj sayacı ve durum dahil).
+    /// Çökmede yarım kalan son commit niyet günlüğünden yalnızca bellekte tamamlanır.
+    /// Adli inceleme ve hasarlı olabilecek bir imajı güvenle açmak içindir. `EarlyFs`'ten
+    /// farklı olarak tam okuma API'si (yol çözme, dizin listeleme, kapasite raporu) kullanılabilir;
+    /// mühürlü birimler de bu yolla monte edilebilir.
+    pub fn mount_readonly(device: D) -> Result<Self, SadakFsError<D>> {
+        Self::mount(device, MountOptions::new().read_only(true))
+    }
+
+    /// Salt okunur erken montajı (`EarlyFs`) yazma yığınını kurarak tam montaja yükseltir.
+    /// `options.read_only` ise birime hiçbir şey yazılmaz.
+    fn promote(early: EarlyFs<D>, options: &MountOptions) -> Result<Self, SadakFsError<D>> {
+        let EarlyFs { cache, metadata_tree, superblock, clock, .. } = early;
+        let MountOptions { read_only, verify, check_policy: policy, .. } = *options;
+        // Tanınmayan `ro_compat` özelliği olan birime yazılmaz; montaj salt okunura düşer
+        let read_only = read_only || superblock.features().unknown().ro_compat != 0;
+        cache.set_capacity(options.cache_blocks)?;
+
+        // 3. Yazma Alt Sistemlerini Başlat
+        let allocator = Allocator::new(cache.clone(), superblock.bitmap_start_id, superblock.total_blocks)?;
+        allocator.set_fast_tier_end(superblock.fast_tier_blocks);
+        allocator.set_reserved_blocks(superblock.reserved_blocks);
+        allocator.set_space_map_checksum(superblock.checksum_policy().space_map);
+        if superblock.space_map_root != 0 {
+            // Önceki biçimdeki kontrol noktası yüklenmez, sayaçlar tahsis sırasında bitmap'ten
+            // hesaplanır; bozuk kontrol noktası aşağıdaki denetimde raporlanır
+            allocator.load_space_map(superblock.space_map_root)?;
+        }
+
+        // Kota kayıtları; bozuk kayıt atlanır ve `recount_quotas` ile düzeltilebilir
+        let mut quotas = Vec::new();
+        metadata_tree.walk_from(&QuotaId::first_key(), &mut |key, value| {
+            if let (Some(id), Some(usage)) = (QuotaId::from_key(key), QuotaUsage::decode(value)) {
+                quotas.push((id, usage));
+            }
+            QuotaId::is_quota_key(key)
+        })?;
+        allocator.quota().load(quotas);
+
+        // Adli kayıt günlüğü; bozuk veya okunamayan günlük montajı engellemez, boş başlar
+        let forensics = match superblock.forensic_log_block {
+            0 => ForensicLog::default(),
+            id => cache
+                .get_block(id)
+                .ok()
+                .and_then(|block_arc| ForensicLog::decode(unsafe { &*block_arc.get() }.data.as_ref()))
+                .unwrap_or_default(),
+        };
+
+        // 4. Periyodik denetim politikası
+        let now = clock.now()?;
+        let due = check_due(&superblock, now);
+        let verify = match (due, policy) {
+            (Some(_), CheckPolicy::Force) => MountVerify::Deep,
+            _ => verify,
+        };
+
+        // 5. Kritik Metadata Denetimi
+        Self::audit_metadata(&cache, &superblock, &allocator, &metadata_tree, verify)?;
+        
+        let scrub_cursor = superblock.scrub_cursor;
+        let previous_unmount_clean = superblock.state == VOLUME_STATE_CLEAN;
+        let fs = SadakFs {
+            cache,
+            allocator,
+            metadata_tree,
             lock: SysLock::new()?,
-            superblock,
-        })
+            pages: PageCache::new()?,
+            superblock: UnsafeCell::new(superblock),
+            dirty_block_budget: DEFAULT_DIRTY_BLOCK_BUDGET,
+            deep_verify_pending: UnsafeCell::new(verify == MountVerify::Fast),
+            check_recommended: match (verify, policy) {
+                (MountVerify::Fast, CheckPolicy::Recommend) => due,
+                _ => None,
+            },
+            write_stats: UnsafeCell::new(WriteStats::default()),
+            accounting: None,
+            changed_in_place: UnsafeCell::new(BTreeSet::new()),
+            scrub: UnsafeCell::new(ScrubState { checkpointed: scrub_cursor, ..ScrubState::default() }),
+            foreground_ops: AtomicU64::new(0),
+            previous_unmount_clean,
+            clock,
+            read_only: AtomicBool::new(read_only),
+            open_tail_block: AtomicU64::new(0),
+            commit_interval: options.commit_interval,
+            errors: options.errors,
+            data_mode: options.data,
+            fencing: options.fencing,
+            pin_metadata: AtomicBool::new(options.pin_metadata),
+            forensics: UnsafeCell::new(forensics),
+            pending_repair: UnsafeCell::new(BTreeMap::new()),
+            forensics_lock: SysLock::new()?,
+            open_inodes: UnsafeCell::new(BTreeMap::new()),
+            transaction_active: AtomicBool::new(false),
+            file_keys: UnsafeCell::new(BTreeMap::new()),
+            keys_lock: SysLock::new()?,
+        };
+
+        // Küçük birimlerde metadata ağacı önbellekte sabitlenir (salt okunur montajda da)
+        if options.pin_metadata {
+            fs.lock.acquire();
+            let result = fs.pin_metadata_locked();
+            fs.lock.release();
+            result?;
+        }
+
+        if read_only {
+            return Ok(fs);
+        }
+
+        // 6. Montaj sayacını ve durumu güncelle; yalnızca Superblock yazılır (işaret ettiği
+        // kökler değişmez). Birim `unmount` edilene kadar etkin sayılır.
+        fs.lock.acquire();
+        let sb = fs.sb_mut();
+        sb.state = VOLUME_STATE_ACTIVE;
+        if verify == MountVerify::Deep {
+            sb.mount_count = 0;
+            sb.last_check_time = now;
+        }
+        sb.mount_count = sb.mount_count.saturating_add(1);
+        let result = fs.write_superblock().and_then(|_| Ok(fs.cache.device.flush()?));
+        fs.lock.release();
+        result?;
+
+        // 7. Önceki montajda açıkken silinmiş ve bırakılmadan kalmış inode'ları geri kazan
+        fs.lock.acquire();
+        let result = fs.reclaim_orphans_locked();
+        fs.lock.release();
+        result?;
+
+        Ok(fs)
     }
     
-    /// Bir dosya sistemini diske biçimlendirir ve ilk Superblock'u yazar.
-    pub fn format(device: D) -> Result<Self, SadakFsError<D>> {
+    /// Bir dosya sistemini `options` ayarlarıyla diske biçimlendirir ve ilk Superblock'u yazar.
+    ///
+    /// Ayarlar aygıt boyutuna göre doğrulanır: desteklenmeyen blok boyutu, yüzde veya
+    /// özellik `EINVAL`, geçersiz etiket `InvalidName` döner; tahsis haritası ve ayrılan
+    /// alandan sonra yeterli blok kalmıyorsa `DeviceTooSmall` döner.
+    pub fn format(device: D, options: FormatOptions) -> Result<Self, SadakFsError<D>> {
+        Self::format_with_clock(device, options, Arc::new(KernelClock))
+    }
+
+    /// `format`'ın zaman damgalarını `clock`'tan alan hali. Gerçek zaman saati olmayan
+    /// hedeflerde `MonotonicClock`, testlerde `FakeClock` verilebilir.
+    pub fn format_with_clock(device: D, options: FormatOptions, clock: Arc<dyn Clock>) -> Result<Self, SadakFsError<D>> {
+        if options.block_size != BLOCK_SIZE
+            || options.reserved_percent > 50
+            || options.bitmap_start == 0
+            || node_blocks_for(options.metadata_node_size).is_none()
+            || !options.features.unknown().is_empty()
+        {
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+        if options.label.len() > VOLUME_LABEL_MAX || options.label.bytes().any(|b| b == 0) {
+            return Err(SadakFsError::InvalidName);
+        }
+        let now = clock.now()?;
+
         // Kilit oluşturma
         let fs_lock = SysLock::new()?;
         fs_lock.acquire(); // İşlem atomik olmalı
@@ -158,139 +1489,7302 @@ where
         let total_blocks = device.total_blocks();
         let cache = Arc::new(BlockCache::new(Arc::new(device))?);
 
-        // 1. Tahsis Yöneticisini Başlat
-        let bitmap_start_id = 1; 
-        let allocator = Allocator::new(cache.clone(), bitmap_start_id)?;
-        
+        // 1. Tahsis Yöneticisini Başlat; harita ve ayrılan alan aygıta sığmalı
+        let bitmap_start_id = options.bitmap_start;
+        let slots = options.superblock_slots && bitmap_start_id > SUPERBLOCK_B_BLOCK;
+        let allocator = Allocator::new(cache.clone(), bitmap_start_id, total_blocks)?;
+        let reserved_blocks = total_blocks / 100 * options.reserved_percent as u64;
+        let needed = bitmap_start_id + allocator.bitmap_block_count() + reserved_blocks + FORMAT_MIN_FREE_BLOCKS;
+        if needed > total_blocks {
+            fs_lock.release();
+            return Err(SadakFsError::DeviceTooSmall { expected: needed, actual: total_blocks });
+        }
+        allocator.set_reserved_blocks(reserved_blocks);
+        allocator.set_space_map_checksum(options.checksums.space_map);
+        allocator.format_bitmap()?;
+        if options.features.incompat & FEATURE_INCOMPAT_WRITE_STAMPS != 0 {
+            cache.enable_write_stamps(0);
+        }
+
         // 2. Kök Ağaçları Oluştur (Metadata B-Tree)
-        let metadata_root_id = allocator.allocate_block()?; 
-        let metadata_tree = BTree::new(cache.clone(), metadata_root_id)?;
+        let metadata_tree = BTree::new(cache.clone(), 0)?
+            .with_checksum(options.checksums.metadata_tree)
+            .with_node_size(options.metadata_node_size);
+        let metadata_root_id = metadata_tree.allocate_node(&allocator)?;
+        metadata_tree.set_root(metadata_root_id);
+        metadata_tree.init_empty_root()?;
         
         // 3. Superblock Oluştur
         let mut new_sb = Superblock {
             magic: SADAK_MAGIC,
-            version: SADAK_VERSION,
             total_blocks,
             metadata_root_id,
             bitmap_start_id,
             timestamp: 0, // İlk başta 0
+            next_generation: 1,
+            next_ino: ROOT_INO,
+            fast_tier_blocks: 0,
+            root_dir_inode: 0, // Kök dizin aşağıda oluşturulur
+            last_check_time: now, // Yeni birim denetlenmiş sayılır
+            check_interval: DEFAULT_CHECK_INTERVAL,
+            space_map_root: 0, // İlk commit'te yazılır
+            change_epoch: 0,
+            scrub_cursor: 0,
+            last_scrub_time: 0,
+            seal_tree_start: 0,
+            warm_set_block: 0,
+            reserved_blocks,
+            forensic_log_block: 0,
+            journal_start: 0, // İlk commit'te ayrılır
+            journal_seq: 0,
+            fence_epoch: 0,
             checksum: 0,
-            padding: [0u8; BLOCK_SIZE - (mem::size_of::<u64>() * 3 + mem::size_of::<u16>() + mem::size_of::<u32>() * 2 + mem::size_of::<u64>() * 2 + mem::size_of::<u8>() * 0)],
+            mount_count: 0,
+            max_mount_count: DEFAULT_MAX_MOUNT_COUNT,
+            state: VOLUME_STATE_ACTIVE,
+            feature_compat: options.features.compat
+                | if slots { FEATURE_COMPAT_SUPERBLOCK_SLOTS } else { 0 },
+            feature_ro_compat: options.features.ro_compat
+                | if reserved_blocks != 0 { FEATURE_RO_COMPAT_RESERVED_BLOCKS } else { 0 }
+                | FEATURE_RO_COMPAT_INODE_TABLE,
+            feature_incompat: options.features.incompat
+                | FEATURE_INCOMPAT_DIR_INO
+                | if options.checksums != ChecksumPolicy::default() { FEATURE_INCOMPAT_CHECKSUM_POLICY } else { 0 }
+                | if options.metadata_node_size > BLOCK_SIZE { FEATURE_INCOMPAT_LARGE_NODES } else { 0 },
+            version: SADAK_VERSION,
+            uuid: generate_uuid(now ^ total_blocks.rotate_left(32) ^ platform::task_id().unwrap_or(0)),
+            label: [0u8; VOLUME_LABEL_MAX],
+            seal_root: [0u8; SEAL_HASH_SIZE],
+            checksum_algorithms: options.checksums.to_raw(),
+            journal_segments: 0,
+            metadata_node_size: options.metadata_node_size as u32,
+            padding: [0u8; SUPERBLOCK_PADDING],
         };
-        
-        // 4. Superblock'u Disk Üzerinde Hazırla
-        let sb_block_arc = cache.get_block(0)?; // Blok 0'ı al
-        let sb_block_mut = unsafe { &mut *sb_block_arc.get() };
-        
-        // Superblock'u bloğun ham verisine kopyala
-        let sb_data_slice: &mut [u8] = sb_block_mut.data.as_mut();
-        unsafe {
-            let sb_ptr = sb_data_slice.as_mut_ptr() as *mut Superblock;
-            *sb_ptr = new_sb;
-        }
-
-        // 5. Checksum Hesapla ve Kaydet
-        new_sb.checksum = checksum::checksum_data(sb_data_slice);
-        // Checksum'ı tekrar bloğa yaz
-        unsafe {
-            let sb_ptr = sb_data_slice.as_mut_ptr() as *mut Superblock;
-            *sb_ptr = new_sb;
-        }
-
-        // 6. Superblock'u "kirli" olarak işaretle ve diske yazılmasını zorla
-        sb_block_mut.is_dirty = true;
-        cache.device.flush()?; // Değişiklikleri kalıcı yap
+        new_sb.label[..options.label.len()].copy_from_slice(options.label.as_bytes());
 
-        fs_lock.release(); // Kilidi bırak.
-
-        Ok(SadakFs {
+        let fs = SadakFs {
             cache,
             allocator,
             metadata_tree,
             lock: fs_lock,
-            superblock: new_sb,
-        })
+            pages: PageCache::new()?,
+            superblock: UnsafeCell::new(new_sb),
+            dirty_block_budget: DEFAULT_DIRTY_BLOCK_BUDGET,
+            deep_verify_pending: UnsafeCell::new(false),
+            check_recommended: None,
+            write_stats: UnsafeCell::new(WriteStats::default()),
+            accounting: None,
+            changed_in_place: UnsafeCell::new(BTreeSet::new()),
+            scrub: UnsafeCell::new(ScrubState::default()),
+            foreground_ops: AtomicU64::new(0),
+            previous_unmount_clean: true,
+            clock,
+            read_only: AtomicBool::new(false),
+            open_tail_block: AtomicU64::new(0),
+            commit_interval: 0,
+            errors: ErrorsPolicy::Continue,
+            data_mode: DataMode::Ordered,
+            fencing: false,
+            pin_metadata: AtomicBool::new(false),
+            forensics: UnsafeCell::new(ForensicLog::default()),
+            pending_repair: UnsafeCell::new(BTreeMap::new()),
+            forensics_lock: SysLock::new()?,
+            open_inodes: UnsafeCell::new(BTreeMap::new()),
+            transaction_active: AtomicBool::new(false),
+            file_keys: UnsafeCell::new(BTreeMap::new()),
+            keys_lock: SysLock::new()?,
+        };
+        
+        // 4. Kök dizini oluştur, ardından kök düğümü, tahsis haritasını ve en son
+        // Superblock'u diske yaz (ilk commit)
+        let result = fs
+            .create_inode_locked(FILE_TYPE_DIRECTORY, 0, &Credentials::ROOT, MAIN_SUBVOLUME_ID)
+            .and_then(|mut root_dir| {
+                // Kökün üst dizini kendisidir
+                root_dir.parent_dir = root_dir.inode_id;
+                fs.write_inode_locked(&mut root_dir)?;
+                fs.sb_mut().root_dir_inode = root_dir.inode_id;
+                fs.commit_locked()?;
+                // Diğer yuva da doldurulur; aygıtta önceki bir birimden kalan eski bir
+                // Superblock kopyası seçilemez
+                if slots {
+                    let other = fs.superblock_slot() ^ SUPERBLOCK_B_BLOCK;
+                    let sb_block_arc = fs.cache.get_block(0)?;
+                    fs.cache.device.write_block(other, unsafe { &*sb_block_arc.get() }.data.as_ref())?;
+                    fs.cache.device.flush()?;
+                }
+                Ok(())
+            });
+        fs.lock.release(); // Kilidi bırak.
+        result?;
+
+        Ok(fs)
     }
     
     // --- Dosya Sistemi İşlemleri ---
 
+    // TODO: Ortak bir VFS trait'i tanımlandığında, create/rename/unlink anlamları,
+    // izinler, var olan hedefin üzerine rename ve sembolik bağlantı uç durumları
+    // için pjdfstest tarzı bir davranış test paketi (host özelliği altında,
+    // `block_device::conformance` gibi) eklenecek. Bu ağaçta henüz VFS trait'i ve
+    // TmpFs/OverlayFs uygulamaları yok.
+
     /// Basit bir dosyayı (inode) B-Ağacında oluşturur.
     pub fn create_file(&self, file_size: u64) -> Result<Inode, SadakFsError<D>> {
+        self.check_writable()?;
         self.lock.acquire(); // Atomik işlem için kilidi al
+        let result = self.atomic_locked(|| self.create_inode_locked(FILE_TYPE_REGULAR, file_size, &Credentials::ROOT, MAIN_SUBVOLUME_ID));
+        self.lock.release(); // Kilidi bırak.
+        let new_inode = result?;
+
+        // Inode tamamen yazıldı; tutarlı bir ara durum olduğu için bütçe aşıldıysa commit et.
+        self.commit_if_over_budget()?;
+        
+        Ok(new_inode)
+    }
+
+    /// `owner` kimliğine ait, `subvolume` alt birimindeki yeni bir inode oluşturur (çağıran
+    /// `lock`'u tutmalıdır). Dosyalar için boş bir veri ağacı da oluşturulur; dizinlerin
+    /// girdileri metadata ağacında tutulduğundan gerekmez.
+    fn create_inode_locked(&self, file_type: u8, file_size: u64, owner: &Credentials, subvolume: u64) -> Result<Inode, SadakFsError<D>> {
+        // 0. Inode kotası; bloklar tahsis edilmeden önce denetlenir
+        if !self.allocator.quota().allows_inodes(QuotaId::User(owner.uid), QuotaId::Subvolume(subvolume), 1) {
+            return Err(SadakFsError::Allocator(AllocatorError::QuotaExceeded));
+        }
 
         // 1. Yeni bir Inode için blok tahsis et.
         let inode_block_id = self.allocator.allocate_block().map_err(SadakFsError::Allocator)?;
 
         // 2. Yeni bir Veri B-Ağacı Kökü tahsis et (Dosya verileri için)
-        let data_root_id = self.allocator.allocate_block().map_err(SadakFsError::Allocator)?;
+        let data_root_id = if file_type == FILE_TYPE_DIRECTORY {
+            0
+        } else {
+            self.allocator.allocate_block().map_err(SadakFsError::Allocator)?
+        };
+
+        self.init_inode_locked(inode_block_id, data_root_id, file_type, file_size, owner, subvolume)
+    }
+
+    /// Önceden tahsis edilmiş bloklarda yeni bir inode kurar (çağıran `lock`'u tutmalıdır).
+    /// `data_root_id` dosyalar için boş veri ağacının kökü olur; dizinlerde 0'dır.
+    fn init_inode_locked(
+        &self,
+        inode_block_id: BlockId,
+        data_root_id: BlockId,
+        file_type: u8,
+        file_size: u64,
+        owner: &Credentials,
+        subvolume: u64,
+    ) -> Result<Inode, SadakFsError<D>> {
+        if data_root_id != 0 {
+            self.data_tree(data_root_id)?.init_empty_root()?; // Boş veri ağacı
+        }
         
         // 3. Inode yapısını oluştur
+        let now = self.get_system_time()?;
         let mut new_inode = Inode {
             file_size,
             block_count: 0, 
-            creation_time: self.get_system_time()?, 
-            modification_time: self.get_system_time()?,
-            file_type: 1, // Dosya
+            creation_time: now, 
+            modification_time: now,
             data_tree_root: data_root_id, 
+            inode_id: inode_block_id,
+            ino: 0,
+            generation: self.take_generation(),
+            parent_dir: 0,
+            subvolume_id: subvolume,
+            change_seq: 0,
+            key_id: 0,
+            crypt_nonce: 0,
             link_count: 1,
             checksum: 0, 
-            padding: [0; 128],
+            uid: owner.uid,
+            gid: owner.gid,
+            mode: if file_type == FILE_TYPE_DIRECTORY { DEFAULT_DIR_MODE } else { DEFAULT_FILE_MODE },
+            file_type,
+            placement_hint: PlacementHint::default().to_raw(),
+            flags: 0,
+            compression: 0,
+            data_tree_height: if data_root_id != 0 { 1 } else { 0 },
+            padding: [0; INODE_PADDING],
         };
         
-        // 4. Inode'u önbelleğe al ve diske yazılmaya hazırla (CoW bloğu)
-        let inode_arc = self.cache.get_block(inode_block_id)?;
-        let inode_block_mut = unsafe { &mut *inode_arc.get() };
-        
-        let inode_data_slice: &mut [u8] = inode_block_mut.data.as_mut();
-        
-        // Kopyalama için ham pointer kullan
-        unsafe {
-            let inode_ptr = inode_data_slice.as_mut_ptr() as *mut Inode;
-            *inode_ptr = new_inode;
+        // 4. Inode tablosu varsa kalıcı bir numara ver ve bloğunu tabloya kaydet
+        if self.sb().feature_ro_compat & FEATURE_RO_COMPAT_INODE_TABLE != 0 {
+            new_inode.ino = self.take_ino();
+            let location = InodeLocation { ino: new_inode.ino, block: inode_block_id };
+            self.metadata_tree.insert_entry(&self.allocator, &InodeLocation::key(location.ino), &location.encode())?;
         }
 
-        // 5. Checksum Hesapla ve Kaydet
-        new_inode.checksum = checksum::checksum_data(inode_data_slice);
-        // Checksum'ı tekrar bloğa yaz
-        unsafe {
-            let inode_ptr = inode_data_slice.as_mut_ptr() as *mut Inode;
-            *inode_ptr = new_inode;
-        }
-        
-        // Bloğu kirli olarak işaretle (CoW işlemi için önemli)
-        inode_block_mut.is_dirty = true;
-        
-        self.lock.release(); // Kilidi bırak.
+        // 5. Inode'u önbelleğe al, checksum'unu hesapla ve diske yazılmaya hazırla
+        self.write_inode_locked(&mut new_inode)?;
+        self.account_quota(&new_inode, 0, 1);
         
         Ok(new_inode)
     }
 
-    /// Superblock'u güncelleyip tüm kirli (dirty) blokları diske yazar (Atomik Commit).
-    pub fn sync(&self) -> Result<(), SadakFsError<D>> {
+    /// Bir inode'u bloğundan okur ve checksum'unu doğrular.
+    ///
+    /// Blok bir inode'a aitse ama checksum'u tutmuyorsa bozukluk onarılmak üzere
+    /// bir sonraki scrub adımına bırakılır (bkz. `corruption_log`).
+    pub fn read_inode(&self, inode_id: BlockId) -> Result<Inode, SadakFsError<D>> {
+        let result = read_inode_in(&self.cache, self.sb().checksum_policy().inode, inode_id);
+        if let Err(SadakFsError::ChecksumError) = result {
+            // Başka bir şey içeren blok (ör. yeniden kullanılmış inode bloğu) bozukluk değildir
+            if let Ok(block_arc) = self.cache.get_block(inode_id) {
+                let data = unsafe { &*block_arc.get() }.data.as_ref();
+                if inode_field_u64(data, mem::offset_of!(Inode, inode_id)) == inode_id {
+                    self.queue_repair(inode_id, CorruptionSource::Inode);
+                }
+            }
+        }
+        result
+    }
+
+    /// `root` kökünden başlayan dosya veri ağacı (birimin veri ağacı checksum algoritmasıyla).
+    fn data_tree(&self, root: BlockId) -> Result<BTree<D>, SadakFsError<D>> {
+        Ok(BTree::new(self.cache.clone(), root)?.with_checksum(self.sb().checksum_policy().data_tree))
+    }
+
+    /// Değişen veri ağacının kökünü ve yüksekliğini inode'a kaydeder.
+    fn store_data_tree(&self, inode: &mut Inode, data_tree: &BTree<D>) -> Result<(), SadakFsError<D>> {
+        inode.data_tree_root = data_tree.root_id();
+        inode.data_tree_height = data_tree.height()?;
+        Ok(())
+    }
+
+    /// Veri ağacında denetlenecek seviye sayısı: kayıtlı yükseklik, yoksa tüm ağaç.
+    fn data_tree_levels(inode: &Inode) -> u8 {
+        if inode.data_tree_height != 0 { inode.data_tree_height } else { u8::MAX }
+    }
+
+    /// Inode'u kalıcı inode numarasıyla okur.
+    ///
+    /// Numara inode tablosunda yoksa (inode silinmiş veya birim inode tablosuz
+    /// biçimlendirilmiş) `NotFound` döner. Tablonun gösterdiği blok başka bir inode
+    /// içeriyorsa tablo bozuktur ve `MetadataCorrupted` döner.
+    pub fn get_inode(&self, ino: u64) -> Result<Inode, SadakFsError<D>> {
+        let value = self.metadata_tree.lookup(&InodeLocation::key(ino))?.ok_or(SadakFsError::NotFound)?;
+        let location = InodeLocation::from_item(&InodeLocation::key(ino), &value)
+            .ok_or_else(|| self.invariant_violated(self.metadata_tree.root_id()))?;
+        let inode = self.read_inode(location.block)?;
+        if inode.ino != ino {
+            return Err(self.invariant_violated(location.block));
+        }
+        Ok(inode)
+    }
+
+    /// Dizin girdisinin işaret ettiği inode. Girdiler inode'ları kalıcı numaralarıyla
+    /// gösterir ve `get_inode` ile çözülür; `FEATURE_INCOMPAT_DIR_INO` özelliğinden önce
+    /// biçimlendirilmiş birimlerde numara inode'un blok ID'sidir.
+    pub fn entry_inode(&self, entry: &DirEntry) -> Result<Inode, SadakFsError<D>> {
+        if self.sb().feature_incompat & FEATURE_INCOMPAT_DIR_INO != 0 {
+            self.get_inode(entry.ino)
+        } else {
+            self.read_inode(entry.ino)
+        }
+    }
+
+    /// Dizin girdisinin işaret ettiği inode'un bloğu. Inode okunmadığından bloğu bozuk
+    /// olsa da bulunur; numara inode tablosunda yoksa `NotFound`.
+    fn entry_block(&self, entry: &DirEntry) -> Result<BlockId, SadakFsError<D>> {
+        if self.sb().feature_incompat & FEATURE_INCOMPAT_DIR_INO == 0 {
+            return Ok(entry.ino);
+        }
+        let key = InodeLocation::key(entry.ino);
+        let value = self.metadata_tree.lookup(&key)?.ok_or(SadakFsError::NotFound)?;
+        InodeLocation::from_item(&key, &value)
+            .map(|location| location.block)
+            .ok_or_else(|| self.invariant_violated(self.metadata_tree.root_id()))
+    }
+
+    /// Dizin girdilerinde `inode`'u gösteren numara (bkz. `entry_ino`).
+    fn entry_ino(&self, inode: &Inode) -> u64 {
+        entry_ino(self.sb(), inode)
+    }
+
+    /// Inode'un güncel değişiklik sayacı. Sayaç, inode'un metadata'sı (boyut, zamanlar,
+    /// izinler, bağlantı sayısı, dizin girdileri) veya verisi her değiştiğinde artar;
+    /// arabelleğe alınmış yazmalar da hemen sayılır. Üst katmanlar (9P istemcileri, mmap,
+    /// dizin önbelleği) sakladıkları `Inode::change_seq` ile tek bir karşılaştırma yaparak
+    /// kopyalarının bayatladığını anlayabilir. Inode bloğu yeniden kullanıldığında sayaç
+    /// sıfırdan başlayabileceğinden `generation` ile birlikte karşılaştırılmalıdır.
+    pub fn change_seq(&self, inode_id: BlockId) -> Result<u64, SadakFsError<D>> {
+        Ok(self.read_inode(inode_id)?.change_seq)
+    }
+
+    // --- Dizin İşlemleri ---
+
+    /// Kök dizinin inode'u.
+    pub fn root_dir(&self) -> Result<Inode, SadakFsError<D>> {
+        self.read_inode(self.sb().root_dir_inode)
+    }
+
+    /// `parent` dizininde `name` adlı yeni bir alt dizin oluşturur.
+    pub fn create_dir(&self, parent: &Inode, name: &str) -> Result<Inode, SadakFsError<D>> {
+        self.create_entry(parent, name, FILE_TYPE_DIRECTORY, &Credentials::ROOT)
+    }
+
+    /// `parent` dizininde `name` adlı yeni, boş bir dosya oluşturur.
+    pub fn create_file_in(&self, parent: &Inode, name: &str) -> Result<Inode, SadakFsError<D>> {
+        self.create_entry(parent, name, FILE_TYPE_REGULAR, &Credentials::ROOT)
+    }
+
+    /// `parent` dizininde `target`'ı gösteren `name` adlı bir sembolik bağlantı oluşturur.
+    ///
+    /// Hedef yalnızca saklanır; var olması gerekmez ve yol çözümlemesinde (`open_at`)
+    /// izlenmez, çağıran `read_link` ile okuyup kendisi çözümler.
+    pub fn create_symlink(&self, parent: &Inode, name: &str, target: &str) -> Result<Inode, SadakFsError<D>> {
+        self.check_writable()?;
+        if parent.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        if !DirEntry::is_valid_name(name) || target.is_empty() {
+            return Err(SadakFsError::InvalidName);
+        }
+        if target.len() > SYMLINK_TARGET_MAX {
+            return Err(SadakFsError::ValueTooLarge);
+        }
+
         self.lock.acquire();
-        
-        // Gerçek implementasyonda: Tüm kirli CoW blokları diske yazılır.
-        // Bu işlem, yeni kök işaretçilerini belirler.
-        // Superblock'u yeni kök işaretçileri ve zaman damgasıyla günceller.
-        
-        // 1. Superblock'u Block 0'a yazar (En son işlem)
-        self.cache.device.flush()?;
+        let result = self.atomic_locked(|| self.create_symlink_locked(parent.inode_id, name, target, DEFAULT_SYMLINK_MODE, &Credentials::ROOT));
+        self.lock.release();
+        let link = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(link)
+    }
+
+    /// `create_symlink` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn create_symlink_locked(
+        &self,
+        parent_id: BlockId,
+        name: &str,
+        target: &str,
+        mode: u16,
+        owner: &Credentials,
+    ) -> Result<Inode, SadakFsError<D>> {
+        let link = self.create_entry_locked(parent_id, name, FILE_TYPE_SYMLINK, owner)?;
+        let mut link = self.write_at_locked(link.inode_id, 0, target.as_bytes())?;
+        link.mode = mode;
+        self.write_inode_locked(&mut link)?;
+        Ok(link)
+    }
+
+    /// Sembolik bağlantının hedefi; `link` bir bağlantı değilse `EINVAL`.
+    pub fn read_link(&self, link: &Inode) -> Result<String, SadakFsError<D>> {
+        let current = self.read_inode(link.inode_id)?;
+        if current.file_type != FILE_TYPE_SYMLINK || current.file_size as usize > SYMLINK_TARGET_MAX {
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+
+        let mut target = alloc::vec![0u8; current.file_size as usize];
+        let len = self.read_at(&current, 0, &mut target)?;
+        target.truncate(len);
+        String::from_utf8(target).map_err(|_| SadakFsError::Syscall(SyscallError::EINVAL))
+    }
 
+    /// `parent` dizininde `names` adlı yeni, boş dosyaları toplu olarak oluşturur (ör. arşiv
+    /// açma). Dosyalar `CREATE_BATCH_MAX`'lık gruplar halinde oluşturulur: her grup için
+    /// dizin bir kez taranır, inode ve veri ağacı blokları ardışık extent'ler olarak
+    /// tahsis edilir ve üst dizin bir kez yazılır; commit yalnızca gruplar arasında,
+    /// bütçe aşıldıysa yapılır.
+    ///
+    /// Adlar önceden doğrulanır; dizinde zaten bulunan bir ad `AlreadyExists` döndürür
+    /// ve o gruptaki hiçbir dosya oluşturulmaz (önceki gruplar oluşturulmuş kalır).
+    ///
+    /// # Döndürür
+    /// Oluşturulan inode'lar, `names` sırasıyla.
+    pub fn create_files_in(&self, parent: &Inode, names: &[&str]) -> Result<Vec<Inode>, SadakFsError<D>> {
+        self.check_writable()?;
+        if parent.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        let mut unique = BTreeSet::new();
+        for name in names {
+            if !DirEntry::is_valid_name(name) {
+                return Err(SadakFsError::InvalidName);
+            }
+            if !unique.insert(casefold_of(parent).fold(name)) {
+                return Err(SadakFsError::AlreadyExists);
+            }
+        }
+
+        let mut created = Vec::with_capacity(names.len());
+        for batch in names.chunks(CREATE_BATCH_MAX) {
+            self.lock.acquire();
+            let result = self.atomic_locked(|| self.create_files_locked(parent.inode_id, batch, &Credentials::ROOT));
+            self.lock.release();
+            created.extend(result?);
+
+            self.commit_if_over_budget()?;
+        }
+        Ok(created)
+    }
+
+    /// `create_files_in` grubunun gövdesi (çağıran `lock`'u tutmalıdır).
+    fn create_files_locked(&self, parent_id: BlockId, names: &[&str], owner: &Credentials) -> Result<Vec<Inode>, SadakFsError<D>> {
+        // 1. Çakışmalar ve sıradaki girdi numarası: doğrusal dizin bir kez taranır, özetli
+        // dizinde her ad kendi özetinde aranır
+        let parent = self.read_inode(parent_id)?;
+        let hashed = parent.flags & INODE_FLAG_DIR_HASHED != 0;
+        let fold = casefold_of(&parent);
+        let mut taken = false;
+        let mut next_index = 0;
+        if hashed {
+            for name in names {
+                taken |= self.find_entry(parent_id, name)?.is_some();
+            }
+        } else {
+            self.scan_dir(parent_id, &mut |key, entry| {
+                next_index = key.offset + 1;
+                taken |= names.iter().any(|name| fold.matches(name, &entry.name));
+                !taken
+            })?;
+        }
+        if taken {
+            return Err(SadakFsError::AlreadyExists);
+        }
+        let subvolume = self.read_inode(parent_id)?.subvolume_id;
+        let quota = self.allocator.quota();
+        if !quota.allows_inodes(QuotaId::User(owner.uid), QuotaId::Subvolume(subvolume), names.len() as u64) {
+            return Err(SadakFsError::Allocator(AllocatorError::QuotaExceeded));
+        }
+
+        // 2. Dosya başına iki blok (inode + veri ağacı kökü), olabildiğince ardışık
+        let needed = names.len() as u64 * 2;
+        let mut blocks = Vec::with_capacity(needed as usize);
+        while (blocks.len() as u64) < needed {
+            let (start, len) = self.allocator.allocate_extent(needed - blocks.len() as u64)?;
+            blocks.extend(start..start + len);
+        }
+
+        // 3. Inode'ları kur ve girdileri sırayla ekle
+        let mut created = Vec::with_capacity(names.len());
+        for (i, name) in names.iter().enumerate() {
+            let inode = self.init_inode_locked(blocks[2 * i], blocks[2 * i + 1], FILE_TYPE_REGULAR, 0, owner, subvolume)?;
+            let entry = DirEntry {
+                name: String::from(*name),
+                ino: self.entry_ino(&inode),
+                file_type: FILE_TYPE_REGULAR,
+            };
+            let key = if hashed { self.find_slot(parent_id, name)?.1 } else { DirEntry::key(self.entry_ino(&parent), next_index + i as u64) };
+            self.metadata_tree.insert_entry(&self.allocator, &key, &entry.encode())?;
+            created.push(inode);
+        }
+
+        // 4. Üst dizin yalnızca bir kez yazılır
+        let mut parent = self.read_inode(parent_id)?;
+        parent.file_size += names.len() as u64;
+        parent.modification_time = self.get_system_time()?;
+        self.write_inode_locked(&mut parent)?;
+        self.index_large_dir_locked(parent_id)?;
+        Ok(created)
+    }
+
+    /// `base` dizininin altında bir dizin iskeletini (dizinler, dosyalar, bağlantılar ve
+    /// bunların izinleri ve sahipleri) tek bir işlemde oluşturur; Sahne64 kurulumu ve
+    /// konteyner çalışma zamanı ad alanlarını bu yolla atomik olarak kurar.
+    ///
+    /// Girdiler sırayla oluşturulur; her girdinin üst dizini ya `base` altında zaten
+    /// bulunmalı ya da iskelette daha önce bir dizin olarak yer almalıdır. Tüm yollar,
+    /// adlar, izinler, kotalar ve çakışmalar hiçbir şey oluşturulmadan önce denetlenir;
+    /// oluşturma sırasında ara commit yapılmaz ve yarıda kalan oluşturma (ör. alan
+    /// bittiğinde) geri alınır, böylece hem hatadan hem bir çökmeden sonra iskeletin ya
+    /// tamamı ya da hiçbiri görünür. Yollar `.` ve `..` içeremez.
+    ///
+    /// # Döndürür
+    /// Oluşturulan inode'lar, `entries` sırasıyla.
+    pub fn create_skeleton(&self, base: &Inode, entries: &[SkeletonEntry<'_>]) -> Result<Vec<Inode>, SadakFsError<D>> {
+        self.check_writable()?;
+        if base.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        let mut unique = BTreeSet::new();
+        for entry in entries {
+            if entry.mode & !MODE_MASK != 0 {
+                return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+            }
+            if let SkeletonKind::Symlink(target) = entry.kind {
+                if target.is_empty() {
+                    return Err(SadakFsError::InvalidName);
+                }
+                if target.len() > SYMLINK_TARGET_MAX {
+                    return Err(SadakFsError::ValueTooLarge);
+                }
+            }
+            if !unique.insert(skeleton_components(entry.path)?) {
+                return Err(SadakFsError::AlreadyExists);
+            }
+        }
+
+        self.lock.acquire();
+        let result = self.atomic_locked(|| self.create_skeleton_locked(base.inode_id, entries));
+        self.lock.release();
+        let created = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(created)
+    }
+
+    /// `create_skeleton` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn create_skeleton_locked(&self, base_id: BlockId, entries: &[SkeletonEntry<'_>]) -> Result<Vec<Inode>, SadakFsError<D>> {
+        // 1. Her girdinin üst dizinini çözümle ve çakışmaları hiçbir şey değiştirmeden bul.
+        // Üst dizin ya mevcut bir dizindir (`Ok`) ya da iskelette daha önce gelen bir dizindir (`Err`).
+        let mut planned = BTreeSet::new();
+        let mut parents = Vec::with_capacity(entries.len());
+        let mut inodes_per_owner: BTreeMap<u32, u64> = BTreeMap::new();
+        for entry in entries {
+            let components = skeleton_components(entry.path)?;
+            let (name, parent_path) = components.split_last().ok_or(SadakFsError::InvalidName)?;
+
+            let parent = if planned.contains(parent_path) {
+                Err(parent_path.to_vec())
+            } else {
+                let mut dir_id = base_id;
+                for component in parent_path {
+                    let (_, found) = self.find_entry(dir_id, component)?.ok_or(SadakFsError::NotFound)?;
+                    if found.file_type != FILE_TYPE_DIRECTORY {
+                        return Err(SadakFsError::NotADirectory);
+                    }
+                    dir_id = self.entry_inode(&found)?.inode_id;
+                }
+                if self.find_entry(dir_id, name)?.is_some() {
+                    return Err(SadakFsError::AlreadyExists);
+                }
+                Ok(dir_id)
+            };
+            parents.push((parent, *name));
+
+            if entry.kind == SkeletonKind::Directory {
+                planned.insert(components.clone());
+            }
+            *inodes_per_owner.entry(entry.uid).or_insert(0) += 1;
+        }
+        let subvolume = self.read_inode(base_id)?.subvolume_id;
+        let quota = self.allocator.quota();
+        for (uid, count) in inodes_per_owner {
+            if !quota.allows_inodes(QuotaId::User(uid), QuotaId::Subvolume(subvolume), count) {
+                return Err(SadakFsError::Allocator(AllocatorError::QuotaExceeded));
+            }
+        }
+
+        // 2. Girdileri sırayla oluştur; yeni dizinlerin inode'ları yollarıyla hatırlanır
+        let mut created_dirs: BTreeMap<Vec<&str>, BlockId> = BTreeMap::new();
+        let mut created = Vec::with_capacity(entries.len());
+        for (entry, (parent, name)) in entries.iter().zip(parents) {
+            let parent_id = match parent {
+                Ok(dir_id) => dir_id,
+                Err(path) => *created_dirs.get(&path).ok_or(SadakFsError::NotFound)?,
+            };
+            let owner = Credentials { uid: entry.uid, gid: entry.gid };
+
+            let inode = match entry.kind {
+                SkeletonKind::Symlink(target) => self.create_symlink_locked(parent_id, name, target, entry.mode, &owner)?,
+                kind => {
+                    let file_type = if kind == SkeletonKind::Directory { FILE_TYPE_DIRECTORY } else { FILE_TYPE_REGULAR };
+                    let mut inode = self.create_entry_locked(parent_id, name, file_type, &owner)?;
+                    inode.mode = entry.mode;
+                    self.write_inode_locked(&mut inode)?;
+                    if kind == SkeletonKind::Directory {
+                        created_dirs.insert(skeleton_components(entry.path)?, inode.inode_id);
+                    }
+                    inode
+                }
+            };
+            created.push(inode);
+        }
+        Ok(created)
+    }
+
+    /// `parent` dizininde `name` adlı girdinin işaret ettiği inode'u bulur.
+    pub fn lookup(&self, parent: &Inode, name: &str) -> Result<Inode, SadakFsError<D>> {
+        if parent.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+
+        match self.find_entry(parent.inode_id, name)? {
+            Some((_, entry)) => self.entry_inode(&entry),
+            None => Err(SadakFsError::NotFound),
+        }
+    }
+
+    /// `lookup`'un izin denetimli hali: `cred` kimliğinin `parent` dizininde arama
+    /// (`Access::EXECUTE`) izni olmalıdır.
+    pub fn lookup_as(&self, parent: &Inode, name: &str, cred: &Credentials) -> Result<Inode, SadakFsError<D>> {
+        if parent.file_type == FILE_TYPE_DIRECTORY && !parent.permits(cred, Access::EXECUTE) {
+            return Err(SadakFsError::PermissionDenied);
+        }
+        self.lookup(parent, name)
+    }
+
+    /// Inode'un izin bitlerini değiştirir (`MODE_MASK` dışındaki bitler `EINVAL`).
+    pub fn chmod(&self, inode: &mut Inode, mode: u16) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        if mode & !MODE_MASK != 0 {
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+
+        self.lock.acquire();
+        let result = self.update_inode_locked(inode.inode_id, |target| target.mode = mode);
         self.lock.release();
+        *inode = result?;
+
+        self.commit_if_over_budget()?;
         Ok(())
     }
-    
-    // --- Yardımcı Fonksiyonlar ---
 
-    /// Sahne64 çekirdeğinden sistem zamanını alır.
-    fn get_system_time(&self) -> Result<u64, SadakFsError<D>> {
-        let result = unsafe { 
-            sahne_syscalls::raw_syscall(sahne_syscalls::SYSCALL_GET_SYSTEM_TIME, 0, 0, 0, 0, 0, 0) 
+    /// Inode'un sahibini ve grubunu değiştirir; kullanım eski sahibin kotasından yenisine
+    /// taşınır (yeni sahibin sınırı uygulanmaz).
+    pub fn chown(&self, inode: &mut Inode, uid: u32, gid: u32) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.update_inode_locked(inode.inode_id, |target| {
+            target.uid = uid;
+            target.gid = gid;
+        });
+        if let Ok(updated) = &result {
+            let quota = self.allocator.quota();
+            if updated.uid != inode.uid {
+                let blocks = updated.block_count as i64;
+                quota.adjust(QuotaId::User(inode.uid), -blocks, -1);
+                quota.adjust(QuotaId::User(updated.uid), blocks, 1);
+            }
+        }
+        self.lock.release();
+        *inode = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    /// Inode'u güncel haliyle okur, `update` ile değiştirip geri yazar (çağıran `lock`'u tutmalıdır).
+    fn update_inode_locked<F: FnOnce(&mut Inode)>(&self, inode_id: BlockId, update: F) -> Result<Inode, SadakFsError<D>> {
+        let mut target = self.read_inode(inode_id)?;
+        update(&mut target);
+        self.write_inode_locked(&mut target)?;
+        Ok(target)
+    }
+
+    /// `parent` dizinindeki `name` girdisini kaldırır ve hedefin bağlantı sayısını azaltır.
+    ///
+    /// Bağlantı sayısı sıfıra inerse dosyanın tüm veri blokları, veri ağacının düğümleri
+    /// ve inode bloğu bırakılır (son commit'e ait bloklar commit tamamlanınca serbest
+    /// kalır). Dizinler yalnızca boşken silinebilir.
+    pub fn unlink(&self, parent: &Inode, name: &str) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        if parent.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        if !DirEntry::is_valid_name(name) {
+            return Err(SadakFsError::InvalidName);
+        }
+
+        self.lock.acquire();
+        let result = self.atomic_locked(|| self.unlink_locked(parent.inode_id, name));
+        self.lock.release();
+        result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    /// `unlink` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn unlink_locked(&self, parent_id: BlockId, name: &str) -> Result<(), SadakFsError<D>> {
+        let (key, entry) = self.find_entry(parent_id, name)?.ok_or(SadakFsError::NotFound)?;
+        let mut target = self.entry_inode(&entry)?;
+        if target.file_type == FILE_TYPE_DIRECTORY && target.file_size != 0 {
+            return Err(SadakFsError::DirectoryNotEmpty);
+        }
+
+        // 1. Girdiyi kaldır ve üst dizini güncelle
+        self.metadata_tree.remove_entry(&self.allocator, &key)?;
+        let mut parent = self.read_inode(parent_id)?;
+        parent.file_size = parent.file_size.saturating_sub(1);
+        parent.modification_time = self.get_system_time()?;
+        self.write_inode_locked(&mut parent)?;
+
+        // 2. Bağlantı sayısını azalt; son bağlantıysa blokları geri kazan
+        self.drop_link_locked(&mut target)
+    }
+
+    /// Bir inode'u açık olarak işaretler (ör. bir dosya tanıtıcısı açıldığında). Açık bir
+    /// inode'un son bağlantısı kaldırılırsa blokları `release_inode` ile son tutucu
+    /// bırakılana kadar geri kazanılmaz; bu arada çökme olursa sonraki montaj geri kazanır.
+    pub fn hold_inode(&self, inode: &Inode) {
+        self.lock.acquire();
+        *unsafe { &mut *self.open_inodes.get() }.entry(inode.inode_id).or_insert(0) += 1;
+        self.lock.release();
+    }
+
+    /// `hold_inode` ile alınan bir tutucuyu bırakır. Son tutucu bırakıldığında inode'un
+    /// bağlantısı kalmamışsa blokları geri kazanılır ve yetim listesinden çıkarılır.
+    pub fn release_inode(&self, inode: &Inode) -> Result<(), SadakFsError<D>> {
+        self.lock.acquire();
+        let result = self.release_inode_locked(inode.inode_id);
+        self.lock.release();
+        result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    fn release_inode_locked(&self, inode_id: BlockId) -> Result<(), SadakFsError<D>> {
+        let open = unsafe { &mut *self.open_inodes.get() };
+        match open.get_mut(&inode_id) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                return Ok(());
+            }
+            Some(_) => {
+                open.remove(&inode_id);
+            }
+            None => return Err(SadakFsError::Syscall(SyscallError::EINVAL)),
+        }
+
+        if self.metadata_tree.lookup(&Orphan::key(inode_id))?.is_none() || self.read_only.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        self.metadata_tree.remove_entry(&self.allocator, &Orphan::key(inode_id))?;
+        let mut inode = self.read_inode(inode_id)?;
+        if inode.link_count == 0 {
+            self.free_inode_locked(&mut inode)?;
+        }
+        Ok(())
+    }
+
+    /// Bir inode'un bağlantı sayısını azaltır ve yazar. Son bağlantı kaldırıldıysa inode
+    /// açık değilse hemen serbest bırakılır, açıksa yetim listesine eklenir (çağıran
+    /// `lock`'u tutmalıdır).
+    fn drop_link_locked(&self, inode: &mut Inode) -> Result<(), SadakFsError<D>> {
+        inode.link_count = inode.link_count.saturating_sub(1);
+        if inode.link_count != 0 {
+            return self.write_inode_locked(inode);
+        }
+        if unsafe { &*self.open_inodes.get() }.contains_key(&inode.inode_id) {
+            self.metadata_tree.insert_entry(&self.allocator, &Orphan::key(inode.inode_id), &[])?;
+            return self.write_inode_locked(inode);
+        }
+        self.free_inode_locked(inode)
+    }
+
+    /// Yetim listesinde kalan tüm inode'ları serbest bırakır ve listeyi boşaltır
+    /// (çağıran `lock`'u tutmalıdır). Montajda ve çevrim dışı küçültmede açık tutucu
+    /// olamayacağından listedeki her inode geri kazanılabilir.
+    fn reclaim_orphans_locked(&self) -> Result<(), SadakFsError<D>> {
+        let mut orphans = Vec::new();
+        self.metadata_tree.walk_from(&Orphan::first_key(), &mut |key, _| match Orphan::from_key(key) {
+            Some(inode_id) => {
+                orphans.push(inode_id);
+                true
+            }
+            None => false,
+        })?;
+        if orphans.is_empty() {
+            return Ok(());
+        }
+
+        for inode_id in orphans {
+            self.metadata_tree.remove_entry(&self.allocator, &Orphan::key(inode_id))?;
+            // Okunamayan inode atlanır; kaydı kalmadığından bir daha denenmez
+            if let Ok(mut inode) = self.read_inode(inode_id) {
+                if inode.link_count == 0 {
+                    self.free_inode_locked(&mut inode)?;
+                }
+            }
+        }
+        self.commit_locked()
+    }
+
+    /// `old_parent` dizinindeki `old_name` girdisini `new_parent` dizinine `new_name` adıyla taşır.
+    ///
+    /// Tüm değişiklikler tek bir işlemde (arada commit olmadan) yapılır; bir çökme
+    /// sonrası girdi ya yalnızca eski ya da yalnızca yeni yerde görünür. Hedefte aynı
+    /// adlı bir girdi varsa yerine geçilir (hedef dizinse boş olmalıdır). Bir dizin
+    /// kendi alt ağacına taşınamaz.
+    pub fn rename(&self, old_parent: &Inode, old_name: &str, new_parent: &Inode, new_name: &str) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        if old_parent.file_type != FILE_TYPE_DIRECTORY || new_parent.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        if !DirEntry::is_valid_name(old_name) || !DirEntry::is_valid_name(new_name) {
+            return Err(SadakFsError::InvalidName);
+        }
+
+        self.lock.acquire();
+        let result = self.atomic_locked(|| self.rename_locked(old_parent.inode_id, old_name, new_parent.inode_id, new_name));
+        self.lock.release();
+        result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    /// `rename` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn rename_locked(&self, old_parent_id: BlockId, old_name: &str, new_parent_id: BlockId, new_name: &str) -> Result<(), SadakFsError<D>> {
+        let (old_key, entry) = self.find_entry(old_parent_id, old_name)?.ok_or(SadakFsError::NotFound)?;
+        let (replaced, free_key) = self.find_slot(new_parent_id, new_name)?;
+
+        // 1. Hedefle ilgili kısıtlar
+        if let Some((key, ref target)) = replaced {
+            if key == old_key && entry.name != new_name {
+                // Harf ayrımsız dizinde yalnızca harf büyüklüğü değişiyor: girdi yerinde yeniden adlandırılır
+                let renamed = DirEntry { name: String::from(new_name), ..entry.clone() };
+                self.metadata_tree.insert_entry(&self.allocator, &key, &renamed.encode())?;
+                let mut dir = self.read_inode(old_parent_id)?;
+                dir.modification_time = self.get_system_time()?;
+                self.write_inode_locked(&mut dir)?;
+                return Ok(());
+            }
+            if target.ino == entry.ino {
+                return Ok(()); // Aynı dosyanın iki adı; değişiklik yok
+            }
+            let target_inode = self.entry_inode(target)?;
+            if target_inode.file_type == FILE_TYPE_LOG {
+                return Err(SadakFsError::AppendOnly); // Günlük üzerine yazılarak silinemez
+            }
+            match (entry.file_type == FILE_TYPE_DIRECTORY, target_inode.file_type == FILE_TYPE_DIRECTORY) {
+                (true, false) => return Err(SadakFsError::NotADirectory),
+                (false, true) => return Err(SadakFsError::IsADirectory),
+                (true, true) if target_inode.file_size != 0 => return Err(SadakFsError::DirectoryNotEmpty),
+                _ => {}
+            }
+        }
+        if old_parent_id != new_parent_id
+            && self.read_inode(old_parent_id)?.subvolume_id != self.read_inode(new_parent_id)?.subvolume_id
+        {
+            // Alt birimler arası taşıma kota hesabını bozar (EXDEV)
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+        if entry.file_type == FILE_TYPE_DIRECTORY && old_parent_id != new_parent_id {
+            // Yeni üst dizinden köke (üst dizini kendisi olan dizine) yürürken taşınan
+            // dizine rastlanmamalı
+            let moved_id = self.entry_inode(&entry)?.inode_id;
+            let mut ancestor = new_parent_id;
+            loop {
+                if ancestor == moved_id {
+                    return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+                }
+                let parent = self.read_inode(ancestor)?.parent_dir;
+                if parent == ancestor {
+                    break;
+                }
+                ancestor = parent;
+            }
+        }
+
+        // 2. Girdiyi yeni yerine yaz ve eskisini kaldır
+        let new_key = match replaced {
+            Some((key, _)) => key,
+            None => free_key,
         };
-        if result < 0 {
-            Err(SadakFsError::Syscall(SyscallError::from_raw(result)))
+        let moved = DirEntry { name: String::from(new_name), ..entry.clone() };
+        self.metadata_tree.remove_entry(&self.allocator, &old_key)?;
+        self.metadata_tree.insert_entry(&self.allocator, &new_key, &moved.encode())?;
+
+        // 3. Üst dizinleri ve taşınan dizinin üst işaretçisini güncelle
+        let now = self.get_system_time()?;
+        let mut old_parent = self.read_inode(old_parent_id)?;
+        old_parent.modification_time = now;
+        if old_parent_id != new_parent_id {
+            old_parent.file_size = old_parent.file_size.saturating_sub(1);
+            self.write_inode_locked(&mut old_parent)?;
+
+            let mut new_parent = self.read_inode(new_parent_id)?;
+            new_parent.modification_time = now;
+            if replaced.is_none() {
+                new_parent.file_size += 1;
+            }
+            self.write_inode_locked(&mut new_parent)?;
+            self.index_large_dir_locked(new_parent_id)?;
+
+            if entry.file_type == FILE_TYPE_DIRECTORY {
+                let mut dir = self.entry_inode(&entry)?;
+                dir.parent_dir = new_parent_id;
+                self.write_inode_locked(&mut dir)?;
+            }
         } else {
-            Ok(result as u64)
+            if replaced.is_some() {
+                old_parent.file_size = old_parent.file_size.saturating_sub(1);
+            }
+            self.write_inode_locked(&mut old_parent)?;
+        }
+
+        // 4. Yerine geçilen hedefin bağlantısını düşür
+        if let Some((_, target)) = replaced {
+            let mut target_inode = self.entry_inode(&target)?;
+            self.drop_link_locked(&mut target_inode)?;
+        }
+        Ok(())
+    }
+
+    /// `path` dosyasının içeriğini atomik olarak `data` ile değiştirir (yaz-geçici-taşı deseni).
+    ///
+    /// Veri, dizinde adı olmayan geçici bir inode'a yazılır; ardından hedefin dizin girdisi
+    /// bu inode'u gösterecek şekilde değiştirilir ve her şey tek bir işlemde diske yazılır
+    /// (`sync`). Bir çökme sonrası dosya ya tamamen eski ya da tamamen yeni içerikle
+    /// görünür. Hedef varsa yeni dosya onun izinlerini ve sahibini alır; yoksa oluşturulur.
+    /// Hedef dizinse `IsADirectory`, günlük dosyasıysa `AppendOnly` döner.
+    ///
+    /// # Döndürür
+    /// Yeni içeriği taşıyan inode.
+    pub fn replace_file(&self, path: &str, data: &[u8]) -> Result<Inode, SadakFsError<D>> {
+        self.check_writable()?;
+        let (parent_path, name) = path::split_last(path).ok_or(SadakFsError::InvalidName)?;
+        if !DirEntry::is_valid_name(name) {
+            return Err(SadakFsError::InvalidName);
         }
+        let parent = self.open(parent_path, OpenFlags::DIRECTORY)?;
+
+        self.charge_io(IoKind::Write, data.len());
+        self.lock.acquire();
+        let result = self.replace_file_locked(parent.inode_id, name, data);
+        self.lock.release();
+        result
     }
-}
\ No newline at end of file
+
+    /// `replace_file` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn replace_file_locked(&self, parent_id: BlockId, name: &str, data: &[u8]) -> Result<Inode, SadakFsError<D>> {
+        let (replaced, free_key) = self.find_slot(parent_id, name)?;
+        let previous = match &replaced {
+            Some((_, entry)) => {
+                let previous = self.entry_inode(entry)?;
+                if previous.file_type == FILE_TYPE_DIRECTORY {
+                    return Err(SadakFsError::IsADirectory);
+                }
+                self.check_rewritable(&previous)?;
+                Some(previous)
+            }
+            None => None,
+        };
+
+        // 1. Geçici inode'u oluştur ve veriyi yaz; hata olursa inode geri bırakılır
+        let owner = previous.as_ref().map_or(Credentials::ROOT, |previous| Credentials { uid: previous.uid, gid: previous.gid });
+        let subvolume = self.read_inode(parent_id)?.subvolume_id;
+        let temp = self.create_inode_locked(FILE_TYPE_REGULAR, 0, &owner, subvolume)?;
+        // Şifreli dosyanın yerine geçen veri de aynı anahtarla şifrelenir
+        let encrypted = match previous.as_ref().filter(|previous| previous.key_id != 0) {
+            Some(previous) => self.set_encryption_locked(temp.inode_id, previous.key_id).map(|_| ()),
+            None => Ok(()),
+        };
+        let written = encrypted.and_then(|_| self.write_at_locked(temp.inode_id, 0, data)).and_then(|mut inode| {
+            if let Some(previous) = &previous {
+                inode.mode = previous.mode;
+            }
+            self.write_inode_locked(&mut inode)?;
+            Ok(inode)
+        });
+        let inode = match written {
+            Ok(inode) => inode,
+            Err(e) => {
+                let mut temp = self.read_inode(temp.inode_id)?;
+                self.free_inode_locked(&mut temp)?;
+                return Err(e);
+            }
+        };
+
+        // 2. Girdiyi yeni inode'a çevir (yoksa ekle), üst dizini güncelle ve eski dosyanın
+        // bağlantısını düşür; yarıda kalan değişiklik geri alınır ve geçici inode bırakılır
+        let entry = DirEntry {
+            name: String::from(name),
+            ino: self.entry_ino(&inode),
+            file_type: FILE_TYPE_REGULAR,
+        };
+        let key = replaced.as_ref().map_or(free_key, |(key, _)| *key);
+        let swapped = self.atomic_locked(|| {
+            self.metadata_tree.insert_entry(&self.allocator, &key, &entry.encode())?;
+            let mut parent = self.read_inode(parent_id)?;
+            if replaced.is_none() {
+                parent.file_size += 1;
+            }
+            parent.modification_time = self.get_system_time()?;
+            self.write_inode_locked(&mut parent)?;
+            self.index_large_dir_locked(parent_id)?;
+            if let Some(mut previous) = previous {
+                self.drop_link_locked(&mut previous)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = swapped {
+            // Geri alınamadıysa birim salt okunurdur; geçici inode'a dokunulmaz
+            if !matches!(e, SadakFsError::RollbackFailed) {
+                let mut temp = self.read_inode(temp.inode_id)?;
+                self.free_inode_locked(&mut temp)?;
+            }
+            return Err(e);
+        }
+
+        // 3. Değişikliğin tamamı tek commit'te kalıcı olur
+        self.commit_locked()?;
+        Ok(inode)
+    }
+
+    /// Bağlantısı kalmamış bir inode'un tüm bloklarını bırakır (çağıran `lock`'u tutmalıdır).
+    fn free_inode_locked(&self, inode: &mut Inode) -> Result<(), SadakFsError<D>> {
+        // Geri yazılmayı bekleyen sayfalar artık hiçbir yere yazılmamalı
+        self.pages.take_inode(inode.inode_id);
+        self.allocator.quota().release_pending(inode.inode_id);
+
+        // Öznitelikleri ve taşma bloklarını kaldır
+        let mut xattrs = Vec::new();
+        self.scan_xattrs(inode.inode_id, &mut |key, entry| {
+            xattrs.push((*key, entry));
+            true
+        })?;
+        for (key, entry) in xattrs {
+            self.metadata_tree.remove_entry(&self.allocator, &key)?;
+            self.release_xattr_value(&entry)?;
+        }
+        // Boşaltılmış ama göçü bitmemiş dizinin kaydı
+        if inode.flags & INODE_FLAG_DIR_MIGRATING != 0 {
+            self.metadata_tree.remove_entry(&self.allocator, &DirMigration::key(self.entry_ino(inode)))?;
+        }
+
+        if inode.data_tree_root != 0 {
+            let data_tree = self.data_tree(inode.data_tree_root)?;
+            let mut extents = Vec::new();
+            let mut fragments = Vec::new();
+            data_tree.walk(&mut |key, value| {
+                if let Some(extent) = Extent::from_item(key, value) {
+                    extents.push(extent);
+                } else if let Some(fragment) = TailFragment::from_item(key, value) {
+                    fragments.push(fragment);
+                }
+            })?;
+
+            for extent in extents {
+                for physical in extent.physical_blocks() {
+                    self.release_data_block_locked(physical)?;
+                }
+            }
+            for fragment in fragments {
+                self.release_tail_fragment_locked(&fragment)?;
+            }
+            for node_id in data_tree.node_ids()? {
+                self.allocator.release_block(node_id)?;
+            }
+        }
+
+        // Blok commit'e kadar tahsisli kalabilir; sıfır bağlantı sayısı dışa aktarım
+        // tanıtıcılarının bu arada da bayat sayılmasını sağlar.
+        self.account_quota(inode, -(inode.block_count as i64), -1);
+        inode.block_count = 0;
+        self.write_inode_locked(inode)?;
+        if inode.ino != 0 {
+            self.metadata_tree.remove_entry(&self.allocator, &InodeLocation::key(inode.ino))?;
+        }
+        self.allocator.release_block(inode.inode_id)?;
+        Ok(())
+    }
+
+    /// Bir dizinin tüm girdilerini döndürür: doğrusal dizinlerde oluşturulma, ad özetli
+    /// dizinlerde özet sırasıyla.
+    pub fn read_dir(&self, dir: &Inode) -> Result<Vec<DirEntry>, SadakFsError<D>> {
+        if dir.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+
+        let mut entries = Vec::new();
+        self.scan_dir(dir.inode_id, &mut |_, entry| {
+            entries.push(entry);
+            true
+        })?;
+        Ok(entries)
+    }
+
+    /// Bir dizinin `cursor`'dan sonraki en fazla `max` girdisini `read_dir` sırasıyla
+    /// döndürür; dönen imleçle okuma kalınan yerden sürdürülür. Dizinin sonuna ulaşıldığında
+    /// dönen imlecin `is_end`'i doğrudur. Büyük dizinleri küçük arabelleklerle okumak için.
+    ///
+    /// `max` 0 ise `EINVAL`. Dizin okuma sürerken ad özetli düzene çevrildiyse (bkz.
+    /// `DIR_HASH_THRESHOLD`) eski sıra numaraları geçersiz kalır ve `StaleHandle` döner;
+    /// çağıran okumayı `DirCursor::START`'tan yeniden başlatmalıdır. Göç parçaları arasında
+    /// taşınan bir girdi aynı okumada iki kez görülebilir.
+    pub fn read_dir_from(&self, dir: &Inode, cursor: DirCursor, max: usize) -> Result<(Vec<DirEntry>, DirCursor), SadakFsError<D>> {
+        if dir.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        if max == 0 {
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+        read_dir_batch_in(&self.metadata_tree, self.sb(), &self.read_inode(dir.inode_id)?, cursor, max)
+    }
+
+    /// Dizini en fazla `batch` girdilik parçalar halinde okuyan yineleyici (bkz. `DirIterator`).
+    pub fn dir_iter(&self, dir: &Inode, batch: usize) -> Result<DirIterator<'_, D>, SadakFsError<D>> {
+        DirIterator::resume(self, dir, DirCursor::START, batch)
+    }
+
+    /// Mutlak bir yolu kök dizinden başlayarak çözer ve hedef inode'u döndürür.
+    pub fn open(&self, path: &str, flags: OpenFlags) -> Result<Inode, SadakFsError<D>> {
+        let root = self.root_dir()?;
+        self.open_at(&root, path, flags)
+    }
+
+    /// Bir yolu `base` dizininden (yol `/` ile başlıyorsa kök dizinden) başlayarak çözer.
+    ///
+    /// Ara bileşenler dizin olmalıdır. Son bileşen yoksa ve `CREATE` verildiyse boş bir
+    /// dosya oluşturulur. Sonda `/` varsa veya `DIRECTORY` verildiyse hedef dizin olmalıdır.
+    pub fn open_at(&self, base: &Inode, path: &str, flags: OpenFlags) -> Result<Inode, SadakFsError<D>> {
+        self.open_at_with(base, path, flags, None)
+    }
+
+    /// `open`'ın izin denetimli hali (bkz. `open_at_as`).
+    pub fn open_as(&self, path: &str, flags: OpenFlags, cred: &Credentials) -> Result<Inode, SadakFsError<D>> {
+        let root = self.root_dir()?;
+        self.open_at_with(&root, path, flags, Some(cred))
+    }
+
+    /// `open_at`'ın izin denetimli hali. Geçilen her dizinde arama, oluşturulan dosyanın
+    /// üst dizininde yazma, açılan hedefte de `READ`/`WRITE` bayraklarına karşılık gelen
+    /// izin gerekir; izin yoksa `PermissionDenied` döner. Oluşturulan dosyanın sahibi `cred` olur.
+    pub fn open_at_as(&self, base: &Inode, path: &str, flags: OpenFlags, cred: &Credentials) -> Result<Inode, SadakFsError<D>> {
+        self.open_at_with(base, path, flags, Some(cred))
+    }
+
+    /// `open_at` gövdesi; `cred` verilmişse izinler denetlenir.
+    fn open_at_with(&self, base: &Inode, path: &str, flags: OpenFlags, cred: Option<&Credentials>) -> Result<Inode, SadakFsError<D>> {
+        let permits = |inode: &Inode, access: Access| cred.map_or(true, |cred| inode.permits(cred, access));
+        if path.is_empty() {
+            return Err(SadakFsError::NotFound);
+        }
+
+        let want_dir = flags.contains(OpenFlags::DIRECTORY) || path::has_trailing_separator(path);
+        let mut current = if path::is_absolute(path) {
+            self.root_dir()?
+        } else {
+            self.read_inode(base.inode_id)?
+        };
+
+        let mut created = false;
+        let mut components = path::components(path).peekable();
+        while let Some(component) = components.next() {
+            let is_last = components.peek().is_none();
+            if current.file_type != FILE_TYPE_DIRECTORY {
+                return Err(SadakFsError::NotADirectory);
+            }
+            if !permits(&current, Access::EXECUTE) {
+                return Err(SadakFsError::PermissionDenied);
+            }
+
+            current = match component {
+                Component::CurDir => current,
+                Component::ParentDir => self.read_inode(current.parent_dir)?,
+                Component::Normal(name) => match self.lookup(&current, name) {
+                    Ok(child) => {
+                        if is_last && flags.contains(OpenFlags::CREATE | OpenFlags::EXCLUSIVE) {
+                            return Err(SadakFsError::AlreadyExists);
+                        }
+                        child
+                    }
+                    Err(SadakFsError::NotFound) if is_last && flags.contains(OpenFlags::CREATE) && !want_dir => {
+                        if !permits(&current, Access::WRITE) {
+                            return Err(SadakFsError::PermissionDenied);
+                        }
+                        created = true;
+                        self.create_entry(&current, name, FILE_TYPE_REGULAR, cred.unwrap_or(&Credentials::ROOT))?
+                    }
+                    Err(e) => return Err(e),
+                },
+            };
+        }
+
+        if want_dir && current.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        if flags.contains(OpenFlags::WRITE) && current.file_type == FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::IsADirectory);
+        }
+        // Yeni oluşturulan dosya, izin bitlerinden bağımsız olarak açılabilir (POSIX)
+        if !created {
+            let wanted = [(OpenFlags::READ, Access::READ), (OpenFlags::WRITE, Access::WRITE)];
+            for (flag, access) in wanted {
+                if flags.contains(flag) && !permits(&current, access) {
+                    return Err(SadakFsError::PermissionDenied);
+                }
+            }
+        }
+        Ok(current)
+    }
+
+    /// `create_dir`, `create_file_in` ve `open_at` için ortak gövde; yeni inode `owner`'a ait olur.
+    fn create_entry(&self, parent: &Inode, name: &str, file_type: u8, owner: &Credentials) -> Result<Inode, SadakFsError<D>> {
+        self.check_writable()?;
+        if parent.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        if !DirEntry::is_valid_name(name) {
+            return Err(SadakFsError::InvalidName);
+        }
+
+        self.lock.acquire();
+        let result = self.atomic_locked(|| self.create_entry_locked(parent.inode_id, name, file_type, owner));
+        self.lock.release();
+        let new_inode = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(new_inode)
+    }
+
+    /// `create_entry` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn create_entry_locked(&self, parent_id: BlockId, name: &str, file_type: u8, owner: &Credentials) -> Result<Inode, SadakFsError<D>> {
+        // 1. İsim çakışmasını denetle ve yeni girdinin anahtarını bul
+        let (existing, free_key) = self.find_slot(parent_id, name)?;
+        if existing.is_some() {
+            return Err(SadakFsError::AlreadyExists);
+        }
+
+        // 2. Inode'u üst dizinin alt biriminde oluştur ve girdiyi metadata ağacına ekle
+        let subvolume = self.read_inode(parent_id)?.subvolume_id;
+        let mut new_inode = self.create_inode_locked(file_type, 0, owner, subvolume)?;
+        if file_type == FILE_TYPE_DIRECTORY {
+            // Alt dizinler üst dizinin ad karşılaştırma kuralını devralır
+            new_inode.parent_dir = parent_id;
+            new_inode.flags |= self.read_inode(parent_id)?.flags & (INODE_FLAG_CASEFOLD | INODE_FLAG_CASEFOLD_UNICODE);
+            self.write_inode_locked(&mut new_inode)?;
+        }
+        let entry = DirEntry {
+            name: String::from(name),
+            ino: self.entry_ino(&new_inode),
+            file_type,
+        };
+        self.metadata_tree.insert_entry(&self.allocator, &free_key, &entry.encode())?;
+
+        // 3. Üst dizinin girdi sayısını ve değişiklik zamanını güncelle
+        let mut parent = self.read_inode(parent_id)?;
+        parent.file_size += 1;
+        parent.modification_time = new_inode.creation_time;
+        self.write_inode_locked(&mut parent)?;
+        self.index_large_dir_locked(parent_id)?;
+
+        Ok(new_inode)
+    }
+
+    /// Bir dizinde `name` adlı girdiyi ve `name` adlı yeni bir girdinin anahtarını bulur.
+    /// Girdi bulunursa arama durduğundan anahtar yalnızca girdi yoksa anlamlıdır.
+    fn find_slot(&self, dir_id: BlockId, name: &str) -> Result<(Option<(BTreeKey, DirEntry)>, BTreeKey), SadakFsError<D>> {
+        let mut found = None;
+        let dir = self.read_inode(dir_id)?;
+        let fold = casefold_of(&dir);
+        if dir.flags & INODE_FLAG_DIR_HASHED == 0 {
+            // Doğrusal dizin: dizindeki en büyük sıra numarasının bir fazlası
+            let mut next_index = 0;
+            self.scan_dir(dir_id, &mut |key, entry| {
+                next_index = key.offset + 1;
+                if fold.matches(&entry.name, name) {
+                    found = Some((*key, entry));
+                    return false;
+                }
+                true
+            })?;
+            return Ok((found, DirEntry::key(self.entry_ino(&dir), next_index)));
+        }
+
+        // Özetli dizin: aynı özeti paylaşan girdilerde kullanılmayan ilk çakışma numarası
+        let hash = DirEntry::name_hash(&fold.fold(name), dir_hash_seed(self.sb()));
+        let mut free = 0;
+        scan_hash_run(&self.metadata_tree, self.entry_ino(&dir), hash, &mut |key, entry| {
+            if fold.matches(&entry.name, name) {
+                found = Some((*key, entry));
+                return false;
+            }
+            if DirEntry::key_collision(key) == free {
+                free += 1;
+            }
+            true
+        })?;
+        if found.is_none() && dir.flags & INODE_FLAG_DIR_MIGRATING != 0 {
+            scan_migrating_in(&self.metadata_tree, self.entry_ino(&dir), &mut |key, entry| {
+                if fold.matches(&entry.name, name) {
+                    found = Some((*key, entry));
+                    return false;
+                }
+                true
+            })?;
+        }
+        // Özetin tüm çakışma numaraları dolu: dizine bu ad için yer kalmadı
+        if found.is_none() && free > DIR_HASH_COLLISION_MAX {
+            return Err(SadakFsError::Allocator(AllocatorError::OutOfSpace));
+        }
+        Ok((found, DirEntry::hashed_key(self.entry_ino(&dir), hash, free)))
+    }
+
+    /// Bir dizinde `name` adlı girdiyi anahtarıyla birlikte bulur (doğrusal dizinde tarama,
+    /// özetli dizinde özet araması; dizinin harf karşılaştırma kuralıyla).
+    fn find_entry(&self, dir_id: BlockId, name: &str) -> Result<Option<(BTreeKey, DirEntry)>, SadakFsError<D>> {
+        find_entry_in(&self.metadata_tree, self.sb(), &self.read_inode(dir_id)?, name)
+    }
+
+    /// Girdi eklenmiş bir dizini ad özetli düzene taşımayı sürdürür (çağıran `lock`'u
+    /// tutmalıdır). `DIR_HASH_THRESHOLD` girdiye ulaşmış doğrusal dizin (eşiği çoktan aşmış
+    /// eski dizinler dahil) ad özetli ve `INODE_FLAG_DIR_MIGRATING` bayraklı olur; kalan
+    /// doğrusal aralık `DirMigration` kaydında tutulur ve aramalar bu aralığı da tarar. Her
+    /// eklemede en fazla `DIR_MIGRATE_BATCH` girdi yeniden anahtarlanır, böylece büyük bir
+    /// dizin tek işlem yerine sınırlı parçalarla çevrilir.
+    fn index_large_dir_locked(&self, dir_id: BlockId) -> Result<(), SadakFsError<D>> {
+        let mut dir = self.read_inode(dir_id)?;
+        let dir_ino = self.entry_ino(&dir);
+        let mut migration = if dir.flags & INODE_FLAG_DIR_MIGRATING != 0 {
+            match dir_migration_in(&self.metadata_tree, dir_ino)? {
+                Some(migration) => migration,
+                None => return Err(self.invariant_violated(dir_id)),
+            }
+        } else if dir.flags & INODE_FLAG_DIR_HASHED == 0 && dir.file_size >= DIR_HASH_THRESHOLD {
+            // Doğrusal girdilerin tümü dizinin son sıra numarasında veya altındadır
+            let limit = match self.metadata_tree.lookup_floor(&DirEntry::key(dir_ino, u64::MAX))? {
+                Some((key, _)) if DirEntry::belongs_to(&key, dir_ino) => key.offset.saturating_add(1),
+                _ => 0,
+            };
+            dir.flags |= INODE_FLAG_DIR_HASHED | INODE_FLAG_DIR_MIGRATING;
+            self.write_inode_locked(&mut dir)?;
+            let sb = self.sb_mut();
+            sb.feature_ro_compat |= FEATURE_RO_COMPAT_DIR_HASH;
+            sb.feature_incompat |= FEATURE_INCOMPAT_DIR_MIGRATE;
+            DirMigration { cursor: 0, limit }
+        } else {
+            return Ok(());
+        };
+
+        // 1. Sıradaki parça; bozuk girdiler yerinde bırakılır (scrub raporlar)
+        let mut batch = Vec::new();
+        let mut full = false;
+        self.metadata_tree.walk_from(&DirEntry::key(dir_ino, migration.cursor), &mut |key, value| {
+            if !DirEntry::belongs_to(key, dir_ino) || key.offset >= migration.limit {
+                return false;
+            }
+            if batch.len() == DIR_MIGRATE_BATCH {
+                full = true;
+                return false;
+            }
+            migration.cursor = key.offset + 1;
+            if let Some(entry) = DirEntry::decode(value) {
+                batch.push((*key, entry));
+            }
+            true
+        })?;
+        if !full {
+            migration.cursor = migration.limit;
+        }
+
+        // 2. Parçayı kaldır ve aralığı daralt; `find_slot` taşınan adları artık bulmaz
+        for (key, _) in &batch {
+            self.metadata_tree.remove_entry(&self.allocator, key)?;
+        }
+        if migration.is_done() {
+            self.metadata_tree.remove_entry(&self.allocator, &DirMigration::key(dir_ino))?;
+            dir.flags &= !INODE_FLAG_DIR_MIGRATING;
+            self.write_inode_locked(&mut dir)?;
+        } else {
+            self.metadata_tree.insert_entry(&self.allocator, &DirMigration::key(dir_ino), &migration.encode())?;
+        }
+
+        // 3. Girdileri ad özetleriyle yeniden ekle
+        for (_, entry) in batch {
+            let (_, key) = self.find_slot(dir_id, &entry.name)?;
+            self.metadata_tree.insert_entry(&self.allocator, &key, &entry.encode())?;
+        }
+        Ok(())
+    }
+
+    /// Bir dizinin girdilerini metadata ağacında sırayla ziyaret eder.
+    /// `visit` `false` döndürdüğünde tarama durur.
+    fn scan_dir<F: FnMut(&BTreeKey, DirEntry) -> bool>(&self, dir_id: BlockId, visit: &mut F) -> Result<(), SadakFsError<D>> {
+        scan_dir_in(&self.metadata_tree, self.entry_ino(&self.read_inode(dir_id)?), visit)
+    }
+
+    /// Boş bir dizinin ad karşılaştırma kuralını değiştirir. Adlar yazıldığı gibi saklanır;
+    /// `Casefold::Ascii` veya `Casefold::Unicode` ile arama, oluşturma ve yeniden adlandırma
+    /// harf büyüklüğünü yok sayar (yalnızca harf büyüklüğü farklı iki ad bulunamaz). Sonradan
+    /// oluşturulan alt dizinler kuralı devralır. Dizin boş değilse `DirectoryNotEmpty`; ilk
+    /// açılışta birime `FEATURE_RO_COMPAT_CASEFOLD` eklenir.
+    pub fn set_casefold(&self, dir: &mut Inode, casefold: Casefold) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        if dir.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        self.lock.acquire();
+        let result = self.set_casefold_locked(dir.inode_id, casefold);
+        self.lock.release();
+        *dir = result?;
+        Ok(())
+    }
+
+    /// `set_casefold` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn set_casefold_locked(&self, dir_id: BlockId, casefold: Casefold) -> Result<Inode, SadakFsError<D>> {
+        // Mevcut adlar yeni kurala göre çakışabilir ve özetleri değişir; boş dizin şartı bunu önler
+        if self.read_inode(dir_id)?.file_size != 0 {
+            return Err(SadakFsError::DirectoryNotEmpty);
+        }
+        let inode = self.update_inode_locked(dir_id, |dir| {
+            dir.flags &= !(INODE_FLAG_CASEFOLD | INODE_FLAG_CASEFOLD_UNICODE);
+            dir.flags |= match casefold {
+                Casefold::None => 0,
+                Casefold::Ascii => INODE_FLAG_CASEFOLD,
+                Casefold::Unicode => INODE_FLAG_CASEFOLD | INODE_FLAG_CASEFOLD_UNICODE,
+            };
+        })?;
+        if casefold != Casefold::None {
+            self.sb_mut().feature_ro_compat |= FEATURE_RO_COMPAT_CASEFOLD;
+        }
+        Ok(inode)
+    }
+
+    /// Dizinin ad karşılaştırma kuralı.
+    pub fn casefold(&self, dir: &Inode) -> Casefold {
+        casefold_of(dir)
+    }
+
+    // --- Genişletilmiş Öznitelikler (xattr) ---
+
+    /// Bir inode'a `name` adlı özniteliği yazar; varsa değeri değiştirilir.
+    /// Metadata öğesine sığmayan değerler ayrı bir taşma bloğunda tutulur.
+    pub fn set_xattr(&self, inode: &Inode, name: &str, value: &[u8]) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        if !XattrEntry::is_valid_name(name) {
+            return Err(SadakFsError::InvalidName);
+        }
+        if value.len() > XATTR_VALUE_MAX {
+            return Err(SadakFsError::ValueTooLarge);
+        }
+
+        self.lock.acquire();
+        let result = self.set_xattr_locked(inode.inode_id, name, value);
+        self.lock.release();
+        result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    /// `set_xattr` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn set_xattr_locked(&self, inode_id: BlockId, name: &str, value: &[u8]) -> Result<(), SadakFsError<D>> {
+        self.read_inode(inode_id)?; // Inode geçerli olmalı
+        let (existing, next_index) = self.find_xattr(inode_id, name)?;
+
+        let stored = if XattrEntry::fits_inline(name, value) {
+            XattrValue::Inline(value.to_vec())
+        } else {
+            // Taşma bloğu her yazmada yeniden tahsis edilir (CoW); eskisi aşağıda bırakılır.
+            let block = self.allocator.allocate_block()?;
+            let block_arc = self.cache.get_block(block)?;
+            let block_mut = unsafe { &mut *block_arc.get() };
+            block_mut.data[..value.len()].copy_from_slice(value);
+            block_mut.data[value.len()..].fill(0);
+            block_mut.is_dirty = true;
+            XattrValue::Overflow { block, len: value.len() as u32 }
+        };
+
+        let key = match &existing {
+            Some((key, _)) => *key,
+            None => XattrEntry::key(inode_id, next_index),
+        };
+        let entry = XattrEntry { name: String::from(name), value: stored };
+        self.metadata_tree.insert_entry(&self.allocator, &key, &entry.encode())?;
+
+        if let Some((_, old)) = existing {
+            self.release_xattr_value(&old)?;
+        }
+        Ok(())
+    }
+
+    /// Bir inode'un `name` adlı özniteliğinin değerini döndürür.
+    pub fn get_xattr(&self, inode: &Inode, name: &str) -> Result<Vec<u8>, SadakFsError<D>> {
+        let (found, _) = self.find_xattr(inode.inode_id, name)?;
+        match found.ok_or(SadakFsError::NotFound)?.1.value {
+            XattrValue::Inline(value) => Ok(value),
+            XattrValue::Overflow { block, len } => {
+                let block_arc = self.cache.get_block(block)?;
+                let data = unsafe { &(*block_arc.get()).data };
+                Ok(data[..(len as usize).min(BLOCK_SIZE)].to_vec())
+            }
+        }
+    }
+
+    /// Bir inode'un tüm özniteliklerinin adlarını oluşturulma sırasıyla döndürür.
+    pub fn list_xattr(&self, inode: &Inode) -> Result<Vec<String>, SadakFsError<D>> {
+        let mut names = Vec::new();
+        self.scan_xattrs(inode.inode_id, &mut |_, entry| {
+            names.push(entry.name);
+            true
+        })?;
+        Ok(names)
+    }
+
+    /// Bir inode'un `name` adlı özniteliğini siler.
+    pub fn remove_xattr(&self, inode: &Inode, name: &str) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.find_xattr(inode.inode_id, name).and_then(|(found, _)| {
+            let (key, entry) = found.ok_or(SadakFsError::NotFound)?;
+            self.metadata_tree.remove_entry(&self.allocator, &key)?;
+            self.release_xattr_value(&entry)
+        });
+        self.lock.release();
+        result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    /// Bir inode'un `name` özniteliğini ve yeni bir öznitelik için sıradaki numarayı bulur.
+    fn find_xattr(&self, inode_id: BlockId, name: &str) -> Result<(Option<(BTreeKey, XattrEntry)>, u64), SadakFsError<D>> {
+        let mut found = None;
+        let mut next_index = 0;
+        self.scan_xattrs(inode_id, &mut |key, entry| {
+            next_index = key.offset + 1;
+            if entry.name == name {
+                found = Some((*key, entry));
+                return false;
+            }
+            true
+        })?;
+        Ok((found, next_index))
+    }
+
+    /// Bir inode'un özniteliklerini metadata ağacında sırayla ziyaret eder.
+    fn scan_xattrs<F: FnMut(&BTreeKey, XattrEntry) -> bool>(&self, inode_id: BlockId, visit: &mut F) -> Result<(), SadakFsError<D>> {
+        self.metadata_tree.walk_from(&XattrEntry::key(inode_id, 0), &mut |key, value| {
+            if !XattrEntry::belongs_to(key, inode_id) {
+                return false;
+            }
+            match XattrEntry::decode(value) {
+                Some(entry) => visit(key, entry),
+                None => true, // Bozuk öğe atlanır; scrub tarafından raporlanır
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Özniteliğin taşma bloğu varsa bırakır.
+    fn release_xattr_value(&self, entry: &XattrEntry) -> Result<(), SadakFsError<D>> {
+        if let XattrValue::Overflow { block, .. } = entry.value {
+            self.allocator.release_block(block)?;
+        }
+        Ok(())
+    }
+
+    // --- Dışa Aktarım Tanıtıcıları ---
+
+    /// Bir inode için ağ istemcilerine verilebilecek kalıcı tanıtıcı üretir.
+    pub fn export_handle(&self, inode: &Inode) -> ExportHandle {
+        ExportHandle {
+            inode_id: inode.inode_id,
+            generation: inode.generation,
+        }
+    }
+
+    /// Bir tanıtıcıyı yeniden açar (ör. sunucu yeniden başladıktan sonra).
+    ///
+    /// Tanıtıcının işaret ettiği blok artık tahsisli değilse, geçerli bir inode
+    /// içermiyorsa, inode silinmişse veya nesil numarası tutmuyorsa (blok yeni bir
+    /// dosya için yeniden kullanılmış) `StaleHandle` döner.
+    pub fn open_export_handle(&self, handle: &ExportHandle) -> Result<Inode, SadakFsError<D>> {
+        if handle.inode_id == 0 || handle.inode_id >= self.sb().total_blocks {
+            return Err(SadakFsError::StaleHandle);
+        }
+        if !self.allocator.is_allocated(handle.inode_id)? {
+            return Err(SadakFsError::StaleHandle);
+        }
+
+        let inode = match self.read_inode(handle.inode_id) {
+            Ok(inode) => inode,
+            // Blok artık bir inode içermiyor
+            Err(SadakFsError::ChecksumError) => return Err(SadakFsError::StaleHandle),
+            Err(e) => return Err(e),
+        };
+
+        if inode.generation != handle.generation || inode.link_count == 0 {
+            return Err(SadakFsError::StaleHandle);
+        }
+        Ok(inode)
+    }
+
+    // --- Veri Yerleşimi (Katmanlama) ---
+
+    /// Aygıtın ilk `blocks` bloğunu hızlı katman (ör. SSD) olarak tanımlar; 0 katmanlamayı
+    /// kapatır. Değer Superblock'a yazılır ve sonraki commit ile kalıcı olur.
+    pub fn set_fast_tier(&self, blocks: BlockId) {
+        self.lock.acquire();
+        let blocks = blocks.min(self.sb().total_blocks);
+        self.sb_mut().fast_tier_blocks = blocks;
+        self.allocator.set_fast_tier_end(blocks);
+        self.lock.release();
+    }
+
+    /// Bir dosyanın yerleşim ipucunu değiştirir. İpucu sonraki tahsisleri ve
+    /// `demote_cold_extents` kararlarını etkiler; mevcut veri hemen taşınmaz.
+    pub fn set_placement_hint(&self, inode: &mut Inode, hint: PlacementHint) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.read_inode(inode.inode_id).and_then(|mut current| {
+            current.placement_hint = hint.to_raw();
+            self.write_inode_locked(&mut current)
+        });
+        self.lock.release();
+        result?;
+
+        inode.placement_hint = hint.to_raw();
+        Ok(())
+    }
+
+    /// Soğuk bir dosyanın hızlı katmandaki extent'lerini yavaş katmana taşır.
+    ///
+    /// Arka plan taşıyıcısının (migrator) her adımda çağırması için tasarlanmıştır:
+    /// tek çağrıda en fazla `budget_blocks` blok taşınır (bütçeyi aşacak extent bir
+    /// sonraki çağrıya kalır). Dosya `Cold` ipuçluysa veya ipucu yokken `COLD_DATA_AGE`
+    /// süresince değişmediyse soğuk sayılır. Eski bloklar commit sonrasında serbest kalır.
+    ///
+    /// # Döndürür
+    /// Taşınan blok sayısı.
+    pub fn demote_cold_extents(&self, inode: &Inode, budget_blocks: u64) -> Result<u64, SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.demote_cold_extents_locked(inode.inode_id, budget_blocks);
+        self.lock.release();
+        let moved = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(moved)
+    }
+
+    /// `demote_cold_extents` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn demote_cold_extents_locked(&self, inode_id: BlockId, budget_blocks: u64) -> Result<u64, SadakFsError<D>> {
+        let fast_end = self.allocator.fast_tier_end();
+        let mut inode = self.read_inode(inode_id)?;
+        if fast_end == 0 || !self.is_cold(&inode)? {
+            return Ok(0);
+        }
+
+        // 1. Hızlı katmanda kalan extent'leri topla (sıkıştırılmış kümeler bölünemediğinden taşınmaz)
+        let data_tree = self.data_tree(inode.data_tree_root)?;
+        let mut victims = Vec::new();
+        data_tree.walk(&mut |key, value| {
+            if let Some(extent) = Extent::from_item(key, value) {
+                if extent.physical_start < fast_end && !extent.is_compressed() {
+                    victims.push(extent);
+                }
+            }
+        })?;
+
+        let cold = PlacementHint { temperature: Temperature::Cold, ..PlacementHint::from_raw(inode.placement_hint) };
+        let mut buffer = try_zeroed(BLOCK_SIZE)?;
+        let mut moved = 0;
+
+        for extent in victims {
+            let length = extent.length as u64;
+            if moved + length > budget_blocks {
+                break;
+            }
+
+            // 2. Yavaş katmanda yeni yer ayır; yavaş katman doluysa taşıma durur
+            let mut pieces = Vec::new();
+            let mut reserved = 0;
+            while reserved < length {
+                let (physical, len) = self.allocator.allocate_extent_hinted(length - reserved, cold)?;
+                pieces.push((physical, len));
+                reserved += len;
+                if physical < fast_end {
+                    break;
+                }
+            }
+            if pieces.iter().any(|&(physical, _)| physical < fast_end) {
+                for (physical, len) in pieces {
+                    for id in physical..physical + len {
+                        self.allocator.release_block(id)?;
+                    }
+                }
+                break;
+            }
+
+            // 3. Veriyi kopyala ve extent kaydını yeni parçalarla değiştir; commit'i bekleyen
+            // (ör. `write_at` ile yeni yazılmış) blokların güncel kopyası önbellektedir
+            data_tree.remove_entry(&self.allocator, &Extent::key(extent.file_block))?;
+            let mut file_block = extent.file_block;
+            for (physical, len) in pieces {
+                self.cache.discard_range(physical, len);
+                for i in 0..len {
+                    let source = extent.physical_for(file_block + i);
+                    if self.cache.has_dirty_in_range(source, 1) {
+                        let block_arc = self.cache.get_block(source)?;
+                        buffer.copy_from_slice(unsafe { &(*block_arc.get()).data[..] });
+                    } else {
+                        self.cache.device.read_block(source, &mut buffer)?;
+                    }
+                    self.cache.device.write_block(physical + i, &buffer)?;
+                }
+
+                let piece = Extent { file_block, physical_start: physical, length: len as u32, ..extent };
+                data_tree.insert_entry(&self.allocator, &Extent::key(piece.file_block), &piece.encode())?;
+                file_block += len;
+            }
+
+            // 4. Eski bloklar son commit'e ait olabilir; serbest bırakma commit'e ertelenir
+            for id in extent.physical_start..extent.physical_start + length {
+                self.release_data_block_locked(id)?;
+            }
+            moved += length;
+        }
+
+        self.store_data_tree(&mut inode, &data_tree)?;
+        self.write_inode_locked(&mut inode)?;
+        Ok(moved)
+    }
+
+    /// Dosya, taşıyıcı açısından soğuk mu?
+    fn is_cold(&self, inode: &Inode) -> Result<bool, SadakFsError<D>> {
+        Ok(match PlacementHint::from_raw(inode.placement_hint).temperature {
+            Temperature::Cold => true,
+            Temperature::Hot => false,
+            Temperature::Default => self.get_system_time()?.saturating_sub(inode.modification_time) > COLD_DATA_AGE,
+        })
+    }
+
+    // --- Saydam Sıkıştırma ---
+
+    /// Dosyanın saydam sıkıştırmasını `compression` algoritmasıyla açar veya (`None`) kapatır.
+    ///
+    /// Açıkken geri yazılan sayfalar (`write_buffered`) `COMPRESS_CLUSTER_BLOCKS` bloğa
+    /// kadar kümeler halinde sıkıştırılır; sıkıştırılamaz görünen kümeler olduğu gibi
+    /// yazılır. `write_at` ile doğrudan yazılan bloklar ve mevcut veri sıkıştırılmaz; yani
+    /// algoritma değişikliği yalnızca bundan sonra yazılan veriyi etkiler. Okumalar
+    /// sıkıştırmayı görmez. `block_count` ve kotalar sıkıştırılmamış boyutu sayar.
+    /// Dosyanın ayarı, alt birimin ayarından (`set_subvolume_compression`) önceliklidir.
+    /// İlk açılışta birime `FEATURE_INCOMPAT_COMPRESSION`, zstd seçilince de
+    /// `FEATURE_INCOMPAT_ZSTD` eklenir.
+    pub fn set_compression(&self, inode: &mut Inode, compression: Option<Compression>) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.update_inode_locked(inode.inode_id, |current| match compression {
+            Some(compression) => {
+                current.flags |= INODE_FLAG_COMPRESS;
+                current.compression = compression.to_raw();
+            }
+            None => {
+                current.flags &= !INODE_FLAG_COMPRESS;
+                current.compression = 0;
+            }
+        });
+        if result.is_ok() {
+            self.enable_compression_features(compression);
+        }
+        self.lock.release();
+        *inode = result?;
+        Ok(())
+    }
+
+    /// Dosyanın sıkıştırma algoritması; kendi ayarı yoksa `None` (alt birimin ayarı yine
+    /// de uygulanabilir, bkz. `subvolume_compression`).
+    pub fn compression(&self, inode: &Inode) -> Option<Compression> {
+        (inode.flags & INODE_FLAG_COMPRESS != 0).then(|| Compression::from_raw(inode.compression))
+    }
+
+    /// Geri yazmada dosyaya uygulanan algoritma: dosyanın kendi ayarı, yoksa alt biriminki.
+    /// Şifreli dosyalar sıkıştırılmaz (şifreli veri sıkışmaz, şifrelemeden önce sıkıştırmak
+    /// ise içerik hakkında boyut üzerinden bilgi sızdırır).
+    fn effective_compression(&self, inode: &Inode) -> Result<Option<Compression>, SadakFsError<D>> {
+        if inode.key_id != 0 {
+            return Ok(None);
+        }
+        match self.compression(inode) {
+            Some(compression) => Ok(Some(compression)),
+            None => self.subvolume_compression_of(inode.subvolume_id),
+        }
+    }
+
+    /// Seçilen algoritmanın yazacağı extent'leri okuyabilmek için gereken özellikleri
+    /// Superblock'a ekler (çağıran `lock`'u tutmalıdır).
+    fn enable_compression_features(&self, compression: Option<Compression>) {
+        match compression {
+            Some(Compression::Lz4) => self.sb_mut().feature_incompat |= FEATURE_INCOMPAT_COMPRESSION,
+            Some(Compression::Zstd(_)) => self.sb_mut().feature_incompat |= FEATURE_INCOMPAT_COMPRESSION | FEATURE_INCOMPAT_ZSTD,
+            None => {}
+        }
+    }
+
+    // --- Dosya Başına Şifreleme ---
+    // Şifreli bir dosyanın yalnızca veri blokları şifrelenir; adı, boyutu, sahibi ve diğer
+    // metadata'sı açıktır. Anahtarı yüklü olmayan dosya opaktır: listelenir ve `stat`
+    // edilebilir, silinebilir, ama içeriği okunamaz ve yazılamaz (`KeyUnavailable`).
+    // Böylece Sahne64'te her kullanıcı kendi anahtarını oturum açtığında yükler ve diğer
+    // kullanıcıların dosyalarını aynı birimde görse de okuyamaz.
+
+    /// Dosya anahtarını anahtarlığa yükler; bu anahtarla şifreli dosyalar okunup yazılabilir
+    /// hale gelir. Anahtarlık yalnızca bellektedir; her montajdan sonra yeniden yüklenmelidir.
+    ///
+    /// # Döndürür
+    /// Anahtarın kimliği (bkz. `set_encryption`). Kimlik anahtardan türetilir; aynı anahtar
+    /// her zaman aynı kimliği verir.
+    pub fn add_file_key(&self, key: &[u8; CRYPT_KEY_SIZE]) -> u64 {
+        let key = FileKey::new(key);
+        let key_id = key.id();
+        self.keys_lock.acquire();
+        unsafe { &mut *self.file_keys.get() }.insert(key_id, Arc::new(key));
+        self.keys_lock.release();
+        key_id
+    }
+
+    /// Anahtarı anahtarlıktan çıkarır; o anahtarla şifreli dosyalar yeniden opak olur.
+    /// Açık sayfalar önceden şifrelenip geri yazılır, böylece bellekte açık veri kalmaz.
+    ///
+    /// # Döndürür
+    /// Anahtar yüklü müydü?
+    pub fn remove_file_key(&self, key_id: u64) -> Result<bool, SadakFsError<D>> {
+        // `lock` anahtar çıkarılana kadar tutulur; arada yeni sayfa yazılamaz
+        self.lock.acquire();
+        let result = self.flush_pages_locked().map(|()| {
+            self.keys_lock.acquire();
+            let removed = unsafe { &mut *self.file_keys.get() }.remove(&key_id).is_some();
+            self.keys_lock.release();
+            removed
+        });
+        self.lock.release();
+        result
+    }
+
+    /// Boş bir normal dosyayı anahtarlıktaki `key_id` anahtarıyla şifreli yapar; bundan
+    /// sonra yazılan veri diske şifreli gider. Şifreleme sonradan kapatılamaz ve anahtar
+    /// değiştirilemez; veri yeni bir dosyaya kopyalanmalıdır. İlk kullanımda birime
+    /// `FEATURE_INCOMPAT_ENCRYPTION` eklenir.
+    ///
+    /// Dosya boş değilse veya zaten başka bir anahtarla şifreliyse `EINVAL`, anahtar yüklü
+    /// değilse `KeyUnavailable` döner.
+    pub fn set_encryption(&self, inode: &mut Inode, key_id: u64) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.set_encryption_locked(inode.inode_id, key_id);
+        self.lock.release();
+        *inode = result?;
+        Ok(())
+    }
+
+    /// `set_encryption` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn set_encryption_locked(&self, inode_id: BlockId, key_id: u64) -> Result<Inode, SadakFsError<D>> {
+        if self.file_key(key_id).is_none() {
+            return Err(SadakFsError::KeyUnavailable);
+        }
+        let current = self.read_inode(inode_id)?;
+        if current.key_id == key_id {
+            return Ok(current);
+        }
+        let empty = current.file_size == 0 && current.block_count == 0 && self.pages.buffered_size(inode_id).is_none();
+        if current.file_type != FILE_TYPE_REGULAR || current.key_id != 0 || !empty {
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+
+        // Nesil sayacı birim içinde tekrar etmez; nonce olarak da kullanılır
+        let nonce = self.take_generation();
+        let inode = self.update_inode_locked(inode_id, |current| {
+            current.key_id = key_id;
+            current.crypt_nonce = nonce;
+        })?;
+        self.sb_mut().feature_incompat |= FEATURE_INCOMPAT_ENCRYPTION;
+        Ok(inode)
+    }
+
+    /// Dosyayı şifreleyen anahtarın kimliği; dosya şifresizse `None`.
+    pub fn encryption_key(&self, inode: &Inode) -> Option<u64> {
+        (inode.key_id != 0).then_some(inode.key_id)
+    }
+
+    /// Dosyanın içeriğine erişilebilir mi? Şifresiz dosyalarda ve anahtarı yüklü şifreli
+    /// dosyalarda `true`; `false` ise dosya opaktır.
+    pub fn is_key_available(&self, inode: &Inode) -> bool {
+        inode.key_id == 0 || self.file_key(inode.key_id).is_some()
+    }
+
+    /// Anahtarlıktaki `key_id` kimlikli anahtar.
+    fn file_key(&self, key_id: u64) -> Option<Arc<FileKey>> {
+        self.keys_lock.acquire();
+        let key = unsafe { &*self.file_keys.get() }.get(&key_id).cloned();
+        self.keys_lock.release();
+        key
+    }
+
+    /// Dosya verisinin şifresi: dosya şifresizse `None`, anahtarı yüklü değilse `KeyUnavailable`.
+    fn file_cipher(&self, inode: &Inode) -> Result<Option<FileCipher>, SadakFsError<D>> {
+        if inode.key_id == 0 {
+            return Ok(None);
+        }
+        match self.file_key(inode.key_id) {
+            Some(key) => Ok(Some(FileCipher::new(key, inode.crypt_nonce))),
+            None => Err(SadakFsError::KeyUnavailable),
+        }
+    }
+
+    // --- Dosya Verisi Yazma (Gecikmeli Tahsis) ---
+
+    /// `data`'yı dosyanın `offset` konumuna yazar.
+    ///
+    /// Veri yalnızca sayfa önbelleğine alınır; disk blokları commit sırasında ardışık
+    /// extent'ler halinde tahsis edilir. `inode`'un boyutu hemen güncellenir, diskteki
+    /// inode ise geri yazma sırasında güncellenir. Yazmanın sonu `u64`'ü aşarsa `EINVAL`.
+    ///
+    /// # Döndürür
+    /// Yazılan bayt sayısı.
+    pub fn write_buffered(&self, inode: &mut Inode, offset: u64, data: &[u8]) -> Result<usize, SadakFsError<D>> {
+        self.check_writable()?;
+        self.charge_io(IoKind::Write, data.len());
+        self.lock.acquire();
+        let result = self.write_buffered_locked(inode, offset, data);
+        self.lock.release();
+        result?;
+
+        // Sayfalar da bütçeye sayılır; çok büyük yazmalar ara commit'lerle geri yazılır.
+        self.commit_if_over_budget()?;
+        Ok(data.len())
+    }
+
+    /// `write_buffered` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn write_buffered_locked(&self, inode: &mut Inode, offset: u64, data: &[u8]) -> Result<(), SadakFsError<D>> {
+        let end = offset.checked_add(data.len() as u64).ok_or(SadakFsError::Syscall(SyscallError::EINVAL))?;
+        self.check_rewritable(&self.read_inode(inode.inode_id)?)?;
+        self.unpack_tail_locked(inode.inode_id)?;
+        let current = self.read_inode(inode.inode_id)?;
+        let cipher = self.file_cipher(&current)?;
+        let data_tree = self.data_tree(current.data_tree_root)?;
+
+        // Bloklar geri yazmada tahsis edilir; deliklere düşen ve henüz tamponlanmamış
+        // sayfaların blokları geri yazılana kadar kotadan ayrılır
+        if !data.is_empty() {
+            let last_block = (end - 1) / BLOCK_SIZE as u64;
+            let mut new_blocks = 0;
+            for file_block in offset / BLOCK_SIZE as u64..=last_block {
+                if !self.pages.contains((inode.inode_id, file_block)) && self.extent_at(&data_tree, file_block)?.is_none() {
+                    new_blocks += 1;
+                }
+            }
+            let owner = QuotaId::User(current.uid);
+            if !self.allocator.quota().reserve_pending(inode.inode_id, owner, QuotaId::Subvolume(current.subvolume_id), new_blocks) {
+                return Err(SadakFsError::Allocator(AllocatorError::QuotaExceeded));
+            }
+        }
+
+        let mut written = 0;
+        while written < data.len() {
+            let position = offset + written as u64;
+            let file_block = position / BLOCK_SIZE as u64;
+            let offset_in_page = (position % BLOCK_SIZE as u64) as usize;
+            let len = (BLOCK_SIZE - offset_in_page).min(data.len() - written);
+
+            // Kısmi sayfa yazmalarında sayfa önce dosyanın mevcut içeriğiyle doldurulur.
+            self.pages.write(
+                (inode.inode_id, file_block),
+                offset_in_page,
+                &data[written..written + len],
+                |page| self.load_file_block(&data_tree, file_block, cipher.as_ref(), page),
+            )?;
+            written += len;
+        }
+
+        inode.file_size = inode.file_size.max(end);
+        self.pages.extend_size(inode.inode_id, inode.file_size);
+        self.stats_mut().logical_bytes += data.len() as u64;
+
+        // Veri yalnızca sayfa önbelleğinde olsa da değişiklik sayacı hemen ilerler
+        inode.change_seq = self.update_inode_locked(inode.inode_id, |_| {})?.change_seq;
+        Ok(())
+    }
+
+    // --- Dosya Verisi Okuma/Yazma (Veri Ağacı Üzerinden) ---
+    // TODO: Asenkron bir aygıt katmanı (tamamlanmayı bekleyen `BlockDevice` istekleri) ve
+    // no_std bir yürütücü eklendiğinde `open`, `read_at`, `write_at` ve `sync`'in `async`
+    // karşılıkları sunulmalı; böylece Sahne64'ün asenkron servisleri disk gecikmesinde
+    // çekirdek görevlerini bloklamaz. Bu ağaçta henüz ikisi de yok; işlemler eşzamanlıdır.
+
+    /// `data`'yı dosyanın `offset` konumuna doğrudan blok önbelleği üzerinden yazar.
+    ///
+    /// `write_buffered`'ın aksine bloklar hemen tahsis edilir: bu işlemde zaten
+    /// tahsis edilmiş bir bloğa yerinde yazılır, diğer bloklar için yeni blok
+    /// tahsis edilip (CoW) veri ağacında eşlenir. Kısmi blok yazmalarında bloğun
+    /// geri kalanı dosyanın mevcut içeriğiyle (delikte sıfırla) doldurulur.
+    /// `inode` güncel haliyle değiştirilir. Yazmanın sonu `u64`'ü aşarsa `EINVAL`.
+    ///
+    /// # Döndürür
+    /// Yazılan bayt sayısı.
+    pub fn write_at(&self, inode: &mut Inode, offset: u64, data: &[u8]) -> Result<usize, SadakFsError<D>> {
+        self.check_writable()?;
+        self.charge_io(IoKind::Write, data.len());
+        self.lock.acquire();
+        let result = self
+            .read_inode(inode.inode_id)
+            .and_then(|current| self.check_rewritable(&current))
+            .and_then(|_| self.write_at_locked(inode.inode_id, offset, data));
+        self.lock.release();
+        *inode = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(data.len())
+    }
+
+    /// `write_at` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn write_at_locked(&self, inode_id: BlockId, offset: u64, data: &[u8]) -> Result<Inode, SadakFsError<D>> {
+        // Bekleyen sayfalar önce geri yazılır; aksi halde commit sırasında bu yazmanın üzerine yazılırlar.
+        self.flush_inode_pages_locked(inode_id)?;
+        // Paketlenmiş kuyruk yazılmadan önce yeniden kendi bloğuna alınır
+        self.unpack_tail_locked(inode_id)?;
+
+        let mut inode = self.read_inode(inode_id)?;
+        if inode.file_type == FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::IsADirectory);
+        }
+        // Önbellekteki bloklar açık veri tutmamalı; şifreli dosyalar sayfa önbelleğinden
+        // geçip geri yazmada şifrelenir
+        if inode.key_id != 0 {
+            self.write_buffered_locked(&mut inode, offset, data)?;
+            self.flush_inode_pages_locked(inode_id)?;
+            return self.read_inode(inode_id);
+        }
+
+        // Tahsisler dosyanın sahibinin ve alt biriminin kotasına yazılır
+        let block_count = inode.block_count;
+        let quota = self.allocator.quota();
+        quota.begin_charge(QuotaId::User(inode.uid), QuotaId::Subvolume(inode.subvolume_id));
+        let result = self.write_blocks_locked(inode, offset, data);
+        quota.end_charge();
+        let updated = result?;
+        self.account_quota(&updated, updated.block_count as i64 - block_count as i64, 0);
+        Ok(updated)
+    }
+
+    /// `write_at_locked`'ın blok yazan kısmı; kota sahibi bildirilmiş olmalıdır.
+    fn write_blocks_locked(&self, mut inode: Inode, offset: u64, data: &[u8]) -> Result<Inode, SadakFsError<D>> {
+        let end = offset.checked_add(data.len() as u64).ok_or(SadakFsError::Syscall(SyscallError::EINVAL))?;
+        let data_tree = self.data_tree(inode.data_tree_root)?;
+        let hint = PlacementHint::from_raw(inode.placement_hint);
+        let nocow = self.is_nocow(inode.subvolume_id)?;
+
+        let mut written = 0;
+        while written < data.len() {
+            let position = offset + written as u64;
+            let file_block = position / BLOCK_SIZE as u64;
+            let offset_in_block = (position % BLOCK_SIZE as u64) as usize;
+            let len = (BLOCK_SIZE - offset_in_block).min(data.len() - written);
+
+            let target = match self.mapped_block(&data_tree, file_block)? {
+                // Son commit'ten sonra tahsis edilmiş blok hiçbir tutarlı duruma ait değil;
+                // yine de bir klonla paylaşılıyorsa yerinde değiştirilemez
+                Some(physical) if self.allocator.is_fresh(physical) && !self.is_shared_block(physical)? => physical,
+                // nocow alt biriminde son commit'e ait blok da yerinde değiştirilir
+                Some(physical) if nocow && self.in_place_allowed() && !self.is_shared_block(physical)? => {
+                    #[cfg(feature = "snapshot")]
+                    unsafe { &mut *self.changed_in_place.get() }.insert(physical);
+                    physical
+                }
+                _ => {
+                    let (physical, _) = self.allocator.allocate_data_extent(1, hint)?;
+                    if len != BLOCK_SIZE {
+                        let block_arc = self.cache.get_block(physical)?;
+                        self.load_file_block(&data_tree, file_block, None, unsafe { &mut (*block_arc.get()).data[..] })?;
+                    }
+
+                    let punched = self.punch_extents_locked(&data_tree, file_block, file_block + 1)?;
+                    let extent = Extent { file_block, physical_start: physical, length: 1, flags: 0, compressed_len: 0, skip_blocks: 0 };
+                    data_tree.insert_entry(&self.allocator, &Extent::key(file_block), &extent.encode())?;
+                    inode.block_count = inode.block_count.saturating_sub(punched) + 1;
+                    physical
+                }
+            };
+
+            let block_arc = self.cache.get_block(target)?;
+            let block = unsafe { &mut *block_arc.get() };
+            block.data[offset_in_block..offset_in_block + len].copy_from_slice(&data[written..written + len]);
+            block.is_dirty = true;
+            written += len;
+        }
+
+        inode.file_size = inode.file_size.max(end);
+        self.store_data_tree(&mut inode, &data_tree)?;
+        inode.modification_time = self.get_system_time()?;
+        self.write_inode_locked(&mut inode)?;
+        self.stats_mut().logical_bytes += data.len() as u64;
+        Ok(inode)
+    }
+
+    /// Dosyanın boyutunu `new_size` yapar.
+    ///
+    /// Küçültmede yeni sonun ötesindeki bloklar bırakılır ve son bloğun dosya sonundan
+    /// sonraki kısmı sıfırlanır; böylece dosya sonradan büyütülürse eski veri görünmez.
+    /// Büyütmede blok tahsis edilmez: eklenen aralık bir delik olarak kalır ve sıfır
+    /// okunur. `inode` güncel haliyle değiştirilir.
+    pub fn truncate(&self, inode: &mut Inode, new_size: u64) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.truncate_locked(inode.inode_id, new_size);
+        self.lock.release();
+        *inode = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    /// `truncate` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn truncate_locked(&self, inode_id: BlockId, new_size: u64) -> Result<Inode, SadakFsError<D>> {
+        // Bekleyen sayfalar önce yazılır; aksi halde kesilen aralığı commit'te geri getirirler.
+        self.flush_inode_pages_locked(inode_id)?;
+
+        let current = self.read_inode(inode_id)?;
+        if current.file_type == FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::IsADirectory);
+        }
+        self.check_rewritable(&current)?;
+        self.unpack_tail_locked(inode_id)?;
+        let current = self.read_inode(inode_id)?;
+        let old_blocks = (current.file_size + BLOCK_SIZE as u64 - 1) / BLOCK_SIZE as u64;
+        let new_blocks = (new_size + BLOCK_SIZE as u64 - 1) / BLOCK_SIZE as u64;
+
+        // 1. Sınırdaki bloğun dosya sonundan sonraki kısmını sıfırla (bu blok CoW ile kopyalanır)
+        let boundary = new_size.min(current.file_size);
+        let tail = (boundary % BLOCK_SIZE as u64) as usize;
+        if tail != 0 {
+            let data_tree = self.data_tree(current.data_tree_root)?;
+            if self.extent_at(&data_tree, boundary / BLOCK_SIZE as u64)?.is_some() {
+                let zeros = [0u8; BLOCK_SIZE];
+                self.write_at_locked(inode_id, boundary, &zeros[..BLOCK_SIZE - tail])?;
+            }
+        }
+
+        let mut inode = self.read_inode(inode_id)?;
+        let data_tree = self.data_tree(inode.data_tree_root)?;
+
+        // 2. Küçültme: yeni sonun ötesindeki blokları bırak
+        if new_blocks < old_blocks {
+            let punched = self.punch_extents_locked(&data_tree, new_blocks, u64::MAX)?;
+            self.account_quota(&inode, -(punched.min(inode.block_count) as i64), 0);
+            inode.block_count = inode.block_count.saturating_sub(punched);
+        }
+
+        // 3. Büyütmede eklenen aralık delik olarak kalır; yalnızca boyut güncellenir
+        inode.file_size = new_size;
+        self.store_data_tree(&mut inode, &data_tree)?;
+        inode.modification_time = self.get_system_time()?;
+        self.write_inode_locked(&mut inode)?;
+        Ok(inode)
+    }
+
+    /// Dosyanın `[offset, offset + len)` aralığını deliğe çevirir; aralık sonradan sıfır okunur.
+    ///
+    /// Aralığın tamamen kapsadığı bloklar veri ağacından çıkarılıp bırakılır (`block_count`
+    /// düşer); kısmen kapsanan baştaki ve sondaki blokların ilgili kısmı sıfırlanır. Dosya
+    /// boyutu değişmez; dosya sonunun ötesi yok sayılır. `inode` güncel haliyle değiştirilir.
+    pub fn punch_hole(&self, inode: &mut Inode, offset: u64, len: u64) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.punch_hole_locked(inode.inode_id, offset, len);
+        self.lock.release();
+        *inode = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    /// `punch_hole` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn punch_hole_locked(&self, inode_id: BlockId, offset: u64, len: u64) -> Result<Inode, SadakFsError<D>> {
+        // Bekleyen sayfalar önce yazılır; aksi halde delinen aralığı commit'te geri getirirler.
+        self.flush_inode_pages_locked(inode_id)?;
+
+        let current = self.read_inode(inode_id)?;
+        if current.file_type == FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::IsADirectory);
+        }
+        self.check_rewritable(&current)?;
+        self.unpack_tail_locked(inode_id)?;
+        let end = offset.saturating_add(len).min(current.file_size);
+        if offset >= end {
+            return Ok(current);
+        }
+        let block_size = BLOCK_SIZE as u64;
+        let first_full = (offset + block_size - 1) / block_size;
+        let last_full = end / block_size;
+
+        // 1. Kısmen kapsanan baştaki ve sondaki blokların ilgili kısmını sıfırla (eşliyse)
+        let zeros = [0u8; BLOCK_SIZE];
+        let mut partials = Vec::new();
+        if offset % block_size != 0 {
+            partials.push((offset, end.min(first_full * block_size)));
+        }
+        if end % block_size != 0 && last_full >= first_full {
+            partials.push((last_full * block_size, end));
+        }
+        for (from, to) in partials {
+            let data_tree = self.data_tree(self.read_inode(inode_id)?.data_tree_root)?;
+            if self.extent_at(&data_tree, from / block_size)?.is_some() {
+                self.write_at_locked(inode_id, from, &zeros[..(to - from) as usize])?;
+            }
+        }
+
+        // 2. Tamamen kapsanan blokları veri ağacından çıkar ve bırak
+        let mut inode = self.read_inode(inode_id)?;
+        if first_full < last_full {
+            let data_tree = self.data_tree(inode.data_tree_root)?;
+            let punched = self.punch_extents_locked(&data_tree, first_full, last_full)?;
+            self.account_quota(&inode, -(punched.min(inode.block_count) as i64), 0);
+            inode.block_count = inode.block_count.saturating_sub(punched);
+            self.store_data_tree(&mut inode, &data_tree)?;
+        }
+
+        // 3. Boyut değişmez; checksum inode yazılırken yeniden hesaplanır
+        inode.modification_time = self.get_system_time()?;
+        self.write_inode_locked(&mut inode)?;
+        Ok(inode)
+    }
+
+    /// `src` dosyasının veri bloklarını kopyalamadan paylaşan yeni bir dosya oluşturur
+    /// (reflink). İki dosyadan biri yazıldığında yalnızca değişen bloklar kopyalanır.
+    ///
+    /// Klonun kendi veri ağacı vardır; paylaşılan yalnızca veri bloklarıdır ve her biri
+    /// metadata ağacında başvuru sayacıyla izlenir (bkz. `SharedBlock`). Klon bir dizine
+    /// bağlanmaz; sahiplik, izinler, alt birim ve yerleşim ipucu kaynaktan alınır. Paylaşılan
+    /// bloklar klonun sahibinin kotasına da tam olarak yazılır.
+    pub fn clone_file(&self, src: &Inode) -> Result<Inode, SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.clone_file_locked(src.inode_id, src.subvolume_id);
+        self.lock.release();
+        let clone = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(clone)
+    }
+
+    /// `clone_file` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn clone_file_locked(&self, src_id: BlockId, subvolume: u64) -> Result<Inode, SadakFsError<D>> {
+        // Bekleyen sayfalar önce yazılır; aksi halde klon bunları görmez.
+        self.flush_inode_pages_locked(src_id)?;
+
+        let src = self.read_inode(src_id)?;
+        if src.file_type == FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::IsADirectory);
+        }
+        // Kuyruk parçaları paylaşılmaz; klonlanacak kuyruk önce kendi bloğuna alınır
+        self.unpack_tail_locked(src_id)?;
+        let src = self.read_inode(src_id)?;
+
+        // 1. Kaynağın extent'lerini topla
+        let src_tree = self.data_tree(src.data_tree_root)?;
+        let mut extents = Vec::new();
+        src_tree.walk(&mut |key, value| {
+            if let Some(extent) = Extent::from_item(key, value) {
+                extents.push(extent);
+            }
+        })?;
+
+        // 2. Aynı extent'leri klonun veri ağacına ekle ve her bloğun sayacını artır
+        let owner = Credentials { uid: src.uid, gid: src.gid };
+        if !self.allocator.quota().allows_blocks(QuotaId::User(owner.uid), QuotaId::Subvolume(subvolume), src.block_count) {
+            return Err(SadakFsError::Allocator(AllocatorError::QuotaExceeded));
+        }
+        let mut clone = self.create_inode_locked(src.file_type, src.file_size, &owner, subvolume)?;
+        let data_tree = self.data_tree(clone.data_tree_root)?;
+        for extent in extents {
+            data_tree.insert_entry(&self.allocator, &Extent::key(extent.file_block), &extent.encode())?;
+            for physical in extent.physical_blocks() {
+                self.share_data_block_locked(physical)?;
+            }
+        }
+
+        clone.block_count = src.block_count;
+        self.account_quota(&clone, clone.block_count as i64, 0);
+        clone.mode = src.mode;
+        clone.placement_hint = src.placement_hint;
+        clone.flags = src.flags;
+        clone.compression = src.compression;
+        clone.key_id = src.key_id;
+        clone.crypt_nonce = src.crypt_nonce;
+        self.store_data_tree(&mut clone, &data_tree)?;
+        self.write_inode_locked(&mut clone)?;
+        Ok(clone)
+    }
+
+    /// Dosyanın `offset` konumundan `buf`'a okur; delikler sıfır okunur.
+    /// Henüz geri yazılmamış sayfalar da görülür.
+    ///
+    /// # Döndürür
+    /// Okunan bayt sayısı (dosya sonunda `buf.len()`'den az, `offset` dosya sonundaysa 0).
+    pub fn read_at(&self, inode: &Inode, offset: u64, buf: &mut [u8]) -> Result<usize, SadakFsError<D>> {
+        self.charge_io(IoKind::Read, buf.len());
+
+        // Çağıranın kopyası eski olabilir; kök ve boyut güncel inode'dan alınır.
+        let current = self.read_inode(inode.inode_id)?;
+        if current.file_type == FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::IsADirectory);
+        }
+        let file_size = self.visible_size(&current);
+        if offset >= file_size {
+            return Ok(0);
+        }
+
+        let total = (buf.len() as u64).min(file_size - offset) as usize;
+        let cipher = self.file_cipher(&current)?;
+        let data_tree = self.data_tree(current.data_tree_root)?;
+        let mut block = allocate_buffer()?;
+
+        let mut done = 0;
+        while done < total {
+            let position = offset + done as u64;
+            let file_block = position / BLOCK_SIZE as u64;
+            let offset_in_block = (position % BLOCK_SIZE as u64) as usize;
+            let len = (BLOCK_SIZE - offset_in_block).min(total - done);
+
+            self.load_file_block(&data_tree, file_block, cipher.as_ref(), block.as_mut())?;
+            self.pages.overlay(inode.inode_id, file_block, block.as_mut());
+            buf[done..done + len].copy_from_slice(&block[offset_in_block..offset_in_block + len]);
+            done += len;
+        }
+        Ok(total)
+    }
+
+    /// Superblock'u güncelleyip tüm kirli (dirty) blokları diske yazar (Atomik Commit).
+    pub fn sync(&self) -> Result<(), SadakFsError<D>> {
+        // Salt okunur montajda yazılacak bir şey yoktur
+        if self.read_only.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        self.lock.acquire();
+        let result = self.commit_locked();
+        self.lock.release();
+        result
+    }
+
+    // --- İşlem Bütçesi (Transaction Budget) ---
+
+    /// Ara commit tetiklenmeden önce biriktirilebilecek kirli blok sayısını ayarlar.
+    /// Bu değer önbellek belleğinin taşmasını engelleyecek kadar küçük seçilmelidir.
+    pub fn set_dirty_block_budget(&mut self, blocks: usize) {
+        self.dirty_block_budget = blocks.max(1);
+    }
+
+    /// Kirli blok sayısı bütçeyi aştıysa veya commit aralığı dolduysa (bkz.
+    /// `MountOptions::commit_interval`) o anki tutarlı durumu commit eder.
+    ///
+    /// Büyük işlemler (özyinelemeli silme, büyük yazmalar) bunu yalnızca tutarlı
+    /// ara noktalarda ve `lock` bırakılmışken çağırmalıdır; böylece bir çökme
+    /// sonrası birim, yarım kalmış bir adımı değil son tamamlanan adımı gösterir.
+    ///
+    /// # Döndürür
+    /// Ara commit yapıldıysa `true`.
+    pub fn commit_if_over_budget(&self) -> Result<bool, SadakFsError<D>> {
+        // Transaction açıkken ara commit yapılmaz; değişiklikler `Transaction::commit`'i bekler
+        if self.transaction_active.load(Ordering::Acquire) {
+            return Ok(false);
+        }
+        let dirty = self.cache.dirty_count() + self.pages.dirty_pages();
+        let interval_elapsed = self.commit_interval != 0
+            && dirty != 0
+            && self.get_system_time()?.saturating_sub(self.sb().timestamp) >= self.commit_interval;
+        // `data=journal` kipinde yerinde yazılan bloklar günlüğe sığmalı
+        let budget = match self.data_mode {
+            DataMode::Ordered => self.dirty_block_budget,
+            DataMode::Journal => self.dirty_block_budget.min(JOURNAL_CAPACITY),
+        };
+        if dirty <= budget && !interval_elapsed {
+            return Ok(false);
+        }
+
+        self.sync()?;
+        Ok(true)
+    }
+
+    // --- Çok İşlemli Transaction'lar ---
+
+    /// Bir transaction başlatır: tanıtıcı açıkken yapılan oluşturma, yazma, yeniden
+    /// adlandırma gibi işlemler CoW kopyalarında biriktirilir ve ara commit yapılmaz.
+    /// `Transaction::commit` hepsini tek bir Superblock güncellemesiyle kalıcı yapar;
+    /// `Transaction::rollback` (veya tanıtıcının düşürülmesi) hepsini geri alır.
+    ///
+    /// Önce o ana kadarki değişiklikler commit edilir. Aynı anda tek transaction açık
+    /// olabilir; açıkken diğer görevlerin yazmaları da transaction'a dahil olur. Birim
+    /// boyutunu, snapshot'ları veya scrub konumunu değiştiren işlemler `TransactionActive`
+    /// döner. Biriken kirli bloklar önbellekte kaldığından transaction'lar kısa tutulmalıdır.
+    pub fn begin_transaction(&self) -> Result<Transaction<'_, D>, SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = if self.transaction_active.load(Ordering::Acquire) {
+            Err(SadakFsError::TransactionActive)
+        } else {
+            self.commit_locked().map(|()| {
+                self.transaction_active.store(true, Ordering::Release);
+                Transaction {
+                    fs: self,
+                    // Superblock düz veridir (Drop'u yok); bit bit kopyası güvenlidir
+                    superblock: Box::new(unsafe { core::ptr::read(self.superblock.get()) }),
+                    quotas: self.allocator.quota().all(),
+                    open_tail_block: self.open_tail_block.load(Ordering::Acquire),
+                    finished: false,
+                }
+            })
+        };
+        self.lock.release();
+        result
+    }
+
+    /// Açık transaction varsa `TransactionActive` döner.
+    fn check_no_transaction(&self) -> Result<(), SadakFsError<D>> {
+        if self.transaction_active.load(Ordering::Acquire) {
+            Err(SadakFsError::TransactionActive)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Transaction'ı tek commit'le kalıcı yapar. Commit başarısız olursa bellekteki
+    /// durum da geri alınır; disk son commit'teki halinde kalır.
+    fn commit_transaction(&self, saved: &Transaction<'_, D>) -> Result<(), SadakFsError<D>> {
+        self.lock.acquire();
+        self.transaction_active.store(false, Ordering::Release);
+        let result = self.commit_locked();
+        if result.is_err() {
+            self.abort_transaction_locked(saved);
+        }
+        self.lock.release();
+        result
+    }
+
+    /// Transaction'da biriken değişiklikleri atar ve bellekteki durumu transaction
+    /// başındaki commit'e döndürür (çağıran `lock`'u tutmalıdır).
+    fn abort_transaction_locked(&self, saved: &Transaction<'_, D>) {
+        // 1. Bekleyen dosya sayfaları ve kirli bloklar hiç yazılmadan atılır; yerinde
+        // değiştirilen bloklar (inode'lar, tahsis haritası) diskteki halleriyle yeniden okunur
+        for inode_id in self.pages.dirty_inodes() {
+            self.pages.take_inode(inode_id);
+            self.allocator.quota().release_pending(inode_id);
+        }
+        self.cache.discard_dirty();
+        unsafe { &mut *self.changed_in_place.get() }.clear();
+
+        // 2. Bu transaction'da tahsis edilen bloklar ve kota ayırmaları unutulur
+        self.allocator.abort_transaction();
+        self.allocator.quota().restore(saved.quotas.clone());
+
+        // 3. Kökler ve sayaçlar transaction başındaki haline döner
+        unsafe { core::ptr::copy_nonoverlapping(&*saved.superblock, self.superblock.get(), 1) };
+        self.metadata_tree.set_root(self.sb().metadata_root_id);
+        self.open_tail_block.store(saved.open_tail_block, Ordering::Release);
+        self.transaction_active.store(false, Ordering::Release);
+    }
+
+    // --- Tek İşlemin Atomikliği ---
+
+    /// `op`'u tek bir birim olarak çalıştırır (çağıran `lock`'u tutmalıdır).
+    ///
+    /// Bir işlem blok tahsis eder, inode'lar yazar ve birden çok ağaca (metadata, veri
+    /// ağaçları, tahsis haritası) dokunur; bunlar önbellekte birikir ve sonraki commit'te
+    /// tek bir Superblock güncellemesiyle birlikte kalıcı olur. `op` hata dönerse
+    /// (ör. girdi eklenirken alan biterse) yarım kalan değişikliklerin o commit'e
+    /// karışmaması için hepsi `OpRecord` ile geri alınır: dokunulan bloklar, tahsis ve
+    /// bırakmalar, kota sayaçları ile Superblock'taki kökler ve sayaçlar işlemden önceki
+    /// haline döner. İç içe çağrılar en dıştaki kayda katılır.
+    fn atomic_locked<T>(&self, op: impl FnOnce() -> Result<T, SadakFsError<D>>) -> Result<T, SadakFsError<D>> {
+        if !self.cache.begin_undo() {
+            return op();
+        }
+        self.allocator.begin_undo();
+        let record = OpRecord {
+            // Superblock düz veridir (Drop'u yok); bit bit kopyası güvenlidir
+            superblock: Box::new(unsafe { core::ptr::read(self.superblock.get()) }),
+            metadata_root: self.metadata_tree.root_id(),
+            quotas: self.allocator.quota().all(),
+            open_tail_block: self.open_tail_block.load(Ordering::Acquire),
+        };
+
+        let result = op();
+        if result.is_ok() {
+            self.cache.end_undo();
+            self.allocator.end_undo();
+        } else {
+            self.rollback_op_locked(record)?;
+        }
+        result
+    }
+
+    /// Başarısız bir işlemin değişikliklerini geri alır (bkz. `atomic_locked`).
+    ///
+    /// Önbellek kaydı eksikse (işlem sırasında geri yazma yapıldıysa veya kopya için
+    /// bellek ayrılamadıysa) yarım değişiklikler geri alınamaz; bunların sonraki commit'le
+    /// diske gitmemesi için birim salt okunur yapılır ve `RollbackFailed` döner.
+    fn rollback_op_locked(&self, record: OpRecord) -> Result<(), SadakFsError<D>> {
+        if !self.cache.rollback_undo() {
+            self.allocator.end_undo();
+            self.read_only.store(true, Ordering::Release);
+            return Err(SadakFsError::RollbackFailed);
+        }
+        self.allocator.rollback_undo();
+        self.allocator.quota().rewind(record.quotas);
+        unsafe { core::ptr::copy_nonoverlapping(&*record.superblock, self.superblock.get(), 1) };
+        self.metadata_tree.set_root(record.metadata_root);
+        self.open_tail_block.store(record.open_tail_block, Ordering::Release);
+        Ok(())
+    }
+
+    // --- Önden Okuma (Prefetch) ---
+
+    /// Bir dosyanın veri ağacını (blok eşleme yapraklarını) tek geçişte önbelleğe alır.
+    /// Dosya ardışık okuma için açıldığında çağrılır; sonraki okumalar dosya içi
+    /// konumları ek metadata I/O'su beklemeden fiziksel bloklara çevirebilir.
+    ///
+    /// # Döndürür
+    /// Diskten okunan ağaç düğümü sayısı (önden okuma kapalıysa 0).
+    pub fn prefetch_file_map(&self, inode: &Inode) -> Result<usize, SadakFsError<D>> {
+        if !READAHEAD_ENABLED {
+            return Ok(0);
+        }
+
+        // Veri ağacının kökü geri yazma ve taşıma sırasında değişir; güncel inode kullanılır.
+        let current = self.read_inode(inode.inode_id)?;
+        let data_tree = self.data_tree(current.data_tree_root)?;
+        Ok(data_tree.prefetch()?)
+    }
+
+    // --- Akış Okuma (Streaming Read) ---
+
+    /// Dosyayı `offset`'ten sonuna kadar okuyup parça parça `sink`'e aktarır.
+    ///
+    /// Toplu dosya sunumu için hızlı yoldur: veri ağacı tek geçişte dolaşılır, her
+    /// extent çağıranın `buffer`'ına büyük `read_blocks` istekleriyle doğrudan okunur
+    /// (önbellek çalkalanmaz) ve delikler için sıfır üretilir.
+    ///
+    /// # Parametreler
+    /// * `offset`: Başlangıç konumu; `BLOCK_SIZE`'ın katı olmalıdır.
+    /// * `buffer`: Ara bellek; uzunluğu sıfırdan büyük ve `BLOCK_SIZE`'ın katı olmalıdır.
+    /// * `sink`: Doldurulan her parçayla çağrılır; `false` dönerse akış durur.
+    ///
+    /// # Döndürür
+    /// `sink`'e aktarılan toplam bayt sayısı.
+    pub fn read_to_stream<F: FnMut(&[u8]) -> bool>(
+        &self,
+        inode: &Inode,
+        offset: u64,
+        buffer: &mut [u8],
+        sink: &mut F,
+    ) -> Result<u64, SadakFsError<D>> {
+        if offset % BLOCK_SIZE as u64 != 0 || buffer.is_empty() || buffer.len() % BLOCK_SIZE != 0 {
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+
+        // Çağıranın kopyası eski olabilir: kök diskteki inode'dan, boyut ise bekleyen
+        // yazmalar da hesaba katılarak alınır.
+        let current = self.read_inode(inode.inode_id)?;
+        let file_size = current.file_size.max(self.pages.buffered_size(inode.inode_id).unwrap_or(0));
+        if current.key_id != 0 {
+            return self.stream_encrypted(&current, file_size, offset, buffer, sink);
+        }
+
+        let start_block = offset / BLOCK_SIZE as u64;
+        let mut stream = ExtentStream::new(
+            &self.cache,
+            &self.pages,
+            inode.inode_id,
+            file_size,
+            start_block,
+            buffer,
+            sink,
+        );
+
+        if current.data_tree_root != 0 && !stream.is_done() {
+            let data_tree = self.data_tree(current.data_tree_root)?;
+
+            // Başlangıç bloğunu kapsayan extent ondan önce başlıyor olabilir.
+            let start_key = match data_tree.lookup_floor(&Extent::key(start_block))? {
+                Some((key, _)) if key.item_type == ITEM_TYPE_EXTENT => key,
+                _ => Extent::key(start_block),
+            };
+
+            let mut failure = None;
+            let mut cluster = Vec::new();
+            data_tree.walk_from(&start_key, &mut |key, value| {
+                let extent = match Extent::from_item(key, value) {
+                    Some(extent) => extent,
+                    None => return false, // Extent öğelerinin sonu
+                };
+                let result = if extent.is_compressed() {
+                    // Çözme arabelleği yalnızca sıkıştırılmış bir extent görülürse ayrılır
+                    let ready = if cluster.is_empty() { try_zeroed(COMPRESS_CLUSTER_BYTES).map(|buf| cluster = buf) } else { Ok(()) };
+                    ready
+                        .map_err(SadakFsError::from)
+                        .and_then(|_| read_compressed_in(&self.cache, &extent, &mut cluster))
+                        .map(|range| stream.read_decoded(&extent, &cluster[range]))
+                } else {
+                    stream.read_extent(&extent).map_err(SadakFsError::Device)
+                };
+                if let Err(e) = result {
+                    failure = Some(e);
+                    return false;
+                }
+                !stream.is_done()
+            })?;
+
+            if let Some(e) = failure {
+                return Err(e);
+            }
+
+            // Paketlenmiş kuyruk, son extent'ten sonra dosyanın son bloğudur
+            if file_size != 0 {
+                if let Some(fragment) = tail_fragment_in(&data_tree, (file_size - 1) / BLOCK_SIZE as u64)? {
+                    let block_arc = self.cache.get_block(fragment.tail_block)?;
+                    stream.read_tail(fragment.file_block, unsafe { &(*block_arc.get()).data[fragment.range()] });
+                }
+            }
+        }
+
+        // Son extent'ten dosya sonuna kadar olan delik
+        stream.zeros_until(u64::MAX);
+        Ok(stream.delivered())
+    }
+
+    /// `read_to_stream`'in şifreli dosya yolu: bloklar çözülmeleri gerektiğinden doğrudan
+    /// aygıttan değil önbellek üzerinden, `buffer` dolusu okunur.
+    fn stream_encrypted<F: FnMut(&[u8]) -> bool>(
+        &self,
+        inode: &Inode,
+        file_size: u64,
+        mut offset: u64,
+        buffer: &mut [u8],
+        sink: &mut F,
+    ) -> Result<u64, SadakFsError<D>> {
+        let cipher = self.file_cipher(inode)?;
+        let data_tree = self.data_tree(inode.data_tree_root)?;
+
+        let mut delivered = 0;
+        while offset < file_size {
+            let first_block = offset / BLOCK_SIZE as u64;
+            let len = (buffer.len() as u64).min(file_size - offset) as usize;
+            let filled = (len + BLOCK_SIZE - 1) / BLOCK_SIZE * BLOCK_SIZE;
+            for (i, block) in buffer[..filled].chunks_mut(BLOCK_SIZE).enumerate() {
+                self.load_file_block(&data_tree, first_block + i as u64, cipher.as_ref(), block)?;
+            }
+            self.pages.overlay(inode.inode_id, first_block, &mut buffer[..filled]);
+
+            delivered += len as u64;
+            offset += len as u64;
+            if !sink(&buffer[..len]) {
+                break;
+            }
+        }
+        Ok(delivered)
+    }
+
+    // --- Denetim Günlükleri (Yalnızca Ekleme) ---
+    // Günlük, kayıtları checksum'lı başlıklarla art arda tutan bir dosyadır. Yazma yolları
+    // (`write_at`, `write_buffered`, `truncate`, `punch_hole`) ve üzerine taşıma günlüğü
+    // reddeder; kayıt yalnızca `append_log` ile dosya sonuna eklenir. Günlük `unlink` ile
+    // silinebilir; silme yetkisi üst dizinin izinleriyle sınırlandırılmalıdır.
+
+    /// `parent` dizininde `name` adlı boş bir denetim günlüğü oluşturur.
+    pub fn create_log(&self, parent: &Inode, name: &str) -> Result<Inode, SadakFsError<D>> {
+        self.create_entry(parent, name, FILE_TYPE_LOG, &Credentials::ROOT)
+    }
+
+    /// Günlüğün sonuna bir kayıt ekler. Kayıt, diğer yazmalar gibi bir sonraki commit'te
+    /// kalıcı olur; hemen kalıcı olması gerekiyorsa ardından `sync` çağrılmalıdır.
+    ///
+    /// # Döndürür
+    /// Kaydın günlükteki konumu (`read_log` ile okunabilir).
+    pub fn append_log(&self, log: &Inode, data: &[u8]) -> Result<u64, SadakFsError<D>> {
+        self.check_writable()?;
+        if data.len() > LOG_RECORD_MAX {
+            return Err(SadakFsError::ValueTooLarge);
+        }
+        let record = encode_log_record(self.get_system_time()?, data);
+        self.charge_io(IoKind::Write, record.len());
+
+        self.lock.acquire();
+        let result = self.append_log_locked(log.inode_id, &record);
+        self.lock.release();
+        let offset = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(offset)
+    }
+
+    /// `append_log` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn append_log_locked(&self, log_id: BlockId, record: &[u8]) -> Result<u64, SadakFsError<D>> {
+        let current = self.read_inode(log_id)?;
+        if current.file_type != FILE_TYPE_LOG {
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+        let offset = current.file_size;
+        self.write_at_locked(log_id, offset, record)?;
+        Ok(offset)
+    }
+
+    /// Günlükte `offset` konumundaki kaydı okur ve checksum'unu doğrular. Günlüğün
+    /// başından (0) başlayıp `LogRecord::next` izlenerek tüm kayıtlar sırayla okunur.
+    ///
+    /// # Döndürür
+    /// Kayıt; `offset` günlüğün sonundaysa `None`. Bozuk veya yarım kayıtta `ChecksumError`.
+    pub fn read_log(&self, log: &Inode, offset: u64) -> Result<Option<LogRecord>, SadakFsError<D>> {
+        let current = self.read_inode(log.inode_id)?;
+        if current.file_type != FILE_TYPE_LOG {
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+        if offset >= current.file_size {
+            return Ok(None);
+        }
+
+        let mut header = [0u8; LOG_RECORD_HEADER_SIZE];
+        if self.read_at(&current, offset, &mut header)? != LOG_RECORD_HEADER_SIZE {
+            return Err(SadakFsError::ChecksumError);
+        }
+        let u32_at = |at: usize| u32::from_le_bytes([header[at], header[at + 1], header[at + 2], header[at + 3]]);
+        let magic = u32_at(0);
+        let len = u32_at(4) as usize;
+        let timestamp = u32_at(8) as u64 | (u32_at(12) as u64) << 32;
+        let stored = u32_at(16);
+        let next = offset + (LOG_RECORD_HEADER_SIZE + len) as u64;
+        if magic != LOG_RECORD_MAGIC || len > LOG_RECORD_MAX || next > current.file_size {
+            return Err(SadakFsError::ChecksumError);
+        }
+
+        let mut data = alloc::vec![0u8; len];
+        self.read_at(&current, offset + LOG_RECORD_HEADER_SIZE as u64, &mut data)?;
+        if encode_log_record(timestamp, &data)[16..20] != stored.to_le_bytes() {
+            return Err(SadakFsError::ChecksumError);
+        }
+        Ok(Some(LogRecord { offset, timestamp, data, next }))
+    }
+
+    // --- Kuyruk Paketleme (Tail Packing) ---
+
+    /// Dosyanın son kısmi bloğunu paylaşılan bir kuyruk bloğuna taşır ve kendi bloğunu
+    /// bırakır. Yapılandırma dosyası gibi çok sayıda küçük dosya içeren birimlerde her
+    /// dosyanın son bloğunda boşa giden alanı geri kazanmak için isteğe bağlı bir işlemdir.
+    ///
+    /// Yalnızca `TAIL_MAX` bayttan kısa kuyruklar paketlenir; parçalar `TAIL_SLOT_SIZE`
+    /// baytlık dilimlere hizalanır. Dosyaya sonraki ilk yazma (ve kesme, delik açma,
+    /// klonlama) kuyruğu yeniden kendi bloğuna alır. Kuyruk blokları kotalara yazılmaz.
+    /// Parçalar yalnızca bu işlemde tahsis edilmiş kuyruk bloklarına eklenir; böylece
+    /// tutarlı bir duruma ait blok hiçbir zaman yerinde değiştirilmez.
+    ///
+    /// # Döndürür
+    /// Kuyruk paketlendiyse `true`; birim `FEATURE_INCOMPAT_TAIL_PACKING` ile biçimlendirilmemişse,
+    /// dosya normal bir dosya değilse, kuyruğu yoksa, çok
+    /// uzunsa, bir delikteyse, bir klonla paylaşılıyorsa veya zaten paketliyse `false`.
+    pub fn pack_tail(&self, inode: &mut Inode) -> Result<bool, SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.pack_tail_locked(inode.inode_id);
+        self.lock.release();
+        let (packed, updated) = result?;
+        *inode = updated;
+
+        self.commit_if_over_budget()?;
+        Ok(packed)
+    }
+
+    /// `pack_tail` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn pack_tail_locked(&self, inode_id: BlockId) -> Result<(bool, Inode), SadakFsError<D>> {
+        if self.sb().feature_incompat & FEATURE_INCOMPAT_TAIL_PACKING == 0 {
+            return Ok((false, self.read_inode(inode_id)?));
+        }
+        self.flush_inode_pages_locked(inode_id)?;
+
+        let mut inode = self.read_inode(inode_id)?;
+        let len = (inode.file_size % BLOCK_SIZE as u64) as usize;
+        // Şifreli bloğun bir parçası tek başına çözülemez; şifreli dosyaların kuyruğu paketlenmez
+        if inode.file_type != FILE_TYPE_REGULAR || len == 0 || len > TAIL_MAX || inode.data_tree_root == 0 || inode.key_id != 0 {
+            return Ok((false, inode));
+        }
+        let file_block = inode.file_size / BLOCK_SIZE as u64;
+        let data_tree = self.data_tree(inode.data_tree_root)?;
+        let physical = match self.mapped_block(&data_tree, file_block)? {
+            Some(physical) if !self.is_shared_block(physical)? => physical,
+            _ => return Ok((false, inode)),
+        };
+
+        // 1. Parçaya yer bul: açık kuyruk bloğu bu işlemde tahsis edilmediyse yenisini aç
+        let open = self.open_tail_block.load(Ordering::Relaxed);
+        let existing = if open != 0 && self.allocator.is_fresh(open) {
+            let key = TailBlock::key(open);
+            self.metadata_tree.lookup(&key)?.and_then(|value| TailBlock::from_item(&key, &value))
+        } else {
+            None
+        };
+        let (mut record, offset) = match existing.and_then(|record| record.find_space(len).map(|offset| (record, offset))) {
+            Some(found) => found,
+            None => {
+                let tail_block = self.allocator.allocate_block()?;
+                let block_arc = self.cache.get_block(tail_block)?;
+                unsafe { (*block_arc.get()).data.fill(0) };
+                self.open_tail_block.store(tail_block, Ordering::Relaxed);
+                (TailBlock { physical: tail_block, used: 0 }, 0)
+            }
+        };
+        let fragment = TailFragment { file_block, tail_block: record.physical, offset, len: len as u32 };
+
+        // 2. Kuyruğu parçaya kopyala ve dilimleri dolu işaretle
+        let source_arc = self.cache.get_block(physical)?;
+        let tail_arc = self.cache.get_block(record.physical)?;
+        let tail = unsafe { &mut *tail_arc.get() };
+        tail.data[fragment.range()].copy_from_slice(unsafe { &(*source_arc.get()).data[..len] });
+        tail.is_dirty = true;
+        record.used |= fragment.slot_mask();
+        self.metadata_tree.insert_entry(&self.allocator, &TailBlock::key(record.physical), &record.encode())?;
+
+        // 3. Bloğu veri ağacında parçayla değiştir
+        let punched = self.punch_extents_locked(&data_tree, file_block, file_block + 1)?;
+        data_tree.insert_entry(&self.allocator, &TailFragment::key(file_block), &fragment.encode())?;
+        self.account_quota(&inode, -(punched.min(inode.block_count) as i64), 0);
+        inode.block_count = inode.block_count.saturating_sub(punched);
+        self.store_data_tree(&mut inode, &data_tree)?;
+        self.write_inode_locked(&mut inode)?;
+        Ok((true, inode))
+    }
+
+    /// Dosyanın paketlenmiş kuyruğu varsa onu yeniden kendi veri bloğuna alır
+    /// (çağıran `lock`'u tutmalıdır).
+    fn unpack_tail_locked(&self, inode_id: BlockId) -> Result<(), SadakFsError<D>> {
+        let mut inode = self.read_inode(inode_id)?;
+        if inode.data_tree_root == 0 || inode.file_size == 0 {
+            return Ok(());
+        }
+        let data_tree = self.data_tree(inode.data_tree_root)?;
+        let fragment = match tail_fragment_in(&data_tree, (inode.file_size - 1) / BLOCK_SIZE as u64)? {
+            Some(fragment) => fragment,
+            None => return Ok(()),
+        };
+
+        let mut tail = allocate_buffer()?;
+        let tail_arc = self.cache.get_block(fragment.tail_block)?;
+        tail[..fragment.len as usize].copy_from_slice(unsafe { &(*tail_arc.get()).data[fragment.range()] });
+
+        data_tree.remove_entry(&self.allocator, &TailFragment::key(fragment.file_block))?;
+        self.store_data_tree(&mut inode, &data_tree)?;
+        self.write_inode_locked(&mut inode)?;
+        self.release_tail_fragment_locked(&fragment)?;
+
+        // Parçanın ötesi zaten sıfır okunur; yalnızca parçanın kendisi yazılır
+        self.write_at_locked(inode_id, fragment.file_block * BLOCK_SIZE as u64, &tail[..fragment.len as usize])?;
+        Ok(())
+    }
+
+    /// Bir kuyruk parçasının dilimlerini boşaltır; kuyruk bloğunda başka parça kalmazsa
+    /// blok serbest bırakılır (çağıran `lock`'u tutmalıdır).
+    fn release_tail_fragment_locked(&self, fragment: &TailFragment) -> Result<(), SadakFsError<D>> {
+        let key = TailBlock::key(fragment.tail_block);
+        let mut record = match self.metadata_tree.lookup(&key)?.and_then(|value| TailBlock::from_item(&key, &value)) {
+            Some(record) => record,
+            None => return Ok(()), // Kayıt yoksa blok zaten bırakılmış; scrub tarafından raporlanır
+        };
+
+        record.used &= !fragment.slot_mask();
+        if record.used == 0 {
+            self.metadata_tree.remove_entry(&self.allocator, &key)?;
+            self.allocator.release_block(fragment.tail_block)?;
+            let _ = self.open_tail_block.compare_exchange(fragment.tail_block, 0, Ordering::Relaxed, Ordering::Relaxed);
+        } else {
+            self.metadata_tree.insert_entry(&self.allocator, &key, &record.encode())?;
+        }
+        Ok(())
+    }
+
+    // --- Alt Birimler (Subvolume) ---
+    // Alt birimler ayrı kök dizinlerdir; girdileri ve inode'ları ana ad alanıyla aynı
+    // metadata ağacında, kendi dizin kimlikleri altında tutulur. Bir alt birim, kök
+    // dizini `open_at` gibi işlemlere taban olarak verilerek monte edilir.
+
+    /// `name` adlı boş bir alt birim oluşturur.
+    pub fn create_subvolume(&self, name: &str) -> Result<Subvolume, SadakFsError<D>> {
+        self.check_writable()?;
+        if !Subvolume::is_valid_name(name) {
+            return Err(SadakFsError::InvalidName);
+        }
+
+        self.lock.acquire();
+        let result = self.create_subvolume_locked(name);
+        self.lock.release();
+        let subvolume = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(subvolume)
+    }
+
+    fn create_subvolume_locked(&self, name: &str) -> Result<Subvolume, SadakFsError<D>> {
+        // 1. İsim çakışmasını denetle ve sıradaki kimliği bul
+        let mut next_id = 1;
+        let mut exists = false;
+        self.scan_subvolumes(&mut |subvolume| {
+            exists |= subvolume.name == name;
+            next_id = next_id.max(subvolume.id + 1);
+            true
+        })?;
+        if exists {
+            return Err(SadakFsError::AlreadyExists);
+        }
+
+        // 2. Kök dizini oluştur; üst dizini kendisidir
+        let mut root = self.create_inode_locked(FILE_TYPE_DIRECTORY, 0, &Credentials::ROOT, next_id)?;
+        root.parent_dir = root.inode_id;
+        self.write_inode_locked(&mut root)?;
+
+        let subvolume = Subvolume {
+            id: next_id,
+            name: String::from(name),
+            root_inode: root.inode_id,
+            creation_time: root.creation_time,
+        };
+        self.metadata_tree.insert_entry(&self.allocator, &Subvolume::key(subvolume.id), &subvolume.encode())?;
+        Ok(subvolume)
+    }
+
+    /// `source` dizininin alt ağacının yazılabilir bir kopyasını `name` adlı yeni bir
+    /// alt birim olarak oluşturur (yazılabilir snapshot). Dosya verisi kopyalanmaz,
+    /// `clone_file` ile paylaşılır; dizinler, öznitelikler ve sabit bağlantılar yeniden
+    /// oluşturulur.
+    ///
+    /// Kopya dizin dizin yapılır ve arada commit edilebilir: kesilirse alt birim yarım
+    /// kalır (silinip yeniden oluşturulabilir). Kopya sırasında `source`'ta yapılan
+    /// değişikliklerin kopyaya yansıyıp yansımayacağı belirsizdir.
+    pub fn clone_subvolume(&self, source: &Inode, name: &str) -> Result<Subvolume, SadakFsError<D>> {
+        self.check_writable()?;
+        if source.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        let subvolume = self.create_subvolume(name)?;
+
+        // Kaynak inode -> kopya inode (sabit bağlantılar tek kopyayı paylaşır)
+        let mut clones: BTreeMap<BlockId, BlockId> = BTreeMap::new();
+        let mut pending = alloc::vec![(source.inode_id, subvolume.root_inode)];
+        while let Some((src_dir, dst_dir)) = pending.pop() {
+            let entries = self.read_dir(&self.read_inode(src_dir)?)?;
+            for entry in entries {
+                self.lock.acquire();
+                let result = self.clone_entry_locked(dst_dir, &entry, &mut clones);
+                self.lock.release();
+                if let Some(dirs) = result? {
+                    pending.push(dirs);
+                }
+                self.commit_if_over_budget()?;
+            }
+        }
+
+        self.lock.acquire();
+        let result = self.copy_xattrs_locked(source.inode_id, subvolume.root_inode);
+        self.lock.release();
+        result?;
+        Ok(subvolume)
+    }
+
+    /// Tek bir dizin girdisini `dst_dir` altına kopyalar.
+    ///
+    /// # Döndürür
+    /// Girdi bir dizinse, içeriği ayrıca kopyalanacak (kaynak, kopya) dizin çifti.
+    fn clone_entry_locked(
+        &self,
+        dst_dir: BlockId,
+        entry: &DirEntry,
+        clones: &mut BTreeMap<BlockId, BlockId>,
+    ) -> Result<Option<(BlockId, BlockId)>, SadakFsError<D>> {
+        let src = self.entry_inode(entry)?;
+        let owner = Credentials { uid: src.uid, gid: src.gid };
+
+        if src.file_type == FILE_TYPE_DIRECTORY {
+            let dir = self.create_entry_locked(dst_dir, &entry.name, FILE_TYPE_DIRECTORY, &owner)?;
+            self.update_inode_locked(dir.inode_id, |dir| dir.mode = src.mode)?;
+            self.copy_xattrs_locked(src.inode_id, dir.inode_id)?;
+            return Ok(Some((src.inode_id, dir.inode_id)));
+        }
+
+        // Dosya: ilk görüldüğünde klonlanır, sonraki bağlantılar aynı klona işaret eder
+        let clone_id = match clones.get(&src.inode_id) {
+            Some(&clone_id) => {
+                self.update_inode_locked(clone_id, |clone| clone.link_count += 1)?;
+                clone_id
+            }
+            None => {
+                let clone = self.clone_file_locked(src.inode_id, self.read_inode(dst_dir)?.subvolume_id)?;
+                self.copy_xattrs_locked(src.inode_id, clone.inode_id)?;
+                clones.insert(src.inode_id, clone.inode_id);
+                clone.inode_id
+            }
+        };
+
+        let (existing, free_key) = self.find_slot(dst_dir, &entry.name)?;
+        if existing.is_some() {
+            return Err(SadakFsError::AlreadyExists);
+        }
+        let link = DirEntry { name: entry.name.clone(), ino: self.entry_ino(&self.read_inode(clone_id)?), file_type: entry.file_type };
+        self.metadata_tree.insert_entry(&self.allocator, &free_key, &link.encode())?;
+        self.update_inode_locked(dst_dir, |dir| dir.file_size += 1)?;
+        self.index_large_dir_locked(dst_dir)?;
+        Ok(None)
+    }
+
+    /// `src` inode'unun tüm özniteliklerini `dst` inode'una yazar.
+    fn copy_xattrs_locked(&self, src: BlockId, dst: BlockId) -> Result<(), SadakFsError<D>> {
+        let mut entries = Vec::new();
+        self.scan_xattrs(src, &mut |_, entry| {
+            entries.push(entry);
+            true
+        })?;
+        for entry in entries {
+            let value = match entry.value {
+                XattrValue::Inline(value) => value,
+                XattrValue::Overflow { block, len } => {
+                    let block_arc = self.cache.get_block(block)?;
+                    let data = unsafe { &(*block_arc.get()).data };
+                    data[..(len as usize).min(BLOCK_SIZE)].to_vec()
+                }
+            };
+            self.set_xattr_locked(dst, &entry.name, &value)?;
+        }
+        Ok(())
+    }
+
+    /// Birimdeki alt birimler, kimlik sırasıyla (ana ad alanı dahil değildir).
+    pub fn subvolumes(&self) -> Result<Vec<Subvolume>, SadakFsError<D>> {
+        let mut subvolumes = Vec::new();
+        self.scan_subvolumes(&mut |subvolume| {
+            subvolumes.push(subvolume);
+            true
+        })?;
+        Ok(subvolumes)
+    }
+
+    /// `name` adlı alt birimin kök dizini; yol çözümü için `open_at`'e taban olarak verilir.
+    pub fn open_subvolume(&self, name: &str) -> Result<Inode, SadakFsError<D>> {
+        let subvolume = self.find_subvolume(name)?.ok_or(SadakFsError::NotFound)?;
+        self.read_inode(subvolume.root_inode)
+    }
+
+    /// `name` adlı alt birimi siler; kök dizini boş olmalıdır.
+    pub fn delete_subvolume(&self, name: &str) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.delete_subvolume_locked(name);
+        self.lock.release();
+        result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    fn delete_subvolume_locked(&self, name: &str) -> Result<(), SadakFsError<D>> {
+        let subvolume = self.find_subvolume(name)?.ok_or(SadakFsError::NotFound)?;
+        let mut root = self.read_inode(subvolume.root_inode)?;
+        if root.file_size != 0 {
+            return Err(SadakFsError::DirectoryNotEmpty);
+        }
+
+        self.metadata_tree.remove_entry(&self.allocator, &Subvolume::key(subvolume.id))?;
+        self.metadata_tree.remove_entry(&self.allocator, &Subvolume::attrs_key(subvolume.id))?;
+        self.free_inode_locked(&mut root)
+    }
+
+    /// `name` alt biriminde CoW'u kapatır veya açar (nocow). Kapalıyken dosyaların
+    /// mevcut veri blokları yerinde güncellenir; yeni bloklar, delikler ve klonlarla
+    /// paylaşılan bloklar yine CoW ile yazılır. Kendi günlüğünü tutan sanal makine
+    /// imajları ve veritabanları için yazma çoğaltmasını azaltır.
+    ///
+    /// Yerinde yazılan veri bir çökmede yarım kalabilir. Canlı snapshot'ların gördüğü
+    /// blokların eski içeriği üzerine yazılmadan önce snapshot'a (bellekte) kopyalanır. Veri blokları bu sürümde checksum'lanmadığından nocow
+    /// bütünlük denetiminden bir şey kaybettirmez; metadata her zaman CoW'dur.
+    pub fn set_subvolume_nocow(&self, name: &str, nocow: bool) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.set_subvolume_nocow_locked(name, nocow);
+        self.lock.release();
+        result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    fn set_subvolume_nocow_locked(&self, name: &str, nocow: bool) -> Result<(), SadakFsError<D>> {
+        self.update_subvolume_attrs_locked(name, |attrs| {
+            if nocow { attrs | SUBVOLUME_ATTR_NOCOW } else { attrs & !SUBVOLUME_ATTR_NOCOW }
+        })
+    }
+
+    /// `name` alt birimindeki dosyaların geri yazılan verisini `compression` algoritmasıyla
+    /// sıkıştırır veya (`None`) sıkıştırmayı kapatır. Kendi ayarı olan dosyalar
+    /// (`set_compression`) etkilenmez; mevcut veri yeniden yazılmaz. Arşiv birimleri için
+    /// yüksek düzeyli zstd, CPU karşılığında alan kazandırır.
+    pub fn set_subvolume_compression(&self, name: &str, compression: Option<Compression>) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.update_subvolume_attrs_locked(name, |attrs| {
+            let attrs = attrs & !(SUBVOLUME_ATTR_COMPRESS | (0xFF << SUBVOLUME_ATTR_COMPRESSION_SHIFT));
+            match compression {
+                Some(compression) => attrs | SUBVOLUME_ATTR_COMPRESS | ((compression.to_raw() as u64) << SUBVOLUME_ATTR_COMPRESSION_SHIFT),
+                None => attrs,
+            }
+        });
+        if result.is_ok() {
+            self.enable_compression_features(compression);
+        }
+        self.lock.release();
+        result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    /// `name` alt biriminin sıkıştırma algoritması (bkz. `set_subvolume_compression`).
+    pub fn subvolume_compression(&self, name: &str) -> Result<Option<Compression>, SadakFsError<D>> {
+        let subvolume = self.find_subvolume(name)?.ok_or(SadakFsError::NotFound)?;
+        self.subvolume_compression_of(subvolume.id)
+    }
+
+    fn subvolume_compression_of(&self, subvolume_id: u64) -> Result<Option<Compression>, SadakFsError<D>> {
+        let attrs = self.subvolume_attrs(subvolume_id)?;
+        Ok((attrs & SUBVOLUME_ATTR_COMPRESS != 0)
+            .then(|| Compression::from_raw((attrs >> SUBVOLUME_ATTR_COMPRESSION_SHIFT) as u8)))
+    }
+
+    /// `name` alt biriminin öznitelik bayraklarını `update` ile değiştirir; hiçbir bayrak
+    /// kalmazsa kayıt silinir.
+    fn update_subvolume_attrs_locked<F: FnOnce(u64) -> u64>(&self, name: &str, update: F) -> Result<(), SadakFsError<D>> {
+        let subvolume = self.find_subvolume(name)?.ok_or(SadakFsError::NotFound)?;
+        let attrs = update(self.subvolume_attrs(subvolume.id)?);
+        if attrs == 0 {
+            self.metadata_tree.remove_entry(&self.allocator, &Subvolume::attrs_key(subvolume.id))?;
+        } else {
+            self.metadata_tree.insert_entry(&self.allocator, &Subvolume::attrs_key(subvolume.id), &Subvolume::encode_attrs(attrs))?;
+        }
+        Ok(())
+    }
+
+    /// `name` alt biriminde CoW kapalı mı? (bkz. `set_subvolume_nocow`)
+    pub fn subvolume_nocow(&self, name: &str) -> Result<bool, SadakFsError<D>> {
+        let subvolume = self.find_subvolume(name)?.ok_or(SadakFsError::NotFound)?;
+        self.is_nocow(subvolume.id)
+    }
+
+    /// Alt birimin öznitelik bayrakları (kaydı yoksa veya bozuksa 0).
+    fn subvolume_attrs(&self, subvolume_id: u64) -> Result<u64, SadakFsError<D>> {
+        let value = self.metadata_tree.lookup(&Subvolume::attrs_key(subvolume_id))?;
+        Ok(value.and_then(|value| Subvolume::decode_attrs(&value)).unwrap_or(0))
+    }
+
+    /// Alt birimin dosyaları yerinde mi güncellenir?
+    fn is_nocow(&self, subvolume_id: u64) -> Result<bool, SadakFsError<D>> {
+        Ok(self.subvolume_attrs(subvolume_id)? & SUBVOLUME_ATTR_NOCOW != 0)
+    }
+
+    fn find_subvolume(&self, name: &str) -> Result<Option<Subvolume>, SadakFsError<D>> {
+        let mut found = None;
+        self.scan_subvolumes(&mut |subvolume| {
+            if subvolume.name == name {
+                found = Some(subvolume);
+                return false;
+            }
+            true
+        })?;
+        Ok(found)
+    }
+
+    /// Alt birim tablosunu kimlik sırasıyla ziyaret eder.
+    fn scan_subvolumes<F: FnMut(Subvolume) -> bool>(&self, visit: &mut F) -> Result<(), SadakFsError<D>> {
+        self.metadata_tree.walk_from(&Subvolume::key(0), &mut |key, value| {
+            if !Subvolume::is_subvolume_key(key) {
+                return false;
+            }
+            match Subvolume::from_item(key, value) {
+                Some(subvolume) => visit(subvolume),
+                None => true, // Bozuk kayıt atlanır; scrub tarafından raporlanır
+            }
+        })?;
+        Ok(())
+    }
+
+    // --- Sıcak Önbellek ---
+
+    /// Önbellekteki en son kullanılan blokların listesini (en fazla `WARM_SET_MAX_BLOCKS`)
+    /// birime yazar ve commit eder; sonraki montajda bu bloklar önceden okunur. `unmount`
+    /// listeyi kendisi kaydeder; bu çağrı birimi ayırmadan bir kontrol noktası içindir.
+    /// Önceki liste bırakılır.
+    ///
+    /// # Döndürür
+    /// Listeye yazılan blok sayısı.
+    pub fn save_warm_set(&self) -> Result<usize, SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.write_warm_set_locked().and_then(|saved| {
+            self.commit_locked()?;
+            Ok(saved)
+        });
+        self.lock.release();
+        result
+    }
+
+    /// Sıcak blok listesini yazar ve Superblock'a bağlar; kalıcı olması için commit
+    /// gerekir (çağıran `lock`'u tutmalıdır).
+    fn write_warm_set_locked(&self) -> Result<usize, SadakFsError<D>> {
+        // 1. Listeyi tahsisten önce topla; Superblock her montajda zaten okunur, serbest
+        // bloklar (ör. bırakılmış CoW kopyaları) yeniden okunmaya değmez
+        let mut ids = Vec::with_capacity(WARM_SET_MAX_BLOCKS);
+        for id in self.cache.hot_blocks(WARM_SET_MAX_BLOCKS) {
+            if id != 0 && self.allocator.is_allocated(id)? {
+                ids.push(id);
+            }
+        }
+        // Önceden okuma için aygıt sırası
+        ids.sort_unstable();
+
+        // 2. Önceki listeyi bırak ve yenisini yaz
+        let old = self.sb().warm_set_block;
+        if old != 0 {
+            self.allocator.release_block(old)?;
+        }
+        let block_id = self.allocator.allocate_block()?;
+        let block_arc = self.cache.get_block(block_id)?;
+        let block = unsafe { &mut *block_arc.get() };
+        block_cache::encode_warm_set(&ids, block.data.as_mut());
+        block.is_dirty = true;
+
+        self.sb_mut().warm_set_block = block_id;
+        Ok(ids.len())
+    }
+
+    // --- İçe Aktarma (Göç) ---
+
+    /// `source` dosya sisteminin (ör. FAT veya ext2 sürücüsü) ağacını `dest` dizininin
+    /// altında yeniden oluşturur; dizinler, dosyalar, bağlantılar ve bunların izinleri ve
+    /// sahipleri aktarılır. `on_progress` her girdiden sonra çağrılır.
+    ///
+    /// Aktarım kaldığı yerden sürdürülebilir: hedefte zaten bulunan dizinlere girilir,
+    /// boyutu kaynağınkine ulaşmış dosyalar ve mevcut bağlantılar atlanır, daha kısa kalmış
+    /// dosyalar hedefteki boyutlarından itibaren tamamlanır. Ara commit'ler bütçeyle
+    /// yapıldığından bir çökme ya da kesintiden sonra aynı çağrı yinelenebilir; kaynak bu
+    /// arada değişmemelidir. Hedefte aynı adla farklı türde bir girdi varsa `AlreadyExists`.
+    pub fn import_tree<S: ImportSource>(
+        &self,
+        source: &mut S,
+        dest: &Inode,
+        mut on_progress: impl FnMut(&ImportProgress),
+    ) -> Result<ImportProgress, ImportError<S::Error, SadakFsError<D>>> {
+        self.check_writable().map_err(ImportError::Target)?;
+        if dest.file_type != FILE_TYPE_DIRECTORY {
+            return Err(ImportError::Target(SadakFsError::NotADirectory));
+        }
+        let mut buf = try_zeroed(IMPORT_CHUNK_BLOCKS * BLOCK_SIZE).map_err(|e| ImportError::Target(e.into()))?;
+        let mut progress = ImportProgress::default();
+        let mut pending = alloc::vec![(String::new(), dest.inode_id)];
+
+        while let Some((dir_path, dir_id)) = pending.pop() {
+            let dir = self.read_inode(dir_id).map_err(ImportError::Target)?;
+            for entry in source.read_dir(&dir_path).map_err(ImportError::Source)? {
+                if entry.name == "." || entry.name == ".." {
+                    continue;
+                }
+                let path = import::join(&dir_path, &entry.name);
+                let existing = match self.lookup(&dir, &entry.name) {
+                    Ok(found) => Some(found),
+                    Err(SadakFsError::NotFound) => None,
+                    Err(e) => return Err(ImportError::Target(e)),
+                };
+                let expected = match entry.kind {
+                    ImportKind::Directory => FILE_TYPE_DIRECTORY,
+                    ImportKind::File => FILE_TYPE_REGULAR,
+                    ImportKind::Symlink => FILE_TYPE_SYMLINK,
+                };
+                if existing.as_ref().map_or(false, |found| found.file_type != expected) {
+                    return Err(ImportError::Target(SadakFsError::AlreadyExists));
+                }
+                let resumed = existing.is_some();
+
+                let mut inode = match (entry.kind, existing) {
+                    (ImportKind::Symlink, Some(link)) => {
+                        progress.skipped += 1;
+                        link
+                    }
+                    (ImportKind::Symlink, None) => {
+                        let target = source.read_link(&path).map_err(ImportError::Source)?;
+                        progress.symlinks += 1;
+                        self.create_symlink(&dir, &entry.name, &target).map_err(ImportError::Target)?
+                    }
+                    (ImportKind::Directory, existing) => {
+                        let created = match existing {
+                            Some(found) => found,
+                            None => self.create_dir(&dir, &entry.name).map_err(ImportError::Target)?,
+                        };
+                        progress.directories += 1;
+                        pending.push((path.clone(), created.inode_id));
+                        created
+                    }
+                    (ImportKind::File, existing) => {
+                        let mut file = match existing {
+                            Some(found) => found,
+                            None => self.create_file_in(&dir, &entry.name).map_err(ImportError::Target)?,
+                        };
+                        if resumed && file.file_size >= entry.size {
+                            progress.skipped += 1;
+                        } else {
+                            let mut offset = file.file_size;
+                            while offset < entry.size {
+                                let want = core::cmp::min(buf.len() as u64, entry.size - offset) as usize;
+                                let len = source.read(&path, offset, &mut buf[..want]).map_err(ImportError::Source)?;
+                                if len == 0 {
+                                    break;
+                                }
+                                self.write_at(&mut file, offset, &buf[..len]).map_err(ImportError::Target)?;
+                                offset += len as u64;
+                                progress.bytes += len as u64;
+                            }
+                            progress.files += 1;
+                        }
+                        file
+                    }
+                };
+
+                if entry.kind != ImportKind::Symlink && inode.mode != entry.mode & MODE_MASK {
+                    self.chmod(&mut inode, entry.mode & MODE_MASK).map_err(ImportError::Target)?;
+                }
+                if inode.uid != entry.uid || inode.gid != entry.gid {
+                    self.chown(&mut inode, entry.uid, entry.gid).map_err(ImportError::Target)?;
+                }
+                on_progress(&progress);
+            }
+        }
+
+        self.sync().map_err(ImportError::Target)?;
+        Ok(progress)
+    }
+
+    // --- Büyütme (Online Resize) ---
+
+    /// Alttaki Sahne64 kaynağı büyütüldükten sonra birimi monte edilmişken `new_total_blocks`
+    /// bloğa büyütür: tahsis haritası genişletilir (gerekirse yeni alana taşınır),
+    /// `Superblock::total_blocks` güncellenir ve değişiklik commit edilir. Yeni alan
+    /// dönüşte tahsis edilebilir. Aygıt o kadar blok bildirmiyorsa `DeviceTooSmall`,
+    /// birim zaten o boyuttaysa veya daha büyükse `EINVAL` döner. Küçültme desteklenmez.
+    pub fn grow(&self, new_total_blocks: u64) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.check_no_transaction()?;
+        let actual = self.cache.device.total_blocks();
+        if actual < new_total_blocks {
+            return Err(SadakFsError::DeviceTooSmall { expected: new_total_blocks, actual });
+        }
+
+        self.lock.acquire();
+        let result = self.grow_locked(new_total_blocks);
+        self.lock.release();
+        result
+    }
+
+    /// `grow` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn grow_locked(&self, new_total_blocks: u64) -> Result<(), SadakFsError<D>> {
+        if self.sb().seal_tree_start != 0 {
+            return Err(SadakFsError::Sealed);
+        }
+        self.allocator.grow(new_total_blocks)?;
+        let sb = self.sb_mut();
+        sb.total_blocks = new_total_blocks;
+        sb.bitmap_start_id = self.allocator.bitmap_start();
+        self.commit_locked()
+    }
+
+    // --- Küçültme (Offline Resize) ---
+
+    /// Birimi `new_total_blocks` bloğa küçültür ve ayırır; ardından alttaki bölüm
+    /// (partition) yeni boyuta kesilebilir.
+    ///
+    /// Birimin sonundaki `[new_total_blocks, total_blocks)` aralığında duran canlı bloklar
+    /// (veri, kuyruk ve xattr taşma blokları, veri ve metadata ağacı düğümleri, inode
+    /// blokları) sınırın altındaki boş alana taşınır ve tüm işaretçileri güncellenir.
+    /// Inode ID'leri blok ID'leri olduğundan taşınan inode'ların dışa aktarım tanıtıcıları
+    /// bayatlar. Değişen blok kayıtları fiziksel blokları gösterdiğinden silinir; sonraki
+    /// yedek tam yedek olmalıdır. Son olarak tahsis haritası kesilir ve Superblock yeni
+    /// boyutla yazılır.
+    ///
+    /// Çevrimdışı bir işlemdir: birimin tek kullanıcısı olunmalıdır ve tüm inode'lar dizin
+    /// ağacından veya alt birim köklerinden ulaşılabilir olmalıdır. Aralıkta yine de canlı
+    /// blok kalırsa `ShrinkBlocked` döner; hatada birim son commit'teki haliyle kalır.
+    pub fn shrink(self, new_total_blocks: u64) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.shrink_locked(new_total_blocks);
+        self.allocator.set_allocation_limit(BlockId::MAX);
+        self.lock.release();
+        result?;
+
+        self.unmount()
+    }
+
+    /// `shrink` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn shrink_locked(&self, new_total: u64) -> Result<(), SadakFsError<D>> {
+        if self.sb().seal_tree_start != 0 {
+            return Err(SadakFsError::Sealed);
+        }
+        let old_total = self.sb().total_blocks;
+        if new_total == 0 || new_total >= old_total {
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+
+        // 1. Bekleyenleri yaz ve yetimleri geri kazan (ulaşılamayan inode'lar taşınamaz);
+        // bundan sonra hiçbir yeni blok sınırın ötesine tahsis edilmez
+        self.reclaim_orphans_locked()?;
+        self.commit_locked()?;
+        self.allocator.set_allocation_limit(new_total);
+        self.open_tail_block.store(0, Ordering::Relaxed);
+
+        // 2. Inode'lara başvuran metadata öğelerini ve ulaşılabilir inode'ları topla
+        let mut dir_entries = Vec::new();
+        let mut migrations = Vec::new();
+        let mut xattrs = Vec::new();
+        let mut subvolumes = Vec::new();
+        self.metadata_tree.walk(&mut |key, value| match key.item_type {
+            ITEM_TYPE_DIR_ENTRY => {
+                if let Some(entry) = DirEntry::decode(value) {
+                    dir_entries.push((*key, entry));
+                }
+            }
+            ITEM_TYPE_DIR_MIGRATION => migrations.extend(DirMigration::from_item(key, value)),
+            ITEM_TYPE_XATTR => {
+                if let Some(entry) = XattrEntry::decode(value) {
+                    xattrs.push((*key, entry));
+                }
+            }
+            _ => {
+                if let Some(subvolume) = Subvolume::from_item(key, value) {
+                    subvolumes.push(subvolume);
+                }
+            }
+        })?;
+        let mut inode_ids = BTreeSet::new();
+        inode_ids.insert(self.sb().root_dir_inode);
+        for (_, entry) in &dir_entries {
+            inode_ids.insert(self.entry_inode(entry)?.inode_id);
+        }
+        inode_ids.extend(subvolumes.iter().map(|subvolume| subvolume.root_inode));
+
+        // 3. Dosya verisini taşı; paylaşılan bloklar tüm dosyalar için bir kez taşınır
+        let mut moved = BTreeMap::new();
+        let mut inodes = Vec::with_capacity(inode_ids.len());
+        for inode_id in inode_ids {
+            let mut inode = self.read_inode(inode_id)?;
+            if inode.data_tree_root != 0 {
+                self.relocate_file_data_locked(&mut inode, new_total, &mut moved)?;
+            }
+            inodes.push(inode);
+        }
+
+        // 4. Sınırın ötesindeki inode'lara yeni bloklar (ve dolayısıyla yeni ID'ler) ver
+        let mut renamed = BTreeMap::new();
+        for inode in &inodes {
+            if inode.inode_id >= new_total {
+                renamed.insert(inode.inode_id, self.allocator.allocate_block()?);
+            }
+        }
+        let map = |id: BlockId| renamed.get(&id).copied().unwrap_or(id);
+        for mut inode in inodes {
+            let old_id = inode.inode_id;
+            inode.inode_id = map(old_id);
+            inode.parent_dir = map(inode.parent_dir);
+            if inode.inode_id != old_id {
+                let block_arc = self.cache.get_block(inode.inode_id)?;
+                unsafe { (*block_arc.get()).data.fill(0) };
+                self.allocator.release_block(old_id)?;
+                // Inode numarası değişmez; yalnızca tablo yeni bloğu gösterir
+                if inode.ino != 0 {
+                    let location = InodeLocation { ino: inode.ino, block: inode.inode_id };
+                    self.metadata_tree.insert_entry(&self.allocator, &InodeLocation::key(location.ino), &location.encode())?;
+                }
+            }
+            self.write_inode_locked(&mut inode)?;
+        }
+
+        // 5. Inode ID'lerine başvuran öğeleri yeniden anahtarla ve güncelle; inode
+        // numarasıyla anahtarlanan dizin girdileri değişmez
+        if self.sb().feature_incompat & FEATURE_INCOMPAT_DIR_INO == 0 {
+            for (key, mut entry) in dir_entries {
+                let new_key = DirEntry::key(map(key.object_id), key.offset);
+                let ino = map(entry.ino);
+                if new_key != key || ino != entry.ino {
+                    entry.ino = ino;
+                    self.metadata_tree.remove_entry(&self.allocator, &key)?;
+                    self.metadata_tree.insert_entry(&self.allocator, &new_key, &entry.encode())?;
+                }
+            }
+            for (dir_ino, migration) in migrations {
+                if renamed.contains_key(&dir_ino) {
+                    self.metadata_tree.remove_entry(&self.allocator, &DirMigration::key(dir_ino))?;
+                    self.metadata_tree.insert_entry(&self.allocator, &DirMigration::key(map(dir_ino)), &migration.encode())?;
+                }
+            }
+        }
+        for (key, mut entry) in xattrs {
+            let new_key = XattrEntry::key(map(key.object_id), key.offset);
+            let mut changed = new_key != key;
+            if let XattrValue::Overflow { block, len } = entry.value {
+                if block >= new_total {
+                    entry.value = XattrValue::Overflow { block: self.move_block_locked(block, &mut moved)?, len };
+                    changed = true;
+                }
+            }
+            if changed {
+                self.metadata_tree.remove_entry(&self.allocator, &key)?;
+                self.metadata_tree.insert_entry(&self.allocator, &new_key, &entry.encode())?;
+            }
+        }
+        for mut subvolume in subvolumes {
+            if renamed.contains_key(&subvolume.root_inode) {
+                subvolume.root_inode = map(subvolume.root_inode);
+                self.metadata_tree.insert_entry(&self.allocator, &Subvolume::key(subvolume.id), &subvolume.encode())?;
+            }
+        }
+        let sb = self.sb_mut();
+        sb.root_dir_inode = map(sb.root_dir_inode);
+
+        // 6. Fiziksel blokları gösteren kayıtlar geçersizleşir; metadata ağacını da taşı
+        let warm_set = self.sb().warm_set_block;
+        if warm_set >= new_total {
+            self.allocator.release_block(warm_set)?;
+            self.sb_mut().warm_set_block = 0;
+        }
+        let journal = self.sb().journal_start;
+        let journal_end = journal + self.sb().journal_blocks();
+        if journal != 0 && journal_end > new_total {
+            // Günlük bir sonraki commit'te sınırın içinde yeniden ayrılır
+            for id in journal..journal_end {
+                self.allocator.release_block(id)?;
+            }
+            self.sb_mut().journal_start = 0;
+        }
+        if self.sb().forensic_log_block >= new_total {
+            // Günlük bir sonraki commit'te sınırın içine yeniden yazılır
+            self.forensics_lock.acquire();
+            unsafe { &mut *self.forensics.get() }.mark_dirty();
+            self.forensics_lock.release();
+        }
+        #[cfg(feature = "snapshot")]
+        self.prune_changes_locked(u64::MAX)?;
+        self.metadata_tree.relocate(&self.allocator, new_total)?;
+        self.commit_locked()?;
+
+        // 7. Aralıkta tahsis haritasının kendisi dışında dolu blok kalmamalı
+        let bitmap = self.allocator.bitmap_start()..self.allocator.bitmap_start() + self.allocator.bitmap_block_count();
+        for id in new_total..old_total {
+            if !bitmap.contains(&id) && self.allocator.is_allocated(id)? {
+                return Err(SadakFsError::ShrinkBlocked(id));
+            }
+        }
+
+        // 8. Haritayı kes ve yeni boyutu yaz
+        self.allocator.shrink(new_total)?;
+        let bitmap_start = self.allocator.bitmap_start();
+        let sb = self.sb_mut();
+        sb.total_blocks = new_total;
+        sb.bitmap_start_id = bitmap_start;
+        sb.fast_tier_blocks = sb.fast_tier_blocks.min(new_total);
+        if sb.scrub_cursor >= new_total {
+            sb.scrub_cursor = 0;
+        }
+        self.commit_locked()
+    }
+
+    /// Bir dosyanın `limit` ve ötesindeki veri bloklarını, kuyruk bloklarını ve veri ağacı
+    /// düğümlerini taşır; `inode`'un veri ağacı kökü güncellenir ama inode yazılmaz
+    /// (çağıran `lock`'u tutmalıdır).
+    fn relocate_file_data_locked(
+        &self,
+        inode: &mut Inode,
+        limit: BlockId,
+        moved: &mut BTreeMap<BlockId, BlockId>,
+    ) -> Result<(), SadakFsError<D>> {
+        let data_tree = self.data_tree(inode.data_tree_root)?;
+        let mut extents = Vec::new();
+        let mut fragments = Vec::new();
+        data_tree.walk(&mut |key, value| {
+            if let Some(extent) = Extent::from_item(key, value) {
+                extents.push(extent);
+            } else if let Some(fragment) = TailFragment::from_item(key, value) {
+                fragments.push(fragment);
+            }
+        })?;
+
+        // Sınırı aşan extent yeniden eşlenir; taşınan bloklar ardışıksa tek extent kalır
+        for extent in extents {
+            if extent.physical_blocks().end <= limit {
+                continue;
+            }
+            data_tree.remove_entry(&self.allocator, &Extent::key(extent.file_block))?;
+            if extent.is_compressed() {
+                // Sıkıştırılmış küme ardışık kalmalı; taşınan bloklar ardışık değilse küçültme durur
+                let mut physical_start = 0;
+                for (i, old) in extent.physical_blocks().enumerate() {
+                    let physical = if old < limit { old } else { self.move_block_locked(old, moved)? };
+                    if i == 0 {
+                        physical_start = physical;
+                    } else if physical != physical_start + i as u64 {
+                        return Err(SadakFsError::ShrinkBlocked(old));
+                    }
+                }
+                let relocated = Extent { physical_start, ..extent };
+                data_tree.insert_entry(&self.allocator, &Extent::key(relocated.file_block), &relocated.encode())?;
+                continue;
+            }
+            let mut run: Option<Extent> = None;
+            for file_block in extent.file_block..extent.end() {
+                let old = extent.physical_for(file_block);
+                let physical = if old < limit { old } else { self.move_block_locked(old, moved)? };
+                match run.as_mut() {
+                    Some(current) if current.physical_start + current.length as u64 == physical => current.length += 1,
+                    _ => {
+                        if let Some(done) = run.replace(Extent { file_block, physical_start: physical, length: 1, ..extent }) {
+                            data_tree.insert_entry(&self.allocator, &Extent::key(done.file_block), &done.encode())?;
+                        }
+                    }
+                }
+            }
+            if let Some(done) = run {
+                data_tree.insert_entry(&self.allocator, &Extent::key(done.file_block), &done.encode())?;
+            }
+        }
+
+        for fragment in fragments {
+            if fragment.tail_block >= limit {
+                let fragment = TailFragment { tail_block: self.move_block_locked(fragment.tail_block, moved)?, ..fragment };
+                data_tree.insert_entry(&self.allocator, &TailFragment::key(fragment.file_block), &fragment.encode())?;
+            }
+        }
+
+        data_tree.relocate(&self.allocator, limit)?;
+        self.store_data_tree(inode, &data_tree)?;
+        Ok(())
+    }
+
+    /// Bir bloğun içeriğini yeni tahsis edilen bir bloğa kopyalar ve eskisini bırakır; blok
+    /// ID'siyle anahtarlanmış kayıtları (paylaşılan blok, kuyruk bloğu) da taşır. Aynı blok
+    /// ikinci kez istenirse `moved`'daki yeni ID döner (çağıran `lock`'u tutmalıdır).
+    fn move_block_locked(&self, old: BlockId, moved: &mut BTreeMap<BlockId, BlockId>) -> Result<BlockId, SadakFsError<D>> {
+        if let Some(&new) = moved.get(&old) {
+            return Ok(new);
+        }
+
+        let new = self.allocator.allocate_block()?;
+        let source_arc = self.cache.get_block(old)?;
+        let target_arc = self.cache.get_block(new)?;
+        let target = unsafe { &mut *target_arc.get() };
+        target.data.copy_from_slice(unsafe { &(*source_arc.get()).data[..] });
+        target.is_dirty = true;
+
+        if let Some(shared) = self.shared_block(old)? {
+            self.metadata_tree.remove_entry(&self.allocator, &SharedBlock::key(old))?;
+            let shared = SharedBlock { physical: new, ..shared };
+            self.metadata_tree.insert_entry(&self.allocator, &SharedBlock::key(new), &shared.encode())?;
+        }
+        if let Some(value) = self.metadata_tree.remove_entry(&self.allocator, &TailBlock::key(old))? {
+            self.metadata_tree.insert_entry(&self.allocator, &TailBlock::key(new), &value)?;
+        }
+
+        self.allocator.release_block(old)?;
+        moved.insert(old, new);
+        Ok(new)
+    }
+
+    // --- Ayırma (Unmount) ---
+
+    /// Birimi temiz olarak ayırır (bkz. `quiesce`).
+    ///
+    /// `unmount` edilmeden bırakılan bir `SadakFs` hiçbir şey yazmaz: birim son commit'in
+    /// tutarlı durumunda kalır ve sonraki montajda `was_cleanly_unmounted` `false` döner.
+    pub fn unmount(self) -> Result<(), SadakFsError<D>> {
+        self.quiesce()
+    }
+
+    /// Birimi düzenli olarak kapatır ve tuttuğu tüm çekirdek kaynaklarını bırakır.
+    ///
+    /// 1. `self` tüketildiğinden yeni işlem başlatılamaz. SADAK kendi görevini başlatmaz;
+    ///    scrub ve zamanlı commit çağıranın adımlarıyla yürüdüğünden bunlar da durur.
+    /// 2. Süren işlemler dosya sistemi kilidi alınarak beklenir.
+    /// 3. Açık kalan inode'lar bırakılmış sayılır ve yetimler geri kazanılır; bekleyen
+    ///    sayfalar, kirli bloklar, sıcak blok listesi ve adli kayıt günlüğü yazılır ve
+    ///    Superblock "temiz ayrıldı" durumuyla en son yazılır.
+    /// 4. Çekirdek kilitleri yok edilir ve önbellek belleği Sahne64'e geri verilir; aygıt
+    ///    son başvurusu bırakıldığında kendi kaynaklarını bırakır.
+    ///
+    /// Salt okunur montajda hiçbir şey yazılmaz. Yazma başarısız olsa da kaynaklar
+    /// bırakılır ve hata döner; birim son başarılı commit'in durumunda kalır.
+    pub fn quiesce(self) -> Result<(), SadakFsError<D>> {
+        self.lock.acquire();
+        let result = if self.read_only.load(Ordering::Acquire) {
+            Ok(())
+        } else {
+            unsafe { &mut *self.open_inodes.get() }.clear();
+            self.reclaim_orphans_locked()
+                .and_then(|_| self.write_warm_set_locked())
+                .and_then(|_| {
+                    self.sb_mut().state = VOLUME_STATE_CLEAN;
+                    self.commit_locked()
+                })
+        };
+        self.lock.release();
+
+        // Ağaçlar ve kilitler bırakıldıktan sonra önbellek bloklarını tutan başka başvuru kalmaz
+        let cache = self.cache.clone();
+        drop(self);
+        cache.release_memory();
+        result
+    }
+
+    /// Önceki montaj `unmount` ile temiz kapatılmış mıydı? `false` ise sistem çökmüş veya
+    /// birim ayrılmadan bırakılmıştır; son commit'ten sonraki yazmalar kaybolmuştur.
+    pub fn was_cleanly_unmounted(&self) -> bool {
+        self.previous_unmount_clean
+    }
+
+    /// Birim salt okunur mu (bkz. `mount_readonly`, `set_readonly`)?
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::Acquire)
+    }
+
+    /// Monte edilmiş birimi ayırmadan salt okunur moda alır veya yazılabilir moda döndürür.
+    ///
+    /// Salt okunura geçerken bekleyen sayfalar ve kirli bloklar önce commit edilir; commit
+    /// başarısız olsa da (ör. çekirdek bir aygıt hatası üzerine geçiş yapıyorsa) birim salt
+    /// okunur olur ve hata döner; yazılamayan değişiklikler diske gitmez. Yazılabilir moda
+    /// dönerken birim yeniden etkin işaretlenir. Mühürlü birim yazılabilir yapılamaz (`Sealed`).
+    pub fn set_readonly(&self, read_only: bool) -> Result<(), SadakFsError<D>> {
+        self.check_no_transaction()?;
+        self.lock.acquire();
+        let result = if read_only == self.is_read_only() {
+            Ok(())
+        } else if read_only {
+            let committed = self.commit_locked();
+            self.read_only.store(true, Ordering::Release);
+            committed
+        } else if self.sb().seal_tree_start != 0 {
+            Err(SadakFsError::Sealed)
+        } else if self.sb().features().unknown().ro_compat != 0 {
+            Err(SadakFsError::UnsupportedFeatures(self.sb().features().unknown().ro_compat))
+        } else {
+            self.read_only.store(false, Ordering::Release);
+            self.sb_mut().state = VOLUME_STATE_ACTIVE;
+            self.write_superblock().and_then(|_| Ok(self.cache.device.flush()?))
+        };
+        self.lock.release();
+        result
+    }
+
+    // --- Yazma Çiti (Fencing) ---
+
+    /// Bu düğümün tuttuğu çit dönemi.
+    pub fn fence_epoch(&self) -> u64 {
+        self.sb().fence_epoch
+    }
+
+    /// Çit dönemini artırıp hemen commit eder; paylaşılan depolamayı devralan düğümde dış
+    /// küme yöneticisi tarafından çağrılır. Eski dönemi tutan ve `MountOptions::fencing`
+    /// ile monte edilmiş düğümler bir sonraki commit'lerinde `Fenced` alır ve salt okunura
+    /// düşer. Denetim ile Superblock yazması arasında süren bir commit'i durdurmaz; yönetici
+    /// eski düğümün süren commit'inin bitmesini (veya aygıta erişiminin kesilmesini)
+    /// beklemelidir.
+    ///
+    /// # Döndürür
+    /// Yeni çit dönemi.
+    pub fn bump_fence_epoch(&self) -> Result<u64, SadakFsError<D>> {
+        self.check_no_transaction()?;
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.bump_fence_epoch_locked();
+        self.lock.release();
+        result
+    }
+
+    fn bump_fence_epoch_locked(&self) -> Result<u64, SadakFsError<D>> {
+        // Diskte başka bir düğümün yazdığı daha yeni dönem varsa onun da ötesine geçilir
+        let disk_epoch = match self.read_disk_superblock()? {
+            Some(disk) if disk.uuid == self.sb().uuid => disk.fence_epoch,
+            _ => 0,
+        };
+        let epoch = disk_epoch.max(self.sb().fence_epoch).wrapping_add(1);
+        let previous = self.sb().fence_epoch;
+        self.sb_mut().fence_epoch = epoch;
+        if let Err(e) = self.commit_locked() {
+            self.sb_mut().fence_epoch = previous;
+            return Err(e);
+        }
+        Ok(epoch)
+    }
+
+    // --- Mühürleme ---
+
+    /// Birimi mühürler: her blok için SHA-256 özetlerinden oluşan bir Merkle ağacı yazılır
+    /// ve kök özet Superblock'a kaydedilir. Mühürlü birim yalnızca `EarlyFs` ile salt
+    /// okunur monte edilebilir ve aygıttan okunan her blok köke karşı doğrulanır; uyuşmayan
+    /// blok aygıt hatası (`EIO`) olarak raporlanır. İçeriği bir kez doldurulan firmware ve
+    /// işletim sistemi bölümleri içindir. Mühür kaldırılamaz; birim yeniden yazılacaksa
+    /// yeniden biçimlendirilmelidir.
+    ///
+    /// Özet ağacı (yaklaşık `total_blocks / 128` blok) boş alanda ardışık tahsis edilir;
+    /// yeterli ardışık alan yoksa `OutOfSpace` döner. Aygıtın tamamı bir kez okunur.
+    pub fn seal(self) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.seal_locked();
+        self.lock.release();
+        result
+    }
+
+    /// `seal` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn seal_locked(&self) -> Result<(), SadakFsError<D>> {
+        // 1. Bekleyen her şeyi yaz; özet ağacının bloklarını tahsis edip onları da commit et.
+        // Bundan sonra Superblock dışında hiçbir blok değişmez.
+        self.commit_locked()?;
+        let leaves = self.sb().total_blocks;
+        let needed = SealGeometry::tree_blocks_for(leaves);
+        let (tree_start, len) = self.allocator.allocate_extent(needed)?;
+        if len < needed {
+            for id in tree_start..tree_start + len {
+                self.allocator.release_block(id)?;
+            }
+            return Err(SadakFsError::Allocator(AllocatorError::OutOfSpace));
+        }
+        self.commit_locked()?;
+
+        // 2. Seviyeleri aşağıdan yukarı yaz: 0. seviye blokların, üst seviyeler bir alt
+        // seviyenin özet bloklarının özetlerini tutar
+        let geometry = SealGeometry::new(leaves, tree_start);
+        let mut block = allocate_buffer()?;
+        let mut node = allocate_buffer()?;
+        for level in 0..geometry.depth() {
+            let (start, count) = geometry.level(level);
+            let (child_start, child_count) = if level == 0 { (0, leaves) } else { geometry.level(level - 1) };
+            for i in 0..count {
+                node.fill(0);
+                for slot in 0..SEAL_HASHES_PER_BLOCK {
+                    let child = i * SEAL_HASHES_PER_BLOCK + slot;
+                    if child >= child_count {
+                        break;
+                    }
+                    let id = child_start + child;
+                    if level == 0 && geometry.is_unsealed(id) {
+                        continue; // Yaprak sıfır kalır
+                    }
+                    self.cache.device.read_block(id, block.as_mut())?;
+                    let offset = slot as usize * SEAL_HASH_SIZE;
+                    node[offset..offset + SEAL_HASH_SIZE].copy_from_slice(&seal::sha256(block.as_ref()));
+                }
+                self.cache.device.write_block(start + i, node.as_ref())?;
+            }
+        }
+        self.cache.device.flush()?;
+
+        // 3. Son yazılan blok tepedir; kök özeti en son Superblock'a yazılır
+        let sb = self.sb_mut();
+        sb.seal_tree_start = tree_start;
+        sb.seal_root = seal::sha256(node.as_ref());
+        self.write_superblock()?;
+        self.cache.device.flush()?;
+        Ok(())
+    }
+
+    // --- Kotalar ---
+    // Kullanım, dosyaların `block_count` toplamı ve inode sayısı olarak sahip (uid) ve alt
+    // birim başına tutulur. Blok sınırı tahsis anında (`Allocator` üzerinden), inode sınırı
+    // inode oluşturulurken uygulanır; tablo commit sırasında metadata ağacına yazılır.
+
+    /// `id` kotasının sınırlarını ayarlar (0 = sınır yok). Mevcut kullanım sınırı aşsa
+    /// bile ayarlanır; yalnızca yeni tahsisler reddedilir.
+    pub fn set_quota(&self, id: QuotaId, block_limit: u64, inode_limit: u64) {
+        self.allocator.quota().set_limits(id, block_limit, inode_limit);
+    }
+
+    /// `id` kotasının kullanımı ve sınırları.
+    pub fn quota_usage(&self, id: QuotaId) -> QuotaUsage {
+        self.allocator.quota().usage(id)
+    }
+
+    /// Tüm kota kayıtları, kimlik sırasıyla.
+    pub fn quotas(&self) -> Vec<(QuotaId, QuotaUsage)> {
+        self.allocator.quota().all()
+    }
+
+    /// Kullanımı ana ad alanının ve tüm alt birimlerin dizin ağaçlarını tarayarak yeniden
+    /// hesaplar (sınırlar korunur). Kotalardan önce oluşturulmuş birimlerde veya kayıtlar
+    /// bozulduğunda kullanılır; dizine bağlı olmayan inode'lar sayılmaz.
+    pub fn recount_quotas(&self) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.recount_quotas_locked();
+        self.lock.release();
+        result?;
+
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    /// `recount_quotas` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn recount_quotas_locked(&self) -> Result<(), SadakFsError<D>> {
+        // Bekleyen sayfalar `block_count`'a yansımadan sayılamaz
+        self.flush_pages_locked()?;
+
+        let mut pending = alloc::vec![self.sb().root_dir_inode];
+        self.scan_subvolumes(&mut |subvolume| {
+            pending.push(subvolume.root_inode);
+            true
+        })?;
+
+        self.allocator.quota().clear_usage();
+        let mut seen = BTreeSet::new();
+        while let Some(dir_id) = pending.pop() {
+            if !seen.insert(dir_id) {
+                continue;
+            }
+            let dir = self.read_inode(dir_id)?;
+            self.account_quota(&dir, dir.block_count as i64, 1);
+
+            let mut entries = Vec::new();
+            self.scan_dir(dir_id, &mut |_, entry| {
+                entries.push(entry);
+                true
+            })?;
+            for entry in entries {
+                let inode = self.entry_inode(&entry)?;
+                if entry.file_type == FILE_TYPE_DIRECTORY {
+                    pending.push(inode.inode_id);
+                } else if seen.insert(inode.inode_id) {
+                    // Sabit bağlantılar bir kez sayılır
+                    self.account_quota(&inode, inode.block_count as i64, 1);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Kullanım değişimini inode'un sahibinin ve alt biriminin kotasına yazar.
+    fn account_quota(&self, inode: &Inode, blocks: i64, inodes: i64) {
+        let quota = self.allocator.quota();
+        quota.adjust(QuotaId::User(inode.uid), blocks, inodes);
+        quota.adjust(QuotaId::Subvolume(inode.subvolume_id), blocks, inodes);
+    }
+
+    /// Son commit'ten beri değişen kota kayıtlarını metadata ağacına yazar; kullanımı
+    /// ve sınırı olmayan kayıtlar silinir (çağıran `lock`'u tutmalıdır).
+    fn save_quotas_locked(&self) -> Result<(), SadakFsError<D>> {
+        for (id, usage) in self.allocator.quota().take_dirty() {
+            if usage == QuotaUsage::default() {
+                self.metadata_tree.remove_entry(&self.allocator, &id.key())?;
+            } else {
+                self.metadata_tree.insert_entry(&self.allocator, &id.key(), &usage.encode())?;
+            }
+        }
+        Ok(())
+    }
+
+    // --- Scrub ---
+    // Bir scrub geçişi, metadata ağacının, inode'ların ve veri ağaçlarının checksum
+    // denetimiyle başlar (montajda ertelenen derin doğrulama), ardından tahsisli tüm blokları bölge bölge doğrudan
+    // aygıttan okuyarak ortam hatalarını arar. Konum Superblock'ta tutulur ve her
+    // `SCRUB_CHECKPOINT_BLOCKS` ilerlemede commit edilir; kesilen geçiş baştan başlamaz.
+
+    /// Scrub hız ayarlarını değiştirir; bir sonraki adımdan itibaren geçerlidir.
+    pub fn set_scrub_rate(&self, rate: ScrubRate) {
+        self.lock.acquire();
+        let state = unsafe { &mut *self.scrub.get() };
+        state.rate = rate;
+        state.credit = 0;
+        self.lock.release();
+    }
+
+    /// Geçerli scrub hız ayarları.
+    pub fn scrub_rate(&self) -> ScrubRate {
+        self.lock.acquire();
+        let rate = unsafe { &*self.scrub.get() }.rate;
+        self.lock.release();
+        rate
+    }
+
+    /// Süren geçişte sıradaki doğrulanacak blok (0 = yeni geçiş) ve son tamamlanan
+    /// geçişin zamanı.
+    pub fn scrub_position(&self) -> (BlockId, u64) {
+        self.lock.acquire();
+        let position = (self.sb().scrub_cursor, self.sb().last_scrub_time);
+        self.lock.release();
+        position
+    }
+
+    /// Süren (yoksa yeni) scrub geçişini birimi ayırmadan sonuna kadar çalıştırır. Arka
+    /// plan görevi için tasarlanmıştır: hız sınırı adımı ertelediğinde görev
+    /// `platform::yield_now` ile işlemciyi bırakır. Bozuk metadata blokları sağlam bir
+    /// kopyadan onarılır; onarılamayanlar raporlanır.
+    ///
+    /// # Döndürür
+    /// Geçişin adımlarının birleştirilmiş raporu.
+    pub fn scrub(&self) -> Result<ScrubReport, SadakFsError<D>> {
+        let mut total = ScrubReport::default();
+        loop {
+            let step = self.scrub_step()?;
+            if step.deferred {
+                platform::yield_now();
+                continue;
+            }
+
+            total.verified_blocks += step.verified_blocks;
+            total.unreadable.extend(step.unreadable);
+            total.corrupt_metadata = total.corrupt_metadata.or(step.corrupt_metadata);
+            total.corrupt_file_metadata.extend(step.corrupt_file_metadata);
+            total.repaired.extend(step.repaired);
+            total.cursor = step.cursor;
+            if step.pass_complete {
+                total.pass_complete = true;
+                return Ok(total);
+            }
+        }
+    }
+
+    /// Scrub'ı bir bölge ilerletir. Arka plan görevinin döngü içinde çağırması için
+    /// tasarlanmıştır: hız sınırı veya `idle_only` adımı ertelediyse `deferred` döner ve
+    /// görev bir süre bekleyip yeniden denemelidir. Bulunan hatalar raporlanır ama
+    /// geçişi durdurmaz.
+    pub fn scrub_step(&self) -> Result<ScrubReport, SadakFsError<D>> {
+        self.check_writable()?;
+        self.check_no_transaction()?;
+        let now = self.get_system_time()?;
+        let ops = self.foreground_ops.load(Ordering::Relaxed);
+
+        self.lock.acquire();
+        let result = self.scrub_step_locked(now, ops);
+        self.lock.release();
+        result
+    }
+
+    fn scrub_step_locked(&self, now: u64, ops: u64) -> Result<ScrubReport, SadakFsError<D>> {
+        let state = unsafe { &mut *self.scrub.get() };
+        let mut report = ScrubReport { cursor: self.sb().scrub_cursor, ..ScrubReport::default() };
+
+        // 1. Hız ayarları: ön plan I/O'su varsa veya hak birikmediyse ertele
+        let busy = state.rate.idle_only && ops != state.seen_ops;
+        state.seen_ops = ops;
+        if busy {
+            state.last_step = now;
+            report.deferred = true;
+            return Ok(report);
+        }
+        let budget = state.take_budget(now);
+        if budget == 0 {
+            report.deferred = true;
+            return Ok(report);
+        }
+
+        // 2. Yeni geçiş: önce metadata ağacının tamamı (Blok 0 montajda doğrulandı), sonra
+        // ulaşılabilen inode'lar ve veri ağaçları
+        let total_blocks = self.sb().total_blocks;
+        let mut start = self.sb().scrub_cursor;
+        if start == 0 {
+            report.corrupt_metadata = self.metadata_tree.find_corrupt_node(u8::MAX)?;
+            if let Some(bad) = report.corrupt_metadata {
+                self.queue_repair(bad, CorruptionSource::MetadataTree);
+            } else {
+                report.corrupt_file_metadata = self.scrub_file_metadata_locked()?;
+            }
+        }
+
+        // 2.5. Okuma yolunda veya ağaç denetiminde bulunan bozuklukları sağlam kopyadan onar
+        report.repaired = self.repair_pending_locked(now)?;
+        if report.corrupt_metadata.is_some_and(|bad| report.repaired.contains(&bad)) {
+            report.corrupt_metadata = self.metadata_tree.find_corrupt_node(u8::MAX)?;
+        }
+        report.corrupt_file_metadata.retain(|bad| !report.repaired.contains(bad));
+        if start == 0 {
+            if report.corrupt_metadata.is_none() {
+                unsafe { *self.deep_verify_pending.get() = false };
+            }
+            start = 1;
+        }
+
+        // 3. Bölgedeki tahsisli blokları önbelleği atlayarak aygıttan oku
+        let end = start.saturating_add(budget).min(total_blocks);
+        let mut buffer = try_zeroed(BLOCK_SIZE)?;
+        for id in start..end {
+            if !self.allocator.is_allocated(id)? {
+                continue;
+            }
+            match self.cache.device.read_block(id, &mut buffer) {
+                Ok(()) => report.verified_blocks += 1,
+                Err(_) => report.unreadable.push(id),
+            }
+        }
+
+        // 4. Konumu ilerlet; geçiş bittiyse sıfırla ve temiz geçişi tam denetim say
+        let sb = self.sb_mut();
+        if end >= total_blocks {
+            sb.scrub_cursor = 0;
+            sb.last_scrub_time = now;
+            if unsafe { !*self.deep_verify_pending.get() } && report.unreadable.is_empty() {
+                sb.last_check_time = now;
+                sb.mount_count = 0;
+            }
+            report.pass_complete = true;
+        } else {
+            sb.scrub_cursor = end;
+        }
+        report.cursor = sb.scrub_cursor;
+
+        // 5. Kontrol noktası: konum yalnızca commit ile kalıcı olur
+        if report.pass_complete || sb.scrub_cursor.saturating_sub(state.checkpointed) >= SCRUB_CHECKPOINT_BLOCKS {
+            self.commit_locked()?;
+            state.checkpointed = self.sb().scrub_cursor;
+        }
+        Ok(report)
+    }
+
+    /// Ulaşılabilen her inode'un ve veri ağacının checksum'unu doğrular; bozuk blokları
+    /// onarım kuyruğuna ekler ve döndürür (çağıran `lock`'u tutmalıdır).
+    fn scrub_file_metadata_locked(&self) -> Result<Vec<BlockId>, SadakFsError<D>> {
+        let mut inode_ids = BTreeSet::new();
+        let mut entries = Vec::new();
+        inode_ids.insert(self.sb().root_dir_inode);
+        self.metadata_tree.walk(&mut |key, value| {
+            if key.item_type == ITEM_TYPE_DIR_ENTRY {
+                entries.extend(DirEntry::decode(value));
+            } else if let Some(subvolume) = Subvolume::from_item(key, value) {
+                inode_ids.insert(subvolume.root_inode);
+            }
+        })?;
+        for entry in &entries {
+            // Tabloda olmayan numara fsck'nin işidir; burada yalnızca bloklar doğrulanır
+            match self.entry_block(entry) {
+                Ok(inode_id) => {
+                    inode_ids.insert(inode_id);
+                }
+                Err(SadakFsError::NotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut corrupt = Vec::new();
+        for inode_id in inode_ids {
+            let inode = match self.read_inode(inode_id) {
+                Ok(inode) => inode,
+                Err(SadakFsError::ChecksumError) => {
+                    self.queue_repair(inode_id, CorruptionSource::Inode);
+                    corrupt.push(inode_id);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            if inode.data_tree_root != 0 {
+                if let Some(bad) = self.data_tree(inode.data_tree_root)?.find_corrupt_node(Self::data_tree_levels(&inode))? {
+                    self.queue_repair(bad, CorruptionSource::DataTree);
+                    corrupt.push(bad);
+                }
+            }
+        }
+        Ok(corrupt)
+    }
+
+    // --- Okuma Onarımı ---
+    // Checksum'u tutmayan bir blok, aygıtın her kopyasından (yansıtmalı dizide her üyeden)
+    // ayrı ayrı okunur. Sağlam bir kopya bulunursa blok tüm kopyalara yeniden yazılır.
+    // Bozuk her kopya, onarılsın veya onarılmasın, adli kayıt günlüğüne eklenir.
+
+    /// Bozuk bulunan bloğu bir sonraki scrub adımında onarılmak üzere kaydeder.
+    fn queue_repair(&self, id: BlockId, source: CorruptionSource) {
+        self.forensics_lock.acquire();
+        unsafe { &mut *self.pending_repair.get() }.insert(id, source);
+        self.forensics_lock.release();
+    }
+
+    /// Bekleyen blokları onarmayı dener; onarılanları döndürür (çağıran `lock`'u tutmalıdır).
+    fn repair_pending_locked(&self, now: u64) -> Result<Vec<BlockId>, SadakFsError<D>> {
+        self.forensics_lock.acquire();
+        let pending = mem::take(unsafe { &mut *self.pending_repair.get() });
+        self.forensics_lock.release();
+
+        let mut repaired = Vec::new();
+        for (id, source) in pending {
+            // Bu arada serbest bırakılan blok artık onarılacak bir şey içermez
+            if self.allocator.is_allocated(id)? && self.repair_block_locked(id, source, now)? {
+                repaired.push(id);
+            }
+        }
+        Ok(repaired)
+    }
+
+    /// Bloğun tüm kopyalarını doğrular ve bozuk kopyaları sağlam olanla değiştirir.
+    /// Sağlam kopya yoksa blok olduğu gibi kalır ve `false` döner (çağıran `lock`'u tutmalıdır).
+    fn repair_block_locked(&self, id: BlockId, source: CorruptionSource, now: u64) -> Result<bool, SadakFsError<D>> {
+        let mut buffer = try_zeroed(BLOCK_SIZE)?;
+        let mut good: Option<Vec<u8>> = None;
+        let mut bad = Vec::new();
+        for copy in 0..self.cache.device.copies() {
+            if self.cache.device.read_copy(id, copy, &mut buffer).is_err() {
+                continue; // Okunamayan kopya scrub'ın ortam hatası olarak raporlanır
+            }
+            let Some((expected, found)) = self.block_checksums(source, &buffer) else {
+                return Ok(false);
+            };
+            if expected == found {
+                if good.is_none() {
+                    good = Some(mem::replace(&mut buffer, try_zeroed(BLOCK_SIZE)?));
+                }
+            } else {
+                bad.push((copy, expected, found));
+            }
+        }
+
+        let repaired = match &good {
+            Some(data) if !bad.is_empty() => {
+                self.cache.device.write_block(id, data)?;
+                self.cache.device.flush()?;
+                true
+            }
+            _ => false,
+        };
+        // Önbellekteki bozuk kopya sağlam içerikle değiştirilir (kirli blok bellekte daha yenidir)
+        if let Some(data) = &good {
+            let block_arc = self.cache.get_block(id)?;
+            let block = unsafe { &mut *block_arc.get() };
+            if !block.is_dirty {
+                block.data.copy_from_slice(data);
+            }
+        }
+
+        self.forensics_lock.acquire();
+        let log = unsafe { &mut *self.forensics.get() };
+        for (copy, expected, found) in bad {
+            log.push(CorruptionRecord {
+                timestamp: now,
+                block: id,
+                source,
+                device: copy as u16,
+                expected,
+                found,
+                repaired,
+            });
+        }
+        self.forensics_lock.release();
+        Ok(repaired)
+    }
+
+    /// Blok içeriğindeki saklı ve hesaplanan checksum; tür bu yolla doğrulanamıyorsa `None`.
+    fn block_checksums(&self, source: CorruptionSource, data: &[u8]) -> Option<(u32, u32)> {
+        let policy = self.sb().checksum_policy();
+        let (offset, found) = match source {
+            CorruptionSource::Inode => (mem::offset_of!(Inode, checksum), inode_checksum(policy.inode, data)),
+            CorruptionSource::MetadataTree => (mem::offset_of!(BTreeNodeHeader, checksum), node_checksum(policy.metadata_tree, data)),
+            CorruptionSource::DataTree => (mem::offset_of!(BTreeNodeHeader, checksum), node_checksum(policy.data_tree, data)),
+            CorruptionSource::SpaceMap => return None,
+        };
+        let expected = u32::from_ne_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]);
+        Some((expected, found))
+    }
+
+    /// Adli kayıt günlüğü değiştiyse yeni bir bloğa yazar (çağıran `lock`'u tutmalıdır).
+    fn save_forensic_log_locked(&self) -> Result<(), SadakFsError<D>> {
+        self.forensics_lock.acquire();
+        let log = unsafe { &mut *self.forensics.get() };
+        if !log.take_dirty() {
+            self.forensics_lock.release();
+            return Ok(());
+        }
+        let result = (|| {
+            let old = self.sb().forensic_log_block;
+            if old != 0 {
+                self.allocator.release_block(old)?;
+            }
+            let block_id = self.allocator.allocate_block()?;
+            let block_arc = self.cache.get_block(block_id)?;
+            let block = unsafe { &mut *block_arc.get() };
+            log.encode(block.data.as_mut());
+            block.is_dirty = true;
+            self.sb_mut().forensic_log_block = block_id;
+            Ok(())
+        })();
+        if result.is_err() {
+            log.mark_dirty();
+        }
+        self.forensics_lock.release();
+        result
+    }
+
+    // --- Tutarlılık Denetimi (fsck) ---
+
+    /// Birimin çevrimdışı tutarlılık denetimi: Superblock'un gösterdiği yapılar, tahsis
+    /// haritası, metadata ağacı ve ulaşılabilen tüm inode'lar dolaşılır. Başvurulan bloklar
+    /// tahsis haritasıyla karşılaştırılır; ağaç düğümlerinin ve inode'ların checksum'ları,
+    /// bağlantı sayıları ve dizin boyutları doğrulanır.
+    ///
+    /// `repair` verilirse giderilebilen sorunlar (bkz. `FsckProblem::is_repairable`)
+    /// düzeltilip commit edilir; bozuk bloklar yalnızca raporlanır. Okunamayan bir ağaç
+    /// varsa sızıntılar ne raporlanır ne de serbest bırakılır. İlk bitmap bloğundan önceki
+    /// ayrılmış alan sızıntı sayılmaz. Çevrimdışı bir işlemdir: birimin tek kullanıcısı
+    /// olunmalı ve açık dosya tutucusu bulunmamalıdır.
+    pub fn fsck(&self, repair: bool) -> Result<FsckReport, SadakFsError<D>> {
+        self.check_no_transaction()?;
+        if repair {
+            self.check_writable()?;
+        }
+        // Denetim kalıcı durumu görür; bekleyen sayfalar ve taze bloklar önce yazılır
+        self.sync()?;
+
+        self.lock.acquire();
+        let result = self.fsck_locked(repair);
+        self.lock.release();
+        result
+    }
+
+    /// `fsck` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn fsck_locked(&self, repair: bool) -> Result<FsckReport, SadakFsError<D>> {
+        let total = self.sb().total_blocks;
+        let bitmap_start = self.allocator.bitmap_start();
+        let mut report = FsckReport { complete: true, ..FsckReport::default() };
+        let mut refs = BlockReferences::new(total);
+
+        // 1. Superblock'un doğrudan gösterdiği yapılar
+        refs.claim(0);
+        if self.sb().feature_compat & FEATURE_COMPAT_SUPERBLOCK_SLOTS != 0 {
+            refs.claim(SUPERBLOCK_B_BLOCK);
+        }
+        for id in bitmap_start..bitmap_start + self.allocator.bitmap_block_count() {
+            refs.claim(id);
+        }
+        for id in self.allocator.space_map_blocks() {
+            refs.claim(id);
+        }
+        let journal = self.sb().journal_start;
+        if journal != 0 {
+            for id in journal..journal + self.sb().journal_blocks() {
+                refs.claim(id);
+            }
+        }
+        for id in [self.sb().warm_set_block, self.sb().forensic_log_block] {
+            if id != 0 {
+                refs.claim(id);
+            }
+        }
+        let seal = self.sb().seal_tree_start;
+        if seal != 0 {
+            for id in seal..seal + SealGeometry::tree_blocks_for(total) {
+                refs.claim(id);
+            }
+        }
+
+        // 2. Metadata ağacı; bozuksa öğeleri güvenilmez olduğundan denetim burada biter
+        if let Some(bad) = self.metadata_tree.find_corrupt_node(u8::MAX)? {
+            report.problems.push(FsckProblem::CorruptBlock(bad));
+            report.complete = false;
+            return Ok(report);
+        }
+        for id in self.metadata_tree.node_ids()? {
+            refs.claim(id);
+        }
+
+        let mut dir_entries = Vec::new();
+        let mut overflow_blocks = Vec::new();
+        let mut tail_blocks = BTreeSet::new();
+        let mut shared = BTreeSet::new();
+        let mut anchors = BTreeSet::new();
+        let mut orphans = Vec::new();
+        anchors.insert(self.sb().root_dir_inode);
+        self.metadata_tree.walk(&mut |key, value| match key.item_type {
+            ITEM_TYPE_DIR_ENTRY => {
+                if let Some(entry) = DirEntry::decode(value) {
+                    dir_entries.push((*key, entry));
+                }
+            }
+            ITEM_TYPE_XATTR => {
+                if let Some(XattrEntry { value: XattrValue::Overflow { block, .. }, .. }) = XattrEntry::decode(value) {
+                    overflow_blocks.push(block);
+                }
+            }
+            _ => {
+                if let Some(subvolume) = Subvolume::from_item(key, value) {
+                    anchors.insert(subvolume.root_inode);
+                } else if let Some(record) = SharedBlock::from_item(key, value) {
+                    shared.insert(record.physical);
+                } else if let Some(record) = TailBlock::from_item(key, value) {
+                    tail_blocks.insert(record.physical);
+                } else if let Some(inode_id) = Orphan::from_key(key) {
+                    orphans.push(inode_id);
+                }
+            }
+        })?;
+
+        // 3. Inode'lar: kökler, dizin girdilerinin hedefleri ve yetimler
+        let mut inodes: BTreeMap<BlockId, Inode> = BTreeMap::new();
+        let mut links: BTreeMap<BlockId, u32> = BTreeMap::new();
+        let mut dir_sizes: BTreeMap<u64, u64> = BTreeMap::new();
+        let mut dangling = Vec::new();
+        for &id in anchors.iter().chain(orphans.iter()) {
+            match self.read_inode(id) {
+                Ok(inode) => {
+                    inodes.insert(id, inode);
+                }
+                Err(_) => {
+                    report.problems.push(FsckProblem::CorruptBlock(id));
+                    report.complete = false;
+                }
+            }
+        }
+        for (key, entry) in &dir_entries {
+            let id = match self.entry_block(entry) {
+                Ok(id) => Some(id),
+                Err(SadakFsError::NotFound) => None,
+                Err(e) => return Err(e),
+            };
+            if let Some(id) = id.filter(|id| !inodes.contains_key(id)) {
+                if let Ok(inode) = self.read_inode(id) {
+                    inodes.insert(id, inode);
+                }
+            }
+            // Tablonun gösterdiği blok başka bir inode'a geçmişse girdi de sahipsizdir
+            let target = id.and_then(|id| inodes.get(&id)).filter(|inode| self.entry_ino(inode) == entry.ino);
+            let id = match target {
+                Some(inode) if inode.file_type == entry.file_type => inode.inode_id,
+                _ => {
+                    report.problems.push(FsckProblem::DanglingEntry { dir: key.object_id, name: entry.name.clone() });
+                    dangling.push(*key);
+                    continue;
+                }
+            };
+            *links.entry(id).or_insert(0) += 1;
+            *dir_sizes.entry(key.object_id).or_insert(0) += 1;
+        }
+
+        // 4. Her inode'un blokları, bağlantı sayısı ve (dizinse) boyutu
+        let mut link_fixes = Vec::new();
+        let mut size_fixes = Vec::new();
+        let mut height_fixes = Vec::new();
+        for (&id, inode) in &inodes {
+            report.inodes += 1;
+            refs.claim(id);
+
+            if inode.data_tree_root != 0 {
+                let data_tree = self.data_tree(inode.data_tree_root)?;
+                if let Some(bad) = data_tree.find_corrupt_node(u8::MAX)? {
+                    report.problems.push(FsckProblem::CorruptBlock(bad));
+                    report.complete = false;
+                } else {
+                    let actual = data_tree.height()?;
+                    if inode.data_tree_height != 0 && inode.data_tree_height != actual {
+                        report.problems.push(FsckProblem::DataTreeHeight { inode: id, stored: inode.data_tree_height, actual });
+                        height_fixes.push((id, actual));
+                    }
+                    for node in data_tree.node_ids()? {
+                        refs.claim(node);
+                    }
+                    data_tree.walk(&mut |key, value| {
+                        if let Some(extent) = Extent::from_item(key, value) {
+                            for physical in extent.physical_blocks() {
+                                if shared.contains(&physical) {
+                                    refs.share(physical);
+                                } else {
+                                    refs.claim(physical);
+                                }
+                            }
+                        } else if let Some(fragment) = TailFragment::from_item(key, value) {
+                            refs.share(fragment.tail_block);
+                        }
+                    })?;
+                }
+            }
+
+            let actual = links.get(&id).copied().unwrap_or(0) + anchors.contains(&id) as u32;
+            if inode.link_count != actual {
+                report.problems.push(FsckProblem::LinkCount { inode: id, stored: inode.link_count, actual });
+                link_fixes.push((id, actual));
+            }
+            if inode.file_type == FILE_TYPE_DIRECTORY {
+                let actual = dir_sizes.get(&self.entry_ino(inode)).copied().unwrap_or(0);
+                if inode.file_size != actual {
+                    report.problems.push(FsckProblem::DirectorySize { dir: id, stored: inode.file_size, actual });
+                    size_fixes.push((id, actual));
+                }
+            }
+        }
+        for block in overflow_blocks {
+            refs.claim(block);
+        }
+        for &block in &tail_blocks {
+            refs.share(block);
+        }
+        report.referenced_blocks = refs.len();
+        report.problems.extend(refs.out_of_range.iter().map(|&id| FsckProblem::OutOfRange(id)));
+        report.problems.extend(refs.duplicates.iter().map(|&id| FsckProblem::DuplicateReference(id)));
+
+        // 5. Başvurular ile tahsis haritasını karşılaştır
+        let mut unallocated = Vec::new();
+        let mut leaked = Vec::new();
+        for id in 0..total {
+            let allocated = self.allocator.is_allocated(id)?;
+            let referenced = refs.contains(id);
+            if referenced && !allocated {
+                unallocated.push(id);
+            } else if allocated && !referenced && id >= bitmap_start && report.complete {
+                leaked.push(id);
+            }
+        }
+        report.problems.extend(fsck::runs(&unallocated).into_iter().map(|(start, length)| FsckProblem::Unallocated { start, length }));
+        report.problems.extend(fsck::runs(&leaked).into_iter().map(|(start, length)| FsckProblem::Leaked { start, length }));
+
+        if !repair {
+            return Ok(report);
+        }
+
+        // 6. Onarım: başvurulan bloklar, ağaç değişikliklerinden önce dolu işaretlenir ki
+        // yeni tahsislere verilmesin
+        for &id in &unallocated {
+            self.allocator.mark_allocated(id)?;
+        }
+        for &id in &leaked {
+            self.allocator.release_block(id)?;
+        }
+        for key in &dangling {
+            self.metadata_tree.remove_entry(&self.allocator, key)?;
+        }
+        for &(id, actual) in &link_fixes {
+            self.update_inode_locked(id, |inode| inode.link_count = actual)?;
+        }
+        for &(id, actual) in &size_fixes {
+            self.update_inode_locked(id, |inode| inode.file_size = actual)?;
+        }
+        for &(id, actual) in &height_fixes {
+            self.update_inode_locked(id, |inode| inode.data_tree_height = actual)?;
+        }
+        report.repaired = report.problems.iter().filter(|problem| problem.is_repairable()).count() as u64;
+        self.commit_locked()?;
+        Ok(report)
+    }
+
+    // --- Snapshot İşlemleri ---
+
+    #[cfg(feature = "snapshot")]
+    /// Dosya sisteminin o anki tutarlı halini bir snapshot olarak dondurur.
+    ///
+    /// Önce bekleyen değişiklikler commit edilir, ardından yerinde güncellenen bloklar
+    /// (Superblock ve tahsis haritası) belleğe kopyalanır. Tahsis haritası kopyası tahsis
+    /// yöneticisine pin olarak kaydedilir: snapshot yaşadıkça gördüğü bloklar serbest
+    /// bırakılmaz ve sonradan yerinde yazılan blokların eski halleri snapshot'ta korunur.
+    /// Snapshot'ın tuttuğu alan son `Arc`'ı düştükten sonraki commit'te geri kazanılır.
+    pub fn create_snapshot(&self) -> Result<Snapshot, SadakFsError<D>> {
+        self.check_writable()?;
+        self.check_no_transaction()?;
+        // Dönemi commit'ten önce kapat: arada yapılan değişiklikler yeni döneme yazılır ve
+        // snapshot'a dahil olsalar da "değişmiş" sayılır (fazlası zararsızdır, eksiği değil).
+        self.lock.acquire();
+        // Üst sınırda dönem artık ilerlemez; sorgular o dönemi de dahil ederek fazlasını raporlar
+        let epoch = self.sb().change_epoch;
+        self.sb_mut().change_epoch = (epoch + 1).min(CHANGE_EPOCH_MAX);
+
+        // Commit ile kopya arasında kilit bırakılmaz: harita tam olarak commit edilen hali gösterir
+        let result = self.commit_locked().and_then(|_| self.create_snapshot_locked(epoch));
+        self.lock.release();
+        result
+    }
+
+    #[cfg(feature = "snapshot")]
+    /// `create_snapshot` gövdesi; son commit'ten sonra çağrılmalıdır (çağıran `lock`'u tutmalıdır).
+    fn create_snapshot_locked(&self, epoch: u64) -> Result<Snapshot, SadakFsError<D>> {
+        // 1. Superblock'un ham kopyasını al; son commit B yuvasına yazılmış olabileceğinden
+        // diskteki Blok 0 yerine bellekteki hal kodlanır
+        let mut superblock_image = try_zeroed(BLOCK_SIZE)?;
+        self.encode_superblock(&mut superblock_image);
+        let slots = self.sb().feature_compat & FEATURE_COMPAT_SUPERBLOCK_SLOTS != 0;
+
+        // 2. Tahsis haritası bloklarının ham kopyalarını al
+        let bitmap_start_id = self.sb().bitmap_start_id;
+        let bitmap_block_count = self.allocator.bitmap_block_count();
+        let mut bitmap_image = Vec::with_capacity(bitmap_block_count as usize * BLOCK_SIZE);
+        for i in 0..bitmap_block_count {
+            let bitmap_arc = self.cache.get_block(bitmap_start_id + i)?;
+            let bitmap_ref = unsafe { &*bitmap_arc.get() };
+            bitmap_image.extend_from_slice(bitmap_ref.data.as_ref());
+        }
+
+        let pin = self.allocator.pin(bitmap_start_id, self.sb().total_blocks, bitmap_image)?;
+
+        let snapshot = Snapshot::new(self.get_system_time()?, epoch, self.sb().metadata_root_id, superblock_image, pin)?;
+        Ok(if slots { snapshot.with_superblock_copy(SUPERBLOCK_B_BLOCK) } else { snapshot })
+    }
+
+    #[cfg(feature = "snapshot")]
+    /// Canlı snapshot'ların gördüğü ve yerinde yazılmak üzere olan blokların diskteki (son
+    /// commit'teki) hallerini snapshot'lara kopyalar (bkz. `AllocationPin::preserve`).
+    /// Bloklar yerlerine yazılmadan önce çağrılmalıdır (çağıran `lock`'u tutmalıdır).
+    fn preserve_for_snapshots_locked(&self, ids: &[BlockId]) -> Result<(), SadakFsError<D>> {
+        let pins = self.allocator.live_pins();
+        if pins.is_empty() {
+            return Ok(());
+        }
+
+        let mut image = try_zeroed(BLOCK_SIZE)?;
+        for &id in ids {
+            if !pins.iter().any(|pin| pin.needs_copy(id)) {
+                continue;
+            }
+            self.cache.device.read_block(id, &mut image)?;
+            for pin in &pins {
+                pin.preserve(id, &image)?;
+            }
+        }
+        Ok(())
+    }
+
+    // --- Değişen Blok Takibi ---
+    // Her commit, son commit'ten beri tahsis edilen blokları ve yerinde yazılan inode
+    // bloklarını geçerli dönemin kayıtlarına ekler. Superblock, tahsis haritası ve alan
+    // haritası her commit'te yeniden yazıldığından kaydedilmez, her sorguda raporlanır.
+
+    #[cfg(feature = "snapshot")]
+    /// `snapshot` alındıktan sonra içeriği değişmiş olabilecek blokları, birleştirilmiş
+    /// (ilk blok, uzunluk) aralıkları olarak döndürür. Yedekleme ajanları yalnızca bu
+    /// blokları kopyalayarak artımlı yedek alabilir. Sonuç fazlasını içerebilir, eksiğini
+    /// içermez; henüz commit edilmemiş değişiklikler de dahildir.
+    pub fn changed_blocks_since(&self, snapshot: &Snapshot) -> Result<Vec<(BlockId, u64)>, SadakFsError<D>> {
+        self.changed_blocks_since_epoch(snapshot.epoch)
+    }
+
+    #[cfg(feature = "snapshot")]
+    /// `changed_blocks_since` gibi, ancak snapshot yerine dönem numarasıyla
+    /// (`Snapshot::epoch`) çalışır; snapshot'ı bellekte tutmayan ajanlar için.
+    pub fn changed_blocks_since_epoch(&self, epoch: u64) -> Result<Vec<(BlockId, u64)>, SadakFsError<D>> {
+        if self.sb().total_blocks > CHANGE_TRACKING_MAX_BLOCKS {
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+
+        self.lock.acquire();
+        let result = self.changed_blocks_locked(epoch);
+        self.lock.release();
+        result
+    }
+
+    #[cfg(feature = "snapshot")]
+    fn changed_blocks_locked(&self, epoch: u64) -> Result<Vec<(BlockId, u64)>, SadakFsError<D>> {
+        let mut runs: Vec<(BlockId, u64)> = Vec::new();
+
+        // 1. Kalıcı kayıtlar: `epoch`ten sonraki dönemler
+        let first_epoch = (epoch + 1).min(CHANGE_EPOCH_MAX);
+        self.metadata_tree.walk_from(&ChangedRun::key(first_epoch, 0), &mut |key, value| {
+            if !ChangedRun::is_change_key(key) {
+                return false;
+            }
+            if let Some(run) = ChangedRun::from_item(key, value) {
+                runs.push((run.start, run.length));
+            }
+            true
+        })?;
+
+        // 2. Henüz kaydedilmemiş değişiklikler
+        let mut pending: BTreeSet<BlockId> = self.allocator.fresh_blocks().into_iter().collect();
+        pending.extend(unsafe { &*self.changed_in_place.get() }.iter().copied());
+        pending.extend(self.allocator.space_map_blocks());
+        runs.extend(changes::to_runs(&pending));
+
+        // 3. Her commit'te yerinde yazılan bloklar
+        runs.push((0, 1));
+        runs.push((self.sb().bitmap_start_id, self.allocator.bitmap_block_count()));
+
+        Ok(changes::merge_runs(runs))
+    }
+
+    #[cfg(feature = "snapshot")]
+    /// `up_to_epoch` dahil o döneme kadarki değişen blok kayıtlarını siler. Yedekleme
+    /// ajanı bu dönemlerden önceki snapshot'lara göre artık sorgu yapmayacaksa çağrılır;
+    /// silinen dönemlere göre yapılan sorgular eksik sonuç verir.
+    pub fn prune_changes(&self, up_to_epoch: u64) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        self.lock.acquire();
+        let result = self.prune_changes_locked(up_to_epoch);
+        self.lock.release();
+        result?;
+        self.commit_if_over_budget()?;
+        Ok(())
+    }
+
+    #[cfg(feature = "snapshot")]
+    /// `policy`'nin saklamadığı snapshot'ları `snapshots` listesinden çıkarır ve kalan en
+    /// eski snapshot'tan önceki dönemlerin değişen blok kayıtlarını siler. Periyodik
+    /// snapshot alan sistemlerin bakım döngüsünde snapshot'tan sonra çağrılır; böylece
+    /// snapshot kopyalarının belleği ve kayıtların disk alanı sınırsız büyümez.
+    ///
+    /// Snapshot'ı tutmadan dönem numarasıyla sorgu yapan ajanların (bkz.
+    /// `changed_blocks_since_epoch`) dönemleri de silinebilir; bu ajanlar snapshot'larını
+    /// listede tutmalıdır. Liste boşalırsa kayıtlara dokunulmaz.
+    ///
+    /// # Döndürür
+    /// Listeden çıkarılan snapshot sayısı.
+    pub fn prune_snapshots(&self, snapshots: &mut Vec<Arc<Snapshot>>, policy: &RetentionPolicy) -> Result<usize, SadakFsError<D>> {
+        let timestamps: Vec<u64> = snapshots.iter().map(|snapshot| snapshot.timestamp).collect();
+        let mut keep = policy.retained(&timestamps).into_iter();
+        let before = snapshots.len();
+        snapshots.retain(|_| keep.next().unwrap_or(true));
+        let removed = before - snapshots.len();
+
+        // Sorgular snapshot'ın döneminden sonraki kayıtları okur; öncesi artık gereksiz
+        if removed != 0 {
+            if let Some(oldest) = snapshots.iter().map(|snapshot| snapshot.epoch).min() {
+                self.prune_changes(oldest)?;
+            }
+        }
+        Ok(removed)
+    }
+
+    #[cfg(feature = "snapshot")]
+    fn prune_changes_locked(&self, up_to_epoch: u64) -> Result<(), SadakFsError<D>> {
+        let mut keys: Vec<BTreeKey> = Vec::new();
+        self.metadata_tree.walk_from(&ChangedRun::key(0, 0), &mut |key, value| {
+            match ChangedRun::from_item(key, value) {
+                Some(run) if run.epoch <= up_to_epoch => {
+                    keys.push(*key);
+                    true
+                }
+                _ => false,
+            }
+        })?;
+
+        for key in keys {
+            self.metadata_tree.remove_entry(&self.allocator, &key)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "snapshot")]
+    /// Commit sırasında, alan haritası yazılmadan önce çağrılır: bu işlemde değişen
+    /// blokları geçerli dönemin kayıtlarına ekler. Kayıt eklemek metadata ağacında yeni
+    /// düğümler tahsis edebileceğinden, eklenecek blok kalmayana kadar tekrarlanır
+    /// (yeni düğümler bu işlemde taze olduğundan yerinde güncellenir ve döngü biter).
+    fn record_changes_locked(&self) -> Result<(), SadakFsError<D>> {
+        if self.sb().total_blocks > CHANGE_TRACKING_MAX_BLOCKS {
+            unsafe { &mut *self.changed_in_place.get() }.clear();
+            return Ok(());
+        }
+
+        let epoch = self.sb().change_epoch;
+        let mut recorded: BTreeSet<BlockId> = BTreeSet::new();
+        loop {
+            let changed = unsafe { &mut *self.changed_in_place.get() };
+            let mut blocks: BTreeSet<BlockId> = core::mem::take(changed);
+            blocks.extend(self.allocator.fresh_blocks());
+            blocks.retain(|id| !recorded.contains(id));
+            if blocks.is_empty() {
+                return Ok(());
+            }
+
+            for (start, length) in changes::to_runs(&blocks) {
+                let key = ChangedRun::key(epoch, start);
+                // Aynı dönemde aynı bloktan başlayan daha uzun bir kayıt varsa korunur
+                let existing = self.metadata_tree.lookup(&key)?
+                    .and_then(|value| ChangedRun::from_item(&key, &value))
+                    .map_or(0, |run| run.length);
+                let run = ChangedRun { epoch, start, length: length.max(existing) };
+                self.metadata_tree.insert_entry(&self.allocator, &key, &run.encode())?;
+            }
+            recorded.extend(blocks);
+        }
+    }
+
+    // --- Alan Hesabı ---
+    // Snapshot'lar blokları kendi tahsis haritası kopyalarıyla tanır. Bir blok, snapshot
+    // anında dolu ve bugün hâlâ canlı dosya sisteminde kullanımdaysa paylaşılmış sayılır.
+    // Canlı dosya sisteminin bıraktığı ama bir snapshot'ın gördüğü bloklar tahsis haritasında
+    // dolu kalır (bkz. `Allocator::is_held`); bunlar snapshot'a ait sayılır.
+
+    #[cfg(feature = "snapshot")]
+    /// Bir dosyanın başvurduğu blokları (inode, veri ağacı düğümleri ve veri) ve bunlardan
+    /// verilen snapshot'ların hiçbiriyle paylaşılmayanları sayar. Geri yazılmayı bekleyen
+    /// sayfalar henüz blok kaplamadığından sayılmaz.
+    pub fn file_space_usage(&self, inode: &Inode, snapshots: &[Arc<Snapshot>]) -> Result<SpaceUsage, SadakFsError<D>> {
+        let current = self.read_inode(inode.inode_id)?;
+        let mut blocks = alloc::vec![current.inode_id];
+
+        if current.data_tree_root != 0 {
+            let data_tree = self.data_tree(current.data_tree_root)?;
+            blocks.extend(data_tree.node_ids()?);
+            data_tree.walk(&mut |key, value| {
+                if let Some(extent) = Extent::from_item(key, value) {
+                    blocks.extend(extent.physical_blocks());
+                }
+            })?;
+        }
+        // Bölünmüş sıkıştırılmış extent'lerin parçaları aynı blokları gösterir
+        blocks.sort_unstable();
+        blocks.dedup();
+
+        let exclusive = blocks
+            .iter()
+            .filter(|&&id| !snapshots.iter().any(|s| s.is_allocated(id)))
+            .count() as u64;
+        Ok(SpaceUsage { referenced: blocks.len() as u64, exclusive })
+    }
+
+    #[cfg(feature = "snapshot")]
+    /// Bir snapshot'ın tuttuğu blokları sayar. `exclusive`, snapshot silinirse geri
+    /// kazanılacak tahmini alandır: canlı dosya sisteminin ve `others` snapshot'larının
+    /// hiçbirinin kullanmadığı bloklar.
+    pub fn snapshot_space_usage(&self, snapshot: &Snapshot, others: &[Arc<Snapshot>]) -> Result<SpaceUsage, SadakFsError<D>> {
+        let mut usage = SpaceUsage::default();
+        for id in 0..snapshot.total_blocks.min(self.sb().total_blocks) {
+            if !snapshot.is_allocated(id) {
+                continue;
+            }
+            usage.referenced += 1;
+            let live = self.allocator.is_allocated(id)? && !self.allocator.is_held(id);
+            if !live && !others.iter().any(|s| s.is_allocated(id)) {
+                usage.exclusive += 1;
+            }
+        }
+        Ok(usage)
+    }
+
+    /// Birimin kapasite ve doluluk özeti (`statfs`). Boş blok sayısı tahsis haritasının
+    /// grup sayaçlarından gelir (bilinmeyen gruplar bir kez okunur); metadata ağacının
+    /// düğümleri sayılırken ağaç dolaşılır. Inode sayısı kota sayaçlarından alındığından
+    /// kotalardan önce oluşturulmuş birimlerde `recount_quotas` sonrası doğrudur.
+    pub fn stat_fs(&self) -> Result<FsStat, SadakFsError<D>> {
+        self.lock.acquire();
+        let result = self.stat_fs_locked();
+        self.lock.release();
+        result
+    }
+
+    /// `stat_fs` gövdesi (çağıran `lock`'u tutmalıdır).
+    fn stat_fs_locked(&self) -> Result<FsStat, SadakFsError<D>> {
+        let free_blocks = self.allocator.free_blocks()?;
+        let metadata_blocks = 1 // Superblock
+            + self.allocator.bitmap_block_count()
+            + self.allocator.space_map_blocks().len() as u64
+            + self.metadata_tree.node_ids()?.len() as u64;
+        let inodes = self
+            .allocator
+            .quota()
+            .all()
+            .iter()
+            .filter(|(id, _)| matches!(id, QuotaId::Subvolume(_)))
+            .map(|(_, usage)| usage.inodes)
+            .sum();
+
+        Ok(FsStat {
+            block_size: BLOCK_SIZE as u64,
+            total_blocks: self.sb().total_blocks,
+            free_blocks,
+            reclaimable_blocks: self.allocator.pending_free_count(),
+            metadata_blocks,
+            inodes,
+            free_inodes: free_blocks,
+        })
+    }
+
+    #[cfg(feature = "snapshot")]
+    /// Snapshot'ların tuttuğu blokları ayrıca gösteren doluluk raporu. Yalnızca canlı
+    /// tahsis haritasına bakan hesap, snapshot'ların tuttuğu alanı boş sanır.
+    pub fn disk_usage(&self, snapshots: &[Arc<Snapshot>]) -> Result<DiskUsage, SadakFsError<D>> {
+        let total_blocks = self.sb().total_blocks;
+        let mut usage = DiskUsage { total_blocks, ..DiskUsage::default() };
+
+        for id in 0..total_blocks {
+            if self.allocator.is_allocated(id)? && !self.allocator.is_held(id) {
+                usage.live_blocks += 1;
+            } else if snapshots.iter().any(|s| s.is_allocated(id)) {
+                usage.snapshot_blocks += 1;
+            }
+        }
+        usage.free_blocks = total_blocks - usage.live_blocks - usage.snapshot_blocks;
+        Ok(usage)
+    }
+
+    #[cfg(feature = "snapshot")]
+    /// Bir snapshot'ı salt okunur sanal bir BlockDevice olarak dışa aktarır.
+    /// Dönen aygıt, yedekleme yazılımına doğrudan verilebilir.
+    pub fn export_snapshot(&self, snapshot: Arc<Snapshot>) -> SnapshotDevice<D> {
+        SnapshotDevice::new(self.cache.device.clone(), snapshot)
+    }
+
+    // --- Çoğaltma (Send/Receive) ---
+
+    #[cfg(feature = "snapshot")]
+    /// `snapshot`'ı taşınabilir bir çoğaltma akışı olarak `writer`'a yazar. `base` verilirse
+    /// yalnızca o snapshot'tan sonra değişmiş olabilecek bloklar gönderilir (artımlı yedek);
+    /// verilmezse snapshot'ın tüm dolu blokları gönderilir. Bloklar snapshot'ın görünümünden
+    /// okunur (bkz. `export_snapshot`); `base`, `snapshot`'tan önce alınmış olmalı ve dönem
+    /// kayıtları budanmamış olmalıdır (bkz. `prune_changes`).
+    ///
+    /// # Döndürür
+    /// Gönderilen blok sayısı.
+    pub fn send<W: SendSink>(&self, snapshot: &Arc<Snapshot>, base: Option<&Snapshot>, writer: &mut W) -> Result<u64, SadakFsError<D>> {
+        // 1. Aday aralıklar: değişenler veya tüm aygıt
+        let candidates = match base {
+            Some(base) if base.epoch >= snapshot.epoch => return Err(SadakFsError::Syscall(SyscallError::EINVAL)),
+            Some(base) => {
+                // İkinci Superblock yuvası yerinde yazılır ve kayıtlarda yer almaz
+                let mut changed = self.changed_blocks_since(base)?;
+                changed.push((SUPERBLOCK_B_BLOCK, 1));
+                changes::merge_runs(changed)
+            }
+            None => alloc::vec![(0, snapshot.total_blocks)],
+        };
+
+        // 2. Yalnızca snapshot'ta dolu (veya dondurulmuş) blokları tut
+        let mut runs: Vec<(BlockId, u64)> = Vec::new();
+        for (start, length) in candidates {
+            let end = (start + length).min(snapshot.total_blocks);
+            for id in start..end {
+                if !snapshot.is_allocated(id) && !snapshot.is_frozen(id) {
+                    continue;
+                }
+                match runs.last_mut() {
+                    Some((run_start, run_len)) if *run_start + *run_len == id && *run_len < STREAM_RUN_MAX_BLOCKS => *run_len += 1,
+                    _ => runs.push((id, 1)),
+                }
+            }
+        }
+
+        // 3. Başlık, aralık kayıtları ve bitiş kaydı
+        let header = StreamHeader {
+            base: base.map_or(0, |base| base.stream_id()),
+            target: snapshot.stream_id(),
+            total_blocks: snapshot.total_blocks,
+            epoch: snapshot.epoch,
+        };
+        writer.write_all(&header.encode())?;
+
+        let view = self.export_snapshot(snapshot.clone());
+        let mut buf = try_zeroed(STREAM_RUN_MAX_BLOCKS as usize * BLOCK_SIZE)?;
+        let mut sent = 0;
+        for (start, length) in runs {
+            let data = &mut buf[..length as usize * BLOCK_SIZE];
+            view.read_blocks(start, data).map_err(|e| match e {
+                SnapshotError::Device(e) => SadakFsError::Device(e),
+                SnapshotError::OutOfRange => SadakFsError::BlockOutOfRange(start),
+                SnapshotError::Syscall(e) => SadakFsError::Syscall(e),
+                SnapshotError::ReadOnly => SadakFsError::Syscall(SyscallError::EIO),
+            })?;
+            writer.write_all(&replication::encode_run(start, data))?;
+            writer.write_all(data)?;
+            sent += length;
+        }
+        writer.write_all(&replication::encode_run(0, &[]))?;
+        Ok(sent)
+    }
+
+    /// `SadakFs::send` ile üretilmiş bir akışı monte edilmemiş `device`'a uygular. Artımlı
+    /// akışlar yalnızca temel snapshot'ı (ve sonrasında montaj edilmemiş) taşıyan aygıta
+    /// uygulanır; aksi halde `StreamBaseMismatch`. Sonrasında aygıt gönderilen snapshot
+    /// olarak monte edilebilir.
+    ///
+    /// Superblock yuvaları en son yazılır: akış yarıda kesilirse aygıt hâlâ temel
+    /// snapshot'ı gösterir (ancak monte edilmemelidir) ve aynı akış yeniden uygulanabilir.
+    ///
+    /// # Döndürür
+    /// Alınan blok sayısı.
+    pub fn receive<R: ReceiveSource>(device: &D, reader: &mut R) -> Result<u64, SadakFsError<D>> {
+        // 1. Başlık ve hedef aygıtın uygunluğu
+        let mut raw = [0u8; STREAM_HEADER_SIZE];
+        reader.read_exact(&mut raw)?;
+        let header = StreamHeader::decode(&raw).ok_or(SadakFsError::Syscall(SyscallError::EINVAL))?;
+        if header.total_blocks > device.total_blocks() {
+            return Err(SadakFsError::DeviceTooSmall { expected: header.total_blocks, actual: device.total_blocks() });
+        }
+        if header.base != 0 {
+            let mut current = try_zeroed(BLOCK_SIZE)?;
+            device.read_block(0, &mut current)?;
+            if checksum::checksum_data(&current) != header.base {
+                return Err(SadakFsError::StreamBaseMismatch);
+            }
+        }
+
+        // 2. Aralık kayıtları; Superblock yuvaları bekletilir
+        let mut buf = try_zeroed(STREAM_RUN_MAX_BLOCKS as usize * BLOCK_SIZE)?;
+        let mut superblocks: Vec<(BlockId, Vec<u8>)> = Vec::new();
+        let mut received = 0;
+        loop {
+            let mut run = [0u8; STREAM_RUN_HEADER_SIZE];
+            reader.read_exact(&mut run)?;
+            let (start, length, crc) = replication::decode_run(&run);
+            if length == 0 {
+                break;
+            }
+            if length > STREAM_RUN_MAX_BLOCKS || start.checked_add(length).map_or(true, |end| end > header.total_blocks) {
+                return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+            }
+
+            let data = &mut buf[..length as usize * BLOCK_SIZE];
+            reader.read_exact(data)?;
+            if checksum::checksum_data(data) != crc {
+                return Err(SadakFsError::ChecksumError);
+            }
+            for (i, block) in data.chunks(BLOCK_SIZE).enumerate() {
+                let id = start + i as BlockId;
+                if id == 0 || id == SUPERBLOCK_B_BLOCK {
+                    let mut copy = try_zeroed(BLOCK_SIZE)?;
+                    copy.copy_from_slice(block);
+                    superblocks.push((id, copy));
+                } else {
+                    device.write_block(id, block)?;
+                }
+            }
+            received += length;
+        }
+
+        // 3. Veri kalıcı olduktan sonra Superblock yuvaları
+        let primary = superblocks.iter().find(|(id, _)| *id == 0);
+        if primary.map_or(true, |(_, data)| checksum::checksum_data(data) != header.target) {
+            return Err(SadakFsError::InvalidSuperblock);
+        }
+        device.flush()?;
+        for (id, data) in &superblocks {
+            device.write_block(*id, data)?;
+        }
+        device.flush()?;
+        Ok(received)
+    }
+
+    /// Montajda ertelenen derin metadata doğrulamasının hâlâ beklenip beklenmediği.
+    /// `true` ise arka plan scrub'ı tüm ağacı doğrulamalıdır.
+    pub fn deep_verify_pending(&self) -> bool {
+        self.lock.acquire();
+        let pending = unsafe { *self.deep_verify_pending.get() };
+        self.lock.release();
+        pending
+    }
+
+    /// Birimin kimliği (UUID).
+    pub fn uuid(&self) -> [u8; VOLUME_UUID_SIZE] {
+        self.sb().uuid
+    }
+
+    /// Birimin disk biçimi özellikleri.
+    pub fn features(&self) -> FeatureSet {
+        self.sb().features()
+    }
+
+    /// Birimin etiketi.
+    pub fn label(&self) -> String {
+        self.lock.acquire();
+        let label = String::from(self.sb().label());
+        self.lock.release();
+        label
+    }
+
+    /// Birimin etiketini değiştirir; değişiklik sonraki commit ile kalıcı olur.
+    pub fn set_label(&self, label: &str) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        if label.len() > VOLUME_LABEL_MAX || label.bytes().any(|b| b == 0) {
+            return Err(SadakFsError::InvalidName);
+        }
+
+        self.lock.acquire();
+        let sb = self.sb_mut();
+        sb.label = [0u8; VOLUME_LABEL_MAX];
+        sb.label[..label.len()].copy_from_slice(label.as_bytes());
+        self.lock.release();
+        Ok(())
+    }
+
+    /// Dosya sistemi okuma/yazmalarını görev başına `accounting` tablosuna yazar ve
+    /// tablodaki sınırları uygular. Aygıt katmanının sayımı için aynı tablo
+    /// `AccountingDevice` ile de kullanılabilir.
+    pub fn with_io_accounting(mut self, accounting: Arc<IoAccounting>) -> Self {
+        self.accounting = Some(accounting);
+        self
+    }
+
+    /// Çağıran görevin işlemini hesaba yazar; sınırı aşmışsa bekletir. Beklerken diğer
+    /// görevleri durdurmamak için `lock` alınmadan önce çağrılmalıdır.
+    fn charge_io(&self, kind: IoKind, bytes: usize) {
+        self.foreground_ops.fetch_add(1, Ordering::Relaxed);
+        if let Some(accounting) = &self.accounting {
+            accounting.charge_logical(kind, bytes as u64);
+        }
+    }
+
+    /// Son çağrıdan bu yana biriken yazma sayaçlarını döndürür ve sıfırlar.
+    pub fn take_write_stats(&self) -> WriteStats {
+        self.lock.acquire();
+        let stats = mem::take(self.stats_mut());
+        self.lock.release();
+        stats
+    }
+
+    /// Tespit edilen bozuklukların adli kaydı, en eskiden en yeniye. Günlük sınırlıdır
+    /// (`FORENSIC_LOG_CAPACITY`); aynı bloğun tekrar tekrar görünmesi ortam sorununa işaret eder.
+    pub fn corruption_log(&self) -> Vec<CorruptionRecord> {
+        self.forensics_lock.acquire();
+        let records = unsafe { &*self.forensics.get() }.records();
+        self.forensics_lock.release();
+        records
+    }
+
+    /// Birimin ömrü boyunca tespit edilen ve onarılan bozuk kopya sayıları.
+    pub fn read_repair_stats(&self) -> ReadRepairStats {
+        self.forensics_lock.acquire();
+        let stats = unsafe { &*self.forensics.get() }.stats();
+        self.forensics_lock.release();
+        stats
+    }
+
+    /// Metadata ağacı önbellekte sabitli mi? `MountOptions::pin_metadata` istendiği halde
+    /// ağaç sabitlenemeyecek kadar büyükse (veya sonradan büyüdüyse) `false`.
+    pub fn metadata_pinned(&self) -> bool {
+        self.pin_metadata.load(Ordering::Acquire)
+    }
+
+    /// Montaj sırasında periyodik tam denetimin süresinin dolduğu görüldüyse gerekçesi.
+    /// `Some` ise yönetici araçları kullanıcıya fsck çalıştırmasını önermelidir.
+    pub fn check_recommended(&self) -> Option<CheckReason> {
+        self.check_recommended
+    }
+
+    /// Periyodik denetim sınırlarını ayarlar (0 = ilgili sınır yok). Değerler
+    /// Superblock'a yazılır ve sonraki commit ile kalıcı olur.
+    pub fn set_check_limits(&self, max_mount_count: u32, check_interval: u64) {
+        self.lock.acquire();
+        let sb = self.sb_mut();
+        sb.max_mount_count = max_mount_count;
+        sb.check_interval = check_interval;
+        self.lock.release();
+    }
+
+    /// Alttaki blok aygıtı (ör. hotplug olaylarını bir `Raid1Device`'a iletmek için).
+    pub fn device(&self) -> &D {
+        &self.cache.device
+    }
+
+    /// Metadata B-Ağacına crate içi erişim (doğrulama ve test araçları için).
+    pub(crate) fn metadata_tree(&self) -> &BTree<D> {
+        &self.metadata_tree
+    }
+
+    /// Metadata ağacını dosya sistemi kilidi altında değiştirir (ör. `kv` deposu için).
+    /// Değişiklikler arada commit olmadan uygulanır; ardından bütçe aşıldıysa commit edilir.
+    pub(crate) fn update_metadata<R, F>(&self, f: F) -> Result<R, SadakFsError<D>>
+    where
+        F: FnOnce(&BTree<D>, &Allocator<D>) -> Result<R, SadakFsError<D>>,
+    {
+        self.lock.acquire();
+        let result = f(&self.metadata_tree, &self.allocator);
+        self.lock.release();
+        let value = result?;
+
+        self.commit_if_over_budget()?;
+        Ok(value)
+    }
+
+    // --- Yardımcı Fonksiyonlar ---
+
+    /// Bellekteki Superblock'a erişim.
+    fn sb(&self) -> &Superblock {
+        unsafe { &*self.superblock.get() }
+    }
+
+    /// Bellekteki Superblock'a değiştirilebilir erişim. Çağıran `lock`'u tutmalıdır.
+    fn sb_mut(&self) -> &mut Superblock {
+        unsafe { &mut *self.superblock.get() }
+    }
+
+    /// Yazma sayaçlarına değiştirilebilir erişim (çağıran `lock`'u tutmalıdır).
+    fn stats_mut(&self) -> &mut WriteStats {
+        unsafe { &mut *self.write_stats.get() }
+    }
+
+    /// Yeni bir inode için nesil numarası ayırır (çağıran `lock`'u tutmalıdır).
+    /// Sayaç Superblock'la birlikte commit edilir; yeniden başlatmalarda geri gitmez.
+    fn take_generation(&self) -> u64 {
+        let sb = self.sb_mut();
+        let generation = sb.next_generation;
+        sb.next_generation += 1;
+        generation
+    }
+
+    /// Yeni bir inode için inode numarası ayırır (çağıran `lock`'u tutmalıdır).
+    /// Numaralar yeniden kullanılmaz; sayaç Superblock'la birlikte commit edilir.
+    fn take_ino(&self) -> u64 {
+        let sb = self.sb_mut();
+        let ino = sb.next_ino;
+        sb.next_ino += 1;
+        ino
+    }
+
+    /// Commit gövdesi (çağıran `lock`'u tutmalıdır).
+    ///
+    /// Önce Superblock dışındaki tüm kirli CoW blokları yazılır ve kalıcı yapılır;
+    /// yeni kökleri gösteren Superblock en son yazılır. Böylece çökme anında disk
+    /// ya eski ya da yeni tutarlı durumu gösterir.
+    fn commit_locked(&self) -> Result<(), SadakFsError<D>> {
+        self.check_writable()?;
+        // Transaction açıkken commit ertelenir; değişiklikler `Transaction::commit` ile yazılır
+        if self.transaction_active.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        let result = self.write_commit_locked();
+        if let Err(e) = &result {
+            match self.errors {
+                ErrorsPolicy::Continue => {}
+                // Yarım kalan commit'in blokları yazılmaz; disk son tutarlı durumda kalır
+                ErrorsPolicy::RemountReadOnly => self.read_only.store(true, Ordering::Release),
+                #[cfg(not(feature = "no_panic"))]
+                ErrorsPolicy::Panic => panic!("SADAK: commit hatası: {:?}", e),
+                // Panik kapalı derlemede sistem durdurulmaz; birim salt okunura düşer
+                #[cfg(feature = "no_panic")]
+                ErrorsPolicy::Panic => self.read_only.store(true, Ordering::Release),
+            }
+        }
+        result
+    }
+
+    /// Metadata'nın kendi içinde tutarsız bulunduğunu (ör. birbirini göstermesi gereken iki
+    /// kaydın uyuşmaması) hata politikasına bildirir ve döndürülecek hatayı üretir.
+    /// Bu durumlar sistemi durdurmaz; `Continue` dışındaki politikalarda birim salt
+    /// okunur yapılarak bozukluğun yazmalarla yayılması önlenir.
+    fn invariant_violated(&self, block: BlockId) -> SadakFsError<D> {
+        if self.errors != ErrorsPolicy::Continue {
+            self.read_only.store(true, Ordering::Release);
+        }
+        SadakFsError::MetadataCorrupted(block)
+    }
+
+    /// `commit_locked` gövdesi.
+    fn write_commit_locked(&self) -> Result<(), SadakFsError<D>> {
+        if self.fencing {
+            self.check_fence_locked()?;
+        }
+
+        // 1. Superblock dışındaki kirli blokları yaz ve kalıcı yap; alan haritası
+        // kontrol noktası son tahsislerden sonra, bitmap ile aynı commit'e yazılır
+        self.flush_pages_locked()?;
+        self.save_quotas_locked()?;
+        self.save_forensic_log_locked()?;
+        #[cfg(feature = "snapshot")]
+        self.record_changes_locked()?;
+
+        // Yerinde yazılan bloklar (taze olmayan inode'lar, tahsis haritası) CoW ile
+        // korunmaz; önce günlüğe, Superblock'tan sonra yerlerine yazılır. Günlük hepsini
+        // alacak kadar büyütülür; alan haritası kaydı ve büyütmenin kendi tahsisleri de
+        // tahsis haritasına dokunduğundan bloklar sığana kadar yeniden sayılır.
+        let (space_map_root, in_place) = loop {
+            self.ensure_journal_locked(self.in_place_dirty_locked().len())?;
+            let space_map_root = self.allocator.save_space_map(self.sb().journal_start != 0)?;
+            let in_place = self.in_place_dirty_locked();
+            if self.sb().journal_start == 0 || in_place.len() <= self.sb().journal_capacity() {
+                break (space_map_root, in_place);
+            }
+        };
+        let timestamp = self.get_system_time()?;
+        let seq = self.sb().journal_seq.wrapping_add(1);
+        #[cfg(feature = "snapshot")]
+        self.preserve_for_snapshots_locked(&in_place)?;
+        let journaled = self.sb().journal_start != 0 && !in_place.is_empty();
+        let mut exclude = alloc::vec![0];
+        if journaled {
+            exclude.extend_from_slice(&in_place);
+        }
+        self.stats_mut().cached_blocks += self.cache.write_back_dirty(&exclude)? as u64;
+        if journaled {
+            self.write_journal_locked(&in_place, seq, timestamp)?;
+        }
+        self.cache.device.flush()?;
+
+        // 2. Superblock'u yeni kök işaretçileri ve zaman damgasıyla güncelle
+        let sb = self.sb_mut();
+        sb.metadata_root_id = self.metadata_tree.root_id();
+        sb.space_map_root = space_map_root;
+        sb.timestamp = timestamp;
+        sb.journal_seq = seq;
+
+        // 3. Superblock'u Block 0'a yazar; commit bu noktada tamamlanmış sayılır
+        self.write_superblock()?;
+        self.cache.device.flush()?;
+
+        // 4. Günlüğe alınan blokları yerlerine yaz. Burada yarıda kalan yazmalar montajda
+        // günlükten tamamlanır; uygulandı işareti kaybolursa yeniden uygulama zararsızdır.
+        if journaled {
+            self.stats_mut().cached_blocks += self.cache.write_back_dirty(&[0])? as u64;
+            self.cache.device.flush()?;
+            let mut header = try_zeroed(BLOCK_SIZE)?;
+            JournalHeader::applied(seq, timestamp).encode(&mut header);
+            let segments = in_place.len().div_ceil(JOURNAL_CAPACITY) as u64;
+            for segment in 0..segments {
+                self.cache.device.write_block(self.sb().journal_start + segment * JOURNAL_BLOCKS, &header)?;
+            }
+            self.stats_mut().journal_blocks += segments;
+        }
+
+        // 5. Eski CoW blokları artık hiçbir tutarlı duruma ait değil; serbest bırak
+        self.allocator.end_transaction()?;
+        self.stats_mut().commits += 1;
+
+        // 6. Sabitlenmiş küme ağacın yeni düğümleriyle değiştirilir; bırakılan düğümler
+        // tahliye edilebilir olur. Sabitleme yalnızca hızlandırmadır; hatası commit'i
+        // geçersiz kılmaz, sabitlemeyi kapatır.
+        if self.pin_metadata.load(Ordering::Acquire) && self.pin_metadata_locked().is_err() {
+            self.cache.pin(&[]).ok();
+            self.pin_metadata.store(false, Ordering::Release);
+        }
+        Ok(())
+    }
+
+    /// Metadata ağacının düğümlerini önbellekte sabitler; ağaç önbellek kapasitesinin
+    /// yarısını aşıyorsa sabitlemeyi kaldırır (çağıran `lock`'u tutmalıdır).
+    fn pin_metadata_locked(&self) -> Result<(), SadakFsError<D>> {
+        let nodes = self.metadata_tree.node_ids()?;
+        if nodes.len() > self.cache.capacity() / 2 {
+            self.cache.pin(&[])?;
+            self.pin_metadata.store(false, Ordering::Release);
+            return Ok(());
+        }
+        self.cache.pin(&nodes)?;
+        Ok(())
+    }
+
+    /// Yerinde yazılacak kirli bloklar: Superblock ve bu transaction'da tahsis edilenler
+    /// (CoW kopyaları) dışındakiler (çağıran `lock`'u tutmalıdır).
+    fn in_place_dirty_locked(&self) -> Vec<BlockId> {
+        self.cache
+            .dirty_blocks()
+            .into_iter()
+            .filter(|&id| id != 0 && !self.allocator.is_fresh(id))
+            .collect()
+    }
+
+    /// Niyet günlüğü bölgesini en az `needed` blok görüntüsü alacak şekilde ayırır veya
+    /// büyütür (çağıran `lock`'u tutmalıdır). Bölge bitişik olmalıdır; kesim sayısı ikinin
+    /// kuvvetine yuvarlanır, eski bölge bu commit'le birlikte bırakılır.
+    ///
+    /// Hiç bölge yokken yer bulunamazsa commit'ler günlüksüz sürer ve sonraki commit'te
+    /// yeniden denenir (küçük birimler). Var olan günlük büyütülemezse yerinde yazmalar
+    /// korumasız kalmasın diye commit `OutOfSpace` ile reddedilir.
+    fn ensure_journal_locked(&self, needed: usize) -> Result<(), SadakFsError<D>> {
+        let old_start = self.sb().journal_start;
+        if old_start != 0 && needed <= self.sb().journal_capacity() {
+            return Ok(());
+        }
+        let segments = needed.div_ceil(JOURNAL_CAPACITY).max(1).next_power_of_two();
+        let segments = u16::try_from(segments).map_err(|_| SadakFsError::Allocator(AllocatorError::OutOfSpace))?;
+        let blocks = JOURNAL_BLOCKS * segments as u64;
+        let (start, len) = match self.allocator.allocate_extent(blocks) {
+            Ok(extent) => extent,
+            Err(AllocatorError::OutOfSpace) if old_start == 0 => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        if len < blocks {
+            for id in start..start + len {
+                self.allocator.release_block(id)?;
+            }
+            if old_start == 0 {
+                return Ok(());
+            }
+            return Err(SadakFsError::Allocator(AllocatorError::OutOfSpace));
+        }
+        if old_start != 0 {
+            // Eski bölgeyi gösteren Superblock diskte kaldıkça bloklar bu commit'in sonuna
+            // kadar ayrılmış kalır
+            for id in old_start..old_start + self.sb().journal_blocks() {
+                self.allocator.release_block(id)?;
+            }
+        }
+        // Bölge önbelleğe alınmaz; eski kopyalar (ör. bırakılmış metadata) atılır
+        self.cache.discard_range(start, len);
+        let sb = self.sb_mut();
+        sb.journal_start = start;
+        sb.journal_segments = segments;
+        sb.feature_compat |= FEATURE_COMPAT_JOURNAL;
+        if segments > 1 {
+            sb.feature_incompat |= FEATURE_INCOMPAT_JOURNAL_SEGMENTS;
+        }
+        Ok(())
+    }
+
+    /// `in_place` bloklarının önbellekteki görüntülerini ve başlıkları günlük bölgesine
+    /// doğrudan yazar (çağıran `lock`'u tutmalıdır). Bloklar sırayla `JOURNAL_CAPACITY`'lik
+    /// kesimlere bölünür. Kalıcılık için çağıran `flush` yapar.
+    fn write_journal_locked(&self, in_place: &[BlockId], seq: u64, timestamp: u64) -> Result<(), SadakFsError<D>> {
+        let mut block = try_zeroed(BLOCK_SIZE)?;
+        for (segment, ids) in in_place.chunks(JOURNAL_CAPACITY).enumerate() {
+            let start = self.sb().journal_start + segment as u64 * JOURNAL_BLOCKS;
+            let mut header = JournalHeader { seq, timestamp, blocks: Vec::with_capacity(ids.len()) };
+            for (i, &id) in ids.iter().enumerate() {
+                let block_arc = self.cache.get_block(id)?;
+                let data = unsafe { &*block_arc.get() }.data.as_ref();
+                header.push(id, data);
+                self.cache.device.write_block(start + 1 + i as u64, data)?;
+            }
+            header.encode(&mut block);
+            self.cache.device.write_block(start, &block)?;
+            self.stats_mut().journal_blocks += ids.len() as u64 + 1;
+        }
+        Ok(())
+    }
+
+    /// Sayfa önbelleğindeki dosya verisini diske yazar (çağıran `lock`'u tutmalıdır).
+    ///
+    /// Ardışık kirli sayfalar için ardışık extent'ler tahsis edilir, veri doğrudan aygıta
+    /// yazılır; ardından dosyanın veri ağacı ve inode'u güncellenir.
+    fn flush_pages_locked(&self) -> Result<(), SadakFsError<D>> {
+        for inode_id in self.pages.dirty_inodes() {
+            self.flush_inode_pages_locked(inode_id)?;
+        }
+        Ok(())
+    }
+
+    /// Tek bir dosyanın bekleyen sayfalarını diske yazar (çağıran `lock`'u tutmalıdır).
+    fn flush_inode_pages_locked(&self, inode_id: BlockId) -> Result<(), SadakFsError<D>> {
+        let (mut pages, size) = self.pages.take_inode(inode_id);
+        // Ayrılan bloklar aşağıda gerçek tahsisle kullanıma yazılır
+        self.allocator.quota().release_pending(inode_id);
+        if pages.is_empty() && size.is_none() {
+            return Ok(());
+        }
+
+        let mut inode = self.read_inode(inode_id)?;
+        // Sayfalar yalnızca anahtar yüklüyken oluşur ve anahtar çıkarılmadan önce geri yazılır
+        if let Some(cipher) = self.file_cipher(&inode)? {
+            for (file_block, page) in pages.iter_mut() {
+                cipher.encrypt(*file_block, page.as_mut());
+            }
+        }
+        let block_count = inode.block_count;
+        let data_tree = self.data_tree(inode.data_tree_root)?;
+        let pages = if self.is_nocow(inode.subvolume_id)? {
+            self.write_pages_in_place_locked(&data_tree, pages)?
+        } else {
+            pages
+        };
+        let compression = self.effective_compression(&inode)?;
+
+        // Ardışık dosya bloklarından oluşan dizileri (run) ayrı ayrı yaz
+        let mut run_start = 0;
+        while run_start < pages.len() {
+            let mut run_end = run_start + 1;
+            while run_end < pages.len() && pages[run_end].0 == pages[run_end - 1].0 + 1 {
+                run_end += 1;
+            }
+            self.write_run_locked(&mut inode, &data_tree, &pages[run_start..run_end], compression)?;
+            run_start = run_end;
+        }
+
+        if let Some(size) = size {
+            inode.file_size = inode.file_size.max(size);
+        }
+        // Bloklar `write_buffered`'da kotadan ayrıldı; geri yazma (commit) burada başarısız olmamalı
+        self.account_quota(&inode, inode.block_count as i64 - block_count as i64, 0);
+        self.store_data_tree(&mut inode, &data_tree)?;
+        inode.modification_time = self.get_system_time()?;
+        self.write_inode_locked(&mut inode)
+    }
+
+    /// nocow alt birimindeki bir dosyanın eşlenmiş ve paylaşılmayan bloklarına düşen
+    /// sayfaları doğrudan yerlerine yazar; CoW ile yazılması gereken sayfaları sırasıyla döndürür.
+    fn write_pages_in_place_locked(
+        &self,
+        data_tree: &BTree<D>,
+        pages: Vec<(u64, Box<[u8; BLOCK_SIZE]>)>,
+    ) -> Result<Vec<(u64, Box<[u8; BLOCK_SIZE]>)>, SadakFsError<D>> {
+        let mut remaining = Vec::new();
+        for (file_block, page) in pages {
+            let physical = match self.mapped_block(data_tree, file_block)? {
+                Some(physical) if self.in_place_allowed() && !self.is_shared_block(physical)? => physical,
+                _ => {
+                    remaining.push((file_block, page));
+                    continue;
+                }
+            };
+            match self.data_mode {
+                DataMode::Ordered => {
+                    #[cfg(feature = "snapshot")]
+                    self.preserve_for_snapshots_locked(&[physical])?;
+                    self.cache.discard_range(physical, 1);
+                    self.cache.device.write_block(physical, page.as_ref())?;
+                    self.stats_mut().data_blocks += 1;
+                }
+                // Blok kirli bırakılır; commit onu önce günlüğe, sonra yerine yazar
+                DataMode::Journal => {
+                    let block_arc = self.cache.get_block(physical)?;
+                    let block = unsafe { &mut *block_arc.get() };
+                    block.data.copy_from_slice(page.as_ref());
+                    block.is_dirty = true;
+                }
+            }
+            #[cfg(feature = "snapshot")]
+            if !self.allocator.is_fresh(physical) {
+                unsafe { &mut *self.changed_in_place.get() }.insert(physical);
+            }
+        }
+        Ok(remaining)
+    }
+
+    /// Veri bloğu yerinde güncellenebilir mi? `data=journal` kipinde yalnızca niyet
+    /// günlüğünde yer varken; kirli blok sayısı yerinde yazılacak blokların üst sınırıdır
+    /// ve yarısı metadata (inode'lar, tahsis haritası) için bırakılır.
+    fn in_place_allowed(&self) -> bool {
+        match self.data_mode {
+            DataMode::Ordered => true,
+            DataMode::Journal => self.sb().journal_start != 0 && self.cache.dirty_count() < JOURNAL_CAPACITY / 2,
+        }
+    }
+
+    /// Ardışık dosya bloklarına ait sayfaları yeni tahsis edilen extent'lere yazar.
+    /// Aralıktaki eski extent'ler önce çıkarılır (CoW: eski veri yerinde ezilmez).
+    /// `compression` verilmişse sayfalar kümeler halinde o algoritmayla sıkıştırılır.
+    fn write_run_locked(
+        &self,
+        inode: &mut Inode,
+        data_tree: &BTree<D>,
+        run: &[(u64, Box<[u8; BLOCK_SIZE]>)],
+        compression: Option<Compression>,
+    ) -> Result<(), SadakFsError<D>> {
+        let first = run[0].0;
+        let count = run.len() as u64;
+        let punched = self.punch_extents_locked(data_tree, first, first + count)?;
+        inode.block_count = inode.block_count.saturating_sub(punched);
+
+        let compression = match compression {
+            Some(compression) => compression,
+            None => return self.write_raw_locked(inode, data_tree, run, 0),
+        };
+        // Sıkıştırmaya değmeyen kümeler olduğu gibi yazılır; karar extent'te işaretlenir
+        for cluster in run.chunks(COMPRESS_CLUSTER_BLOCKS) {
+            if !self.write_compressed_locked(inode, data_tree, cluster, compression)? {
+                self.write_raw_locked(inode, data_tree, cluster, EXTENT_FLAG_INCOMPRESSIBLE)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Ardışık dosya bloklarına ait sayfaları `flags` bayraklı extent'lere olduğu gibi yazar.
+    fn write_raw_locked(
+        &self,
+        inode: &mut Inode,
+        data_tree: &BTree<D>,
+        run: &[(u64, Box<[u8; BLOCK_SIZE]>)],
+        flags: u32,
+    ) -> Result<(), SadakFsError<D>> {
+        let first = run[0].0;
+        let count = run.len() as u64;
+        let mut done = 0;
+        while done < count {
+            let hint = PlacementHint::from_raw(inode.placement_hint);
+            let (physical, len) = self.allocator.allocate_data_extent(count - done, hint)?;
+
+            // Aynı bloklara ait eski önbellek kopyaları (ör. serbest bırakılmış metadata) atılır.
+            self.cache.discard_range(physical, len);
+            for i in 0..len {
+                let (_, page) = &run[(done + i) as usize];
+                self.cache.device.write_block(physical + i, page.as_ref())?;
+            }
+            self.stats_mut().data_blocks += len;
+
+            let extent = Extent { file_block: first + done, physical_start: physical, length: len as u32, flags, compressed_len: 0, skip_blocks: 0 };
+            data_tree.insert_entry(&self.allocator, &Extent::key(extent.file_block), &extent.encode())?;
+            inode.block_count += len;
+            done += len;
+        }
+        Ok(())
+    }
+
+    /// Ardışık dosya bloklarından oluşan bir kümeyi sıkıştırıp tek extent olarak yazar.
+    /// Örnek sıkıştırılamaz görünüyorsa, sıkıştırma en az bir blok kazandırmıyorsa veya
+    /// sıkıştırılmış veriye ardışık yer bulunamazsa hiçbir şey yazılmaz. `block_count`
+    /// kümenin sıkıştırılmamış blok sayısı kadar artar.
+    ///
+    /// # Döndürür
+    /// Küme yazıldı mı?
+    fn write_compressed_locked(
+        &self,
+        inode: &mut Inode,
+        data_tree: &BTree<D>,
+        cluster: &[(u64, Box<[u8; BLOCK_SIZE]>)],
+        compression: Compression,
+    ) -> Result<bool, SadakFsError<D>> {
+        let blocks = cluster.len();
+        if blocks < 2 {
+            return Ok(false);
+        }
+        let mut plain = try_zeroed(blocks * BLOCK_SIZE)?;
+        for (chunk, (_, page)) in plain.chunks_mut(BLOCK_SIZE).zip(cluster) {
+            chunk.copy_from_slice(page.as_ref());
+        }
+        if !compress::should_compress(&plain) {
+            return Ok(false);
+        }
+        let mut packed = try_zeroed((blocks - 1) * BLOCK_SIZE)?;
+        let compressed_len = match compression.compress(&plain, &mut packed) {
+            Some(len) => len,
+            None => return Ok(false),
+        };
+        let stored = ((compressed_len + BLOCK_SIZE - 1) / BLOCK_SIZE) as u64;
+
+        let hint = PlacementHint::from_raw(inode.placement_hint);
+        let (physical, len) = self.allocator.allocate_data_extent(stored, hint)?;
+        if len < stored {
+            for id in physical..physical + len {
+                self.allocator.release_block(id)?;
+            }
+            return Ok(false);
+        }
+        self.cache.discard_range(physical, len);
+        for (i, chunk) in packed[..stored as usize * BLOCK_SIZE].chunks(BLOCK_SIZE).enumerate() {
+            self.cache.device.write_block(physical + i as BlockId, chunk)?;
+        }
+        self.stats_mut().data_blocks += len;
+
+        let extent = Extent {
+            file_block: cluster[0].0,
+            physical_start: physical,
+            length: blocks as u32,
+            flags: match compression {
+                Compression::Lz4 => EXTENT_FLAG_COMPRESSED,
+                Compression::Zstd(_) => EXTENT_FLAG_COMPRESSED | EXTENT_FLAG_ZSTD,
+            },
+            compressed_len: compressed_len as u32,
+            skip_blocks: 0,
+        };
+        data_tree.insert_entry(&self.allocator, &Extent::key(extent.file_block), &extent.encode())?;
+        inode.block_count += blocks as u64;
+        Ok(true)
+    }
+
+    /// `[from, to)` dosya bloklarıyla çakışan extent'leri veri ağacından çıkarır; çakışmayan
+    /// baş ve son kısımlar yeniden eklenir, çakışan fiziksel bloklar bırakılır.
+    /// Sıkıştırılmış bir küme bölünemez: kalan parçalar aynı blokları gösterir (iki parça
+    /// kalırsa bloklar paylaşılır) ve bloklar yalnızca kümeden hiçbir şey kalmayınca bırakılır.
+    ///
+    /// # Döndürür
+    /// Bırakılan blok sayısı.
+    fn punch_extents_locked(&self, data_tree: &BTree<D>, from: u64, to: u64) -> Result<u64, SadakFsError<D>> {
+        let mut punched = 0;
+        loop {
+            // Aralığın sonundan geriye doğru çakışan son extent
+            let found = data_tree
+                .lookup_floor(&Extent::key(to - 1))?
+                .and_then(|(key, value)| Extent::from_item(&key, &value));
+            let extent = match found {
+                Some(extent) if extent.end() > from => extent,
+                _ => break,
+            };
+
+            data_tree.remove_entry(&self.allocator, &Extent::key(extent.file_block))?;
+            if extent.file_block < from {
+                let head = Extent { length: (from - extent.file_block) as u32, ..extent };
+                data_tree.insert_entry(&self.allocator, &Extent::key(head.file_block), &head.encode())?;
+            }
+            if extent.end() > to {
+                let tail = if extent.is_compressed() {
+                    let skipped = (to - extent.file_block) as u32;
+                    Extent { file_block: to, length: (extent.end() - to) as u32, skip_blocks: extent.skip_blocks + skipped, ..extent }
+                } else {
+                    Extent { file_block: to, physical_start: extent.physical_for(to), length: (extent.end() - to) as u32, ..extent }
+                };
+                data_tree.insert_entry(&self.allocator, &Extent::key(tail.file_block), &tail.encode())?;
+            }
+
+            let overlap_start = extent.file_block.max(from);
+            let overlap_end = extent.end().min(to);
+            if extent.is_compressed() {
+                match (extent.file_block < from, extent.end() > to) {
+                    (false, false) => {
+                        for physical in extent.physical_blocks() {
+                            self.release_data_block_locked(physical)?;
+                        }
+                    }
+                    (true, true) => {
+                        for physical in extent.physical_blocks() {
+                            self.share_data_block_locked(physical)?;
+                        }
+                    }
+                    _ => {}
+                }
+            } else {
+                for file_block in overlap_start..overlap_end {
+                    self.release_data_block_locked(extent.physical_for(file_block))?;
+                }
+            }
+            punched += overlap_end - overlap_start;
+        }
+        Ok(punched)
+    }
+
+    /// Veri bloğuna bir başvuru daha ekler (klonlama).
+    fn share_data_block_locked(&self, physical: BlockId) -> Result<(), SadakFsError<D>> {
+        let extra_refs = self.shared_block(physical)?.map_or(0, |shared| shared.extra_refs);
+        let shared = SharedBlock { physical, extra_refs: extra_refs + 1 };
+        self.metadata_tree.insert_entry(&self.allocator, &SharedBlock::key(physical), &shared.encode())?;
+        Ok(())
+    }
+
+    /// Bir dosyanın veri bloğuna başvurusunu bırakır: blok paylaşılıyorsa yalnızca sayaç
+    /// düşer, değilse blok serbest bırakılır.
+    fn release_data_block_locked(&self, physical: BlockId) -> Result<(), SadakFsError<D>> {
+        let key = SharedBlock::key(physical);
+        match self.shared_block(physical)? {
+            Some(shared) if shared.extra_refs > 1 => {
+                let shared = SharedBlock { extra_refs: shared.extra_refs - 1, ..shared };
+                self.metadata_tree.insert_entry(&self.allocator, &key, &shared.encode())?;
+            }
+            Some(_) => {
+                self.metadata_tree.remove_entry(&self.allocator, &key)?;
+            }
+            None => self.allocator.release_block(physical)?,
+        }
+        Ok(())
+    }
+
+    /// Veri bloğu birden fazla dosya tarafından paylaşılıyor mu?
+    fn is_shared_block(&self, physical: BlockId) -> Result<bool, SadakFsError<D>> {
+        Ok(self.shared_block(physical)?.is_some())
+    }
+
+    fn shared_block(&self, physical: BlockId) -> Result<Option<SharedBlock>, SadakFsError<D>> {
+        let key = SharedBlock::key(physical);
+        Ok(self.metadata_tree.lookup(&key)?.and_then(|value| SharedBlock::from_item(&key, &value)))
+    }
+
+    /// Bir dosya bloğunun mevcut içeriğini `out`'a okur; delikler sıfır okunur.
+    /// Paketlenmiş kuyruk bloğu parçasından, kalanı sıfırla doldurularak okunur. Şifreli
+    /// dosyaların eşlenmiş blokları `cipher` ile çözülür.
+    fn load_file_block(&self, data_tree: &BTree<D>, file_block: u64, cipher: Option<&FileCipher>, out: &mut [u8]) -> Result<(), SadakFsError<D>> {
+        match self.extent_at(data_tree, file_block)? {
+            Some(extent) if extent.is_compressed() => {
+                let mut cluster = try_zeroed(COMPRESS_CLUSTER_BYTES)?;
+                let range = read_compressed_in(&self.cache, &extent, &mut cluster)?;
+                let at = range.start + (file_block - extent.file_block) as usize * BLOCK_SIZE;
+                let block = cluster.get(at..at + BLOCK_SIZE).ok_or(SadakFsError::DataCorrupted(extent.physical_start))?;
+                out.copy_from_slice(block);
+            }
+            Some(extent) => {
+                let block_arc = self.cache.get_block(extent.physical_for(file_block))?;
+                out.copy_from_slice(unsafe { &(*block_arc.get()).data[..] });
+                if let Some(cipher) = cipher {
+                    cipher.decrypt(file_block, out);
+                }
+            }
+            None => {
+                out.fill(0);
+                if let Some(fragment) = tail_fragment_in(data_tree, file_block)? {
+                    let block_arc = self.cache.get_block(fragment.tail_block)?;
+                    out[..fragment.len as usize].copy_from_slice(unsafe { &(*block_arc.get()).data[fragment.range()] });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Bir dosya bloğunun eşlendiği disk bloğu; blok bir delikteyse `None`.
+    fn mapped_block(&self, data_tree: &BTree<D>, file_block: u64) -> Result<Option<BlockId>, SadakFsError<D>> {
+        mapped_block_in(data_tree, file_block)
+    }
+
+    /// Bir dosya bloğunu kapsayan extent; blok bir delikteyse `None`.
+    fn extent_at(&self, data_tree: &BTree<D>, file_block: u64) -> Result<Option<Extent>, SadakFsError<D>> {
+        extent_at_in(data_tree, file_block)
+    }
+
+    /// Inode'u checksum'uyla birlikte kendi bloğuna yazar ve bloğu kirli işaretler.
+    fn write_inode_locked(&self, inode: &mut Inode) -> Result<(), SadakFsError<D>> {
+        let inode_arc = self.cache.get_block(inode.inode_id)?;
+        let inode_block_mut = unsafe { &mut *inode_arc.get() };
+
+        // Değişiklik sayacı bloktaki son yazılan değerden ilerletilir; aynı işlemde eski
+        // bir kopya yazılsa da sayaç geri gitmez
+        let stored_id = inode_field_u64(inode_block_mut.data.as_ref(), mem::offset_of!(Inode, inode_id));
+        let stored_seq = inode_field_u64(inode_block_mut.data.as_ref(), mem::offset_of!(Inode, change_seq));
+        let base = if stored_id == inode.inode_id { stored_seq.max(inode.change_seq) } else { inode.change_seq };
+        inode.change_seq = base.wrapping_add(1);
+
+        inode.checksum = 0;
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                inode as *const Inode as *const u8,
+                inode_block_mut.data.as_mut_ptr(),
+                mem::size_of::<Inode>(),
+            );
+        }
+        inode.checksum = inode_checksum(self.sb().checksum_policy().inode, inode_block_mut.data.as_ref());
+
+        let offset = mem::offset_of!(Inode, checksum);
+        inode_block_mut.data[offset..offset + mem::size_of::<u32>()].copy_from_slice(&inode.checksum.to_ne_bytes());
+
+        // Bloğu kirli olarak işaretle (commit sırasında yazılır)
+        inode_block_mut.is_dirty = true;
+
+        // Inode'lar yerinde yazılır; taze değilse değişen blok kayıtlarına eklenmeli
+        #[cfg(feature = "snapshot")]
+        if !self.allocator.is_fresh(inode.inode_id) {
+            unsafe { &mut *self.changed_in_place.get() }.insert(inode.inode_id);
+        }
+        Ok(())
+    }
+
+    /// Bellekteki Superblock'u checksum'uyla birlikte geçerli commit'in yuvasına yazar
+    /// (bkz. `superblock_slot`). Önbellekteki Blok 0 kopyası her zaman son yazılan haldir.
+    fn write_superblock(&self) -> Result<(), SadakFsError<D>> {
+        let sb_block_arc = self.cache.get_block(0)?;
+        let sb_block_mut = unsafe { &mut *sb_block_arc.get() };
+        self.encode_superblock(sb_block_mut.data.as_mut());
+
+        self.cache.device.write_block(self.superblock_slot(), sb_block_mut.data.as_ref())?;
+        sb_block_mut.is_dirty = false;
+        self.stats_mut().superblock_writes += 1;
+        Ok(())
+    }
+
+    /// Bellekteki Superblock'u checksum'uyla birlikte bir blok boyutundaki `out`'a yazar
+    /// (çağıran `lock`'u tutmalıdır).
+    fn encode_superblock(&self, out: &mut [u8]) {
+        let sb = self.sb_mut();
+        sb.checksum = 0;
+        unsafe {
+            core::ptr::copy_nonoverlapping(sb as *const Superblock as *const u8, out.as_mut_ptr(), BLOCK_SIZE);
+        }
+        sb.checksum = superblock_checksum(&out[..BLOCK_SIZE]);
+
+        let offset = mem::offset_of!(Superblock, checksum);
+        out[offset..offset + mem::size_of::<u32>()].copy_from_slice(&sb.checksum.to_ne_bytes());
+    }
+
+    /// Diskteki en yeni Superblock'u önbelleği atlayarak okur (paylaşılan depolamada başka
+    /// bir düğümün yazdığı hal). Çağıran `lock`'u tutmalıdır.
+    fn read_disk_superblock(&self) -> Result<Option<Superblock>, SadakFsError<D>> {
+        let mut slot_a = try_zeroed(BLOCK_SIZE)?;
+        let mut slot_b = try_zeroed(BLOCK_SIZE)?;
+        self.cache.device.read_block(0, &mut slot_a)?;
+        if self.sb().feature_compat & FEATURE_COMPAT_SUPERBLOCK_SLOTS == 0
+            || self.cache.device.read_block(SUPERBLOCK_B_BLOCK, &mut slot_b).is_err()
+        {
+            slot_b.fill(0);
+        }
+        Ok(Superblock::from_slots(&slot_a, &slot_b).map(|(superblock, _)| superblock))
+    }
+
+    /// Diskteki çit dönemi bu düğümünkünden yeniyse birimi salt okunur yapar ve `Fenced`
+    /// döner (çağıran `lock`'u tutmalıdır).
+    fn check_fence_locked(&self) -> Result<(), SadakFsError<D>> {
+        match self.read_disk_superblock()? {
+            Some(disk) if disk.uuid == self.sb().uuid && disk.fence_epoch > self.sb().fence_epoch => {
+                self.read_only.store(true, Ordering::Release);
+                Err(SadakFsError::Fenced)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Sıradaki Superblock yazmasının yuvası: yuvalar açıksa commit sırası tekse B, çiftse
+    /// Blok 0. Yarıda kalan yazma yalnızca bir yuvayı bozar; diğeri önceki commit'tir.
+    fn superblock_slot(&self) -> BlockId {
+        let sb = self.sb();
+        if sb.feature_compat & FEATURE_COMPAT_SUPERBLOCK_SLOTS != 0 && sb.journal_seq % 2 == 1 {
+            SUPERBLOCK_B_BLOCK
+        } else {
+            0
+        }
+    }
+
+    /// Montaj öncesi kritik metadata denetimi: Superblock işaretçileri, tahsis
+    /// haritası blokları ve checksum'ları, alan haritası kontrol noktası ve metadata
+    /// ağacının ilk seviyeleri (Deep ise tamamı).
+    fn audit_metadata(
+        cache: &BlockCache<D>,
+        superblock: &Superblock,
+        allocator: &Allocator<D>,
+        metadata_tree: &BTree<D>,
+        verify: MountVerify,
+    ) -> Result<(), SadakFsError<D>> {
+        // 1. Superblock'taki işaretçiler aygıt sınırları içinde olmalı
+        let bitmap_end = superblock.bitmap_start_id + allocator.bitmap_block_count();
+        if superblock.metadata_root_id == 0
+            || superblock.metadata_root_id >= superblock.total_blocks
+            || bitmap_end > superblock.total_blocks
+        {
+            return Err(SadakFsError::InvalidSuperblock);
+        }
+
+        // 2. Tahsis haritası blokları okunabilir olmalı; alan haritası kontrol noktasının
+        // ve kaydettiği bitmap checksum'larının doğrulaması
+        for id in superblock.bitmap_start_id..bitmap_end {
+            cache.get_block(id)?;
+        }
+        if superblock.space_map_root != 0 {
+            if let Some(bad) = allocator.find_corrupt_space_map(superblock.space_map_root)? {
+                return Err(SadakFsError::MetadataCorrupted(bad));
+            }
+        }
+
+        // 3. Metadata kökü ve kök dizin tahsis haritasında dolu olarak işaretli olmalı
+        for id in [superblock.metadata_root_id, superblock.root_dir_inode] {
+            if id == 0 || id >= superblock.total_blocks || !allocator.is_allocated(id)? {
+                return Err(SadakFsError::MetadataCorrupted(id));
+            }
+        }
+
+        // 4. Ağaç düğümlerinin checksum doğrulaması
+        let levels = match verify {
+            MountVerify::Fast => MOUNT_AUDIT_TREE_LEVELS,
+            MountVerify::Deep => u8::MAX,
+        };
+        if let Some(bad) = metadata_tree.find_corrupt_node(levels)? {
+            return Err(SadakFsError::MetadataCorrupted(bad));
+        }
+
+        Ok(())
+    }
+
+    /// Dosyanın, henüz geri yazılmamış sayfalar dahil görünen boyutu.
+    fn visible_size(&self, current: &Inode) -> u64 {
+        current.file_size.max(self.pages.buffered_size(current.inode_id).unwrap_or(0))
+    }
+
+    /// Yalnızca eklenebilir günlüklerin içeriğini değiştiren yazmaları reddeder.
+    fn check_rewritable(&self, inode: &Inode) -> Result<(), SadakFsError<D>> {
+        if inode.file_type == FILE_TYPE_LOG {
+            Err(SadakFsError::AppendOnly)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Salt okunur montajda yazma yollarını reddeder.
+    fn check_writable(&self) -> Result<(), SadakFsError<D>> {
+        if self.read_only.load(Ordering::Acquire) {
+            Err(SadakFsError::ReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Zaman kaynağından geçerli zamanı alır.
+    fn get_system_time(&self) -> Result<u64, SadakFsError<D>> {
+        Ok(self.clock.now()?)
+    }
+}
+
+
+// --- 3.1. Açık Dosya Tanıtıcıları ---
+
+/// Bir dosyanın hangi amaçla açıldığı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenMode {
+    /// Yalnızca okuma; `write` `PermissionDenied` döner.
+    ReadOnly,
+    /// Okuma ve konumdan yazma.
+    ReadWrite,
+    /// Okuma ve her yazmada dosya sonuna ekleme (konumdan bağımsız).
+    Append,
+}
+
+impl OpenMode {
+    /// Bu kipte açmak için `open`'a verilecek bayraklar.
+    pub fn flags(&self) -> OpenFlags {
+        match self {
+            OpenMode::ReadOnly => OpenFlags::READ,
+            OpenMode::ReadWrite | OpenMode::Append => OpenFlags::READ | OpenFlags::WRITE,
+        }
+    }
+}
+
+/// `FileHandle::seek` için konum başlangıcı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekFrom {
+    /// Dosyanın başından.
+    Start(u64),
+    /// Geçerli konumdan.
+    Current(i64),
+    /// Dosyanın (görünen) sonundan.
+    End(i64),
+}
+
+/// Tek bir işlemin geri alma kaydı (bkz. `SadakFs::atomic_locked`): işlem başındaki
+/// bellek içi durum. Blok değişiklikleri önbelleğin, tahsis ve bırakmalar tahsis
+/// yöneticisinin kaydında tutulur.
+struct OpRecord {
+    superblock: Box<Superblock>,
+    /// Metadata ağacının canlı kökü (Superblock'takinden yeni olabilir).
+    metadata_root: BlockId,
+    quotas: Vec<(QuotaId, QuotaUsage)>,
+    open_tail_block: u64,
+}
+
+/// Açık bir transaction (bkz. `SadakFs::begin_transaction`). Dosya sistemine `Deref`
+/// ile erişilir; tanıtıcı açıkken yapılan işlemler `commit` ile birlikte kalıcı olur.
+/// `commit` çağrılmadan düşürülen transaction geri alınır.
+pub struct Transaction<'a, D: BlockDevice>
+where
+    D: Debug + 'static,
+{
+    fs: &'a SadakFs<D>,
+    /// Transaction başındaki (son commit'teki) Superblock.
+    superblock: Box<Superblock>,
+    /// Transaction başındaki kota tablosu.
+    quotas: Vec<(QuotaId, QuotaUsage)>,
+    open_tail_block: u64,
+    /// `commit` veya `rollback` çağrıldı mı?
+    finished: bool,
+}
+
+impl<'a, D> Transaction<'a, D>
+where
+    D: BlockDevice + Debug + 'static,
+{
+    /// Biriken değişiklikleri tek bir Superblock güncellemesiyle kalıcı yapar.
+    /// Hata dönerse değişikliklerin hiçbiri kalıcı olmaz ve transaction geri alınmış olur.
+    pub fn commit(mut self) -> Result<(), SadakFsError<D>> {
+        self.finished = true;
+        self.fs.commit_transaction(&self)
+    }
+
+    /// Biriken değişiklikleri atar; birim transaction başındaki haline döner.
+    pub fn rollback(mut self) {
+        self.finished = true;
+        self.abort();
+    }
+
+    fn abort(&self) {
+        self.fs.lock.acquire();
+        self.fs.abort_transaction_locked(self);
+        self.fs.lock.release();
+    }
+}
+
+impl<'a, D> core::ops::Deref for Transaction<'a, D>
+where
+    D: BlockDevice + Debug + 'static,
+{
+    type Target = SadakFs<D>;
+
+    fn deref(&self) -> &SadakFs<D> {
+        self.fs
+    }
+}
+
+impl<'a, D> Drop for Transaction<'a, D>
+where
+    D: BlockDevice + Debug + 'static,
+{
+    fn drop(&mut self) {
+        if !self.finished {
+            self.abort();
+        }
+    }
+}
+
+/// Açık bir dosya: geçerli konumu ve açılış kipini tutar, böylece çağıranlar konum
+/// hesabını kendileri yapmak zorunda kalmaz.
+///
+/// Yazmalar sayfa önbelleğine (`write_buffered`) gider ve dosya sisteminin bir sonraki
+/// commit'iyle kalıcı olur; `close` commit yapmaz.
+pub struct FileHandle<'a, D: BlockDevice> {
+    fs: &'a SadakFs<D>,
+    /// Açılan dosyanın inode'u (yazmalarla güncellenir).
+    inode: Inode,
+    /// Sonraki okuma/yazmanın başlayacağı bayt konumu.
+    offset: u64,
+    mode: OpenMode,
+}
+
+impl<'a, D> FileHandle<'a, D>
+where
+    D: BlockDevice + Debug + 'static,
+{
+    /// Yolu `open` ile çözer ve dosyayı `mode` kipinde açar; konum 0'dan başlar.
+    pub fn open(fs: &'a SadakFs<D>, path: &str, mode: OpenMode) -> Result<Self, SadakFsError<D>> {
+        let inode = fs.open(path, mode.flags())?;
+        Self::new(fs, inode, mode)
+    }
+
+    /// Zaten çözülmüş bir inode için tanıtıcı oluşturur.
+    pub fn new(fs: &'a SadakFs<D>, inode: Inode, mode: OpenMode) -> Result<Self, SadakFsError<D>> {
+        if inode.file_type == FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::IsADirectory);
+        }
+        Ok(FileHandle { fs, inode, offset: 0, mode })
+    }
+
+    /// Geçerli konumdan `buf`'a okur ve konumu okunan kadar ilerletir.
+    ///
+    /// # Döndürür
+    /// Okunan bayt sayısı (dosya sonunda 0).
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, SadakFsError<D>> {
+        let read = self.fs.read_at(&self.inode, self.offset, buf)?;
+        self.offset += read as u64;
+        Ok(read)
+    }
+
+    /// Geçerli konuma (`Append` kipinde dosya sonuna) yazar ve konumu yazılan verinin sonuna taşır.
+    ///
+    /// # Döndürür
+    /// Yazılan bayt sayısı.
+    pub fn write(&mut self, data: &[u8]) -> Result<usize, SadakFsError<D>> {
+        match self.mode {
+            OpenMode::ReadOnly => return Err(SadakFsError::PermissionDenied),
+            OpenMode::Append => self.offset = self.size()?,
+            OpenMode::ReadWrite => {}
+        }
+
+        let written = self.fs.write_buffered(&mut self.inode, self.offset, data)?;
+        self.offset += written as u64;
+        Ok(written)
+    }
+
+    /// Konumu değiştirir. Dosya sonunun ötesine konumlanmak serbesttir (sonraki yazma
+    /// bir delik bırakır); başın öncesine konumlanmak `EINVAL` döner.
+    ///
+    /// # Döndürür
+    /// Yeni konum.
+    pub fn seek(&mut self, pos: SeekFrom) -> Result<u64, SadakFsError<D>> {
+        let (base, delta) = match pos {
+            SeekFrom::Start(offset) => (offset, 0),
+            SeekFrom::Current(delta) => (self.offset, delta),
+            SeekFrom::End(delta) => (self.size()?, delta),
+        };
+
+        let new_offset = base.checked_add_signed(delta).ok_or(SadakFsError::Syscall(SyscallError::EINVAL))?;
+        self.offset = new_offset;
+        Ok(new_offset)
+    }
+
+    /// Geçerli konum.
+    pub fn position(&self) -> u64 {
+        self.offset
+    }
+
+    /// Açılış kipi.
+    pub fn mode(&self) -> OpenMode {
+        self.mode
+    }
+
+    /// Açılan dosyanın inode'u.
+    pub fn inode(&self) -> &Inode {
+        &self.inode
+    }
+
+    /// Tanıtıcıyı kapatır. Yazılan veri sayfa önbelleğinde kalır ve bir sonraki commit ile
+    /// kalıcı olur; kalıcılık gerekiyorsa önce `SadakFs::sync` çağrılmalıdır.
+    pub fn close(self) {}
+
+    /// Dosyanın geri yazılmamış sayfalar dahil güncel boyutu.
+    fn size(&self) -> Result<u64, SadakFsError<D>> {
+        let current = self.fs.read_inode(self.inode.inode_id)?;
+        Ok(self.fs.visible_size(&current))
+    }
+}
+
+
+// --- 3.2. Dizin Yineleyicisi ---
+
+/// Bir dizini sınırlı boyutlu parçalar halinde okur; her `next` en fazla `batch` girdi
+/// döndürür (bkz. `SadakFs::read_dir_from`). Okuma `cursor` ile kaydedilip daha sonra
+/// `resume` ile sürdürülebilir; yineleyici dosya sistemi kilidini tutmaz.
+///
+/// Hata dönen bir `next` imleci ilerletmez; çağıran aynı parçayı yeniden isteyebilir.
+pub struct DirIterator<'a, D: BlockDevice> {
+    fs: &'a SadakFs<D>,
+    /// Okunan dizinin inode'u; her parçada yeniden okunur ki düzen değişikliği görülsün.
+    dir_id: BlockId,
+    /// Sonraki parçanın başlayacağı yer.
+    cursor: DirCursor,
+    batch: usize,
+}
+
+impl<'a, D> DirIterator<'a, D>
+where
+    D: BlockDevice + Debug + 'static,
+{
+    /// `cursor`'dan itibaren okuyan yineleyici. `dir` dizin değilse `NotADirectory`,
+    /// `batch` 0 ise `EINVAL`.
+    pub fn resume(fs: &'a SadakFs<D>, dir: &Inode, cursor: DirCursor, batch: usize) -> Result<Self, SadakFsError<D>> {
+        if dir.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        if batch == 0 {
+            return Err(SadakFsError::Syscall(SyscallError::EINVAL));
+        }
+        Ok(DirIterator { fs, dir_id: dir.inode_id, cursor, batch })
+    }
+
+    /// Okunmuş son parçanın ardı; `resume` ile okuma buradan sürdürülür.
+    pub fn cursor(&self) -> DirCursor {
+        self.cursor
+    }
+}
+
+impl<'a, D> Iterator for DirIterator<'a, D>
+where
+    D: BlockDevice + Debug + 'static,
+{
+    type Item = Result<Vec<DirEntry>, SadakFsError<D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cursor.is_end() {
+            return None;
+        }
+
+        let batch = self.fs.read_inode(self.dir_id).and_then(|dir| self.fs.read_dir_from(&dir, self.cursor, self.batch));
+        match batch {
+            Ok((entries, cursor)) => {
+                self.cursor = cursor;
+                if entries.is_empty() {
+                    return None;
+                }
+                Some(Ok(entries))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+
+// --- 4. Erken (Salt Okunur) Montaj ---
+
+/// Sahne64 önyüklemesinin çok erken aşamasında (ör. init ikililerini yüklemek için)
+/// kullanılabilen salt okunur montaj.
+///
+/// Yalnızca Superblock doğrulanır ve metadata ağacı açılır: tahsis yöneticisi
+/// başlatılmaz, çekirdek kilidi oluşturulmaz, diske hiçbir şey yazılmaz (montaj
+/// sayacı dahil). Yazma yığını hazır olduğunda `promote_rw` ile tam montaja geçilir.
+pub struct EarlyFs<D: BlockDevice> {
+    cache: Arc<BlockCache<D>>,
+    metadata_tree: BTree<D>,
+    superblock: Superblock,
+    /// Kurtarma montajında aygıtın bildirdiği blok sayısı; bu ID ve ötesi okunmaz.
+    salvage_limit: Option<BlockId>,
+    /// Tam montajda kullanılacak zaman kaynağı.
+    clock: Arc<dyn Clock>,
+}
+
+impl<D: BlockDevice> EarlyFs<D>
+where
+    D: Debug + 'static,
+{
+    /// Diski salt okunur olarak monte eder. Aygıt superblock'un beklediğinden küçükse
+    /// `DeviceTooSmall` döner.
+    pub fn mount(device: D) -> Result<Self, SadakFsError<D>> {
+        Self::mount_checked(device, false)
+    }
+
+    /// Küçülmüş veya boyutunu yanlış bildiren bir aygıttan veri kurtarmak için zorla salt
+    /// okunur montaj. Aygıtın sonunun ötesindeki bloklara hiç dokunulmaz; bu bloklara
+    /// düşen inode'lar ve veri `BlockOutOfRange`, metadata düğümleri ise aygıt hatası
+    /// olarak raporlanır. Bu montaj tam montaja yükseltilemez. Aygıt yeterince büyükse
+    /// `mount` ile aynıdır.
+    pub fn mount_salvage(device: D) -> Result<Self, SadakFsError<D>> {
+        Self::mount_checked(device, true)
+    }
+
+    fn mount_checked(device: D, salvage: bool) -> Result<Self, SadakFsError<D>> {
+        let cache = Arc::new(BlockCache::new(Arc::new(device))?);
+        
+        // 1. Superblock'u oku: Blok 0 (A) ve `SUPERBLOCK_B_BLOCK` (B) yuvaları. B yuvası
+        // önbelleğe alınmaz; okunamaması montajı engellemez
+        let sb_block = cache.get_block(0)?;
+        let sb_ref = unsafe { &*sb_block.get() };
+        let mut slot_b = try_zeroed(BLOCK_SIZE)?;
+        if cache.device.read_block(SUPERBLOCK_B_BLOCK, &mut slot_b).is_err() {
+            slot_b.fill(0);
+        }
+
+        // 2. Superblock Checksum Doğrulaması
+        // Checksum veya Magic Number uyuşmazlığı, veri bozulması. Yuvalardan geçerli olan en yenisi seçilir.
+        let (superblock, _) = Superblock::from_slots(sb_ref.data.as_ref(), &slot_b).ok_or(SadakFsError::InvalidSuperblock)?;
+        // Alan eklenmeden önce biçimlendirilen birimlerde 0'dır (tek bloklu düğüm).
+        let node_size = match superblock.metadata_node_size {
+            0 => BTREE_NODE_SIZE,
+            size => size as usize,
+        };
+        if node_blocks_for(node_size).is_none() {
+            return Err(SadakFsError::InvalidSuperblock);
+        }
+        let unknown = superblock.features().unknown();
+        if unknown.incompat != 0 {
+            // Bilinmeyen bir biçim değişikliğiyle yazılmış birim yanlış yorumlanabilir
+            return Err(SadakFsError::UnsupportedFeatures(unknown.incompat));
+        }
+        let checksums = ChecksumPolicy::from_raw(&superblock.checksum_algorithms).ok_or(SadakFsError::InvalidSuperblock)?;
+        if superblock.metadata_root_id == 0 || superblock.metadata_root_id >= superblock.total_blocks {
+            return Err(SadakFsError::InvalidSuperblock);
+        }
+        // Damga sırası commit sırasından türetilir; önceki montajların damgalarını tekrarlamaz
+        if superblock.feature_incompat & FEATURE_INCOMPAT_WRITE_STAMPS != 0 {
+            cache.enable_write_stamps(superblock.journal_seq << 32);
+        }
+
+        // 3. Aygıt boyutu: 0, kapasitesi bilinmeyen aygıt demektir ve denetlenmez
+        let salvage_limit = match check_device_size(&cache, &superblock) {
+            Ok(()) => None,
+            Err(SadakFsError::DeviceTooSmall { actual, .. }) if salvage => {
+                cache.set_block_limit(actual);
+                if superblock.metadata_root_id >= actual {
+                    return Err(SadakFsError::BlockOutOfRange(superblock.metadata_root_id));
+                }
+                Some(actual)
+            }
+            Err(e) => return Err(e),
+        };
+
+        // 4. Son commit'in yarım kalan yerinde yazmaları günlükten tamamlanır. Görüntüler
+        // önbelleğe kirli olarak yüklenir: salt okunur montajda yalnızca bellekte kalır,
+        // yazılabilir montajda ilk commit ile yerlerine yazılır.
+        replay_journal(&cache, &superblock, salvage_limit)?;
+
+        // 5. Mühürlü birimde bundan sonra okunan her blok kök özete karşı doğrulanır
+        if superblock.seal_tree_start != 0 {
+            let geometry = SealGeometry::new(superblock.total_blocks, superblock.seal_tree_start);
+            cache.set_seal(SealVerifier::new(geometry, superblock.seal_root));
+        }
+
+        // 6. Sıcak blok listesi en iyi çabayla önceden okunur; bozuk liste veya okunamayan
+        // blok montajı engellemez
+        if superblock.warm_set_block != 0 && superblock.warm_set_block < salvage_limit.unwrap_or(BlockId::MAX) {
+            let limit = salvage_limit.unwrap_or(superblock.total_blocks).min(superblock.total_blocks);
+            let ids = cache
+                .get_block(superblock.warm_set_block)
+                .ok()
+                .and_then(|block_arc| block_cache::decode_warm_set(unsafe { &*block_arc.get() }.data.as_ref()));
+            if let Some(mut ids) = ids {
+                ids.retain(|&id| id < limit);
+                let _ = cache.prefetch(&ids);
+            }
+        }
+
+        let metadata_tree = BTree::new(cache.clone(), superblock.metadata_root_id)?
+            .with_checksum(checksums.metadata_tree)
+            .with_node_size(node_size);
+        Ok(EarlyFs { cache, metadata_tree, superblock, salvage_limit, clock: Arc::new(KernelClock) })
+    }
+
+    /// Montaj, küçülmüş bir aygıt üzerinde kurtarma modunda mı yapıldı?
+    pub fn is_salvage(&self) -> bool {
+        self.salvage_limit.is_some()
+    }
+
+    /// Birim mühürlü mü? Mühürlü birimde okunan her blok doğrulanır ve montaj tam
+    /// montaja yükseltilemez.
+    pub fn is_sealed(&self) -> bool {
+        self.superblock.seal_tree_start != 0
+    }
+
+    /// Son commit'in zaman damgası. Sayaç tabanlı bir saat bunun ötesinden başlatılırsa
+    /// yeniden başlatmalarda zaman geri gitmez.
+    pub fn last_commit_time(&self) -> u64 {
+        self.superblock.timestamp
+    }
+
+    /// Tam montajın (ve sonrasındaki tüm zaman damgalarının) kullanacağı zaman kaynağı.
+    /// Varsayılan çekirdek saatidir (`KernelClock`).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Yazma yığınını (tahsis yöneticisi, kilitler, sayfa önbelleği) kurar ve hızlı
+    /// metadata denetimiyle tam montaja geçer. Okunmuş bloklar önbellekte kalır.
+    pub fn promote_rw(self) -> Result<SadakFs<D>, SadakFsError<D>> {
+        self.promote_with(MountVerify::Fast, CheckPolicy::Recommend)
+    }
+
+    /// `promote_rw`'nin denetim derinliği ve periyodik denetim politikası verilebilen hali.
+    /// Kurtarma montajı (`DeviceTooSmall`) ve mühürlü birim (`Sealed`) yükseltilemez.
+    pub fn promote_with(self, verify: MountVerify, policy: CheckPolicy) -> Result<SadakFs<D>, SadakFsError<D>> {
+        self.promote_with_options(&MountOptions::new().verify(verify).check_policy(policy))
+    }
+
+    /// `promote_rw`'nin tüm montaj ayarları verilebilen hali (`read_only` yok sayılır).
+    pub fn promote_with_options(self, options: &MountOptions) -> Result<SadakFs<D>, SadakFsError<D>> {
+        if self.is_sealed() {
+            return Err(SadakFsError::Sealed);
+        }
+        check_device_size(&self.cache, &self.superblock)?;
+        SadakFs::promote(self, &options.read_only(false))
+    }
+
+    /// Kök dizinin inode'u.
+    pub fn root_dir(&self) -> Result<Inode, SadakFsError<D>> {
+        self.read_inode(self.superblock.root_dir_inode)
+    }
+
+    /// Bir inode'u bloğundan okur ve checksum'unu doğrular.
+    pub fn read_inode(&self, inode_id: BlockId) -> Result<Inode, SadakFsError<D>> {
+        self.check_in_range(inode_id)?;
+        read_inode_in(&self.cache, self.superblock.checksum_policy().inode, inode_id)
+    }
+
+    /// Inode'u kalıcı inode numarasıyla okur (bkz. `SadakFs::get_inode`).
+    pub fn get_inode(&self, ino: u64) -> Result<Inode, SadakFsError<D>> {
+        let key = InodeLocation::key(ino);
+        let value = self.metadata_tree.lookup(&key)?.ok_or(SadakFsError::NotFound)?;
+        let location = InodeLocation::from_item(&key, &value)
+            .ok_or(SadakFsError::MetadataCorrupted(self.metadata_tree.root_id()))?;
+        let inode = self.read_inode(location.block)?;
+        if inode.ino != ino {
+            return Err(SadakFsError::MetadataCorrupted(location.block));
+        }
+        Ok(inode)
+    }
+
+    /// Dizin girdisinin işaret ettiği inode (bkz. `SadakFs::entry_inode`).
+    pub fn entry_inode(&self, entry: &DirEntry) -> Result<Inode, SadakFsError<D>> {
+        if self.superblock.feature_incompat & FEATURE_INCOMPAT_DIR_INO != 0 {
+            self.get_inode(entry.ino)
+        } else {
+            self.read_inode(entry.ino)
+        }
+    }
+
+    /// `root` kökünden başlayan dosya veri ağacı (birimin veri ağacı checksum algoritmasıyla).
+    fn data_tree(&self, root: BlockId) -> Result<BTree<D>, SadakFsError<D>> {
+        Ok(BTree::new(self.cache.clone(), root)?.with_checksum(self.superblock.checksum_policy().data_tree))
+    }
+
+    /// Kurtarma montajında aygıtın sonunun ötesindeki bloğu reddeder.
+    fn check_in_range(&self, id: BlockId) -> Result<(), SadakFsError<D>> {
+        match self.salvage_limit {
+            Some(limit) if id >= limit => Err(SadakFsError::BlockOutOfRange(id)),
+            _ => Ok(()),
+        }
+    }
+
+    /// `parent` dizininde `name` adlı girdinin işaret ettiği inode'u bulur.
+    pub fn lookup(&self, parent: &Inode, name: &str) -> Result<Inode, SadakFsError<D>> {
+        if parent.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+
+        match find_entry_in(&self.metadata_tree, &self.superblock, parent, name)? {
+            Some((_, entry)) => self.entry_inode(&entry),
+            None => Err(SadakFsError::NotFound),
+        }
+    }
+
+    /// Bir yolu kök dizinden başlayarak çözer. Sonda `/` varsa hedef dizin olmalıdır.
+    pub fn open(&self, path: &str) -> Result<Inode, SadakFsError<D>> {
+        if path.is_empty() {
+            return Err(SadakFsError::NotFound);
+        }
+
+        let mut current = self.root_dir()?;
+        for component in path::components(path) {
+            if current.file_type != FILE_TYPE_DIRECTORY {
+                return Err(SadakFsError::NotADirectory);
+            }
+            current = match component {
+                Component::CurDir => current,
+                Component::ParentDir => self.read_inode(current.parent_dir)?,
+                Component::Normal(name) => self.lookup(&current, name)?,
+            };
+        }
+
+        if path::has_trailing_separator(path) && current.file_type != FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::NotADirectory);
+        }
+        Ok(current)
+    }
+
+    /// Dosyanın `offset` konumundan `buf`'a okur; delikler sıfır okunur.
+    ///
+    /// # Döndürür
+    /// Okunan bayt sayısı (dosya sonunda `buf.len()`'den az, `offset` dosya sonundaysa 0).
+    pub fn read_at(&self, inode: &Inode, offset: u64, buf: &mut [u8]) -> Result<usize, SadakFsError<D>> {
+        if inode.file_type == FILE_TYPE_DIRECTORY {
+            return Err(SadakFsError::IsADirectory);
+        }
+        // Erken montajın anahtarlığı yoktur; şifreli dosyalar opaktır
+        if inode.key_id != 0 {
+            return Err(SadakFsError::KeyUnavailable);
+        }
+        if offset >= inode.file_size {
+            return Ok(0);
+        }
+
+        let total = (buf.len() as u64).min(inode.file_size - offset) as usize;
+        let data_tree = self.data_tree(inode.data_tree_root)?;
+
+        let mut done = 0;
+        while done < total {
+            let position = offset + done as u64;
+            let file_block = position / BLOCK_SIZE as u64;
+            let offset_in_block = (position % BLOCK_SIZE as u64) as usize;
+            let len = (BLOCK_SIZE - offset_in_block).min(total - done);
+
+            let dest = &mut buf[done..done + len];
+            match extent_at_in(&data_tree, file_block)? {
+                Some(extent) if extent.is_compressed() => {
+                    self.check_in_range(extent.physical_blocks().end - 1)?;
+                    let mut cluster = try_zeroed(COMPRESS_CLUSTER_BYTES)?;
+                    let range = read_compressed_in(&self.cache, &extent, &mut cluster)?;
+                    let at = range.start + (file_block - extent.file_block) as usize * BLOCK_SIZE + offset_in_block;
+                    dest.copy_from_slice(&cluster[at..at + len]);
+                }
+                Some(extent) => {
+                    let physical = extent.physical_for(file_block);
+                    self.check_in_range(physical)?;
+                    let block_arc = self.cache.get_block(physical)?;
+                    dest.copy_from_slice(unsafe { &(*block_arc.get()).data[offset_in_block..offset_in_block + len] });
+                }
+                None => {
+                    dest.fill(0);
+                    if let Some(fragment) = tail_fragment_in(&data_tree, file_block)? {
+                        self.check_in_range(fragment.tail_block)?;
+                        let block_arc = self.cache.get_block(fragment.tail_block)?;
+                        let tail = unsafe { &(*block_arc.get()).data[fragment.range()] };
+                        if offset_in_block < tail.len() {
+                            let n = (tail.len() - offset_in_block).min(len);
+                            dest[..n].copy_from_slice(&tail[offset_in_block..offset_in_block + n]);
+                        }
+                    }
+                }
+            }
+            done += len;
+        }
+        Ok(total)
+    }
+}
+
+
+// --- 5. Ortak Okuma Yardımcıları ---
+// Hem tam montaj (`SadakFs`) hem salt okunur erken montaj (`EarlyFs`) tarafından kullanılır.
+
+/// Bir inode'u bloğundan okur ve checksum'unu doğrular.
+fn read_inode_in<D: BlockDevice>(cache: &BlockCache<D>, algorithm: ChecksumAlgorithm, inode_id: BlockId) -> Result<Inode, SadakFsError<D>> {
+    let inode_arc = cache.get_block(inode_id)?;
+    let inode_ref = unsafe { &*inode_arc.get() };
+    // Önbellek arabelleğinin `Inode` hizalamasında olması garanti değildir
+    let inode = unsafe { (inode_ref.data.as_ptr() as *const Inode).read_unaligned() };
+
+    if inode.inode_id != inode_id || inode_checksum(algorithm, inode_ref.data.as_ref()) != inode.checksum {
+        return Err(SadakFsError::ChecksumError);
+    }
+    Ok(inode)
+}
+
+/// Dizin girdilerinde `inode`'u gösteren numara: `FEATURE_INCOMPAT_DIR_INO` birimlerinde
+/// kalıcı inode numarası, daha eski birimlerde inode'un blok ID'si. Dizinin girdileri de
+/// bu numarayla anahtarlanır.
+fn entry_ino(superblock: &Superblock, inode: &Inode) -> u64 {
+    if superblock.feature_incompat & FEATURE_INCOMPAT_DIR_INO != 0 {
+        inode.ino
+    } else {
+        inode.inode_id
+    }
+}
+
+/// Bir dizinin girdilerini metadata ağacında sırayla ziyaret eder.
+/// `visit` `false` döndürdüğünde tarama durur.
+fn scan_dir_in<D, F>(metadata_tree: &BTree<D>, dir_ino: u64, visit: &mut F) -> Result<(), SadakFsError<D>>
+where
+    D: BlockDevice,
+    F: FnMut(&BTreeKey, DirEntry) -> bool,
+{
+    metadata_tree.walk_from(&DirEntry::key(dir_ino, 0), &mut |key, value| {
+        if !DirEntry::belongs_to(key, dir_ino) {
+            return false;
+        }
+        match DirEntry::decode(value) {
+            Some(entry) => visit(key, entry),
+            None => true, // Bozuk girdi atlanır; scrub tarafından raporlanır
+        }
+    })?;
+    Ok(())
+}
+
+/// `dir` dizininin `cursor`'dan sonraki en fazla `max` girdisi ve okumanın sürdürüleceği imleç.
+fn read_dir_batch_in<D: BlockDevice>(
+    metadata_tree: &BTree<D>,
+    superblock: &Superblock,
+    dir: &Inode,
+    cursor: DirCursor,
+    max: usize,
+) -> Result<(Vec<DirEntry>, DirCursor), SadakFsError<D>> {
+    let layout = if dir.flags & INODE_FLAG_DIR_HASHED != 0 { DIR_CURSOR_HASHED } else { 0 };
+    if cursor.is_end() {
+        return Ok((Vec::new(), cursor));
+    }
+    // Başlangıç imleci her iki düzende de geçerlidir; girdi döndürmüş bir imleç yalnızca
+    // üretildiği düzende anlamlıdır
+    if cursor.next != 0 && cursor.state & DIR_CURSOR_HASHED != layout {
+        return Err(SadakFsError::StaleHandle);
+    }
+
+    let dir_ino = entry_ino(superblock, dir);
+    let mut entries = Vec::new();
+    let mut last = None;
+    let mut full = false;
+    metadata_tree.walk_from(&DirEntry::key(dir_ino, cursor.next), &mut |key, value| {
+        if !DirEntry::belongs_to(key, dir_ino) {
+            return false;
+        }
+        if entries.len() == max {
+            full = true;
+            return false;
+        }
+        if let Some(entry) = DirEntry::decode(value) {
+            entries.push(entry);
+        }
+        last = Some(key.offset);
+        true
+    })?;
+
+    // Parça dolmadan biten ya da son sıra numarasına ulaşan okuma dizinin sonudur
+    let next = match last {
+        Some(offset) => offset.checked_add(1),
+        None => Some(cursor.next),
+    };
+    let cursor = match next {
+        Some(next) if full => DirCursor { next, state: layout },
+        Some(next) => DirCursor { next, state: layout | DIR_CURSOR_END },
+        None => DirCursor { next: u64::MAX, state: layout | DIR_CURSOR_END },
+    };
+    Ok((entries, cursor))
+}
+
+/// Özetli dizinde `hash` özetini paylaşan girdileri çakışma numarası sırasıyla ziyaret eder.
+/// `visit` `false` döndürdüğünde tarama durur.
+fn scan_hash_run<D, F>(metadata_tree: &BTree<D>, dir_ino: u64, hash: u64, visit: &mut F) -> Result<(), SadakFsError<D>>
+where
+    D: BlockDevice,
+    F: FnMut(&BTreeKey, DirEntry) -> bool,
+{
+    metadata_tree.walk_from(&DirEntry::hashed_key(dir_ino, hash, 0), &mut |key, value| {
+        if !DirEntry::belongs_to(key, dir_ino) || DirEntry::key_hash(key) != hash {
+            return false;
+        }
+        match DirEntry::decode(value) {
+            Some(entry) => visit(key, entry),
+            None => true,
+        }
+    })?;
+    Ok(())
+}
+
+/// Göç süren dizinin henüz taşınmamış doğrusal girdilerini sırayla ziyaret eder (bkz.
+/// `DirMigration`). `visit` `false` döndürdüğünde tarama durur.
+fn scan_migrating_in<D, F>(metadata_tree: &BTree<D>, dir_ino: u64, visit: &mut F) -> Result<(), SadakFsError<D>>
+where
+    D: BlockDevice,
+    F: FnMut(&BTreeKey, DirEntry) -> bool,
+{
+    let Some(migration) = dir_migration_in(metadata_tree, dir_ino)? else {
+        return Ok(());
+    };
+    metadata_tree.walk_from(&DirEntry::key(dir_ino, migration.cursor), &mut |key, value| {
+        if !DirEntry::belongs_to(key, dir_ino) || key.offset >= migration.limit {
+            return false;
+        }
+        match DirEntry::decode(value) {
+            Some(entry) => visit(key, entry),
+            None => true,
+        }
+    })?;
+    Ok(())
+}
+
+/// `dir_ino` dizininin göç kaydı; dizin göç sürmüyorsa `None`.
+fn dir_migration_in<D: BlockDevice>(metadata_tree: &BTree<D>, dir_ino: u64) -> Result<Option<DirMigration>, SadakFsError<D>> {
+    Ok(metadata_tree.lookup(&DirMigration::key(dir_ino))?.and_then(|value| DirMigration::decode(&value)))
+}
+
+/// `dir` dizininde `name` adlı girdiyi anahtarıyla birlikte bulur: özetli dizinde yalnızca
+/// adın özetini paylaşan girdiler (göç sürüyorsa ayrıca taşınmamış doğrusal girdiler),
+/// doğrusal dizinde tüm girdiler okunur.
+fn find_entry_in<D: BlockDevice>(
+    metadata_tree: &BTree<D>,
+    superblock: &Superblock,
+    dir: &Inode,
+    name: &str,
+) -> Result<Option<(BTreeKey, DirEntry)>, SadakFsError<D>> {
+    let fold = casefold_of(dir);
+    let dir_ino = entry_ino(superblock, dir);
+    let mut found = None;
+    let mut visit = |key: &BTreeKey, entry: DirEntry| {
+        if found.is_some() {
+            return false;
+        }
+        if fold.matches(&entry.name, name) {
+            found = Some((*key, entry));
+            return false;
+        }
+        true
+    };
+    if dir.flags & INODE_FLAG_DIR_HASHED != 0 {
+        scan_hash_run(metadata_tree, dir_ino, DirEntry::name_hash(&fold.fold(name), dir_hash_seed(superblock)), &mut visit)?;
+        if dir.flags & INODE_FLAG_DIR_MIGRATING != 0 {
+            scan_migrating_in(metadata_tree, dir_ino, &mut visit)?;
+        }
+    } else {
+        scan_dir_in(metadata_tree, dir_ino, &mut visit)?;
+    }
+    Ok(found)
+}
+
+/// Dizin inode'unun bayraklarındaki ad karşılaştırma kuralı.
+fn casefold_of(dir: &Inode) -> Casefold {
+    match dir.flags & (INODE_FLAG_CASEFOLD | INODE_FLAG_CASEFOLD_UNICODE) {
+        0 => Casefold::None,
+        INODE_FLAG_CASEFOLD => Casefold::Ascii,
+        _ => Casefold::Unicode,
+    }
+}
+
+/// Birimin ad özeti tohumu; UUID'den türetilir ki özet çakışmaları birimden birime değişsin.
+fn dir_hash_seed(superblock: &Superblock) -> u64 {
+    let mut seed = [0u8; 8];
+    seed.copy_from_slice(&superblock.uuid[..8]);
+    u64::from_le_bytes(seed)
+}
+
+/// Aygıtın bildirdiği blok sayısı superblock'un beklediğinden azsa `DeviceTooSmall`.
+/// Kapasitesini bildirmeyen (0 döndüren) aygıtlar denetlenmez.
+fn check_device_size<D: BlockDevice>(cache: &BlockCache<D>, superblock: &Superblock) -> Result<(), SadakFsError<D>> {
+    let actual = cache.device.total_blocks();
+    if actual != 0 && actual < superblock.total_blocks {
+        return Err(SadakFsError::DeviceTooSmall { expected: superblock.total_blocks, actual });
+    }
+    Ok(())
+}
+
+/// Niyet günlüğü Superblock'un gösterdiği son commit'e aitse blok görüntülerini önbelleğe
+/// kirli olarak yükler. Kurtarma montajında aygıtın sonunun ötesindeki hedefler atlanır.
+///
+/// # Döndürür
+/// Yüklenen görüntü sayısı. Bozuk görüntüde `MetadataCorrupted` (görüntünün günlükteki bloğu).
+fn replay_journal<D: BlockDevice>(
+    cache: &BlockCache<D>,
+    superblock: &Superblock,
+    salvage_limit: Option<BlockId>,
+) -> Result<usize, SadakFsError<D>> {
+    let start = superblock.journal_start;
+    let limit = salvage_limit.unwrap_or(BlockId::MAX);
+    if start == 0 || superblock.feature_compat & FEATURE_COMPAT_JOURNAL == 0 || start + superblock.journal_blocks() > limit {
+        return Ok(0);
+    }
+
+    // Günlük Superblock'tan önce kalıcı yapıldığından commit'in bütün kesimleri ve
+    // görüntüleri tam olmalı; hiçbiri doğrulanmadan uygulanmaz
+    let mut block = try_zeroed(BLOCK_SIZE)?;
+    let mut targets = Vec::new();
+    let mut images = Vec::new();
+    for segment in 0..superblock.journal_segments.max(1) as u64 {
+        let segment_start = start + segment * JOURNAL_BLOCKS;
+        cache.device.read_block(segment_start, &mut block)?;
+        let header = match JournalHeader::decode(&block) {
+            Some(header) if header.needs_replay(superblock.journal_seq, superblock.timestamp) => header,
+            // Kullanılmayan veya uygulandı işaretlenmiş kesim
+            _ => continue,
+        };
+        for i in 0..header.blocks.len() {
+            cache.device.read_block(segment_start + 1 + i as u64, &mut block)?;
+            if !header.image_valid(i, &block) {
+                return Err(SadakFsError::MetadataCorrupted(segment_start + 1 + i as u64));
+            }
+            images.push(mem::replace(&mut block, try_zeroed(BLOCK_SIZE)?));
+        }
+        targets.extend(header.blocks);
+    }
+
+    let mut replayed = 0;
+    for (&(id, _), image) in targets.iter().zip(images) {
+        if id == 0 || id >= limit {
+            continue;
+        }
+        let block_arc = cache.get_block(id)?;
+        let target = unsafe { &mut *block_arc.get() };
+        target.data.copy_from_slice(&image);
+        target.is_dirty = true;
+        replayed += 1;
+    }
+    Ok(replayed)
+}
+
+/// İskelet yolunu bileşenlerine ayırır; `.`, `..`, geçersiz ad veya boş yol `InvalidName`.
+fn skeleton_components<D: BlockDevice>(path: &str) -> Result<Vec<&str>, SadakFsError<D>> {
+    let mut names = Vec::new();
+    for component in path::components(path) {
+        match component {
+            Component::Normal(name) if DirEntry::is_valid_name(name) => names.push(name),
+            _ => return Err(SadakFsError::InvalidName),
+        }
+    }
+    if names.is_empty() {
+        return Err(SadakFsError::InvalidName);
+    }
+    Ok(names)
+}
+
+/// Bir dosya bloğunun paketlenmiş kuyruk parçası; blok paketlenmemişse `None`.
+fn tail_fragment_in<D: BlockDevice>(data_tree: &BTree<D>, file_block: u64) -> Result<Option<TailFragment>, SadakFsError<D>> {
+    let key = TailFragment::key(file_block);
+    Ok(data_tree.lookup(&key)?.and_then(|value| TailFragment::from_item(&key, &value)))
+}
+
+/// Bir dosya bloğunun eşlendiği disk bloğu; blok bir delikteyse veya sıkıştırılmış bir
+/// kümedeyse (kendi disk bloğu yoksa) `None`.
+fn mapped_block_in<D: BlockDevice>(data_tree: &BTree<D>, file_block: u64) -> Result<Option<BlockId>, SadakFsError<D>> {
+    Ok(extent_at_in(data_tree, file_block)?
+        .filter(|extent| !extent.is_compressed())
+        .map(|extent| extent.physical_for(file_block)))
+}
+
+/// Bir dosya bloğunu kapsayan extent; blok bir delikteyse `None`.
+fn extent_at_in<D: BlockDevice>(data_tree: &BTree<D>, file_block: u64) -> Result<Option<Extent>, SadakFsError<D>> {
+    let found = data_tree
+        .lookup_floor(&Extent::key(file_block))?
+        .and_then(|(key, value)| Extent::from_item(&key, &value));
+
+    Ok(found.filter(|extent| extent.end() > file_block))
+}
+
+/// Sıkıştırılmış bir extent'in kümesini `cluster`'a (`COMPRESS_CLUSTER_BYTES`) çözer.
+/// Önbellekte kirli kopyası olan bloklar önbellekten okunur.
+///
+/// # Döndürür
+/// Extent'in kapsadığı blokların `cluster` içindeki bayt aralığı. Veri çözülemezse
+/// `DataCorrupted`.
+fn read_compressed_in<D: BlockDevice>(
+    cache: &BlockCache<D>,
+    extent: &Extent,
+    cluster: &mut [u8],
+) -> Result<core::ops::Range<usize>, SadakFsError<D>> {
+    let mut stored = try_zeroed(extent.stored_blocks() as usize * BLOCK_SIZE)?;
+    if cache.has_dirty_in_range(extent.physical_start, extent.stored_blocks()) {
+        for (i, chunk) in stored.chunks_mut(BLOCK_SIZE).enumerate() {
+            let block_arc = cache.get_block(extent.physical_start + i as BlockId)?;
+            chunk.copy_from_slice(unsafe { &(*block_arc.get()).data[..] });
+        }
+    } else {
+        cache.device.read_blocks(extent.physical_start, &mut stored)?;
+    }
+
+    let start = extent.skip_blocks as usize * BLOCK_SIZE;
+    let end = start + extent.length as usize * BLOCK_SIZE;
+    match stored.get(..extent.compressed_len as usize).and_then(|packed| extent.compression().decompress(packed, cluster)) {
+        Some(len) if len >= end => Ok(start..end),
+        _ => Err(SadakFsError::DataCorrupted(extent.physical_start)),
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_device::RamDevice;
+    use crate::clock::FakeClock;
+
+    fn ram_fs() -> SadakFs<RamDevice> {
+        SadakFs::format_with_clock(RamDevice::new(4096), FormatOptions::default(), Arc::new(FakeClock::new(1)))
+            .expect("biçimlendirme")
+    }
+
+    /// İstendiğinde `EIO` döndüren saat; işlemlerin ortasına hata enjekte etmek için
+    /// (ağaç değişikliklerinden sonraki zaman damgası okuması başarısız olur).
+    #[derive(Default)]
+    struct FailingClock {
+        failing: core::sync::atomic::AtomicBool,
+    }
+
+    impl FailingClock {
+        fn set_failing(&self, failing: bool) {
+            self.failing.store(failing, Ordering::Relaxed);
+        }
+    }
+
+    impl Clock for FailingClock {
+        fn now(&self) -> Result<u64, SyscallError> {
+            if self.failing.load(Ordering::Relaxed) {
+                return Err(SyscallError::EIO);
+            }
+            Ok(1)
+        }
+    }
+
+    fn failing_clock_fs() -> (SadakFs<RamDevice>, Arc<FailingClock>) {
+        let clock = Arc::new(FailingClock::default());
+        let fs = SadakFs::format_with_clock(RamDevice::new(4096), FormatOptions::default(), clock.clone()).expect("biçimlendirme");
+        (fs, clock)
+    }
+
+    /// Aygıtın diskteki halinin kopyası; kopya ayrıca monte edilebilir.
+    fn copy_device(device: &RamDevice) -> RamDevice {
+        let copy = RamDevice::new(device.total_blocks());
+        let mut block = alloc::vec![0u8; BLOCK_SIZE];
+        for id in 0..device.total_blocks() {
+            device.read_block(id, &mut block).expect("okuma");
+            copy.write_block(id, &block).expect("yazma");
+        }
+        copy
+    }
+
+    /// `id` bloğunun bir baytını bozar.
+    fn flip_byte(device: &RamDevice, id: BlockId, at: usize) {
+        let mut block = alloc::vec![0u8; BLOCK_SIZE];
+        device.read_block(id, &mut block).expect("okuma");
+        block[at] ^= 1;
+        device.write_block(id, &block).expect("yazma");
+    }
+
+    #[test]
+    fn mount_audit_verifies_bitmap_and_space_map() {
+        let fs = ram_fs();
+        let root = fs.root_dir().expect("kök dizin");
+        fs.create_file_in(&root, "dosya").expect("dosya oluşturma");
+        fs.sync().expect("commit");
+        assert!(fs.sb().journal_start != 0); // Checksum'lar yalnızca günlüklü haritada doğrulanır
+        let options = MountOptions::new().read_only(true);
+        assert!(SadakFs::mount(copy_device(fs.device()), options).is_ok());
+
+        // Bitmap bloğundaki tek bir bit de, kontrol noktası bloğundaki bir bayt da raporlanır
+        let bitmap = fs.sb().bitmap_start_id;
+        let space_map = fs.sb().space_map_root;
+        for (id, at) in [(bitmap, BLOCK_SIZE - 1), (space_map, 100)] {
+            let device = copy_device(fs.device());
+            flip_byte(&device, id, at);
+            assert!(matches!(SadakFs::mount(device, options), Err(SadakFsError::MetadataCorrupted(bad)) if bad == id));
+        }
+    }
+
+    #[test]
+    fn large_metadata_nodes_span_blocks() {
+        let options = FormatOptions::default().metadata_node_size(4 * BLOCK_SIZE);
+        let fs = SadakFs::format_with_clock(RamDevice::new(8192), options, Arc::new(FakeClock::new(1))).expect("biçimlendirme");
+        let small = ram_fs();
+        assert!(fs.sb().feature_incompat & FEATURE_INCOMPAT_LARGE_NODES != 0);
+
+        let names: Vec<String> = (0..600).map(|i| alloc::format!("dosya{i}")).collect();
+        for (target, root) in [(&fs, fs.root_dir()), (&small, small.root_dir())] {
+            let root = root.expect("kök dizin");
+            for name in &names {
+                target.create_file_in(&root, name).expect("dosya oluşturma");
+            }
+            target.sync().expect("commit");
+            for name in &names {
+                assert!(target.lookup(&root, name).is_ok());
+            }
+        }
+
+        // Dört bloklu düğümlerin her bloğu ağacın blok listesinde yer alır ve ağaç alçalır
+        assert_eq!(fs.metadata_tree.node_ids().expect("düğümler").len() % 4, 0);
+        assert!(fs.metadata_tree.height().expect("yükseklik") < small.metadata_tree.height().expect("yükseklik"));
+        assert_eq!(fs.metadata_tree.find_corrupt_node(u8::MAX).expect("denetim"), None);
+        assert!(matches!(
+            SadakFs::format(RamDevice::new(8192), FormatOptions::default().metadata_node_size(3 * BLOCK_SIZE)),
+            Err(SadakFsError::Syscall(SyscallError::EINVAL))
+        ));
+    }
+
+    #[test]
+    fn write_stamps_reject_torn_nodes() {
+        let features = FeatureSet { incompat: FEATURE_INCOMPAT_WRITE_STAMPS, ..FeatureSet::default() };
+        let options = FormatOptions::default().features(features);
+        let fs = SadakFs::format_with_clock(RamDevice::new(4096), options, Arc::new(FakeClock::new(1))).expect("biçimlendirme");
+        let root = fs.root_dir().expect("kök dizin");
+
+        // Blok başına bir yuva eksik olsa da düğümler bölünür ve tüm girdiler bulunur
+        let names: Vec<String> = (0..100).map(|i| alloc::format!("dosya{i}")).collect();
+        for name in &names {
+            fs.create_file_in(&root, name).expect("dosya oluşturma");
+        }
+        fs.sync().expect("commit");
+        for name in &names {
+            assert!(fs.lookup(&root, name).is_ok());
+        }
+        assert_eq!(fs.metadata_tree.find_corrupt_node(u8::MAX).expect("denetim"), None);
+
+        // Diskteki düğümün iki damgası aynıdır; son kesimi başka bir yazmadan kalmış düğüm
+        // damgalı okumada reddedilir, önceden önbelleğe alınmış olsa bile
+        let node = fs.metadata_tree.root_id();
+        let mut block = alloc::vec![0u8; BLOCK_SIZE];
+        fs.device().read_block(node, &mut block).expect("okuma");
+        assert!(block_cache::write_stamps_match(&block));
+        assert_ne!(block[BLOCK_SIZE - 8..], [0u8; 8]);
+        let device = copy_device(fs.device());
+        flip_byte(&device, node, BLOCK_SIZE - 1);
+        let cache = BlockCache::new(Arc::new(device)).expect("önbellek");
+        cache.enable_write_stamps(0);
+        assert!(cache.get_stamped_block(node).is_err());
+        assert!(cache.get_block(node).is_ok());
+        assert!(cache.get_stamped_block(node).is_err());
+    }
+
+    #[test]
+    fn decoded_fragment_ranges_stay_in_block() {
+        // `no_panic` kapısı dilimlemeyi denetlemez (bkz. lib.rs); diskten gelen aralık
+        // çözülürken bloğun dışına taşıyorsa kayıt reddedilir
+        let key = TailFragment::key(3);
+        let fragment = TailFragment { file_block: 3, tail_block: 100, offset: (BLOCK_SIZE - 8) as u32, len: 8 };
+        assert_eq!(TailFragment::from_item(&key, &fragment.encode()).map(|f| f.range()), Some(BLOCK_SIZE - 8..BLOCK_SIZE));
+        let overflowing = TailFragment { len: 16, ..fragment };
+        assert_eq!(TailFragment::from_item(&key, &overflowing.encode()), None);
+    }
+
+    #[test]
+    fn dir_entries_reference_inode_numbers() {
+        let fs = ram_fs();
+        assert!(fs.sb().feature_incompat & FEATURE_INCOMPAT_DIR_INO != 0);
+        let root = fs.root_dir().expect("kök dizin");
+        assert_eq!(root.ino, ROOT_INO);
+        let dir = fs.create_dir(&root, "alt").expect("dizin oluşturma");
+        let file = fs.create_file_in(&dir, "dosya").expect("dosya oluşturma");
+
+        // Girdiler hedefin numarasını taşır ve üst dizinin numarasıyla anahtarlanır
+        let entries = fs.read_dir(&dir).expect("dizin okuma");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].ino, file.ino);
+        assert_ne!(file.ino, file.inode_id);
+        assert!(fs.metadata_tree.lookup(&DirEntry::key(dir.ino, 0)).expect("arama").is_some());
+        assert_eq!(fs.entry_inode(&entries[0]).expect("çözümleme").inode_id, file.inode_id);
+        assert_eq!(fs.lookup(&dir, "dosya").expect("arama").inode_id, file.inode_id);
+        assert!(fs.fsck(false).expect("fsck").problems.is_empty());
+    }
+
+    #[test]
+    fn failed_op_restores_previous_state() {
+        let fs = ram_fs();
+        let root = fs.root_dir().expect("kök dizin");
+        let free_before = fs.allocator.free_blocks().expect("boş blok sayısı");
+        let root_before = fs.metadata_tree.root_id();
+
+        // İşlemin ortasında hata: dosya oluşturulduktan sonra aygıt hatası enjekte edilir
+        fs.lock.acquire();
+        let result = fs.atomic_locked(|| {
+            fs.create_files_locked(root.inode_id, &["yarim"], &Credentials::ROOT)?;
+            Err::<(), _>(SadakFsError::Syscall(SyscallError::EIO))
+        });
+        fs.lock.release();
+
+        assert!(matches!(result, Err(SadakFsError::Syscall(SyscallError::EIO))));
+        assert!(!fs.is_read_only());
+        assert!(matches!(fs.lookup(&root, "yarim"), Err(SadakFsError::NotFound)));
+        assert_eq!(fs.allocator.free_blocks().expect("boş blok sayısı"), free_before);
+        assert_eq!(fs.metadata_tree.root_id(), root_before);
+
+        // Geri alınan işlemden sonra aynı ad yeniden oluşturulabilir
+        fs.create_file_in(&root, "yarim").expect("dosya oluşturma");
+    }
+
+    #[test]
+    fn failed_batch_creates_nothing() {
+        let fs = ram_fs();
+        let root = fs.root_dir().expect("kök dizin");
+        fs.create_file_in(&root, "var").expect("dosya oluşturma");
+
+        // İkinci ad çakışır; grubun ilk dosyası da geri alınmalı
+        fs.lock.acquire();
+        let result = fs.atomic_locked(|| {
+            fs.create_files_locked(root.inode_id, &["yeni"], &Credentials::ROOT)?;
+            fs.create_files_locked(root.inode_id, &["var"], &Credentials::ROOT)
+        });
+        fs.lock.release();
+
+        assert!(matches!(result, Err(SadakFsError::AlreadyExists)));
+        assert!(matches!(fs.lookup(&root, "yeni"), Err(SadakFsError::NotFound)));
+        assert!(fs.lookup(&root, "var").is_ok());
+    }
+
+    #[test]
+    fn failed_unlink_keeps_entry() {
+        let (fs, clock) = failing_clock_fs();
+        let root = fs.root_dir().expect("kök dizin");
+        fs.create_file_in(&root, "kalır").expect("dosya oluşturma");
+
+        // Girdi kaldırıldıktan sonra üst dizin güncellenemez; kaldırma geri alınmalı
+        clock.set_failing(true);
+        assert!(matches!(fs.unlink(&root, "kalır"), Err(SadakFsError::Syscall(SyscallError::EIO))));
+        clock.set_failing(false);
+
+        assert!(!fs.is_read_only());
+        assert!(fs.lookup(&root, "kalır").is_ok());
+        assert_eq!(fs.root_dir().expect("kök dizin").file_size, 1);
+        fs.sync().expect("commit");
+        assert!(fs.fsck(false).expect("fsck").problems.is_empty());
+    }
+
+    #[test]
+    fn failed_rename_keeps_entry_in_source() {
+        let (fs, clock) = failing_clock_fs();
+        let root = fs.root_dir().expect("kök dizin");
+        let source = fs.create_dir(&root, "kaynak").expect("dizin");
+        let target = fs.create_dir(&root, "hedef").expect("dizin");
+        fs.create_file_in(&source, "dosya").expect("dosya oluşturma");
+
+        // Girdi taşındıktan sonra üst dizinler güncellenemez; taşıma geri alınmalı
+        clock.set_failing(true);
+        assert!(matches!(fs.rename(&source, "dosya", &target, "yeni"), Err(SadakFsError::Syscall(SyscallError::EIO))));
+        clock.set_failing(false);
+
+        assert!(!fs.is_read_only());
+        let source = fs.lookup(&root, "kaynak").expect("arama");
+        let target = fs.lookup(&root, "hedef").expect("arama");
+        assert!(fs.lookup(&source, "dosya").is_ok());
+        assert!(matches!(fs.lookup(&target, "yeni"), Err(SadakFsError::NotFound)));
+        assert_eq!((source.file_size, target.file_size), (1, 0));
+        fs.sync().expect("commit");
+        assert!(fs.fsck(false).expect("fsck").problems.is_empty());
+
+        fs.rename(&source, "dosya", &target, "yeni").expect("taşıma");
+        assert!(fs.lookup(&target, "yeni").is_ok());
+    }
+
+    #[test]
+    fn failed_skeleton_creates_nothing() {
+        let fs = ram_fs();
+        let root = fs.root_dir().expect("kök dizin");
+        let paths: Vec<String> = (0..64).map(|i| alloc::format!("/iskelet/dosya{i}")).collect();
+        let mut entries = alloc::vec![SkeletonEntry { path: "/iskelet", kind: SkeletonKind::Directory, mode: 0o755, uid: 0, gid: 0 }];
+        entries.extend(paths.iter().map(|path| SkeletonEntry { path, kind: SkeletonKind::File, mode: 0o644, uid: 0, gid: 0 }));
+
+        // Alan birkaç girdiden sonra biter; oluşturulanlar da geri alınmalı
+        let probe = fs.allocator.allocate_block().expect("tahsis");
+        fs.allocator.set_allocation_limit(probe + 16);
+        let result = fs.create_skeleton(&root, &entries);
+        fs.allocator.set_allocation_limit(BlockId::MAX);
+
+        assert!(matches!(result, Err(SadakFsError::Allocator(AllocatorError::OutOfSpace))));
+        assert!(!fs.is_read_only());
+        assert!(matches!(fs.lookup(&root, "iskelet"), Err(SadakFsError::NotFound)));
+        assert_eq!(fs.root_dir().expect("kök dizin").file_size, 0);
+        assert_eq!(fs.create_skeleton(&root, &entries).expect("iskelet").len(), entries.len());
+    }
+
+    #[test]
+    fn rollback_after_writeback_fails_loudly() {
+        let fs = ram_fs();
+        let root = fs.root_dir().expect("kök dizin");
+
+        // İşlem sırasında kirli bloklar diske gittiyse geri alma eksik kalır
+        fs.lock.acquire();
+        let result = fs.atomic_locked(|| {
+            fs.create_files_locked(root.inode_id, &["yazildi"], &Credentials::ROOT)?;
+            fs.cache.write_back_dirty(&[])?;
+            Err::<(), _>(SadakFsError::Syscall(SyscallError::EIO))
+        });
+        fs.lock.release();
+
+        assert!(matches!(result, Err(SadakFsError::RollbackFailed)));
+        assert!(fs.is_read_only());
+        assert!(matches!(fs.create_file_in(&root, "sonra"), Err(SadakFsError::ReadOnly)));
+    }
+
+    #[test]
+    fn large_linear_dir_migrates_in_batches() {
+        let fs = ram_fs();
+        let root = fs.root_dir().expect("kök dizin");
+        let names: Vec<String> = (0..DIR_MIGRATE_BATCH + 40).map(|i| alloc::format!("dosya{i}")).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+        // Eşiği tek eklemede aşan dizinde yalnızca bir parça taşınır; kalan girdiler bulunur
+        fs.create_files_in(&root, &names).expect("dosyalar");
+        let root = fs.root_dir().expect("kök dizin");
+        assert_eq!(root.flags & (INODE_FLAG_DIR_HASHED | INODE_FLAG_DIR_MIGRATING), INODE_FLAG_DIR_HASHED | INODE_FLAG_DIR_MIGRATING);
+        assert!(names.iter().all(|name| fs.lookup(&root, name).is_ok()));
+        assert!(matches!(fs.create_file_in(&root, names[names.len() - 1]), Err(SadakFsError::AlreadyExists)));
+
+        // Sonraki ekleme göçü bitirir
+        fs.create_file_in(&root, "son").expect("dosya");
+        let root = fs.root_dir().expect("kök dizin");
+        assert_eq!(root.flags & INODE_FLAG_DIR_MIGRATING, 0);
+        assert!(fs.metadata_tree.lookup(&DirMigration::key(fs.entry_ino(&root))).expect("arama").is_none());
+        assert!(names.iter().all(|name| fs.lookup(&root, name).is_ok()));
+        assert_eq!(fs.read_dir(&root).expect("okuma").len(), names.len() + 1);
+    }
+
+    #[test]
+    fn unicode_casefold_uses_simple_folding() {
+        let fold = Casefold::Unicode;
+        assert!(fold.matches("ſ", "S"));
+        assert!(fold.matches("ς", "Σ"));
+        assert!(fold.matches("\u{212A}", "k"));
+        assert!(fold.matches("ẞ", "ß"));
+        // Basit katlama karakter sayısını korur: 'ß' "ss", 'İ' "i" olmaz
+        assert!(!fold.matches("ß", "ss"));
+        assert!(!fold.matches("İ", "i"));
+
+        let fs = ram_fs();
+        let root = fs.root_dir().expect("kök dizin");
+        let mut dir = fs.create_dir(&root, "klasör").expect("dizin");
+        fs.set_casefold(&mut dir, Casefold::Unicode).expect("katlama");
+        fs.create_file_in(&dir, "Straße").expect("dosya");
+        assert!(fs.lookup(&dir, "ſTRAẞE").is_ok());
+        assert!(matches!(fs.lookup(&dir, "STRASSE"), Err(SadakFsError::NotFound)));
+        assert!(matches!(fs.create_file_in(&dir, "straße"), Err(SadakFsError::AlreadyExists)));
+    }
+
+    #[test]
+    fn buffered_writes_reserve_quota() {
+        let fs = ram_fs();
+        let root = fs.root_dir().expect("kök dizin");
+        let mut first = fs.create_file_in(&root, "bir").expect("dosya");
+        let mut second = fs.create_file_in(&root, "iki").expect("dosya");
+        let owner = QuotaId::User(first.uid);
+        let used = fs.quota_usage(owner).blocks;
+        fs.set_quota(owner, used + 3, 0);
+
+        // Ayrı deliklere tamponlanan yazmalar birlikte sınırı aşamaz
+        fs.write_buffered(&mut first, 0, &[1u8; 2 * BLOCK_SIZE]).expect("yazma");
+        let over = fs.write_buffered(&mut second, 0, &[2u8; 2 * BLOCK_SIZE]);
+        assert!(matches!(over, Err(SadakFsError::Allocator(AllocatorError::QuotaExceeded))));
+
+        // Zaten tamponlanmış sayfaya yazmak yeniden ayırmaz
+        fs.write_buffered(&mut first, 0, &[3u8; BLOCK_SIZE]).expect("yazma");
+        fs.write_buffered(&mut second, 0, &[2u8; BLOCK_SIZE]).expect("yazma");
+        fs.sync().expect("commit");
+        assert_eq!(fs.quota_usage(owner).blocks, used + 3);
+
+        // Geri yazılan bloklar artık kullanımdadır; ayırma bırakıldı
+        assert!(fs.write_buffered(&mut second, BLOCK_SIZE as u64, &[4u8; BLOCK_SIZE]).is_err());
+        fs.set_quota(owner, used + 4, 0);
+        fs.write_buffered(&mut second, BLOCK_SIZE as u64, &[4u8; BLOCK_SIZE]).expect("yazma");
+    }
+
+    #[test]
+    fn demote_copies_uncommitted_blocks() {
+        let fs = ram_fs();
+        fs.set_fast_tier(2048);
+        let root = fs.root_dir().expect("kök dizin");
+        let mut file = fs.create_file_in(&root, "soğuk").expect("dosya");
+        let data: Vec<u8> = (0..3 * BLOCK_SIZE).map(|i| (i % 251) as u8).collect();
+
+        // Yeni yazılan bloklar henüz yalnızca önbellekte; taşıma onları kopyalamalı
+        fs.write_at(&mut file, 0, &data).expect("yazma");
+        fs.set_placement_hint(&mut file, PlacementHint { temperature: Temperature::Cold, ..PlacementHint::default() }).expect("ipucu");
+        assert_eq!(fs.demote_cold_extents(&file, u64::MAX).expect("taşıma"), 3);
+
+        let file = fs.lookup(&root, "soğuk").expect("arama");
+        let mut buf = alloc::vec![0u8; data.len()];
+        assert_eq!(fs.read_at(&file, 0, &mut buf).expect("okuma"), data.len());
+        assert_eq!(buf, data);
+    }
+
+    /// `name` dosyasını iki bloklu `fill` verisiyle oluşturur, klonlar ve commit eder.
+    fn cloned_pair(fs: &SadakFs<RamDevice>, name: &str, fill: u8) -> (Inode, Inode) {
+        let root = fs.root_dir().expect("kök dizin");
+        let mut file = fs.create_file_in(&root, name).expect("dosya oluşturma");
+        fs.write_at(&mut file, 0, &alloc::vec![fill; 2 * BLOCK_SIZE]).expect("yazma");
+        let clone = fs.clone_file(&file).expect("klonlama");
+        fs.sync().expect("commit");
+        (fs.lookup(&root, name).expect("arama"), clone)
+    }
+
+    /// Dosyanın `file_block` bloğunun eşlendiği disk bloğu.
+    fn physical_of(fs: &SadakFs<RamDevice>, inode: &Inode, file_block: u64) -> BlockId {
+        let data_tree = fs.data_tree(inode.data_tree_root).expect("veri ağacı");
+        mapped_block_in(&data_tree, file_block).expect("extent").expect("eşlenmiş blok")
+    }
+
+    #[test]
+    fn writing_a_clone_leaves_the_source_unchanged() {
+        let fs = ram_fs();
+        let (file, mut clone) = cloned_pair(&fs, "asıl", 0x11);
+        let shared = physical_of(&fs, &file, 0);
+        assert_eq!(physical_of(&fs, &clone, 0), shared);
+        assert!(fs.is_shared_block(shared).expect("paylaşım"));
+
+        fs.write_at(&mut clone, 0, &[0x22u8; BLOCK_SIZE]).expect("yazma");
+        fs.sync().expect("commit");
+
+        // Klonun ilk bloğu kendi kopyasına taşınır; asıl dosya eski veriyi okur
+        let mut buf = alloc::vec![0u8; 2 * BLOCK_SIZE];
+        assert_eq!(fs.read_at(&file, 0, &mut buf).expect("okuma"), buf.len());
+        assert!(buf.iter().all(|&b| b == 0x11));
+        assert_eq!(fs.read_at(&clone, 0, &mut buf).expect("okuma"), buf.len());
+        assert!(buf[..BLOCK_SIZE].iter().all(|&b| b == 0x22));
+        assert!(buf[BLOCK_SIZE..].iter().all(|&b| b == 0x11));
+        assert_ne!(physical_of(&fs, &clone, 0), shared);
+        assert!(!fs.is_shared_block(shared).expect("paylaşım"));
+    }
+
+    #[test]
+    fn releasing_the_last_reference_frees_the_block() {
+        let fs = ram_fs();
+        let (mut file, mut clone) = cloned_pair(&fs, "asıl", 0x33);
+        let blocks = [physical_of(&fs, &file, 0), physical_of(&fs, &file, 1)];
+
+        // Klon bırakılınca bloklar asıl dosyaya kalır ve artık paylaşılmaz
+        fs.truncate(&mut clone, 0).expect("kesme");
+        fs.sync().expect("commit");
+        for &id in &blocks {
+            assert!(!fs.is_shared_block(id).expect("paylaşım"));
+            assert!(fs.allocator.is_allocated(id).expect("harita"));
+        }
+
+        // Son başvuru da bırakılınca bloklar serbest kalır
+        fs.truncate(&mut file, 0).expect("kesme");
+        fs.sync().expect("commit");
+        for &id in &blocks {
+            assert!(!fs.allocator.is_allocated(id).expect("harita"));
+        }
+    }
+}
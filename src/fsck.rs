@@ -0,0 +1,129 @@
+// src/fsck.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::BlockId;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+
+// --- 1. Bulunan Sorunlar ---
+
+/// Çevrimdışı tutarlılık denetiminin (bkz. `SadakFs::fsck`) bulduğu tek bir tutarsızlık.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsckProblem {
+    /// Checksum'u tutmayan ağaç düğümü veya inode; onarılamaz.
+    CorruptBlock(BlockId),
+    /// Bir yapının gösterdiği blok aygıtın dışında; onarılamaz.
+    OutOfRange(BlockId),
+    /// Başvurulan ama tahsis haritasında boş görünen ardışık bloklar (ilk blok, uzunluk);
+    /// onarımda dolu işaretlenir.
+    Unallocated { start: BlockId, length: u64 },
+    /// Tahsis haritasında dolu ama hiçbir yapının göstermediği ardışık bloklar (ilk blok,
+    /// uzunluk); onarımda serbest bırakılır.
+    Leaked { start: BlockId, length: u64 },
+    /// Paylaşım kaydı olmadan birden çok yerden başvurulan blok; onarılamaz.
+    DuplicateReference(BlockId),
+    /// Okunamayan veya türü uyuşmayan inode'u gösteren dizin girdisi; onarımda silinir.
+    /// `dir`, girdilerin anahtarlandığı dizin numarasıdır (bkz. `dir::DirEntry`).
+    DanglingEntry { dir: u64, name: String },
+    /// Inode'un bağlantı sayısı ona başvuran girdi sayısıyla uyuşmuyor; onarımda düzeltilir.
+    LinkCount { inode: BlockId, stored: u32, actual: u32 },
+    /// Dizinin `file_size` alanı girdi sayısıyla uyuşmuyor; onarımda düzeltilir.
+    DirectorySize { dir: BlockId, stored: u64, actual: u64 },
+    /// Inode'un kayıtlı veri ağacı yüksekliği ağacın gerçek yüksekliğiyle uyuşmuyor;
+    /// onarımda düzeltilir.
+    DataTreeHeight { inode: BlockId, stored: u8, actual: u8 },
+}
+
+impl FsckProblem {
+    /// Sorun `repair` ile giderilebilir mi?
+    pub fn is_repairable(&self) -> bool {
+        !matches!(self, FsckProblem::CorruptBlock(_) | FsckProblem::OutOfRange(_) | FsckProblem::DuplicateReference(_))
+    }
+}
+
+
+// --- 2. Denetim Raporu ---
+
+/// Tutarlılık denetiminin sonucu.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FsckReport {
+    /// Bulunan tutarsızlıklar, bulunma sırasıyla.
+    pub problems: Vec<FsckProblem>,
+    /// Denetlenen inode sayısı.
+    pub inodes: u64,
+    /// Bir yapının başvurduğu blok sayısı.
+    pub referenced_blocks: u64,
+    /// Giderilen sorun sayısı (yalnızca onarım istendiyse).
+    pub repaired: u64,
+    /// Tüm ağaçlar okunabildi mi? Okunamayan bir ağacın başvurduğu bloklar bilinmediğinden
+    /// `false` ise sızıntılar raporlanmaz ve serbest bırakılmaz.
+    pub complete: bool,
+}
+
+impl FsckReport {
+    /// Hiç tutarsızlık bulunmadı mı?
+    pub fn is_clean(&self) -> bool {
+        self.complete && self.problems.is_empty()
+    }
+}
+
+/// Denetim sırasında yapıların başvurduğu bloklar.
+pub struct BlockReferences {
+    total_blocks: u64,
+    blocks: BTreeSet<BlockId>,
+    /// Tek sahipli olması gerekirken ikinci kez başvurulan bloklar.
+    pub duplicates: Vec<BlockId>,
+    /// Aygıtın dışını gösteren başvurular.
+    pub out_of_range: Vec<BlockId>,
+}
+
+impl BlockReferences {
+    pub fn new(total_blocks: u64) -> Self {
+        BlockReferences { total_blocks, blocks: BTreeSet::new(), duplicates: Vec::new(), out_of_range: Vec::new() }
+    }
+
+    /// Tek sahibi olması gereken bloğa başvuru (metadata, inode, paylaşılmayan veri).
+    pub fn claim(&mut self, id: BlockId) {
+        if id >= self.total_blocks {
+            self.out_of_range.push(id);
+        } else if !self.blocks.insert(id) {
+            self.duplicates.push(id);
+        }
+    }
+
+    /// Birden çok sahibi olabilen bloğa başvuru (paylaşılan veri, kuyruk bloğu).
+    pub fn share(&mut self, id: BlockId) {
+        if id >= self.total_blocks {
+            self.out_of_range.push(id);
+        } else {
+            self.blocks.insert(id);
+        }
+    }
+
+    pub fn contains(&self, id: BlockId) -> bool {
+        self.blocks.contains(&id)
+    }
+
+    pub fn len(&self) -> u64 {
+        self.blocks.len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+/// Sıralı blok listesini ardışık aralıklara (ilk blok, uzunluk) böler.
+pub fn runs(blocks: &[BlockId]) -> Vec<(BlockId, u64)> {
+    let mut runs: Vec<(BlockId, u64)> = Vec::new();
+    for &id in blocks {
+        match runs.last_mut() {
+            Some((start, length)) if *start + *length == id => *length += 1,
+            _ => runs.push((id, 1)),
+        }
+    }
+    runs
+}
@@ -0,0 +1,81 @@
+// src/hotplug.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::sahne_syscalls::{self, ResourceHandle, SyscallError, SYSCALL_RESOURCE_RELEASE, raw_syscall};
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Sahne64'ün blok aygıt ekleme/çıkarma olaylarını yayınladığı bildirim kaynağı.
+pub const HOTPLUG_RESOURCE: &str = "sahne://events/block";
+
+// Olay kaydının boyutu: kind (4) + ayrılmış (4) + device_id (8)
+const EVENT_RECORD_SIZE: usize = 16;
+
+// Olay türleri (çekirdekteki karşılıklarıyla eşleşmelidir)
+const EVENT_KIND_REMOVED: u32 = 1;
+const EVENT_KIND_INSERTED: u32 = 2;
+
+/// Çekirdeğin bir fiziksel aygıta verdiği, çıkarılıp yeniden takılınca değişmeyen kimlik.
+pub type DeviceId = u64;
+
+/// Bildirim kaynağından okunan bir aygıt olayı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceEvent {
+    /// Aygıt haber verilmeden çıkarıldı (surprise removal).
+    Removed(DeviceId),
+    /// Aygıt (yeniden) takıldı.
+    Inserted(DeviceId),
+}
+
+
+// --- 2. Olay Dinleyici ---
+
+/// Sahne64 bildirim kaynağını dinleyip aygıt olaylarını RAID ve montaj katmanlarına
+/// iletilmek üzere okuyan yardımcı.
+pub struct HotplugListener {
+    /// Bildirim kaynağının handle'ı.
+    handle: ResourceHandle,
+}
+
+impl HotplugListener {
+    /// Blok aygıt bildirim kaynağını edinir.
+    pub fn open() -> Result<Self, SyscallError> {
+        let handle = sahne_syscalls::resource_acquire(HOTPLUG_RESOURCE.as_ptr(), HOTPLUG_RESOURCE.len())?;
+        Ok(HotplugListener { handle })
+    }
+
+    /// Bekleyen bir sonraki olayı okur; bekleyen olay yoksa `None`.
+    /// Bilinmeyen türdeki olaylar atlanır.
+    pub fn next_event(&self) -> Result<Option<DeviceEvent>, SyscallError> {
+        let mut record = [0u8; EVENT_RECORD_SIZE];
+        loop {
+            let read = sahne_syscalls::resource_read(self.handle, record.as_mut_ptr(), EVENT_RECORD_SIZE)?;
+            if read == 0 {
+                return Ok(None);
+            }
+            if read != EVENT_RECORD_SIZE {
+                return Err(SyscallError::EIO); // Yarım olay kaydı
+            }
+
+            let mut kind = [0u8; 4];
+            let mut device_id = [0u8; 8];
+            kind.copy_from_slice(&record[0..4]);
+            device_id.copy_from_slice(&record[8..16]);
+            let device_id = DeviceId::from_le_bytes(device_id);
+
+            match u32::from_le_bytes(kind) {
+                EVENT_KIND_REMOVED => return Ok(Some(DeviceEvent::Removed(device_id))),
+                EVENT_KIND_INSERTED => return Ok(Some(DeviceEvent::Inserted(device_id))),
+                _ => continue,
+            }
+        }
+    }
+}
+
+impl Drop for HotplugListener {
+    fn drop(&mut self) {
+        unsafe { raw_syscall(SYSCALL_RESOURCE_RELEASE, self.handle, 0, 0, 0, 0, 0) };
+    }
+}
@@ -0,0 +1,80 @@
+// src/import.rs
+
+#![allow(dead_code, unused_variables)]
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+
+// --- 1. Kaynak Dosya Sistemi ---
+
+/// Kaynak ağaçtaki bir girdinin türü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportKind {
+    Directory,
+    File,
+    Symlink,
+}
+
+/// Kaynak dizindeki tek bir girdi.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportEntry {
+    pub name: String,
+    pub kind: ImportKind,
+    /// Dosyanın bayt cinsinden boyutu (dizinler ve bağlantılar için yok sayılır).
+    pub size: u64,
+    /// POSIX izin bitleri (`MODE_MASK`); izin tutmayan kaynaklar (FAT) varsayılan verir.
+    pub mode: u16,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+/// SADAK birimine aktarılacak salt okunur kaynak dosya sistemi (ör. FAT veya ext2 sürücüsü).
+///
+/// Yollar kaynağın köküne göredir, `/` ile ayrılır; kök dizin boş yoldur.
+pub trait ImportSource {
+    type Error;
+
+    /// `path` dizinindeki girdiler (`.` ve `..` dahil edilmemelidir).
+    fn read_dir(&mut self, path: &str) -> Result<Vec<ImportEntry>, Self::Error>;
+
+    /// `path` dosyasının `offset`'ten başlayan verisini `buf`'a okur; okunan bayt sayısı
+    /// (dosya sonunda 0).
+    fn read(&mut self, path: &str, offset: u64, buf: &mut [u8]) -> Result<usize, Self::Error>;
+
+    /// `path` sembolik bağlantısının hedefi.
+    fn read_link(&mut self, path: &str) -> Result<String, Self::Error>;
+}
+
+
+// --- 2. İlerleme ve Hatalar ---
+
+/// İçe aktarmanın o ana kadarki ilerlemesi (bkz. `SadakFs::import_tree`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ImportProgress {
+    pub directories: u64,
+    pub files: u64,
+    pub symlinks: u64,
+    /// Hedefe yazılan dosya verisi (bayt).
+    pub bytes: u64,
+    /// Önceki (yarıda kalmış) bir aktarımda tamamlandığı için atlanan girdiler.
+    pub skipped: u64,
+}
+
+/// İçe aktarma hatası: kaynağın okunamaması veya hedefe yazılamaması.
+#[derive(Debug)]
+pub enum ImportError<S, T> {
+    Source(S),
+    Target(T),
+}
+
+/// Kaynak yolunu `name` ile uzatır.
+pub fn join(parent: &str, name: &str) -> String {
+    let mut path = String::with_capacity(parent.len() + 1 + name.len());
+    if !parent.is_empty() {
+        path.push_str(parent);
+        path.push('/');
+    }
+    path.push_str(name);
+    path
+}
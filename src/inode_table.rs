@@ -0,0 +1,71 @@
+// src/inode_table.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::BlockId;
+use crate::btree::{BTreeKey, ITEM_TYPE_INODE_MAP};
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Inode tablosu kayıtlarının metadata ağacındaki nesne kimliği (kuyruk bloğu kayıtlarının
+// `u64::MAX - 4` kimliğinin hemen altı; inode ID'leriyle çakışmaz).
+const INODE_TABLE_OBJECT: u64 = u64::MAX - 5;
+
+// Kayıt değerinin boyutu: block (8)
+const INODE_LOCATION_SIZE: usize = 8;
+
+// Kök dizinin inode numarası; biçimlendirmede ilk oluşturulan inode'dur.
+pub const ROOT_INO: u64 = 1;
+
+
+// --- 2. Inode Konumu ---
+
+/// Kalıcı bir inode numarasını inode'un güncel bloğuna eşleyen kayıt.
+///
+/// Inode numarası inode oluşturulurken Superblock'taki sayaçtan verilir ve inode
+/// yaşadığı sürece değişmez; inode bloğu taşınırsa (ör. `shrink`) yalnızca bu kayıt
+/// güncellenir. Kayıtlar metadata ağacında `(INODE_TABLE_OBJECT, ITEM_TYPE_INODE_MAP,
+/// inode numarası)` anahtarıyla tutulur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InodeLocation {
+    /// Inode numarası (anahtarın `offset` alanı; 0 numarasız eski inode'lar içindir).
+    pub ino: u64,
+    /// Inode'un bulunduğu blok.
+    pub block: BlockId,
+}
+
+impl InodeLocation {
+    /// `ino` numaralı inode'un kaydının anahtarı.
+    pub fn key(ino: u64) -> BTreeKey {
+        BTreeKey {
+            object_id: INODE_TABLE_OBJECT,
+            item_type: ITEM_TYPE_INODE_MAP,
+            offset: ino,
+        }
+    }
+
+    /// Anahtar bir inode tablosu kaydına mı ait?
+    pub fn is_location_key(key: &BTreeKey) -> bool {
+        key.object_id == INODE_TABLE_OBJECT && key.item_type == ITEM_TYPE_INODE_MAP
+    }
+
+    /// Kaydı B-Ağacı değer formatına yazar: block (LE).
+    pub fn encode(&self) -> [u8; INODE_LOCATION_SIZE] {
+        self.block.to_le_bytes()
+    }
+
+    /// Anahtar ve değerden kaydı çözer; kayıt değilse veya bozuksa `None`.
+    pub fn from_item(key: &BTreeKey, value: &[u8]) -> Option<Self> {
+        if !Self::is_location_key(key) || value.len() != INODE_LOCATION_SIZE {
+            return None;
+        }
+
+        let mut block = [0u8; 8];
+        block.copy_from_slice(value);
+        Some(InodeLocation {
+            ino: key.offset,
+            block: BlockId::from_le_bytes(block),
+        })
+    }
+}
@@ -0,0 +1,114 @@
+// src/journal.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockId, BLOCK_SIZE};
+use crate::checksum::{checksum_data, checksum_excluding};
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ---
+
+// Günlük başlık bloğunun sihirli sayısı ("SJRN").
+const JOURNAL_MAGIC: u32 = 0x534A_524E;
+
+// Başlık: magic (4) + checksum (4) + count (4) + ayrılmış (4) + seq (8) + timestamp (8)
+const JOURNAL_HEADER_SIZE: usize = 32;
+
+// Tanımlayıcı: hedef blok (8) + görüntünün checksum'u (4) + ayrılmış (4)
+const JOURNAL_DESCRIPTOR_SIZE: usize = 16;
+
+/// Tek günlük kesimine (segment) alınabilecek en fazla yerinde yazılan blok. Daha büyük
+/// commit'ler için günlük bölgesi birden çok kesime büyütülür (bkz.
+/// `Superblock::journal_segments`).
+pub const JOURNAL_CAPACITY: usize = (BLOCK_SIZE - JOURNAL_HEADER_SIZE) / JOURNAL_DESCRIPTOR_SIZE;
+
+/// Bir günlük kesiminin blok sayısı: başlık bloğu ve ardından blok görüntüleri.
+pub const JOURNAL_BLOCKS: u64 = 1 + JOURNAL_CAPACITY as u64;
+
+
+// --- 2. Günlük Başlığı ---
+
+/// Metadata niyet günlüğünün (intent journal) başlık bloğu.
+///
+/// Yerinde yazılan bloklar (inode'lar, tahsis haritası) CoW ile korunmaz; bir commit'in
+/// bu bloklara yapacağı yazmalar önce blok görüntüleri olarak günlüğe yazılır. Superblock
+/// günlüğün `seq` değerini gösterdikten sonra bloklar yerine yazılır. Bu yazmalar
+/// yarıda kalırsa montaj görüntüleri yeniden uygular; böylece çok bloklu işlemler
+/// (rename, unlink) yarım kalmaz.
+///
+/// Kesim düzeni: `[başlık][görüntü 0]...[görüntü count-1]`. Bölge ardışık kesimlerden
+/// oluşur; bir commit'in kesimlerinin hepsi aynı `seq` ve `timestamp`'i taşır.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JournalHeader {
+    /// Günlüğü yazan commit'in sırası (`Superblock::journal_seq`).
+    pub seq: u64,
+    /// Günlüğü yazan commit'in Superblock zaman damgası. Alanı tanımayan bir sürüm
+    /// sonradan commit ettiyse eşleşmez ve eski görüntüler uygulanmaz.
+    pub timestamp: u64,
+    /// Hedef bloklar ve görüntülerinin checksum'ları, bölgedeki sırayla.
+    pub blocks: Vec<(BlockId, u32)>,
+}
+
+impl JournalHeader {
+    /// Görüntüleri zaten yerine yazılmış (uygulanacak bir şey kalmamış) başlık.
+    pub fn applied(seq: u64, timestamp: u64) -> Self {
+        JournalHeader { seq, timestamp, blocks: Vec::new() }
+    }
+
+    /// `data` görüntüsünü `id` bloğu için başlığa ekler.
+    pub fn push(&mut self, id: BlockId, data: &[u8]) {
+        self.blocks.push((id, checksum_data(data)));
+    }
+
+    /// Görüntü `index`, başlıktaki checksum'la uyuşuyor mu? (Yarım yazılmış günlük)
+    pub fn image_valid(&self, index: usize, data: &[u8]) -> bool {
+        self.blocks.get(index).map_or(false, |&(_, crc)| checksum_data(data) == crc)
+    }
+
+    /// Başlığı bir blok boyutundaki `out` tamponuna yazar.
+    pub fn encode(&self, out: &mut [u8]) {
+        out.fill(0);
+        out[0..4].copy_from_slice(&JOURNAL_MAGIC.to_le_bytes());
+        out[8..12].copy_from_slice(&(self.blocks.len() as u32).to_le_bytes());
+        out[16..24].copy_from_slice(&self.seq.to_le_bytes());
+        out[24..32].copy_from_slice(&self.timestamp.to_le_bytes());
+        for (i, &(id, crc)) in self.blocks.iter().enumerate() {
+            let at = JOURNAL_HEADER_SIZE + i * JOURNAL_DESCRIPTOR_SIZE;
+            out[at..at + 8].copy_from_slice(&id.to_le_bytes());
+            out[at + 8..at + 12].copy_from_slice(&crc.to_le_bytes());
+        }
+        let crc = checksum_excluding(&out[..BLOCK_SIZE], 4, 4);
+        out[4..8].copy_from_slice(&crc.to_le_bytes());
+    }
+
+    /// Bir başlık bloğunu çözer; magic veya checksum uyuşmazsa `None`.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < BLOCK_SIZE {
+            return None;
+        }
+        let u32_at = |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+        let u64_at = |at: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data[at..at + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        let count = u32_at(8) as usize;
+        if u32_at(0) != JOURNAL_MAGIC || u32_at(4) != checksum_excluding(&data[..BLOCK_SIZE], 4, 4) || count > JOURNAL_CAPACITY {
+            return None;
+        }
+
+        let blocks = (0..count)
+            .map(|i| {
+                let at = JOURNAL_HEADER_SIZE + i * JOURNAL_DESCRIPTOR_SIZE;
+                (u64_at(at), u32_at(at + 8))
+            })
+            .collect();
+        Some(JournalHeader { seq: u64_at(16), timestamp: u64_at(24), blocks })
+    }
+
+    /// Günlük, Superblock'un gösterdiği son commit'e mi ait ve uygulanmamış görüntü var mı?
+    pub fn needs_replay(&self, seq: u64, timestamp: u64) -> bool {
+        self.seq == seq && self.timestamp == timestamp && !self.blocks.is_empty()
+    }
+}
@@ -0,0 +1,197 @@
+// src/kv.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::BlockDevice;
+use crate::btree::{BTreeKey, BTREE_VALUE_MAX, ITEM_TYPE_KV_ITEM, ITEM_TYPE_KV_KEYSPACE};
+use crate::fs::{SadakFs, SadakFsError};
+use core::fmt::Debug;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Anahtar alanı kayıtlarının tutulduğu nesne kimliği. Anahtar alanı kimlikleri 1'den başlar.
+const KEYSPACE_REGISTRY_OBJECT: u64 = 0;
+
+// Bir anahtar alanı adının bayt cinsinden en büyük uzunluğu.
+pub const KEYSPACE_NAME_MAX: usize = BTREE_VALUE_MAX;
+
+// Bir değerin bayt cinsinden en büyük uzunluğu (tek B-Ağacı öğesine sığmalı).
+pub const KV_VALUE_MAX: usize = BTREE_VALUE_MAX;
+
+
+// --- 2. Anahtar-Değer Deposu ---
+
+/// Metadata motoru (CoW B-Ağacı ve tahsis yöneticisi) üzerine kurulu, adlandırılmış
+/// bir anahtar alanı. Sahne64 sistem servisleri küçük yapısal verilerini kendi dosya
+/// formatlarını icat etmeden bununla saklayabilir.
+///
+/// Öğeler metadata ağacında `(anahtar alanı kimliği, ITEM_TYPE_KV_ITEM, anahtar)`
+/// anahtarıyla tutulur. Değişiklikler dosya sisteminin bir sonraki commit'iyle kalıcı olur.
+pub struct KvStore<'a, D: BlockDevice> {
+    fs: &'a SadakFs<D>,
+    /// Anahtar alanının kimliği (öğe anahtarlarının `object_id` alanı).
+    keyspace: u64,
+}
+
+impl<'a, D> KvStore<'a, D>
+where
+    D: BlockDevice + Debug + 'static,
+{
+    /// `name` adlı anahtar alanını açar; yoksa oluşturur.
+    pub fn open(fs: &'a SadakFs<D>, name: &str) -> Result<Self, SadakFsError<D>> {
+        if name.is_empty() || name.len() > KEYSPACE_NAME_MAX {
+            return Err(SadakFsError::InvalidName);
+        }
+
+        let keyspace = fs.update_metadata(|tree, allocator| {
+            // Kayıtları tara: ad bulunursa kimliğini, yoksa sıradaki boş kimliği al
+            let mut found = None;
+            let mut next_id = 1;
+            tree.walk_from(&registry_key(0), &mut |key, value| {
+                if key.object_id != KEYSPACE_REGISTRY_OBJECT || key.item_type != ITEM_TYPE_KV_KEYSPACE {
+                    return false;
+                }
+                next_id = key.offset + 1;
+                if value == name.as_bytes() {
+                    found = Some(key.offset);
+                    return false;
+                }
+                true
+            })?;
+
+            match found {
+                Some(id) => Ok(id),
+                None => {
+                    tree.insert_entry(allocator, &registry_key(next_id), name.as_bytes())?;
+                    Ok(next_id)
+                }
+            }
+        })?;
+
+        Ok(KvStore { fs, keyspace })
+    }
+
+    /// Anahtarın değerini döndürür; anahtar yoksa `None`.
+    pub fn get(&self, key: u64) -> Result<Option<Vec<u8>>, SadakFsError<D>> {
+        Ok(self.fs.metadata_tree().lookup(&self.item_key(key))?)
+    }
+
+    /// Anahtara değer yazar; anahtar varsa değeri değiştirilir.
+    pub fn put(&self, key: u64, value: &[u8]) -> Result<(), SadakFsError<D>> {
+        let mut txn = self.transaction();
+        txn.put(key, value)?;
+        self.apply(&txn.ops)
+    }
+
+    /// Anahtarı siler.
+    ///
+    /// # Döndürür
+    /// Anahtar vardıysa `true`.
+    pub fn delete(&self, key: u64) -> Result<bool, SadakFsError<D>> {
+        let item_key = self.item_key(key);
+        self.fs.update_metadata(|tree, allocator| Ok(tree.remove_entry(allocator, &item_key)?.is_some()))
+    }
+
+    /// `start` anahtarından başlayarak öğeleri anahtar sırasıyla ziyaret eder.
+    /// `visit` `false` döndürdüğünde tarama durur.
+    pub fn scan<F: FnMut(u64, &[u8]) -> bool>(&self, start: u64, visit: &mut F) -> Result<(), SadakFsError<D>> {
+        let keyspace = self.keyspace;
+        self.fs.metadata_tree().walk_from(&self.item_key(start), &mut |key, value| {
+            if key.object_id != keyspace || key.item_type != ITEM_TYPE_KV_ITEM {
+                return false;
+            }
+            visit(key.offset, value)
+        })?;
+        Ok(())
+    }
+
+    /// Birden çok değişikliği bir arada uygulayan yeni bir işlem başlatır.
+    pub fn transaction(&self) -> KvTransaction<'_, 'a, D> {
+        KvTransaction {
+            store: self,
+            ops: BTreeMap::new(),
+        }
+    }
+
+    /// Değişiklikleri dosya sistemi kilidi altında, arada commit olmadan uygular.
+    fn apply(&self, ops: &BTreeMap<u64, Option<Vec<u8>>>) -> Result<(), SadakFsError<D>> {
+        self.fs.update_metadata(|tree, allocator| {
+            for (&key, value) in ops {
+                let item_key = self.item_key(key);
+                match value {
+                    Some(value) => tree.insert_entry(allocator, &item_key, value)?,
+                    None => {
+                        tree.remove_entry(allocator, &item_key)?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Bu anahtar alanındaki `key` öğesinin metadata ağacı anahtarı.
+    fn item_key(&self, key: u64) -> BTreeKey {
+        BTreeKey {
+            object_id: self.keyspace,
+            item_type: ITEM_TYPE_KV_ITEM,
+            offset: key,
+        }
+    }
+}
+
+/// `id` kimlikli anahtar alanının kayıt anahtarı.
+fn registry_key(id: u64) -> BTreeKey {
+    BTreeKey {
+        object_id: KEYSPACE_REGISTRY_OBJECT,
+        item_type: ITEM_TYPE_KV_KEYSPACE,
+        offset: id,
+    }
+}
+
+
+// --- 3. İşlemler (Transactions) ---
+
+/// Bir anahtar alanında biriktirilen değişiklikler. `commit` ile hepsi birlikte
+/// uygulanıp kalıcı yapılır; çökme sonrası ya hepsi ya hiçbiri görünür. İşlem
+/// commit edilmeden bırakılırsa değişiklikler atılır.
+pub struct KvTransaction<'s, 'a, D: BlockDevice> {
+    store: &'s KvStore<'a, D>,
+    /// Anahtar -> yeni değer (`None` = silme).
+    ops: BTreeMap<u64, Option<Vec<u8>>>,
+}
+
+impl<'s, 'a, D> KvTransaction<'s, 'a, D>
+where
+    D: BlockDevice + Debug + 'static,
+{
+    /// İşleme bir yazma ekler.
+    pub fn put(&mut self, key: u64, value: &[u8]) -> Result<(), SadakFsError<D>> {
+        if value.len() > KV_VALUE_MAX {
+            return Err(SadakFsError::ValueTooLarge);
+        }
+        self.ops.insert(key, Some(value.to_vec()));
+        Ok(())
+    }
+
+    /// İşleme bir silme ekler.
+    pub fn delete(&mut self, key: u64) {
+        self.ops.insert(key, None);
+    }
+
+    /// Anahtarın işlem içinden görünen değeri (işlemin kendi yazmaları dahil).
+    pub fn get(&self, key: u64) -> Result<Option<Vec<u8>>, SadakFsError<D>> {
+        match self.ops.get(&key) {
+            Some(value) => Ok(value.clone()),
+            None => self.store.get(key),
+        }
+    }
+
+    /// Değişiklikleri uygular ve dosya sistemini commit ederek kalıcı yapar.
+    pub fn commit(self) -> Result<(), SadakFsError<D>> {
+        self.store.apply(&self.ops)?;
+        self.store.fs.sync()
+    }
+}
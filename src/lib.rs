@@ -8,6 +8,14 @@
 #![no_main] 
 // Geliştirme aşamasında yardımcı olması için kullanılmayan uyarıları kaldır.
 #![allow(dead_code, unused_variables)] 
+// `no_panic` kurulumlarında kütüphane kodunda `unwrap`/`expect` derlenmez; hatalar
+// `Result` olarak yukarı taşınmalıdır (testler `clippy.toml` ile muaftır).
+// `clippy::indexing_slicing` bilerek kapının dışındadır: blok düzenleri sabit boyutlu
+// arabelleklerde sabit konumlarla dilimlenir ve lint bu sınırları göremez. Diskten gelen
+// konum ve uzunluklar kullanılmadan önce çözülürken doğrulanır (ör. `TailFragment::from_item`);
+// doğrulanmamış bir değerle dilimleme `get` ile yapılır ve bozulma hatası olarak döner (ör.
+// `read_compressed_in`).
+#![cfg_attr(feature = "no_panic", deny(clippy::unwrap_used, clippy::expect_used))]
 
 // 'alloc' kütüphanesini dahil et. Bu, Vec, Box, Arc gibi dinamik koleksiyonları
 // no-std ortamında kullanmamızı sağlar (ancak tahsisçinin çekirdek tarafından sağlanması gerekir).
@@ -19,23 +27,128 @@ extern crate alloc;
 // Sahne64 sistem çağrılarını sarmalayan düşük seviyeli I/O modülü.
 pub mod sahne_syscalls;
 
+// Motorun kilit, bellek, zaman ve görev ilkelleri; Sahne64 ya da taşınabilir (host) arka uç.
+pub mod platform;
+
 // Disk I/O'yu soyutlayan temel katman (HDD, SSD, vb.).
 pub mod block_device;
 
 // RAID-1 (Mirroring) uygulamasını BlockDevice trait'i üzerine kurar.
+#[cfg(feature = "raid")]
 pub mod raid;
 
-// Metadata bütünlüğü için CRC32C Checksum hesaplama modülü.
+// Sanal makinelerde virtio-blk kuyruğunu doğrudan süren blok aygıt uygulaması.
+pub mod virtio;
+
+// Çok kuyruklu NVMe denetleyicisini doğrudan süren blok aygıt uygulaması.
+pub mod nvme;
+
+// Aynı depolamaya ulaşan birden çok yolu yol yedeklemesiyle (failover) tek aygıt olarak sunar.
+pub mod multipath;
+
+// Birimin tüm bloklarını AES-256-XTS ile şifreleyen, parola korumalı anahtar yuvalı BlockDevice sarmalayıcısı.
+pub mod crypt;
+
+// Sahne64 bildirim kaynağından blok aygıt ekleme/çıkarma olaylarını okuyan dinleyici.
+pub mod hotplug;
+
+// Sahne64 kaynak yollarını yoklayıp birimleri UUID/etikete göre bulan yardımcı.
+pub mod discovery;
+
+// Blok işlemlerini kaydeden ve yakalanan izi yeniden oynatan hata ayıklama aygıtları.
+pub mod trace;
+
+// Metadata bütünlüğü için checksum algoritmaları (CRC32C, xxHash64, SHA-256) ve nesne türü başına seçimi.
 pub mod checksum;
 
+// Mühürlü (salt okunur) birimlerin SHA-256 Merkle ağacı ve okuma doğrulayıcısı.
+pub mod seal;
+
 // Blok önbelleği, kilit yönetimi ve bellek tahsisini yöneten modül.
 pub mod cache; 
 
 // Copy-on-Write için temel B-Ağacı (B-Tree) yapıları.
 pub mod btree;
 
+// Dosya veri ağacındaki extent kayıtları ve önbelleği atlayan akış okuyucusu.
+pub mod extent;
+
+// Dosya verisinin LZ4 sıkıştırıcısı ve sıkıştırılabilirlik tahmini.
+pub mod compress;
+
+// zstd çerçeve biçiminde sıkıştırıcı ve çözücü (arşiv birimleri için yüksek oranlı sıkıştırma).
+pub mod zstd;
+
+// Küçük dosyaların son bloklarını paylaşılan kuyruk bloklarında tutan kayıt formatları.
+pub mod tail;
+
+// Dosya verisi yazmalarını disk bloğu tahsis etmeden biriktiren sayfa önbelleği.
+pub mod page_cache;
+
+// Metadata ağacında tutulan dizin girdisi formatı.
+pub mod dir;
+
+// Metadata ağacında tutulan genişletilmiş öznitelik (xattr) formatı.
+pub mod xattr;
+
+// Açıkken silinen inode'ların çökme sonrası geri kazanımı için yetim listesi kayıt formatı.
+pub mod orphan;
+
+// Tespit edilen checksum bozukluklarının sınırlı adli kayıt günlüğü ve okuma onarımı sayaçları.
+pub mod forensics;
+
+// Kalıcı inode numaralarını inode bloklarına eşleyen inode tablosu kayıt formatı.
+pub mod inode_table;
+
+// Yerinde yazılan metadata bloklarının çökme sonrası yeniden uygulanması için niyet günlüğü formatı.
+pub mod journal;
+
+// no_std ortamı için yol (path) ayrıştırıcı.
+pub mod path;
+
+// Kurulum ve konteyner ad alanları için dizin iskeleti tanımı ve ayrıştırıcısı.
+pub mod skeleton;
+
+// FAT/ext2 gibi kaynak dosya sistemlerinden SADAK'a ağaç aktarımı için kaynak arayüzü.
+pub mod import;
+
 // Disk üzerindeki boş/dolu blokların yönetimini yapan Tahsis Yöneticisi.
 pub mod allocator;
 
+// Snapshot'ları salt okunur sanal blok aygıtı olarak dışa aktaran modül.
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+
+// Snapshot dönemleri arasında değişen blokların kayıt formatı (yedekleme için).
+pub mod changes;
+
+// Snapshot'ları başka bir aygıta taşıyan send/receive çoğaltma akışının biçimi ve uçları.
+pub mod replication;
+
+// Çevrimdışı tutarlılık denetiminin (fsck) rapor ve başvuru takibi türleri.
+pub mod fsck;
+
+// Aynı birimde bağımsız ad alanları (alt birimler) için kayıt formatı.
+pub mod subvolume;
+
+// Kullanıcı ve alt birim başına blok/inode kotaları.
+pub mod quota;
+
+// Görev başına I/O hesabı ve bant genişliği/IOPS sınırları.
+pub mod accounting;
+
+// Zaman damgaları için değiştirilebilir zaman kaynağı (çekirdek saati, sayaç, test saati).
+pub mod clock;
+
 // SADAK'ın ana yapısını, Superblock'u ve dosya sistemi API'lerini içerir.
-pub mod fs;
\ No newline at end of file
+pub mod fs;
+
+// Metadata motoru üzerine kurulu adlandırılmış anahtar-değer deposu.
+pub mod kv;
+
+// Dış tüketiciler için sürümlenmiş genel API cephesi (motor ve Sahne64 arka ucu ayrımı).
+pub mod api;
+
+// Crash-simülasyon testleri için mantıksal durum özeti (yalnızca host özelliğiyle).
+#[cfg(feature = "host")]
+pub mod oracle;
\ No newline at end of file
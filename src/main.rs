@@ -8,7 +8,7 @@
 extern crate alloc; 
 
 use sadak_fs::{
-    fs::SadakFs,
+    fs::{FormatOptions, SadakFs},
     block_device::{BlockDevice, BlockId, BLOCK_SIZE},
     sahne_syscalls::SyscallError,
     raid::Raid1Device
@@ -94,7 +94,7 @@ pub extern "C" fn sadak_entry_point() -> ! {
     match Raid1Device::new(vec![device1, device2]) {
         Ok(raid_device) => {
             // RAID cihazı üzerinde SADAK'ı formatla
-            let sadak_result = SadakFs::format(raid_device);
+            let sadak_result = SadakFs::format(raid_device, FormatOptions::default());
             
             match sadak_result {
                 Ok(fs) => {
@@ -0,0 +1,213 @@
+// src/multipath.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE, BlockDeviceError};
+use crate::sahne_syscalls::SyscallError;
+use core::fmt::Debug;
+use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use alloc::vec;
+use alloc::vec::Vec;
+
+
+// --- 1. Multipath Hata Türü ---
+
+/// Çok yollu aygıt işlemleri sırasında ortaya çıkabilecek hatalar.
+#[derive(Debug)]
+pub enum MultipathError<D: BlockDevice> {
+    /// Denenen tüm yollarda I/O hatası oluştu (yol başına bir hata).
+    IoError(Vec<D::Error>),
+    /// Hiç yol verilmedi.
+    NoPaths,
+    /// Yollar farklı boyutta bir depolama gösteriyor (aynı aygıta ulaşmıyorlar).
+    SizeMismatch,
+    /// Kullanılabilir (etkin) yol kalmadı.
+    NoActivePaths,
+    /// Dahili sistem çağrısı hatası.
+    Syscall(SyscallError),
+}
+
+impl<D: BlockDevice + Debug> BlockDeviceError for MultipathError<D> {}
+
+impl<D: BlockDevice> From<SyscallError> for MultipathError<D> {
+    fn from(e: SyscallError) -> Self {
+        MultipathError::Syscall(e)
+    }
+}
+
+
+// --- 2. Multipath Yapısı ---
+
+// Yol durumları
+const PATH_ACTIVE: u8 = 0;
+// Yol hata verdi; `check_paths` yeniden yoklayana kadar kullanılmaz.
+const PATH_FAILED: u8 = 1;
+
+/// Okumaların yollara dağıtılma biçimi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathPolicy {
+    /// Tüm istekler birincil yoldan gider; yalnızca hata durumunda sıradakine geçilir.
+    Failover,
+    /// Okumalar etkin yollar arasında sırayla dağıtılır. Yazmalar ve flush, sıralamanın
+    /// korunması için yine birincil yoldan gider.
+    RoundRobin,
+}
+
+/// Aynı depolamaya ulaşan birden çok yolu (ör. iki denetleyici üzerinden açılmış iki
+/// Sahne64 kaynağı) tek bir aygıt gibi sunan BlockDevice uygulaması.
+///
+/// Hata veren yol devre dışı bırakılır ve istek sıradaki etkin yolda yeniden denenir;
+/// çağıran yalnızca tüm yollar başarısız olursa hata görür. Devre dışı yollar
+/// `check_paths` ile periyodik olarak yoklanır ve yanıt verirse yeniden kullanılır.
+/// RAID-1'in aksine veri tek kopyadır; yollar yalnızca ona ulaşma biçimidir.
+pub struct MultipathDevice<D: BlockDevice> {
+    /// Aynı depolamaya ulaşan yollar; dizideki sıra tercih sırasıdır.
+    paths: Vec<D>,
+    /// Her yolun durumu (`PATH_*`).
+    states: Vec<AtomicU8>,
+    /// Yazma ve flush için kullanılan birincil yolun indeksi.
+    primary: AtomicUsize,
+    /// Sıradaki okumanın gönderileceği yol (yalnızca `RoundRobin`).
+    next_read: AtomicUsize,
+    /// Okuma dağıtım politikası.
+    policy: PathPolicy,
+    /// Depolamanın toplam blok sayısı.
+    total_blocks: BlockId,
+}
+
+impl<D: BlockDevice> MultipathDevice<D> {
+    /// Yollardan yeni bir çok yollu aygıt oluşturur. Tüm yollar aynı boyutu
+    /// bildirmelidir; ilk yol birincil olur.
+    pub fn new(paths: Vec<D>, policy: PathPolicy) -> Result<Self, MultipathError<D>> {
+        let total_blocks = match paths.first() {
+            Some(path) => path.total_blocks(),
+            None => return Err(MultipathError::NoPaths),
+        };
+        if paths.iter().any(|path| path.total_blocks() != total_blocks) {
+            return Err(MultipathError::SizeMismatch);
+        }
+
+        let states = paths.iter().map(|_| AtomicU8::new(PATH_ACTIVE)).collect();
+        Ok(MultipathDevice {
+            paths,
+            states,
+            primary: AtomicUsize::new(0),
+            next_read: AtomicUsize::new(0),
+            policy,
+            total_blocks,
+        })
+    }
+
+    /// Devre dışı yolları Superblock'u (blok 0) okuyarak yoklar; yanıt verenleri yeniden
+    /// etkinleştirir. Birincil yol devre dışıysa ilk etkin yol birincil olur.
+    ///
+    /// # Döndürür
+    /// Etkin yol sayısı.
+    pub fn check_paths(&self) -> usize {
+        let mut buffer = vec![0u8; BLOCK_SIZE];
+        for (index, path) in self.paths.iter().enumerate() {
+            if !self.is_active(index) && path.read_block(0, &mut buffer).is_ok() {
+                self.states[index].store(PATH_ACTIVE, Ordering::Release);
+            }
+        }
+
+        if !self.is_active(self.primary.load(Ordering::Acquire)) {
+            if let Some(index) = (0..self.paths.len()).find(|&i| self.is_active(i)) {
+                self.primary.store(index, Ordering::Release);
+            }
+        }
+        self.active_paths()
+    }
+
+    /// Etkin yol sayısı.
+    pub fn active_paths(&self) -> usize {
+        self.states.iter().filter(|s| s.load(Ordering::Acquire) == PATH_ACTIVE).count()
+    }
+
+    /// Şu anki birincil yolun indeksi.
+    pub fn primary_path(&self) -> usize {
+        self.primary.load(Ordering::Acquire)
+    }
+
+    /// En az bir yol devre dışı mı?
+    pub fn is_degraded(&self) -> bool {
+        self.active_paths() < self.paths.len()
+    }
+
+    fn is_active(&self, index: usize) -> bool {
+        self.states[index].load(Ordering::Acquire) == PATH_ACTIVE
+    }
+
+    /// İsteği `first` yolundan başlayarak etkin yollarda sırayla dener. Hata veren yol
+    /// devre dışı bırakılır; birincil yolsa sıradaki başarılı yol birincil olur.
+    fn with_failover<F>(&self, first: usize, mut op: F) -> Result<(), MultipathError<D>>
+    where
+        F: FnMut(&D) -> Result<(), D::Error>,
+    {
+        let mut errors = Vec::new();
+        for offset in 0..self.paths.len() {
+            let index = (first + offset) % self.paths.len();
+            if !self.is_active(index) {
+                continue;
+            }
+            match op(&self.paths[index]) {
+                Ok(()) => {
+                    if !self.is_active(self.primary.load(Ordering::Acquire)) {
+                        self.primary.store(index, Ordering::Release);
+                    }
+                    return Ok(());
+                }
+                Err(e) => {
+                    self.states[index].store(PATH_FAILED, Ordering::Release);
+                    errors.push(e);
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Err(MultipathError::NoActivePaths)
+        } else {
+            Err(MultipathError::IoError(errors))
+        }
+    }
+
+    /// Okumanın başlayacağı yol.
+    fn read_path(&self) -> usize {
+        match self.policy {
+            PathPolicy::Failover => self.primary.load(Ordering::Acquire),
+            PathPolicy::RoundRobin => self.next_read.fetch_add(1, Ordering::Relaxed) % self.paths.len(),
+        }
+    }
+}
+
+
+// --- 3. BlockDevice Trait'inin Uygulanması ---
+
+impl<D: BlockDevice + Debug + Sync + Send + 'static> BlockDevice for MultipathDevice<D> {
+    type Error = MultipathError<D>;
+
+    fn read_block(&self, id: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.with_failover(self.read_path(), |path| path.read_block(id, buffer))
+    }
+
+    fn write_block(&self, id: BlockId, data: &[u8]) -> Result<(), Self::Error> {
+        // Blok yazması idempotenttir; başka yoldan yeniden denemek güvenlidir
+        self.with_failover(self.primary.load(Ordering::Acquire), |path| path.write_block(id, data))
+    }
+
+    fn total_blocks(&self) -> BlockId {
+        self.total_blocks
+    }
+
+    fn read_blocks(&self, start: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.with_failover(self.read_path(), |path| path.read_blocks(start, buffer))
+    }
+
+    fn write_batch(&self, blocks: &[(BlockId, &[u8])]) -> Result<(), Self::Error> {
+        self.with_failover(self.primary.load(Ordering::Acquire), |path| path.write_batch(blocks))
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.with_failover(self.primary.load(Ordering::Acquire), |path| path.flush())
+    }
+}
@@ -0,0 +1,535 @@
+// src/nvme.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockDevice, BlockId, BufferRequirements, BLOCK_SIZE};
+use crate::platform::SysLock;
+use crate::virtio::DmaRegion;
+use crate::sahne_syscalls::{
+    self, ResourceHandle, SyscallError,
+    SYSCALL_NOTIFICATION_WAIT, SYSCALL_RESOURCE_MAP, SYSCALL_RESOURCE_RELEASE, SYSCALL_TASK_SLEEP,
+    raw_syscall
+};
+use core::cell::UnsafeCell;
+use core::ptr;
+use core::sync::atomic::{fence, Ordering};
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler (NVMe 1.4, PCIe taşıyıcısı) ---
+
+// Denetleyici yazmaç ofsetleri (BAR0)
+const REG_CAP: usize = 0x00;
+const REG_CC: usize = 0x14;
+const REG_CSTS: usize = 0x1c;
+const REG_AQA: usize = 0x24;
+const REG_ASQ: usize = 0x28;
+const REG_ACQ: usize = 0x30;
+const REG_DOORBELL_BASE: usize = 0x1000;
+
+// Eşlenen BAR0 penceresi; yazmaçlar ve en fazla `NVME_MAX_IO_QUEUES` kuyruğun kapı zilleri
+const BAR_WINDOW_SIZE: usize = 0x4000;
+
+// CC alanları: etkin, NVM komut kümesi, 4 KiB bellek sayfası, 64 baytlık SQ ve 16 baytlık CQ girdileri
+const CC_ENABLE: u32 = 1;
+const CC_IOSQES: u32 = 6 << 16;
+const CC_IOCQES: u32 = 4 << 20;
+const CSTS_READY: u32 = 1;
+const CSTS_FATAL: u32 = 2;
+
+// Denetleyicinin hazır olmasını beklerken işlemciyi bırakma sayısı üst sınırı
+const READY_POLL_LIMIT: u32 = 1_000_000;
+
+// Yönetim (admin) komutları
+const ADMIN_CREATE_IO_SQ: u8 = 0x01;
+const ADMIN_CREATE_IO_CQ: u8 = 0x05;
+const ADMIN_IDENTIFY: u8 = 0x06;
+const ADMIN_SET_FEATURES: u8 = 0x09;
+const FEATURE_NUMBER_OF_QUEUES: u32 = 0x07;
+const IDENTIFY_NAMESPACE: u32 = 0;
+
+// NVM komutları
+const NVM_FLUSH: u8 = 0x00;
+const NVM_WRITE: u8 = 0x01;
+const NVM_READ: u8 = 0x02;
+
+const SQ_ENTRY_SIZE: usize = 64;
+const CQ_ENTRY_SIZE: usize = 16;
+
+// Yönetim kuyruğu uzunluğu (yalnızca kurulumda kullanılır)
+const ADMIN_QUEUE_SIZE: u16 = 16;
+
+// G/Ç kuyruğu başına girdi sayısı; denetleyici daha azını destekliyorsa onun sınırı kullanılır.
+pub const NVME_QUEUE_DEPTH: u16 = 64;
+
+// En fazla G/Ç kuyruk çifti sayısı.
+pub const NVME_MAX_IO_QUEUES: u16 = 16;
+
+
+// --- 2. Kuyruk Çifti ---
+
+/// Bir gönderim (SQ) ve tamamlanma (CQ) kuyruğu çifti ve her girdiye ayrılmış veri
+/// ara belleği. Komut kimliği (CID) aynı zamanda ara bellek yuvasının indeksidir.
+struct QueuePair {
+    /// Kuyruk kimliği (0 = yönetim kuyruğu); kesme vektörü de budur.
+    id: u16,
+    /// Girdi sayısı.
+    size: u16,
+    sq: DmaRegion,
+    cq: DmaRegion,
+    /// `size` blokluk veri ara belleği (yönetim kuyruğunda tek blok).
+    buffers: DmaRegion,
+    /// Kuyruk işaretçileri; yalnızca `lock` altında.
+    state: UnsafeCell<QueueState>,
+    /// Kuyruğu kullanan görevleri sıralayan çekirdek kilidi.
+    lock: SysLock,
+}
+
+#[derive(Debug)]
+struct QueueState {
+    sq_tail: u16,
+    cq_head: u16,
+    /// Tamamlanma girdisinin yeni olduğunu gösteren faz biti; her turda tersine döner.
+    phase: bool,
+}
+
+/// Bir kuyruğa verilecek tek blokluk işlem.
+enum NvmeOp<'a> {
+    Read(BlockId, &'a mut [u8]),
+    Write(BlockId, &'a [u8]),
+    /// Fiziksel adresi verilen çağıran arabelleğine doğrudan (kopyasız) okuma.
+    ReadDirect(BlockId, u64),
+    /// Fiziksel adresi verilen çağıran arabelleğinden doğrudan (kopyasız) yazma.
+    WriteDirect(BlockId, u64),
+    Flush,
+}
+
+impl QueuePair {
+    fn new(id: u16, size: u16, buffer_blocks: usize) -> Result<Self, SyscallError> {
+        Ok(QueuePair {
+            id,
+            size,
+            sq: DmaRegion::allocate(size as usize * SQ_ENTRY_SIZE)?,
+            cq: DmaRegion::allocate(size as usize * CQ_ENTRY_SIZE)?,
+            buffers: DmaRegion::allocate(buffer_blocks * BLOCK_SIZE)?,
+            state: UnsafeCell::new(QueueState { sq_tail: 0, cq_head: 0, phase: true }),
+            lock: SysLock::new()?,
+        })
+    }
+
+    /// Gönderim kuyruğunun sonuna bir komut yazar (kapı zili çalınmaz).
+    /// Çağıran `lock`'u tutmalıdır.
+    fn push_locked(&self, opcode: u8, cid: u16, nsid: u32, prp1: u64, cdw: [u32; 6]) {
+        let state = unsafe { &mut *self.state.get() };
+        let entry = self.sq.ptr::<u32>(state.sq_tail as usize * SQ_ENTRY_SIZE);
+        unsafe {
+            ptr::write_bytes(entry as *mut u8, 0, SQ_ENTRY_SIZE);
+            ptr::write_volatile(entry, opcode as u32 | (cid as u32) << 16);
+            ptr::write_volatile(entry.add(1), nsid);
+            ptr::write_volatile(entry.add(6) as *mut u64, prp1);
+            for (i, value) in cdw.iter().enumerate() {
+                ptr::write_volatile(entry.add(10 + i), *value);
+            }
+        }
+        state.sq_tail = (state.sq_tail + 1) % self.size;
+    }
+
+    /// Sıradaki tamamlanma girdisini alır: (CID, durum, komuta özel sonuç). Henüz yeni
+    /// girdi yoksa `None`. Çağıran `lock`'u tutmalıdır.
+    fn pop_locked(&self) -> Option<(u16, u16, u32)> {
+        let state = unsafe { &mut *self.state.get() };
+        let entry = self.cq.ptr::<u32>(state.cq_head as usize * CQ_ENTRY_SIZE);
+        fence(Ordering::SeqCst);
+        let dw3 = unsafe { ptr::read_volatile(entry.add(3)) };
+        if (dw3 >> 16) & 1 != state.phase as u32 {
+            return None;
+        }
+        let result = unsafe { ptr::read_volatile(entry) };
+
+        state.cq_head += 1;
+        if state.cq_head == self.size {
+            state.cq_head = 0;
+            state.phase = !state.phase;
+        }
+        Some((dw3 as u16, (dw3 >> 17) as u16 & 0x7fff, result))
+    }
+}
+
+
+// --- 3. NVMe Aygıtı ---
+
+/// Bir NVMe denetleyicisinin ilk ad alanını (namespace) birden çok G/Ç kuyruğu
+/// üzerinden doğrudan süren BlockDevice uygulaması.
+///
+/// Her G/Ç kuyruk çiftinin kendi kilidi vardır; tek blokluk istekler çağıran görevin
+/// kimliğine göre bir kuyruğa atanır, böylece farklı görevler (ve üzerinde çalıştıkları
+/// çekirdekler) birbirini beklemeden G/Ç yapar. Toplu istekler (`write_batch`,
+/// `read_blocks`) tüm kuyruklara dağıtılır: önce her kuyruğa kuyruk derinliği kadar
+/// komut yazılıp kapı zilleri çalınır, sonra tamamlanmalar toplanır. Böylece önbelleğin
+/// geri yazma turu aygıtın paralelliğini kullanır.
+pub struct NvmeDevice {
+    /// Denetleyici kaynağının handle'ı (BAR eşlemesi ve kesmeler bu handle'a bağlıdır).
+    handle: ResourceHandle,
+    /// Eşlenmiş BAR0 penceresi.
+    bar: *mut u8,
+    /// Kapı zilleri arasındaki adım (bayt).
+    doorbell_stride: usize,
+    /// G/Ç kuyruk çiftleri.
+    queues: Vec<QueuePair>,
+    /// Kullanılan ad alanı.
+    nsid: u32,
+    /// Bir SADAK bloğundaki mantıksal blok (LBA) sayısı.
+    lbas_per_block: u64,
+    /// Aygıtın toplam kapasitesi (blok cinsinden).
+    capacity_blocks: BlockId,
+}
+
+// BAR ve DMA bölgelerine yalnızca ilgili kuyruğun kilidi altında erişilir.
+unsafe impl Send for NvmeDevice {}
+unsafe impl Sync for NvmeDevice {}
+
+impl core::fmt::Debug for NvmeDevice {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("NvmeDevice")
+            .field("handle", &self.handle)
+            .field("queues", &self.queues.len())
+            .field("capacity_blocks", &self.capacity_blocks)
+            .finish()
+    }
+}
+
+impl NvmeDevice {
+    /// `resource_path`'teki NVMe denetleyicisini edinir, sıfırlar ve en fazla
+    /// `queue_count` G/Ç kuyruk çifti kurar (genellikle işlemci çekirdeği sayısı).
+    /// Denetleyici daha az kuyruk verirse onunla yetinilir.
+    ///
+    /// Ad alanının LBA boyutu `BLOCK_SIZE`'ı bölmüyorsa `EINVAL` döner.
+    pub fn new(resource_path: &str, queue_count: u16) -> Result<Self, SyscallError> {
+        let handle = sahne_syscalls::resource_acquire(resource_path.as_ptr(), resource_path.len())?;
+        match Self::init(handle, queue_count.clamp(1, NVME_MAX_IO_QUEUES)) {
+            Ok(device) => Ok(device),
+            Err(e) => {
+                unsafe { raw_syscall(SYSCALL_RESOURCE_RELEASE, handle, 0, 0, 0, 0, 0) };
+                Err(e)
+            }
+        }
+    }
+
+    fn init(handle: ResourceHandle, queue_count: u16) -> Result<Self, SyscallError> {
+        // 1. BAR0'ı eşle ve denetleyiciyi devre dışı bırak
+        let result = unsafe { raw_syscall(SYSCALL_RESOURCE_MAP, handle, 0, BAR_WINDOW_SIZE as u64, 0, 0, 0) };
+        if result < 0 {
+            return Err(SyscallError::from_raw(result));
+        }
+        let bar = result as usize as *mut u8;
+        let cap = unsafe { ptr::read_volatile(bar.add(REG_CAP) as *const u64) };
+        let max_entries = ((cap & 0xffff) + 1).min(u16::MAX as u64) as u16;
+        let doorbell_stride = 4usize << ((cap >> 32) & 0xf);
+
+        let mut device = NvmeDevice {
+            handle,
+            bar,
+            doorbell_stride,
+            queues: Vec::new(),
+            nsid: 1,
+            lbas_per_block: 1,
+            capacity_blocks: 0,
+        };
+        device.write_reg(REG_CC, 0);
+        device.wait_ready(false)?;
+
+        // 2. Yönetim kuyruğunu kur ve denetleyiciyi etkinleştir
+        let admin_size = ADMIN_QUEUE_SIZE.min(max_entries);
+        let admin = QueuePair::new(0, admin_size, 1)?;
+        let aqa = (admin_size as u32 - 1) << 16 | (admin_size as u32 - 1);
+        device.write_reg(REG_AQA, aqa);
+        unsafe {
+            ptr::write_volatile(bar.add(REG_ASQ) as *mut u64, admin.sq.phys);
+            ptr::write_volatile(bar.add(REG_ACQ) as *mut u64, admin.cq.phys);
+        }
+        device.write_reg(REG_CC, CC_ENABLE | CC_IOSQES | CC_IOCQES);
+        device.wait_ready(true)?;
+
+        // 3. Ad alanını tanı: boyut (NSZE) ve etkin LBA biçiminin veri boyutu
+        device.admin_command(&admin, ADMIN_IDENTIFY, device.nsid, admin.buffers.phys, [IDENTIFY_NAMESPACE, 0, 0, 0, 0, 0])?;
+        let identify = admin.buffers.virt;
+        let (nsze, lba_shift) = unsafe {
+            let nsze = ptr::read_volatile(identify as *const u64);
+            let format = (ptr::read_volatile(identify.add(26)) & 0xf) as usize;
+            let lbaf = ptr::read_volatile(identify.add(128 + format * 4) as *const u32);
+            (nsze, (lbaf >> 16) & 0xff)
+        };
+        let lba_size = 1u64 << lba_shift;
+        if lba_shift < 9 || lba_size > BLOCK_SIZE as u64 {
+            return Err(SyscallError::EINVAL);
+        }
+        device.lbas_per_block = BLOCK_SIZE as u64 / lba_size;
+        device.capacity_blocks = nsze / device.lbas_per_block;
+
+        // 4. Kuyruk sayısını iste; denetleyicinin verdiği kadarını kur
+        let wanted = (queue_count as u32 - 1) << 16 | (queue_count as u32 - 1);
+        let granted = device.admin_command(&admin, ADMIN_SET_FEATURES, 0, 0, [FEATURE_NUMBER_OF_QUEUES, wanted, 0, 0, 0, 0])?;
+        let count = queue_count.min((granted & 0xffff) as u16 + 1).min(((granted >> 16) & 0xffff) as u16 + 1);
+
+        let depth = NVME_QUEUE_DEPTH.min(max_entries);
+        for id in 1..=count {
+            let queue = QueuePair::new(id, depth, depth as usize)?;
+            let qsize = (depth as u32 - 1) << 16 | id as u32;
+            // CQ: fiziksel ardışık, kesme etkin, vektör = kuyruk kimliği
+            device.admin_command(&admin, ADMIN_CREATE_IO_CQ, 0, queue.cq.phys, [qsize, 0b11 | (id as u32) << 16, 0, 0, 0, 0])?;
+            // SQ: fiziksel ardışık, aynı kimlikli CQ'ya bağlı
+            device.admin_command(&admin, ADMIN_CREATE_IO_SQ, 0, queue.sq.phys, [qsize, 0b1 | (id as u32) << 16, 0, 0, 0, 0])?;
+            device.queues.push(queue);
+        }
+
+        Ok(device)
+    }
+
+    /// Kurulan G/Ç kuyruk çifti sayısı.
+    pub fn queue_count(&self) -> usize {
+        self.queues.len()
+    }
+
+    fn read_reg(&self, offset: usize) -> u32 {
+        unsafe { ptr::read_volatile(self.bar.add(offset) as *const u32) }
+    }
+
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { ptr::write_volatile(self.bar.add(offset) as *mut u32, value) }
+    }
+
+    /// Kuyruğun gönderim (`completion == false`) veya tamamlanma kapı ziline yazar.
+    fn ring_doorbell(&self, queue: &QueuePair, completion: bool) {
+        let state = unsafe { &*queue.state.get() };
+        let index = 2 * queue.id as usize + completion as usize;
+        let value = if completion { state.cq_head } else { state.sq_tail };
+        fence(Ordering::SeqCst);
+        self.write_reg(REG_DOORBELL_BASE + index * self.doorbell_stride, value as u32);
+    }
+
+    /// CSTS.RDY `ready` olana kadar bekler.
+    fn wait_ready(&self, ready: bool) -> Result<(), SyscallError> {
+        for _ in 0..READY_POLL_LIMIT {
+            let status = self.read_reg(REG_CSTS);
+            if status & CSTS_FATAL != 0 {
+                return Err(SyscallError::EIO);
+            }
+            if (status & CSTS_READY != 0) == ready {
+                return Ok(());
+            }
+            unsafe { raw_syscall(SYSCALL_TASK_SLEEP, 0, 0, 0, 0, 0, 0) };
+        }
+        Err(SyscallError::EIO)
+    }
+
+    /// Yönetim kuyruğunda tek bir komut yürütür ve tamamlanmayı yoklayarak bekler.
+    /// Komuta özel sonucu (DW0) döndürür. Yalnızca kurulum sırasında çağrılır.
+    fn admin_command(&self, admin: &QueuePair, opcode: u8, nsid: u32, prp1: u64, cdw: [u32; 6]) -> Result<u32, SyscallError> {
+        admin.push_locked(opcode, 0, nsid, prp1, cdw);
+        self.ring_doorbell(admin, false);
+        for _ in 0..READY_POLL_LIMIT {
+            if let Some((_, status, result)) = admin.pop_locked() {
+                self.ring_doorbell(admin, true);
+                return if status == 0 { Ok(result) } else { Err(SyscallError::EIO) };
+            }
+            unsafe { raw_syscall(SYSCALL_TASK_SLEEP, 0, 0, 0, 0, 0, 0) };
+        }
+        Err(SyscallError::EIO)
+    }
+
+    /// Çağıran görevin kuyruğu.
+    fn queue_for_task(&self) -> usize {
+        sahne_syscalls::task_id().unwrap_or(0) as usize % self.queues.len()
+    }
+
+    /// İşlemleri `first` kuyruğundan başlayarak kuyruklara sırayla dağıtır ve yürütür.
+    /// Her turda kuyruk başına en fazla `size - 1` komut verilir. Kilitler kuyruk
+    /// sırasıyla alındığından eş zamanlı toplu istekler birbirini kilitlemez.
+    fn run(&self, first: usize, ops: &mut [NvmeOp]) -> Result<(), SyscallError> {
+        let queue_count = self.queues.len();
+        let per_queue = self.queues[0].size as usize - 1;
+        let mut result = Ok(());
+
+        for round in ops.chunks_mut(queue_count * per_queue) {
+            let mut groups: Vec<Vec<&mut NvmeOp>> = (0..queue_count).map(|_| Vec::new()).collect();
+            for (i, op) in round.iter_mut().enumerate() {
+                groups[(first + i) % queue_count].push(op);
+            }
+
+            // 1. Her kuyruğa komutları yaz ve kapı zilini çal
+            for (queue, group) in self.queues.iter().zip(groups.iter()) {
+                if group.is_empty() {
+                    continue;
+                }
+                queue.lock.acquire();
+                for (cid, op) in group.iter().enumerate() {
+                    let prp = queue.buffers.phys + (cid * BLOCK_SIZE) as u64;
+                    let slot = unsafe { queue.buffers.virt.add(cid * BLOCK_SIZE) };
+                    let lbas = self.lbas_per_block;
+                    match op {
+                        NvmeOp::Read(id, _) => {
+                            let lba = id * lbas;
+                            queue.push_locked(NVM_READ, cid as u16, self.nsid, prp, [lba as u32, (lba >> 32) as u32, lbas as u32 - 1, 0, 0, 0]);
+                        }
+                        NvmeOp::Write(id, data) => {
+                            unsafe { ptr::copy_nonoverlapping(data.as_ptr(), slot, BLOCK_SIZE) };
+                            let lba = id * lbas;
+                            queue.push_locked(NVM_WRITE, cid as u16, self.nsid, prp, [lba as u32, (lba >> 32) as u32, lbas as u32 - 1, 0, 0, 0]);
+                        }
+                        NvmeOp::ReadDirect(id, phys) => {
+                            let lba = *id * lbas;
+                            queue.push_locked(NVM_READ, cid as u16, self.nsid, *phys, [lba as u32, (lba >> 32) as u32, lbas as u32 - 1, 0, 0, 0]);
+                        }
+                        NvmeOp::WriteDirect(id, phys) => {
+                            let lba = *id * lbas;
+                            queue.push_locked(NVM_WRITE, cid as u16, self.nsid, *phys, [lba as u32, (lba >> 32) as u32, lbas as u32 - 1, 0, 0, 0]);
+                        }
+                        NvmeOp::Flush => queue.push_locked(NVM_FLUSH, cid as u16, self.nsid, 0, [0; 6]),
+                    }
+                }
+                self.ring_doorbell(queue, false);
+            }
+
+            // 2. Tamamlanmaları topla; bir hata olsa da tüm komutlar beklenir ki ara
+            // bellekler yeniden kullanılmadan önce aygıt işini bitirmiş olsun
+            for (queue, group) in self.queues.iter().zip(groups.iter_mut()) {
+                if group.is_empty() {
+                    continue;
+                }
+                let mut remaining = group.len();
+                while remaining > 0 {
+                    match queue.pop_locked() {
+                        Some((cid, status, _)) => {
+                            remaining -= 1;
+                            if status != 0 {
+                                result = Err(SyscallError::EIO);
+                            } else if let Some(NvmeOp::Read(_, buffer)) = group.get_mut(cid as usize).map(|op| &mut **op) {
+                                let slot = unsafe { queue.buffers.virt.add(cid as usize * BLOCK_SIZE) };
+                                unsafe { ptr::copy_nonoverlapping(slot, buffer.as_mut_ptr(), BLOCK_SIZE) };
+                            }
+                        }
+                        None => {
+                            self.ring_doorbell(queue, true);
+                            let waited = unsafe { raw_syscall(SYSCALL_NOTIFICATION_WAIT, self.handle, queue.id as u64, 0, 0, 0, 0) };
+                            if waited < 0 {
+                                // Kesme beklenemiyorsa yoklamaya devam edilir
+                                unsafe { raw_syscall(SYSCALL_TASK_SLEEP, 0, 0, 0, 0, 0, 0) };
+                            }
+                        }
+                    }
+                }
+                self.ring_doorbell(queue, true);
+                queue.lock.release();
+            }
+
+            if result.is_err() {
+                break;
+            }
+        }
+        result
+    }
+
+    fn check_range(&self, start: BlockId, count: u64) -> Result<(), SyscallError> {
+        match start.checked_add(count) {
+            Some(end) if end <= self.capacity_blocks => Ok(()),
+            _ => Err(SyscallError::EINVAL),
+        }
+    }
+}
+
+impl Drop for NvmeDevice {
+    fn drop(&mut self) {
+        // Denetleyici devre dışı bırakılır ki DMA bölgeleri serbest kaldıktan sonra onlara erişmesin
+        self.write_reg(REG_CC, 0);
+        let _ = self.wait_ready(false);
+        unsafe { raw_syscall(SYSCALL_RESOURCE_RELEASE, self.handle, 0, 0, 0, 0, 0) };
+    }
+}
+
+impl BlockDevice for NvmeDevice {
+    type Error = SyscallError;
+
+    fn read_block(&self, id: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if buffer.len() != BLOCK_SIZE {
+            return Err(SyscallError::EINVAL);
+        }
+        self.check_range(id, 1)?;
+        self.run(self.queue_for_task(), &mut [NvmeOp::Read(id, buffer)])
+    }
+
+    fn write_block(&self, id: BlockId, data: &[u8]) -> Result<(), Self::Error> {
+        if data.len() != BLOCK_SIZE {
+            return Err(SyscallError::EINVAL);
+        }
+        self.check_range(id, 1)?;
+        self.run(self.queue_for_task(), &mut [NvmeOp::Write(id, data)])
+    }
+
+    fn total_blocks(&self) -> BlockId {
+        self.capacity_blocks
+    }
+
+    fn read_blocks(&self, start: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if buffer.is_empty() || buffer.len() % BLOCK_SIZE != 0 {
+            return Err(SyscallError::EINVAL);
+        }
+        self.check_range(start, (buffer.len() / BLOCK_SIZE) as u64)?;
+
+        let mut ops: Vec<NvmeOp> = buffer
+            .chunks_mut(BLOCK_SIZE)
+            .enumerate()
+            .map(|(i, chunk)| NvmeOp::Read(start + i as BlockId, chunk))
+            .collect();
+        self.run(self.queue_for_task(), &mut ops)
+    }
+
+    fn write_batch(&self, blocks: &[(BlockId, &[u8])]) -> Result<(), Self::Error> {
+        for &(id, data) in blocks {
+            if data.len() != BLOCK_SIZE {
+                return Err(SyscallError::EINVAL);
+            }
+            self.check_range(id, 1)?;
+        }
+
+        let mut ops: Vec<NvmeOp> = blocks.iter().map(|&(id, data)| NvmeOp::Write(id, data)).collect();
+        self.run(self.queue_for_task(), &mut ops)
+    }
+
+    fn buffer_requirements(&self) -> BufferRequirements {
+        // Tek bir PRP girdisi, sayfa hizalı ve sayfayı aşmayan bir blok arabelleğini tanımlar
+        BufferRequirements::DMA_PAGE
+    }
+
+    unsafe fn read_block_dma(&self, id: BlockId, buffer: &mut [u8], phys: u64) -> Result<(), Self::Error> {
+        if buffer.len() != BLOCK_SIZE {
+            return Err(SyscallError::EINVAL);
+        }
+        self.check_range(id, 1)?;
+        self.run(self.queue_for_task(), &mut [NvmeOp::ReadDirect(id, phys)])
+    }
+
+    unsafe fn write_batch_dma(&self, blocks: &[(BlockId, &[u8])], phys: &[Option<u64>]) -> Result<(), Self::Error> {
+        for &(id, data) in blocks {
+            if data.len() != BLOCK_SIZE {
+                return Err(SyscallError::EINVAL);
+            }
+            self.check_range(id, 1)?;
+        }
+
+        // Fiziksel adresi olmayan bloklar kuyruğun ara belleğiyle yazılır
+        let mut ops: Vec<NvmeOp> = blocks
+            .iter()
+            .zip(phys)
+            .map(|(&(id, data), &address)| match address {
+                Some(address) => NvmeOp::WriteDirect(id, address),
+                None => NvmeOp::Write(id, data),
+            })
+            .collect();
+        self.run(self.queue_for_task(), &mut ops)
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        // Ad alanının geçici belleği tek bir komutla boşaltılır
+        self.run(self.queue_for_task(), &mut [NvmeOp::Flush])
+    }
+}
@@ -0,0 +1,195 @@
+// src/oracle.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockDevice, BLOCK_SIZE};
+use crate::btree::{BTreeKey, ITEM_TYPE_KV_ITEM, ITEM_TYPE_KV_KEYSPACE};
+use crate::fs::{Inode, SadakFs, SadakFsError, FILE_TYPE_DIRECTORY, INODE_FLAG_DIR_HASHED, INODE_FLAG_DIR_MIGRATING};
+use core::fmt::Debug;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ---
+
+// 64-bit FNV-1a parametreleri.
+const FNV_OFFSET_BASIS: u64 = 0xCBF29CE484222325;
+const FNV_PRIME: u64 = 0x100000001B3;
+
+// Ad alanı kayıtlarının türleri; bitişik kayıtların karışmasını engeller.
+const RECORD_ENTRY: u8 = 1;
+const RECORD_XATTR: u8 = 2;
+const RECORD_DATA: u8 = 3;
+const RECORD_SUBVOLUME: u8 = 4;
+// Anahtarı olmadığı için içeriği okunamayan şifreli dosya
+const RECORD_LOCKED: u8 = 5;
+
+
+// --- 2. Deterministik Özet Fonksiyonu ---
+
+/// Platformdan ve çalıştırmadan bağımsız, deterministik 64-bit özet (FNV-1a).
+/// Kriptografik değildir; yalnızca test karşılaştırmaları için kullanılır.
+pub struct LogicalHasher {
+    state: u64,
+}
+
+impl LogicalHasher {
+    /// Boş bir özet durumu oluşturur.
+    pub fn new() -> Self {
+        LogicalHasher { state: FNV_OFFSET_BASIS }
+    }
+
+    /// Verilen baytları özete ekler.
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state ^= byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Uzunluk önekli bir bayt dizisini (ad, yol, öznitelik değeri) özete ekler.
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u32).to_le_bytes());
+        self.write(bytes);
+    }
+
+    /// Bir B-Ağacı öğesini (anahtar + uzunluk önekli değer) özete ekler.
+    /// Uzunluk öneki, bitişik öğelerin sınırlarının karışmasını engeller.
+    pub fn write_item(&mut self, key: &BTreeKey, value: &[u8]) {
+        self.write(&key.object_id.to_le_bytes());
+        self.write(&[key.item_type]);
+        self.write(&key.offset.to_le_bytes());
+        self.write(&(value.len() as u32).to_le_bytes());
+        self.write(value);
+    }
+
+    /// Nihai özet değerini döndürür.
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+
+// --- 3. Shadow-Paging Test Kahini (Oracle) ---
+
+/// Birimin tüm mantıksal durumunun (yollar, öznitelikler, içerikler) özetini hesaplar.
+///
+/// Ad alanı kök dizinden ve her alt birimin kökünden başlayarak dolaşılır; her girdi
+/// için tam yolu, inode öznitelikleri, genişletilmiş öznitelikleri ve dosya içeriği
+/// (`read_at` ile) özetlenir. Dizinler ada göre sıralanır. Blok ID'leri, checksum'lar ve
+/// yerleşime bağlı alanlar (blok sayısı, veri ağacı, inode numarası, nesil, değişiklik
+/// sayacı, dizin düzeni) özete girmez; böylece sonuç blokların diskte nereye
+/// yazıldığından (CoW yerleşimi) bağımsızdır, içerik bozulmaları ise özeti değiştirir.
+/// Anahtar-değer deposunun kayıtları da (yalnızca mantıksal anahtarlar taşırlar) eklenir.
+///
+/// Crash-simülasyon testleri, kurtarılan birimin özetini işlem öncesi/sonrası özetlerle
+/// karşılaştırır.
+pub fn logical_hash<D>(fs: &SadakFs<D>) -> Result<u64, SadakFsError<D>>
+where
+    D: BlockDevice + Debug + 'static,
+{
+    let mut hasher = LogicalHasher::new();
+
+    let root = fs.root_dir()?;
+    let mut path = String::new();
+    hash_inode(fs, &mut hasher, &root)?;
+    hash_dir(fs, &mut hasher, &mut path, &root)?;
+
+    for subvolume in fs.subvolumes()? {
+        let root = fs.read_inode(subvolume.root_inode)?;
+        let mut path = String::new();
+        hasher.write(&[RECORD_SUBVOLUME]);
+        hasher.write_bytes(subvolume.name.as_bytes());
+        hash_inode(fs, &mut hasher, &root)?;
+        hash_dir(fs, &mut hasher, &mut path, &root)?;
+    }
+
+    fs.metadata_tree().walk(&mut |key, value| {
+        if key.item_type == ITEM_TYPE_KV_ITEM || key.item_type == ITEM_TYPE_KV_KEYSPACE {
+            hasher.write_item(key, value);
+        }
+    })?;
+
+    Ok(hasher.finish())
+}
+
+/// `dir`'in girdilerini ada göre sıralı olarak özetler ve alt dizinlere iner; `path`
+/// dizinin köke göre yoludur (dönüşte eski haline getirilir).
+fn hash_dir<D>(fs: &SadakFs<D>, hasher: &mut LogicalHasher, path: &mut String, dir: &Inode) -> Result<(), SadakFsError<D>>
+where
+    D: BlockDevice + Debug + 'static,
+{
+    let mut entries = fs.read_dir(dir)?;
+    entries.sort_unstable_by(|a, b| a.name.as_bytes().cmp(b.name.as_bytes()));
+
+    for entry in entries {
+        let parent_len = path.len();
+        path.push('/');
+        path.push_str(&entry.name);
+
+        let inode = fs.entry_inode(&entry)?;
+        hasher.write(&[RECORD_ENTRY]);
+        hasher.write_bytes(path.as_bytes());
+        hash_inode(fs, hasher, &inode)?;
+        if inode.file_type == FILE_TYPE_DIRECTORY {
+            hash_dir(fs, hasher, path, &inode)?;
+        }
+
+        path.truncate(parent_len);
+    }
+    Ok(())
+}
+
+/// Bir inode'un mantıksal özniteliklerini, genişletilmiş özniteliklerini ve (dizin
+/// değilse) içeriğini özetler.
+fn hash_inode<D>(fs: &SadakFs<D>, hasher: &mut LogicalHasher, inode: &Inode) -> Result<(), SadakFsError<D>>
+where
+    D: BlockDevice + Debug + 'static,
+{
+    hasher.write(&[inode.file_type, inode.flags & !(INODE_FLAG_DIR_HASHED | INODE_FLAG_DIR_MIGRATING), inode.compression, inode.placement_hint]);
+    hasher.write(&inode.mode.to_le_bytes());
+    hasher.write(&inode.uid.to_le_bytes());
+    hasher.write(&inode.gid.to_le_bytes());
+    hasher.write(&inode.link_count.to_le_bytes());
+    hasher.write(&inode.file_size.to_le_bytes());
+    hasher.write(&inode.creation_time.to_le_bytes());
+    hasher.write(&inode.modification_time.to_le_bytes());
+    hasher.write(&inode.key_id.to_le_bytes());
+
+    let mut names = fs.list_xattr(inode)?;
+    names.sort_unstable();
+    for name in names {
+        hasher.write(&[RECORD_XATTR]);
+        hasher.write_bytes(name.as_bytes());
+        hasher.write_bytes(&fs.get_xattr(inode, &name)?);
+    }
+
+    if inode.file_type == FILE_TYPE_DIRECTORY {
+        return Ok(());
+    }
+    hasher.write(&[RECORD_DATA]);
+    let mut buf: Vec<u8> = alloc::vec![0u8; BLOCK_SIZE];
+    let mut offset = 0;
+    while offset < inode.file_size {
+        let read = match fs.read_at(inode, offset, &mut buf) {
+            Ok(read) => read,
+            // Anahtarı yüklenmemiş şifreli dosyada yalnızca anahtar kimliği özetlenmiş olur
+            Err(SadakFsError::KeyUnavailable) => {
+                hasher.write(&[RECORD_LOCKED]);
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        if read == 0 {
+            break;
+        }
+        hasher.write(&buf[..read]);
+        offset += read as u64;
+    }
+    Ok(())
+}
+
+/// Kurtarılan durumun özeti, geçerli durumlardan (ör. işlem öncesi ve sonrası) biriyle eşleşiyor mu?
+pub fn matches_legal_state(recovered: u64, legal_states: &[u64]) -> bool {
+    legal_states.iter().any(|&legal| legal == recovered)
+}
@@ -0,0 +1,49 @@
+// src/orphan.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::BlockId;
+use crate::btree::{BTreeKey, ITEM_TYPE_ORPHAN};
+
+
+// --- 1. Sabitler ---
+
+// Yetim kayıtlarının metadata ağacındaki nesne kimliği (inode tablosu kayıtlarının
+// `u64::MAX - 5` kimliğinin hemen altı; inode ID'leriyle çakışmaz).
+const ORPHAN_OBJECT: u64 = u64::MAX - 6;
+
+
+// --- 2. Yetim Listesi ---
+
+/// Son bağlantısı kaldırıldığı halde hâlâ açık tutulan bir inode'un kaydı.
+///
+/// Böyle bir inode'un blokları son tutucu bırakılana kadar geri kazanılmaz. Kayıtlar
+/// metadata ağacında `(ORPHAN_OBJECT, ITEM_TYPE_ORPHAN, inode ID'si)` anahtarıyla
+/// değersiz tutulur; bir çökme inode bırakılmadan önce olursa sonraki montaj listede
+/// kalan inode'ları geri kazanır.
+pub struct Orphan;
+
+impl Orphan {
+    /// `inode_id` inode'unun yetim kaydının anahtarı.
+    pub fn key(inode_id: BlockId) -> BTreeKey {
+        BTreeKey {
+            object_id: ORPHAN_OBJECT,
+            item_type: ITEM_TYPE_ORPHAN,
+            offset: inode_id,
+        }
+    }
+
+    /// Listedeki en küçük anahtar (taramanın başlangıcı).
+    pub fn first_key() -> BTreeKey {
+        Self::key(0)
+    }
+
+    /// Anahtar bir yetim kaydına mı ait? Öyleyse inode ID'sini döndürür.
+    pub fn from_key(key: &BTreeKey) -> Option<BlockId> {
+        if key.object_id == ORPHAN_OBJECT && key.item_type == ITEM_TYPE_ORPHAN {
+            Some(key.offset)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,159 @@
+// src/page_cache.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockId, BLOCK_SIZE};
+use crate::cache::allocate_buffer;
+use crate::platform::SysLock;
+use crate::sahne_syscalls::SyscallError;
+use core::cell::UnsafeCell;
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Bir sayfanın kimliği: (inode'un blok ID'si, dosya içi blok indeksi)
+pub type PageKey = (BlockId, u64);
+
+
+// --- 2. Sayfa Önbelleği ---
+
+/// Dosya verisi yazmalarını, disk bloğu tahsis etmeden (inode, konum) ile ilişkili
+/// bellek sayfalarında biriktiren önbellek (gecikmeli tahsis / delayed allocation).
+///
+/// Disk blokları yalnızca geri yazma (commit) sırasında, ardışık sayfalar için
+/// ardışık extent'ler halinde tahsis edilir. Böylece küçük yazmalar birleşir ve
+/// dosyalar parçalanmaz.
+pub struct PageCache {
+    /// Kirli sayfalar. Yalnızca `lock` alınmışken erişilir.
+    pages: UnsafeCell<BTreeMap<PageKey, Box<[u8; BLOCK_SIZE]>>>,
+    /// Arabelleğe alınmış yazmalardan sonra dosyaların ulaştığı boyut (inode -> bayt).
+    sizes: UnsafeCell<BTreeMap<BlockId, u64>>,
+    lock: SysLock,
+}
+
+impl PageCache {
+    /// Boş bir sayfa önbelleği oluşturur.
+    pub fn new() -> Result<Self, SyscallError> {
+        Ok(PageCache {
+            pages: UnsafeCell::new(BTreeMap::new()),
+            sizes: UnsafeCell::new(BTreeMap::new()),
+            lock: SysLock::new()?,
+        })
+    }
+
+    /// Geri yazılmayı bekleyen toplam sayfa sayısı.
+    pub fn dirty_pages(&self) -> usize {
+        self.lock.acquire();
+        let count = unsafe { &*self.pages.get() }.len();
+        self.lock.release();
+        count
+    }
+
+    /// Bir sayfanın bir kısmını değiştirir. Sayfa henüz yoksa önce `fill` ile
+    /// (ör. diskteki eski içerik veya sıfırlarla) doldurulur.
+    ///
+    /// # Parametreler
+    /// * `offset_in_page`: Verinin sayfa içindeki başlangıç konumu.
+    /// * `data`: Yazılacak veri; sayfa sonunu aşmamalıdır.
+    pub fn write<E, F>(&self, key: PageKey, offset_in_page: usize, data: &[u8], fill: F) -> Result<(), E>
+    where
+        E: From<SyscallError>,
+        F: FnOnce(&mut [u8]) -> Result<(), E>,
+    {
+        self.lock.acquire();
+        let pages = unsafe { &mut *self.pages.get() };
+
+        if !pages.contains_key(&key) {
+            let mut page = match allocate_buffer() {
+                Ok(page) => page,
+                Err(e) => {
+                    self.lock.release();
+                    return Err(E::from(e));
+                }
+            };
+            // Tam sayfa yazmalarında eski içeriği okumaya gerek yoktur.
+            if data.len() != BLOCK_SIZE {
+                if let Err(e) = fill(page.as_mut()) {
+                    self.lock.release();
+                    return Err(e);
+                }
+            }
+            pages.insert(key, page);
+        }
+
+        if let Some(page) = pages.get_mut(&key) {
+            page[offset_in_page..offset_in_page + data.len()].copy_from_slice(data);
+        }
+
+        self.lock.release();
+        Ok(())
+    }
+
+    /// Sayfa önbellekte mi?
+    pub fn contains(&self, key: PageKey) -> bool {
+        self.lock.acquire();
+        let found = unsafe { &*self.pages.get() }.contains_key(&key);
+        self.lock.release();
+        found
+    }
+
+    /// Dosyanın arabelleğe alınmış boyutunu en az `size` olacak şekilde büyütür.
+    pub fn extend_size(&self, inode_id: BlockId, size: u64) {
+        self.lock.acquire();
+        let sizes = unsafe { &mut *self.sizes.get() };
+        let entry = sizes.entry(inode_id).or_insert(0);
+        *entry = (*entry).max(size);
+        self.lock.release();
+    }
+
+    /// Arabelleğe alınmış yazmalarla dosyanın ulaştığı boyut (bekleyen yazma yoksa `None`).
+    pub fn buffered_size(&self, inode_id: BlockId) -> Option<u64> {
+        self.lock.acquire();
+        let size = unsafe { &*self.sizes.get() }.get(&inode_id).copied();
+        self.lock.release();
+        size
+    }
+
+    /// `[first_block, first_block + buffer.len() / BLOCK_SIZE)` aralığındaki kirli
+    /// sayfaları, diskten okunmuş `buffer`'ın üzerine kopyalar.
+    pub fn overlay(&self, inode_id: BlockId, first_block: u64, buffer: &mut [u8]) {
+        let blocks = (buffer.len() / BLOCK_SIZE) as u64;
+
+        self.lock.acquire();
+        let pages = unsafe { &*self.pages.get() };
+        for (&(_, file_block), page) in pages.range((inode_id, first_block)..(inode_id, first_block + blocks)) {
+            let start = (file_block - first_block) as usize * BLOCK_SIZE;
+            buffer[start..start + BLOCK_SIZE].copy_from_slice(page.as_ref());
+        }
+        self.lock.release();
+    }
+
+    /// Kirli sayfası olan inode'ların listesi.
+    pub fn dirty_inodes(&self) -> Vec<BlockId> {
+        self.lock.acquire();
+        let mut inodes: BTreeSet<BlockId> = unsafe { &*self.sizes.get() }.keys().copied().collect();
+        inodes.extend(unsafe { &*self.pages.get() }.keys().map(|(inode_id, _)| *inode_id));
+        self.lock.release();
+        inodes.into_iter().collect()
+    }
+
+    /// Bir inode'un tüm kirli sayfalarını ve arabelleğe alınmış boyutunu önbellekten
+    /// çıkarıp geri yazma için döndürür. Sayfalar dosya içi blok sırasına göredir.
+    pub fn take_inode(&self, inode_id: BlockId) -> (Vec<(u64, Box<[u8; BLOCK_SIZE]>)>, Option<u64>) {
+        self.lock.acquire();
+        let pages = unsafe { &mut *self.pages.get() };
+
+        let keys: Vec<PageKey> = pages.range((inode_id, 0)..=(inode_id, u64::MAX)).map(|(k, _)| *k).collect();
+        let taken = keys
+            .into_iter()
+            .filter_map(|key| pages.remove(&key).map(|page| (key.1, page)))
+            .collect();
+        let size = unsafe { &mut *self.sizes.get() }.remove(&inode_id);
+
+        self.lock.release();
+        (taken, size)
+    }
+}
@@ -0,0 +1,60 @@
+// src/path.rs
+
+#![allow(dead_code, unused_variables)]
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Yol bileşenlerini ayıran karakter.
+pub const SEPARATOR: char = '/';
+
+/// Bir yolun tek bir bileşeni.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component<'a> {
+    /// `.`: bulunulan dizin.
+    CurDir,
+    /// `..`: üst dizin.
+    ParentDir,
+    /// Sıradan bir dizin girdisi adı.
+    Normal(&'a str),
+}
+
+
+// --- 2. Yol Ayrıştırma (no_std) ---
+
+/// Yol kök dizinden mi başlıyor?
+pub fn is_absolute(path: &str) -> bool {
+    path.starts_with(SEPARATOR)
+}
+
+/// Yol `/` ile mi bitiyor? (Son bileşenin dizin olması gerektiğini belirtir.)
+pub fn has_trailing_separator(path: &str) -> bool {
+    path.len() > 1 && path.ends_with(SEPARATOR)
+}
+
+/// Yolu bileşenlerine ayırır. Baştaki, sondaki ve art arda gelen ayırıcılar
+/// boş bileşen üretmez; `a//b/` ile `a/b` aynı bileşenleri verir.
+pub fn components(path: &str) -> impl Iterator<Item = Component<'_>> {
+    path.split(SEPARATOR)
+        .filter(|part| !part.is_empty())
+        .map(|part| match part {
+            "." => Component::CurDir,
+            ".." => Component::ParentDir,
+            name => Component::Normal(name),
+        })
+}
+
+/// Yolu üst dizin yoluna ve son bileşen adına ayırır: `/etc/hosts` -> (`/etc/`, `hosts`),
+/// `hosts` -> (`.`, `hosts`). Sondaki ayırıcılar yok sayılır. Son bileşen sıradan bir ad
+/// değilse (boş yol, `/`, `.` veya `..`) `None`.
+pub fn split_last(path: &str) -> Option<(&str, &str)> {
+    let trimmed = path.trim_end_matches(SEPARATOR);
+    let (parent, name) = match trimmed.rfind(SEPARATOR) {
+        Some(index) => (&trimmed[..=index], &trimmed[index + 1..]),
+        None => (".", trimmed),
+    };
+    match name {
+        "" | "." | ".." => None,
+        name => Some((parent, name)),
+    }
+}
@@ -0,0 +1,231 @@
+// src/platform.rs
+
+#![allow(dead_code, unused_variables)]
+
+// Çekirdek motorun (B-Ağacı, tahsis, önbellek, dosya sistemi) işletim sisteminden
+// istediği ilkel işlemler: kilit, bellek, DMA belleği, görev kimliği, işlemciyi bırakma
+// ve sistem zamanı. Motor sistem çağrılarına yalnızca bu modül üzerinden ulaşır.
+//
+// Varsayılan arka uç Sahne64 sistem çağrılarını kullanır. `host` özelliğiyle yalnızca
+// `alloc` ve atomiklere dayanan taşınabilir arka uç seçilir; ana makine araçları, fuzz
+// testleri ve başka çekirdekler aynı motoru `RamDevice` gibi aygıtlarla kullanabilir.
+
+#[cfg(not(feature = "host"))]
+pub use self::sahne::*;
+#[cfg(feature = "host")]
+pub use self::host::*;
+
+
+// --- 1. Sahne64 Arka Ucu ---
+
+#[cfg(not(feature = "host"))]
+mod sahne {
+    use core::ptr;
+
+    use crate::sahne_syscalls::{
+        self, SyscallError,
+        SYSCALL_LOCK_CREATE, SYSCALL_LOCK_ACQUIRE, SYSCALL_LOCK_RELEASE, SYSCALL_LOCK_DESTROY,
+        SYSCALL_MEMORY_ALLOCATE, SYSCALL_MEMORY_RELEASE, SYSCALL_TASK_SLEEP,
+        SYSCALL_DMA_ALLOCATE, SYSCALL_DMA_RELEASE, SYSCALL_GET_SYSTEM_TIME,
+        raw_syscall
+    };
+
+    // Sahne64'ün kilit handle'ı için bir tür alias'ı
+    pub type LockHandle = u64;
+
+    /// Sahne64 çekirdeğinin kilitlerini sarmalayan temel bir yapı.
+    /// Bu, önbelleğe eş zamanlı erişimi yönetmek için kullanılacaktır.
+    pub struct SysLock {
+        handle: LockHandle,
+    }
+
+    impl SysLock {
+        /// Yeni bir çekirdek kilidi oluşturur.
+        pub fn new() -> Result<Self, SyscallError> {
+            let result = unsafe { raw_syscall(SYSCALL_LOCK_CREATE, 0, 0, 0, 0, 0, 0) };
+            if result < 0 {
+                Err(SyscallError::from_raw(result))
+            } else {
+                Ok(SysLock { handle: result as LockHandle })
+            }
+        }
+
+        /// Kilidi alır (Bloklayabilir).
+        pub fn acquire(&self) {
+            unsafe { raw_syscall(SYSCALL_LOCK_ACQUIRE, self.handle, 0, 0, 0, 0, 0) };
+        }
+
+        /// Kilidi serbest bırakır.
+        pub fn release(&self) {
+            unsafe { raw_syscall(SYSCALL_LOCK_RELEASE, self.handle, 0, 0, 0, 0, 0) };
+        }
+    }
+
+    impl Drop for SysLock {
+        /// Çekirdek kilidini yok eder; handle bir daha kullanılamaz.
+        fn drop(&mut self) {
+            unsafe { raw_syscall(SYSCALL_LOCK_DESTROY, self.handle, 0, 0, 0, 0, 0) };
+        }
+    }
+
+    /// `size` baytlık sıfırlanmış bellek tahsis eder (SYSCALL_MEMORY_ALLOCATE).
+    pub(crate) fn memory_allocate(size: usize) -> Result<*mut u8, SyscallError> {
+        let result = unsafe { raw_syscall(SYSCALL_MEMORY_ALLOCATE, size as u64, 0, 0, 0, 0, 0) };
+        if result == 0 {
+            return Err(SyscallError::ENOMEM);
+        }
+        let memory = result as usize as *mut u8;
+        unsafe { ptr::write_bytes(memory, 0, size) };
+        Ok(memory)
+    }
+
+    /// `memory_allocate` ile alınmış belleği geri verir.
+    ///
+    /// # Safety
+    /// `memory` ve `size`, aynı `memory_allocate` çağrısından gelmeli ve bellek bir daha kullanılmamalıdır.
+    pub(crate) unsafe fn memory_release(memory: *mut u8, size: usize) {
+        raw_syscall(SYSCALL_MEMORY_RELEASE, memory as u64, size as u64, 0, 0, 0, 0);
+    }
+
+    /// Fiziksel olarak ardışık, sayfa hizalı, sıfırlanmış `size` baytlık DMA belleği tahsis
+    /// eder; sanal adresi ve fiziksel adresi döner.
+    pub(crate) fn dma_allocate(size: usize) -> Result<(*mut u8, u64), SyscallError> {
+        let mut phys: u64 = 0;
+        let result = unsafe {
+            raw_syscall(SYSCALL_DMA_ALLOCATE, size as u64, &mut phys as *mut u64 as u64, 0, 0, 0, 0)
+        };
+        if result < 0 {
+            return Err(SyscallError::from_raw(result));
+        }
+        if result == 0 {
+            return Err(SyscallError::ENOMEM);
+        }
+        let memory = result as usize as *mut u8;
+        unsafe { ptr::write_bytes(memory, 0, size) };
+        Ok((memory, phys))
+    }
+
+    /// `dma_allocate` ile alınmış belleği geri verir.
+    ///
+    /// # Safety
+    /// `memory` ve `size`, aynı `dma_allocate` çağrısından gelmeli; aygıt belleğe artık erişmemelidir.
+    pub(crate) unsafe fn dma_release(memory: *mut u8, size: usize) {
+        raw_syscall(SYSCALL_DMA_RELEASE, memory as u64, size as u64, 0, 0, 0, 0);
+    }
+
+    /// Sıfır süreli uyku: işlemciyi diğer görevlere bırakır (SYSCALL_TASK_SLEEP).
+    pub fn yield_now() {
+        unsafe { raw_syscall(SYSCALL_TASK_SLEEP, 0, 0, 0, 0, 0, 0) };
+    }
+
+    /// Çağıran Sahne64 görevinin kimliği.
+    pub fn task_id() -> Result<u64, SyscallError> {
+        sahne_syscalls::task_id()
+    }
+
+    /// Sahne64 sistem zamanı (SYSCALL_GET_SYSTEM_TIME).
+    pub fn system_time() -> Result<u64, SyscallError> {
+        let result = unsafe { raw_syscall(SYSCALL_GET_SYSTEM_TIME, 0, 0, 0, 0, 0, 0) };
+        if result < 0 {
+            Err(SyscallError::from_raw(result))
+        } else {
+            Ok(result as u64)
+        }
+    }
+}
+
+
+// --- 2. Taşınabilir (Ana Makine) Arka Ucu ---
+
+#[cfg(feature = "host")]
+mod host {
+    use alloc::alloc::{alloc_zeroed, dealloc, Layout};
+    use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+    use crate::sahne_syscalls::SyscallError;
+
+    /// Dönerek bekleyen kilit; çekirdek kaynağı gerektirmez. Arayüzü Sahne64 arka
+    /// ucundaki `SysLock` ile aynıdır.
+    pub struct SysLock {
+        locked: AtomicBool,
+    }
+
+    impl SysLock {
+        /// Yeni, serbest bir kilit oluşturur.
+        pub fn new() -> Result<Self, SyscallError> {
+            Ok(SysLock { locked: AtomicBool::new(false) })
+        }
+
+        /// Kilidi alır; serbest kalana kadar döner.
+        pub fn acquire(&self) {
+            while self
+                .locked
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                core::hint::spin_loop();
+            }
+        }
+
+        /// Kilidi serbest bırakır.
+        pub fn release(&self) {
+            self.locked.store(false, Ordering::Release);
+        }
+    }
+
+    // Tahsisler bayt hizalıdır ki `Box<[u8; N]>` olarak düşürülen bellek de aynı
+    // düzenle geri verilsin.
+    fn layout(size: usize) -> Result<Layout, SyscallError> {
+        Layout::array::<u8>(size).map_err(|_| SyscallError::EINVAL)
+    }
+
+    /// `size` baytlık sıfırlanmış bellek tahsis eder (genel tahsisçi).
+    pub(crate) fn memory_allocate(size: usize) -> Result<*mut u8, SyscallError> {
+        let memory = unsafe { alloc_zeroed(layout(size)?) };
+        if memory.is_null() {
+            Err(SyscallError::ENOMEM)
+        } else {
+            Ok(memory)
+        }
+    }
+
+    /// `memory_allocate` ile alınmış belleği geri verir.
+    ///
+    /// # Safety
+    /// `memory` ve `size`, aynı `memory_allocate` çağrısından gelmeli ve bellek bir daha kullanılmamalıdır.
+    pub(crate) unsafe fn memory_release(memory: *mut u8, size: usize) {
+        if let Ok(layout) = layout(size) {
+            dealloc(memory, layout);
+        }
+    }
+
+    /// Ana makinede DMA belleği yoktur; çağıranlar olağan belleğe düşer.
+    pub(crate) fn dma_allocate(size: usize) -> Result<(*mut u8, u64), SyscallError> {
+        Err(SyscallError::ENOMEM)
+    }
+
+    /// `dma_allocate` hiçbir zaman başarılı olmadığından çağrılmaz.
+    ///
+    /// # Safety
+    /// Sahne64 arka ucundaki `dma_release` ile aynı koşullar.
+    pub(crate) unsafe fn dma_release(memory: *mut u8, size: usize) {}
+
+    /// İşlemciye dönüş beklediğini bildirir.
+    pub fn yield_now() {
+        core::hint::spin_loop();
+    }
+
+    /// Ana makinede görev kavramı yoktur; tüm çağıranlar 0 numaralı görev (yönetici) sayılır.
+    pub fn task_id() -> Result<u64, SyscallError> {
+        Ok(0)
+    }
+
+    // `system_time`'ın bir sonraki değeri.
+    static NEXT_TIME: AtomicU64 = AtomicU64::new(1);
+
+    /// no_std ortamında takvim saati olmadığından her okumada bir artan sayaç döner
+    /// (bkz. `clock::MonotonicClock`); zaman damgaları deterministik olur.
+    pub fn system_time() -> Result<u64, SyscallError> {
+        Ok(NEXT_TIME.fetch_add(1, Ordering::Relaxed))
+    }
+}
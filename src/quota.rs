@@ -0,0 +1,354 @@
+// src/quota.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::BlockId;
+use crate::btree::{BTreeKey, ITEM_TYPE_QUOTA};
+use crate::platform::SysLock;
+use crate::sahne_syscalls::SyscallError;
+use core::cell::UnsafeCell;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Kota kayıtlarının metadata ağacındaki nesne kimliği (alt birim kayıtlarının
+// `u64::MAX - 2` kimliğinin hemen altı; inode ID'leriyle çakışmaz).
+const QUOTA_OBJECT: u64 = u64::MAX - 3;
+
+// Anahtarın `offset` alanında kimliğe ayrılan bit sayısı; üstteki bayt kota türüdür.
+const QUOTA_ID_BITS: u32 = 56;
+
+// Kayıt değerinin boyutu: blocks (8) + inodes (8) + block_limit (8) + inode_limit (8)
+const QUOTA_RECORD_SIZE: usize = 32;
+
+/// Kotanın kime uygulandığı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QuotaId {
+    /// Bir sahibin (`Inode::uid`) tüm dosyaları.
+    User(u32),
+    /// Bir alt birimin (`Inode::subvolume_id`; 0 = ana ad alanı) tüm dosyaları.
+    Subvolume(u64),
+}
+
+impl QuotaId {
+    /// Kaydın metadata ağacındaki anahtarı.
+    pub fn key(&self) -> BTreeKey {
+        let (kind, id) = match *self {
+            QuotaId::User(uid) => (1u64, uid as u64),
+            QuotaId::Subvolume(id) => (2u64, id),
+        };
+        BTreeKey {
+            object_id: QUOTA_OBJECT,
+            item_type: ITEM_TYPE_QUOTA,
+            offset: (kind << QUOTA_ID_BITS) | (id & ((1 << QUOTA_ID_BITS) - 1)),
+        }
+    }
+
+    /// Anahtar bir kota kaydına mı ait?
+    pub fn is_quota_key(key: &BTreeKey) -> bool {
+        key.object_id == QUOTA_OBJECT && key.item_type == ITEM_TYPE_QUOTA
+    }
+
+    /// Anahtardan kimliği çözer; kota kaydı değilse `None`.
+    pub fn from_key(key: &BTreeKey) -> Option<Self> {
+        if !Self::is_quota_key(key) {
+            return None;
+        }
+        let id = key.offset & ((1 << QUOTA_ID_BITS) - 1);
+        match key.offset >> QUOTA_ID_BITS {
+            1 => Some(QuotaId::User(id as u32)),
+            2 => Some(QuotaId::Subvolume(id)),
+            _ => None,
+        }
+    }
+
+    /// İlk kota kaydının anahtarı (tarama başlangıcı).
+    pub fn first_key() -> BTreeKey {
+        BTreeKey { object_id: QUOTA_OBJECT, item_type: ITEM_TYPE_QUOTA, offset: 0 }
+    }
+}
+
+/// Bir kotanın kullanımı ve sınırları. Sınır 0 ise uygulanmaz.
+///
+/// Blok kullanımı dosyaların `block_count` toplamıdır: klonlarla paylaşılan bloklar her
+/// dosyanın sahibine ayrı ayrı yazılır; metadata düğümleri sayılmaz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct QuotaUsage {
+    pub blocks: u64,
+    pub inodes: u64,
+    pub block_limit: u64,
+    pub inode_limit: u64,
+}
+
+impl QuotaUsage {
+    /// B-Ağacı değer formatı (LE).
+    pub fn encode(&self) -> [u8; QUOTA_RECORD_SIZE] {
+        let mut out = [0u8; QUOTA_RECORD_SIZE];
+        out[0..8].copy_from_slice(&self.blocks.to_le_bytes());
+        out[8..16].copy_from_slice(&self.inodes.to_le_bytes());
+        out[16..24].copy_from_slice(&self.block_limit.to_le_bytes());
+        out[24..32].copy_from_slice(&self.inode_limit.to_le_bytes());
+        out
+    }
+
+    /// B-Ağacı değerinden çözer; değer bozuksa `None`.
+    pub fn decode(value: &[u8]) -> Option<Self> {
+        if value.len() < QUOTA_RECORD_SIZE {
+            return None;
+        }
+        let field = |i: usize| {
+            let mut raw = [0u8; 8];
+            raw.copy_from_slice(&value[i * 8..i * 8 + 8]);
+            u64::from_le_bytes(raw)
+        };
+        Some(QuotaUsage { blocks: field(0), inodes: field(1), block_limit: field(2), inode_limit: field(3) })
+    }
+
+    /// Kullanıma `blocks` blok daha eklenebilir mi?
+    fn allows_blocks(&self, blocks: u64) -> bool {
+        self.block_limit == 0 || self.blocks.saturating_add(blocks) <= self.block_limit
+    }
+
+    /// Kullanıma `count` inode daha eklenebilir mi?
+    fn allows_inodes(&self, count: u64) -> bool {
+        self.inode_limit == 0 || self.inodes.saturating_add(count) <= self.inode_limit
+    }
+}
+
+
+// --- 2. Kota Tablosu ---
+
+/// Kullanıcı ve alt birim başına kota tablosu.
+///
+/// Tablo bellekte tutulur ve commit sırasında değişen kayıtlar metadata ağacına yazılır.
+/// Blok sınırı tahsis anında uygulanır: dosya sistemi bir dosyaya blok tahsis etmeden
+/// önce `begin_charge` ile sahibini bildirir, tahsis yöneticisi her tahsiste `reserve`
+/// çağırır. İşlem bitince gerçek kullanım değişimi (`block_count` farkı) `adjust` ile
+/// yazılır ve ayrılan miktar `end_charge` ile bırakılır; ağaç düğümleri de ayrılan
+/// miktara sayıldığından denetim temkinlidir. Blokları geri yazmada tahsis edilecek
+/// tamponlu sayfalar `reserve_pending` ile önceden ayrılır ve geri yazılana kadar her
+/// denetimde kullanıma eklenir.
+pub struct QuotaTable {
+    lock: SysLock,
+    entries: UnsafeCell<BTreeMap<QuotaId, QuotaUsage>>,
+    /// Son commit'ten beri değişen kayıtlar.
+    dirty: UnsafeCell<BTreeSet<QuotaId>>,
+    /// Şu an blok tahsis edilen dosyanın sahibi ve alt birimi.
+    charge: UnsafeCell<Option<(QuotaId, QuotaId)>>,
+    /// Geçerli işlemde ayrılan (henüz `adjust` ile yazılmamış) blok sayısı.
+    reserved: UnsafeCell<u64>,
+    /// Geri yazılmayı bekleyen tamponlu sayfalara ayrılan bloklar:
+    /// (inode'un blok ID'si, sahip, alt birim) -> blok sayısı.
+    pending: UnsafeCell<BTreeMap<(BlockId, QuotaId, QuotaId), u64>>,
+}
+
+// Tabloya her erişim `lock` altında yapıldığı için paylaşım güvenlidir.
+unsafe impl Sync for QuotaTable {}
+unsafe impl Send for QuotaTable {}
+
+impl QuotaTable {
+    /// Boş bir kota tablosu oluşturur (hiçbir sınır yok).
+    pub fn new() -> Result<Self, SyscallError> {
+        Ok(QuotaTable {
+            lock: SysLock::new()?,
+            entries: UnsafeCell::new(BTreeMap::new()),
+            dirty: UnsafeCell::new(BTreeSet::new()),
+            charge: UnsafeCell::new(None),
+            reserved: UnsafeCell::new(0),
+            pending: UnsafeCell::new(BTreeMap::new()),
+        })
+    }
+
+    /// Montajda diskteki kayıtları yükler.
+    pub fn load(&self, records: Vec<(QuotaId, QuotaUsage)>) {
+        self.with(|entries, _| entries.extend(records));
+    }
+
+    /// Kotanın kullanımı ve sınırları (kaydı yoksa sıfır).
+    pub fn usage(&self, id: QuotaId) -> QuotaUsage {
+        self.with(|entries, _| entries.get(&id).copied().unwrap_or_default())
+    }
+
+    /// Tüm kayıtlar, kimlik sırasıyla.
+    pub fn all(&self) -> Vec<(QuotaId, QuotaUsage)> {
+        self.with(|entries, _| entries.iter().map(|(&id, &usage)| (id, usage)).collect())
+    }
+
+    /// Kotanın sınırlarını ayarlar (0 = sınır yok).
+    pub fn set_limits(&self, id: QuotaId, block_limit: u64, inode_limit: u64) {
+        self.with(|entries, dirty| {
+            let usage = entries.entry(id).or_default();
+            usage.block_limit = block_limit;
+            usage.inode_limit = inode_limit;
+            dirty.insert(id);
+        });
+    }
+
+    /// Kullanımı değiştirir (negatif değerler düşer).
+    pub fn adjust(&self, id: QuotaId, blocks: i64, inodes: i64) {
+        if blocks == 0 && inodes == 0 {
+            return;
+        }
+        self.with(|entries, dirty| {
+            let usage = entries.entry(id).or_default();
+            usage.blocks = usage.blocks.saturating_add_signed(blocks);
+            usage.inodes = usage.inodes.saturating_add_signed(inodes);
+            dirty.insert(id);
+        });
+    }
+
+    /// `owner` ve `subvolume` kotalarına `count` inode daha eklenebilir mi?
+    pub fn allows_inodes(&self, owner: QuotaId, subvolume: QuotaId, count: u64) -> bool {
+        self.with(|entries, _| {
+            [owner, subvolume].iter().all(|id| entries.get(id).map_or(true, |usage| usage.allows_inodes(count)))
+        })
+    }
+
+    /// `owner` ve `subvolume` kotalarına (bekleyen ayırmalar dahil) `blocks` blok daha
+    /// eklenebilir mi?
+    pub fn allows_blocks(&self, owner: QuotaId, subvolume: QuotaId, blocks: u64) -> bool {
+        self.with(|entries, _| self.allows_blocks_locked(entries, owner, subvolume, blocks))
+    }
+
+    /// `allows_blocks` gövdesi (`lock` tutulmalıdır).
+    fn allows_blocks_locked(&self, entries: &BTreeMap<QuotaId, QuotaUsage>, owner: QuotaId, subvolume: QuotaId, blocks: u64) -> bool {
+        [owner, subvolume].iter().all(|id| {
+            entries.get(id).map_or(true, |usage| usage.allows_blocks(blocks.saturating_add(self.pending_locked(*id))))
+        })
+    }
+
+    /// `inode_id` dosyasının tamponlu sayfaları için `blocks` blok ayırır; ayırma sayfalar
+    /// geri yazılınca veya atılınca `release_pending` ile bırakılır. `owner` veya
+    /// `subvolume` kotası aşılacaksa hiçbir şey ayrılmaz ve `false` döner.
+    pub fn reserve_pending(&self, inode_id: BlockId, owner: QuotaId, subvolume: QuotaId, blocks: u64) -> bool {
+        if blocks == 0 {
+            return true;
+        }
+        self.lock.acquire();
+        let allowed = self.allows_blocks_locked(unsafe { &*self.entries.get() }, owner, subvolume, blocks);
+        if allowed {
+            *unsafe { &mut *self.pending.get() }.entry((inode_id, owner, subvolume)).or_insert(0) += blocks;
+        }
+        self.lock.release();
+        allowed
+    }
+
+    /// `inode_id` dosyasının tamponlu sayfaları için ayrılan blokları bırakır.
+    pub fn release_pending(&self, inode_id: BlockId) {
+        self.lock.acquire();
+        unsafe { &mut *self.pending.get() }.retain(|(id, ..), _| *id != inode_id);
+        self.lock.release();
+    }
+
+    /// `id` kotasına sayılan, geri yazılmayı bekleyen blok sayısı (`lock` tutulmalıdır).
+    fn pending_locked(&self, id: QuotaId) -> u64 {
+        unsafe { &*self.pending.get() }
+            .iter()
+            .filter(|((_, owner, subvolume), _)| *owner == id || *subvolume == id)
+            .map(|(_, blocks)| blocks)
+            .sum()
+    }
+
+    /// Sonraki tahsisleri `owner` ve `subvolume` kotalarına yazmaya başlar.
+    pub fn begin_charge(&self, owner: QuotaId, subvolume: QuotaId) {
+        self.lock.acquire();
+        unsafe {
+            *self.charge.get() = Some((owner, subvolume));
+            *self.reserved.get() = 0;
+        }
+        self.lock.release();
+    }
+
+    /// Tahsisleri kotaya yazmayı bırakır; ayrılan miktar unutulur.
+    pub fn end_charge(&self) {
+        self.lock.acquire();
+        unsafe {
+            *self.charge.get() = None;
+            *self.reserved.get() = 0;
+        }
+        self.lock.release();
+    }
+
+    /// Geçerli sahip adına en fazla `max` blok ayırır ve ayrılan sayıyı döndürür;
+    /// sınıra ulaşıldıysa 0. Sahip bildirilmemişse `max` döner.
+    pub fn reserve(&self, max: u64) -> u64 {
+        self.lock.acquire();
+        let entries = unsafe { &*self.entries.get() };
+        let reserved = unsafe { &mut *self.reserved.get() };
+        let granted = match unsafe { *self.charge.get() } {
+            None => max,
+            Some((owner, subvolume)) => [owner, subvolume].iter().fold(max, |granted, id| {
+                match entries.get(id) {
+                    Some(usage) if usage.block_limit != 0 => {
+                        let used = usage.blocks.saturating_add(*reserved).saturating_add(self.pending_locked(*id));
+                        granted.min(usage.block_limit.saturating_sub(used))
+                    }
+                    _ => granted,
+                }
+            }),
+        };
+        *reserved += granted;
+        self.lock.release();
+        granted
+    }
+
+    /// Ayrılıp kullanılmayan blokları geri verir.
+    pub fn unreserve(&self, blocks: u64) {
+        self.lock.acquire();
+        let reserved = unsafe { &mut *self.reserved.get() };
+        *reserved = reserved.saturating_sub(blocks);
+        self.lock.release();
+    }
+
+    /// Son commit'ten beri değişen kayıtları döndürür ve değişmemiş sayar.
+    pub fn take_dirty(&self) -> Vec<(QuotaId, QuotaUsage)> {
+        self.with(|entries, dirty| {
+            let changed = dirty.iter().map(|id| (*id, entries.get(id).copied().unwrap_or_default())).collect();
+            dirty.clear();
+            changed
+        })
+    }
+
+    /// Tabloyu `all` ile alınmış bir önceki hale döndürür (geri alınan transaction).
+    /// Değişmiş kayıtlar ve ayrılan miktar unutulur.
+    pub fn restore(&self, records: Vec<(QuotaId, QuotaUsage)>) {
+        self.with(|entries, dirty| {
+            *entries = records.into_iter().collect();
+            dirty.clear();
+        });
+        self.end_charge();
+    }
+
+    /// Kayıtları `all` ile alınmış bir önceki hale döndürür (geri alınan tek işlem).
+    /// `restore`'dan farklı olarak değişmiş işaretleri korunur; önceki işlemlerin
+    /// değişiklikleri yine commit'te yazılır.
+    pub fn rewind(&self, records: Vec<(QuotaId, QuotaUsage)>) {
+        self.with(|entries, dirty| {
+            *entries = records.into_iter().collect();
+            // İşlemde oluşturulup geri alınan kayıtlar yazılmaz
+            dirty.retain(|id| entries.contains_key(id));
+        });
+        self.end_charge();
+    }
+
+    /// Kullanım sayaçlarını sıfırlar (sınırlar korunur); yeniden sayımdan önce çağrılır.
+    pub fn clear_usage(&self) {
+        self.with(|entries, dirty| {
+            for (id, usage) in entries.iter_mut() {
+                usage.blocks = 0;
+                usage.inodes = 0;
+                dirty.insert(*id);
+            }
+        });
+    }
+
+    /// Tablo üzerinde kilit altında işlem yapar.
+    fn with<R>(&self, f: impl FnOnce(&mut BTreeMap<QuotaId, QuotaUsage>, &mut BTreeSet<QuotaId>) -> R) -> R {
+        self.lock.acquire();
+        let result = f(unsafe { &mut *self.entries.get() }, unsafe { &mut *self.dirty.get() });
+        self.lock.release();
+        result
+    }
+}
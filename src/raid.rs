@@ -3,8 +3,12 @@
 #![allow(dead_code, unused_variables)]
 
 use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE, BlockDeviceError};
+use crate::hotplug::{DeviceEvent, DeviceId};
+use crate::cache::try_zeroed;
 use crate::sahne_syscalls::SyscallError;
 use core::fmt::{self, Debug};
+use core::sync::atomic::{AtomicU8, Ordering};
+use alloc::vec;
 use alloc::vec::Vec;
 use alloc::sync::Arc;
 
@@ -20,6 +24,8 @@ pub enum RaidError<D: BlockDevice> {
     NotEnoughDevices,
     /// Aygıtların boyutları (blok sayısı) birbirini tutmuyor.
     SizeMismatch,
+    /// Dizide okunabilir (çevrim içi) hiçbir üye kalmadı.
+    NoOnlineMembers,
     /// Dahili kilitlenme veya sistem çağrısı hatası.
     Syscall(SyscallError),
 }
@@ -35,19 +41,119 @@ impl<D: BlockDevice> From<SyscallError> for RaidError<D> {
 
 // --- 2. RAID-1 Yapısı ---
 
+// Üye durumları
+const MEMBER_ONLINE: u8 = 0;
+// Aygıt çıkarıldı; okuma ve yazmalar bu üyeyi atlar.
+const MEMBER_MISSING: u8 = 1;
+// Aygıt geri döndü ve eşitleniyor; yazmalar alır ama okunmaz.
+const MEMBER_REBUILDING: u8 = 2;
+
+/// Bir aygıt olayının dizi üzerindeki etkisi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotplugAction {
+    /// Olay bu dizinin bir üyesiyle ilgili değil.
+    Ignored,
+    /// Üye çıkarıldı; dizi kalan üyelerle bozulmuş (degraded) modda çalışıyor.
+    Degraded(usize),
+    /// Son çevrim içi üye de çıkarıldı; dizi artık I/O yapamaz.
+    Failed(usize),
+    /// Çıkarılmış üye geri döndü; `reassemble` ile yeniden diziye alınabilir.
+    ReassemblyAvailable(usize),
+}
+
+/// Bir üyenin bağlı olduğu arıza alanı (failure domain). Aynı denetleyiciye veya aynı
+/// kasaya bağlı aygıtlar tek bir arızayla birlikte kaybedilebilir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailureDomain {
+    /// Aygıtın bağlı olduğu denetleyici (controller) etiketi.
+    pub controller: u32,
+    /// Aygıtın bulunduğu kasa (enclosure) etiketi.
+    pub enclosure: u32,
+}
+
+/// Dizinin yedekliliğinin tek bir arıza alanına çöktüğünü bildiren uyarı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedundancyWarning {
+    /// Tüm çevrim içi üyeler aynı denetleyiciye bağlı.
+    SingleController(u32),
+    /// Tüm çevrim içi üyeler aynı kasada.
+    SingleEnclosure(u32),
+}
+
 /// İki veya daha fazla fiziksel diski tek bir mantıksal disk gibi yöneten
 /// RAID-1 (Mirroring/Yansıtma) implementasyonu.
 /// SADAK, bu yapıyı temel BlockDevice olarak kullanacaktır.
 pub struct Raid1Device<D: BlockDevice> {
     /// Verinin kopyalanacağı fiziksel disklerin listesi.
     devices: Vec<Arc<D>>,
+    /// Her üyenin çekirdekteki aygıt kimliği (hotplug olaylarını eşlemek için).
+    device_ids: Vec<Option<DeviceId>>,
+    /// Her üyenin arıza alanı (biliniyorsa).
+    domains: Vec<Option<FailureDomain>>,
+    /// Her üyenin durumu (`MEMBER_*`).
+    states: Vec<AtomicU8>,
     /// En küçük aygıtın toplam blok sayısı (Tüm diskler bu boyutta görünür).
     total_blocks: BlockId,
 }
 
 impl<D: BlockDevice> Raid1Device<D> {
-    /// Yeni bir RAID-1 dizisi oluşturur.
+    /// Yeni bir RAID-1 dizisi oluşturur. Üyelerin aygıt kimliği bilinmediğinden
+    /// hotplug olayları bu diziye eşlenmez; bunun için `new_with_ids` kullanılmalıdır.
     pub fn new(devices: Vec<Arc<D>>) -> Result<Self, RaidError<D>> {
+        let ids = vec![None; devices.len()];
+        let domains = vec![None; devices.len()];
+        Self::build(devices, ids, domains)
+    }
+
+    /// Üyeleri çekirdekteki aygıt kimlikleriyle birlikte alan yeni bir RAID-1 dizisi oluşturur.
+    /// `ids[i]`, `devices[i]`'nin kimliğidir.
+    pub fn new_with_ids(devices: Vec<Arc<D>>, ids: Vec<DeviceId>) -> Result<Self, RaidError<D>> {
+        if ids.len() != devices.len() {
+            return Err(RaidError::Syscall(SyscallError::EINVAL));
+        }
+        let domains = vec![None; devices.len()];
+        Self::build(devices, ids.into_iter().map(Some).collect(), domains)
+    }
+
+    /// Üyeleri aygıt kimlikleri ve arıza alanı etiketleriyle birlikte alan yeni bir RAID-1
+    /// dizisi oluşturur. `ids[i]` ve `domains[i]`, `devices[i]`'ye aittir.
+    ///
+    /// Üyeler, ardışık üyeler farklı alanlarda olacak şekilde alanlar arasında dönüşümlü
+    /// sıralanır; böylece okumalar ve yeniden eşitleme kaynağı öncelikle farklı alanlardan
+    /// seçilir. Dizinin yedekliliği tek bir alana çökmüşse `redundancy_warning` bunu bildirir.
+    pub fn new_with_domains(
+        devices: Vec<Arc<D>>,
+        ids: Vec<DeviceId>,
+        domains: Vec<FailureDomain>,
+    ) -> Result<Self, RaidError<D>> {
+        if ids.len() != devices.len() || domains.len() != devices.len() {
+            return Err(RaidError::Syscall(SyscallError::EINVAL));
+        }
+        let order = interleave_by_domain(&domains);
+        let mut slots: Vec<Option<(Arc<D>, DeviceId, FailureDomain)>> = devices.into_iter()
+            .zip(ids)
+            .zip(domains)
+            .map(|((device, id), domain)| Some((device, id, domain)))
+            .collect();
+
+        let mut devices = Vec::with_capacity(slots.len());
+        let mut ids = Vec::with_capacity(slots.len());
+        let mut domains = Vec::with_capacity(slots.len());
+        for index in order {
+            // `order` her indeksi tam olarak bir kez içerir; aksi bir hata olarak döner.
+            let (device, id, domain) = slots[index].take().ok_or(RaidError::Syscall(SyscallError::EINVAL))?;
+            devices.push(device);
+            ids.push(Some(id));
+            domains.push(Some(domain));
+        }
+        Self::build(devices, ids, domains)
+    }
+
+    fn build(
+        devices: Vec<Arc<D>>,
+        device_ids: Vec<Option<DeviceId>>,
+        domains: Vec<Option<FailureDomain>>,
+    ) -> Result<Self, RaidError<D>> {
         if devices.len() < 2 {
             return Err(RaidError::NotEnoughDevices);
         }
@@ -55,8 +161,7 @@ impl<D: BlockDevice> Raid1Device<D> {
         let min_blocks = devices.iter()
             .map(|d| d.total_blocks())
             .min()
-            // Zaten en az iki cihaz olduğu için unwrap güvenlidir.
-            .unwrap(); 
+            .ok_or(RaidError::NotEnoughDevices)?;
 
         // Boyut uyumsuzluğunu kontrol et (İsteğe bağlı, ancak önemlidir)
         let mismatch = devices.iter()
@@ -68,14 +173,201 @@ impl<D: BlockDevice> Raid1Device<D> {
             return Err(RaidError::SizeMismatch);
         }
 
+        let states = devices.iter().map(|_| AtomicU8::new(MEMBER_ONLINE)).collect();
         Ok(Raid1Device {
             devices,
+            device_ids,
+            domains,
+            states,
             total_blocks: min_blocks,
         })
     }
+
+    // --- Hotplug ---
+
+    /// Bir aygıt olayını diziye uygular.
+    ///
+    /// Haber verilmeden çıkarılan üye hemen devre dışı bırakılır ve dizi kalan üyelerle
+    /// çalışmaya devam eder. Geri dönen üye otomatik olarak diziye alınmaz (içeriği
+    /// eskidir); `ReassemblyAvailable` ile bildirilir ve `reassemble` ile eşitlenir.
+    pub fn handle_event(&self, event: DeviceEvent) -> HotplugAction {
+        let (id, removed) = match event {
+            DeviceEvent::Removed(id) => (id, true),
+            DeviceEvent::Inserted(id) => (id, false),
+        };
+        let index = match self.device_ids.iter().position(|member| *member == Some(id)) {
+            Some(index) => index,
+            None => return HotplugAction::Ignored,
+        };
+
+        if removed {
+            self.states[index].store(MEMBER_MISSING, Ordering::Release);
+            if self.online_members() == 0 {
+                HotplugAction::Failed(index)
+            } else {
+                HotplugAction::Degraded(index)
+            }
+        } else if self.states[index].load(Ordering::Acquire) == MEMBER_MISSING {
+            HotplugAction::ReassemblyAvailable(index)
+        } else {
+            HotplugAction::Ignored
+        }
+    }
+
+    /// Geri dönen bir üyeyi çevrim içi bir üyeden blok blok eşitleyip diziye geri alır.
+    ///
+    /// Eşitleme sırasında üye yazmaları almaya devam eder, okumalar ise diğer
+    /// üyelerden yapılır; böylece dizi eşitleme boyunca kullanılabilir kalır.
+    pub fn reassemble(&self, index: usize) -> Result<(), RaidError<D>> {
+        if index >= self.devices.len() {
+            return Err(RaidError::Syscall(SyscallError::EINVAL));
+        }
+        if self.states[index].load(Ordering::Acquire) == MEMBER_ONLINE {
+            return Ok(());
+        }
+        // Mümkünse eşitlenen üyeyle aynı arıza alanını paylaşmayan bir kaynak seç.
+        let candidates = || (0..self.devices.len())
+            .filter(move |&i| i != index && self.states[i].load(Ordering::Acquire) == MEMBER_ONLINE);
+        let source = candidates()
+            .find(|&i| !self.shares_domain(i, index))
+            .or_else(|| candidates().next())
+            .ok_or(RaidError::NoOnlineMembers)?;
+
+        self.states[index].store(MEMBER_REBUILDING, Ordering::Release);
+        let mut buffer = try_zeroed(BLOCK_SIZE)?;
+        for id in 0..self.total_blocks {
+            let copied = self.devices[source]
+                .read_block(id, &mut buffer)
+                .and_then(|_| self.devices[index].write_block(id, &buffer));
+            if let Err(e) = copied {
+                self.states[index].store(MEMBER_MISSING, Ordering::Release);
+                return Err(RaidError::IoError(vec![e]));
+            }
+        }
+        if let Err(e) = self.devices[index].flush() {
+            self.states[index].store(MEMBER_MISSING, Ordering::Release);
+            return Err(RaidError::IoError(vec![e]));
+        }
+
+        self.states[index].store(MEMBER_ONLINE, Ordering::Release);
+        Ok(())
+    }
+
+    /// Okuma yapılabilen (tam eşitlenmiş) üye sayısı.
+    pub fn online_members(&self) -> usize {
+        self.states.iter().filter(|s| s.load(Ordering::Acquire) == MEMBER_ONLINE).count()
+    }
+
+    /// Dizi en az bir üyesini kaybetmiş durumda mı?
+    pub fn is_degraded(&self) -> bool {
+        self.online_members() < self.devices.len()
+    }
+
+    /// Üyenin arıza alanı (`new_with_domains` ile kurulmamışsa `None`).
+    pub fn member_domain(&self, index: usize) -> Option<FailureDomain> {
+        self.domains.get(index).copied().flatten()
+    }
+
+    /// Dizinin yedekliliği tek bir arıza alanına çökmüş mü?
+    ///
+    /// Yazma alabilen en az iki üye varken bunların tümü aynı denetleyiciye veya aynı
+    /// kasaya bağlıysa, tek bir denetleyici/kasa arızası tüm kopyaları birlikte götürür.
+    /// Arıza alanı bilinmeyen üyeler varsa uyarı üretilmez.
+    pub fn redundancy_warning(&self) -> Option<RedundancyWarning> {
+        let mut domains = Vec::new();
+        for index in 0..self.devices.len() {
+            if !self.is_writable(index) {
+                continue;
+            }
+            domains.push(self.domains[index]?);
+        }
+        if domains.len() < 2 {
+            return None;
+        }
+        let first = domains[0];
+        if domains.iter().all(|d| d.controller == first.controller) {
+            return Some(RedundancyWarning::SingleController(first.controller));
+        }
+        if domains.iter().all(|d| d.enclosure == first.enclosure) {
+            return Some(RedundancyWarning::SingleEnclosure(first.enclosure));
+        }
+        None
+    }
+
+    /// İki üye aynı denetleyiciyi veya kasayı paylaşıyor mu?
+    fn shares_domain(&self, a: usize, b: usize) -> bool {
+        match (self.domains[a], self.domains[b]) {
+            (Some(x), Some(y)) => x.controller == y.controller || x.enclosure == y.enclosure,
+            _ => false,
+        }
+    }
+
+    /// Üye okumalara açık mı?
+    fn is_readable(&self, index: usize) -> bool {
+        self.states[index].load(Ordering::Acquire) == MEMBER_ONLINE
+    }
+
+    /// Üye yazmaları almalı mı? (Çevrim içi veya eşitlenmekte.)
+    fn is_writable(&self, index: usize) -> bool {
+        self.states[index].load(Ordering::Acquire) != MEMBER_MISSING
+    }
 }
 
 
+/// Üyeleri arıza alanlarına göre gruplayıp gruplar arasında dönüşümlü sıralar.
+/// Gruplar önce denetleyiciye, denetleyici içinde kasaya göre ayrılır; her turda
+/// her gruptan bir üye alınır. Dönen vektör özgün indekslerin bir permütasyonudur.
+fn interleave_by_domain(domains: &[FailureDomain]) -> Vec<usize> {
+    let mut groups: Vec<(FailureDomain, Vec<usize>)> = Vec::new();
+    for (index, domain) in domains.iter().enumerate() {
+        match groups.iter_mut().find(|(d, _)| d == domain) {
+            Some((_, members)) => members.push(index),
+            None => groups.push((*domain, vec![index])),
+        }
+    }
+    // Aynı denetleyicideki kasaları yan yana koy; tur sırası önce denetleyiciyi değiştirsin.
+    groups.sort_by_key(|(d, _)| (d.controller, d.enclosure));
+    let controllers: Vec<u32> = {
+        let mut c: Vec<u32> = groups.iter().map(|(d, _)| d.controller).collect();
+        c.dedup();
+        c
+    };
+    // Her denetleyici için kendi kasaları arasında dönüşümlü bir kuyruk oluştur.
+    let queues: Vec<Vec<usize>> = controllers.iter().map(|&controller| {
+        let members: Vec<&Vec<usize>> = groups.iter()
+            .filter(|(d, _)| d.controller == controller)
+            .map(|(_, m)| m)
+            .collect();
+        let longest = members.iter().map(|m| m.len()).max().unwrap_or(0);
+        let mut queue = Vec::new();
+        for round in 0..longest {
+            for m in &members {
+                if let Some(&index) = m.get(round) {
+                    queue.push(index);
+                }
+            }
+        }
+        queue
+    }).collect();
+
+    let mut order = Vec::with_capacity(domains.len());
+    let longest = queues.iter().map(|q| q.len()).max().unwrap_or(0);
+    for round in 0..longest {
+        for queue in &queues {
+            if let Some(&index) = queue.get(round) {
+                order.push(index);
+            }
+        }
+    }
+    order
+}
+
+// TODO: RAID-5/6 eklendiğinde, parite yazılmadan önce güncellenen küçük bir şerit
+// (stripe) başına sıra numarası/CRC günlüğü tutulmalı. Güç kesintisinden sonra
+// yarım yazılmış (torn) şeritler bu günlükle tespit edilip sessizce bozuk veri
+// sunulmak yerine pariteden yeniden oluşturulmalı. Bu ağaçta şimdilik yalnızca
+// yansıtma (RAID-1) var; RAID-1'de her blok tam kopya olduğundan parite yok.
+
 // --- 3. BlockDevice Trait'inin Uygulanması ---
 
 impl<D: BlockDevice + Sync + Send + 'static> BlockDevice for Raid1Device<D> {
@@ -88,7 +380,10 @@ impl<D: BlockDevice + Sync + Send + 'static> BlockDevice for Raid1Device<D> {
         let mut errors = Vec::new();
 
         // Cihazları sırayla oku. İlk başarılı okuma yeterlidir.
-        for device in self.devices.iter() {
+        for (index, device) in self.devices.iter().enumerate() {
+            if !self.is_readable(index) {
+                continue; // Çıkarılmış veya henüz eşitlenmemiş üye
+            }
             match device.read_block(id, buffer) {
                 Ok(_) => return Ok(()), // Başarılı, hemen dön
                 Err(e) => {
@@ -99,6 +394,9 @@ impl<D: BlockDevice + Sync + Send + 'static> BlockDevice for Raid1Device<D> {
         }
 
         // Tüm okuma denemeleri başarısız olduysa
+        if errors.is_empty() {
+            return Err(RaidError::NoOnlineMembers);
+        }
         Err(RaidError::IoError(errors))
     }
 
@@ -106,9 +404,14 @@ impl<D: BlockDevice + Sync + Send + 'static> BlockDevice for Raid1Device<D> {
     fn write_block(&self, id: BlockId, data: &[u8]) -> Result<(), Self::Error> {
         let mut errors = Vec::new();
         let mut successful_writes = 0;
+        let mut attempted = 0;
 
-        // Tüm cihazlara yaz.
-        for device in self.devices.iter() {
+        // Çıkarılmamış tüm cihazlara yaz.
+        for (index, device) in self.devices.iter().enumerate() {
+            if !self.is_writable(index) {
+                continue;
+            }
+            attempted += 1;
             match device.write_block(id, data) {
                 Ok(_) => successful_writes += 1,
                 Err(e) => {
@@ -118,8 +421,10 @@ impl<D: BlockDevice + Sync + Send + 'static> BlockDevice for Raid1Device<D> {
             }
         }
         
-        // RAID-1 için: Tüm kopyaların yazılması idealdir.
-        if successful_writes < self.devices.len() {
+        // RAID-1 için: Tüm kopyaların yazılması idealdir (bozulmuş modda kalan üyelerin).
+        if attempted == 0 {
+            Err(RaidError::NoOnlineMembers)
+        } else if successful_writes < attempted {
             // Yazma başarısız oldu, hata döndürülmeli.
             Err(RaidError::IoError(errors)) 
         } else {
@@ -136,18 +441,39 @@ impl<D: BlockDevice + Sync + Send + 'static> BlockDevice for Raid1Device<D> {
     fn flush(&self) -> Result<(), Self::Error> {
         let mut errors = Vec::new();
         let mut successful_flushes = 0;
+        let mut attempted = 0;
 
-        for device in self.devices.iter() {
+        for (index, device) in self.devices.iter().enumerate() {
+            if !self.is_writable(index) {
+                continue;
+            }
+            attempted += 1;
             match device.flush() {
                 Ok(_) => successful_flushes += 1,
                 Err(e) => errors.push(e),
             }
         }
         
-        if successful_flushes < self.devices.len() {
+        if successful_flushes < attempted {
              Err(RaidError::IoError(errors)) 
         } else {
             Ok(())
         }
     }
+
+    /// Her üye bloğun tam bir kopyasını tutar.
+    fn copies(&self) -> usize {
+        self.devices.len()
+    }
+
+    /// `copy` numaralı üyeden okur; üye çıkarılmış veya eşitleniyorsa kopyası yoktur.
+    fn read_copy(&self, id: BlockId, copy: usize, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if copy >= self.devices.len() {
+            return Err(RaidError::Syscall(SyscallError::EINVAL));
+        }
+        if !self.is_readable(copy) {
+            return Err(RaidError::NoOnlineMembers);
+        }
+        self.devices[copy].read_block(id, buffer).map_err(|e| RaidError::IoError(vec![e]))
+    }
 }
\ No newline at end of file
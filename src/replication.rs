@@ -0,0 +1,161 @@
+// src/replication.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockId, BLOCK_SIZE};
+use crate::checksum::checksum_data;
+use crate::sahne_syscalls::{self, ResourceHandle, SyscallError, SYSCALL_RESOURCE_RELEASE, SYSCALL_RESOURCE_WRITE, raw_syscall};
+
+
+// --- 1. Sabitler ---
+
+// Akış başlığının sihirli sayısı ("SSND").
+const STREAM_MAGIC: u32 = 0x5353_4E44;
+
+// Akış biçiminin sürümü; uyuşmayan akışlar reddedilir.
+const STREAM_VERSION: u32 = 1;
+
+/// Akış başlığı: magic (4) + sürüm (4) + temel (4) + hedef (4) + toplam blok (8)
+/// + dönem (8) + checksum (4) + ayrılmış (4)
+pub const STREAM_HEADER_SIZE: usize = 40;
+
+/// Aralık kaydı başlığı: ilk blok (8) + uzunluk (4) + verinin checksum'u (4). Ardından
+/// `uzunluk * BLOCK_SIZE` bayt blok verisi gelir; uzunluğu 0 olan kayıt akışı bitirir.
+pub const STREAM_RUN_HEADER_SIZE: usize = 16;
+
+/// Tek kayıtta gönderilen en fazla blok; daha uzun aralıklar bölünür.
+pub const STREAM_RUN_MAX_BLOCKS: u64 = 256;
+
+
+// --- 2. Akış Başlığı ---
+
+/// Bir snapshot'ın (tamamının veya bir temel snapshot'a göre değişen bloklarının)
+/// taşınabilir çoğaltma akışının başlığı (bkz. `SadakFs::send`, `SadakFs::receive`).
+///
+/// Snapshot'lar dondurulmuş Superblock'larının checksum'uyla tanımlanır
+/// (`Snapshot::stream_id`). Alınan akıştan sonra hedef aygıtın Blok 0'ı bu Superblock'un
+/// birebir kopyasıdır; artımlı bir akış yalnızca Blok 0'ı `base` ile uyuşan aygıta uygulanır.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamHeader {
+    /// Temel snapshot'ın kimliği; tam akışta 0.
+    pub base: u32,
+    /// Gönderilen snapshot'ın kimliği.
+    pub target: u32,
+    /// Gönderilen snapshot'taki toplam blok sayısı.
+    pub total_blocks: u64,
+    /// Gönderilen snapshot'ın dönemi (`Snapshot::epoch`).
+    pub epoch: u64,
+}
+
+impl StreamHeader {
+    /// Akış başlığının bayt karşılığı.
+    pub fn encode(&self) -> [u8; STREAM_HEADER_SIZE] {
+        let mut out = [0u8; STREAM_HEADER_SIZE];
+        out[0..4].copy_from_slice(&STREAM_MAGIC.to_le_bytes());
+        out[4..8].copy_from_slice(&STREAM_VERSION.to_le_bytes());
+        out[8..12].copy_from_slice(&self.base.to_le_bytes());
+        out[12..16].copy_from_slice(&self.target.to_le_bytes());
+        out[16..24].copy_from_slice(&self.total_blocks.to_le_bytes());
+        out[24..32].copy_from_slice(&self.epoch.to_le_bytes());
+        let crc = checksum_data(&out[..32]);
+        out[32..36].copy_from_slice(&crc.to_le_bytes());
+        out
+    }
+
+    /// Başlığı çözer; magic, sürüm veya checksum uyuşmazsa `None`.
+    pub fn decode(raw: &[u8; STREAM_HEADER_SIZE]) -> Option<Self> {
+        let u32_at = |at: usize| u32::from_le_bytes([raw[at], raw[at + 1], raw[at + 2], raw[at + 3]]);
+        let u64_at = |at: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&raw[at..at + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        if u32_at(0) != STREAM_MAGIC || u32_at(4) != STREAM_VERSION || u32_at(32) != checksum_data(&raw[..32]) {
+            return None;
+        }
+        Some(StreamHeader { base: u32_at(8), target: u32_at(12), total_blocks: u64_at(16), epoch: u64_at(24) })
+    }
+}
+
+/// Bir aralık kaydının başlığı; `data` kaydın blok verisidir (bitiş kaydında boş).
+pub fn encode_run(start: BlockId, data: &[u8]) -> [u8; STREAM_RUN_HEADER_SIZE] {
+    let mut out = [0u8; STREAM_RUN_HEADER_SIZE];
+    out[0..8].copy_from_slice(&start.to_le_bytes());
+    out[8..12].copy_from_slice(&((data.len() / BLOCK_SIZE) as u32).to_le_bytes());
+    out[12..16].copy_from_slice(&checksum_data(data).to_le_bytes());
+    out
+}
+
+/// Aralık kaydı başlığını (ilk blok, uzunluk, verinin checksum'u) olarak çözer.
+pub fn decode_run(raw: &[u8; STREAM_RUN_HEADER_SIZE]) -> (BlockId, u64, u32) {
+    let mut start = [0u8; 8];
+    start.copy_from_slice(&raw[0..8]);
+    let length = u32::from_le_bytes([raw[8], raw[9], raw[10], raw[11]]);
+    let crc = u32::from_le_bytes([raw[12], raw[13], raw[14], raw[15]]);
+    (u64::from_le_bytes(start), length as u64, crc)
+}
+
+
+// --- 3. Akış Uçları ---
+
+/// Gönderilen akışın yazıldığı uç (ör. bir dosya, boru veya ağ kaynağı).
+pub trait SendSink {
+    /// `data`'nın tamamını akışa ekler.
+    fn write_all(&mut self, data: &[u8]) -> Result<(), SyscallError>;
+}
+
+/// Alınan akışın okunduğu uç.
+pub trait ReceiveSource {
+    /// `buf`'u tamamen doldurur; akış erken biterse `EIO`.
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), SyscallError>;
+}
+
+/// Akışı bir Sahne64 kaynağına yazan veya ondan okuyan uç.
+pub struct ResourceStream {
+    handle: ResourceHandle,
+}
+
+impl ResourceStream {
+    /// `path` kaynağını edinir.
+    pub fn open(path: &str) -> Result<Self, SyscallError> {
+        let handle = sahne_syscalls::resource_acquire(path.as_ptr(), path.len())?;
+        Ok(ResourceStream { handle })
+    }
+}
+
+impl SendSink for ResourceStream {
+    fn write_all(&mut self, mut data: &[u8]) -> Result<(), SyscallError> {
+        while !data.is_empty() {
+            let written = unsafe {
+                raw_syscall(SYSCALL_RESOURCE_WRITE, self.handle, data.as_ptr() as u64, data.len() as u64, 0, 0, 0)
+            };
+            if written < 0 {
+                return Err(SyscallError::from_raw(written));
+            }
+            if written == 0 {
+                return Err(SyscallError::EIO);
+            }
+            data = &data[written as usize..];
+        }
+        Ok(())
+    }
+}
+
+impl ReceiveSource for ResourceStream {
+    fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<(), SyscallError> {
+        while !buf.is_empty() {
+            let read = sahne_syscalls::resource_read(self.handle, buf.as_mut_ptr(), buf.len())?;
+            if read == 0 {
+                return Err(SyscallError::EIO); // Akış erken bitti
+            }
+            buf = &mut buf[read..];
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ResourceStream {
+    fn drop(&mut self) {
+        unsafe { raw_syscall(SYSCALL_RESOURCE_RELEASE, self.handle, 0, 0, 0, 0, 0) };
+    }
+}
@@ -21,10 +21,16 @@ pub const SYSCALL_TASK_SLEEP: u64 = 10;
 pub const SYSCALL_LOCK_CREATE: u64 = 11;
 pub const SYSCALL_LOCK_ACQUIRE: u64 = 12;
 pub const SYSCALL_LOCK_RELEASE: u64 = 13;
+pub const SYSCALL_LOCK_DESTROY: u64 = 14;
 pub const SYSCALL_GET_SYSTEM_TIME: u64 = 16;
 // Yeni I/O ve Kontrol Çağrıları
 pub const SYSCALL_RESOURCE_CONTROL: u64 = 102;
 pub const SYSCALL_RESOURCE_SEEK: u64 = 103;   
+// Doğrudan aygıt erişimi (virtio vb. taşıyıcılar için)
+pub const SYSCALL_RESOURCE_MAP: u64 = 104; // Kaynağın yazmaç penceresini adres alanına eşler
+pub const SYSCALL_DMA_ALLOCATE: u64 = 105; // Fiziksel olarak ardışık bellek; fiziksel adres çıkış argümanına yazılır
+pub const SYSCALL_DMA_RELEASE: u64 = 106;
+pub const SYSCALL_NOTIFICATION_WAIT: u64 = 107; // Kaynağın kesme bildirimini bekler
 
 
 // --- 2. Temel Veri Tipleri ---
@@ -114,4 +120,14 @@ pub fn resource_read(handle: ResourceHandle, buffer_ptr: *mut u8, length: Length
     } else {
         Ok(result as Length) // Başarıyla okunan bayt sayısı
     }
+}
+
+/// Çağıran görevin kimliğini döndürür.
+pub fn task_id() -> Result<u64, SyscallError> {
+    let result = unsafe { raw_syscall(SYSCALL_GET_TASK_ID, 0, 0, 0, 0, 0, 0) };
+    if result < 0 {
+        Err(SyscallError::from_raw(result))
+    } else {
+        Ok(result as u64)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,243 @@
+// src/seal.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE};
+use core::cell::UnsafeCell;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ---
+
+// Merkle ağacındaki bir özetin bayt boyutu (SHA-256).
+pub const SEAL_HASH_SIZE: usize = 32;
+
+// Bir özet bloğunun tuttuğu özet sayısı.
+pub const SEAL_HASHES_PER_BLOCK: u64 = (BLOCK_SIZE / SEAL_HASH_SIZE) as u64;
+
+// Doğrulanmış özet bloklarının bellekte tutulan en fazla sayısı; dolunca önbellek boşaltılır.
+const VERIFIED_NODE_CACHE_MAX: usize = 64;
+
+
+// --- 2. SHA-256 ---
+// Mühür, rastgele bozulmanın yanında kasıtlı değişikliği de yakalamalıdır; bu yüzden
+// metadata checksum'larındaki CRC32C yerine kriptografik bir özet kullanılır.
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const SHA256_INITIAL: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Verinin SHA-256 özetini hesaplar.
+pub fn sha256(data: &[u8]) -> [u8; SEAL_HASH_SIZE] {
+    let mut state = SHA256_INITIAL;
+
+    // Tam 64 baytlık parçalar doğrudan, kalan kısım ise doldurmayla birlikte işlenir
+    let mut chunks = data.chunks_exact(64);
+    for chunk in &mut chunks {
+        sha256_compress(&mut state, chunk);
+    }
+    let rest = chunks.remainder();
+    let mut tail = [0u8; 128];
+    tail[..rest.len()].copy_from_slice(rest);
+    tail[rest.len()] = 0x80;
+    let tail_len = if rest.len() < 56 { 64 } else { 128 };
+    tail[tail_len - 8..tail_len].copy_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+    for chunk in tail[..tail_len].chunks_exact(64) {
+        sha256_compress(&mut state, chunk);
+    }
+
+    let mut out = [0u8; SEAL_HASH_SIZE];
+    for (i, word) in state.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+/// Tek bir 64 baytlık parçayı duruma katar.
+fn sha256_compress(state: &mut [u32; 8], chunk: &[u8]) {
+    let mut w = [0u32; 64];
+    for i in 0..16 {
+        w[i] = u32::from_be_bytes([chunk[i * 4], chunk[i * 4 + 1], chunk[i * 4 + 2], chunk[i * 4 + 3]]);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(SHA256_K[i]).wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+
+// --- 3. Merkle Ağacı Yerleşimi ---
+
+/// Mühürlü bir birimin özet ağacının diskteki yerleşimi.
+///
+/// Birimin her bloğu (`0..leaves`) bir yapraktır; yaprak özeti bloğun SHA-256'sıdır.
+/// Superblock (blok 0) kendi checksum'uyla korunduğundan ve kök özeti taşıdığından,
+/// özet ağacının kendi blokları da ağaç üzerinden doğrulandığından bu blokların
+/// yaprağı sıfırdır. Seviyeler `tree_start`'tan başlayarak ardışık yerleşir: önce
+/// yaprak özetlerini tutan 0. seviye, en sonda tek bloklu tepe. Kök özet tepe bloğunun
+/// SHA-256'sıdır ve Superblock'ta saklanır.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealGeometry {
+    /// Yaprak (kapsanan blok) sayısı; mühürleme anındaki `Superblock::total_blocks`.
+    pub leaves: u64,
+    /// Özet ağacının ilk bloğu.
+    pub tree_start: BlockId,
+    /// Her seviyenin (ilk blok, blok sayısı) çifti; 0. seviye yaprakların hemen üstüdür.
+    levels: Vec<(BlockId, u64)>,
+}
+
+impl SealGeometry {
+    /// `leaves` bloğu kapsayan, `tree_start`'ta başlayan ağacın yerleşimi.
+    pub fn new(leaves: u64, tree_start: BlockId) -> Self {
+        let mut levels = Vec::new();
+        let mut start = tree_start;
+        let mut count = leaves.div_ceil(SEAL_HASHES_PER_BLOCK).max(1);
+        loop {
+            levels.push((start, count));
+            start += count;
+            if count == 1 {
+                break;
+            }
+            count = count.div_ceil(SEAL_HASHES_PER_BLOCK);
+        }
+        SealGeometry { leaves, tree_start, levels }
+    }
+
+    /// `leaves` bloğu kapsayan bir ağacın toplam blok sayısı.
+    pub fn tree_blocks_for(leaves: u64) -> u64 {
+        Self::new(leaves, 0).tree_blocks()
+    }
+
+    /// Ağacın toplam blok sayısı.
+    pub fn tree_blocks(&self) -> u64 {
+        self.levels.iter().map(|&(_, count)| count).sum()
+    }
+
+    /// Seviye sayısı.
+    pub fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// `level` seviyesinin (ilk blok, blok sayısı) çifti.
+    pub fn level(&self, level: usize) -> (BlockId, u64) {
+        self.levels[level]
+    }
+
+    /// Blok özet ağacına mı ait?
+    pub fn is_tree_block(&self, id: BlockId) -> bool {
+        id >= self.tree_start && id < self.tree_start + self.tree_blocks()
+    }
+
+    /// Bloğun yaprağı sıfır mı (Superblock, özet ağacı veya kapsam dışı)?
+    pub fn is_unsealed(&self, id: BlockId) -> bool {
+        id == 0 || id >= self.leaves || self.is_tree_block(id)
+    }
+}
+
+
+// --- 4. Okuma Doğrulayıcısı ---
+
+/// Mühürlü bir birimden okunan her bloğu kök özete karşı doğrular.
+///
+/// Blok önbelleği aygıttan okuduğu her bloğu `verify`'a verir (bkz.
+/// `BlockCache::set_seal`). Yoldaki özet blokları aygıttan doğrudan okunup tepeden
+/// aşağı doğrulanır; doğrulanmış olanlar `VERIFIED_NODE_CACHE_MAX`'a kadar bellekte
+/// tutulur. Yalnızca önbellek kilidi altında çağrılır.
+pub struct SealVerifier {
+    geometry: SealGeometry,
+    root: [u8; SEAL_HASH_SIZE],
+    /// Doğrulanmış özet blokları (blok ID'si -> içerik).
+    verified: UnsafeCell<BTreeMap<BlockId, Box<[u8; BLOCK_SIZE]>>>,
+}
+
+impl SealVerifier {
+    pub fn new(geometry: SealGeometry, root: [u8; SEAL_HASH_SIZE]) -> Self {
+        SealVerifier { geometry, root, verified: UnsafeCell::new(BTreeMap::new()) }
+    }
+
+    /// Özet ağacının yerleşimi.
+    pub fn geometry(&self) -> &SealGeometry {
+        &self.geometry
+    }
+
+    /// `id` bloğundan okunan `data`'nın mühürle uyuşup uyuşmadığını denetler.
+    /// Superblock her zaman kabul edilir; özet ağacının blokları ve kapsam dışı bloklar
+    /// dosya sistemi tarafından okunmaması gerektiğinden reddedilir.
+    pub fn verify<D: BlockDevice>(&self, device: &D, id: BlockId, data: &[u8]) -> Result<bool, D::Error> {
+        if id == 0 {
+            return Ok(true);
+        }
+        if self.geometry.is_unsealed(id) {
+            return Ok(false);
+        }
+
+        // Yol: her seviyedeki (blok ID'si, blok içindeki yuva); tepeden aşağıya doğrulanır
+        let mut path = Vec::with_capacity(self.geometry.depth());
+        let mut index = id;
+        for level in 0..self.geometry.depth() {
+            let slot = (index % SEAL_HASHES_PER_BLOCK) as usize;
+            index /= SEAL_HASHES_PER_BLOCK;
+            path.push((self.geometry.level(level).0 + index, slot));
+        }
+
+        let verified = unsafe { &mut *self.verified.get() };
+        let mut expected = self.root;
+        for &(node_id, slot) in path.iter().rev() {
+            if !verified.contains_key(&node_id) {
+                let mut node = Box::new([0u8; BLOCK_SIZE]);
+                device.read_block(node_id, node.as_mut())?;
+                if sha256(node.as_ref()) != expected {
+                    return Ok(false);
+                }
+                if verified.len() >= VERIFIED_NODE_CACHE_MAX {
+                    verified.clear();
+                }
+                verified.insert(node_id, node);
+            }
+            let node = &verified[&node_id];
+            expected.copy_from_slice(&node[slot * SEAL_HASH_SIZE..(slot + 1) * SEAL_HASH_SIZE]);
+        }
+
+        Ok(sha256(data) == expected)
+    }
+}
+
+// Doğrulayıcıya yalnızca önbellek kilidi altında erişilir.
+unsafe impl Sync for SealVerifier {}
+unsafe impl Send for SealVerifier {}
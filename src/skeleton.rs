@@ -0,0 +1,93 @@
+// src/skeleton.rs
+
+#![allow(dead_code, unused_variables)]
+
+use alloc::vec::Vec;
+
+
+// --- 1. İskelet Girdisi ---
+
+/// İskeletteki bir girdinin türü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkeletonKind<'a> {
+    /// Boş dizin.
+    Directory,
+    /// Boş dosya.
+    File,
+    /// Verilen hedefi gösteren sembolik bağlantı.
+    Symlink(&'a str),
+}
+
+/// Bir dizin iskeletinin (ör. kurulumda veya konteyner ad alanında önceden oluşturulan
+/// `/etc`, `/var/log`, `/bin -> usr/bin` gibi yollar) tek bir girdisi.
+///
+/// Yollar iskeletin oluşturulduğu taban dizine göredir; baştaki `/` taban dizini gösterir.
+/// Bir girdinin üst dizini ya taban dizinde zaten bulunmalı ya da iskelette daha önce
+/// bir `Directory` girdisi olarak yer almalıdır (bkz. `SadakFs::create_skeleton`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkeletonEntry<'a> {
+    pub path: &'a str,
+    pub kind: SkeletonKind<'a>,
+    /// POSIX izin bitleri (`MODE_MASK`).
+    pub mode: u16,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+
+// --- 2. Metin Biçimi ---
+
+/// İskelet tanımının ayrıştırılamayan satırı (1'den başlar).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkeletonParseError {
+    pub line: usize,
+}
+
+/// Satır başına bir girdiden oluşan kısa iskelet tanımını ayrıştırır:
+///
+/// ```text
+/// # tür  izin  uid:gid  yol         [-> hedef]
+/// d      0755  0:0      /etc
+/// f      0644  0:0      /etc/hostname
+/// l      0777  0:0      /bin        -> usr/bin
+/// ```
+///
+/// Tür `d` (dizin), `f` (dosya) veya `l` (bağlantı) olur; izin sekizlik yazılır. Boş
+/// satırlar ve `#` ile başlayan satırlar atlanır. Yollar burada doğrulanmaz; adlar ve
+/// üst dizinler oluşturma sırasında denetlenir.
+pub fn parse(text: &str) -> Result<Vec<SkeletonEntry<'_>>, SkeletonParseError> {
+    let mut entries = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = SkeletonParseError { line: index + 1 };
+
+        // Bağlantı hedefi boşluk içerebilir; önce `->` ile ayrılır
+        let (head, target) = match line.split_once("->") {
+            Some((head, target)) => (head, Some(target.trim())),
+            None => (line, None),
+        };
+
+        let mut fields = head.split_whitespace();
+        let (kind, mode, owner, path) = match (fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) {
+            (Some(kind), Some(mode), Some(owner), Some(path), None) => (kind, mode, owner, path),
+            _ => return Err(error),
+        };
+
+        let kind = match (kind, target) {
+            ("d", None) => SkeletonKind::Directory,
+            ("f", None) => SkeletonKind::File,
+            ("l", Some(target)) if !target.is_empty() => SkeletonKind::Symlink(target),
+            _ => return Err(error),
+        };
+        let mode = u16::from_str_radix(mode, 8).map_err(|_| error)?;
+        let (uid, gid) = owner.split_once(':').ok_or(error)?;
+        let uid = uid.parse().map_err(|_| error)?;
+        let gid = gid.parse().map_err(|_| error)?;
+
+        entries.push(SkeletonEntry { path, kind, mode, uid, gid });
+    }
+    Ok(entries)
+}
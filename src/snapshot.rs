@@ -0,0 +1,271 @@
+// src/snapshot.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::allocator::AllocationPin;
+use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE, BlockDeviceError};
+use crate::checksum::checksum_data;
+use crate::sahne_syscalls::SyscallError;
+use core::fmt::Debug;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+
+// --- 1. Snapshot Hata Türü ---
+
+/// Salt okunur snapshot aygıtı üzerinde oluşabilecek hatalar.
+#[derive(Debug)]
+pub enum SnapshotError<D: BlockDevice> {
+    /// Alttaki fiziksel aygıtta I/O hatası oluştu.
+    Device(D::Error),
+    /// Snapshot aygıtına yazma girişimi (aygıt salt okunurdur).
+    ReadOnly,
+    /// İstenen blok, snapshot alındığı andaki aygıt boyutunun dışında.
+    OutOfRange,
+    /// Dahili sistem çağrısı hatası.
+    Syscall(SyscallError),
+}
+
+impl<D: BlockDevice + Debug> BlockDeviceError for SnapshotError<D> {}
+
+impl<D: BlockDevice> From<SyscallError> for SnapshotError<D> {
+    fn from(e: SyscallError) -> Self {
+        SnapshotError::Syscall(e)
+    }
+}
+
+
+// --- 2. Snapshot Yapısı ---
+
+/// Dosya sisteminin belirli bir andaki (point-in-time) tutarlı görüntüsü.
+///
+/// CoW sayesinde ağaç blokları yerinde değiştirilmez. Snapshot, her commit'te yerinde
+/// güncellenen Superblock (Blok 0) ve tahsis haritasının o anki kopyalarını bellekte
+/// saklar. Tahsis haritası kopyası tahsis yöneticisinde bir pin'dir (bkz.
+/// `AllocationPin`): snapshot yaşadıkça gördüğü bloklar yeniden kullanılmaz, sonradan
+/// yerinde yazılan bloklar (inode'lar, nocow veri) ise yazılmadan önce pin'e kopyalanır.
+pub struct Snapshot {
+    /// Snapshot'ın alındığı zaman (SYSCALL_GET_SYSTEM_TIME).
+    pub timestamp: u64,
+    /// Snapshot'ın kapattığı değişiklik dönemi; sonraki değişiklikler daha büyük dönemlere
+    /// yazılır (bkz. `SadakFs::changed_blocks_since`).
+    pub epoch: u64,
+    /// Snapshot anındaki metadata B-Ağacı kökü.
+    pub metadata_root_id: BlockId,
+    /// Snapshot anındaki toplam blok sayısı.
+    pub total_blocks: BlockId,
+    /// Tahsis haritasının diskteki başlangıç bloğu.
+    pub bitmap_start_id: BlockId,
+    /// Blok 0'ın (Superblock) snapshot anındaki ham kopyası.
+    superblock_image: Vec<u8>,
+    /// Superblock'un ikinci yuvası (varsa); o blok da `superblock_image` olarak okunur.
+    superblock_copy: Option<BlockId>,
+    /// Tahsis haritası kopyası ve korunan bloklar.
+    pin: Arc<AllocationPin>,
+}
+
+impl Snapshot {
+    /// Önceden okunmuş Superblock kopyasından ve tahsis haritası pin'inden bir snapshot
+    /// oluşturur (bkz. `Allocator::pin`).
+    ///
+    /// # Parametreler
+    /// * `superblock_image`: `BLOCK_SIZE` boyutunda Blok 0 kopyası.
+    pub fn new(
+        timestamp: u64,
+        epoch: u64,
+        metadata_root_id: BlockId,
+        superblock_image: Vec<u8>,
+        pin: Arc<AllocationPin>,
+    ) -> Result<Self, SyscallError> {
+        if superblock_image.len() != BLOCK_SIZE {
+            return Err(SyscallError::EINVAL);
+        }
+
+        Ok(Snapshot {
+            timestamp,
+            epoch,
+            metadata_root_id,
+            total_blocks: pin.total_blocks(),
+            bitmap_start_id: pin.bitmap_start(),
+            superblock_image,
+            superblock_copy: None,
+            pin,
+        })
+    }
+
+    /// Superblock'un `id` bloğundaki ikinci yuvasını da dondurur; aksi halde snapshot
+    /// aygıtı o yuvadaki daha yeni canlı Superblock'u gösterirdi.
+    pub fn with_superblock_copy(mut self, id: BlockId) -> Self {
+        self.superblock_copy = Some(id);
+        self
+    }
+
+    /// Snapshot'ta dondurulmuş bitmap bloklarının sayısı.
+    pub fn bitmap_block_count(&self) -> BlockId {
+        (self.pin.bitmap().len() / BLOCK_SIZE) as BlockId
+    }
+
+    /// Blok, snapshot anındaki tahsis haritasında dolu olarak mı işaretliydi?
+    pub fn is_allocated(&self, id: BlockId) -> bool {
+        self.pin.is_allocated(id)
+    }
+
+    /// Blok bellekte dondurulmuş mu (Superblock yuvaları, tahsis haritası ve snapshot'tan
+    /// sonra yerinde yazılmadan önce korunan bloklar)?
+    pub fn is_frozen(&self, id: BlockId) -> bool {
+        id == 0 || self.superblock_copy == Some(id) || self.in_bitmap(id) || self.pin.is_preserved(id)
+    }
+
+    /// Snapshot'ın bellekte tuttuğu, sonradan yerinde yazılmadan önce korunan blok sayısı.
+    pub fn preserved_blocks(&self) -> usize {
+        self.pin.preserved_count()
+    }
+
+    /// Snapshot'ı çoğaltma akışlarında tanımlayan değer: dondurulmuş Superblock'un
+    /// checksum'u (bkz. `replication::StreamHeader`).
+    pub fn stream_id(&self) -> u32 {
+        checksum_data(&self.superblock_image)
+    }
+
+    /// Blok dondurulmuş tahsis haritasının bir parçası mı?
+    fn in_bitmap(&self, id: BlockId) -> bool {
+        id >= self.bitmap_start_id && id < self.bitmap_start_id + self.bitmap_block_count()
+    }
+
+    /// Verilen blok snapshot içinde dondurulmuşsa, o kopyanın ham verisini bir blok
+    /// boyutundaki `out`'a yazar.
+    ///
+    /// # Döndürür
+    /// Blok dondurulmuşsa `true`; değilse `out` değişmez ve blok aygıttan okunmalıdır.
+    fn read_frozen(&self, id: BlockId, out: &mut [u8]) -> bool {
+        if id == 0 || self.superblock_copy == Some(id) {
+            out.copy_from_slice(&self.superblock_image);
+            return true;
+        }
+
+        if self.in_bitmap(id) {
+            let start = (id - self.bitmap_start_id) as usize * BLOCK_SIZE;
+            out.copy_from_slice(&self.pin.bitmap()[start..start + BLOCK_SIZE]);
+            return true;
+        }
+
+        self.pin.read_preserved(id, out)
+    }
+}
+
+
+// --- 3. Salt Okunur Snapshot Aygıtı ---
+
+/// Bir snapshot'ı sanal, salt okunur bir BlockDevice olarak dışa aktarır.
+/// Yedekleme yazılımları bu aygıtı blok blok okuyarak, canlı dosya sistemi
+/// değişmeye devam ederken tutarlı bir disk imajı alabilir.
+pub struct SnapshotDevice<D: BlockDevice> {
+    /// Canlı dosya sisteminin altındaki fiziksel aygıt.
+    device: Arc<D>,
+    /// Dışa aktarılan snapshot.
+    snapshot: Arc<Snapshot>,
+}
+
+impl<D: BlockDevice> SnapshotDevice<D> {
+    /// Verilen aygıt ve snapshot için yeni bir salt okunur aygıt oluşturur.
+    pub fn new(device: Arc<D>, snapshot: Arc<Snapshot>) -> Self {
+        SnapshotDevice { device, snapshot }
+    }
+
+    /// Dışa aktarılan snapshot'a erişim.
+    pub fn snapshot(&self) -> &Snapshot {
+        &self.snapshot
+    }
+}
+
+impl<D: BlockDevice + Debug> BlockDevice for SnapshotDevice<D> {
+    type Error = SnapshotError<D>;
+
+    /// Dondurulmuş bloklar bellekten, diğerleri (CoW ile korunan) alttaki aygıttan okunur.
+    fn read_block(&self, id: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if buffer.len() != BLOCK_SIZE {
+            return Err(SnapshotError::Syscall(SyscallError::EINVAL));
+        }
+        if id >= self.snapshot.total_blocks {
+            return Err(SnapshotError::OutOfRange);
+        }
+
+        if self.snapshot.read_frozen(id, buffer) {
+            return Ok(());
+        }
+        self.device.read_block(id, buffer).map_err(SnapshotError::Device)
+    }
+
+    /// Snapshot aygıtı salt okunurdur; her yazma girişimi reddedilir.
+    fn write_block(&self, id: BlockId, data: &[u8]) -> Result<(), Self::Error> {
+        Err(SnapshotError::ReadOnly)
+    }
+
+    /// Snapshot anındaki toplam blok sayısını döndürür.
+    fn total_blocks(&self) -> BlockId {
+        self.snapshot.total_blocks
+    }
+}
+
+
+// --- 4. Saklama Politikası ---
+
+// Seyreltme dilimlerinin uzunlukları (saniye birimli saat varsayılarak).
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+/// Periyodik alınan snapshot'lardan hangilerinin saklanacağı (bkz. `SadakFs::prune_snapshots`).
+///
+/// Bir snapshot, kurallardan herhangi biri onu seçerse saklanır: en yeni `keep_last`
+/// snapshot ve snapshot bulunan son `hourly` saatin, `daily` günün, `weekly` haftanın
+/// her birinden en yeni snapshot. Tüm alanları 0 olan politika hiçbir şeyi silmez.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RetentionPolicy {
+    /// Her durumda saklanan en yeni snapshot sayısı.
+    pub keep_last: usize,
+    /// Saatlik seyreltmede saklanan saat sayısı.
+    pub hourly: usize,
+    /// Günlük seyreltmede saklanan gün sayısı.
+    pub daily: usize,
+    /// Haftalık seyreltmede saklanan hafta sayısı.
+    pub weekly: usize,
+}
+
+impl RetentionPolicy {
+    /// Politika sınırsız mı (tüm alanlar 0)?
+    pub fn is_unlimited(&self) -> bool {
+        self.keep_last == 0 && self.hourly == 0 && self.daily == 0 && self.weekly == 0
+    }
+
+    /// Zaman damgaları verilen snapshot'lardan hangilerinin saklanacağını, aynı sırayla döndürür.
+    pub fn retained(&self, timestamps: &[u64]) -> Vec<bool> {
+        let mut keep = alloc::vec![self.is_unlimited(); timestamps.len()];
+        if self.is_unlimited() {
+            return keep;
+        }
+
+        // En yeniden eskiye; her dilimde ilk görülen o dilimin en yenisidir
+        let mut order: Vec<usize> = (0..timestamps.len()).collect();
+        order.sort_by(|&a, &b| timestamps[b].cmp(&timestamps[a]));
+        for &i in order.iter().take(self.keep_last) {
+            keep[i] = true;
+        }
+        for (period, count) in [(HOUR, self.hourly), (DAY, self.daily), (WEEK, self.weekly)] {
+            let mut last_bucket = None;
+            let mut buckets = 0;
+            for &i in &order {
+                if buckets == count {
+                    break;
+                }
+                let bucket = timestamps[i] / period;
+                if last_bucket != Some(bucket) {
+                    keep[i] = true;
+                    last_bucket = Some(bucket);
+                    buckets += 1;
+                }
+            }
+        }
+        keep
+    }
+}
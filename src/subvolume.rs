@@ -0,0 +1,129 @@
+// src/subvolume.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::BlockId;
+use crate::btree::{BTreeKey, BTREE_VALUE_MAX, ITEM_TYPE_SUBVOLUME, ITEM_TYPE_SUBVOLUME_ATTRS};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Alt birim kayıtlarının metadata ağacındaki nesne kimliği (paylaşılan blok kayıtlarının
+// `u64::MAX - 1` kimliğinin hemen altı; inode ID'leriyle çakışmaz).
+const SUBVOLUME_OBJECT: u64 = u64::MAX - 2;
+
+// Kayıt değerinin sabit kısmı: root_inode (8) + creation_time (8)
+const SUBVOLUME_HEADER_SIZE: usize = 16;
+
+// Bir alt birim adının bayt cinsinden en büyük uzunluğu (tek B-Ağacı öğesine sığmalı).
+pub const SUBVOLUME_NAME_MAX: usize = BTREE_VALUE_MAX - SUBVOLUME_HEADER_SIZE;
+
+// Ana ad alanının (Superblock'taki kök dizin) kimliği; alt birim tablosunda yer almaz.
+pub const MAIN_SUBVOLUME_ID: u64 = 0;
+
+/// Alt birimdeki dosyaların mevcut veri blokları CoW yapılmadan yerinde güncellenir
+/// (bkz. `SadakFs::set_subvolume_nocow`).
+pub const SUBVOLUME_ATTR_NOCOW: u64 = 1 << 0;
+
+/// Alt birimdeki dosyaların geri yazılan verisi, dosyanın kendi ayarı yoksa da sıkıştırılır;
+/// algoritma `SUBVOLUME_ATTR_COMPRESSION_SHIFT` bitinden başlayan baytta tutulur
+/// (`Compression::to_raw`, bkz. `SadakFs::set_subvolume_compression`).
+pub const SUBVOLUME_ATTR_COMPRESS: u64 = 1 << 1;
+pub const SUBVOLUME_ATTR_COMPRESSION_SHIFT: u32 = 8;
+
+
+// --- 2. Alt Birim Kaydı ---
+
+/// Aynı SADAK biriminde bağımsız olarak monte edilip değiştirilebilen bir ad alanı.
+///
+/// Her alt birimin kendi kök dizini vardır; kökün üst dizini kendisidir, dolayısıyla
+/// `..` ile ana ad alanına veya başka bir alt birime çıkılamaz. Kayıtlar metadata
+/// ağacında `(SUBVOLUME_OBJECT, ITEM_TYPE_SUBVOLUME, alt birim kimliği)` anahtarıyla
+/// tutulur; yeni alt birim en büyük kimliğin bir fazlasını alır.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subvolume {
+    /// Alt birimin kimliği (1'den başlar; 0 ana ad alanıdır).
+    pub id: u64,
+    /// Alt birimin adı (UTF-8, en fazla `SUBVOLUME_NAME_MAX` bayt).
+    pub name: String,
+    /// Kök dizinin inode blok ID'si.
+    pub root_inode: BlockId,
+    /// Oluşturulma zamanı (SYSCALL_GET_SYSTEM_TIME).
+    pub creation_time: u64,
+}
+
+impl Subvolume {
+    /// `id` kimlikli alt birimin kaydının anahtarı.
+    pub fn key(id: u64) -> BTreeKey {
+        BTreeKey {
+            object_id: SUBVOLUME_OBJECT,
+            item_type: ITEM_TYPE_SUBVOLUME,
+            offset: id,
+        }
+    }
+
+    /// Anahtar bir alt birim kaydına mı ait?
+    pub fn is_subvolume_key(key: &BTreeKey) -> bool {
+        key.object_id == SUBVOLUME_OBJECT && key.item_type == ITEM_TYPE_SUBVOLUME
+    }
+
+    /// Kaydı B-Ağacı değer formatına yazar: root_inode (LE), creation_time (LE), ad baytları.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(SUBVOLUME_HEADER_SIZE + self.name.len());
+        out.extend_from_slice(&self.root_inode.to_le_bytes());
+        out.extend_from_slice(&self.creation_time.to_le_bytes());
+        out.extend_from_slice(self.name.as_bytes());
+        out
+    }
+
+    /// Anahtar ve değerden kaydı çözer; kayıt değilse veya bozuksa `None`.
+    pub fn from_item(key: &BTreeKey, value: &[u8]) -> Option<Self> {
+        if !Self::is_subvolume_key(key) || value.len() < SUBVOLUME_HEADER_SIZE {
+            return None;
+        }
+
+        let mut root_inode = [0u8; 8];
+        let mut creation_time = [0u8; 8];
+        root_inode.copy_from_slice(&value[0..8]);
+        creation_time.copy_from_slice(&value[8..16]);
+        let name = core::str::from_utf8(&value[SUBVOLUME_HEADER_SIZE..]).ok()?;
+
+        Some(Subvolume {
+            id: key.offset,
+            name: String::from(name),
+            root_inode: BlockId::from_le_bytes(root_inode),
+            creation_time: u64::from_le_bytes(creation_time),
+        })
+    }
+
+    /// `id` kimlikli alt birimin öznitelik kaydının anahtarı. Kayıt alt birim kaydından
+    /// ayrı tutulur; öznitelik bilmeyen sürümler kaydı yok sayar.
+    pub fn attrs_key(id: u64) -> BTreeKey {
+        BTreeKey {
+            object_id: SUBVOLUME_OBJECT,
+            item_type: ITEM_TYPE_SUBVOLUME_ATTRS,
+            offset: id,
+        }
+    }
+
+    /// Öznitelik kaydının değeri: bayraklar (LE).
+    pub fn encode_attrs(attrs: u64) -> [u8; 8] {
+        attrs.to_le_bytes()
+    }
+
+    /// Öznitelik kaydının değerini çözer; bozuksa `None`.
+    pub fn decode_attrs(value: &[u8]) -> Option<u64> {
+        let bytes: [u8; 8] = value.get(..8)?.try_into().ok()?;
+        Some(u64::from_le_bytes(bytes))
+    }
+
+    /// Bir adın alt birim adı olarak kullanılabilir olup olmadığını denetler:
+    /// boş olmamalı, `/` veya NUL içermemeli ve sığmalıdır.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name.len() <= SUBVOLUME_NAME_MAX
+            && !name.bytes().any(|b| b == b'/' || b == 0)
+    }
+}
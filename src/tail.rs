@@ -0,0 +1,172 @@
+// src/tail.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockId, BLOCK_SIZE};
+use crate::btree::{BTreeKey, ITEM_TYPE_TAIL_BLOCK, ITEM_TYPE_TAIL_FRAGMENT};
+use crate::extent::DATA_TREE_OBJECT_ID;
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Kuyruk bloğu kayıtlarının metadata ağacındaki nesne kimliği (kota kayıtlarının
+// `u64::MAX - 3` kimliğinin hemen altı; inode ID'leriyle çakışmaz).
+const TAIL_BLOCK_OBJECT: u64 = u64::MAX - 4;
+
+// Kuyruk bloklarının bölündüğü dilimin boyutu; parçalar dilim sınırında başlar.
+pub const TAIL_SLOT_SIZE: usize = 64;
+
+// Bir kuyruk bloğundaki dilim sayısı (doluluk haritası tek bir u64'e sığar).
+pub const TAIL_SLOTS: usize = BLOCK_SIZE / TAIL_SLOT_SIZE;
+
+// Paketlenebilecek en büyük kuyruk; daha uzun kuyruklar bloğun çoğunu zaten kullanır.
+pub const TAIL_MAX: usize = BLOCK_SIZE / 2;
+
+// Kuyruk parçası kaydının disk üzerindeki boyutu: tail_block (8) + offset (4) + len (4)
+const TAIL_FRAGMENT_SIZE: usize = 16;
+
+const _: () = assert!(TAIL_SLOTS == 64);
+
+
+// --- 2. Kuyruk Parçası ---
+
+/// Bir dosyanın son (kısmi) bloğunun paylaşılan bir kuyruk bloğunda tutulan içeriği.
+///
+/// Dosyanın veri ağacında `(DATA_TREE_OBJECT_ID, ITEM_TYPE_TAIL_FRAGMENT, dosya bloğu)`
+/// anahtarıyla tutulur ve o blok için bir extent'in yerini alır. Parçanın ötesi, dosya
+/// sonuna kadar sıfır okunur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TailFragment {
+    /// Parçanın ait olduğu dosya bloğu (anahtarın `offset` alanı).
+    pub file_block: u64,
+    /// Parçayı taşıyan kuyruk bloğunun diskteki ID'si.
+    pub tail_block: BlockId,
+    /// Parçanın kuyruk bloğu içindeki bayt konumu (`TAIL_SLOT_SIZE`'ın katı).
+    pub offset: u32,
+    /// Parçanın bayt cinsinden uzunluğu (en fazla `TAIL_MAX`).
+    pub len: u32,
+}
+
+impl TailFragment {
+    /// `file_block` bloğunun parça kaydının veri ağacındaki anahtarı.
+    pub fn key(file_block: u64) -> BTreeKey {
+        BTreeKey {
+            object_id: DATA_TREE_OBJECT_ID,
+            item_type: ITEM_TYPE_TAIL_FRAGMENT,
+            offset: file_block,
+        }
+    }
+
+    /// Kaydın değer kısmını disk formatına (little-endian) yazar.
+    pub fn encode(&self) -> [u8; TAIL_FRAGMENT_SIZE] {
+        let mut out = [0u8; TAIL_FRAGMENT_SIZE];
+        out[0..8].copy_from_slice(&self.tail_block.to_le_bytes());
+        out[8..12].copy_from_slice(&self.offset.to_le_bytes());
+        out[12..16].copy_from_slice(&self.len.to_le_bytes());
+        out
+    }
+
+    /// Bir veri ağacı öğesini parça olarak çözer; öğe parça değilse veya bozuksa `None`.
+    pub fn from_item(key: &BTreeKey, value: &[u8]) -> Option<Self> {
+        if key.object_id != DATA_TREE_OBJECT_ID
+            || key.item_type != ITEM_TYPE_TAIL_FRAGMENT
+            || value.len() < TAIL_FRAGMENT_SIZE
+        {
+            return None;
+        }
+
+        let mut tail_block = [0u8; 8];
+        let mut offset = [0u8; 4];
+        let mut len = [0u8; 4];
+        tail_block.copy_from_slice(&value[0..8]);
+        offset.copy_from_slice(&value[8..12]);
+        len.copy_from_slice(&value[12..16]);
+
+        let fragment = TailFragment {
+            file_block: key.offset,
+            tail_block: BlockId::from_le_bytes(tail_block),
+            offset: u32::from_le_bytes(offset),
+            len: u32::from_le_bytes(len),
+        };
+        if fragment.offset as usize + fragment.len as usize > BLOCK_SIZE {
+            return None;
+        }
+        Some(fragment)
+    }
+
+    /// Parçanın kuyruk bloğundaki bayt aralığı.
+    pub fn range(&self) -> core::ops::Range<usize> {
+        self.offset as usize..self.offset as usize + self.len as usize
+    }
+
+    /// Parçanın kapladığı dilimlerin doluluk haritasındaki maskesi.
+    pub fn slot_mask(&self) -> u64 {
+        slot_mask(self.offset as usize / TAIL_SLOT_SIZE, slots_for(self.len as usize))
+    }
+}
+
+
+// --- 3. Kuyruk Bloğu Kaydı ---
+
+/// Kuyruk parçalarını taşıyan bir bloğun doluluk kaydı.
+///
+/// Metadata ağacında `(TAIL_BLOCK_OBJECT, ITEM_TYPE_TAIL_BLOCK, fiziksel blok)` anahtarıyla
+/// tutulur; değer, dolu dilimlerin bit haritasıdır (LE, bit `i` = `i`. dilim). Son parça
+/// bırakıldığında kayıt silinir ve blok serbest bırakılır.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TailBlock {
+    pub physical: BlockId,
+    /// Dolu dilimlerin bit haritası.
+    pub used: u64,
+}
+
+impl TailBlock {
+    /// `physical` bloğunun kaydının anahtarı.
+    pub fn key(physical: BlockId) -> BTreeKey {
+        BTreeKey {
+            object_id: TAIL_BLOCK_OBJECT,
+            item_type: ITEM_TYPE_TAIL_BLOCK,
+            offset: physical,
+        }
+    }
+
+    /// Kaydın değer kısmı.
+    pub fn encode(&self) -> [u8; 8] {
+        self.used.to_le_bytes()
+    }
+
+    /// Anahtar ve değerden kaydı çözer; kayıt değilse veya bozuksa `None`.
+    pub fn from_item(key: &BTreeKey, value: &[u8]) -> Option<Self> {
+        if key.object_id != TAIL_BLOCK_OBJECT || key.item_type != ITEM_TYPE_TAIL_BLOCK || value.len() < 8 {
+            return None;
+        }
+        let mut used = [0u8; 8];
+        used.copy_from_slice(&value[..8]);
+        Some(TailBlock { physical: key.offset, used: u64::from_le_bytes(used) })
+    }
+
+    /// `len` baytlık bir parçanın sığacağı ilk boş dilim dizisinin bayt konumu (first-fit).
+    pub fn find_space(&self, len: usize) -> Option<u32> {
+        let slots = slots_for(len);
+        (0..=TAIL_SLOTS - slots)
+            .find(|&first| self.used & slot_mask(first, slots) == 0)
+            .map(|first| (first * TAIL_SLOT_SIZE) as u32)
+    }
+}
+
+
+// --- 4. Yardımcı Fonksiyonlar ---
+
+/// `len` baytın kapladığı dilim sayısı (en az 1).
+fn slots_for(len: usize) -> usize {
+    ((len + TAIL_SLOT_SIZE - 1) / TAIL_SLOT_SIZE).max(1)
+}
+
+/// `first` diliminden başlayan `count` dilimin maskesi.
+fn slot_mask(first: usize, count: usize) -> u64 {
+    if count >= TAIL_SLOTS {
+        u64::MAX
+    } else {
+        ((1u64 << count) - 1) << first
+    }
+}
@@ -0,0 +1,416 @@
+// src/trace.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockDevice, BlockId, BLOCK_SIZE};
+use crate::checksum;
+use crate::sahne_syscalls::{self, ResourceHandle, SyscallError, SYSCALL_RESOURCE_RELEASE, SYSCALL_RESOURCE_WRITE, raw_syscall};
+use core::sync::atomic::{AtomicU64, Ordering};
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Kayıt başlığının boyutu: seq (8) + block (8) + duration (8) + count (4) + hash (4)
+// + op (1) + status (1) + ayrılmış (6)
+pub const TRACE_RECORD_SIZE: usize = 40;
+
+// Kaydı izleyen veri var mı (yalnızca yazmalarda, veri yakalama açıksa).
+const TRACE_FLAG_DATA: u8 = 1 << 1;
+// İşlem hatayla sonuçlandı.
+const TRACE_FLAG_FAILED: u8 = 1 << 0;
+
+/// İzlenen blok işlemi türü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceOp {
+    Read = 1,
+    Write = 2,
+    /// Ardışık blokları tek istekte okuma (`read_blocks`).
+    ReadRange = 3,
+    Flush = 4,
+}
+
+impl TraceOp {
+    fn from_raw(raw: u8) -> Option<Self> {
+        match raw {
+            1 => Some(TraceOp::Read),
+            2 => Some(TraceOp::Write),
+            3 => Some(TraceOp::ReadRange),
+            4 => Some(TraceOp::Flush),
+            _ => None,
+        }
+    }
+}
+
+/// Tek bir blok işleminin izi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceRecord {
+    /// Aygıt üzerindeki sıra numarası (0'dan başlar).
+    pub seq: u64,
+    pub op: TraceOp,
+    /// İlk blok (`Flush` için 0).
+    pub block: BlockId,
+    /// Blok sayısı (`Flush` için 0).
+    pub count: u32,
+    /// Okunan veya yazılan verinin CRC32C özeti (başarısız okumalarda 0).
+    pub hash: u32,
+    /// İşlemin süresi (Sahne64 sistem zamanı birimiyle).
+    pub duration: u64,
+    /// İşlem hata döndürdü mü?
+    pub failed: bool,
+}
+
+impl TraceRecord {
+    /// Kaydı `TRACE_RECORD_SIZE` baytlık başlık formatına (LE) yazar.
+    fn encode(&self, has_data: bool, out: &mut Vec<u8>) {
+        let mut flags = 0;
+        if self.failed {
+            flags |= TRACE_FLAG_FAILED;
+        }
+        if has_data {
+            flags |= TRACE_FLAG_DATA;
+        }
+
+        out.extend_from_slice(&self.seq.to_le_bytes());
+        out.extend_from_slice(&self.block.to_le_bytes());
+        out.extend_from_slice(&self.duration.to_le_bytes());
+        out.extend_from_slice(&self.count.to_le_bytes());
+        out.extend_from_slice(&self.hash.to_le_bytes());
+        out.push(self.op as u8);
+        out.push(flags);
+        out.extend_from_slice(&[0u8; 6]);
+    }
+
+    /// Başlığı çözer; bozuksa `None`. İkinci değer, ardından veri gelip gelmediğidir.
+    fn decode(raw: &[u8]) -> Option<(Self, bool)> {
+        if raw.len() < TRACE_RECORD_SIZE {
+            return None;
+        }
+
+        let u64_at = |at: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&raw[at..at + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        let u32_at = |at: usize| {
+            let mut bytes = [0u8; 4];
+            bytes.copy_from_slice(&raw[at..at + 4]);
+            u32::from_le_bytes(bytes)
+        };
+
+        let flags = raw[33];
+        let record = TraceRecord {
+            seq: u64_at(0),
+            block: u64_at(8),
+            duration: u64_at(16),
+            count: u32_at(24),
+            hash: u32_at(28),
+            op: TraceOp::from_raw(raw[32])?,
+            failed: flags & TRACE_FLAG_FAILED != 0,
+        };
+        Some((record, flags & TRACE_FLAG_DATA != 0))
+    }
+}
+
+/// İz kayıtlarının gönderildiği yan kanal.
+pub trait TraceSink {
+    /// Bir kaydı (ve yazmalarda, yakalanıyorsa, yazılan veriyi) kanala ekler.
+    fn record(&self, record: &TraceRecord, data: Option<&[u8]>) -> Result<(), SyscallError>;
+}
+
+
+// --- 2. Kaydeden Aygıt ---
+
+/// Her blok işlemini alttaki aygıta iletirken bir `TraceSink`'e kaydeden sarmalayıcı.
+///
+/// Kullanıcının bildirdiği bozulma senaryolarını birebir yeniden üretmek için kullanılır:
+/// veri yakalama açıksa yazılan bloklar da kaydedilir ve iz `ReplayDevice` ile yeniden
+/// oynatılabilir. Kanal hataları I/O sonucunu etkilemez; yalnızca `dropped` sayılır.
+pub struct RecordingDevice<D: BlockDevice, S: TraceSink> {
+    inner: D,
+    sink: S,
+    /// Yazılan verinin de kaydedilip kaydedilmeyeceği (oynatma için gerekli).
+    capture_data: bool,
+    /// Sonraki kaydın sıra numarası.
+    next_seq: AtomicU64,
+    /// Kanala yazılamayan kayıt sayısı.
+    dropped: AtomicU64,
+}
+
+impl<D: BlockDevice, S: TraceSink> RecordingDevice<D, S> {
+    /// `inner` aygıtını sarar. `capture_data` kapalıysa yalnızca özetler kaydedilir.
+    pub fn new(inner: D, sink: S, capture_data: bool) -> Self {
+        RecordingDevice {
+            inner,
+            sink,
+            capture_data,
+            next_seq: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Kanala yazılamayan kayıt sayısı; sıfır değilse iz eksiktir.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Sarılan aygıtı geri verir.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+
+    /// `started` zamanında başlayan işlemin sonucunu kaydeder. `data` okunan veya yazılan
+    /// veridir (başarısız işlemlerde özetlenmez); yazmalarda yakalama açıksa kaydedilir.
+    fn log(&self, op: TraceOp, block: BlockId, count: u32, started: u64, failed: bool, data: Option<&[u8]>) {
+        let duration = system_time().saturating_sub(started);
+        let data = if failed { None } else { data };
+        let record = TraceRecord {
+            seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+            op,
+            block,
+            count,
+            hash: data.map_or(0, checksum::checksum_data),
+            duration,
+            failed,
+        };
+
+        let payload = if op == TraceOp::Write && self.capture_data { data } else { None };
+        if self.sink.record(&record, payload).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<D: BlockDevice, S: TraceSink> BlockDevice for RecordingDevice<D, S> {
+    type Error = D::Error;
+
+    fn read_block(&self, id: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let started = system_time();
+        let result = self.inner.read_block(id, buffer);
+        self.log(TraceOp::Read, id, 1, started, result.is_err(), Some(buffer));
+        result
+    }
+
+    fn write_block(&self, id: BlockId, data: &[u8]) -> Result<(), Self::Error> {
+        let started = system_time();
+        let result = self.inner.write_block(id, data);
+        self.log(TraceOp::Write, id, 1, started, result.is_err(), Some(data));
+        result
+    }
+
+    fn total_blocks(&self) -> BlockId {
+        self.inner.total_blocks()
+    }
+
+    fn read_blocks(&self, start: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let started = system_time();
+        let result = self.inner.read_blocks(start, buffer);
+        let count = (buffer.len() / BLOCK_SIZE) as u32;
+        self.log(TraceOp::ReadRange, start, count, started, result.is_err(), Some(buffer));
+        result
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        let started = system_time();
+        let result = self.inner.flush();
+        self.log(TraceOp::Flush, 0, 0, started, result.is_err(), None);
+        result
+    }
+
+    fn copies(&self) -> usize {
+        self.inner.copies()
+    }
+
+    fn read_copy(&self, id: BlockId, copy: usize, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let started = system_time();
+        let result = self.inner.read_copy(id, copy, buffer);
+        self.log(TraceOp::Read, id, 1, started, result.is_err(), Some(buffer));
+        result
+    }
+}
+
+/// Kayıtları bir Sahne64 kaynağına (ör. bir hata ayıklama kanalına veya dosyaya) yazan kanal.
+/// Her kayıt, başlığı ve verisiyle birlikte tek bir yazma çağrısıyla gönderilir.
+pub struct ResourceTraceSink {
+    handle: ResourceHandle,
+}
+
+impl ResourceTraceSink {
+    /// `path` kaynağını edinir.
+    pub fn open(path: &str) -> Result<Self, SyscallError> {
+        let handle = sahne_syscalls::resource_acquire(path.as_ptr(), path.len())?;
+        Ok(ResourceTraceSink { handle })
+    }
+}
+
+impl TraceSink for ResourceTraceSink {
+    fn record(&self, record: &TraceRecord, data: Option<&[u8]>) -> Result<(), SyscallError> {
+        let mut out = Vec::with_capacity(TRACE_RECORD_SIZE + data.map_or(0, |d| d.len()));
+        record.encode(data.is_some(), &mut out);
+        if let Some(data) = data {
+            out.extend_from_slice(data);
+        }
+
+        let written = unsafe {
+            raw_syscall(SYSCALL_RESOURCE_WRITE, self.handle, out.as_ptr() as u64, out.len() as u64, 0, 0, 0)
+        };
+        if written < 0 {
+            return Err(SyscallError::from_raw(written));
+        }
+        if written as usize != out.len() {
+            return Err(SyscallError::EIO); // Yarım kayıt
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ResourceTraceSink {
+    fn drop(&mut self) {
+        unsafe { raw_syscall(SYSCALL_RESOURCE_RELEASE, self.handle, 0, 0, 0, 0, 0) };
+    }
+}
+
+
+// --- 3. Yeniden Oynatma ---
+
+/// Yakalanmış bir izdeki tek işlem ve (varsa) yazılan veri.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub record: TraceRecord,
+    pub data: Option<Vec<u8>>,
+}
+
+/// İzi ayrıştırırken veya oynatırken oluşan hatalar.
+#[derive(Debug)]
+pub enum ReplayError<D: BlockDevice> {
+    /// İz, verilen bayt konumunda bozuk veya yarım.
+    Malformed(usize),
+    /// Yazma kaydının verisi yakalanmamış; iz `capture_data` ile alınmalı.
+    MissingData(u64),
+    /// Oynatılan okuma, kayıttakinden farklı veri döndürdü (sıra numarası, blok).
+    Diverged { seq: u64, block: BlockId },
+    Device(D::Error),
+}
+
+/// `ResourceTraceSink`'in ürettiği bayt dizisini kayıtlara ayırır.
+pub fn parse_trace<D: BlockDevice>(raw: &[u8]) -> Result<Vec<TraceEntry>, ReplayError<D>> {
+    let mut entries = Vec::new();
+    let mut at = 0;
+    while at < raw.len() {
+        let (record, has_data) = TraceRecord::decode(&raw[at..]).ok_or(ReplayError::Malformed(at))?;
+        let header_end = at + TRACE_RECORD_SIZE;
+
+        let data = if has_data {
+            let data_end = header_end + record.count as usize * BLOCK_SIZE;
+            Some(raw.get(header_end..data_end).ok_or(ReplayError::Malformed(at))?.to_vec())
+        } else {
+            None
+        };
+        at = header_end + data.as_ref().map_or(0, |d| d.len());
+        entries.push(TraceEntry { record, data });
+    }
+    Ok(entries)
+}
+
+/// Yakalanmış bir izi, kayıt anındaki aygıt imajının kopyası üzerinde sırayla yeniden
+/// çalıştıran aygıt.
+///
+/// Yazmalar kayıttaki veriyle tekrarlanır; okumalar yeniden yapılıp özetleri kayıtla
+/// karşılaştırılır, böylece içeriğin ilk ayrıştığı işlem bulunur. Herhangi bir noktada
+/// durup aygıtın o anki hali (`BlockDevice` olarak) monte edilip incelenebilir.
+pub struct ReplayDevice<D: BlockDevice> {
+    inner: D,
+    entries: Vec<TraceEntry>,
+    /// Sıradaki oynatılacak kaydın indeksi.
+    position: usize,
+}
+
+impl<D: BlockDevice> ReplayDevice<D> {
+    /// `inner`, kaydın başladığı andaki imajı taşımalıdır.
+    pub fn new(inner: D, entries: Vec<TraceEntry>) -> Self {
+        ReplayDevice { inner, entries, position: 0 }
+    }
+
+    /// Sıradaki kaydı oynatır; iz bittiyse `None`. Kayıtta başarısız olan işlemler atlanır.
+    pub fn step(&mut self) -> Result<Option<&TraceRecord>, ReplayError<D>> {
+        let entry = match self.entries.get(self.position) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        self.position += 1;
+        let record = &entry.record;
+        if record.failed {
+            return Ok(Some(record));
+        }
+
+        match record.op {
+            TraceOp::Write => {
+                let data = entry.data.as_ref().ok_or(ReplayError::MissingData(record.seq))?;
+                self.inner.write_block(record.block, data).map_err(ReplayError::Device)?;
+            }
+            TraceOp::Read | TraceOp::ReadRange => {
+                let mut buffer = alloc::vec![0u8; record.count as usize * BLOCK_SIZE];
+                self.inner.read_blocks(record.block, &mut buffer).map_err(ReplayError::Device)?;
+                if checksum::checksum_data(&buffer) != record.hash {
+                    return Err(ReplayError::Diverged { seq: record.seq, block: record.block });
+                }
+            }
+            TraceOp::Flush => self.inner.flush().map_err(ReplayError::Device)?,
+        }
+        Ok(Some(record))
+    }
+
+    /// Sıra numarası `seq`'ten küçük tüm kayıtları oynatır (`seq` kaydının hemen öncesinde durur).
+    pub fn run_until(&mut self, seq: u64) -> Result<(), ReplayError<D>> {
+        while self.entries.get(self.position).is_some_and(|entry| entry.record.seq < seq) {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// İzin geri kalanını oynatır.
+    pub fn run(&mut self) -> Result<(), ReplayError<D>> {
+        while self.step()?.is_some() {}
+        Ok(())
+    }
+
+    /// Oynatılan kayıt sayısı.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Oynatılan aygıtı geri verir.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for ReplayDevice<D> {
+    type Error = D::Error;
+
+    fn read_block(&self, id: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.inner.read_block(id, buffer)
+    }
+
+    fn write_block(&self, id: BlockId, data: &[u8]) -> Result<(), Self::Error> {
+        self.inner.write_block(id, data)
+    }
+
+    fn total_blocks(&self) -> BlockId {
+        self.inner.total_blocks()
+    }
+
+    fn read_blocks(&self, start: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.inner.read_blocks(start, buffer)
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// Sahne64 sistem zamanı; süre ölçümü yalnızca bilgi amaçlı olduğundan hata 0 sayılır.
+fn system_time() -> u64 {
+    let result = unsafe { raw_syscall(sahne_syscalls::SYSCALL_GET_SYSTEM_TIME, 0, 0, 0, 0, 0, 0) };
+    if result < 0 { 0 } else { result as u64 }
+}
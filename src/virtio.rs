@@ -0,0 +1,480 @@
+// src/virtio.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockDevice, BlockId, BufferRequirements, BLOCK_SIZE};
+use crate::platform::SysLock;
+use crate::sahne_syscalls::{
+    self, ResourceHandle, SyscallError,
+    SYSCALL_DMA_ALLOCATE, SYSCALL_DMA_RELEASE, SYSCALL_NOTIFICATION_WAIT, SYSCALL_RESOURCE_MAP,
+    SYSCALL_RESOURCE_RELEASE, raw_syscall
+};
+use core::cell::UnsafeCell;
+use core::ptr;
+use core::sync::atomic::{fence, Ordering};
+
+
+// --- 1. Sabitler (virtio 1.x, MMIO taşıyıcısı) ---
+
+// MMIO yazmaç ofsetleri
+const REG_MAGIC: usize = 0x000;
+const REG_VERSION: usize = 0x004;
+const REG_DEVICE_ID: usize = 0x008;
+const REG_DEVICE_FEATURES: usize = 0x010;
+const REG_DEVICE_FEATURES_SEL: usize = 0x014;
+const REG_DRIVER_FEATURES: usize = 0x020;
+const REG_DRIVER_FEATURES_SEL: usize = 0x024;
+const REG_QUEUE_SEL: usize = 0x030;
+const REG_QUEUE_NUM_MAX: usize = 0x034;
+const REG_QUEUE_NUM: usize = 0x038;
+const REG_QUEUE_READY: usize = 0x044;
+const REG_QUEUE_NOTIFY: usize = 0x050;
+const REG_INTERRUPT_STATUS: usize = 0x060;
+const REG_INTERRUPT_ACK: usize = 0x064;
+const REG_STATUS: usize = 0x070;
+const REG_QUEUE_DESC_LOW: usize = 0x080;
+const REG_QUEUE_DESC_HIGH: usize = 0x084;
+const REG_QUEUE_DRIVER_LOW: usize = 0x090;
+const REG_QUEUE_DRIVER_HIGH: usize = 0x094;
+const REG_QUEUE_DEVICE_LOW: usize = 0x0a0;
+const REG_QUEUE_DEVICE_HIGH: usize = 0x0a4;
+// Aygıta özel yapılandırma alanı; virtio-blk'de ilk alan 512 baytlık sektör sayısıdır
+const REG_CONFIG_CAPACITY: usize = 0x100;
+
+// Eşlenen MMIO penceresinin boyutu
+const MMIO_WINDOW_SIZE: usize = 0x1000;
+
+const VIRTIO_MAGIC: u32 = 0x7472_6976; // "virt"
+const VIRTIO_MMIO_VERSION: u32 = 2;
+const VIRTIO_DEVICE_ID_BLOCK: u32 = 2;
+
+// Aygıt durum bitleri
+const STATUS_ACKNOWLEDGE: u32 = 1;
+const STATUS_DRIVER: u32 = 2;
+const STATUS_DRIVER_OK: u32 = 4;
+const STATUS_FEATURES_OK: u32 = 8;
+const STATUS_FAILED: u32 = 128;
+
+// Özellik bitleri
+const VIRTIO_BLK_F_RO: u64 = 1 << 5;
+const VIRTIO_BLK_F_FLUSH: u64 = 1 << 9;
+const VIRTIO_F_VERSION_1: u64 = 1 << 32;
+
+// İstek türleri ve durum kodları
+const VIRTIO_BLK_T_IN: u32 = 0;
+const VIRTIO_BLK_T_OUT: u32 = 1;
+const VIRTIO_BLK_T_FLUSH: u32 = 4;
+const VIRTIO_BLK_S_OK: u8 = 0;
+const VIRTIO_BLK_S_UNSUPP: u8 = 2;
+
+// Tanımlayıcı bayrakları
+const VIRTQ_DESC_F_NEXT: u16 = 1;
+const VIRTQ_DESC_F_WRITE: u16 = 2;
+
+// virtio-blk sektör boyutu (aygıtın mantıksal blok boyutundan bağımsızdır)
+const VIRTIO_SECTOR_SIZE: u64 = 512;
+const SECTORS_PER_BLOCK: u64 = BLOCK_SIZE as u64 / VIRTIO_SECTOR_SIZE;
+
+// Kuyruk uzunluğu. Aynı anda tek istek (başlık, veri, durum) kuyrukta olduğundan
+// küçük tutulur; aygıt daha azını destekliyorsa onun sınırı kullanılır.
+const VIRTIO_QUEUE_SIZE: u16 = 8;
+
+// Tek istekte aktarılabilecek en fazla blok; veri bu boyuttaki DMA ara belleğinden geçer.
+pub const VIRTIO_MAX_TRANSFER_BLOCKS: usize = 64;
+
+// Halka bölgesinin yerleşimi (tek DMA sayfası): tanımlayıcı tablosu, sürücü (avail) ve
+// aygıt (used) halkaları, istek başlığı ve durum baytı.
+const RING_REGION_SIZE: usize = 4096;
+const DESC_TABLE_OFFSET: usize = 0;
+const AVAIL_RING_OFFSET: usize = 16 * VIRTIO_QUEUE_SIZE as usize;
+const USED_RING_OFFSET: usize = 1024;
+const REQUEST_HEADER_OFFSET: usize = 2048;
+const REQUEST_STATUS_OFFSET: usize = REQUEST_HEADER_OFFSET + 16;
+
+
+// --- 2. DMA Bölgesi ---
+
+/// Çekirdekten alınan, fiziksel olarak ardışık ve aygıtın doğrudan erişebildiği bellek
+/// (sayfa hizalı). Diğer doğrudan erişimli taşıyıcılar (NVMe) da kullanır.
+pub(crate) struct DmaRegion {
+    pub(crate) virt: *mut u8,
+    pub(crate) phys: u64,
+    size: usize,
+}
+
+impl DmaRegion {
+    /// `size` baytlık sıfırlanmış bir DMA bölgesi tahsis eder.
+    pub(crate) fn allocate(size: usize) -> Result<Self, SyscallError> {
+        let mut phys: u64 = 0;
+        let result = unsafe {
+            raw_syscall(SYSCALL_DMA_ALLOCATE, size as u64, &mut phys as *mut u64 as u64, 0, 0, 0, 0)
+        };
+        if result < 0 {
+            return Err(SyscallError::from_raw(result));
+        }
+        let virt = result as usize as *mut u8;
+        unsafe { ptr::write_bytes(virt, 0, size) };
+        Ok(DmaRegion { virt, phys, size })
+    }
+
+    pub(crate) fn ptr<T>(&self, offset: usize) -> *mut T {
+        unsafe { self.virt.add(offset) as *mut T }
+    }
+}
+
+impl Drop for DmaRegion {
+    fn drop(&mut self) {
+        unsafe { raw_syscall(SYSCALL_DMA_RELEASE, self.virt as u64, self.size as u64, 0, 0, 0, 0) };
+    }
+}
+
+
+// --- 3. virtio-blk Aygıtı ---
+
+/// Bir virtio-blk aygıtını (MMIO taşıyıcısı, tek bölünmüş kuyruk) doğrudan süren
+/// BlockDevice uygulaması.
+///
+/// Sanal makinelerde `Sahne64Device`'ın her blok için yaptığı konumlanma ve kaynak
+/// okuma/yazma çağrıları yerine istekler kuyruğa doğrudan yazılır: aygıtın MMIO
+/// penceresi ve halkalar Sahne64 kaynak eşlemesiyle süreç adres alanına alınır,
+/// tamamlanma kesmesi bildirim çağrısıyla beklenir. Bir I/O için yalnızca bekleme
+/// sistem çağrısı yapılır; `read_blocks` aralığın tamamını tek istekte okur.
+///
+/// Aynı anda tek istek kuyruktadır; eş zamanlı çağrılar `lock` ile sıralanır.
+pub struct VirtioBlkDevice {
+    /// Aygıt kaynağının handle'ı (MMIO eşlemesi ve kesme bildirimi bu handle'a bağlıdır).
+    handle: ResourceHandle,
+    /// Eşlenmiş MMIO yazmaç penceresi.
+    mmio: *mut u8,
+    /// Tanımlayıcı tablosu, halkalar, istek başlığı ve durum baytı.
+    rings: DmaRegion,
+    /// Veri aktarımlarının geçtiği ara bellek (`VIRTIO_MAX_TRANSFER_BLOCKS` blok).
+    bounce: DmaRegion,
+    /// Kullanılan kuyruk uzunluğu.
+    queue_size: u16,
+    /// Kuyruk durumu (sıradaki avail indeksi, son görülen used indeksi); yalnızca `lock` altında.
+    queue: UnsafeCell<QueueState>,
+    /// İstekleri sıralayan çekirdek kilidi.
+    lock: SysLock,
+    /// Aygıtın toplam kapasitesi (blok cinsinden).
+    capacity_blocks: BlockId,
+    /// Aygıt salt okunur mu (VIRTIO_BLK_F_RO)?
+    read_only: bool,
+    /// Aygıt önbellek boşaltmayı destekliyor mu (VIRTIO_BLK_F_FLUSH)?
+    supports_flush: bool,
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    next_avail: u16,
+    last_used: u16,
+}
+
+// MMIO ve DMA bölgelerine yalnızca `lock` altında erişilir.
+unsafe impl Send for VirtioBlkDevice {}
+unsafe impl Sync for VirtioBlkDevice {}
+
+impl core::fmt::Debug for VirtioBlkDevice {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VirtioBlkDevice")
+            .field("handle", &self.handle)
+            .field("capacity_blocks", &self.capacity_blocks)
+            .field("read_only", &self.read_only)
+            .finish()
+    }
+}
+
+impl VirtioBlkDevice {
+    /// `resource_path`'teki virtio-blk aygıtını edinir, sıfırlar ve kuyruğunu kurar.
+    ///
+    /// Aygıt virtio-blk değilse, eski (legacy) MMIO sürümündeyse veya sürücünün
+    /// istediği özellikleri kabul etmezse `EINVAL` döner.
+    pub fn new(resource_path: &str) -> Result<Self, SyscallError> {
+        let handle = sahne_syscalls::resource_acquire(resource_path.as_ptr(), resource_path.len())?;
+        match Self::init(handle) {
+            Ok(device) => Ok(device),
+            Err(e) => {
+                unsafe { raw_syscall(SYSCALL_RESOURCE_RELEASE, handle, 0, 0, 0, 0, 0) };
+                Err(e)
+            }
+        }
+    }
+
+    fn init(handle: ResourceHandle) -> Result<Self, SyscallError> {
+        // 1. MMIO penceresini eşle ve aygıtı tanı
+        let result = unsafe { raw_syscall(SYSCALL_RESOURCE_MAP, handle, 0, MMIO_WINDOW_SIZE as u64, 0, 0, 0) };
+        if result < 0 {
+            return Err(SyscallError::from_raw(result));
+        }
+        let mmio = result as usize as *mut u8;
+        let read = |offset: usize| unsafe { ptr::read_volatile(mmio.add(offset) as *const u32) };
+        let write = |offset: usize, value: u32| unsafe { ptr::write_volatile(mmio.add(offset) as *mut u32, value) };
+
+        if read(REG_MAGIC) != VIRTIO_MAGIC
+            || read(REG_VERSION) != VIRTIO_MMIO_VERSION
+            || read(REG_DEVICE_ID) != VIRTIO_DEVICE_ID_BLOCK
+        {
+            return Err(SyscallError::EINVAL);
+        }
+
+        // 2. Sıfırla ve sürücü olarak tanıt
+        write(REG_STATUS, 0);
+        write(REG_STATUS, STATUS_ACKNOWLEDGE);
+        write(REG_STATUS, STATUS_ACKNOWLEDGE | STATUS_DRIVER);
+
+        // 3. Özellik anlaşması: VERSION_1 zorunlu; FLUSH ve RO varsa kullanılır
+        write(REG_DEVICE_FEATURES_SEL, 0);
+        let mut offered = read(REG_DEVICE_FEATURES) as u64;
+        write(REG_DEVICE_FEATURES_SEL, 1);
+        offered |= (read(REG_DEVICE_FEATURES) as u64) << 32;
+        if offered & VIRTIO_F_VERSION_1 == 0 {
+            write(REG_STATUS, STATUS_FAILED);
+            return Err(SyscallError::EINVAL);
+        }
+        let accepted = offered & (VIRTIO_F_VERSION_1 | VIRTIO_BLK_F_FLUSH | VIRTIO_BLK_F_RO);
+        write(REG_DRIVER_FEATURES_SEL, 0);
+        write(REG_DRIVER_FEATURES, accepted as u32);
+        write(REG_DRIVER_FEATURES_SEL, 1);
+        write(REG_DRIVER_FEATURES, (accepted >> 32) as u32);
+        write(REG_STATUS, STATUS_ACKNOWLEDGE | STATUS_DRIVER | STATUS_FEATURES_OK);
+        if read(REG_STATUS) & STATUS_FEATURES_OK == 0 {
+            write(REG_STATUS, STATUS_FAILED);
+            return Err(SyscallError::EINVAL);
+        }
+
+        // 4. Kuyruk 0'ı kur
+        write(REG_QUEUE_SEL, 0);
+        let max = read(REG_QUEUE_NUM_MAX);
+        if max < 3 {
+            // Bir istek üç tanımlayıcı gerektirir
+            write(REG_STATUS, STATUS_FAILED);
+            return Err(SyscallError::EINVAL);
+        }
+        let queue_size = (max as u16).min(VIRTIO_QUEUE_SIZE);
+        let rings = DmaRegion::allocate(RING_REGION_SIZE)?;
+        let bounce = DmaRegion::allocate(VIRTIO_MAX_TRANSFER_BLOCKS * BLOCK_SIZE)?;
+
+        let desc = rings.phys + DESC_TABLE_OFFSET as u64;
+        let avail = rings.phys + AVAIL_RING_OFFSET as u64;
+        let used = rings.phys + USED_RING_OFFSET as u64;
+        write(REG_QUEUE_NUM, queue_size as u32);
+        write(REG_QUEUE_DESC_LOW, desc as u32);
+        write(REG_QUEUE_DESC_HIGH, (desc >> 32) as u32);
+        write(REG_QUEUE_DRIVER_LOW, avail as u32);
+        write(REG_QUEUE_DRIVER_HIGH, (avail >> 32) as u32);
+        write(REG_QUEUE_DEVICE_LOW, used as u32);
+        write(REG_QUEUE_DEVICE_HIGH, (used >> 32) as u32);
+        write(REG_QUEUE_READY, 1);
+
+        // 5. Aygıtı etkinleştir ve kapasiteyi oku
+        write(REG_STATUS, STATUS_ACKNOWLEDGE | STATUS_DRIVER | STATUS_FEATURES_OK | STATUS_DRIVER_OK);
+        let sectors = read(REG_CONFIG_CAPACITY) as u64 | (read(REG_CONFIG_CAPACITY + 4) as u64) << 32;
+
+        Ok(VirtioBlkDevice {
+            handle,
+            mmio,
+            rings,
+            bounce,
+            queue_size,
+            queue: UnsafeCell::new(QueueState::default()),
+            lock: SysLock::new()?,
+            capacity_blocks: sectors / SECTORS_PER_BLOCK,
+            read_only: accepted & VIRTIO_BLK_F_RO != 0,
+            supports_flush: accepted & VIRTIO_BLK_F_FLUSH != 0,
+        })
+    }
+
+    /// Aygıt salt okunur mu? Salt okunur aygıta yazma `EINVAL` döner.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn read_reg(&self, offset: usize) -> u32 {
+        unsafe { ptr::read_volatile(self.mmio.add(offset) as *const u32) }
+    }
+
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { ptr::write_volatile(self.mmio.add(offset) as *mut u32, value) }
+    }
+
+    /// `index` numaralı tanımlayıcıyı doldurur.
+    fn set_descriptor(&self, index: u16, addr: u64, len: u32, flags: u16, next: u16) {
+        let desc = self.rings.ptr::<u8>(DESC_TABLE_OFFSET + index as usize * 16);
+        unsafe {
+            ptr::write_volatile(desc as *mut u64, addr);
+            ptr::write_volatile(desc.add(8) as *mut u32, len);
+            ptr::write_volatile(desc.add(12) as *mut u16, flags);
+            ptr::write_volatile(desc.add(14) as *mut u16, next);
+        }
+    }
+
+    /// Tek bir isteği (başlık, isteğe bağlı veri, durum) kuyruğa koyar ve tamamlanmasını
+    /// bekler. Veri `data_phys` fiziksel adresinde `bytes` bayttır (genelde ara bellek).
+    /// Çağıran `lock`'u tutmalıdır.
+    fn submit_locked(&self, request_type: u32, sector: u64, data_phys: u64, bytes: usize) -> Result<(), SyscallError> {
+        let queue = unsafe { &mut *self.queue.get() };
+
+        // 1. Başlık ve durum
+        let header = self.rings.ptr::<u8>(REQUEST_HEADER_OFFSET);
+        let status = self.rings.ptr::<u8>(REQUEST_STATUS_OFFSET);
+        unsafe {
+            ptr::write_volatile(header as *mut u32, request_type);
+            ptr::write_volatile(header.add(4) as *mut u32, 0);
+            ptr::write_volatile(header.add(8) as *mut u64, sector);
+            ptr::write_volatile(status, 0xff);
+        }
+
+        // 2. Tanımlayıcı zinciri: 0 = başlık, 1 = veri (varsa), 2 = durum
+        let header_phys = self.rings.phys + REQUEST_HEADER_OFFSET as u64;
+        let status_phys = self.rings.phys + REQUEST_STATUS_OFFSET as u64;
+        if bytes > 0 {
+            let data_flags = if request_type == VIRTIO_BLK_T_IN { VIRTQ_DESC_F_WRITE } else { 0 };
+            self.set_descriptor(0, header_phys, 16, VIRTQ_DESC_F_NEXT, 1);
+            self.set_descriptor(1, data_phys, bytes as u32, data_flags | VIRTQ_DESC_F_NEXT, 2);
+        } else {
+            self.set_descriptor(0, header_phys, 16, VIRTQ_DESC_F_NEXT, 2);
+        }
+        self.set_descriptor(2, status_phys, 1, VIRTQ_DESC_F_WRITE, 0);
+
+        // 3. Zinciri sürücü halkasına koy ve aygıta bildir
+        let avail = self.rings.ptr::<u16>(AVAIL_RING_OFFSET);
+        let slot = (queue.next_avail % self.queue_size) as usize;
+        unsafe { ptr::write_volatile(avail.add(2 + slot), 0) };
+        queue.next_avail = queue.next_avail.wrapping_add(1);
+        fence(Ordering::SeqCst);
+        unsafe { ptr::write_volatile(avail.add(1), queue.next_avail) };
+        fence(Ordering::SeqCst);
+        self.write_reg(REG_QUEUE_NOTIFY, 0);
+
+        // 4. Aygıt halkasında tamamlanmayı bekle; kesme bildirim çağrısıyla beklenir
+        let used_idx = self.rings.ptr::<u16>(USED_RING_OFFSET + 2);
+        loop {
+            fence(Ordering::SeqCst);
+            if unsafe { ptr::read_volatile(used_idx) } != queue.last_used {
+                break;
+            }
+            let result = unsafe { raw_syscall(SYSCALL_NOTIFICATION_WAIT, self.handle, 0, 0, 0, 0, 0) };
+            if result < 0 {
+                return Err(SyscallError::from_raw(result));
+            }
+            let pending = self.read_reg(REG_INTERRUPT_STATUS);
+            self.write_reg(REG_INTERRUPT_ACK, pending);
+        }
+        queue.last_used = queue.last_used.wrapping_add(1);
+        fence(Ordering::SeqCst);
+
+        match unsafe { ptr::read_volatile(status) } {
+            VIRTIO_BLK_S_OK => Ok(()),
+            VIRTIO_BLK_S_UNSUPP => Err(SyscallError::EINVAL),
+            _ => Err(SyscallError::EIO),
+        }
+    }
+
+    /// `start`'tan itibaren `buffer`'ı ara bellek boyutunda parçalar halinde okur.
+    fn read_range(&self, start: BlockId, buffer: &mut [u8]) -> Result<(), SyscallError> {
+        let blocks = (buffer.len() / BLOCK_SIZE) as u64;
+        if start.checked_add(blocks).map_or(true, |end| end > self.capacity_blocks) {
+            return Err(SyscallError::EINVAL);
+        }
+
+        self.lock.acquire();
+        let mut result = Ok(());
+        for (i, chunk) in buffer.chunks_mut(VIRTIO_MAX_TRANSFER_BLOCKS * BLOCK_SIZE).enumerate() {
+            let block = start + (i * VIRTIO_MAX_TRANSFER_BLOCKS) as u64;
+            result = self.submit_locked(VIRTIO_BLK_T_IN, block * SECTORS_PER_BLOCK, self.bounce.phys, chunk.len());
+            if result.is_err() {
+                break;
+            }
+            unsafe { ptr::copy_nonoverlapping(self.bounce.virt, chunk.as_mut_ptr(), chunk.len()) };
+        }
+        self.lock.release();
+        result
+    }
+}
+
+impl Drop for VirtioBlkDevice {
+    fn drop(&mut self) {
+        // Aygıt sıfırlanır ki DMA bölgeleri serbest kaldıktan sonra onlara erişmesin
+        self.write_reg(REG_STATUS, 0);
+        unsafe { raw_syscall(SYSCALL_RESOURCE_RELEASE, self.handle, 0, 0, 0, 0, 0) };
+    }
+}
+
+impl BlockDevice for VirtioBlkDevice {
+    type Error = SyscallError;
+
+    fn read_block(&self, id: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if buffer.len() != BLOCK_SIZE {
+            return Err(SyscallError::EINVAL);
+        }
+        self.read_range(id, buffer)
+    }
+
+    fn write_block(&self, id: BlockId, data: &[u8]) -> Result<(), Self::Error> {
+        if data.len() != BLOCK_SIZE || id >= self.capacity_blocks || self.read_only {
+            return Err(SyscallError::EINVAL);
+        }
+
+        self.lock.acquire();
+        unsafe { ptr::copy_nonoverlapping(data.as_ptr(), self.bounce.virt, BLOCK_SIZE) };
+        let result = self.submit_locked(VIRTIO_BLK_T_OUT, id * SECTORS_PER_BLOCK, self.bounce.phys, BLOCK_SIZE);
+        self.lock.release();
+        result
+    }
+
+    fn total_blocks(&self) -> BlockId {
+        self.capacity_blocks
+    }
+
+    fn read_blocks(&self, start: BlockId, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if buffer.is_empty() || buffer.len() % BLOCK_SIZE != 0 {
+            return Err(SyscallError::EINVAL);
+        }
+        self.read_range(start, buffer)
+    }
+
+    fn buffer_requirements(&self) -> BufferRequirements {
+        BufferRequirements::DMA_PAGE
+    }
+
+    unsafe fn read_block_dma(&self, id: BlockId, buffer: &mut [u8], phys: u64) -> Result<(), Self::Error> {
+        if buffer.len() != BLOCK_SIZE || id >= self.capacity_blocks {
+            return Err(SyscallError::EINVAL);
+        }
+
+        // Aygıt doğrudan çağıranın arabelleğine yazar; ara bellekten kopya yapılmaz
+        self.lock.acquire();
+        let result = self.submit_locked(VIRTIO_BLK_T_IN, id * SECTORS_PER_BLOCK, phys, BLOCK_SIZE);
+        self.lock.release();
+        result
+    }
+
+    unsafe fn write_batch_dma(&self, blocks: &[(BlockId, &[u8])], phys: &[Option<u64>]) -> Result<(), Self::Error> {
+        for (&(id, data), &address) in blocks.iter().zip(phys) {
+            let Some(address) = address else {
+                self.write_block(id, data)?;
+                continue;
+            };
+            if data.len() != BLOCK_SIZE || id >= self.capacity_blocks || self.read_only {
+                return Err(SyscallError::EINVAL);
+            }
+            self.lock.acquire();
+            let result = self.submit_locked(VIRTIO_BLK_T_OUT, id * SECTORS_PER_BLOCK, address, BLOCK_SIZE);
+            self.lock.release();
+            result?;
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        // Önbellek boşaltmayı bildirmeyen aygıt yazmaları doğrudan kalıcı yapar (write-through)
+        if !self.supports_flush || self.read_only {
+            return Ok(());
+        }
+
+        self.lock.acquire();
+        let result = self.submit_locked(VIRTIO_BLK_T_FLUSH, 0, 0, 0);
+        self.lock.release();
+        result
+    }
+}
@@ -0,0 +1,131 @@
+// src/xattr.rs
+
+#![allow(dead_code, unused_variables)]
+
+use crate::block_device::{BlockId, BLOCK_SIZE};
+use crate::btree::{BTreeKey, BTREE_VALUE_MAX, ITEM_TYPE_XATTR};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ve Türler ---
+
+// Öğe değerinin sabit kısmı: bayraklar (1) + ad uzunluğu (1)
+const XATTR_HEADER_SIZE: usize = 2;
+
+// Taşma bloğu işaretçisinin boyutu: blok ID'si (8) + değer uzunluğu (4)
+const XATTR_OVERFLOW_REF_SIZE: usize = 12;
+
+// Değer, öğenin içinde değil ayrı bir taşma bloğunda.
+const XATTR_FLAG_OVERFLOW: u8 = 1;
+
+// Bir öznitelik adının bayt cinsinden en büyük uzunluğu (taşma işaretçisiyle birlikte sığmalı).
+pub const XATTR_NAME_MAX: usize = BTREE_VALUE_MAX - XATTR_HEADER_SIZE - XATTR_OVERFLOW_REF_SIZE;
+
+// Bir öznitelik değerinin bayt cinsinden en büyük uzunluğu (tek taşma bloğu).
+pub const XATTR_VALUE_MAX: usize = BLOCK_SIZE;
+
+
+// --- 2. Öznitelik Kaydı ---
+
+/// Bir özniteliğin değerinin nerede tutulduğu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XattrValue {
+    /// Değer, metadata öğesinin içinde.
+    Inline(Vec<u8>),
+    /// Değer, ayrı bir taşma bloğunun ilk `len` baytında.
+    Overflow { block: BlockId, len: u32 },
+}
+
+/// Bir inode'a bağlı tek bir genişletilmiş öznitelik (xattr).
+///
+/// Öznitelikler metadata B-Ağacında `(inode, ITEM_TYPE_XATTR, sıra numarası)` anahtarıyla,
+/// dizin girdileri gibi doğrusal olarak tutulur. Öğeye sığmayan değerler bir taşma
+/// bloğuna yazılır ve öğe yalnızca bloğu işaret eder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XattrEntry {
+    /// Özniteliğin adı (ör. `security.label`).
+    pub name: String,
+    /// Özniteliğin değeri veya taşma bloğu.
+    pub value: XattrValue,
+}
+
+impl XattrEntry {
+    /// `inode_id` inode'unun `index` sıra numaralı özniteliğinin anahtarı.
+    pub fn key(inode_id: BlockId, index: u64) -> BTreeKey {
+        BTreeKey {
+            object_id: inode_id,
+            item_type: ITEM_TYPE_XATTR,
+            offset: index,
+        }
+    }
+
+    /// Anahtar bu inode'un bir özniteliğine mi ait?
+    pub fn belongs_to(key: &BTreeKey, inode_id: BlockId) -> bool {
+        key.object_id == inode_id && key.item_type == ITEM_TYPE_XATTR
+    }
+
+    /// Değer, ad ile birlikte metadata öğesine sığıyor mu?
+    pub fn fits_inline(name: &str, value: &[u8]) -> bool {
+        XATTR_HEADER_SIZE + name.len() + value.len() <= BTREE_VALUE_MAX
+    }
+
+    /// Bir adın öznitelik adı olarak kullanılabilir olup olmadığını denetler.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && name.len() <= XATTR_NAME_MAX && !name.bytes().any(|b| b == 0)
+    }
+
+    /// Kaydı B-Ağacı değer formatına yazar: bayraklar, ad uzunluğu, ad, değer veya
+    /// taşma işaretçisi (blok ID'si ve uzunluk, LE).
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(BTREE_VALUE_MAX);
+        match &self.value {
+            XattrValue::Inline(value) => {
+                out.push(0);
+                out.push(self.name.len() as u8);
+                out.extend_from_slice(self.name.as_bytes());
+                out.extend_from_slice(value);
+            }
+            XattrValue::Overflow { block, len } => {
+                out.push(XATTR_FLAG_OVERFLOW);
+                out.push(self.name.len() as u8);
+                out.extend_from_slice(self.name.as_bytes());
+                out.extend_from_slice(&block.to_le_bytes());
+                out.extend_from_slice(&len.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    /// B-Ağacı değerinden kaydı çözer; değer bozuksa `None`.
+    pub fn decode(raw: &[u8]) -> Option<Self> {
+        if raw.len() < XATTR_HEADER_SIZE {
+            return None;
+        }
+
+        let name_end = XATTR_HEADER_SIZE + raw[1] as usize;
+        let name = core::str::from_utf8(raw.get(XATTR_HEADER_SIZE..name_end)?).ok()?;
+        let rest = &raw[name_end..];
+
+        let value = if raw[0] & XATTR_FLAG_OVERFLOW != 0 {
+            if rest.len() < XATTR_OVERFLOW_REF_SIZE {
+                return None;
+            }
+            let mut block = [0u8; 8];
+            let mut len = [0u8; 4];
+            block.copy_from_slice(&rest[0..8]);
+            len.copy_from_slice(&rest[8..12]);
+            XattrValue::Overflow {
+                block: BlockId::from_le_bytes(block),
+                len: u32::from_le_bytes(len),
+            }
+        } else {
+            XattrValue::Inline(rest.to_vec())
+        };
+
+        Some(XattrEntry {
+            name: String::from(name),
+            value,
+        })
+    }
+}
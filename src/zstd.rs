@@ -0,0 +1,787 @@
+// src/zstd.rs
+
+#![allow(dead_code, unused_variables)]
+
+use alloc::vec::Vec;
+
+
+// --- 1. Sabitler ---
+
+/// Desteklenen en düşük ve en yüksek sıkıştırma düzeyi; düzey eşleşme aramasının ne
+/// kadar derin yapılacağını belirler (bkz. `MatchParams::for_level`).
+pub const ZSTD_LEVEL_MIN: u8 = 1;
+pub const ZSTD_LEVEL_MAX: u8 = 19;
+/// Düzey belirtilmediğinde kullanılan düzey (zstd aracının varsayılanıyla aynı).
+pub const ZSTD_LEVEL_DEFAULT: u8 = 3;
+
+// Çerçeve sihirli sayısı (RFC 8878, 3.1.1)
+const ZSTD_MAGIC: u32 = 0xFD2F_B528;
+
+// Bir bloğun çözülmüş boyutunun üst sınırı; sıkıştırıcı girdiyi tek blokta yazar.
+const ZSTD_BLOCK_MAX: usize = 128 * 1024;
+
+// Blok türleri
+const BLOCK_RAW: u32 = 0;
+const BLOCK_RLE: u32 = 1;
+const BLOCK_COMPRESSED: u32 = 2;
+
+// Değişmez bölümü türleri (yalnızca Huffman'sız olanlar desteklenir)
+const LITERALS_RAW: u8 = 0;
+const LITERALS_RLE: u8 = 1;
+
+// Dizi tablolarının kipleri (yalnızca öntanımlı ve RLE desteklenir)
+const MODE_PREDEFINED: u8 = 0;
+const MODE_RLE: u8 = 1;
+
+// Eşleşme aramasında karma tablosunun boyutu (2^15 girdi) ve en kısa eşleşme.
+const HASH_BITS: u32 = 15;
+const MIN_MATCH: usize = 4;
+const NO_POSITION: u32 = u32::MAX;
+
+// FSE tablolarının en büyüğü (öntanımlı tablolar 2^6 durumludur).
+const FSE_MAX_STATES: usize = 64;
+
+// Öntanımlı dağılımlar (RFC 8878, 3.1.1.3.2.2); -1 "düşük olasılıklı" semboldür.
+const LL_DEFAULT_NORM: [i16; 36] = [
+    4, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 2, 1, 1, 1, 1, 1,
+    -1, -1, -1, -1,
+];
+const LL_DEFAULT_LOG: u32 = 6;
+const ML_DEFAULT_NORM: [i16; 53] = [
+    1, 4, 3, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1, -1, -1,
+];
+const ML_DEFAULT_LOG: u32 = 6;
+const OF_DEFAULT_NORM: [i16; 29] = [
+    1, 1, 1, 1, 1, 1, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1,
+];
+const OF_DEFAULT_LOG: u32 = 5;
+
+// Değişmez ve eşleşme uzunluğu kodlarının taban değerleri ve ek bit sayıları.
+const LL_BASE: [u32; 36] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 18, 20, 22, 24, 28, 32, 40, 48, 64,
+    128, 256, 512, 1024, 2048, 4096, 8192, 16384, 32768, 65536,
+];
+const LL_BITS: [u8; 36] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 3, 3, 4, 6, 7, 8, 9, 10, 11,
+    12, 13, 14, 15, 16,
+];
+const ML_BASE: [u32; 53] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27,
+    28, 29, 30, 31, 32, 33, 34, 35, 37, 39, 41, 43, 47, 51, 59, 67, 83, 99, 131, 259, 515, 1027,
+    2051, 4099, 8195, 16387, 32771, 65539,
+];
+const ML_BITS: [u8; 53] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+];
+
+
+// --- 2. Sıkıştırma ---
+
+/// `input`'u tek bir zstd çerçevesi olarak `output`'a sıkıştırır (RFC 8878).
+///
+/// Üretilen çerçeve standart zstd araçlarıyla açılabilir. Değişmezler Huffman'sız,
+/// diziler öntanımlı FSE tablolarıyla kodlanır; kazanç, düzeyle derinleşen eşleşme
+/// aramasından ve entropi kodlu uzunluk/uzaklıklardan gelir. `input` en fazla 128 KiB
+/// olmalıdır; `level` `[ZSTD_LEVEL_MIN, ZSTD_LEVEL_MAX]` aralığına sıkıştırılır.
+///
+/// # Döndürür
+/// Çerçevenin uzunluğu; sonuç `output`'a sığmazsa veya çalışma belleği ayrılamazsa `None`
+/// (veri olduğu gibi saklanmalı).
+pub fn zstd_compress(input: &[u8], output: &mut [u8], level: u8) -> Option<usize> {
+    if input.len() > ZSTD_BLOCK_MAX {
+        return None;
+    }
+    let params = MatchParams::for_level(level.clamp(ZSTD_LEVEL_MIN, ZSTD_LEVEL_MAX));
+    let mut out = write_frame_header(output, input.len())?;
+
+    let (literals, sequences) = find_sequences(input, &params)?;
+    let mut block = Vec::new();
+    block.try_reserve(input.len()).ok()?;
+    if !sequences.is_empty() {
+        encode_literals(&mut block, &literals);
+        encode_sequences(&mut block, &sequences);
+    }
+
+    // Kazanç yoksa blok ham yazılır; çağıran zaten kısalmayan sonucu atar
+    let (block_type, content) = if sequences.is_empty() || block.len() >= input.len() {
+        (BLOCK_RAW, input)
+    } else {
+        (BLOCK_COMPRESSED, &block[..])
+    };
+    let header = 1 | (block_type << 1) | ((content.len() as u32) << 3);
+    output.get_mut(out..out + 3)?.copy_from_slice(&header.to_le_bytes()[..3]);
+    out += 3;
+    output.get_mut(out..out + content.len())?.copy_from_slice(content);
+    Some(out + content.len())
+}
+
+/// Tek bölütlü çerçeve başlığını (içerik boyutu dahil, sağlama toplamı olmadan) yazar.
+fn write_frame_header(output: &mut [u8], content_size: usize) -> Option<usize> {
+    output.get_mut(0..4)?.copy_from_slice(&ZSTD_MAGIC.to_le_bytes());
+    let single_segment = 1 << 5;
+    let (descriptor, fcs): (u8, &[u8]) = if content_size < 256 {
+        (single_segment, &(content_size as u8).to_le_bytes())
+    } else if content_size < 65536 + 256 {
+        (single_segment | (1 << 6), &((content_size - 256) as u16).to_le_bytes())
+    } else {
+        (single_segment | (2 << 6), &(content_size as u32).to_le_bytes())
+    };
+    *output.get_mut(4)? = descriptor;
+    output.get_mut(5..5 + fcs.len())?.copy_from_slice(fcs);
+    Some(5 + fcs.len())
+}
+
+/// Bir düzeyin eşleşme arama ayarları.
+struct MatchParams {
+    /// Karma zincirinde incelenecek en fazla aday.
+    depth: usize,
+    /// Bir sonraki konumda daha uzun eşleşme varsa mevcut eşleşme ertelenir mi?
+    lazy: bool,
+}
+
+impl MatchParams {
+    fn for_level(level: u8) -> Self {
+        let depth = match level {
+            0..=2 => 1,
+            3..=5 => 4,
+            6..=9 => 16,
+            10..=14 => 64,
+            _ => 256,
+        };
+        MatchParams { depth, lazy: level >= 6 }
+    }
+}
+
+/// Bir dizi: `lit_len` değişmezin ardından `offset_value` ile gösterilen uzaklıktan
+/// `match_len` baytlık kopya. `offset_value` 1-3 ise tekrar uzaklığıdır.
+struct Sequence {
+    lit_len: u32,
+    match_len: u32,
+    offset_value: u32,
+}
+
+/// Karma zincirli eşleştirici; girdideki konumları 4 baytlık önekleriyle izler.
+struct MatchFinder<'a> {
+    input: &'a [u8],
+    head: Vec<u32>,
+    chain: Vec<u32>,
+    /// Bu konumdan önceki tüm konumlar tablolara eklenmiştir.
+    next_insert: usize,
+}
+
+impl<'a> MatchFinder<'a> {
+    fn new(input: &'a [u8]) -> Option<Self> {
+        let mut head = Vec::new();
+        head.try_reserve_exact(1 << HASH_BITS).ok()?;
+        head.resize(1 << HASH_BITS, NO_POSITION);
+        let mut chain = Vec::new();
+        chain.try_reserve_exact(input.len()).ok()?;
+        chain.resize(input.len(), NO_POSITION);
+        Some(MatchFinder { input, head, chain, next_insert: 0 })
+    }
+
+    /// `until`'dan önceki konumları tablolara ekler.
+    fn insert_until(&mut self, until: usize) {
+        while self.next_insert < until {
+            let pos = self.next_insert;
+            let slot = hash(read_u32(self.input, pos));
+            self.chain[pos] = self.head[slot];
+            self.head[slot] = pos as u32;
+            self.next_insert += 1;
+        }
+    }
+
+    /// `pos`'taki en uzun eşleşme: (uzunluk, uzaklık); yoksa uzunluk 0.
+    fn find(&mut self, pos: usize, depth: usize) -> (usize, usize) {
+        self.insert_until(pos);
+        let input = self.input;
+        let prefix = read_u32(input, pos);
+        let mut best = (0, 0);
+        let mut candidate = self.head[hash(prefix)];
+        for _ in 0..depth {
+            if candidate == NO_POSITION {
+                break;
+            }
+            let start = candidate as usize;
+            if read_u32(input, start) == prefix {
+                let len = MIN_MATCH + input[start + MIN_MATCH..]
+                    .iter()
+                    .zip(&input[pos + MIN_MATCH..])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if len > best.0 {
+                    best = (len, pos - start);
+                    if pos + len == input.len() {
+                        break;
+                    }
+                }
+            }
+            candidate = self.chain[start];
+        }
+        best
+    }
+}
+
+/// Girdiyi değişmezlere ve dizilere ayırır. Son dizi sonrasında kalan değişmezler de
+/// değişmez arabelleğinin sonundadır.
+fn find_sequences(input: &[u8], params: &MatchParams) -> Option<(Vec<u8>, Vec<Sequence>)> {
+    let mut literals = Vec::new();
+    literals.try_reserve(input.len()).ok()?;
+    let mut sequences = Vec::new();
+    let mut finder = MatchFinder::new(input)?;
+    // Tekrar uzaklıklarından yalnızca ilki kullanılır; çözücüyle aynı geçmiş tutulur
+    let mut repeats = [1u32, 4, 8];
+
+    let mut anchor = 0;
+    let mut pos = 0;
+    while pos + MIN_MATCH <= input.len() {
+        let (mut len, mut offset) = finder.find(pos, params.depth);
+        if len < MIN_MATCH {
+            pos += 1;
+            continue;
+        }
+        while params.lazy && pos + 1 + MIN_MATCH <= input.len() {
+            let (next_len, next_offset) = finder.find(pos + 1, params.depth);
+            if next_len <= len {
+                break;
+            }
+            pos += 1;
+            len = next_len;
+            offset = next_offset;
+        }
+
+        let lit_len = (pos - anchor) as u32;
+        let offset = offset as u32;
+        let offset_value = if lit_len > 0 && offset == repeats[0] {
+            1
+        } else {
+            repeats = [offset, repeats[0], repeats[1]];
+            offset + 3
+        };
+        literals.extend_from_slice(&input[anchor..pos]);
+        sequences.try_reserve(1).ok()?;
+        sequences.push(Sequence { lit_len, match_len: len as u32, offset_value });
+        pos += len;
+        anchor = pos;
+    }
+    literals.extend_from_slice(&input[anchor..]);
+    Some((literals, sequences))
+}
+
+/// Değişmez bölümünü ham olarak yazar.
+fn encode_literals(block: &mut Vec<u8>, literals: &[u8]) {
+    let size = literals.len() as u32;
+    if size < 32 {
+        block.push(LITERALS_RAW | (size << 3) as u8);
+    } else if size < 4096 {
+        let header = LITERALS_RAW as u32 | (1 << 2) | (size << 4);
+        block.extend_from_slice(&header.to_le_bytes()[..2]);
+    } else {
+        let header = LITERALS_RAW as u32 | (3 << 2) | (size << 4);
+        block.extend_from_slice(&header.to_le_bytes()[..3]);
+    }
+    block.extend_from_slice(literals);
+}
+
+/// Dizi bölümünü öntanımlı tablolarla yazar. Diziler sondan başa, geriye doğru okunan
+/// tek bir bit akışına kodlanır (RFC 8878, 3.1.1.3.2.1).
+fn encode_sequences(block: &mut Vec<u8>, sequences: &[Sequence]) {
+    let count = sequences.len();
+    if count < 128 {
+        block.push(count as u8);
+    } else if count < 0x7F00 {
+        block.push(((count >> 8) + 128) as u8);
+        block.push(count as u8);
+    } else {
+        block.push(0xFF);
+        block.extend_from_slice(&((count - 0x7F00) as u16).to_le_bytes());
+    }
+    block.push(0); // Üç tablo da öntanımlı
+
+    let ll_table = FseEncoder::new(&LL_DEFAULT_NORM, LL_DEFAULT_LOG);
+    let ml_table = FseEncoder::new(&ML_DEFAULT_NORM, ML_DEFAULT_LOG);
+    let of_table = FseEncoder::new(&OF_DEFAULT_NORM, OF_DEFAULT_LOG);
+    let codes = |sequence: &Sequence| {
+        let ll_code = length_code(&LL_BASE, sequence.lit_len);
+        let ml_code = length_code(&ML_BASE, sequence.match_len);
+        let of_code = 31 - sequence.offset_value.leading_zeros() as usize;
+        (ll_code, ml_code, of_code)
+    };
+    let extra_bits = |bits: &mut BitWriter, sequence: &Sequence, (ll_code, ml_code, of_code): (usize, usize, usize)| {
+        bits.add(sequence.lit_len - LL_BASE[ll_code], LL_BITS[ll_code] as u32);
+        bits.add(sequence.match_len - ML_BASE[ml_code], ML_BITS[ml_code] as u32);
+        bits.add(sequence.offset_value - (1 << of_code), of_code as u32);
+    };
+
+    let mut bits = BitWriter::new(block);
+    let last = &sequences[count - 1];
+    let (ll_code, ml_code, of_code) = codes(last);
+    let mut ml_state = ml_table.init_state(ml_code);
+    let mut of_state = of_table.init_state(of_code);
+    let mut ll_state = ll_table.init_state(ll_code);
+    extra_bits(&mut bits, last, (ll_code, ml_code, of_code));
+
+    for sequence in sequences[..count - 1].iter().rev() {
+        let (ll_code, ml_code, of_code) = codes(sequence);
+        of_table.encode(&mut bits, &mut of_state, of_code);
+        ml_table.encode(&mut bits, &mut ml_state, ml_code);
+        ll_table.encode(&mut bits, &mut ll_state, ll_code);
+        extra_bits(&mut bits, sequence, (ll_code, ml_code, of_code));
+    }
+
+    bits.add(ml_state, ML_DEFAULT_LOG);
+    bits.add(of_state, OF_DEFAULT_LOG);
+    bits.add(ll_state, LL_DEFAULT_LOG);
+    bits.finish();
+}
+
+/// Bir uzunluğun kodu: tabanı uzunluğu aşmayan en büyük kod.
+fn length_code(base: &[u32], value: u32) -> usize {
+    base.iter().rposition(|&start| start <= value).unwrap_or(0)
+}
+
+/// Bir sembolün FSE kodlama dönüşümü.
+#[derive(Clone, Copy, Default)]
+struct SymbolTransform {
+    delta_find_state: i32,
+    delta_nb_bits: u32,
+}
+
+/// Normalleştirilmiş bir dağılımdan kurulan FSE kodlama tablosu.
+struct FseEncoder {
+    log: u32,
+    states: [u16; FSE_MAX_STATES],
+    transforms: [SymbolTransform; ML_BASE.len()],
+}
+
+impl FseEncoder {
+    fn new(norm: &[i16], log: u32) -> Self {
+        let size = 1u32 << log;
+        let symbols = spread_symbols(norm, log);
+
+        // Her sembolün durumları, yayılım sırasına göre ardışık dizilir
+        let mut cumul = [0u32; ML_BASE.len() + 1];
+        for (s, &count) in norm.iter().enumerate() {
+            cumul[s + 1] = cumul[s] + if count == -1 { 1 } else { count.max(0) as u32 };
+        }
+        let mut states = [0u16; FSE_MAX_STATES];
+        for (u, &symbol) in symbols[..size as usize].iter().enumerate() {
+            let slot = &mut cumul[symbol as usize];
+            states[*slot as usize] = (size + u as u32) as u16;
+            *slot += 1;
+        }
+
+        let mut transforms = [SymbolTransform::default(); ML_BASE.len()];
+        let mut total = 0i32;
+        for (s, &count) in norm.iter().enumerate() {
+            match count {
+                0 => {}
+                -1 | 1 => {
+                    transforms[s] = SymbolTransform { delta_find_state: total - 1, delta_nb_bits: (log << 16).wrapping_sub(size) };
+                    total += 1;
+                }
+                _ => {
+                    let count = count as u32;
+                    let max_bits_out = log - highbit(count - 1);
+                    let min_state_plus = count << max_bits_out;
+                    transforms[s] = SymbolTransform {
+                        delta_find_state: total - count as i32,
+                        delta_nb_bits: (max_bits_out << 16).wrapping_sub(min_state_plus),
+                    };
+                    total += count as i32;
+                }
+            }
+        }
+        FseEncoder { log, states, transforms }
+    }
+
+    /// İlk kodlanan (çözücünün son okuyacağı) sembolün durumu; bit yazılmaz.
+    fn init_state(&self, symbol: usize) -> u32 {
+        let transform = self.transforms[symbol];
+        let nb_bits_out = transform.delta_nb_bits.wrapping_add(1 << 15) >> 16;
+        let value = (nb_bits_out << 16).wrapping_sub(transform.delta_nb_bits);
+        self.states[((value >> nb_bits_out) as i32 + transform.delta_find_state) as usize] as u32
+    }
+
+    fn encode(&self, bits: &mut BitWriter, state: &mut u32, symbol: usize) {
+        let transform = self.transforms[symbol];
+        let nb_bits_out = state.wrapping_add(transform.delta_nb_bits) >> 16;
+        bits.add(*state, nb_bits_out);
+        *state = self.states[((*state >> nb_bits_out) as i32 + transform.delta_find_state) as usize] as u32;
+    }
+}
+
+/// Düşük bitten başlayarak bayt bayt biriktiren bit yazıcısı.
+struct BitWriter<'a> {
+    out: &'a mut Vec<u8>,
+    container: u64,
+    filled: u32,
+}
+
+impl<'a> BitWriter<'a> {
+    fn new(out: &'a mut Vec<u8>) -> Self {
+        BitWriter { out, container: 0, filled: 0 }
+    }
+
+    /// `value`'nun düşük `count` bitini ekler (`count` ≤ 32).
+    fn add(&mut self, value: u32, count: u32) {
+        let mask = if count == 32 { u32::MAX } else { (1u32 << count) - 1 };
+        self.container |= ((value & mask) as u64) << self.filled;
+        self.filled += count;
+        while self.filled >= 8 {
+            self.out.push(self.container as u8);
+            self.container >>= 8;
+            self.filled -= 8;
+        }
+    }
+
+    /// Akışı kapatır: çözücünün başlangıcı bulması için 1 biti eklenir ve bayta tamamlanır.
+    fn finish(mut self) {
+        self.add(1, 1);
+        if self.filled > 0 {
+            self.out.push(self.container as u8);
+        }
+    }
+}
+
+
+// --- 3. Çözme ---
+
+/// Bir zstd çerçevesini `output`'a çözer.
+///
+/// Ham ve RLE bloklar ile Huffman'sız değişmezler, öntanımlı veya RLE dizi tabloları
+/// içeren sıkıştırılmış bloklar desteklenir; bu, `zstd_compress`'in ürettiği her şeyi
+/// kapsar. Huffman veya FSE tablosu taşıyan (ör. başka bir zstd uygulamasının yazdığı)
+/// bloklar ve sözlük kullanan çerçeveler reddedilir. Sağlama toplamı doğrulanmaz.
+///
+/// # Döndürür
+/// Çözülen uzunluk; girdi bozuksa, desteklenmiyorsa veya `output`'a sığmıyorsa `None`.
+pub fn zstd_decompress(input: &[u8], output: &mut [u8]) -> Option<usize> {
+    if u32::from_le_bytes(input.get(0..4)?.try_into().ok()?) != ZSTD_MAGIC {
+        return None;
+    }
+    let descriptor = *input.get(4)?;
+    if descriptor & 0x08 != 0 {
+        return None; // Ayrılmış bit
+    }
+    let single_segment = descriptor & 0x20 != 0;
+    let has_checksum = descriptor & 0x04 != 0;
+    let mut pos = 5 + if single_segment { 0 } else { 1 };
+
+    let dict_id_len = [0, 1, 2, 4][(descriptor & 0x03) as usize];
+    let dict_id = input.get(pos..pos + dict_id_len)?.iter().rev().fold(0u32, |acc, &b| (acc << 8) | b as u32);
+    if dict_id != 0 {
+        return None;
+    }
+    pos += dict_id_len;
+
+    let fcs_len = match descriptor >> 6 {
+        0 => if single_segment { 1 } else { 0 },
+        1 => 2,
+        2 => 4,
+        _ => 8,
+    };
+    let mut content_size = None;
+    if fcs_len > 0 {
+        let raw = input.get(pos..pos + fcs_len)?.iter().rev().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+        content_size = Some(if fcs_len == 2 { raw + 256 } else { raw });
+        pos += fcs_len;
+    }
+    if content_size.is_some_and(|size| size > output.len() as u64) {
+        return None;
+    }
+
+    let mut out = 0;
+    let mut repeats = [1u32, 4, 8];
+    loop {
+        let header = input.get(pos..pos + 3)?;
+        let header = u32::from_le_bytes([header[0], header[1], header[2], 0]);
+        pos += 3;
+        let last = header & 1 != 0;
+        let size = (header >> 3) as usize;
+        match (header >> 1) & 0b11 {
+            BLOCK_RAW => {
+                let data = input.get(pos..pos + size)?;
+                output.get_mut(out..out + size)?.copy_from_slice(data);
+                pos += size;
+                out += size;
+            }
+            BLOCK_RLE => {
+                let byte = *input.get(pos)?;
+                output.get_mut(out..out + size)?.fill(byte);
+                pos += 1;
+                out += size;
+            }
+            BLOCK_COMPRESSED => {
+                if size > ZSTD_BLOCK_MAX {
+                    return None;
+                }
+                out = decode_block(input.get(pos..pos + size)?, output, out, &mut repeats)?;
+                pos += size;
+            }
+            _ => return None,
+        }
+        if last {
+            break;
+        }
+    }
+    if has_checksum {
+        input.get(pos..pos + 4)?;
+    }
+    match content_size {
+        Some(size) if size != out as u64 => None,
+        _ => Some(out),
+    }
+}
+
+/// Sıkıştırılmış bir bloğu `output`'un `out` konumuna çözer.
+///
+/// # Döndürür
+/// Bloktan sonraki çıktı konumu.
+fn decode_block(block: &[u8], output: &mut [u8], mut out: usize, repeats: &mut [u32; 3]) -> Option<usize> {
+    // Değişmez bölümü
+    let first = *block.first()?;
+    let literals_type = first & 0b11;
+    let (size, header_len) = match (first >> 2) & 0b11 {
+        0 | 2 => ((first >> 3) as usize, 1),
+        1 => ((u16::from_le_bytes([first, *block.get(1)?]) >> 4) as usize, 2),
+        _ => ((u32::from_le_bytes([first, *block.get(1)?, *block.get(2)?, 0]) >> 4) as usize, 3),
+    };
+    let mut pos = header_len;
+    let rle_literals;
+    let literals: &[u8] = match literals_type {
+        LITERALS_RAW => {
+            pos += size;
+            block.get(header_len..pos)?
+        }
+        LITERALS_RLE => {
+            let byte = *block.get(pos)?;
+            pos += 1;
+            if size > ZSTD_BLOCK_MAX {
+                return None;
+            }
+            rle_literals = alloc::vec![byte; size];
+            &rle_literals
+        }
+        _ => return None, // Huffman kodlu değişmezler
+    };
+
+    // Dizi bölümü
+    let byte0 = *block.get(pos)? as usize;
+    let count = if byte0 < 128 {
+        pos += 1;
+        byte0
+    } else if byte0 < 255 {
+        pos += 2;
+        ((byte0 - 128) << 8) + *block.get(pos - 1)? as usize
+    } else {
+        pos += 3;
+        u16::from_le_bytes([*block.get(pos - 2)?, *block.get(pos - 1)?]) as usize + 0x7F00
+    };
+    if count == 0 {
+        output.get_mut(out..out + literals.len())?.copy_from_slice(literals);
+        return Some(out + literals.len());
+    }
+
+    let modes = *block.get(pos)?;
+    pos += 1;
+    if modes & 0b11 != 0 {
+        return None;
+    }
+    let mut table = |mode: u8, norm: &[i16], log: u32| -> Option<FseDecoder> {
+        match mode {
+            MODE_PREDEFINED => Some(FseDecoder::new(norm, log)),
+            MODE_RLE => {
+                let symbol = *block.get(pos)?;
+                pos += 1;
+                ((symbol as usize) < norm.len()).then(|| FseDecoder::rle(symbol))
+            }
+            _ => None, // FSE tablosu taşıyan veya önceki bloğu tekrarlayan kipler
+        }
+    };
+    let ll_table = table(modes >> 6, &LL_DEFAULT_NORM, LL_DEFAULT_LOG)?;
+    let of_table = table((modes >> 4) & 0b11, &OF_DEFAULT_NORM, OF_DEFAULT_LOG)?;
+    let ml_table = table((modes >> 2) & 0b11, &ML_DEFAULT_NORM, ML_DEFAULT_LOG)?;
+
+    let mut bits = BitReader::new(block.get(pos..)?)?;
+    let mut ll_state = bits.read(ll_table.log)? as usize;
+    let mut of_state = bits.read(of_table.log)? as usize;
+    let mut ml_state = bits.read(ml_table.log)? as usize;
+    let mut lit_pos = 0;
+
+    for i in 0..count {
+        let of_code = of_table.entries[of_state].symbol as u32;
+        let ml_code = ml_table.entries[ml_state].symbol as usize;
+        let ll_code = ll_table.entries[ll_state].symbol as usize;
+        if of_code > 31 {
+            return None;
+        }
+        let offset_value = (1u32 << of_code) + bits.read(of_code)?;
+        let match_len = (ML_BASE[ml_code] + bits.read(ML_BITS[ml_code] as u32)?) as usize;
+        let lit_len = (LL_BASE[ll_code] + bits.read(LL_BITS[ll_code] as u32)?) as usize;
+        if i + 1 < count {
+            ll_state = ll_table.next(ll_state, &mut bits)?;
+            ml_state = ml_table.next(ml_state, &mut bits)?;
+            of_state = of_table.next(of_state, &mut bits)?;
+        }
+
+        // Uzaklık: 3'ten büyükse yeni, değilse tekrar geçmişinden (değişmezsiz dizide kayar)
+        let offset = if offset_value > 3 {
+            let offset = offset_value - 3;
+            *repeats = [offset, repeats[0], repeats[1]];
+            offset
+        } else {
+            let index = offset_value as usize - 1 + if lit_len == 0 { 1 } else { 0 };
+            let offset = if index == 3 { repeats[0].wrapping_sub(1) } else { repeats[index] };
+            match index {
+                0 => {}
+                1 => *repeats = [offset, repeats[0], repeats[2]],
+                _ => *repeats = [offset, repeats[0], repeats[1]],
+            }
+            offset
+        } as usize;
+
+        let source = literals.get(lit_pos..lit_pos + lit_len)?;
+        output.get_mut(out..out + lit_len)?.copy_from_slice(source);
+        lit_pos += lit_len;
+        out += lit_len;
+
+        if offset == 0 || offset > out || out + match_len > output.len() {
+            return None;
+        }
+        for i in out..out + match_len {
+            output[i] = output[i - offset];
+        }
+        out += match_len;
+    }
+    if bits.remaining != 0 {
+        return None;
+    }
+
+    let rest = &literals[lit_pos..];
+    output.get_mut(out..out + rest.len())?.copy_from_slice(rest);
+    Some(out + rest.len())
+}
+
+/// FSE çözme tablosunun bir durumu.
+#[derive(Clone, Copy, Default)]
+struct FseEntry {
+    symbol: u8,
+    nb_bits: u8,
+    base: u16,
+}
+
+/// Normalleştirilmiş bir dağılımdan (veya tek sembolden) kurulan FSE çözme tablosu.
+struct FseDecoder {
+    log: u32,
+    entries: [FseEntry; FSE_MAX_STATES],
+}
+
+impl FseDecoder {
+    fn new(norm: &[i16], log: u32) -> Self {
+        let size = 1u32 << log;
+        let symbols = spread_symbols(norm, log);
+        let mut next = [0u32; ML_BASE.len()];
+        for (s, &count) in norm.iter().enumerate() {
+            next[s] = if count == -1 { 1 } else { count.max(0) as u32 };
+        }
+
+        let mut entries = [FseEntry::default(); FSE_MAX_STATES];
+        for (entry, &symbol) in entries.iter_mut().zip(&symbols[..size as usize]) {
+            let state = next[symbol as usize];
+            next[symbol as usize] += 1;
+            let nb_bits = log - highbit(state);
+            *entry = FseEntry { symbol, nb_bits: nb_bits as u8, base: ((state << nb_bits) - size) as u16 };
+        }
+        FseDecoder { log, entries }
+    }
+
+    /// Her durumu aynı sembolü veren, bit tüketmeyen tablo (RLE kipi).
+    fn rle(symbol: u8) -> Self {
+        let mut entries = [FseEntry::default(); FSE_MAX_STATES];
+        entries[0].symbol = symbol;
+        FseDecoder { log: 0, entries }
+    }
+
+    fn next(&self, state: usize, bits: &mut BitReader) -> Option<usize> {
+        let entry = self.entries[state];
+        Some(entry.base as usize + bits.read(entry.nb_bits as u32)? as usize)
+    }
+}
+
+/// Sondan başa okunan bit akışı: son baytın en yüksek 1 biti akışın başlangıcıdır.
+struct BitReader<'a> {
+    data: &'a [u8],
+    /// Okunmamış bit sayısı; sıradaki okuma bu konumun hemen altındaki bitleri alır.
+    remaining: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let last = *data.last()?;
+        if last == 0 {
+            return None;
+        }
+        let remaining = data.len() * 8 - (last.leading_zeros() as usize + 1);
+        Some(BitReader { data, remaining })
+    }
+
+    fn read(&mut self, count: u32) -> Option<u32> {
+        let count = count as usize;
+        if count > self.remaining {
+            return None;
+        }
+        self.remaining -= count;
+        let mut value = 0u32;
+        for i in 0..count {
+            let bit = self.remaining + i;
+            value |= (((self.data[bit >> 3] >> (bit & 7)) & 1) as u32) << i;
+        }
+        Some(value)
+    }
+}
+
+
+// --- 4. Yardımcılar ---
+
+/// Sembolleri FSE tablosunun durumlarına yayar; düşük olasılıklı (-1) semboller
+/// tablonun sonuna yerleşir.
+fn spread_symbols(norm: &[i16], log: u32) -> [u8; FSE_MAX_STATES] {
+    let size = 1usize << log;
+    let mask = size - 1;
+    let mut symbols = [0u8; FSE_MAX_STATES];
+    let mut high = size - 1;
+    for (s, &count) in norm.iter().enumerate() {
+        if count == -1 {
+            symbols[high] = s as u8;
+            high -= 1;
+        }
+    }
+
+    let step = (size >> 1) + (size >> 3) + 3;
+    let mut position = 0;
+    for (s, &count) in norm.iter().enumerate() {
+        for _ in 0..count.max(0) {
+            symbols[position] = s as u8;
+            position = (position + step) & mask;
+            while position > high {
+                position = (position + step) & mask;
+            }
+        }
+    }
+    symbols
+}
+
+fn highbit(value: u32) -> u32 {
+    31 - value.leading_zeros()
+}
+
+fn read_u32(data: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+}
+
+fn hash(prefix: u32) -> usize {
+    (prefix.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+}